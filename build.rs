@@ -0,0 +1,26 @@
+//! Records build details for `slang::build_info()`.
+
+use std::path::Path;
+use std::process::Command;
+
+fn main() {
+    // Re-run when HEAD moves so the recorded commit stays current.
+    for path in [".git/HEAD", ".git/refs/heads", ".git/packed-refs"] {
+        if Path::new(path).exists() {
+            println!("cargo:rerun-if-changed={}", path);
+        }
+    }
+
+    let git_hash = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .and_then(|out| String::from_utf8(out.stdout).ok())
+        .map(|hash| hash.trim().to_string())
+        .unwrap_or_default();
+    println!("cargo:rustc-env=SLANG_GIT_HASH={}", git_hash);
+
+    let target = std::env::var("TARGET").unwrap_or_default();
+    println!("cargo:rustc-env=SLANG_TARGET={}", target);
+}
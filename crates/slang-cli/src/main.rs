@@ -0,0 +1,683 @@
+use std::env::args;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use slang::capability;
+use slang::compat;
+use slang::compile;
+use slang::crash;
+use slang::debug::Debugger;
+use slang::diff;
+use slang::env::{EnvRef, new_env};
+use slang::event_loop;
+use slang::formatter::{self, FormatOptions};
+use slang::learn;
+use slang::lint;
+use slang::optimizer;
+use slang::package::{self, Dependency, Installed};
+use slang::lexer::Lexer;
+use slang::object::Object;
+use slang::parser::Parser;
+use slang::profile;
+use slang::repl;
+use slang::runtime::{eval, run_tests_with, TestOptions, TestRunSummary};
+
+/// Subcommands that take the place of a script path.
+const SUBCOMMANDS: &[&str] = &["test", "check", "debug", "dap", "lsp", "fix", "fmt", "compile", "learn", "diff-config", "add", "install"];
+
+fn main() {
+    let mut args: Vec<String> = args().collect();
+    if let Some(pos) = args.iter().position(|a| a == "--dry-run") {
+        args.remove(pos);
+        capability::enable_dry_run();
+    }
+    if let Some(pos) = args.iter().position(|a| a == "--no-cache") {
+        args.remove(pos);
+        slang::cache::set_enabled(false);
+    }
+    if let Some(pos) = args.iter().position(|a| a == "--no-prelude") {
+        args.remove(pos);
+        slang::stdlib::set_enabled(false);
+    }
+    if let Some(pos) = args.iter().position(|a| a == "--audit") {
+        if pos + 1 >= args.len() {
+            eprintln!("Usage: slang --audit <log-file> [script.sl]");
+            std::process::exit(1);
+        }
+        let log_path = args.remove(pos + 1);
+        args.remove(pos);
+        if let Err(e) = capability::enable_audit(Path::new(&log_path)) {
+            eprintln!("Failed to open audit log {}: {}", log_path, e);
+            std::process::exit(1);
+        }
+    }
+
+    if args.iter().any(|a| a == "--version" || a == "-V") {
+        let info = slang::build_info();
+        if args.iter().any(|a| a == "--json") {
+            println!("{}", serde_json::to_string(&info).expect("build info serializes"));
+        } else {
+            println!("{}", info.summary());
+        }
+        return;
+    }
+
+    if let Some(pos) = args.iter().position(|a| a == "--profile-startup") {
+        args.remove(pos);
+        profile::enable();
+    }
+
+    let script = match args.get(1).map(String::as_str) {
+        Some("run") => args.get(2),
+        Some(arg) if !arg.starts_with('-') && !SUBCOMMANDS.contains(&arg) => args.get(1),
+        _ => None,
+    };
+    crash::install(script.map(PathBuf::from));
+
+    let env = profile::time("new_env", new_env);
+    crash::watch_env(&env);
+
+    if args.len() < 2 {
+        repl::run_repl(Rc::clone(&env));
+    } else {
+        if args[1] == "test" {
+            run_test_mode(Rc::clone(&env), &args);
+        } else if args[1] == "check" {
+            run_check_mode(&args);
+        } else if args[1] == "debug" {
+            run_debug_mode(Rc::clone(&env), &args);
+        } else if args[1] == "dap" {
+            run_dap_mode();
+        } else if args[1] == "lsp" {
+            run_lsp_mode();
+        } else if args[1] == "fix" {
+            run_fix_mode(&args);
+        } else if args[1] == "fmt" {
+            run_fmt_mode(&args);
+        } else if args[1] == "compile" {
+            run_compile_mode(&args);
+        } else if args[1] == "learn" {
+            run_learn_mode(&args);
+        } else if args[1] == "diff-config" {
+            run_diff_config_mode(&args);
+        } else if args[1] == "add" {
+            run_add_mode(&args);
+        } else if args[1] == "install" {
+            run_install_mode(&args);
+        } else if args[1] == "run" {
+            if args.len() < 3 {
+                eprintln!("Usage: slang run <script.sl|script.slc>");
+                std::process::exit(2);
+            }
+            run_script_mode(Rc::clone(&env), &args[2]);
+        } else {
+            run_script_mode(Rc::clone(&env), &args[1]);
+        }
+    }
+
+    if profile::is_enabled() {
+        eprint!("{}", profile::report());
+    }
+}
+
+/// Run a script, either Slang source or a `.slc` file from `slang compile`.
+fn run_script_mode(env: EnvRef, file_path_str: &str) {
+    let file_path = Path::new(file_path_str);
+    if !file_path.exists() {
+        eprintln!("File not found: {}", file_path_str);
+        return;
+    }
+
+    let file_content = profile::time("read script", || std::fs::read(file_path))
+        .expect("failed to read file");
+    env.borrow_mut()
+        .set_module_dir(file_path.parent().map(|p| p.to_path_buf()));
+    // Lets an import cycle back to the script itself be reported.
+    if let Ok(path) = std::fs::canonicalize(file_path) {
+        env.borrow_mut().set_import_chain(vec![path]);
+    }
+
+    let mut program = if compile::is_compiled(&file_content) {
+        match profile::time("load compiled", || compile::load_compiled(&file_content)) {
+            Ok(program) => program,
+            Err(e) => {
+                eprintln!("{}: {}", file_path_str, e);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        let source = String::from_utf8_lossy(&file_content);
+        profile::time("parse", || {
+            let lexer = Lexer::new(&source);
+            let mut parser = Parser::new(lexer);
+            parser.parse_program()
+        })
+    };
+    profile::time("optimize", || optimizer::optimize(&mut program));
+    let program = Rc::new(program);
+    crash::set_program(Rc::clone(&program));
+    event_loop::install_signal_handler();
+    let result = profile::time("eval", || eval(&program, Rc::clone(&env)));
+    // Shutdown hooks run whether or not the script failed; the script's own
+    // error takes precedence over one from a hook.
+    let result = match event_loop::shutdown(env) {
+        err @ Object::Error(_) if !result.is_error() => err,
+        _ => result,
+    };
+    println!("{}", result);
+}
+
+/// Run a script under the step debugger, taking commands from stdin.
+fn run_debug_mode(env: EnvRef, args: &[String]) {
+    let usage = "Usage: slang debug <script.sl> [--break [file:]line ...]";
+    let mut script = None;
+    let mut breaks = Vec::new();
+
+    let mut rest = args[2..].iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--break" | "-b" => match rest.next() {
+                Some(spec) => breaks.push(spec.clone()),
+                None => {
+                    eprintln!("{}", usage);
+                    std::process::exit(2);
+                }
+            },
+            _ if script.is_none() => script = Some(arg.clone()),
+            _ => {
+                eprintln!("{}", usage);
+                std::process::exit(2);
+            }
+        }
+    }
+    let Some(file_path_str) = script else {
+        eprintln!("{}", usage);
+        std::process::exit(2);
+    };
+
+    let file_path = Path::new(&file_path_str);
+    let source = match std::fs::read_to_string(file_path) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("{}: failed to read: {}", file_path_str, e);
+            std::process::exit(1);
+        }
+    };
+    let mut parser = Parser::new(Lexer::new(&source));
+    let program = parser.parse_program();
+    if !parser.errors.is_empty() {
+        eprintln!("Parse errors:");
+        for err in parser.errors {
+            eprintln!("  {}", err);
+        }
+        std::process::exit(1);
+    }
+
+    let mut debugger = Debugger::new(&source, std::io::stdin().lock(), std::io::stdout())
+        .stop_on_entry(breaks.is_empty());
+    for spec in breaks {
+        let (file, line) = match spec.rsplit_once(':') {
+            Some((file, line)) => (Some(file), line),
+            None => (None, spec.as_str()),
+        };
+        if file.is_some_and(|file| Path::new(file).file_name() != file_path.file_name()) {
+            eprintln!("{}: only breakpoints in {} are supported", spec, file_path_str);
+            std::process::exit(2);
+        }
+        match line.parse::<usize>() {
+            Ok(line) if line > 0 => debugger = debugger.break_at(line),
+            _ => {
+                eprintln!("{}: expected a line number", spec);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    env.borrow_mut()
+        .set_module_dir(file_path.parent().map(|p| p.to_path_buf()));
+    let result = debugger.run(&program, env);
+    println!("{}", result);
+}
+
+/// Serve the Debug Adapter Protocol on stdin and stdout for an editor.
+fn run_dap_mode() {
+    if let Err(e) = slang::debug::dap::serve(std::io::stdin().lock(), std::io::stdout()) {
+        eprintln!("slang dap: {}", e);
+        std::process::exit(1);
+    }
+}
+
+/// Serve the Language Server Protocol on stdin and stdout for an editor.
+fn run_lsp_mode() {
+    if let Err(e) = slang::lsp::serve(std::io::stdin().lock(), std::io::stdout()) {
+        eprintln!("slang lsp: {}", e);
+        std::process::exit(1);
+    }
+}
+
+/// Report likely mistakes in scripts without running them.
+fn run_check_mode(args: &[String]) {
+    let files = &args[2..];
+    if files.is_empty() {
+        eprintln!("Usage: slang check <script.sl>...");
+        std::process::exit(2);
+    }
+
+    let mut found = false;
+    for file_path_str in files {
+        let source = match std::fs::read_to_string(file_path_str) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("{}: failed to read: {}", file_path_str, e);
+                found = true;
+                continue;
+            }
+        };
+        let (kind, diagnostics) = match lint::check_source(&source) {
+            Ok(warnings) => ("warning", warnings),
+            Err(errors) => ("error", errors),
+        };
+        for diagnostic in diagnostics {
+            println!("{}:{}: {}: {}", file_path_str, diagnostic.line, kind, diagnostic.message);
+            found = true;
+        }
+    }
+    if found {
+        std::process::exit(1);
+    }
+}
+
+fn run_compile_mode(args: &[String]) {
+    let usage = "Usage: slang compile <script.sl> [-o <script.slc>]";
+    let mut input = None;
+    let mut output = None;
+
+    let mut rest = args[2..].iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "-o" | "--output" => match rest.next() {
+                Some(path) => output = Some(path.clone()),
+                None => {
+                    eprintln!("{}", usage);
+                    std::process::exit(2);
+                }
+            },
+            _ if input.is_none() => input = Some(arg.clone()),
+            _ => {
+                eprintln!("{}", usage);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let Some(input) = input else {
+        eprintln!("{}", usage);
+        std::process::exit(2);
+    };
+    let output = output.unwrap_or_else(|| {
+        Path::new(&input)
+            .with_extension("slc")
+            .to_string_lossy()
+            .into_owned()
+    });
+
+    let source = match std::fs::read_to_string(&input) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("{}: failed to read: {}", input, e);
+            std::process::exit(1);
+        }
+    };
+
+    let bytes = match compile::compile_source(&source) {
+        Ok(bytes) => bytes,
+        Err(errors) => {
+            eprintln!("{}: parse errors:", input);
+            for err in errors {
+                eprintln!("  {}", err);
+            }
+            std::process::exit(1);
+        }
+    };
+
+    if capability::is_dry_run() {
+        capability::log_dry_run("compile", &format!("would write {}", output));
+    } else if let Err(e) = std::fs::write(&output, bytes) {
+        eprintln!("{}: failed to write: {}", output, e);
+        std::process::exit(1);
+    } else {
+        println!("{} -> {}", input, output);
+    }
+}
+
+fn run_test_mode(_base_env: EnvRef, args: &[String]) {
+    let usage = "Usage: slang test <script.sl> [--filter <text>] [--update-snapshots] [--json | --junit]";
+    let mut script = None;
+    let mut options = TestOptions::default();
+    let mut report = "text";
+
+    let mut rest = args[2..].iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--filter" => match rest.next() {
+                Some(filter) => options.filter = Some(filter.clone()),
+                None => {
+                    eprintln!("{}", usage);
+                    std::process::exit(2);
+                }
+            },
+            "--update-snapshots" => options.update_snapshots = true,
+            "--json" => report = "json",
+            "--junit" => report = "junit",
+            _ if script.is_none() => script = Some(arg.clone()),
+            _ => {
+                eprintln!("{}", usage);
+                std::process::exit(2);
+            }
+        }
+    }
+    let Some(file_path_str) = script else {
+        eprintln!("{}", usage);
+        return;
+    };
+
+    let file_path = Path::new(&file_path_str);
+    if !file_path.exists() {
+        eprintln!("File not found: {}", file_path_str);
+        return;
+    }
+
+    let file_content = std::fs::read_to_string(file_path).expect("failed to read file");
+    _base_env
+        .borrow_mut()
+        .set_module_dir(file_path.parent().map(|p| p.to_path_buf()));
+    let lexer = Lexer::new(&file_content);
+    let mut parser = Parser::new(lexer);
+    let program = parser.parse_program();
+
+    if !parser.errors.is_empty() {
+        eprintln!("Parse errors:");
+        for err in parser.errors {
+            eprintln!("  {}", err);
+        }
+        return;
+    }
+
+    options.snapshots = Some(file_path.with_extension("snap"));
+    let summary: TestRunSummary = run_tests_with(&program, &options);
+    match report {
+        "json" => println!("{}", summary.to_json()),
+        "junit" => print!("{}", summary.to_junit(&file_path_str)),
+        _ => {
+            let color = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+            if color {
+                println!("{}", summary.colored().trim_end());
+            } else {
+                println!("{}", summary.output.trim_end());
+            }
+        }
+    }
+
+    if summary.failed > 0 {
+        // Non-zero exit code to signal failure when used in CI or scripts.
+        std::process::exit(1);
+    }
+}
+
+fn run_fix_mode(args: &[String]) {
+    if args.len() < 3 {
+        eprintln!("Usage: slang fix <script.sl>...");
+        return;
+    }
+
+    let mut failed = false;
+    for file_path_str in &args[2..] {
+        let source = match std::fs::read_to_string(file_path_str) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("{}: failed to read: {}", file_path_str, e);
+                failed = true;
+                continue;
+            }
+        };
+
+        let (fixed, fixes) = match compat::fix_source(&source) {
+            Ok(result) => result,
+            Err(errors) => {
+                eprintln!("{}: parse errors, not fixed:", file_path_str);
+                for err in errors {
+                    eprintln!("  {}", err);
+                }
+                failed = true;
+                continue;
+            }
+        };
+
+        if fixes.is_empty() {
+            println!("{}: nothing to fix", file_path_str);
+            continue;
+        }
+
+        for fix in &fixes {
+            println!(
+                "{}: `{}` -> `{}` ({} occurrence{})",
+                file_path_str,
+                fix.legacy,
+                fix.replacement,
+                fix.count,
+                if fix.count == 1 { "" } else { "s" }
+            );
+        }
+
+        if capability::is_dry_run() {
+            capability::log_dry_run("fix", &format!("would rewrite {}", file_path_str));
+        } else if let Err(e) = std::fs::write(file_path_str, fixed) {
+            eprintln!("{}: failed to write: {}", file_path_str, e);
+            failed = true;
+        }
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+fn run_fmt_mode(args: &[String]) {
+    let usage = "Usage: slang fmt [--check] [--indent N] <script.sl>...";
+    let mut check = false;
+    let mut options = FormatOptions::default();
+    let mut files = Vec::new();
+
+    let mut rest = args[2..].iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--indent" => match rest.next().and_then(|n| n.parse().ok()) {
+                Some(n) => options.indent = n,
+                None => {
+                    eprintln!("{}", usage);
+                    std::process::exit(2);
+                }
+            },
+            _ => files.push(arg),
+        }
+    }
+
+    if files.is_empty() {
+        eprintln!("{}", usage);
+        std::process::exit(2);
+    }
+
+    let mut failed = false;
+    for file_path_str in files {
+        let source = match std::fs::read_to_string(file_path_str) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("{}: failed to read: {}", file_path_str, e);
+                failed = true;
+                continue;
+            }
+        };
+
+        let formatted = match formatter::format_source(&source, &options) {
+            Ok(formatted) => formatted,
+            Err(errors) => {
+                eprintln!("{}: parse errors, not formatted:", file_path_str);
+                for err in errors {
+                    eprintln!("  {}", err);
+                }
+                failed = true;
+                continue;
+            }
+        };
+
+        if formatted == source {
+            continue;
+        }
+
+        if check {
+            println!("{}: not formatted", file_path_str);
+            failed = true;
+        } else if capability::is_dry_run() {
+            capability::log_dry_run("fmt", &format!("would rewrite {}", file_path_str));
+        } else if let Err(e) = std::fs::write(file_path_str, formatted) {
+            eprintln!("{}: failed to write: {}", file_path_str, e);
+            failed = true;
+        } else {
+            println!("{}: formatted", file_path_str);
+        }
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+fn run_learn_mode(args: &[String]) {
+    let usage = "Usage: slang learn [--list | <lesson number>]";
+    match args.get(2).map(String::as_str) {
+        None => learn::run_learn(1),
+        Some("--list") => {
+            for (i, lesson) in learn::LESSONS.iter().enumerate() {
+                println!("{:>2}. {}", i + 1, lesson.title);
+            }
+        }
+        Some(n) => match n.parse::<usize>() {
+            Ok(n) if (1..=learn::LESSONS.len()).contains(&n) && args.len() == 3 => {
+                learn::run_learn(n)
+            }
+            _ => {
+                eprintln!("{}", usage);
+                std::process::exit(2);
+            }
+        },
+    }
+}
+
+/// Exits with 0 when the files match, 1 when they differ and 2 on errors.
+fn run_diff_config_mode(args: &[String]) {
+    if args.len() != 4 {
+        eprintln!("Usage: slang diff-config <a.json|yaml|toml> <b.json|yaml|toml>");
+        std::process::exit(2);
+    }
+
+    let load = |path: &str| match diff::load_config(Path::new(path)) {
+        Ok(value) => value,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+    let old = load(&args[2]);
+    let new = load(&args[3]);
+
+    let changes = diff::diff_values(&old, &new);
+    for change in &changes {
+        println!("{}", change);
+    }
+    if !changes.is_empty() {
+        std::process::exit(1);
+    }
+}
+
+/// The project the package commands work on: the nearest directory with a
+/// `slang.toml`, or the current directory when there is none yet.
+fn project_dir() -> PathBuf {
+    let cwd = std::env::current_dir().unwrap_or_else(|e| {
+        eprintln!("failed to read the current directory: {}", e);
+        std::process::exit(1);
+    });
+    package::project_root(&cwd).unwrap_or(cwd)
+}
+
+/// Record a git dependency in slang.toml and install it.
+fn run_add_mode(args: &[String]) {
+    let usage = "Usage: slang add <name> <git-url> [--rev <rev>]";
+    let dep = match &args[2..] {
+        [name, git] => Dependency { name: name.clone(), git: git.clone(), rev: None },
+        [name, git, flag, rev] if flag == "--rev" => Dependency {
+            name: name.clone(),
+            git: git.clone(),
+            rev: Some(rev.clone()),
+        },
+        _ => {
+            eprintln!("{}", usage);
+            std::process::exit(2);
+        }
+    };
+    let root = project_dir();
+    if capability::is_dry_run() {
+        capability::log_dry_run(
+            "add",
+            &format!("would add {} ({}) to {}", dep.name, dep.git, root.join(package::MANIFEST).display()),
+        );
+        return;
+    }
+    if let Err(e) = package::add_dependency(&root, &dep) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+    println!("added {} ({})", dep.name, dep.git);
+    install_packages(&root);
+}
+
+fn run_install_mode(args: &[String]) {
+    if args.len() != 2 {
+        eprintln!("Usage: slang install");
+        std::process::exit(2);
+    }
+    let root = project_dir();
+    if capability::is_dry_run() {
+        match package::read_manifest(&root) {
+            Ok(deps) => {
+                for dep in deps {
+                    capability::log_dry_run("install", &format!("would fetch {} from {}", dep.name, dep.git));
+                }
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    install_packages(&root);
+}
+
+fn install_packages(root: &Path) {
+    match package::install(root) {
+        Ok(done) => {
+            for step in done {
+                match step {
+                    Installed::Cloned(name) => println!("{}: installed", name),
+                    Installed::Present(name) => println!("{}: already installed", name),
+                    Installed::CheckedOut(name, rev) => println!("{}: checked out {}", name, rev),
+                }
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
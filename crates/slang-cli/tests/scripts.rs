@@ -0,0 +1,257 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::fs;
+use slang::lexer::Lexer;
+use slang::parser::Parser;
+use slang::runtime::{TestRunSummary, run_tests};
+
+/// The compiled `slang` binary, writing its module cache to a temp dir
+/// rather than the user's cache dir.
+fn slang() -> Command {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_slang"));
+    cmd.env("SLANG_CACHE_DIR", std::env::temp_dir().join("slang_test_ast_cache"));
+    cmd
+}
+
+/// Run a script from `test_scripts/` through the compiled `slang` binary
+/// and return its trimmed stdout.
+fn run_script(script_name: &str) -> String {
+    run_script_in(script_name, Path::new(env!("CARGO_MANIFEST_DIR")))
+}
+
+/// Like [`run_script`], with `dir` as the working directory for scripts
+/// that write files.
+fn run_script_in(script_name: &str, dir: &Path) -> String {
+    // Build an absolute path to the script inside `test_scripts/`.
+    let script_path: PathBuf = [
+        env!("CARGO_MANIFEST_DIR"),
+        "test_scripts",
+        script_name,
+    ]
+    .iter()
+    .collect();
+
+    let output = slang()
+        .arg(&script_path)
+        .current_dir(dir)
+        .output()
+        .expect("failed to invoke slang binary");
+
+    if !output.status.success() {
+        panic!(
+            "slang process exited with status {:?}\nstdout:\n{}\nstderr:\n{}",
+            output.status.code(),
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr),
+        );
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .to_string()
+}
+
+fn run_tests_script(script_name: &str) -> TestRunSummary {
+    let script_path: PathBuf = [
+        env!("CARGO_MANIFEST_DIR"),
+        "test_scripts",
+        script_name,
+    ]
+    .iter()
+    .collect();
+
+    let src = fs::read_to_string(script_path)
+    .expect("failed to read file");
+
+    let lexer = Lexer::new(&src);
+    let mut parser = Parser::new(lexer);
+    let program = parser.parse_program();
+
+    run_tests(&program)
+}
+
+#[test]
+fn fact_script_produces_expected_result() {
+    let output = run_script("fact.sl");
+    // 20! = 2432902008176640000
+    assert_eq!(output, "2432902008176640000");
+}
+
+#[test]
+fn test_a_script_produces_expected_result() {
+    let output = run_script("test_a.sl");
+    // len([2, 3]) == 2, len([1, 2, 3, 4]) == 4, 2 + 4 == 6
+    assert_eq!(output, "6");
+}
+
+#[test]
+fn file_builtins_script_produces_expected_result() {
+    let dir = std::env::temp_dir().join(format!("slang_file_builtins_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let output = run_script_in("test_file_builtins.sl", &dir);
+    assert_eq!(output, "\"Hello, world!\"\nnull");
+    assert_eq!(fs::read_to_string(dir.join("test.txt")).unwrap(), "Hello, world!");
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_objects_script_produces_expected_result() {
+    let output = run_script("test_objects.sl");
+    assert_eq!(output, "10\n15\n25\n30\n1\n2\n3\n4\n5\n6\n6\nnull");
+}
+
+#[test]
+fn test_fizzbuzz_script_produces_expected_result() {
+    let output = run_script("fizzbuzz.sl");
+    assert_eq!(output, "1\n2\n\"Fizz\"\n4\n\"Buzz\"\n\"Fizz\"\n7\n8\n\"Fizz\"\n\"Buzz\"\n11\n\"Fizz\"\n13\n14\n\"FizzBuzz\"\n16\n17\n\"Fizz\"\n19\n\"Buzz\"\n\"Fizz\"\n22\n23\n\"Fizz\"\n\"Buzz\"\n26\n\"Fizz\"\n28\n29\n\"FizzBuzz\"\n31\n32\n\"Fizz\"\n34\n\"Buzz\"\n\"Fizz\"\n37\n38\n\"Fizz\"\n\"Buzz\"\n41\n\"Fizz\"\n43\n44\n\"FizzBuzz\"\n46\n47\n\"Fizz\"\n49\n\"Buzz\"\n\"Fizz\"\n52\n53\n\"Fizz\"\n\"Buzz\"\n56\n\"Fizz\"\n58\n59\n\"FizzBuzz\"\n61\n62\n\"Fizz\"\n64\n\"Buzz\"\n\"Fizz\"\n67\n68\n\"Fizz\"\n\"Buzz\"\n71\n\"Fizz\"\n73\n74\n\"FizzBuzz\"\n76\n77\n\"Fizz\"\n79\n\"Buzz\"\n\"Fizz\"\n82\n83\n\"Fizz\"\n\"Buzz\"\n86\n\"Fizz\"\n88\n89\n\"FizzBuzz\"\n91\n92\n\"Fizz\"\n94\n\"Buzz\"\n\"Fizz\"\n97\n98\n\"Fizz\"\n\"Buzz\"\nnull");
+}
+
+#[test]
+fn test_higher_order_functions_script_produces_expected_result() {
+    let output = run_script("higher_order_funcs.sl");
+    assert_eq!(output, "5\n17\nnull");
+}
+
+#[test]
+fn test_monads_script_produces_expected_result() {
+    let output = run_script("monads.sl");
+    assert_eq!(output, "5\n\"failure\"\n\"Found value in list at index\"\n2\n\"Value not in list\"\nnull");
+}
+
+#[test]
+fn test_the_test_suite_script_produces_expected_result() {
+    let output: TestRunSummary = run_tests_script("testing_tests.sl");
+    assert_eq!(output.total, 4);
+    assert_eq!(output.failed, 1);
+    assert_eq!(output.output, "PASS: adds two numbers (1 assertion)\nPASS: simple boolean assertion (1 assertion)\nPASS: testing not equals (1 assertion)\nFAIL: this test should fail (1 assertion) - Assertion failed: \"This is expected to fail.\"\n\nTest results: 3/4 passed, 1 failed\n");
+}
+
+#[test]
+fn test_import_namespace_script_produces_expected_result() {
+    let output = run_script("test_import_namespace.sl");
+    assert_eq!(output, "12");
+}
+
+#[test]
+fn type_casting_script_produces_expected_result() {
+    let output = run_script("type_casting.sl");
+    assert_eq!(
+        output,
+        "42\n3.14\n\"123\"\nfalse\ntrue\ntrue\nfalse\n2.5\n\"type error\"\n\"Some(5)\"\nnull"
+    );
+}
+
+#[test]
+fn pubsub_script_invokes_tagged_functions() {
+    let output = run_script("pubsub.sl");
+    assert_eq!(output, "12\n35\n144\n144\nnull");
+}
+
+#[test]
+fn shutdown_hooks_run_in_reverse_order_on_completion() {
+    let output = run_script("shutdown.sl");
+    assert_eq!(output, "\"working\"\n\"closing socket\"\n\"closing db\"\nnull");
+}
+
+#[test]
+fn no_prelude_leaves_out_the_slang_stdlib() {
+    assert_eq!(run_script("prelude.sl"), "Some(1)");
+
+    let script: PathBuf = [env!("CARGO_MANIFEST_DIR"), "test_scripts", "prelude.sl"].iter().collect();
+    let output = slang()
+        .arg("--no-prelude")
+        .arg(&script)
+        .output()
+        .expect("failed to invoke slang binary");
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "\"no prelude\"");
+}
+
+#[test]
+fn slang_path_directories_are_searched_for_imports() {
+    let dir: PathBuf = [env!("CARGO_MANIFEST_DIR"), "test_scripts"].iter().collect();
+    let run = |slang_path: Option<PathBuf>| {
+        let mut cmd = slang();
+        cmd.arg(dir.join("slang_path.sl")).env_remove("SLANG_PATH");
+        if let Some(path) = slang_path {
+            cmd.env("SLANG_PATH", path);
+        }
+        let output = cmd.output().expect("failed to invoke slang binary");
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    };
+
+    assert_eq!(run(Some(dir.join("shared_lib"))), "\"hello path\"");
+    assert!(run(None).contains("failed to read import"));
+}
+
+#[test]
+fn importing_the_running_script_is_a_circular_import() {
+    assert_eq!(
+        run_script("cycle_main.sl"),
+        "circular import: cycle_main.sl -> cycle_other.sl -> cycle_main.sl"
+    );
+}
+
+#[test]
+fn diff_config_reports_structural_changes_across_formats() {
+    let config = |name: &str| -> PathBuf {
+        [env!("CARGO_MANIFEST_DIR"), "test_scripts", "configs", name].iter().collect()
+    };
+    let diff_config = |a: &str, b: &str| {
+        slang()
+            .arg("diff-config")
+            .arg(config(a))
+            .arg(config(b))
+            .output()
+            .expect("failed to invoke slang binary")
+    };
+
+    let same = diff_config("app.json", "app.toml");
+    assert_eq!(same.status.code(), Some(0));
+    assert!(same.stdout.is_empty());
+
+    let changed = diff_config("app.json", "app.yaml");
+    assert_eq!(changed.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&changed.stdout),
+        "- debug: true\n+ log: \"info\"\n- server.hosts[1]: \"b.example\"\n~ server.port: 8080 -> 9090\n"
+    );
+
+    let missing = diff_config("app.json", "missing.json");
+    assert_eq!(missing.status.code(), Some(2));
+}
+
+#[test]
+fn check_reports_warnings_without_running_scripts() {
+    let check = |name: &str| {
+        let script: PathBuf = [env!("CARGO_MANIFEST_DIR"), "test_scripts", name].iter().collect();
+        slang()
+            .arg("check")
+            .arg(&script)
+            .output()
+            .expect("failed to invoke slang binary")
+    };
+
+    let clean = check("fact.sl");
+    assert_eq!(clean.status.code(), Some(0));
+    assert!(clean.stdout.is_empty());
+
+    let warned = check("test_a.sl");
+    assert_eq!(warned.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&warned.stdout);
+    assert!(stdout.contains("test_a.sl:4: warning: `b` is never used\n"), "{}", stdout);
+    assert!(stdout.contains("test_a.sl:5: warning: `c` is never used\n"), "{}", stdout);
+}
+
+#[test]
+fn version_json_reports_build_info() {
+    let output = slang()
+        .args(["--version", "--json"])
+        .output()
+        .expect("failed to invoke slang binary");
+    assert!(output.status.success());
+
+    let info: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+    assert_eq!(info["version"], env!("CARGO_PKG_VERSION"));
+    assert!(info["features"].is_array());
+    assert!(info["target"].is_string());
+}
@@ -9,8 +9,11 @@ pub use nodes::{
     InfixExpression,
     InfixOp,
     LetStatement,
+    DestructureStatement,
+    DestructurePattern,
     ExpressionStatement,
     ImportStatement,
+    ExportStatement,
     NamespaceStatement,
     BlockStatement,
     IfExpression,
@@ -21,6 +24,7 @@ pub use nodes::{
     ReturnStatement,
     WhileStatement,
     StringLiteral,
+    BytesLiteral,
     ArrayLiteral,
     IndexExpression,
     PublishExpression,
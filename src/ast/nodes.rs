@@ -1,14 +1,31 @@
+use std::cell::{Cell, OnceCell};
 use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
 
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+use crate::env::ScopeLayout;
+use crate::object::types::BuiltinFunction;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Program {
     pub statements: Vec<Statement>,
+    /// Source line of each statement, parallel to `statements`; empty for
+    /// programs that weren't parsed from source.
+    pub lines: Vec<usize>,
+}
+
+impl Default for Program {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Program {
     pub fn new() -> Self {
         Program {
             statements: Vec::new(),
+            lines: Vec::new(),
         }
     }
 }
@@ -22,18 +39,28 @@ impl Display for Program {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Statement {
     Let(LetStatement),
     Return(ReturnStatement),
     Expression(ExpressionStatement),
     While(WhileStatement),
     For(ForStatement),
+    ForIn(ForInStatement),
+    Switch(SwitchStatement),
     Function(FunctionStatement),
     Test(TestStatement),
+    Describe(DescribeStatement),
+    Hook(HookStatement),
     Namespace(NamespaceStatement),
     Import(ImportStatement),
+    Use(UseStatement),
+    Export(ExportStatement),
     Class(ClassStatement),
+    Destructure(DestructureStatement),
+    Yield(YieldStatement),
+    /// `breakpoint;` – pauses under `slang debug`, otherwise does nothing.
+    Breakpoint,
 }
 
 impl Display for Statement {
@@ -43,29 +70,95 @@ impl Display for Statement {
             Statement::Return(rs) => write!(f, "{}", rs),
             Statement::While(ws) => write!(f, "{}", ws),
             Statement::For(fs) => write!(f, "{}", fs),
+            Statement::ForIn(fs) => write!(f, "{}", fs),
+            Statement::Switch(ss) => write!(f, "{}", ss),
             Statement::Expression(es) => write!(f, "{}", es),
             Statement::Function(fs) => write!(f, "{}", fs),
             Statement::Test(ts) => write!(f, "{}", ts),
+            Statement::Describe(ds) => write!(f, "{}", ds),
+            Statement::Hook(hs) => write!(f, "{}", hs),
             Statement::Namespace(ns) => write!(f, "{}", ns),
             Statement::Import(is) => write!(f, "{}", is),
+            Statement::Use(us) => write!(f, "{}", us),
+            Statement::Export(es) => write!(f, "{}", es),
+            Statement::Destructure(ds) => write!(f, "{}", ds),
             Statement::Class(cs) => write!(f, "{}", cs),
+            Statement::Yield(ys) => write!(f, "{}", ys),
+            Statement::Breakpoint => write!(f, "breakpoint;"),
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LetStatement {
     pub name: Identifier,
     pub value: Expression,
+    /// Declared with `const`, so the binding can't be reassigned.
+    pub constant: bool,
 }
 
 impl Display for LetStatement {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "let {} = {};", self.name, self.value)
+        let keyword = if self.constant { "const" } else { "let" };
+        write!(f, "{} {} = {};", keyword, self.name, self.value)
+    }
+}
+
+/// `let [a, b, ...rest] = value;` or `let { x, y } = value;`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DestructureStatement {
+    pub pattern: DestructurePattern,
+    pub value: Expression,
+}
+
+impl Display for DestructureStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "let {} = {};", self.pattern, self.value)
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DestructurePattern {
+    /// Binds elements by position; `rest` collects any remaining elements.
+    Array {
+        names: Vec<Identifier>,
+        rest: Option<Identifier>,
+    },
+    /// Binds properties to variables of the same name.
+    Object { names: Vec<Identifier> },
+}
+
+impl DestructurePattern {
+    /// Every name the pattern binds, in source order.
+    pub fn bindings(&self) -> Vec<Identifier> {
+        match self {
+            DestructurePattern::Array { names, rest } => {
+                names.iter().chain(rest.iter()).cloned().collect()
+            }
+            DestructurePattern::Object { names } => names.clone(),
+        }
+    }
+}
+
+impl Display for DestructurePattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DestructurePattern::Array { names, rest } => {
+                let mut parts: Vec<String> = names.iter().map(|n| n.value.clone()).collect();
+                if let Some(rest) = rest {
+                    parts.push(format!("...{}", rest.value));
+                }
+                write!(f, "[{}]", parts.join(", "))
+            }
+            DestructurePattern::Object { names } => {
+                let parts: Vec<String> = names.iter().map(|n| n.value.clone()).collect();
+                write!(f, "{{ {} }}", parts.join(", "))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ExpressionStatement {
     pub expression: Expression,
 }
@@ -76,9 +169,26 @@ impl Display for ExpressionStatement {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockStatement {
     pub statements: Vec<Statement>,
+    /// Source line of each statement, parallel to `statements`.
+    pub lines: Vec<usize>,
+    /// Source line the block ends on: its closing brace, or the label after
+    /// a switch case. Blocks the parser builds without braces end on their
+    /// only statement's line.
+    pub end_line: usize,
+    /// For a function body, the names its call scope binds, set by the
+    /// resolver. Unset for other blocks and for bodies left unresolved.
+    #[serde(skip)]
+    pub scope: OnceCell<Arc<ScopeLayout>>,
+}
+
+/// Blocks are equal when their statements are, wherever they were written.
+impl PartialEq for BlockStatement {
+    fn eq(&self, other: &Self) -> bool {
+        self.statements == other.statements
+    }
 }
 
 impl Display for BlockStatement {
@@ -90,7 +200,7 @@ impl Display for BlockStatement {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ReturnStatement {
     pub return_value: Expression,
 }
@@ -101,7 +211,19 @@ impl Display for ReturnStatement {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// `yield value;`, only meaningful inside a generator function (`fn*`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct YieldStatement {
+    pub value: Expression,
+}
+
+impl Display for YieldStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "yield {};", self.value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WhileStatement {
     pub condition: Expression,
     pub body: BlockStatement,
@@ -115,7 +237,7 @@ impl Display for WhileStatement {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ForStatement {
     pub init: Option<Box<Statement>>,
     pub condition: Option<Expression>,
@@ -142,11 +264,116 @@ impl Display for ForStatement {
     }
 }
 
+/// `for (name in iterable) { ... }` over an array or a seq.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForInStatement {
+    pub name: Identifier,
+    pub iterable: Expression,
+    pub body: BlockStatement,
+}
+
+impl Display for ForInStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "for ({} in {}) {{{}}}", self.name, self.iterable, self.body)
+    }
+}
+
+/// `switch (subject) { case a, b: ... default: ... }`. Only the first case
+/// with a matching value runs; there is no fallthrough.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SwitchStatement {
+    pub subject: Expression,
+    pub cases: Vec<SwitchCase>,
+    pub default: Option<BlockStatement>,
+}
+
+impl Display for SwitchStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "switch ({}) {{", self.subject)?;
+        for case in &self.cases {
+            write!(f, "{}", case)?;
+        }
+        if let Some(default) = &self.default {
+            write!(f, "default: {}", default)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SwitchCase {
+    pub values: Vec<Expression>,
+    pub body: BlockStatement,
+}
+
+impl Display for SwitchCase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let values: Vec<String> = self.values.iter().map(|v| v.to_string()).collect();
+        write!(f, "case {}: {}", values.join(", "), self.body)
+    }
+}
+
 // ---------- Expressions ----------
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Identifier {
     pub value: String,
+    /// Where the resolver placed the binding; not part of the syntax.
+    #[serde(skip)]
+    pub binding: Resolution,
+}
+
+impl Identifier {
+    pub fn new(value: impl Into<String>) -> Self {
+        Identifier {
+            value: value.into(),
+            binding: Resolution::default(),
+        }
+    }
+}
+
+/// Where an identifier's binding lives, counting function scopes out from
+/// where it is evaluated. Worked out once, before the program runs, by the
+/// evaluator's resolver.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Binding {
+    /// Not resolved: search the scopes by name.
+    #[default]
+    Dynamic,
+    /// Declared in the function scope `depth` scopes out, at `slot` of its
+    /// [`crate::env::ScopeLayout`].
+    Local { depth: u32, slot: u32 },
+    /// Declared in none of the `depth` function scopes around it, so the
+    /// search by name starts past them.
+    Outer { depth: u32 },
+}
+
+/// An identifier's [`Binding`], set by the resolver through a shared
+/// reference to the AST.
+#[derive(Clone, Default)]
+pub struct Resolution(Cell<Binding>);
+
+impl Resolution {
+    pub fn get(&self) -> Binding {
+        self.0.get()
+    }
+
+    pub fn set(&self, binding: Binding) {
+        self.0.set(binding);
+    }
+}
+
+/// Resolving never makes two identifiers different.
+impl PartialEq for Resolution {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl fmt::Debug for Resolution {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.0.get())
+    }
 }
 
 impl Display for Identifier {
@@ -155,13 +382,15 @@ impl Display for Identifier {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Expression {
     Identifier(Identifier),
     IntegerLiteral(IntegerLiteral),
     BooleanLiteral(BooleanLiteral),
     FloatLiteral(FloatLiteral),
     StringLiteral(StringLiteral),
+    BytesLiteral(BytesLiteral),
+    NullLiteral,
     Infix(InfixExpression),
     If(Box<IfExpression>),
     Prefix(Box<PrefixExpression>),
@@ -174,6 +403,10 @@ pub enum Expression {
     PropertyAccess(Box<PropertyAccess>),
     Publish(Box<PublishExpression>),
     New(Box<NewExpression>),
+    /// `...value`, only valid as a call argument or array element.
+    Spread(Box<SpreadExpression>),
+    /// `Ns::member` looked up ahead of time by the optimizer.
+    Builtin(BuiltinRef),
 }
 
 impl Display for Expression {
@@ -184,6 +417,8 @@ impl Display for Expression {
             Expression::BooleanLiteral(bl) => write!(f, "{}", bl),
             Expression::FloatLiteral(fl) => write!(f, "{}", fl),
             Expression::StringLiteral(sl) => write!(f, "{}", sl),
+            Expression::NullLiteral => write!(f, "null"),
+            Expression::BytesLiteral(bl) => write!(f, "{}", bl),
             Expression::Infix(infix) => write!(f, "{}", infix),
             Expression::If(ifexpr) => write!(f, "{}", ifexpr),
             Expression::Prefix(prefix) => write!(f, "{}", prefix),
@@ -196,13 +431,18 @@ impl Display for Expression {
             Expression::PropertyAccess(pa) => write!(f, "{}", pa),
             Expression::Publish(pubexpr) => write!(f, "{}", pubexpr),
             Expression::New(newexpr) => write!(f, "{}", newexpr),
+            Expression::Spread(spread) => write!(f, "{}", spread),
+            Expression::Builtin(builtin) => write!(f, "{}", builtin),
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IntegerLiteral {
     pub value: i64,
+    /// The literal as written (`0xFF`, `1_000`), so the formatter can print
+    /// it back; `None` for literals the optimizer folded.
+    pub lexeme: Option<String>,
 }
 
 impl Display for IntegerLiteral {
@@ -211,9 +451,11 @@ impl Display for IntegerLiteral {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FloatLiteral {
     pub value: f64,
+    /// The literal as written (`1.5e3`), like [`IntegerLiteral::lexeme`].
+    pub lexeme: Option<String>,
 }
 
 impl Display for FloatLiteral {
@@ -222,7 +464,7 @@ impl Display for FloatLiteral {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BooleanLiteral {
     pub value: bool,
 }
@@ -233,19 +475,78 @@ impl Display for BooleanLiteral {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct StringLiteral {
     pub value: String,
+    /// The literal as written (`r"\d+"`), like [`IntegerLiteral::lexeme`].
+    pub lexeme: Option<String>,
 }
 
 impl Display for StringLiteral {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        // You can print without quotes or with; I’ll include quotes:
-        write!(f, "\"{}\"", self.value)
+        write!(f, "\"{}\"", escape_string(&self.value, false))
+    }
+}
+
+/// Escape `value` for the inside of a string literal. A backslash is only
+/// doubled where it would otherwise start an escape, so `\d+` stays as
+/// written. In a `"""` literal (`multiline`) newlines are kept and only a
+/// quote that would start a closing `"""` is escaped.
+pub fn escape_string(value: &str, multiline: bool) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    let mut out = String::with_capacity(value.len() + 2);
+    for (i, &c) in chars.iter().enumerate() {
+        match c {
+            '\\' => match chars.get(i + 1) {
+                Some(&next) if !next.is_control() && !matches!(next, 'n' | 't' | 'r' | '0' | '"' | '\\' | 'u') => {
+                    out.push('\\')
+                }
+                _ => out.push_str("\\\\"),
+            },
+            '"' if !multiline || chars[i + 1..].starts_with(&['"', '"']) => out.push_str("\\\""),
+            '\n' if !multiline => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            '\0' => out.push_str("\\0"),
+            c if c.is_control() && c != '\n' => out.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BytesLiteral {
+    pub value: Vec<u8>,
+}
+
+impl Display for BytesLiteral {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", quote_bytes(&self.value))
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// Render bytes as a `b"..."` literal that reads back to the same bytes:
+/// printable ASCII as is, everything else escaped.
+pub fn quote_bytes(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() + 3);
+    out.push_str("b\"");
+    for &b in bytes {
+        match b {
+            b'"' => out.push_str("\\\""),
+            b'\\' => out.push_str("\\\\"),
+            b'\n' => out.push_str("\\n"),
+            b'\r' => out.push_str("\\r"),
+            b'\t' => out.push_str("\\t"),
+            0x20..=0x7e => out.push(b as char),
+            _ => out.push_str(&format!("\\x{:02x}", b)),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ArrayLiteral {
     pub elements: Vec<Expression>,
 }
@@ -263,7 +564,7 @@ impl Display for ArrayLiteral {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IndexExpression {
     pub left: Box<Expression>,
     pub index: Box<Expression>,
@@ -275,7 +576,7 @@ impl Display for IndexExpression {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ObjectLiteral {
     /// Properties in insertion order: `name: expr`
     pub properties: Vec<(Identifier, Expression)>,
@@ -294,19 +595,61 @@ impl Display for ObjectLiteral {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PropertyAccess {
     pub object: Box<Expression>,
     pub property: Identifier,
+    /// `true` for `Ns::member`, `false` for `obj.member`; both evaluate the same way.
+    pub namespaced: bool,
+    /// `true` for `obj?.member`, which is null rather than an error when `obj` is null.
+    pub optional: bool,
+}
+
+impl PropertyAccess {
+    /// The separator as written: `::`, `?.` or `.`.
+    pub fn separator(&self) -> &'static str {
+        if self.namespaced {
+            "::"
+        } else if self.optional {
+            "?."
+        } else {
+            "."
+        }
+    }
 }
 
 impl Display for PropertyAccess {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{}.{}", self.object, self.property)
+        let sep = self.separator();
+        write!(f, "{}{}{}", self.object, sep, self.property)
+    }
+}
+
+/// A builtin namespace member, e.g. `Array::map`, that the optimizer found
+/// the script can't rebind.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuiltinRef {
+    pub namespace: String,
+    pub member: String,
+    /// Not serialized: a compiled script looks the member up when it runs.
+    #[serde(skip)]
+    pub func: Option<BuiltinFunction>,
+}
+
+/// References are equal when they name the same member.
+impl PartialEq for BuiltinRef {
+    fn eq(&self, other: &Self) -> bool {
+        self.namespace == other.namespace && self.member == other.member
+    }
+}
+
+impl Display for BuiltinRef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}::{}", self.namespace, self.member)
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum InfixOp {
     Assign,
     And,
@@ -322,6 +665,19 @@ pub enum InfixOp {
     Multiply,
     Divide,
     Modulo,
+    /// `**`, right-associative.
+    Power,
+    /// `div`: division rounded down, integer for integer operands.
+    IntDivide,
+    BitAnd,
+    BitOr,
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
+    /// `??`: the left side unless it is null, otherwise the right side.
+    Coalesce,
+    /// `|>`: `x |> f(a)` calls `f(x, a)`, and `x |> f` calls `f(x)`.
+    Pipeline,
 }
 
 impl Display for InfixOp {
@@ -341,12 +697,21 @@ impl Display for InfixOp {
             InfixOp::Multiply => "*",
             InfixOp::Divide => "/",
             InfixOp::Modulo => "%",
+            InfixOp::Power => "**",
+            InfixOp::IntDivide => "div",
+            InfixOp::BitAnd => "&",
+            InfixOp::BitOr => "|",
+            InfixOp::BitXor => "^",
+            InfixOp::ShiftLeft => "<<",
+            InfixOp::ShiftRight => ">>",
+            InfixOp::Coalesce => "??",
+            InfixOp::Pipeline => "|>",
         };
         write!(f, "{}", s)
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct InfixExpression {
     pub left: Box<Expression>,
     pub operator: InfixOp,
@@ -359,7 +724,7 @@ impl Display for InfixExpression {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IfExpression {
     pub condition: Box<Expression>,
     pub consequence: BlockStatement,
@@ -382,10 +747,11 @@ impl Display for IfExpression {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum PrefixOp {
     Not,
     Negate,
+    BitNot,
     PreIncrement,
     PreDecrement,
 }
@@ -395,6 +761,7 @@ impl Display for PrefixOp {
         let s = match self {
             PrefixOp::Not => "!",
             PrefixOp::Negate => "-",
+            PrefixOp::BitNot => "~",
             PrefixOp::PreIncrement => "++",
             PrefixOp::PreDecrement => "--",
         };
@@ -402,7 +769,7 @@ impl Display for PrefixOp {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PrefixExpression {
     pub operator: PrefixOp,
     pub right: Box<Expression>,
@@ -414,10 +781,13 @@ impl Display for PrefixExpression {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum PostfixOp {
     Increment,
     Decrement,
+    /// `x?`: unwrap a `Some`/`Ok`, or return the `None`/`Err` from the
+    /// enclosing function.
+    Try,
 }
 
 impl Display for PostfixOp {
@@ -425,12 +795,13 @@ impl Display for PostfixOp {
         let s = match self {
             PostfixOp::Increment => "++",
             PostfixOp::Decrement => "--",
+            PostfixOp::Try => "?",
         };
         write!(f, "{}", s)
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PostfixExpression {
     pub left: Box<Expression>,
     pub operator: PostfixOp,
@@ -442,28 +813,61 @@ impl Display for PostfixExpression {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FunctionLiteral {
     pub params: Vec<Identifier>,
+    /// Default values, one entry per param (`None` where the param has no default).
+    pub defaults: Vec<Option<Expression>>,
+    /// `...name`, collecting any arguments past `params` into an array.
+    pub rest: Option<Identifier>,
+    /// Preconditions, checked after the arguments are bound.
+    pub requires: Vec<Expression>,
+    /// Postconditions, checked against the return value (bound as `result`).
+    pub ensures: Vec<Expression>,
     pub body: BlockStatement,
+    /// `fn*`: calling the function returns a seq of the values it yields.
+    pub generator: bool,
+    /// Written with arrow syntax, `(a, b) => a + b`.
+    pub arrow: bool,
+}
+
+impl FunctionLiteral {
+    /// The parameter list as written, without the surrounding parens.
+    pub fn params_source(&self) -> String {
+        let mut parts: Vec<String> = self
+            .params
+            .iter()
+            .enumerate()
+            .map(|(i, p)| match self.defaults.get(i) {
+                Some(Some(default)) => format!("{} = {}", p, default),
+                _ => p.to_string(),
+            })
+            .collect();
+        if let Some(rest) = &self.rest {
+            parts.push(format!("...{}", rest));
+        }
+        parts.join(", ")
+    }
+
+    /// The `requires` / `ensures` clauses as written, each with a leading
+    /// space, or an empty string if there are none.
+    pub fn contracts_source(&self) -> String {
+        let requires = self.requires.iter().map(|c| format!(" requires {}", c));
+        let ensures = self.ensures.iter().map(|c| format!(" ensures {}", c));
+        requires.chain(ensures).collect()
+    }
 }
 
 impl Display for FunctionLiteral {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "fn(")?;
-        for (i, p) in self.params.iter().enumerate() {
-            if i > 0 {
-                write!(f, ", ")?;
-            }
-            write!(f, "{}", p)?;
-        }
-        write!(f, ") {{")?;
+        let star = if self.generator { "*" } else { "" };
+        write!(f, "fn{}({}){} {{", star, self.params_source(), self.contracts_source())?;
         write!(f, "{}", self.body)?;
         write!(f, "}}")
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FunctionStatement {
     pub name: Identifier,
     pub literal: FunctionLiteral,
@@ -480,23 +884,23 @@ impl Display for FunctionStatement {
                 }
                 write!(f, ":{}", tag)?;
             }
-            write!(f, ")\n")?;
+            writeln!(f, ")")?;
         }
 
-        write!(f, "function {}(", self.name)?;
-        for (i, p) in self.literal.params.iter().enumerate() {
-            if i > 0 {
-                write!(f, ", ")?;
-            }
-            write!(f, "{}", p)?;
-        }
-        write!(f, ") {{")?;
+        write!(
+            f,
+            "function{} {}({}){} {{",
+            if self.literal.generator { "*" } else { "" },
+            self.name,
+            self.literal.params_source(),
+            self.literal.contracts_source()
+        )?;
         write!(f, "{}", self.literal.body)?;
         write!(f, "}}")
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TestStatement {
     pub name: String,
     pub body: BlockStatement,
@@ -510,7 +914,50 @@ impl Display for TestStatement {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// `describe "name" { ... }`: a group of tests sharing setup statements and
+/// `beforeEach`/`afterEach` hooks. Groups nest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DescribeStatement {
+    pub name: String,
+    pub body: BlockStatement,
+}
+
+impl Display for DescribeStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "describe \"{}\" {{{}}}", self.name, self.body)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HookKind {
+    BeforeEach,
+    AfterEach,
+}
+
+impl HookKind {
+    pub fn keyword(self) -> &'static str {
+        match self {
+            HookKind::BeforeEach => "beforeEach",
+            HookKind::AfterEach => "afterEach",
+        }
+    }
+}
+
+/// `beforeEach { ... }` / `afterEach { ... }`: run around every test in the
+/// enclosing file or `describe` group.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HookStatement {
+    pub kind: HookKind,
+    pub body: BlockStatement,
+}
+
+impl Display for HookStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {{{}}}", self.kind.keyword(), self.body)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct NamespaceStatement {
     pub name: Identifier,
     pub body: BlockStatement,
@@ -524,21 +971,94 @@ impl Display for NamespaceStatement {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ImportStatement {
     pub path: String,
+    /// Names requested via `import { a, b } from "..."`; empty for a whole-module import.
+    pub names: Vec<Identifier>,
 }
 
 impl Display for ImportStatement {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "import \"{}\";", self.path)
+        if self.names.is_empty() {
+            return write!(f, "import \"{}\";", self.path);
+        }
+        let names: Vec<String> = self.names.iter().map(|n| n.value.clone()).collect();
+        write!(f, "import {{ {} }} from \"{}\";", names.join(", "), self.path)
+    }
+}
+
+/// `use A::B;`, `use A::B as C;` or `use A::*;`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UseStatement {
+    /// The namespace path, e.g. `A`, `B` for `A::B`.
+    pub path: Vec<Identifier>,
+    /// The name to bind; `None` binds the last name in `path`.
+    pub alias: Option<Identifier>,
+    /// `use A::*`, which binds every member of `A` instead.
+    pub glob: bool,
+}
+
+impl UseStatement {
+    /// The name `use` binds, unless it is a `::*` import.
+    pub fn binding(&self) -> Option<&Identifier> {
+        if self.glob {
+            return None;
+        }
+        self.alias.as_ref().or(self.path.last())
+    }
+}
+
+impl Display for UseStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let path: Vec<&str> = self.path.iter().map(|n| n.value.as_str()).collect();
+        write!(f, "use {}", path.join("::"))?;
+        if self.glob {
+            write!(f, "::*")?;
+        }
+        if let Some(alias) = &self.alias {
+            write!(f, " as {}", alias)?;
+        }
+        write!(f, ";")
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// `export <declaration>` or `export { a, b };`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportStatement {
+    /// The exported declaration, if this is the `export <declaration>` form.
+    pub declaration: Option<Box<Statement>>,
+    pub names: Vec<Identifier>,
+}
+
+impl Display for ExportStatement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.declaration {
+            Some(decl) => write!(f, "export {}", decl),
+            None => {
+                let names: Vec<String> = self.names.iter().map(|n| n.value.clone()).collect();
+                write!(f, "export {{ {} }};", names.join(", "))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClassStatement {
     pub name: Identifier,
     pub methods: Vec<FunctionStatement>,
+    /// Source line of each method, parallel to `methods`.
+    pub lines: Vec<usize>,
+    /// Source line of the closing brace.
+    pub end_line: usize,
+}
+
+/// Classes are equal when their names and methods are, wherever they were
+/// written.
+impl PartialEq for ClassStatement {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.methods == other.methods
+    }
 }
 
 impl Display for ClassStatement {
@@ -551,7 +1071,7 @@ impl Display for ClassStatement {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CallExpression {
     pub function: Box<Expression>, // identifier or fn literal
     pub arguments: Vec<Expression>,
@@ -570,7 +1090,18 @@ impl Display for CallExpression {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpreadExpression {
+    pub value: Expression,
+}
+
+impl Display for SpreadExpression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "...{}", self.value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct NewExpression {
     pub class_name: Identifier,
     pub arguments: Vec<Expression>,
@@ -589,7 +1120,7 @@ impl Display for NewExpression {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PublishExpression {
     pub args: Vec<Expression>,
     /// Each inner vec is a tag group for a publish stage in the chain.
@@ -640,10 +1171,9 @@ mod tests {
     #[test]
     fn program_display_renders_let() {
         let stmt = Statement::Let(LetStatement {
-            name: Identifier {
-                value: "x".to_string(),
-            },
-            value: Expression::IntegerLiteral(IntegerLiteral { value: 5 }),
+            name: Identifier::new("x"),
+            value: Expression::IntegerLiteral(IntegerLiteral { value: 5, lexeme: None }),
+            constant: false,
         });
 
         let mut program = Program::new();
@@ -655,12 +1185,12 @@ mod tests {
     #[test]
     fn infix_display_renders_parens() {
         let expr = Expression::Infix(InfixExpression {
-            left: Box::new(Expression::IntegerLiteral(IntegerLiteral { value: 1 })),
+            left: Box::new(Expression::IntegerLiteral(IntegerLiteral { value: 1, lexeme: None })),
             operator: InfixOp::Plus,
             right: Box::new(Expression::Infix(InfixExpression {
-                left: Box::new(Expression::IntegerLiteral(IntegerLiteral { value: 2 })),
+                left: Box::new(Expression::IntegerLiteral(IntegerLiteral { value: 2, lexeme: None })),
                 operator: InfixOp::Multiply,
-                right: Box::new(Expression::IntegerLiteral(IntegerLiteral { value: 3 })),
+                right: Box::new(Expression::IntegerLiteral(IntegerLiteral { value: 3, lexeme: None })),
             })),
         });
 
@@ -0,0 +1,2 @@
+pub mod core;
+pub use core::{BuildInfo, build_info};
@@ -0,0 +1,72 @@
+//! Details of the interpreter build, for `slang --version --json` and for
+//! embedders that need to check compatibility before running scripts.
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BuildInfo {
+    /// Crate version (semver), as reported by `Sys::version()`.
+    pub version: &'static str,
+    /// Abbreviated commit hash, or `None` when built outside a git checkout.
+    pub git_hash: Option<&'static str>,
+    /// Cargo features compiled in.
+    pub features: Vec<&'static str>,
+    /// Target triple, e.g. `x86_64-unknown-linux-gnu`.
+    pub target: &'static str,
+}
+
+impl BuildInfo {
+    /// One-line summary for `slang --version`.
+    pub fn summary(&self) -> String {
+        match self.git_hash {
+            Some(hash) => format!("slang {} ({} {})", self.version, hash, self.target),
+            None => format!("slang {} ({})", self.version, self.target),
+        }
+    }
+}
+
+pub fn build_info() -> BuildInfo {
+    let mut features = Vec::new();
+    for (name, enabled) in [
+        ("debug-logging", cfg!(feature = "debug-logging")),
+        ("http", cfg!(feature = "http")),
+        ("regex", cfg!(feature = "regex")),
+        ("encoding", cfg!(feature = "encoding")),
+        ("crypto", cfg!(feature = "crypto")),
+        ("formats", cfg!(feature = "formats")),
+        ("repl", cfg!(feature = "repl")),
+        ("cache", cfg!(feature = "cache")),
+        ("crash-report", cfg!(feature = "crash-report")),
+        ("signals", cfg!(feature = "signals")),
+    ] {
+        if enabled {
+            features.push(name);
+        }
+    }
+
+    let git_hash = env!("SLANG_GIT_HASH");
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        git_hash: (!git_hash.is_empty()).then_some(git_hash),
+        features,
+        target: env!("SLANG_TARGET"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build_info;
+
+    #[test]
+    fn build_info_serializes_for_tooling() {
+        let info = build_info();
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert!(!info.target.is_empty());
+        assert!(info.summary().starts_with(&format!("slang {} (", info.version)));
+
+        let json: serde_json::Value = serde_json::to_value(&info).unwrap();
+        for key in ["version", "git_hash", "features", "target"] {
+            assert!(json.get(key).is_some(), "missing {}", key);
+        }
+    }
+}
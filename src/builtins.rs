@@ -1,3 +1,4 @@
+pub mod docs;
 pub mod native;
 
 pub use native::get;
\ No newline at end of file
@@ -0,0 +1,1357 @@
+//! Hover and completion docs for the builtins, used by `slang lsp`. They
+//! live here rather than being scraped from the README, so rewording the
+//! README can't change what editors show.
+
+/// A documented builtin: the names it covers (several for entries like
+/// `Option::isSome` / `Option::isNone`) and its markdown text.
+pub struct BuiltinDoc {
+    pub names: &'static [&'static str],
+    pub text: &'static str,
+}
+
+pub static DOCS: &[BuiltinDoc] = &[
+    BuiltinDoc {
+        names: &["Option::Some"],
+        text: r#"`Option::Some(value)` – wrap a value in an `Option`."#,
+    },
+    BuiltinDoc {
+        names: &["Option::None"],
+        text: r#"`Option::None()` – create an empty option."#,
+    },
+    BuiltinDoc {
+        names: &["Option::isSome", "Option::isNone"],
+        text: r#"`Option::isSome(opt)` / `Option::isNone(opt)` – boolean checks."#,
+    },
+    BuiltinDoc {
+        names: &["Option::unwrapOr"],
+        text: r#"`Option::unwrapOr(opt, default)` – returns the inner value or a default."#,
+    },
+    BuiltinDoc {
+        names: &["Option::map", "Option::fmap"],
+        text: r#"`Option::map(opt, fn)` / `Option::fmap(opt, fn)` – transform the inner value if present."#,
+    },
+    BuiltinDoc {
+        names: &["Option::andThen", "Option::bind"],
+        text: r#"`Option::andThen(opt, fn)` / `Option::bind(opt, fn)` – monadic bind; `fn` should return an `Option`."#,
+    },
+    BuiltinDoc {
+        names: &["Option::unwrap"],
+        text: r#"`Option::unwrap(opt)` – returns the inner value; `None` is an error."#,
+    },
+    BuiltinDoc {
+        names: &["Option::expect"],
+        text: r#"`Option::expect(opt, message)` – like `unwrap`, but `None` is an error reading `message`."#,
+    },
+    BuiltinDoc {
+        names: &["Option::okOr"],
+        text: r#"`Option::okOr(opt, err)` – `Some(v)` becomes `Ok(v)`, `None` becomes `Err(err)`."#,
+    },
+    BuiltinDoc {
+        names: &["Option::filter"],
+        text: r#"`Option::filter(opt, fn)` – keeps `Some(v)` only when `fn(v)` returns `true`."#,
+    },
+    BuiltinDoc {
+        names: &["Option::zip"],
+        text: r#"`Option::zip(a, b)` – `Some([x, y])` when both are `Some`, otherwise `None`."#,
+    },
+    BuiltinDoc {
+        names: &["Option::flatten"],
+        text: r#"`Option::flatten(opt)` – turns `Some(Some(v))` into `Some(v)` and `Some(None())` into `None`."#,
+    },
+    BuiltinDoc {
+        names: &["Option::toArray"],
+        text: r#"`Option::toArray(opt)` – `[v]` for `Some(v)`, `[]` for `None`."#,
+    },
+    BuiltinDoc {
+        names: &["Result::Ok", "Result::Err"],
+        text: r#"`Result::Ok(value)` / `Result::Err(errorValue)`."#,
+    },
+    BuiltinDoc {
+        names: &["Result::isOk", "Result::isErr"],
+        text: r#"`Result::isOk(res)` / `Result::isErr(res)`."#,
+    },
+    BuiltinDoc {
+        names: &["Result::unwrapOr"],
+        text: r#"`Result::unwrapOr(res, default)` – returns inner value on `Ok`, default on `Err`."#,
+    },
+    BuiltinDoc {
+        names: &["Result::map", "Result::fmap"],
+        text: r#"`Result::map(res, fn)` / `Result::fmap(res, fn)` – transform the success value."#,
+    },
+    BuiltinDoc {
+        names: &["Result::andThen", "Result::bind"],
+        text: r#"`Result::andThen(res, fn)` / `Result::bind(res, fn)` – monadic bind; `fn` should return a `Result`."#,
+    },
+    BuiltinDoc {
+        names: &["Result::mapErr"],
+        text: r#"`Result::mapErr(res, fn)` – transform the error value."#,
+    },
+    BuiltinDoc {
+        names: &["Result::unwrap"],
+        text: r#"`Result::unwrap(res)` – returns the success value; `Err(e)` is an error mentioning `e`."#,
+    },
+    BuiltinDoc {
+        names: &["Result::expect"],
+        text: r#"`Result::expect(res, message)` – like `unwrap`, but the error reads `message: e`."#,
+    },
+    BuiltinDoc {
+        names: &["Result::ok"],
+        text: r#"`Result::ok(res)` – `Ok(v)` becomes `Some(v)`, `Err(_)` becomes `None`."#,
+    },
+    BuiltinDoc {
+        names: &["Type::int"],
+        text: r#"`Type::int(value)` → `Result::Ok(Integer)` or `Result::Err("...")`"#,
+    },
+    BuiltinDoc {
+        names: &["Type::float"],
+        text: r#"`Type::float(value)` → `Result::Ok(Float)` or `Result::Err("...")`"#,
+    },
+    BuiltinDoc {
+        names: &["Type::str"],
+        text: r#"`Type::str(value)` → `Result::Ok(String)` (never errors)"#,
+    },
+    BuiltinDoc {
+        names: &["Type::bool"],
+        text: r#"`Type::bool(value)` → `Result::Ok(Boolean)` or `Result::Err("...")`"#,
+    },
+    BuiltinDoc {
+        names: &["Type::toInt"],
+        text: r#"`Type::toInt(x)` – integers unchanged; floats truncate toward zero, so `Type::toInt(-2.7)` is `-2`. NaN, infinities and floats outside the integer range are errors."#,
+    },
+    BuiltinDoc {
+        names: &["Type::toFloat"],
+        text: r#"`Type::toFloat(x)` – integers become floats (exactly up to 2^53, the nearest float beyond); floats unchanged."#,
+    },
+    BuiltinDoc {
+        names: &["Type::of"],
+        text: r#"`Type::of(value)` – returns a string describing the type: `"integer"`, `"float"`, `"boolean"`, `"string"`, `"array"`, `"object"`, `"function"`, `"option"`, `"result"`, `"null"`."#,
+    },
+    BuiltinDoc {
+        names: &["Type::isInt", "Type::isFloat", "Type::isNumber"],
+        text: r#"`Type::isInt(value)` / `Type::isFloat(value)` / `Type::isNumber(value)` – numeric type checks."#,
+    },
+    BuiltinDoc {
+        names: &["Type::isBool", "Type::isString"],
+        text: r#"`Type::isBool(value)` / `Type::isString(value)` – primitive type checks."#,
+    },
+    BuiltinDoc {
+        names: &["Type::isArray", "Type::isObject"],
+        text: r#"`Type::isArray(value)` / `Type::isObject(value)` – compound type checks."#,
+    },
+    BuiltinDoc {
+        names: &["Type::isCallable"],
+        text: r#"`Type::isCallable(value)` – returns `true` for functions and builtins."#,
+    },
+    BuiltinDoc {
+        names: &["Type::isIterable"],
+        text: r#"`Type::isIterable(value)` – returns `true` for arrays and strings."#,
+    },
+    BuiltinDoc {
+        names: &["Type::isNull"],
+        text: r#"`Type::isNull(value)` – returns `true` if value is `null`."#,
+    },
+    BuiltinDoc {
+        names: &["Type::isOption", "Type::isResult"],
+        text: r#"`Type::isOption(value)` / `Type::isResult(value)` – monad type checks."#,
+    },
+    BuiltinDoc {
+        names: &["Array::map"],
+        text: r#"`Array::map(arr, fn)` – returns a new array with `fn(element)` applied to each element."#,
+    },
+    BuiltinDoc {
+        names: &["Array::filter"],
+        text: r#"`Array::filter(arr, fn)` – returns a new array containing only elements where `fn(element)` is `true`."#,
+    },
+    BuiltinDoc {
+        names: &["Array::parMap", "Array::parFilter"],
+        text: r#"`Array::parMap(arr, fn)` / `Array::parFilter(arr, fn)` – like `map` and `filter`, but the calls are split across one thread per CPU, each running its own interpreter (as a `Task` does), and the results keep the array's order. Meant for pure, CPU-heavy callbacks: side effects such as printing happen in no particular order. When the callback can see a ref, file, seq, bloom filter or host function, whose shared state a copy on another thread would lose, the calls run in order on the current thread instead, as they also do on a single-CPU machine."#,
+    },
+    BuiltinDoc {
+        names: &["Array::reduce"],
+        text: r#"`Array::reduce(arr, initial, fn)` – folds the array from left to right, calling `fn(acc, element)`."#,
+    },
+    BuiltinDoc {
+        names: &["Array::find"],
+        text: r#"`Array::find(arr, fn)` – returns `Option::Some(element)` for the first element where `fn(element)` is `true`, or `Option::None()` if none match."#,
+    },
+    BuiltinDoc {
+        names: &["Array::some"],
+        text: r#"`Array::some(arr, fn)` – returns `true` if any element matches the predicate."#,
+    },
+    BuiltinDoc {
+        names: &["Array::every"],
+        text: r#"`Array::every(arr, fn)` – returns `true` if all elements match the predicate."#,
+    },
+    BuiltinDoc {
+        names: &["Array::flatMap"],
+        text: r#"`Array::flatMap(arr, fn)` – maps each element to an array and concatenates the results."#,
+    },
+    BuiltinDoc {
+        names: &["Array::forEach"],
+        text: r#"`Array::forEach(arr, fn)` – executes `fn(element)` for each element (side effects only, returns `null`)."#,
+    },
+    BuiltinDoc {
+        names: &["Array::groupBy"],
+        text: r#"`Array::groupBy(arr, fn)` – groups elements by the string key returned by `fn(element)`."#,
+    },
+    BuiltinDoc {
+        names: &["Array::countBy"],
+        text: r#"`Array::countBy(arr, fn)` – counts elements by the key returned by `fn(element)`, e.g. `{ fruit: 2, vegetable: 1 }`."#,
+    },
+    BuiltinDoc {
+        names: &["Array::frequencies"],
+        text: r#"`Array::frequencies(arr)` – counts how often each string, integer or boolean value occurs, e.g. `Array::frequencies(["a", "b", "a"])` is `{ a: 2, b: 1 }`. Object keys are strings, so values that print the same are counted together: `Array::frequencies([1, "1"])` is `{ 1: 2 }`."#,
+    },
+    BuiltinDoc {
+        names: &["Array::partition"],
+        text: r#"`Array::partition(arr, fn)` – splits into `[matching, non-matching]` based on predicate."#,
+    },
+    BuiltinDoc {
+        names: &["Array::minBy", "Array::maxBy"],
+        text: r#"`Array::minBy(arr, fn)` / `Array::maxBy(arr, fn)` – returns `Option::Some(element)` for the first element with the smallest / largest key `fn(element)` (numbers or strings), or `Option::None()` for an empty array."#,
+    },
+    BuiltinDoc {
+        names: &["Array::sumBy"],
+        text: r#"`Array::sumBy(arr, fn)` – sums the numbers returned by `fn(element)`; an empty array sums to `0`."#,
+    },
+    BuiltinDoc {
+        names: &["Array::sort"],
+        text: r#"`Array::sort(arr)` – returns a new sorted array (ascending order, homogeneous types only)."#,
+    },
+    BuiltinDoc {
+        names: &["Array::sortBy"],
+        text: r#"`Array::sortBy(arr, compareFn)` – custom sort using `compareFn(a, b)` returning negative/zero/positive."#,
+    },
+    BuiltinDoc {
+        names: &["Array::insertSorted"],
+        text: r#"`Array::insertSorted(arr, value[, compareFn])` – returns a new array with `value` inserted in order (after any equal elements), so a sorted array stays sorted without re-sorting."#,
+    },
+    BuiltinDoc {
+        names: &["Array::indexOf"],
+        text: r#"`Array::indexOf(arr, value)` – returns `Option::Some(index)` or `Option::None()`."#,
+    },
+    BuiltinDoc {
+        names: &["Array::includes"],
+        text: r#"`Array::includes(arr, value)` – returns `true` if the value exists in the array."#,
+    },
+    BuiltinDoc {
+        names: &["Array::binarySearch"],
+        text: r#"`Array::binarySearch(arr, value)` – finds `value` in an array sorted with `Array::sort` in O(log n); returns `Option::Some(index)` or `Option::None()`."#,
+    },
+    BuiltinDoc {
+        names: &["Array::binarySearchBy"],
+        text: r#"`Array::binarySearchBy(arr, fn)` – binary search where `fn(element)` returns negative if the element comes before the target, `0` on a match and positive if after."#,
+    },
+    BuiltinDoc {
+        names: &["Array::slice"],
+        text: r#"`Array::slice(arr, start, end)` – extracts a portion (supports negative indices)."#,
+    },
+    BuiltinDoc {
+        names: &["Array::take"],
+        text: r#"`Array::take(arr, n)` – returns the first `n` elements."#,
+    },
+    BuiltinDoc {
+        names: &["Array::drop"],
+        text: r#"`Array::drop(arr, n)` – returns all elements after the first `n`."#,
+    },
+    BuiltinDoc {
+        names: &["Array::takeWhile"],
+        text: r#"`Array::takeWhile(arr, pred)` – returns the leading elements for which `pred` is `true`."#,
+    },
+    BuiltinDoc {
+        names: &["Array::dropWhile"],
+        text: r#"`Array::dropWhile(arr, pred)` (alias `Array::skipWhile`) – returns the elements from the first one for which `pred` is `false`."#,
+    },
+    BuiltinDoc {
+        names: &["Array::concat"],
+        text: r#"`Array::concat(arr1, arr2)` – concatenates two arrays."#,
+    },
+    BuiltinDoc {
+        names: &["Array::reverse"],
+        text: r#"`Array::reverse(arr)` – returns a new reversed array."#,
+    },
+    BuiltinDoc {
+        names: &["Array::flatten"],
+        text: r#"`Array::flatten(arr)` – flattens one level of nested arrays."#,
+    },
+    BuiltinDoc {
+        names: &["Array::unique"],
+        text: r#"`Array::unique(arr)` – returns a new array with duplicates removed."#,
+    },
+    BuiltinDoc {
+        names: &["Array::zip"],
+        text: r#"`Array::zip(arr1, arr2)` – combines into array of pairs `[[a1, b1], [a2, b2], ...]`."#,
+    },
+    BuiltinDoc {
+        names: &["Array::unzip"],
+        text: r#"`Array::unzip(arr)` – splits array of pairs into `[[a1, a2, ...], [b1, b2, ...]]`."#,
+    },
+    BuiltinDoc {
+        names: &["Array::product"],
+        text: r#"`Array::product(a, b, ...)` – the cartesian product, e.g. `Seq::toArray(Array::product([1, 2], ["x", "y"]))` is `[[1, "x"], [1, "y"], [2, "x"], [2, "y"]]`."#,
+    },
+    BuiltinDoc {
+        names: &["Array::combinations"],
+        text: r#"`Array::combinations(arr, k)` – every `k`-element selection, keeping the original order within each one."#,
+    },
+    BuiltinDoc {
+        names: &["Array::permutations"],
+        text: r#"`Array::permutations(arr[, k])` – every ordering of `k` elements (all of them by default)."#,
+    },
+    BuiltinDoc {
+        names: &["Array::range"],
+        text: r#"`Array::range(start, end[, step])` – creates an array of integers from `start` to `end` (exclusive)."#,
+    },
+    BuiltinDoc {
+        names: &["Array::fill"],
+        text: r#"`Array::fill(value, count)` – creates an array with `count` copies of `value`."#,
+    },
+    BuiltinDoc {
+        names: &["Array::isEmpty"],
+        text: r#"`Array::isEmpty(arr)` – returns `true` if the array has no elements."#,
+    },
+    BuiltinDoc {
+        names: &["Array::len"],
+        text: r#"`Array::len(arr)` – returns the number of elements."#,
+    },
+    BuiltinDoc {
+        names: &["Array::first", "Array::last"],
+        text: r#"`Array::first(arr)` / `Array::last(arr)` – first / last element (`null` for an empty array)."#,
+    },
+    BuiltinDoc {
+        names: &["Array::rest"],
+        text: r#"`Array::rest(arr)` – all elements after the first."#,
+    },
+    BuiltinDoc {
+        names: &["Array::push"],
+        text: r#"`Array::push(arr, value)` – returns a new array with `value` appended."#,
+    },
+    BuiltinDoc {
+        names: &["Array::pushMut"],
+        text: r#"`Array::pushMut(ref, value)` – appends `value` to the array held by a `Ref` in place and returns the new length (see **Ref**)."#,
+    },
+    BuiltinDoc {
+        names: &["Object::keys"],
+        text: r#"`Object::keys(obj)` – returns an array of all keys."#,
+    },
+    BuiltinDoc {
+        names: &["Object::values"],
+        text: r#"`Object::values(obj)` – returns an array of all values."#,
+    },
+    BuiltinDoc {
+        names: &["Object::entries"],
+        text: r#"`Object::entries(obj)` – returns an array of `[key, value]` pairs."#,
+    },
+    BuiltinDoc {
+        names: &["Object::fromEntries"],
+        text: r#"`Object::fromEntries(arr)` – creates an object from an array of `[key, value]` pairs."#,
+    },
+    BuiltinDoc {
+        names: &["Object::has"],
+        text: r#"`Object::has(obj, key)` – returns `true` if the key exists."#,
+    },
+    BuiltinDoc {
+        names: &["Object::get"],
+        text: r#"`Object::get(obj, key)` – returns `Option::Some(value)` or `Option::None()`."#,
+    },
+    BuiltinDoc {
+        names: &["Object::set"],
+        text: r#"`Object::set(obj, key, value)` – returns a new object with the key set (immutable)."#,
+    },
+    BuiltinDoc {
+        names: &["Object::setMut"],
+        text: r#"`Object::setMut(ref, key, value)` – sets the key on the object held by a `Ref` in place (see **Ref**)."#,
+    },
+    BuiltinDoc {
+        names: &["Object::delete"],
+        text: r#"`Object::delete(obj, key)` – returns a new object with the key removed (immutable)."#,
+    },
+    BuiltinDoc {
+        names: &["Object::merge"],
+        text: r#"`Object::merge(obj1, obj2)` – returns a new object combining both (obj2 values override obj1)."#,
+    },
+    BuiltinDoc {
+        names: &["Object::isEmpty"],
+        text: r#"`Object::isEmpty(obj)` – returns `true` if the object has no keys."#,
+    },
+    BuiltinDoc {
+        names: &["Object::len"],
+        text: r#"`Object::len(obj)` – returns the number of key-value pairs."#,
+    },
+    BuiltinDoc {
+        names: &["Object::map"],
+        text: r#"`Object::map(obj, fn(key, value))` – builds a new object from the `[key, value]` pair the function returns for each entry."#,
+    },
+    BuiltinDoc {
+        names: &["Object::mapValues"],
+        text: r#"`Object::mapValues(obj, fn(value))` – returns an object with the same keys and mapped values."#,
+    },
+    BuiltinDoc {
+        names: &["Object::filter"],
+        text: r#"`Object::filter(obj, fn(key, value))` – keeps the entries for which the function returns `true`."#,
+    },
+    BuiltinDoc {
+        names: &["Object::pick", "Object::omit"],
+        text: r#"`Object::pick(obj, keys)` / `Object::omit(obj, keys)` – keep only / drop the listed keys."#,
+    },
+    BuiltinDoc {
+        names: &["Object::mergeDeep"],
+        text: r#"`Object::mergeDeep(obj1, obj2)` – like `merge`, but objects under the same key are merged recursively (arrays and other values from obj2 replace those in obj1)."#,
+    },
+    BuiltinDoc {
+        names: &["Object::clone"],
+        text: r#"`Object::clone(value)` – returns a deep copy of any value; nested arrays, objects and `Ref`s are copied too, and the copy is never frozen."#,
+    },
+    BuiltinDoc {
+        names: &["Object::freeze"],
+        text: r#"`Object::freeze(value)` – returns a frozen copy of an object or array, along with every object and array nested in it; `value` itself is unchanged. Assigning to a property or index of a frozen value (`cfg.port = 1`, `cfg.ports[0] = 80`) or calling `Object::setMut` / `Array::pushMut` on it is an error; `Object::set`, `Array::push` and friends still return an ordinary, unfrozen copy."#,
+    },
+    BuiltinDoc {
+        names: &["Object::isFrozen"],
+        text: r#"`Object::isFrozen(value)` – returns `true` for an object or array frozen by `Object::freeze`."#,
+    },
+    BuiltinDoc {
+        names: &["String::trim"],
+        text: r#"`String::trim(s)` – trims leading and trailing whitespace."#,
+    },
+    BuiltinDoc {
+        names: &["String::toUpper", "String::toLower"],
+        text: r#"`String::toUpper(s)` / `String::toLower(s)` – case conversion."#,
+    },
+    BuiltinDoc {
+        names: &["String::toCamelCase"],
+        text: r#"`String::toCamelCase(s)`, `String::toSnakeCase(s)`, `String::toKebabCase(s)`, `String::toTitleCase(s)` – re-case identifiers and keys. Words are split at separators, at lower-to-upper changes and around acronyms, so `"parseHTTPResponse"` becomes `"parse_http_response"` and `"user ID"` becomes `"userId"`. Non-ASCII letters are handled (`"straße_nr"` becomes `"straßeNr"`)."#,
+    },
+    BuiltinDoc {
+        names: &["String::capitalize"],
+        text: r#"`String::capitalize(s)` – uppercases the first character, leaving the rest unchanged."#,
+    },
+    BuiltinDoc {
+        names: &["String::split"],
+        text: r#"`String::split(s, sep)` – splits into an array of strings (`sep == ""` splits into characters)."#,
+    },
+    BuiltinDoc {
+        names: &["String::join"],
+        text: r#"`String::join(arr, sep)` – joins an array of strings with a separator."#,
+    },
+    BuiltinDoc {
+        names: &["String::len"],
+        text: r#"`String::len(s)` – returns the number of characters (not bytes)."#,
+    },
+    BuiltinDoc {
+        names: &["String::isEmpty"],
+        text: r#"`String::isEmpty(s)` – returns `true` if the string has no characters."#,
+    },
+    BuiltinDoc {
+        names: &["String::contains"],
+        text: r#"`String::contains(s, substr)` – returns `true` if `substr` is found."#,
+    },
+    BuiltinDoc {
+        names: &["String::startsWith", "String::endsWith"],
+        text: r#"`String::startsWith(s, prefix)` / `String::endsWith(s, suffix)` – prefix/suffix checks."#,
+    },
+    BuiltinDoc {
+        names: &["String::indexOf"],
+        text: r#"`String::indexOf(s, substr)` – returns `Option::Some(index)` or `Option::None()`."#,
+    },
+    BuiltinDoc {
+        names: &["String::lastIndexOf"],
+        text: r#"`String::lastIndexOf(s, substr)` – returns index of last occurrence."#,
+    },
+    BuiltinDoc {
+        names: &["String::slice"],
+        text: r#"`String::slice(s, start, end)` – extracts a substring (supports negative indices)."#,
+    },
+    BuiltinDoc {
+        names: &["String::replace"],
+        text: r#"`String::replace(s, from, to)` – replaces the first occurrence."#,
+    },
+    BuiltinDoc {
+        names: &["String::replaceAll"],
+        text: r#"`String::replaceAll(s, from, to)` – replaces all occurrences."#,
+    },
+    BuiltinDoc {
+        names: &["String::repeat"],
+        text: r#"`String::repeat(s, count)` – repeats the string `count` times."#,
+    },
+    BuiltinDoc {
+        names: &["String::reverse"],
+        text: r#"`String::reverse(s)` – reverses the string."#,
+    },
+    BuiltinDoc {
+        names: &["String::padLeft", "String::padRight"],
+        text: r#"`String::padLeft(s, length, char)` / `String::padRight(s, length, char)` – pads to target length."#,
+    },
+    BuiltinDoc {
+        names: &["String::truncate"],
+        text: r#"`String::truncate(s, len[, ellipsis])` – shortens to at most `len` characters, ending with `ellipsis` (default `"..."`) if anything was cut."#,
+    },
+    BuiltinDoc {
+        names: &["String::wrap"],
+        text: r#"`String::wrap(s, width)` – word-wraps into an array of lines no longer than `width`. Existing line breaks are kept; words longer than `width` are split."#,
+    },
+    BuiltinDoc {
+        names: &["String::indent"],
+        text: r#"`String::indent(s, prefix)` – adds `prefix` to the start of every non-blank line."#,
+    },
+    BuiltinDoc {
+        names: &["String::format"],
+        text: r#"`String::format(template, ...args)` – fills `{}` placeholders in order, `{0}`/`{1}` by position, or `{name}` from a single object argument. A `:spec` after the selector controls layout: fill and alignment (`{:<8}`, `{:>8}`, `{:*^8}`), `+` for an explicit sign, zero padding (`{:05}`), precision (`{:.2}` for numbers, a maximum length for strings), and `x`/`X`/`b`/`o`/`e` for hex, binary, octal or scientific output. `{:?}` shows a value as `print` would, quotes included. Write `{{`/`}}` for literal braces. Also callable as `"...".format(...)`."#,
+    },
+    BuiltinDoc {
+        names: &["String::chars"],
+        text: r#"`String::chars(s)` – returns an array of single-character strings."#,
+    },
+    BuiltinDoc {
+        names: &["String::charCodeAt"],
+        text: r#"`String::charCodeAt(s, index)` – returns the Unicode code point at the index."#,
+    },
+    BuiltinDoc {
+        names: &["String::charCodes"],
+        text: r#"`String::charCodes(s)` – returns an array of all character codes."#,
+    },
+    BuiltinDoc {
+        names: &["String::fromCharCode"],
+        text: r#"`String::fromCharCode(code)` – creates a single-character string from a code point."#,
+    },
+    BuiltinDoc {
+        names: &["String::fromCharCodes"],
+        text: r#"`String::fromCharCodes(arr)` – creates a string from an array of code points."#,
+    },
+    BuiltinDoc {
+        names: &["String::words"],
+        text: r#"`String::words(s)` – splits into words (runs of letters, digits, `_` and apostrophes), dropping punctuation."#,
+    },
+    BuiltinDoc {
+        names: &["String::lines"],
+        text: r#"`String::lines(s)` – splits on `\n` or `\r\n`; a trailing newline doesn't add an empty line."#,
+    },
+    BuiltinDoc {
+        names: &["String::naturalCompare"],
+        text: r#"`String::naturalCompare(a, b)` – returns `-1`, `0` or `1`, comparing embedded numbers by value so `"file2"` sorts before `"file10"`. Pass it straight to `Array::sortBy`."#,
+    },
+    BuiltinDoc {
+        names: &["String::levenshtein"],
+        text: r#"`String::levenshtein(a, b)` – edit distance in characters, handy for "did you mean" suggestions."#,
+    },
+    BuiltinDoc {
+        names: &["Math::abs"],
+        text: r#"`Math::abs(x)` – absolute value."#,
+    },
+    BuiltinDoc {
+        names: &["Math::floor"],
+        text: r#"`Math::floor(x)`, `Math::ceil(x)`, `Math::round(x)`, `Math::trunc(x)` – round toward negative infinity, toward positive infinity, to nearest (halves away from zero) and toward zero. All four always return an **integer**, and give an error for NaN, infinities and floats too large for an integer."#,
+    },
+    BuiltinDoc {
+        names: &["Math::roundTo"],
+        text: r#"`Math::roundTo(x, digits)` – rounds to `digits` decimal places (halves away from zero); negative `digits` round to tens, hundreds, and so on. Unlike `round`, it keeps the type: a float stays a float (`Math::roundTo(3.14159, 2)` is `3.14`)."#,
+    },
+    BuiltinDoc {
+        names: &["Math::floorDiv"],
+        text: r#"`Math::floorDiv(a, b)` – division rounded toward negative infinity, like `a div b`: `Math::floorDiv(-7, 2)` is `-4` where `-7 / 2` is `-3.5`."#,
+    },
+    BuiltinDoc {
+        names: &["Math::divmod"],
+        text: r#"`Math::divmod(a, b)` – `[Math::floorDiv(a, b), remainder]`; the remainder has the sign of `b`, so `Math::divmod(-7, 2)` is `[-4, 1]`. Integers give integers; any float gives floats."#,
+    },
+    BuiltinDoc {
+        names: &["Math::min"],
+        text: r#"`Math::min(a, b)`, `Math::max(a, b)` – minimum/maximum of two numbers."#,
+    },
+    BuiltinDoc {
+        names: &["Math::pow"],
+        text: r#"`Math::pow(base, exp)` – exponentiation."#,
+    },
+    BuiltinDoc {
+        names: &["Math::sqrt"],
+        text: r#"`Math::sqrt(x)` – square root."#,
+    },
+    BuiltinDoc {
+        names: &["Math::sign"],
+        text: r#"`Math::sign(x)` – returns -1, 0, or 1."#,
+    },
+    BuiltinDoc {
+        names: &["Math::clamp"],
+        text: r#"`Math::clamp(x, min, max)` – constrains a value to a range."#,
+    },
+    BuiltinDoc {
+        names: &["Math::sin"],
+        text: r#"`Math::sin(x)`, `Math::cos(x)`, `Math::tan(x)` – basic trig (radians)."#,
+    },
+    BuiltinDoc {
+        names: &["Math::asin"],
+        text: r#"`Math::asin(x)`, `Math::acos(x)`, `Math::atan(x)` – inverse trig."#,
+    },
+    BuiltinDoc {
+        names: &["Math::atan2"],
+        text: r#"`Math::atan2(y, x)` – two-argument arctangent."#,
+    },
+    BuiltinDoc {
+        names: &["Math::sinh"],
+        text: r#"`Math::sinh(x)`, `Math::cosh(x)`, `Math::tanh(x)` – hyperbolic functions."#,
+    },
+    BuiltinDoc {
+        names: &["Math::log"],
+        text: r#"`Math::log(x)` – natural logarithm (base e)."#,
+    },
+    BuiltinDoc {
+        names: &["Math::log10"],
+        text: r#"`Math::log10(x)` – base-10 logarithm."#,
+    },
+    BuiltinDoc {
+        names: &["Math::log2"],
+        text: r#"`Math::log2(x)` – base-2 logarithm."#,
+    },
+    BuiltinDoc {
+        names: &["Math::exp"],
+        text: r#"`Math::exp(x)` – e raised to the power x."#,
+    },
+    BuiltinDoc {
+        names: &["Math::PI"],
+        text: r#"`Math::PI` – π (3.14159...)."#,
+    },
+    BuiltinDoc {
+        names: &["Math::E"],
+        text: r#"`Math::E` – Euler's number e (2.71828...)."#,
+    },
+    BuiltinDoc {
+        names: &["Math::TAU"],
+        text: r#"`Math::TAU` – τ = 2π (6.28318...)."#,
+    },
+    BuiltinDoc {
+        names: &["Math::random"],
+        text: r#"`Math::random()` – returns a random float in [0, 1)."#,
+    },
+    BuiltinDoc {
+        names: &["Math::randomInt"],
+        text: r#"`Math::randomInt(min, max)` – returns a random integer in [min, max]."#,
+    },
+    BuiltinDoc {
+        names: &["Math::randomHex"],
+        text: r#"`Math::randomHex(n)` – returns `n` (at most 1048576) random lowercase hex digits from the operating system's secure generator, for tokens and identifiers."#,
+    },
+    BuiltinDoc {
+        names: &["Number::parse"],
+        text: r#"`Number::parse(s[, options])` – returns `Option::Some(number)` or `Option::None()` if `s` isn't a well-formed number. Integers come back as integers, values with a fractional part as floats."#,
+    },
+    BuiltinDoc {
+        names: &["Schema::string"],
+        text: r#"`Schema::string([options])` – `min` / `max` length and a regex `pattern`."#,
+    },
+    BuiltinDoc {
+        names: &["Schema::int"],
+        text: r#"`Schema::int([options])`, `Schema::float([options])` – `min` / `max` bounds. Float schemas accept integers too."#,
+    },
+    BuiltinDoc {
+        names: &["Schema::bool"],
+        text: r#"`Schema::bool()`, `Schema::any()`."#,
+    },
+    BuiltinDoc {
+        names: &["Schema::array"],
+        text: r#"`Schema::array(itemSchema[, options])` – every element must match `itemSchema`; `min` / `max` bound the length."#,
+    },
+    BuiltinDoc {
+        names: &["Schema::object"],
+        text: r#"`Schema::object(fields[, options])` – `fields` maps names to schemas. With `strict: true`, fields not listed are errors."#,
+    },
+    BuiltinDoc {
+        names: &["Schema::optional"],
+        text: r#"`Schema::optional(schema)` – also accepts `null` (and missing fields). Every constructor also takes `optional: true`."#,
+    },
+    BuiltinDoc {
+        names: &["Schema::validate"],
+        text: r#"`Schema::validate(schema, value)` – returns `Result::Ok(value)` or `Result::Err(errors)`, where each error is a string like `"address.city: expected string, got integer"` or `"tags[1]: length must be at most 10"`."#,
+    },
+    BuiltinDoc {
+        names: &["Schema::coerce"],
+        text: r#"`Schema::coerce(schema, value)` – like `validate`, but first converts values with an obvious reading as the expected type (`"8080"` to `8080`, `"true"` to `true`, `42` to `"42"`, `1` to `1.0`) and returns the converted value."#,
+    },
+    BuiltinDoc {
+        names: &["Bloom::new"],
+        text: r#"`Bloom::new(expectedItems, falsePositiveRate)` – sizes the filter so that after `expectedItems` additions, `mightContain` wrongly says yes about `falsePositiveRate` of the time."#,
+    },
+    BuiltinDoc {
+        names: &["Bloom::add"],
+        text: r#"`Bloom::add(filter, item)` – records `item` (a string, number or boolean). Returns `true` if it was definitely new, `false` if it may have been added before."#,
+    },
+    BuiltinDoc {
+        names: &["Bloom::mightContain"],
+        text: r#"`Bloom::mightContain(filter, item)` – `false` means `item` was never added; `true` means it probably was."#,
+    },
+    BuiltinDoc {
+        names: &["Ref::new"],
+        text: r#"`Ref::new(value)` – creates a ref holding `value`."#,
+    },
+    BuiltinDoc {
+        names: &["Ref::get"],
+        text: r#"`Ref::get(ref)` – returns (a copy of) the current value."#,
+    },
+    BuiltinDoc {
+        names: &["Ref::set"],
+        text: r#"`Ref::set(ref, value)` – replaces the held value and returns it."#,
+    },
+    BuiltinDoc {
+        names: &["Array::pushMut"],
+        text: r#"`Array::pushMut(ref, value)` and `Object::setMut(ref, key, value)` update an array or object held by a ref in place."#,
+    },
+    BuiltinDoc {
+        names: &["Seq::from"],
+        text: r#"`Seq::from(arr)` (alias `Seq::fromArray`) – a sequence over an array's elements."#,
+    },
+    BuiltinDoc {
+        names: &["Seq::range"],
+        text: r#"`Seq::range(start, [end], [step])` – integers from `start`, stopping before `end`; without `end` the sequence never ends."#,
+    },
+    BuiltinDoc {
+        names: &["Seq::map"],
+        text: r#"`Seq::map(seq, f)`, `Seq::filter(seq, pred)`, `Seq::take(seq, n)`, `Seq::drop(seq, n)`."#,
+    },
+    BuiltinDoc {
+        names: &["Seq::takeWhile"],
+        text: r#"`Seq::takeWhile(seq, pred)`, `Seq::dropWhile(seq, pred)` (alias `Seq::skipWhile`)."#,
+    },
+    BuiltinDoc {
+        names: &["Seq::enumerate"],
+        text: r#"`Seq::enumerate(seq)` – `[index, value]` pairs."#,
+    },
+    BuiltinDoc {
+        names: &["Seq::toArray"],
+        text: r#"`Seq::toArray(seq)` (alias `Seq::collect`) – runs the sequence and collects every value. Never returns for an unbounded sequence, so limit it with `take` or `takeWhile` first."#,
+    },
+    BuiltinDoc {
+        names: &["Bytes::fromString"],
+        text: r#"`Bytes::fromString(s, [encoding])` – encodes a string (UTF-8 by default; also `"latin1"`, `"utf-16"`, `"utf-16le"`, `"utf-16be"`)."#,
+    },
+    BuiltinDoc {
+        names: &["Bytes::toString"],
+        text: r#"`Bytes::toString(b, [encoding])` – `Result::Ok(string)`, or `Result::Err(error)` when the bytes aren't valid in the encoding."#,
+    },
+    BuiltinDoc {
+        names: &["Bytes::len"],
+        text: r#"`Bytes::len(b)`, `Bytes::slice(b, start, [end])` – `slice` takes negative indices like `Array::slice`."#,
+    },
+    BuiltinDoc {
+        names: &["Bytes::concat"],
+        text: r#"`Bytes::concat(a, b, ...)` – joins byte strings, like `a + b`."#,
+    },
+    BuiltinDoc {
+        names: &["Bytes::fromArray"],
+        text: r#"`Bytes::fromArray(ints)`, `Bytes::toArray(b)` – convert to and from arrays of integers 0 to 255."#,
+    },
+    BuiltinDoc {
+        names: &["Encoding::base64Encode"],
+        text: r#"`Encoding::base64Encode(data, [{ urlSafe }])` – standard base64 with padding, or with `urlSafe: true` the URL-safe alphabet without padding."#,
+    },
+    BuiltinDoc {
+        names: &["Encoding::hexEncode"],
+        text: r#"`Encoding::hexEncode(data)` – lowercase hex."#,
+    },
+    BuiltinDoc {
+        names: &["Encoding::urlEncode"],
+        text: r#"`Encoding::urlEncode(data)` – percent-encodes everything except letters, digits and `-_.~`."#,
+    },
+    BuiltinDoc {
+        names: &["Encoding::base64Decode"],
+        text: r#"`Encoding::base64Decode(text, [{ urlSafe, bytes }])` – with `urlSafe`, padding is optional."#,
+    },
+    BuiltinDoc {
+        names: &["Encoding::hexDecode"],
+        text: r#"`Encoding::hexDecode(text, [{ bytes }])` – upper- or lowercase digits."#,
+    },
+    BuiltinDoc {
+        names: &["Encoding::urlDecode"],
+        text: r#"`Encoding::urlDecode(text, [{ bytes }])` – decodes `%XX` escapes and leaves `+` alone."#,
+    },
+    BuiltinDoc {
+        names: &["Crypto::md5"],
+        text: r#"`Crypto::md5(data, [{ bytes }])`, `Crypto::sha1(data, [{ bytes }])`, `Crypto::sha256(data, [{ bytes }])` – MD5 and SHA-1 are only fit for checksums and legacy APIs."#,
+    },
+    BuiltinDoc {
+        names: &["Crypto::hmacSha256"],
+        text: r#"`Crypto::hmacSha256(key, data, [{ bytes }])` – the key may be a string or bytes, so a `{ bytes: true }` result can key the next HMAC."#,
+    },
+    BuiltinDoc {
+        names: &["Crypto::randomBytes"],
+        text: r#"`Crypto::randomBytes(n)` – `n` bytes (at most 1 MiB) from the operating system's secure random generator."#,
+    },
+    BuiltinDoc {
+        names: &["Uuid::v4"],
+        text: r#"`Uuid::v4()` – a random UUID, e.g. `"3f0c2a9e-4b1d-4c8e-9a52-07d6e1f3b8c4"`."#,
+    },
+    BuiltinDoc {
+        names: &["Uuid::v7"],
+        text: r#"`Uuid::v7()` – a UUID that starts with the current Unix time in milliseconds, so v7 UUIDs sort in the order they were made (also within the same millisecond). Good for database keys."#,
+    },
+    BuiltinDoc {
+        names: &["Time::now"],
+        text: r#"`Time::now()` – returns the current Unix timestamp in milliseconds."#,
+    },
+    BuiltinDoc {
+        names: &["Time::nowSecs"],
+        text: r#"`Time::nowSecs()` – returns the current Unix timestamp in seconds."#,
+    },
+    BuiltinDoc {
+        names: &["Time::year"],
+        text: r#"`Time::year(ts)`, `Time::month(ts)`, `Time::day(ts)` – date components (UTC)."#,
+    },
+    BuiltinDoc {
+        names: &["Time::hour"],
+        text: r#"`Time::hour(ts)`, `Time::minute(ts)`, `Time::second(ts)` – time components (UTC)."#,
+    },
+    BuiltinDoc {
+        names: &["Time::dayOfWeek"],
+        text: r#"`Time::dayOfWeek(ts)` – returns 0-6 (Sunday = 0)."#,
+    },
+    BuiltinDoc {
+        names: &["Time::format"],
+        text: r#"`Time::format(ts, formatStr)` – formats using strftime syntax (e.g., `"%Y-%m-%d %H:%M:%S"`)."#,
+    },
+    BuiltinDoc {
+        names: &["Time::toObject"],
+        text: r#"`Time::toObject(ts)` – returns an object with `year`, `month`, `day`, `hour`, `minute`, `second`, `dayOfWeek`."#,
+    },
+    BuiltinDoc {
+        names: &["Time::fromComponents"],
+        text: r#"`Time::fromComponents({ year, month, day, hour, minute, second, millis })` – builds a timestamp. Only `year` is required; `month` and `day` default to 1 and the rest to 0. The fields are read as UTC unless the object also has `zone` (e.g. `"Europe/Paris"`) or `offset` (minutes east of UTC), so the objects returned by `Time::toObject` and `Time::toZone` convert back unchanged."#,
+    },
+    BuiltinDoc {
+        names: &["Time::parse"],
+        text: r#"`Time::parse(text, formatStr)` – returns `Result::Ok(ts)` or `Result::Err(message)`. Understands `%Y`, `%m`, `%d`, `%H`, `%M`, `%S`, `%f` (fraction of a second), `%z` (`Z`, `+02:00` or `+0200`) and `%%`; without `%z` the text is read as UTC."#,
+    },
+    BuiltinDoc {
+        names: &["Time::add"],
+        text: r#"`Time::add(ts, { years, months, weeks, days, hours, minutes, seconds, millis })` – any field may be negative. Years and months follow the calendar and clamp the day (Jan 31 plus one month is Feb 29 in a leap year); the other units are exact durations."#,
+    },
+    BuiltinDoc {
+        names: &["Time::diff"],
+        text: r#"`Time::diff(a, b)` – the time from `b` to `a` as `{ millis, seconds, minutes, hours, days, weeks }`, each truncated towards zero."#,
+    },
+    BuiltinDoc {
+        names: &["Time::toZone"],
+        text: r#"`Time::toZone(ts, zone)` – the local date and time in `zone`: the `Time::toObject` fields plus `millis`, `offset` (minutes east of UTC), `abbreviation` (e.g. `"CEST"`) and `zone`. `zone` is an IANA name read from the system tz database (`$TZDIR` or `/usr/share/zoneinfo`), `"UTC"`, or a fixed offset such as `"+05:30"`."#,
+    },
+    BuiltinDoc {
+        names: &["Time::sleep"],
+        text: r#"`Time::sleep(ms)` – pauses execution for the specified milliseconds."#,
+    },
+    BuiltinDoc {
+        names: &["Timer::after"],
+        text: r#"`Timer::after(ms, fn)` – runs `fn` once after `ms` milliseconds; returns a timer handle."#,
+    },
+    BuiltinDoc {
+        names: &["Timer::every"],
+        text: r#"`Timer::every(ms, fn)` – runs `fn` every `ms` milliseconds until cancelled; returns a timer handle."#,
+    },
+    BuiltinDoc {
+        names: &["Timer::cancel"],
+        text: r#"`Timer::cancel(handle)` – cancels a pending timer; returns `false` if it already fired or was cancelled."#,
+    },
+    BuiltinDoc {
+        names: &["Runtime::run"],
+        text: r#"`Runtime::run()` – dispatches timers in due order, sleeping in between, and returns once none are pending. An error from a callback stops the loop and is returned."#,
+    },
+    BuiltinDoc {
+        names: &["Runtime::onShutdown"],
+        text: r#"`Runtime::onShutdown(fn)` – registers a cleanup callback. Callbacks run once, most recently registered first, when the script finishes (even with an error), calls `Sys::exit`, or receives SIGINT/SIGTERM. After a signal the process exits with status 130 once the callbacks are done; a second signal exits immediately."#,
+    },
+    BuiltinDoc {
+        names: &["Events::subscribe"],
+        text: r#"`Events::subscribe(tag, fn)` – adds `fn` to the subscribers of `tag`, like a `(:Tag)` annotation."#,
+    },
+    BuiltinDoc {
+        names: &["Events::publish"],
+        text: r#"`Events::publish(tag, ...values)` – queues the values for the subscribers of `tag`; returns immediately."#,
+    },
+    BuiltinDoc {
+        names: &["Events::run"],
+        text: r#"`Events::run()` – delivers queued events, oldest first, until none are left (events published by handlers included). A handler's error stops delivery and is returned."#,
+    },
+    BuiltinDoc {
+        names: &["Events::pending"],
+        text: r#"`Events::pending()` – the number of events waiting to be delivered."#,
+    },
+    BuiltinDoc {
+        names: &["Task::spawn"],
+        text: r#"`Task::spawn(fn, ...args)` – runs `fn(args...)` on a new thread; returns a task handle."#,
+    },
+    BuiltinDoc {
+        names: &["Task::join"],
+        text: r#"`Task::join(handle)` – waits for the task and returns what `fn` returned. If the task failed, its error is returned. A task can be joined once."#,
+    },
+    BuiltinDoc {
+        names: &["Task::isDone"],
+        text: r#"`Task::isDone(handle)` – whether the task has finished, without waiting."#,
+    },
+    BuiltinDoc {
+        names: &["Task::sleep"],
+        text: r#"`Task::sleep(ms)` – same as `Time::sleep`."#,
+    },
+    BuiltinDoc {
+        names: &["Channel::new"],
+        text: r#"`Channel::new()` – returns a channel handle."#,
+    },
+    BuiltinDoc {
+        names: &["Channel::send"],
+        text: r#"`Channel::send(ch, value)` – queues a copy of `value`; errors once the channel is closed."#,
+    },
+    BuiltinDoc {
+        names: &["Channel::recv"],
+        text: r#"`Channel::recv(ch)` – `Some(value)` for the oldest queued value, waiting for one if the channel is empty. Returns `None` once the channel is closed and empty."#,
+    },
+    BuiltinDoc {
+        names: &["Channel::tryRecv"],
+        text: r#"`Channel::tryRecv(ch)` – like `recv` but returns `None` straight away when nothing is queued."#,
+    },
+    BuiltinDoc {
+        names: &["Channel::close"],
+        text: r#"`Channel::close(ch)` – no more values can be sent; queued ones can still be received."#,
+    },
+    BuiltinDoc {
+        names: &["Sys::env"],
+        text: r#"`Sys::env()` – returns an object with all environment variables."#,
+    },
+    BuiltinDoc {
+        names: &["Sys::env"],
+        text: r#"`Sys::env(name)` – returns `Option::Some(value)` or `Option::None()` for a specific variable."#,
+    },
+    BuiltinDoc {
+        names: &["Sys::setEnv"],
+        text: r#"`Sys::setEnv(name, value)` – sets an environment variable."#,
+    },
+    BuiltinDoc {
+        names: &["Sys::args"],
+        text: r#"`Sys::args()` – returns an array of command-line arguments."#,
+    },
+    BuiltinDoc {
+        names: &["Sys::cwd"],
+        text: r#"`Sys::cwd()` – returns the current working directory."#,
+    },
+    BuiltinDoc {
+        names: &["Sys::setCwd"],
+        text: r#"`Sys::setCwd(path)` – changes the current working directory."#,
+    },
+    BuiltinDoc {
+        names: &["Sys::platform"],
+        text: r#"`Sys::platform` – the OS name (e.g., `"macos"`, `"linux"`, `"windows"`)."#,
+    },
+    BuiltinDoc {
+        names: &["Sys::arch"],
+        text: r#"`Sys::arch()` – returns the CPU architecture (e.g., `"x86_64"`, `"aarch64"`)."#,
+    },
+    BuiltinDoc {
+        names: &["Sys::dryRun"],
+        text: r#"`Sys::dryRun()` – returns `true` when running with `--dry-run`."#,
+    },
+    BuiltinDoc {
+        names: &["Sys::version"],
+        text: r#"`Sys::version()` – returns the interpreter version (e.g., `"0.1.0"`)."#,
+    },
+    BuiltinDoc {
+        names: &["Sys::hasNamespace"],
+        text: r#"`Sys::hasNamespace(name)` – returns `true` if `name` is bound to a namespace (e.g., `Sys::hasNamespace("HTTP")`)."#,
+    },
+    BuiltinDoc {
+        names: &["Sys::features"],
+        text: r#"`Sys::features()` – returns an array of enabled cargo features (such as `"debug-logging"` or `"http"`) and active runtime modes (`"dry-run"`, `"audit"`)."#,
+    },
+    BuiltinDoc {
+        names: &["Sys::exit"],
+        text: r#"`Sys::exit(code)` – exits the process with the given status code."#,
+    },
+    BuiltinDoc {
+        names: &["Sys::exec"],
+        text: r#"`Sys::exec(command)` – executes a shell command, returns `Result::Ok({ code, stdout, stderr })` or `Result::Err(error)`."#,
+    },
+    BuiltinDoc {
+        names: &["Process::spawn"],
+        text: r#"`Process::spawn(cmd[, args[, options]])` – starts `cmd` with an array of string arguments, returns `Result::Ok(handle)` or `Result::Err(error)` if it can't be started."#,
+    },
+    BuiltinDoc {
+        names: &["Process::run"],
+        text: r#"`Process::run(cmd[, args[, options]])` – runs `cmd` to completion and returns `Result::Ok({ status, stdout, stderr })`, or `Result::Err` of the same object when it exits with a non-zero status."#,
+    },
+    BuiltinDoc {
+        names: &["Process::readStdout", "Process::readStderr"],
+        text: r#"`Process::readStdout(handle)` / `Process::readStderr(handle)` – waits for output and returns `Option::Some(text)` with whatever has arrived, or `Option::None()` once the stream is closed."#,
+    },
+    BuiltinDoc {
+        names: &["Process::writeStdin"],
+        text: r#"`Process::writeStdin(handle, text)` – writes to the child's stdin, returns `Result::Ok(null)` or `Result::Err(error)`."#,
+    },
+    BuiltinDoc {
+        names: &["Process::closeStdin"],
+        text: r#"`Process::closeStdin(handle)` – closes stdin so the child sees end of input."#,
+    },
+    BuiltinDoc {
+        names: &["Process::wait"],
+        text: r#"`Process::wait(handle)` – closes stdin, waits for the child to exit and returns its status (`-1` if it was killed by a signal)."#,
+    },
+    BuiltinDoc {
+        names: &["Process::kill"],
+        text: r#"`Process::kill(handle)` – kills the child; returns `false` if it had already exited."#,
+    },
+    BuiltinDoc {
+        names: &["HTTP::get"],
+        text: r#"`HTTP::get(url[, options])` – performs a GET request."#,
+    },
+    BuiltinDoc {
+        names: &["HTTP::post"],
+        text: r#"`HTTP::post(url, body[, options])` – performs a POST request."#,
+    },
+    BuiltinDoc {
+        names: &["HTTP::put"],
+        text: r#"`HTTP::put(url, body[, options])` – performs a PUT request."#,
+    },
+    BuiltinDoc {
+        names: &["HTTP::delete"],
+        text: r#"`HTTP::delete(url[, options])` – performs a DELETE request."#,
+    },
+    BuiltinDoc {
+        names: &["HTTP::patch"],
+        text: r#"`HTTP::patch(url, body[, options])` – performs a PATCH request."#,
+    },
+    BuiltinDoc {
+        names: &["HTTP::head"],
+        text: r#"`HTTP::head(url[, options])` – performs a HEAD request."#,
+    },
+    BuiltinDoc {
+        names: &["HttpServer::listen"],
+        text: r#"`HttpServer::listen(port, handler[, options])` – serves HTTP/1.1 on `port` and calls `handler(request)` for each request, one at a time. It blocks until `maxRequests` requests have been handled (or forever, until Ctrl-C), then returns `Result::Ok(null)`; failing to bind the port gives `Result::Err(message)`."#,
+    },
+    BuiltinDoc {
+        names: &["Fn::identity"],
+        text: r#"`Fn::identity(x)` – returns its argument unchanged."#,
+    },
+    BuiltinDoc {
+        names: &["Fn::constant"],
+        text: r#"`Fn::constant(value)` – returns a function that always returns `value`."#,
+    },
+    BuiltinDoc {
+        names: &["Fn::compose"],
+        text: r#"`Fn::compose(f, g)` – returns `fn(x) { f(g(x)) }` (right-to-left composition)."#,
+    },
+    BuiltinDoc {
+        names: &["Fn::pipe"],
+        text: r#"`Fn::pipe(f, g)` – returns `fn(x) { g(f(x)) }` (left-to-right composition)."#,
+    },
+    BuiltinDoc {
+        names: &["Fn::apply"],
+        text: r#"`Fn::apply(fn, argsArray)` – calls `fn` with arguments from an array."#,
+    },
+    BuiltinDoc {
+        names: &["Fn::call"],
+        text: r#"`Fn::call(fn, ...args)` – calls `fn` with the provided arguments."#,
+    },
+    BuiltinDoc {
+        names: &["Fn::negate"],
+        text: r#"`Fn::negate(predicateFn)` – returns a function that negates the predicate result."#,
+    },
+    BuiltinDoc {
+        names: &["Fn::flip"],
+        text: r#"`Fn::flip(fn)` – returns a function with the first two arguments swapped."#,
+    },
+    BuiltinDoc {
+        names: &["Fn::partial"],
+        text: r#"`Fn::partial(fn, ...boundArgs)` – returns a partially applied function."#,
+    },
+    BuiltinDoc {
+        names: &["Fn::isCallable"],
+        text: r#"`Fn::isCallable(value)` – returns `true` if the value can be called as a function."#,
+    },
+    BuiltinDoc {
+        names: &["Fn::tap"],
+        text: r#"`Fn::tap(value, fn)` – calls `fn(value)` and returns `value`, to look inside a pipeline: `xs |> Fn::tap(print) |> len`."#,
+    },
+    BuiltinDoc {
+        names: &["Fn::times"],
+        text: r#"`Fn::times(n, fn)` – `[fn(0), fn(1), ..., fn(n - 1)]`."#,
+    },
+    BuiltinDoc {
+        names: &["Fn::once"],
+        text: r#"`Fn::once(fn)` – returns a function that calls `fn` on its first call and returns that result from then on."#,
+    },
+    BuiltinDoc {
+        names: &["Queue::empty", "Queue::from"],
+        text: r#"`Queue::empty()` / `Queue::from(array)` – a new queue (the array's first element at the front)."#,
+    },
+    BuiltinDoc {
+        names: &["Queue::push"],
+        text: r#"`Queue::push(queue, value)` – adds `value` at the back."#,
+    },
+    BuiltinDoc {
+        names: &["Queue::peek"],
+        text: r#"`Queue::peek(queue)` – `Some(front)`, or `None` when empty."#,
+    },
+    BuiltinDoc {
+        names: &["Queue::pop"],
+        text: r#"`Queue::pop(queue)` – `Some([front, rest])`, or `None` when empty."#,
+    },
+    BuiltinDoc {
+        names: &["Queue::len"],
+        text: r#"`Queue::len(queue)`, `Queue::isEmpty(queue)`, `Queue::toArray(queue)`."#,
+    },
+    BuiltinDoc {
+        names: &["Regex::isMatch"],
+        text: r#"`Regex::isMatch(text, pattern)` – boolean match test."#,
+    },
+    BuiltinDoc {
+        names: &["Regex::find"],
+        text: r#"`Regex::find(text, pattern)` – returns `Option::Some(match)` or `Option::None()`."#,
+    },
+    BuiltinDoc {
+        names: &["Regex::replace"],
+        text: r#"`Regex::replace(text, pattern, replacement)` – returns a new string with replacements."#,
+    },
+    BuiltinDoc {
+        names: &["Regex::match"],
+        text: r#"`Regex::match(text, pattern)` – returns `Option::Some(arrayOfGroups)` or `Option::None()`."#,
+    },
+    BuiltinDoc {
+        names: &["Regex::findAll"],
+        text: r#"`Regex::findAll(text, pattern)` – returns every matched string as an array."#,
+    },
+    BuiltinDoc {
+        names: &["Regex::matchAll"],
+        text: r#"`Regex::matchAll(text, pattern)` – returns an array of match objects (`match`, `start`, `end`, `groups`, `named`)."#,
+    },
+    BuiltinDoc {
+        names: &["Regex::captures"],
+        text: r#"`Regex::captures(text, pattern)` – returns `Option::Some(matchObject)` for the first match or `Option::None()`."#,
+    },
+    BuiltinDoc {
+        names: &["Regex::split"],
+        text: r#"`Regex::split(text, pattern)` – splits `text` on every match of `pattern`."#,
+    },
+    BuiltinDoc {
+        names: &["File::open"],
+        text: r#"`File::open(path, mode, options?)` – returns `Result::Ok(file)` or `Result::Err(error)`. Modes are `r`, `w`, `a`, `r+`, `w+` and `a+`; add `b` (`"rb"`, `"w+b"`) for binary mode. In text mode `options.encoding` picks `"utf-8"` (the default), `"latin-1"`, `"utf-16"` (little-endian unless the file starts with a big-endian byte order mark), `"utf-16le"` or `"utf-16be"`."#,
+    },
+    BuiltinDoc {
+        names: &["File::read"],
+        text: r#"`File::read(file)` – returns `Result::Ok(string)` or `Result::Err(error)`; in binary mode the contents are bytes rather than a string. Data that isn't valid in the file's encoding is an `Err`, never replaced characters."#,
+    },
+    BuiltinDoc {
+        names: &["File::write"],
+        text: r#"`File::write(file, data)` – writes a string (encoded with the file's encoding) or bytes; returns `Result::Ok(unit)` or `Result::Err(error)`, e.g. when a latin-1 file is given `"€"`."#,
+    },
+    BuiltinDoc {
+        names: &["File::seek"],
+        text: r#"`File::seek(file, offset, origin)` – returns `Result::Ok(unit)` or `Result::Err(error)`."#,
+    },
+    BuiltinDoc {
+        names: &["File::close"],
+        text: r#"`File::close(file)` – returns `Result::Ok(unit)` or `Result::Err(error)`."#,
+    },
+    BuiltinDoc {
+        names: &["File::readLine"],
+        text: r#"`File::readLine(file)` – reads the next line (without its `\n` or `\r\n`) as `Ok(Option::Some(line))`, or `Ok(Option::None())` at the end of the file. Only the line is read, so large files can be processed one line at a time."#,
+    },
+    BuiltinDoc {
+        names: &["File::readLines"],
+        text: r#"`File::readLines(fileOrPath)` – the remaining lines of a handle, or every line of the file at a path, as an array."#,
+    },
+    BuiltinDoc {
+        names: &["File::readBytes"],
+        text: r#"`File::readBytes(file, n)` – reads at most `n` bytes as bytes, whatever the file's mode."#,
+    },
+    BuiltinDoc {
+        names: &["File::exists"],
+        text: r#"`File::exists(path)`, `File::size(pathOrFile)` – `Ok(bool)` and `Ok(sizeInBytes)`."#,
+    },
+    BuiltinDoc {
+        names: &["File::readToString"],
+        text: r#"`File::readToString(path, options?)`, `File::writeString(path, s, options?)` – read or replace a whole file in one call; `options.encoding` works as in `File::open`."#,
+    },
+    BuiltinDoc {
+        names: &["File::append"],
+        text: r#"`File::append(path, data)` – appends a string or bytes, creating the file if needed."#,
+    },
+    BuiltinDoc {
+        names: &["File::delete"],
+        text: r#"`File::delete(path)`, `File::copy(from, to)` – `copy` returns the number of bytes copied."#,
+    },
+    BuiltinDoc {
+        names: &["Dir::list"],
+        text: r#"`Dir::list(path)` – the names of the entries in a directory, sorted."#,
+    },
+    BuiltinDoc {
+        names: &["Dir::walk"],
+        text: r#"`Dir::walk(path)` – every file and directory below `path` (depth-first, in name order), each joined onto `path`. Symlinks are listed but not followed."#,
+    },
+    BuiltinDoc {
+        names: &["Dir::exists"],
+        text: r#"`Dir::exists(path)` – `Ok(true)` only if `path` is a directory."#,
+    },
+    BuiltinDoc {
+        names: &["Dir::create"],
+        text: r#"`Dir::create(path)` – creates the directory and any missing parents; an existing directory is not an error."#,
+    },
+    BuiltinDoc {
+        names: &["Dir::remove"],
+        text: r#"`Dir::remove(path, options?)` – removes an empty directory, or everything in it with `{ recursive: true }`."#,
+    },
+    BuiltinDoc {
+        names: &["Path::join"],
+        text: r#"`Path::join(a, b, ...)` – joins segments; an absolute segment replaces everything before it."#,
+    },
+    BuiltinDoc {
+        names: &["Path::basename"],
+        text: r#"`Path::basename(path)`, `Path::dirname(path)`, `Path::extension(path)` – `Option::Some(part)` or `Option::None()` when the path has no such part (`Path::extension("Makefile")`). The extension has no leading dot."#,
+    },
+    BuiltinDoc {
+        names: &["Path::absolute"],
+        text: r#"`Path::absolute(path)` – `Result::Ok` with `path` resolved against the current directory. The path doesn't have to exist, and symlinks are not resolved."#,
+    },
+    BuiltinDoc {
+        names: &["Json::parse"],
+        text: r#"`Json::parse(s)` – parses a JSON string into Slang values, returning `Result::Ok(value)` or `Result::Err(errorString)`."#,
+    },
+    BuiltinDoc {
+        names: &["Json::stringify"],
+        text: r#"`Json::stringify(value)` – converts a Slang value back into a JSON string, returning `Result::Ok(string)` or `Result::Err(errorString)`."#,
+    },
+    BuiltinDoc {
+        names: &["Yaml::parse"],
+        text: r#"`Yaml::parse(s)` – reads the first document in `s`."#,
+    },
+    BuiltinDoc {
+        names: &["Yaml::stringify"],
+        text: r#"`Yaml::stringify(value)` – block-style YAML ending in a newline."#,
+    },
+    BuiltinDoc {
+        names: &["Toml::parse"],
+        text: r#"`Toml::parse(s)` – always gives an object; dates and times become strings."#,
+    },
+    BuiltinDoc {
+        names: &["Toml::stringify"],
+        text: r#"`Toml::stringify(object)` – fails for anything but an object, and for `null` anywhere inside it, since TOML has neither."#,
+    },
+    BuiltinDoc {
+        names: &["Test::assert"],
+        text: r#"`Test::assert(condition)` – fails if `condition` is false."#,
+    },
+    BuiltinDoc {
+        names: &["Test::assertEq"],
+        text: r#"`Test::assertEq(expected, actual)` – equality assertion. When two arrays or objects differ, the failure lists each differing path (`- tags[1]: "b"` missing from `actual`, `+ admin: true` extra in `actual`, `~ name: "ada" -> "bob"` changed); long values are truncated and only the first 20 differences are shown. `slang test` colours results and diff lines when printing to a terminal, unless `NO_COLOR` is set."#,
+    },
+    BuiltinDoc {
+        names: &["Test::assertNotEq"],
+        text: r#"`Test::assertNotEq(expected, actual)` – inequality assertion."#,
+    },
+    BuiltinDoc {
+        names: &["Test::assertTrue", "Test::assertFalse"],
+        text: r#"`Test::assertTrue(value)` / `Test::assertFalse(value)` – the boolean `true`/`false` exactly, not just a truthy or falsy value."#,
+    },
+    BuiltinDoc {
+        names: &["Test::assertApproxEq"],
+        text: r#"`Test::assertApproxEq(expected, actual, epsilon)` – numbers within `epsilon` of each other; the failure shows how far off `actual` was."#,
+    },
+    BuiltinDoc {
+        names: &["Test::assertContains"],
+        text: r#"`Test::assertContains(haystack, needle)` – a substring of a string, an element of an array, or a key of an object."#,
+    },
+    BuiltinDoc {
+        names: &["Test::assertThrows"],
+        text: r#"`Test::assertThrows(fn)` – calls `fn` with no arguments and passes if it fails; returns the error message so you can check it too."#,
+    },
+    BuiltinDoc {
+        names: &["Test::assertMatch"],
+        text: r#"`Test::assertMatch(text, pattern)` – `text` matches the regex `pattern` (only with the `regex` feature)."#,
+    },
+    BuiltinDoc {
+        names: &["Test::assertSnapshot"],
+        text: r#"`Test::assertSnapshot(name, value)` – `value` matches the snapshot recorded under `name`."#,
+    },
+    BuiltinDoc {
+        names: &["Test::assertSome"],
+        text: r#"`Test::assertSome(option)`, `Test::assertNone(option)`, `Test::assertOk(result)`, `Test::assertErr(result)` – the value is that variant; all but `assertNone` return the value inside, so `let user = Test::assertOk(load());` keeps the test going."#,
+    },
+    BuiltinDoc {
+        names: &["Debug::inspect"],
+        text: r#"`Debug::inspect(value)` – prints `value` to stdout with nested arrays and objects spread over indented lines, then returns `value` unchanged, so it can wrap any expression."#,
+    },
+    BuiltinDoc {
+        names: &["Debug::pretty"],
+        text: r#"`Debug::pretty(value)` – the text `Debug::inspect` would print, as a string. Object keys keep their insertion order, and containers that fit in 60 columns stay on one line."#,
+    },
+    BuiltinDoc {
+        names: &["Log::debug"],
+        text: r#"`Log::debug(msg, [fields])`, `Log::info(...)`, `Log::warn(...)`, `Log::error(...)` – write one record. `fields` is an object of extra key/value pairs, e.g. `Log::info("request done", { status: 200, ms: 12 })` prints `INFO  request done ms=12 status=200`."#,
+    },
+    BuiltinDoc {
+        names: &["Log::setLevel"],
+        text: r#"`Log::setLevel(level)` – drop records below `level` (`"debug"`, `"info"`, `"warn"`, `"error"` or `"off"`). Without it, the level comes from the `SLANG_LOG` environment variable, defaulting to `info`."#,
+    },
+    BuiltinDoc {
+        names: &["Log::level"],
+        text: r#"`Log::level()` – the current level's name; `Log::enabled(level)` – whether a record at `level` would be written, to skip building expensive messages."#,
+    },
+    BuiltinDoc {
+        names: &["Log::setFormat"],
+        text: r#"`Log::setFormat(format)` – `"text"` (the default) or `"json"`, one object per line with `ts` (Unix milliseconds), `level`, `msg` and the fields. `SLANG_LOG_FORMAT=json` does the same from the environment."#,
+    },
+    BuiltinDoc {
+        names: &["len"],
+        text: r#"**len(x)** – length of a string or array."#,
+    },
+    BuiltinDoc {
+        names: &["print"],
+        text: r#"**print(...args)** – print values to stdout on one line, separated by spaces (used throughout the examples)."#,
+    },
+    BuiltinDoc {
+        names: &["println"],
+        text: r#"**println(...args)** – print each value to stdout on a line of its own; `println()` prints an empty line."#,
+    },
+    BuiltinDoc {
+        names: &["eprint"],
+        text: r#"**eprint(...args)** – like `print`, but to stderr."#,
+    },
+    BuiltinDoc {
+        names: &["printf"],
+        text: r#"**printf(template, ...args)** – print `String::format(template, ...args)` to stdout without a trailing newline."#,
+    },
+    BuiltinDoc {
+        names: &["debug"],
+        text: r#"**debug(bool)** – enable or disable Slang's internal debug logging."#,
+    },
+    BuiltinDoc {
+        names: &["int", "float", "str", "bool"],
+        text: r#"**int(x)** / **float(x)** / **str(x)** / **bool(x)** – raw conversions."#,
+    },
+];
+/// The docs for the builtin `symbol`, e.g. `Array::map`.
+pub fn doc(symbol: &str) -> Option<String> {
+    let entries: Vec<&str> = DOCS
+        .iter()
+        .filter(|doc| doc.names.contains(&symbol))
+        .map(|doc| doc.text)
+        .collect();
+    if entries.is_empty() {
+        None
+    } else {
+        Some(entries.join("\n\n"))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::env::new_env;
+    use crate::object::Object;
+
+    #[test]
+    fn every_documented_name_is_a_builtin() {
+        let env = new_env();
+        for name in DOCS.iter().flat_map(|doc| doc.names) {
+            let mut path = name.split("::");
+            let mut value = env.borrow().get(path.next().unwrap());
+            for member in path {
+                value = match value {
+                    Some(Object::Object(map)) => map.get(member).cloned(),
+                    _ => None,
+                };
+            }
+            assert!(value.is_some() || crate::builtins::get(name).is_some(), "{} is not a builtin", name);
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::cell::RefCell;
+
 use crate::env::EnvRef;
 use crate::object::Object;
 use crate::object::Object::Integer;
@@ -5,6 +7,7 @@ use crate::object::types::BuiltinFunction;
 
 pub mod monad_builtins;
 pub mod file_builtins;
+#[cfg(feature = "regex")]
 pub mod regex_builtins;
 pub mod test_builtins;
 pub mod array_builtins;
@@ -15,8 +18,31 @@ pub mod type_builtins;
 pub mod object_builtins;
 pub mod time_builtins;
 pub mod system_builtins;
+pub mod process_builtins;
+#[cfg(feature = "http")]
 pub mod http_builtins;
+#[cfg(feature = "http")]
+pub mod http_server_builtins;
 pub mod fn_builtins;
+pub mod number_builtins;
+pub mod runtime_builtins;
+pub mod task_builtins;
+pub mod schema_builtins;
+pub mod bloom_builtins;
+pub mod ref_builtins;
+pub mod dir_builtins;
+pub mod path_builtins;
+pub mod seq_builtins;
+pub mod bytes_builtins;
+pub mod uuid_builtins;
+pub mod debug_builtins;
+pub mod log_builtins;
+#[cfg(feature = "encoding")]
+pub mod encoding_builtins;
+#[cfg(feature = "crypto")]
+pub mod crypto_builtins;
+#[cfg(feature = "formats")]
+pub mod format_builtins;
 
 // Re-export file builtins so other modules (like env) can
 // attach them under namespaces without knowing the submodule path.
@@ -28,6 +54,33 @@ pub use file_builtins::{
     builtin_close,
 };
 
+/// Receives what scripts print in place of stdout.
+pub type StdoutSink = Box<dyn FnMut(&str)>;
+
+thread_local! {
+    static STDOUT_SINK: RefCell<Option<StdoutSink>> = const { RefCell::new(None) };
+}
+
+/// Send what `print`, `println`, `printf` and `Debug::inspect` write to `sink` instead
+/// of stdout (or back to stdout with `None`), e.g. when stdout carries a
+/// debugger protocol.
+pub fn redirect_stdout(sink: Option<StdoutSink>) {
+    STDOUT_SINK.with(|s| *s.borrow_mut() = sink);
+}
+
+/// Write `text` to stdout, or wherever [`redirect_stdout`] sent it.
+pub(crate) fn write_stdout(text: &str) {
+    STDOUT_SINK.with(|s| match s.borrow_mut().as_mut() {
+        Some(sink) => sink(text),
+        None => {
+            use std::io::Write;
+            let mut out = std::io::stdout();
+            let _ = out.write_all(text.as_bytes());
+            let _ = out.flush();
+        }
+    });
+}
+
 pub struct Builtin {
     pub name: &'static str,
     pub func: BuiltinFunction,
@@ -40,18 +93,15 @@ const BUILTINS: &[Builtin] = &[
     Builtin { name: "rest",  func: builtin_rest },
     Builtin { name: "push",  func: builtin_push },
     Builtin { name: "print", func: builtin_print },
+    Builtin { name: "println", func: builtin_println },
+    Builtin { name: "eprint", func: builtin_eprint },
+    Builtin { name: "printf", func: builtin_printf },
     Builtin { name: "debug", func: builtin_debug },
     Builtin { name: "int", func: type_builtins::builtin_int },
     Builtin { name: "float", func: type_builtins::builtin_float },
     Builtin { name: "str", func: type_builtins::builtin_str },
     Builtin { name: "bool", func: type_builtins::builtin_bool },
 
-    // Regex builtins
-    Builtin { name: "regexIsMatch", func: regex_builtins::builtin_regex_is_match },
-    Builtin { name: "regexFind", func: regex_builtins::builtin_regex_find },
-    Builtin { name: "regexReplace", func: regex_builtins::builtin_regex_replace },
-    Builtin { name: "regexMatch", func: regex_builtins::builtin_regex_match },
-
     // File builtins
     Builtin { name: "file_open", func: file_builtins::builtin_open },
     Builtin { name: "file_read", func: file_builtins::builtin_read },
@@ -65,8 +115,19 @@ const BUILTINS: &[Builtin] = &[
     Builtin { name: "test_assert_not_eq", func: test_builtins::test_assert_not_eq },
 ];
 
+#[cfg(feature = "regex")]
+const REGEX_BUILTINS: &[Builtin] = &[
+    Builtin { name: "regexIsMatch", func: regex_builtins::builtin_regex_is_match },
+    Builtin { name: "regexFind", func: regex_builtins::builtin_regex_find },
+    Builtin { name: "regexReplace", func: regex_builtins::builtin_regex_replace },
+    Builtin { name: "regexMatch", func: regex_builtins::builtin_regex_match },
+];
+
+#[cfg(not(feature = "regex"))]
+const REGEX_BUILTINS: &[Builtin] = &[];
+
 pub fn get(name: &str) -> Option<BuiltinFunction> {
-    for b in BUILTINS {
+    for b in BUILTINS.iter().chain(REGEX_BUILTINS) {
         if b.name == name {
             return Some(b.func);
         }
@@ -74,6 +135,11 @@ pub fn get(name: &str) -> Option<BuiltinFunction> {
     None
 }
 
+/// Names of every top-level builtin.
+pub fn names() -> impl Iterator<Item = &'static str> {
+    BUILTINS.iter().chain(REGEX_BUILTINS).map(|b| b.name)
+}
+
 // Native func implementations
 
 fn builtin_len(args: Vec<Object>, _env: EnvRef) -> Object {
@@ -84,11 +150,12 @@ fn builtin_len(args: Vec<Object>, _env: EnvRef) -> Object {
     match &args[0] {
         Object::String(s) => Integer(s.len() as i64),
         Object::Array(a) => Integer(a.len() as i64),
+        Object::Bytes(b) => Integer(b.len() as i64),
         other => Object::error(format!("len not supported for value: {:?}", other)),
     }
 }
 
-fn builtin_first(args: Vec<Object>, _env: EnvRef) -> Object {
+pub(crate) fn builtin_first(args: Vec<Object>, _env: EnvRef) -> Object {
     if args.len() != 1 {
         return Object::error("first expects exactly 1 argument");
     }
@@ -105,7 +172,7 @@ fn builtin_first(args: Vec<Object>, _env: EnvRef) -> Object {
     }
 }
 
-fn builtin_last(args: Vec<Object>, _env: EnvRef) -> Object {
+pub(crate) fn builtin_last(args: Vec<Object>, _env: EnvRef) -> Object {
     if args.len() != 1 {
         return Object::error("last expects exactly 1 argument");
     }
@@ -122,7 +189,7 @@ fn builtin_last(args: Vec<Object>, _env: EnvRef) -> Object {
     }
 }
 
-fn builtin_rest(args: Vec<Object>, _env: EnvRef) -> Object {
+pub(crate) fn builtin_rest(args: Vec<Object>, _env: EnvRef) -> Object {
     if args.len() != 1 {
         return Object::error("rest expects exactly 1 argument");
     }
@@ -130,16 +197,16 @@ fn builtin_rest(args: Vec<Object>, _env: EnvRef) -> Object {
     match &args[0] {
         Object::Array(elems) => {
             if elems.len() <= 1 {
-                Object::Array(vec![])
+                Object::array(vec![])
             } else {
-                Object::Array(elems[1..].to_vec())
+                Object::array(elems[1..].to_vec())
             }
         }
         other => Object::error(format!("rest expects array, got {:?}", other)),
     }
 }
 
-fn builtin_push(mut args: Vec<Object>, _env: EnvRef) -> Object {
+pub(crate) fn builtin_push(mut args: Vec<Object>, _env: EnvRef) -> Object {
     if args.len() != 2 {
         return Object::error("push expects exactly 2 arguments");
     }
@@ -149,24 +216,58 @@ fn builtin_push(mut args: Vec<Object>, _env: EnvRef) -> Object {
 
     match array {
         Object::Array(mut elems) => {
-            elems.push(value);
+            object_builtins::unfrozen_array_mut(&mut elems).push(value);
             Object::Array(elems)
         }
         other => Object::error(format!("push expects array as first argument, got {:?}", other)),
     }
 }
 
-fn builtin_print(args: Vec<Object>, _env: EnvRef) -> Object {
-    // Just print all args separated by space and newline
-    let text = args
-        .iter()
+/// The arguments of `print` and friends as one line: each value's display
+/// form, separated by spaces.
+fn print_line(args: &[Object]) -> String {
+    args.iter()
         .map(|o| o.to_string())
         .collect::<Vec<_>>()
-        .join(" ");
-    println!("{}", text);
+        .join(" ")
+}
+
+/// print(...args) – prints the values on one line.
+fn builtin_print(args: Vec<Object>, _env: EnvRef) -> Object {
+    write_stdout(&format!("{}\n", print_line(&args)));
     Object::Null
 }
 
+/// println(...args) – prints each value on a line of its own; with no
+/// arguments, an empty line.
+fn builtin_println(args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.is_empty() {
+        write_stdout("\n");
+    }
+    for arg in &args {
+        write_stdout(&format!("{}\n", arg));
+    }
+    Object::Null
+}
+
+/// eprint(...args) – like `print`, but to stderr.
+fn builtin_eprint(args: Vec<Object>, _env: EnvRef) -> Object {
+    eprintln!("{}", print_line(&args));
+    Object::Null
+}
+
+/// printf(template, ...args) – prints `String::format(template, ...args)`
+/// without adding a newline.
+fn builtin_printf(args: Vec<Object>, env: EnvRef) -> Object {
+    match string_builtins::string_format(args, env) {
+        Object::String(text) => {
+            write_stdout(&text);
+            Object::Null
+        }
+        err => err,
+    }
+}
+
 fn builtin_debug(args: Vec<Object>, _env: EnvRef) -> Object {
     if args.len() != 1 {
         return Object::error("debug expects exactly 1 argument");
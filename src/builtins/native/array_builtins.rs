@@ -1,8 +1,12 @@
 use std::rc::Rc;
 
 use crate::env::EnvRef;
+use crate::builtins::native::object_builtins::{is_frozen_array, unfrozen_array_mut};
+use crate::builtins::native::ref_builtins::{check_no_cycle, expect_ref};
 use crate::evaluator::core::expr::apply_function_with_this;
-use crate::object::Object;
+use crate::object::{Object, ObjectMap};
+use crate::object::types::SeqNode;
+use crate::task::core as task;
 
 /// Array::map(arr, f) – returns a new array with f(element) applied to each element.
 pub(crate) fn array_map(mut args: Vec<Object>, env: EnvRef) -> Object {
@@ -17,7 +21,7 @@ pub(crate) fn array_map(mut args: Vec<Object>, env: EnvRef) -> Object {
         Object::Array(elems) => {
             let mut out = Vec::with_capacity(elems.len());
 
-            for elem in elems.into_iter() {
+            for elem in Rc::unwrap_or_clone(elems) {
                 let result =
                     apply_function_with_this(func.clone(), vec![elem], None, Rc::clone(&env));
                 if result.is_error() {
@@ -26,7 +30,7 @@ pub(crate) fn array_map(mut args: Vec<Object>, env: EnvRef) -> Object {
                 out.push(result);
             }
 
-            Object::Array(out)
+            Object::array(out)
         }
         other => Object::error(format!(
             "Array::map expects an Array value as first argument, got {:?}",
@@ -68,7 +72,7 @@ pub(crate) fn array_filter(mut args: Vec<Object>, env: EnvRef) -> Object {
                 }
             }
 
-            Object::Array(out)
+            Object::array(out)
         }
         other => Object::error(format!(
             "Array::filter expects an Array value as first argument, got {:?}",
@@ -77,6 +81,71 @@ pub(crate) fn array_filter(mut args: Vec<Object>, env: EnvRef) -> Object {
     }
 }
 
+/// The array and callback of `Array::parMap`/`Array::parFilter`.
+fn par_args<'a>(args: &'a [Object], name: &str) -> Result<(&'a [Object], &'a Object), Object> {
+    match args {
+        [Object::Array(elems), func] => Ok((elems, func)),
+        [other, _] => Err(Object::error(format!(
+            "{} expects an Array value as first argument, got {:?}",
+            name, other
+        ))),
+        _ => Err(Object::error(format!("{} expects exactly 2 arguments (array, fn)", name))),
+    }
+}
+
+/// Array::parMap(arr, f) – like Array::map, with the calls spread across
+/// threads; see `task::core::par_apply` for when they run in order instead.
+pub(crate) fn array_par_map(args: Vec<Object>, env: EnvRef) -> Object {
+    let (elems, func) = match par_args(&args, "Array::parMap") {
+        Ok(parts) => parts,
+        Err(e) => return e,
+    };
+    match task::par_apply(func, elems, task::worker_count(), &env) {
+        Some(Ok(out)) => Object::array(out),
+        Some(Err(e)) => e,
+        None => array_map(args, env),
+    }
+}
+
+/// Array::parFilter(arr, f) – like Array::filter, with the predicate calls
+/// spread across threads.
+pub(crate) fn array_par_filter(args: Vec<Object>, env: EnvRef) -> Object {
+    let (elems, func) = match par_args(&args, "Array::parFilter") {
+        Ok(parts) => parts,
+        Err(e) => return e,
+    };
+    let keep = match task::par_apply(func, elems, task::worker_count(), &env) {
+        Some(Ok(keep)) => keep,
+        Some(Err(e)) => return e,
+        None => {
+            let mut keep = Vec::with_capacity(elems.len());
+            for elem in elems {
+                let predicate =
+                    apply_function_with_this(func.clone(), vec![elem.clone()], None, Rc::clone(&env));
+                if predicate.is_error() {
+                    return predicate;
+                }
+                keep.push(predicate);
+            }
+            keep
+        }
+    };
+    let mut out = Vec::new();
+    for (elem, predicate) in elems.iter().zip(keep) {
+        match predicate {
+            Object::Boolean(true) => out.push(elem.clone()),
+            Object::Boolean(false) => {}
+            other => {
+                return Object::error(format!(
+                    "Array::parFilter predicate must return boolean, got {:?}",
+                    other
+                ))
+            }
+        }
+    }
+    Object::array(out)
+}
+
 /// Array::reduce(arr, initial, f) – folds array with accumulator function f(acc, element).
 pub(crate) fn array_reduce(mut args: Vec<Object>, env: EnvRef) -> Object {
     if args.len() != 3 {
@@ -89,7 +158,7 @@ pub(crate) fn array_reduce(mut args: Vec<Object>, env: EnvRef) -> Object {
 
     match arr {
         Object::Array(elems) => {
-            for elem in elems.into_iter() {
+            for elem in Rc::unwrap_or_clone(elems) {
                 let result = apply_function_with_this(
                     func.clone(),
                     vec![acc, elem],
@@ -246,12 +315,12 @@ pub(crate) fn array_flat_map(mut args: Vec<Object>, env: EnvRef) -> Object {
         Object::Array(elems) => {
             let mut out = Vec::new();
 
-            for elem in elems.into_iter() {
+            for elem in Rc::unwrap_or_clone(elems) {
                 let result =
                     apply_function_with_this(func.clone(), vec![elem], None, Rc::clone(&env));
 
                 match result {
-                    Object::Array(inner) => out.extend(inner.into_iter()),
+                    Object::Array(inner) => out.extend(Rc::unwrap_or_clone(inner)),
                     other => {
                         return Object::error(format!(
                             "Array::flatMap expects function to return array, got {:?}",
@@ -261,7 +330,7 @@ pub(crate) fn array_flat_map(mut args: Vec<Object>, env: EnvRef) -> Object {
                 }
             }
 
-            Object::Array(out)
+            Object::array(out)
         }
         other => Object::error(format!(
             "Array::flatMap expects an Array value as first argument, got {:?}",
@@ -326,7 +395,7 @@ pub(crate) fn array_sort(args: Vec<Object>, _env: EnvRef) -> Object {
 
     match arr {
         Object::Array(mut elems) => {
-            elems.sort_by(compare_objects);
+            unfrozen_array_mut(&mut elems).sort_by(compare_objects);
             Object::Array(elems)
         }
         other => Object::error(format!(
@@ -351,7 +420,7 @@ pub(crate) fn array_sort_by(mut args: Vec<Object>, env: EnvRef) -> Object {
             // We need to handle errors during sorting
             let mut sort_error: Option<Object> = None;
 
-            elems.sort_by(|a, b| {
+            unfrozen_array_mut(&mut elems).sort_by(|a, b| {
                 if sort_error.is_some() {
                     return std::cmp::Ordering::Equal;
                 }
@@ -409,7 +478,7 @@ pub(crate) fn array_reverse(args: Vec<Object>, _env: EnvRef) -> Object {
 
     match arr {
         Object::Array(mut elems) => {
-            elems.reverse();
+            unfrozen_array_mut(&mut elems).reverse();
             Object::Array(elems)
         }
         other => Object::error(format!(
@@ -488,7 +557,7 @@ pub(crate) fn array_concat(args: Vec<Object>, _env: EnvRef) -> Object {
     };
 
     let mut result = elems1;
-    result.extend(elems2);
+    unfrozen_array_mut(&mut result).extend(elems2.iter().cloned());
     Object::Array(result)
 }
 
@@ -546,10 +615,10 @@ pub(crate) fn array_slice(args: Vec<Object>, _env: EnvRef) -> Object {
     };
 
     if start_idx >= end_idx {
-        return Object::Array(vec![]);
+        return Object::array(vec![]);
     }
 
-    Object::Array(elems[start_idx..end_idx].to_vec())
+    Object::array(elems[start_idx..end_idx].to_vec())
 }
 
 /// Array::take(arr, n) – returns first n elements.
@@ -584,7 +653,7 @@ pub(crate) fn array_take(args: Vec<Object>, _env: EnvRef) -> Object {
     }
 
     let take_count = (n_val as usize).min(elems.len());
-    Object::Array(elems[..take_count].to_vec())
+    Object::array(elems[..take_count].to_vec())
 }
 
 /// Array::drop(arr, n) – returns array without first n elements.
@@ -619,7 +688,64 @@ pub(crate) fn array_drop(args: Vec<Object>, _env: EnvRef) -> Object {
     }
 
     let drop_count = (n_val as usize).min(elems.len());
-    Object::Array(elems[drop_count..].to_vec())
+    Object::array(elems[drop_count..].to_vec())
+}
+
+/// Array::takeWhile(arr, f) – returns the leading elements for which f(element) is true.
+pub(crate) fn array_take_while(args: Vec<Object>, env: EnvRef) -> Object {
+    match leading_run(args, "Array::takeWhile", env) {
+        Ok((elems, n)) => Object::array(elems[..n].to_vec()),
+        Err(e) => e,
+    }
+}
+
+/// Array::dropWhile(arr, f) – returns the elements from the first one for which
+/// f(element) is false (also available as Array::skipWhile).
+pub(crate) fn array_drop_while(args: Vec<Object>, env: EnvRef) -> Object {
+    match leading_run(args, "Array::dropWhile", env) {
+        Ok((elems, n)) => Object::array(elems[n..].to_vec()),
+        Err(e) => e,
+    }
+}
+
+/// The array argument and how many leading elements satisfy the predicate.
+fn leading_run(
+    mut args: Vec<Object>,
+    name: &str,
+    env: EnvRef,
+) -> Result<(Rc<Vec<Object>>, usize), Object> {
+    if args.len() != 2 {
+        return Err(Object::error(format!("{} expects exactly 2 arguments (array, fn)", name)));
+    }
+
+    let func = args.pop().unwrap();
+    let elems = match args.pop().unwrap() {
+        Object::Array(elems) => elems,
+        other => {
+            return Err(Object::error(format!(
+                "{} expects an Array value as first argument, got {:?}",
+                name, other
+            )))
+        }
+    };
+
+    for (i, elem) in elems.iter().enumerate() {
+        let result =
+            apply_function_with_this(func.clone(), vec![elem.clone()], None, Rc::clone(&env));
+        match result {
+            Object::Boolean(true) => {}
+            Object::Boolean(false) => return Ok((elems, i)),
+            Object::Error(_) => return Err(result),
+            other => {
+                return Err(Object::error(format!(
+                    "{} predicate must return boolean, got {:?}",
+                    name, other
+                )))
+            }
+        }
+    }
+    let n = elems.len();
+    Ok((elems, n))
 }
 
 /// Array::range(start, end) – generates an array of integers from start (inclusive) to end (exclusive).
@@ -683,7 +809,7 @@ pub(crate) fn array_range(mut args: Vec<Object>, _env: EnvRef) -> Object {
         }
     }
 
-    Object::Array(result)
+    Object::array(result)
 }
 
 /// Array::unique(arr) – returns a new array with duplicate elements removed (preserves first occurrence).
@@ -698,14 +824,14 @@ pub(crate) fn array_unique(args: Vec<Object>, _env: EnvRef) -> Object {
             let mut seen = Vec::new();
             let mut result = Vec::new();
 
-            for elem in elems {
+            for elem in Rc::unwrap_or_clone(elems) {
                 if !seen.contains(&elem) {
                     seen.push(elem.clone());
                     result.push(elem);
                 }
             }
 
-            Object::Array(result)
+            Object::array(result)
         }
         other => Object::error(format!(
             "Array::unique expects an array, got {:?}",
@@ -725,14 +851,14 @@ pub(crate) fn array_flatten(args: Vec<Object>, _env: EnvRef) -> Object {
         Object::Array(elems) => {
             let mut result = Vec::new();
 
-            for elem in elems {
+            for elem in Rc::unwrap_or_clone(elems) {
                 match elem {
-                    Object::Array(inner) => result.extend(inner),
+                    Object::Array(inner) => result.extend(Rc::unwrap_or_clone(inner)),
                     other => result.push(other),
                 }
             }
 
-            Object::Array(result)
+            Object::array(result)
         }
         other => Object::error(format!(
             "Array::flatten expects an array, got {:?}",
@@ -768,13 +894,13 @@ pub(crate) fn array_zip(args: Vec<Object>, _env: EnvRef) -> Object {
         }
     };
 
-    let result: Vec<Object> = elems1
+    let result: Vec<Object> = Rc::unwrap_or_clone(elems1)
         .into_iter()
-        .zip(elems2.into_iter())
-        .map(|(a, b)| Object::Array(vec![a, b]))
+        .zip(Rc::unwrap_or_clone(elems2))
+        .map(|(a, b)| Object::array(vec![a, b]))
         .collect();
 
-    Object::Array(result)
+    Object::array(result)
 }
 
 /// Array::unzip(arr) – converts [[a,b], ...] to [[a,...], [b,...]].
@@ -789,7 +915,7 @@ pub(crate) fn array_unzip(args: Vec<Object>, _env: EnvRef) -> Object {
             let mut firsts = Vec::new();
             let mut seconds = Vec::new();
 
-            for elem in elems {
+            for elem in Rc::unwrap_or_clone(elems) {
                 match elem {
                     Object::Array(pair) if pair.len() >= 2 => {
                         firsts.push(pair[0].clone());
@@ -804,7 +930,7 @@ pub(crate) fn array_unzip(args: Vec<Object>, _env: EnvRef) -> Object {
                 }
             }
 
-            Object::Array(vec![Object::Array(firsts), Object::Array(seconds)])
+            Object::array(vec![Object::array(firsts), Object::array(seconds)])
         }
         other => Object::error(format!(
             "Array::unzip expects an array, got {:?}",
@@ -813,6 +939,17 @@ pub(crate) fn array_unzip(args: Vec<Object>, _env: EnvRef) -> Object {
     }
 }
 
+/// The object key used for a grouping/counting value: strings as-is, ints
+/// and bools as their text. Other values can't be keys.
+fn object_key(value: &Object) -> Option<String> {
+    match value {
+        Object::String(s) => Some(s.clone()),
+        Object::Integer(i) => Some(i.to_string()),
+        Object::Boolean(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
 /// Array::groupBy(arr, f) – groups elements by key returned by f.
 /// Returns an object { key: [elements] }.
 pub(crate) fn array_group_by(mut args: Vec<Object>, env: EnvRef) -> Object {
@@ -825,10 +962,10 @@ pub(crate) fn array_group_by(mut args: Vec<Object>, env: EnvRef) -> Object {
 
     match arr {
         Object::Array(elems) => {
-            let mut groups: std::collections::HashMap<String, Vec<Object>> =
-                std::collections::HashMap::new();
+            let mut groups: indexmap::IndexMap<String, Vec<Object>> =
+                indexmap::IndexMap::new();
 
-            for elem in elems {
+            for elem in Rc::unwrap_or_clone(elems) {
                 let key_result = apply_function_with_this(
                     func.clone(),
                     vec![elem.clone()],
@@ -836,15 +973,15 @@ pub(crate) fn array_group_by(mut args: Vec<Object>, env: EnvRef) -> Object {
                     Rc::clone(&env),
                 );
 
-                let key = match key_result {
-                    Object::String(s) => s,
-                    Object::Integer(i) => i.to_string(),
-                    Object::Boolean(b) => b.to_string(),
-                    Object::Error(_) => return key_result,
-                    other => {
+                if key_result.is_error() {
+                    return key_result;
+                }
+                let key = match object_key(&key_result) {
+                    Some(key) => key,
+                    None => {
                         return Object::error(format!(
                             "Array::groupBy key function must return string/int/bool, got {:?}",
-                            other
+                            key_result
                         ))
                     }
                 };
@@ -852,12 +989,12 @@ pub(crate) fn array_group_by(mut args: Vec<Object>, env: EnvRef) -> Object {
                 groups.entry(key).or_default().push(elem);
             }
 
-            let result: std::collections::HashMap<String, Object> = groups
+            let result: ObjectMap = groups
                 .into_iter()
-                .map(|(k, v)| (k, Object::Array(v)))
+                .map(|(k, v)| (k, Object::array(v)))
                 .collect();
 
-            Object::Object(result)
+            Object::from_map(result)
         }
         other => Object::error(format!(
             "Array::groupBy expects an array as first argument, got {:?}",
@@ -866,6 +1003,93 @@ pub(crate) fn array_group_by(mut args: Vec<Object>, env: EnvRef) -> Object {
     }
 }
 
+/// Array::countBy(arr, f) – counts elements by key returned by f.
+/// Returns an object { key: count }.
+pub(crate) fn array_count_by(mut args: Vec<Object>, env: EnvRef) -> Object {
+    if args.len() != 2 {
+        return Object::error("Array::countBy expects exactly 2 arguments (array, fn)");
+    }
+
+    let func = args.pop().unwrap();
+    let arr = args.pop().unwrap();
+
+    match arr {
+        Object::Array(elems) => {
+            let mut counts: ObjectMap =
+                ObjectMap::new();
+
+            for elem in Rc::unwrap_or_clone(elems) {
+                let key_result =
+                    apply_function_with_this(func.clone(), vec![elem], None, Rc::clone(&env));
+
+                if key_result.is_error() {
+                    return key_result;
+                }
+                let key = match object_key(&key_result) {
+                    Some(key) => key,
+                    None => {
+                        return Object::error(format!(
+                            "Array::countBy key function must return string/int/bool, got {:?}",
+                            key_result
+                        ))
+                    }
+                };
+
+                increment_count(&mut counts, key);
+            }
+
+            Object::from_map(counts)
+        }
+        other => Object::error(format!(
+            "Array::countBy expects an array as first argument, got {:?}",
+            other
+        )),
+    }
+}
+
+/// Array::frequencies(arr) – counts how often each value occurs.
+/// Returns an object { value: count }; keys are strings, so `1` and `"1"`
+/// are counted together.
+pub(crate) fn array_frequencies(args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.len() != 1 {
+        return Object::error("Array::frequencies expects exactly 1 argument (array)");
+    }
+
+    match args.into_iter().next().unwrap() {
+        Object::Array(elems) => {
+            let mut counts: ObjectMap =
+                ObjectMap::new();
+
+            for elem in Rc::unwrap_or_clone(elems) {
+                let key = match object_key(&elem) {
+                    Some(key) => key,
+                    None => {
+                        return Object::error(format!(
+                            "Array::frequencies expects string/int/bool elements, got {:?}",
+                            elem
+                        ))
+                    }
+                };
+
+                increment_count(&mut counts, key);
+            }
+
+            Object::from_map(counts)
+        }
+        other => Object::error(format!(
+            "Array::frequencies expects an array, got {:?}",
+            other
+        )),
+    }
+}
+
+fn increment_count(counts: &mut ObjectMap, key: String) {
+    let count = counts.entry(key).or_insert(Object::Integer(0));
+    if let Object::Integer(n) = count {
+        *n += 1;
+    }
+}
+
 /// Array::partition(arr, f) – splits array into [matches, nonMatches].
 pub(crate) fn array_partition(mut args: Vec<Object>, env: EnvRef) -> Object {
     if args.len() != 2 {
@@ -880,7 +1104,7 @@ pub(crate) fn array_partition(mut args: Vec<Object>, env: EnvRef) -> Object {
             let mut matches = Vec::new();
             let mut non_matches = Vec::new();
 
-            for elem in elems {
+            for elem in Rc::unwrap_or_clone(elems) {
                 let predicate = apply_function_with_this(
                     func.clone(),
                     vec![elem.clone()],
@@ -901,7 +1125,7 @@ pub(crate) fn array_partition(mut args: Vec<Object>, env: EnvRef) -> Object {
                 }
             }
 
-            Object::Array(vec![Object::Array(matches), Object::Array(non_matches)])
+            Object::array(vec![Object::array(matches), Object::array(non_matches)])
         }
         other => Object::error(format!(
             "Array::partition expects an array as first argument, got {:?}",
@@ -931,8 +1155,8 @@ pub(crate) fn array_fill(args: Vec<Object>, _env: EnvRef) -> Object {
         return Object::error("Array::fill count must be non-negative");
     }
 
-    let result: Vec<Object> = std::iter::repeat(value).take(n_val as usize).collect();
-    Object::Array(result)
+    let result: Vec<Object> = std::iter::repeat_n(value, n_val as usize).collect();
+    Object::array(result)
 }
 
 /// Array::isEmpty(arr) – returns true if array has no elements.
@@ -962,7 +1186,7 @@ pub(crate) fn array_for_each(mut args: Vec<Object>, env: EnvRef) -> Object {
 
     match arr {
         Object::Array(elems) => {
-            for elem in elems {
+            for elem in Rc::unwrap_or_clone(elems) {
                 let result = apply_function_with_this(
                     func.clone(),
                     vec![elem],
@@ -999,3 +1223,426 @@ pub(crate) fn array_len(args: Vec<Object>, _env: EnvRef) -> Object {
         )),
     }
 }
+
+/// Turn a comparator's return value (negative / zero / positive integer)
+/// into an `Ordering`.
+fn comparator_ordering(result: Object, name: &str) -> Result<std::cmp::Ordering, Object> {
+    match result {
+        Object::Integer(n) => Ok(n.cmp(&0)),
+        err @ Object::Error(_) => Err(err),
+        other => Err(Object::error(format!(
+            "{name} comparator must return integer, got {:?}",
+            other
+        ))),
+    }
+}
+
+/// Binary search a sorted slice. `probe` orders an element relative to the
+/// target. Returns `Ok(index)` of a match or `Err(index)` where the target
+/// would be inserted; on a tie the insertion point is after equal elements.
+fn search_sorted(
+    elems: &[Object],
+    mut probe: impl FnMut(&Object) -> Result<std::cmp::Ordering, Object>,
+) -> Result<Result<usize, usize>, Object> {
+    use std::cmp::Ordering;
+
+    let (mut lo, mut hi) = (0, elems.len());
+    let mut found = None;
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        match probe(&elems[mid])? {
+            Ordering::Less => lo = mid + 1,
+            Ordering::Greater => hi = mid,
+            Ordering::Equal => {
+                found = Some(mid);
+                lo = mid + 1;
+            }
+        }
+    }
+    Ok(found.ok_or(lo))
+}
+
+fn search_result(result: Result<Result<usize, usize>, Object>) -> Object {
+    match result {
+        Ok(Ok(i)) => Object::OptionSome(Box::new(Object::Integer(i as i64))),
+        Ok(Err(_)) => Object::OptionNone,
+        Err(e) => e,
+    }
+}
+
+/// Array::binarySearch(arr, value) – finds value in an array sorted with
+/// `Array::sort`, returning Option::Some(index) or Option::None.
+pub(crate) fn array_binary_search(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (arr, value) = match expect_two_args(args, "Array::binarySearch") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    match arr {
+        Object::Array(elems) => {
+            search_result(search_sorted(&elems, |e| Ok(compare_objects(e, &value))))
+        }
+        other => Object::error(format!(
+            "Array::binarySearch expects an array as first argument, got {:?}",
+            other
+        )),
+    }
+}
+
+/// Array::binarySearchBy(arr, f) – searches a sorted array where f(element)
+/// returns a negative number if the element comes before the one wanted,
+/// 0 on a match and positive if it comes after. Returns Option of index.
+pub(crate) fn array_binary_search_by(args: Vec<Object>, env: EnvRef) -> Object {
+    let (arr, func) = match expect_two_args(args, "Array::binarySearchBy") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    match arr {
+        Object::Array(elems) => search_result(search_sorted(&elems, |e| {
+            let result = apply_function_with_this(func.clone(), vec![e.clone()], None, Rc::clone(&env));
+            comparator_ordering(result, "Array::binarySearchBy")
+        })),
+        other => Object::error(format!(
+            "Array::binarySearchBy expects an array as first argument, got {:?}",
+            other
+        )),
+    }
+}
+
+/// Array::insertSorted(arr, value[, f]) – returns a new array with value
+/// inserted after any equal elements, keeping a sorted array sorted. The
+/// optional comparator f(a, b) is the same as for `Array::sortBy`.
+pub(crate) fn array_insert_sorted(mut args: Vec<Object>, env: EnvRef) -> Object {
+    if args.len() != 2 && args.len() != 3 {
+        return Object::error("Array::insertSorted expects 2 or 3 arguments (array, value[, fn])");
+    }
+
+    let func = if args.len() == 3 { args.pop() } else { None };
+    let value = args.pop().unwrap();
+    let mut elems = match args.pop().unwrap() {
+        Object::Array(elems) => elems,
+        other => {
+            return Object::error(format!(
+                "Array::insertSorted expects an array as first argument, got {:?}",
+                other
+            ))
+        }
+    };
+
+    let position = search_sorted(&elems, |e| match &func {
+        Some(func) => {
+            let result = apply_function_with_this(
+                func.clone(),
+                vec![e.clone(), value.clone()],
+                None,
+                Rc::clone(&env),
+            );
+            comparator_ordering(result, "Array::insertSorted")
+        }
+        None => Ok(compare_objects(e, &value)),
+    });
+    let index = match position {
+        // search_sorted reports the last equal element, so insert after it.
+        Ok(Ok(i)) => i + 1,
+        Ok(Err(i)) => i,
+        Err(e) => return e,
+    };
+
+    unfrozen_array_mut(&mut elems).insert(index, value);
+    Object::Array(elems)
+}
+
+/// Shared body of `Array::minBy` / `Array::maxBy`: returns the first element
+/// whose key orders as `wanted` against every other key.
+fn extreme_by(args: Vec<Object>, env: EnvRef, name: &str, wanted: std::cmp::Ordering) -> Object {
+    let (arr, func) = match expect_two_args(args, name) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let elems = match arr {
+        Object::Array(elems) => elems,
+        other => {
+            return Object::error(format!(
+                "{name} expects an array as first argument, got {:?}",
+                other
+            ))
+        }
+    };
+
+    let mut best: Option<(Object, Object)> = None;
+    for elem in Rc::unwrap_or_clone(elems) {
+        let key = apply_function_with_this(func.clone(), vec![elem.clone()], None, Rc::clone(&env));
+        match (&key, best.as_ref().map(|(k, _)| k)) {
+            (Object::Error(_), _) => return key,
+            (Object::Integer(_) | Object::Float(_), None | Some(Object::Integer(_) | Object::Float(_)))
+            | (Object::String(_), None | Some(Object::String(_))) => {}
+            (Object::Integer(_) | Object::Float(_) | Object::String(_), Some(_)) => {
+                return Object::error(format!(
+                    "{name} keys must be all numbers or all strings"
+                ))
+            }
+            _ => {
+                return Object::error(format!(
+                    "{name} key function must return a number or string, got {:?}",
+                    key
+                ))
+            }
+        }
+
+        let replace = match &best {
+            Some((best_key, _)) => compare_objects(&key, best_key) == wanted,
+            None => true,
+        };
+        if replace {
+            best = Some((key, elem));
+        }
+    }
+
+    match best {
+        Some((_, elem)) => Object::OptionSome(Box::new(elem)),
+        None => Object::OptionNone,
+    }
+}
+
+/// Array::minBy(arr, f) – returns Option::Some of the first element with the
+/// smallest key f(element), or Option::None for an empty array.
+pub(crate) fn array_min_by(args: Vec<Object>, env: EnvRef) -> Object {
+    extreme_by(args, env, "Array::minBy", std::cmp::Ordering::Less)
+}
+
+/// Array::maxBy(arr, f) – returns Option::Some of the first element with the
+/// largest key f(element), or Option::None for an empty array.
+pub(crate) fn array_max_by(args: Vec<Object>, env: EnvRef) -> Object {
+    extreme_by(args, env, "Array::maxBy", std::cmp::Ordering::Greater)
+}
+
+/// Array::sumBy(arr, f) – sums the numbers returned by f(element). The total
+/// is an integer unless any key is a float; an empty array sums to 0.
+pub(crate) fn array_sum_by(args: Vec<Object>, env: EnvRef) -> Object {
+    let (arr, func) = match expect_two_args(args, "Array::sumBy") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let elems = match arr {
+        Object::Array(elems) => elems,
+        other => {
+            return Object::error(format!(
+                "Array::sumBy expects an array as first argument, got {:?}",
+                other
+            ))
+        }
+    };
+
+    let mut total = Object::Integer(0);
+    for elem in Rc::unwrap_or_clone(elems) {
+        let key = apply_function_with_this(func.clone(), vec![elem], None, Rc::clone(&env));
+        total = match (total, key) {
+            (_, err @ Object::Error(_)) => return err,
+            (Object::Integer(a), Object::Integer(b)) => match a.checked_add(b) {
+                Some(sum) => Object::Integer(sum),
+                None => return Object::error("integer overflow in Array::sumBy"),
+            },
+            (Object::Integer(a), Object::Float(b)) => Object::Float(a as f64 + b),
+            (Object::Float(a), Object::Integer(b)) => Object::Float(a + b as f64),
+            (Object::Float(a), Object::Float(b)) => Object::Float(a + b),
+            (_, other) => {
+                return Object::error(format!(
+                    "Array::sumBy key function must return a number, got {:?}",
+                    other
+                ))
+            }
+        };
+    }
+
+    total
+}
+
+/// Array::pushMut(ref, value) – appends value to the array held by ref in
+/// place, so every holder of the ref sees it. Returns the new length.
+pub(crate) fn array_push_mut(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (target, value) = match expect_two_args(args, "Array::pushMut") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let cell = match expect_ref(target, "Array::pushMut") {
+        Ok(cell) => cell,
+        Err(e) => return e,
+    };
+    if let Err(e) = check_no_cycle(&cell, &value, "Array::pushMut") {
+        return e;
+    }
+
+    let mut held = cell.borrow_mut();
+    match &mut *held {
+        Object::Array(elems) => {
+            if is_frozen_array(elems) {
+                return Object::error("Array::pushMut: cannot push onto a frozen array");
+            }
+            Rc::make_mut(elems).push(value);
+            Object::Integer(elems.len() as i64)
+        }
+        other => Object::error(format!(
+            "Array::pushMut expects a ref holding an array, got ref to {}",
+            other.type_name()
+        )),
+    }
+}
+
+fn expect_size(value: Object, name: &str) -> Result<usize, Object> {
+    match value {
+        Object::Integer(k) if k >= 0 => Ok(k as usize),
+        Object::Integer(k) => Err(Object::error(format!(
+            "{name} size must not be negative, got {k}"
+        ))),
+        other => Err(Object::error(format!(
+            "{name} expects an integer size, got {:?}",
+            other
+        ))),
+    }
+}
+
+/// Array::product(a, b, ...) – a lazy seq of the cartesian product: every
+/// array `[x, y, ...]` taking x from a, y from b and so on, in order.
+pub(crate) fn array_product(args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.is_empty() {
+        return Object::error("Array::product expects at least 1 array");
+    }
+
+    let mut arrays = Vec::with_capacity(args.len());
+    for arg in args {
+        match arg {
+            Object::Array(elems) => arrays.push(elems),
+            other => {
+                return Object::error(format!(
+                    "Array::product expects arrays, got {:?}",
+                    other
+                ))
+            }
+        }
+    }
+
+    Object::Seq(Rc::new(SeqNode::Product(arrays)))
+}
+
+/// Array::combinations(arr, k) – a lazy seq of every k-element selection of
+/// arr, keeping the original order within each selection.
+pub(crate) fn array_combinations(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (arr, k) = match expect_two_args(args, "Array::combinations") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let elems = match arr {
+        Object::Array(elems) => elems,
+        other => {
+            return Object::error(format!(
+                "Array::combinations expects an array as first argument, got {:?}",
+                other
+            ))
+        }
+    };
+    match expect_size(k, "Array::combinations") {
+        Ok(k) => Object::Seq(Rc::new(SeqNode::Combinations(elems, k))),
+        Err(e) => e,
+    }
+}
+
+/// Array::permutations(arr[, k]) – a lazy seq of every ordering of k
+/// elements of arr (all of them by default), in lexicographic order of
+/// position.
+pub(crate) fn array_permutations(mut args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.is_empty() || args.len() > 2 {
+        return Object::error("Array::permutations expects 1 or 2 arguments (array[, k])");
+    }
+
+    let k = if args.len() == 2 { Some(args.pop().unwrap()) } else { None };
+    let elems = match args.pop().unwrap() {
+        Object::Array(elems) => elems,
+        other => {
+            return Object::error(format!(
+                "Array::permutations expects an array as first argument, got {:?}",
+                other
+            ))
+        }
+    };
+    let k = match k.map(|k| expect_size(k, "Array::permutations")) {
+        Some(Ok(k)) => k,
+        Some(Err(e)) => return e,
+        None => elems.len(),
+    };
+    Object::Seq(Rc::new(SeqNode::Permutations(elems, k)))
+}
+
+/// The values of an `Array::product` seq.
+pub(crate) fn product(arrays: Vec<Rc<Vec<Object>>>) -> impl Iterator<Item = Object> {
+    // Odometer over one index per input array, last array fastest.
+    let mut next = arrays.iter().all(|elems| !elems.is_empty()).then(|| vec![0; arrays.len()]);
+    std::iter::from_fn(move || {
+        let mut indices = next.take()?;
+        let value = Object::array(
+            indices.iter().zip(&arrays).map(|(&i, elems)| elems[i].clone()).collect(),
+        );
+        for (pos, elems) in arrays.iter().enumerate().rev() {
+            indices[pos] += 1;
+            if indices[pos] < elems.len() {
+                next = Some(indices);
+                break;
+            }
+            indices[pos] = 0;
+        }
+        Some(value)
+    })
+}
+
+/// The values of an `Array::combinations` seq.
+pub(crate) fn combinations(elems: Rc<Vec<Object>>, k: usize) -> impl Iterator<Item = Object> {
+    let n = elems.len();
+    let mut next = (k <= n).then(|| (0..k).collect::<Vec<usize>>());
+    std::iter::from_fn(move || {
+        let mut indices = next.take()?;
+        let value = Object::array(indices.iter().map(|&i| elems[i].clone()).collect());
+
+        // Advance the rightmost index that still has room to move.
+        if let Some(pos) = (0..k).rev().find(|&pos| indices[pos] < n - k + pos) {
+            indices[pos] += 1;
+            for next in pos + 1..k {
+                indices[next] = indices[next - 1] + 1;
+            }
+            next = Some(indices);
+        }
+        Some(value)
+    })
+}
+
+/// The values of an `Array::permutations` seq.
+pub(crate) fn permutations(elems: Rc<Vec<Object>>, k: usize) -> impl Iterator<Item = Object> {
+    let n = elems.len();
+    let mut next = (k <= n).then(|| (0..k).collect::<Vec<usize>>());
+    std::iter::from_fn(move || {
+        let mut chosen = next.take()?;
+        let value = Object::array(chosen.iter().map(|&i| elems[i].clone()).collect());
+
+        // Move the rightmost position that can take a later unused index,
+        // then fill the positions after it with the smallest unused ones.
+        for pos in (0..k).rev() {
+            let mut used = vec![false; n];
+            for &i in &chosen[..pos] {
+                used[i] = true;
+            }
+            let Some(bigger) = (chosen[pos] + 1..n).find(|&i| !used[i]) else {
+                continue;
+            };
+            chosen[pos] = bigger;
+            used[bigger] = true;
+            let mut free = (0..n).filter(|&i| !used[i]);
+            for slot in &mut chosen[pos + 1..] {
+                *slot = free.next().expect("k <= n leaves enough unused indices");
+            }
+            next = Some(chosen);
+            break;
+        }
+        Some(value)
+    })
+}
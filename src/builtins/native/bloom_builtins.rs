@@ -0,0 +1,89 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::env::EnvRef;
+use crate::object::bloom::BloomFilter;
+use crate::object::types::BloomRef;
+use crate::object::Object;
+
+/// Split `(filter, item)` arguments into the filter handle and the key the
+/// item is hashed under. Only scalars are accepted so the key is stable; the
+/// type name keeps `1` and `"1"` apart.
+fn filter_and_key(args: Vec<Object>, name: &str) -> Result<(BloomRef, String), Object> {
+    if args.len() != 2 {
+        return Err(Object::error(format!(
+            "{} expects 2 arguments (filter, item)",
+            name
+        )));
+    }
+    let mut args = args.into_iter();
+    let filter = match args.next().unwrap() {
+        Object::Bloom(filter) => filter,
+        other => {
+            return Err(Object::error(format!(
+                "{} expects a bloom filter as first argument, got {}",
+                name,
+                other.type_name()
+            )))
+        }
+    };
+    let key = match args.next().unwrap() {
+        item @ (Object::String(_) | Object::Integer(_) | Object::Float(_) | Object::Boolean(_)) => {
+            format!("{}:{}", item.type_name(), item)
+        }
+        other => {
+            return Err(Object::error(format!(
+                "{} expects a string, number or boolean item, got {}",
+                name,
+                other.type_name()
+            )))
+        }
+    };
+    Ok((filter, key))
+}
+
+/// Bloom::new(expectedItems, falsePositiveRate) -> bloom
+pub(crate) fn bloom_new(args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.len() != 2 {
+        return Object::error("Bloom::new expects 2 arguments (expectedItems, falsePositiveRate)");
+    }
+    let expected = match &args[0] {
+        Object::Integer(n) if *n > 0 => *n as usize,
+        Object::Integer(_) => return Object::error("Bloom::new expectedItems must be positive"),
+        other => {
+            return Object::error(format!(
+                "Bloom::new expects integer expectedItems, got {}",
+                other.type_name()
+            ))
+        }
+    };
+    let rate = match &args[1] {
+        Object::Float(p) if *p > 0.0 && *p < 1.0 => *p,
+        Object::Float(_) => {
+            return Object::error("Bloom::new falsePositiveRate must be between 0 and 1 (exclusive)")
+        }
+        other => {
+            return Object::error(format!(
+                "Bloom::new expects float falsePositiveRate, got {}",
+                other.type_name()
+            ))
+        }
+    };
+    Object::Bloom(Rc::new(RefCell::new(BloomFilter::with_rate(expected, rate))))
+}
+
+/// Bloom::add(filter, item) -> bool (true if item was definitely new)
+pub(crate) fn bloom_add(args: Vec<Object>, _env: EnvRef) -> Object {
+    match filter_and_key(args, "Bloom::add") {
+        Ok((filter, key)) => Object::Boolean(filter.borrow_mut().insert(&key)),
+        Err(e) => e,
+    }
+}
+
+/// Bloom::mightContain(filter, item) -> bool
+pub(crate) fn bloom_might_contain(args: Vec<Object>, _env: EnvRef) -> Object {
+    match filter_and_key(args, "Bloom::mightContain") {
+        Ok((filter, key)) => Object::Boolean(filter.borrow().contains(&key)),
+        Err(e) => e,
+    }
+}
@@ -0,0 +1,121 @@
+use crate::env::EnvRef;
+use crate::object::types::TextEncoding;
+use crate::object::Object;
+
+/// The encoding named by an optional argument, UTF-8 when it's missing.
+fn encoding_arg(arg: Option<&Object>, name: &str) -> Result<TextEncoding, Object> {
+    match arg {
+        None => Ok(TextEncoding::Utf8),
+        Some(Object::String(enc)) => TextEncoding::from_name(enc)
+            .ok_or_else(|| Object::error(format!("{}: unknown encoding {:?}", name, enc))),
+        Some(other) => Err(Object::error(format!(
+            "{} expects encoding name as second argument, got {:?}",
+            name, other
+        ))),
+    }
+}
+
+/// Bytes::fromString(s, [encoding]) -> bytes (UTF-8 unless an encoding is named)
+pub(crate) fn bytes_from_string(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (text, encoding) = match args.as_slice() {
+        [Object::String(text)] | [Object::String(text), _] => (text, args.get(1)),
+        _ => return Object::error("Bytes::fromString expects 1 or 2 arguments (string, [encoding])"),
+    };
+    let encoding = match encoding_arg(encoding, "Bytes::fromString") {
+        Ok(encoding) => encoding,
+        Err(e) => return e,
+    };
+    match encoding.encode(text) {
+        Ok(bytes) => Object::bytes(bytes),
+        Err(e) => Object::error(format!("Bytes::fromString: {}", e)),
+    }
+}
+
+/// Bytes::toString(b, [encoding]) -> Result<string>
+/// Bytes that aren't valid in the encoding give an `Err`, never replaced characters.
+pub(crate) fn bytes_to_string(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (bytes, encoding) = match args.as_slice() {
+        [Object::Bytes(bytes)] | [Object::Bytes(bytes), _] => (bytes, args.get(1)),
+        _ => return Object::error("Bytes::toString expects 1 or 2 arguments (bytes, [encoding])"),
+    };
+    let encoding = match encoding_arg(encoding, "Bytes::toString") {
+        Ok(encoding) => encoding,
+        Err(e) => return e,
+    };
+    match encoding.decode(bytes.to_vec()) {
+        Ok(text) => Object::ResultOk(Box::new(Object::String(text))),
+        Err(e) => Object::ResultErr(Box::new(Object::String(e))),
+    }
+}
+
+/// Bytes::len(b) -> integer
+pub(crate) fn bytes_len(args: Vec<Object>, _env: EnvRef) -> Object {
+    match args.as_slice() {
+        [Object::Bytes(bytes)] => Object::Integer(bytes.len() as i64),
+        _ => Object::error("Bytes::len expects 1 argument (bytes)"),
+    }
+}
+
+/// Bytes::slice(b, start, [end]) -> bytes
+/// Negative indices count from the end; out-of-range indices are clamped.
+pub(crate) fn bytes_slice(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (bytes, start, end) = match args.as_slice() {
+        [Object::Bytes(bytes), Object::Integer(start)] => (bytes, *start, None),
+        [Object::Bytes(bytes), Object::Integer(start), Object::Integer(end)] => {
+            (bytes, *start, Some(*end))
+        }
+        _ => return Object::error("Bytes::slice expects 2 or 3 arguments (bytes, start, [end])"),
+    };
+    let len = bytes.len() as i64;
+    let clamp = |i: i64| if i < 0 { (len + i).max(0) } else { i.min(len) } as usize;
+    let (start, end) = (clamp(start), clamp(end.unwrap_or(len)));
+    if start >= end {
+        return Object::bytes(Vec::new());
+    }
+    Object::bytes(bytes[start..end].to_vec())
+}
+
+/// Bytes::concat(a, b, ...) -> bytes
+pub(crate) fn bytes_concat(args: Vec<Object>, _env: EnvRef) -> Object {
+    let mut out = Vec::new();
+    for arg in &args {
+        match arg {
+            Object::Bytes(bytes) => out.extend_from_slice(bytes),
+            other => {
+                return Object::error(format!("Bytes::concat expects bytes, got {:?}", other))
+            }
+        }
+    }
+    Object::bytes(out)
+}
+
+/// Bytes::fromArray(ints) -> bytes (each integer must be 0 to 255)
+pub(crate) fn bytes_from_array(args: Vec<Object>, _env: EnvRef) -> Object {
+    let items = match args.as_slice() {
+        [Object::Array(items)] => items,
+        _ => return Object::error("Bytes::fromArray expects 1 argument (array of integers)"),
+    };
+    let mut out = Vec::with_capacity(items.len());
+    for item in items.iter() {
+        match item {
+            Object::Integer(i) if (0..=255).contains(i) => out.push(*i as u8),
+            other => {
+                return Object::error(format!(
+                    "Bytes::fromArray expects integers from 0 to 255, got {}",
+                    other
+                ))
+            }
+        }
+    }
+    Object::bytes(out)
+}
+
+/// Bytes::toArray(b) -> array of integers
+pub(crate) fn bytes_to_array(args: Vec<Object>, _env: EnvRef) -> Object {
+    match args.as_slice() {
+        [Object::Bytes(bytes)] => {
+            Object::array(bytes.iter().map(|b| Object::Integer(*b as i64)).collect())
+        }
+        _ => Object::error("Bytes::toArray expects 1 argument (bytes)"),
+    }
+}
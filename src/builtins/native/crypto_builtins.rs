@@ -0,0 +1,117 @@
+use hmac::{Hmac, Mac};
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+use crate::env::EnvRef;
+use crate::object::Object;
+
+/// A string's UTF-8 bytes, or bytes as they are.
+fn as_data<'a>(arg: &'a Object, what: &str, name: &str) -> Result<&'a [u8], Object> {
+    match arg {
+        Object::String(s) => Ok(s.as_bytes()),
+        Object::Bytes(bytes) => Ok(bytes.as_slice()),
+        other => Err(Object::error(format!(
+            "{} expects {} to be a string or bytes, got {:?}",
+            name, what, other
+        ))),
+    }
+}
+
+/// Whether the optional options object asks for `{ bytes: true }`.
+fn wants_bytes(options: Option<&Object>, name: &str) -> Result<bool, Object> {
+    match options {
+        None => Ok(false),
+        Some(Object::Object(options)) => match options.get("bytes") {
+            None => Ok(false),
+            Some(Object::Boolean(b)) => Ok(*b),
+            Some(_) => Err(Object::error(format!("{} option bytes must be a boolean", name))),
+        },
+        Some(other) => Err(Object::error(format!(
+            "{} expects an options object, got {:?}",
+            name, other
+        ))),
+    }
+}
+
+/// A digest as lowercase hex, or as bytes with `{ bytes: true }`.
+fn output(digest: Vec<u8>, as_bytes: bool) -> Object {
+    if as_bytes {
+        Object::bytes(digest)
+    } else {
+        Object::String(digest.iter().map(|b| format!("{:02x}", b)).collect())
+    }
+}
+
+/// Shared shape of the plain hash functions: `(data, [options])`.
+fn hash(args: Vec<Object>, name: &str, digest: fn(&[u8]) -> Vec<u8>) -> Object {
+    let (data, options) = match args.as_slice() {
+        [data] => (data, None),
+        [data, options] => (data, Some(options)),
+        _ => return Object::error(format!("{} expects 1 or 2 arguments (data, [options])", name)),
+    };
+    match (as_data(data, "data", name), wants_bytes(options, name)) {
+        (Ok(data), Ok(as_bytes)) => output(digest(data), as_bytes),
+        (Err(e), _) | (_, Err(e)) => e,
+    }
+}
+
+/// Crypto::md5(data, [{ bytes }]) -> hex string or bytes
+/// Only for checksums and legacy APIs; MD5 is broken for anything security-related.
+pub(crate) fn crypto_md5(args: Vec<Object>, _env: EnvRef) -> Object {
+    hash(args, "Crypto::md5", |data| Md5::digest(data).to_vec())
+}
+
+/// Crypto::sha1(data, [{ bytes }]) -> hex string or bytes
+pub(crate) fn crypto_sha1(args: Vec<Object>, _env: EnvRef) -> Object {
+    hash(args, "Crypto::sha1", |data| Sha1::digest(data).to_vec())
+}
+
+/// Crypto::sha256(data, [{ bytes }]) -> hex string or bytes
+pub(crate) fn crypto_sha256(args: Vec<Object>, _env: EnvRef) -> Object {
+    hash(args, "Crypto::sha256", |data| Sha256::digest(data).to_vec())
+}
+
+/// Crypto::hmacSha256(key, data, [{ bytes }]) -> hex string or bytes
+/// Pass `{ bytes: true }` to feed the result into another HMAC as a key, as
+/// request-signing schemes often do.
+pub(crate) fn crypto_hmac_sha256(args: Vec<Object>, _env: EnvRef) -> Object {
+    let name = "Crypto::hmacSha256";
+    let (key, data, options) = match args.as_slice() {
+        [key, data] => (key, data, None),
+        [key, data, options] => (key, data, Some(options)),
+        _ => return Object::error(format!("{} expects 2 or 3 arguments (key, data, [options])", name)),
+    };
+    let (key, data, as_bytes) = match (
+        as_data(key, "key", name),
+        as_data(data, "data", name),
+        wants_bytes(options, name),
+    ) {
+        (Ok(key), Ok(data), Ok(as_bytes)) => (key, data, as_bytes),
+        (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => return e,
+    };
+    // HMAC takes keys of any length, so this can't fail.
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    output(mac.finalize().into_bytes().to_vec(), as_bytes)
+}
+
+/// Most bytes `Crypto::randomBytes` hands out in one call (1 MiB).
+const MAX_RANDOM_BYTES: i64 = 1 << 20;
+
+/// Crypto::randomBytes(n) -> bytes from the operating system's secure generator
+pub(crate) fn crypto_random_bytes(args: Vec<Object>, _env: EnvRef) -> Object {
+    let n = match args.as_slice() {
+        [Object::Integer(n)] if (0..=MAX_RANDOM_BYTES).contains(n) => *n as usize,
+        [Object::Integer(n)] if *n > MAX_RANDOM_BYTES => {
+            return Object::error(format!("Crypto::randomBytes count must be at most {}", MAX_RANDOM_BYTES))
+        }
+        [Object::Integer(_)] => return Object::error("Crypto::randomBytes count must be non-negative"),
+        _ => return Object::error("Crypto::randomBytes expects 1 argument (count)"),
+    };
+    let mut buf = vec![0u8; n];
+    match getrandom::getrandom(&mut buf) {
+        Ok(()) => Object::bytes(buf),
+        Err(e) => Object::error(format!("Crypto::randomBytes: {}", e)),
+    }
+}
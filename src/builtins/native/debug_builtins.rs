@@ -0,0 +1,62 @@
+use crate::env::EnvRef;
+use crate::object::Object;
+
+/// Containers whose one-line form is at most this long stay on one line.
+const INLINE_WIDTH: usize = 60;
+
+/// `value` laid out over several lines, nested values indented by two
+/// spaces per level. Object keys keep their insertion order, as in `print`.
+pub(crate) fn pretty(value: &Object) -> String {
+    let mut out = String::new();
+    write_pretty(value, 0, &mut out);
+    out
+}
+
+fn write_pretty(value: &Object, level: usize, out: &mut String) {
+    let inline = value.to_string();
+    let items: Vec<(Option<&String>, &Object)> = match value {
+        Object::Array(elements) => elements.iter().map(|v| (None, v)).collect(),
+        Object::Object(map) => map.iter().map(|(k, v)| (Some(k), v)).collect(),
+        _ => Vec::new(),
+    };
+    if items.is_empty() || inline.chars().count() + level * 2 <= INLINE_WIDTH {
+        out.push_str(&inline);
+        return;
+    }
+
+    let (open, close) = if matches!(value, Object::Array(_)) { ('[', ']') } else { ('{', '}') };
+    out.push(open);
+    out.push('\n');
+    for (key, item) in items {
+        out.push_str(&"  ".repeat(level + 1));
+        if let Some(key) = key {
+            out.push_str(key);
+            out.push_str(": ");
+        }
+        write_pretty(item, level + 1, out);
+        out.push_str(",\n");
+    }
+    out.push_str(&"  ".repeat(level));
+    out.push(close);
+}
+
+/// Debug::inspect(value) -> value
+/// Prints value pretty-printed to stdout and hands it back, so it can wrap
+/// an expression without changing what the expression does.
+pub(crate) fn debug_inspect(mut args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.len() != 1 {
+        return Object::error("Debug::inspect expects exactly 1 argument");
+    }
+    let value = args.pop().unwrap();
+    super::write_stdout(&format!("{}\n", pretty(&value)));
+    value
+}
+
+/// Debug::pretty(value) -> string
+/// The text `Debug::inspect` would print.
+pub(crate) fn debug_pretty(args: Vec<Object>, _env: EnvRef) -> Object {
+    match args.as_slice() {
+        [value] => Object::String(pretty(value)),
+        _ => Object::error("Debug::pretty expects exactly 1 argument"),
+    }
+}
@@ -0,0 +1,130 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::capability;
+use crate::env::EnvRef;
+use crate::object::Object;
+
+fn ok(value: Object) -> Object {
+    Object::ResultOk(Box::new(value))
+}
+
+fn err(msg: String) -> Object {
+    Object::ResultErr(Box::new(Object::String(msg)))
+}
+
+fn path_string(path: &Path) -> Object {
+    Object::String(path.to_string_lossy().into_owned())
+}
+
+/// Dir::list(path) -> Result<array of entry names, sorted>
+pub(crate) fn dir_list(args: Vec<Object>, _env: EnvRef) -> Object {
+    let path = match args.as_slice() {
+        [Object::String(path)] => path,
+        _ => return Object::error("Dir::list expects 1 argument (path)"),
+    };
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(e) => return err(format!("failed to list {}: {}", path, e)),
+    };
+    let mut names = Vec::new();
+    for entry in entries {
+        match entry {
+            Ok(entry) => names.push(entry.file_name().to_string_lossy().into_owned()),
+            Err(e) => return err(format!("failed to list {}: {}", path, e)),
+        }
+    }
+    names.sort();
+    ok(Object::array(names.into_iter().map(Object::String).collect()))
+}
+
+/// Dir::exists(path) -> Result<bool> (true only for directories)
+pub(crate) fn dir_exists(args: Vec<Object>, _env: EnvRef) -> Object {
+    match args.as_slice() {
+        [Object::String(path)] => match fs::metadata(path) {
+            Ok(metadata) => ok(Object::Boolean(metadata.is_dir())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => ok(Object::Boolean(false)),
+            Err(e) => err(format!("failed to check {}: {}", path, e)),
+        },
+        _ => Object::error("Dir::exists expects 1 argument (path)"),
+    }
+}
+
+/// Dir::walk(path) -> Result<array of paths>
+/// Every file and directory below `path`, depth-first in name order, each
+/// joined onto `path`. Symlinks are listed but not followed.
+pub(crate) fn dir_walk(args: Vec<Object>, _env: EnvRef) -> Object {
+    let root = match args.as_slice() {
+        [Object::String(path)] => PathBuf::from(path),
+        _ => return Object::error("Dir::walk expects 1 argument (path)"),
+    };
+    let mut found = Vec::new();
+    match walk(&root, &mut found) {
+        Ok(()) => ok(Object::array(found.iter().map(|p| path_string(p)).collect())),
+        Err(e) => err(format!("failed to walk {}: {}", root.display(), e)),
+    }
+}
+
+fn walk(dir: &Path, found: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    let mut entries = fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = entry.path();
+        found.push(path.clone());
+        if entry.file_type()?.is_dir() {
+            walk(&path, found)?;
+        }
+    }
+    Ok(())
+}
+
+/// Dir::create(path) -> Result<null>
+/// Creates missing parent directories too; an existing directory is fine.
+pub(crate) fn dir_create(args: Vec<Object>, env: EnvRef) -> Object {
+    capability::audited("Dir::create", args, env, create_dir)
+}
+
+fn create_dir(args: Vec<Object>, _env: EnvRef) -> Object {
+    let path = match args.as_slice() {
+        [Object::String(path)] => path,
+        _ => return Object::error("Dir::create expects 1 argument (path)"),
+    };
+    if capability::is_dry_run() {
+        capability::log_dry_run("Dir::create", &format!("would create {}", path));
+        return ok(Object::Null);
+    }
+    match fs::create_dir_all(path) {
+        Ok(()) => ok(Object::Null),
+        Err(e) => err(format!("failed to create {}: {}", path, e)),
+    }
+}
+
+/// Dir::remove(path, options?) -> Result<null>
+/// Only removes empty directories unless `{ recursive: true }` is passed.
+pub(crate) fn dir_remove(args: Vec<Object>, env: EnvRef) -> Object {
+    capability::audited("Dir::remove", args, env, remove_dir)
+}
+
+fn remove_dir(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (path, recursive) = match args.as_slice() {
+        [Object::String(path)] => (path, false),
+        [Object::String(path), Object::Object(options)] => {
+            match options.get("recursive") {
+                None => (path, false),
+                Some(Object::Boolean(recursive)) => (path, *recursive),
+                Some(_) => return Object::error("Dir::remove option recursive must be a boolean"),
+            }
+        }
+        _ => return Object::error("Dir::remove expects 1 or 2 arguments (path, options?)"),
+    };
+    if capability::is_dry_run() {
+        let what = if recursive { "and everything in it" } else { "if empty" };
+        capability::log_dry_run("Dir::remove", &format!("would remove {} {}", path, what));
+        return ok(Object::Null);
+    }
+    let removed = if recursive { fs::remove_dir_all(path) } else { fs::remove_dir(path) };
+    match removed {
+        Ok(()) => ok(Object::Null),
+        Err(e) => err(format!("failed to remove {}: {}", path, e)),
+    }
+}
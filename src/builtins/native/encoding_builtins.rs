@@ -0,0 +1,172 @@
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE, URL_SAFE_NO_PAD};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC};
+
+use crate::env::EnvRef;
+use crate::object::Object;
+
+/// Everything except the RFC 3986 unreserved characters gets escaped.
+const URL_ESCAPED: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.').remove(b'~');
+
+/// The data to encode: a string's UTF-8 bytes, or bytes as they are.
+fn input_bytes<'a>(args: &'a [Object], name: &str) -> Result<&'a [u8], Object> {
+    match args.first() {
+        Some(Object::String(s)) => Ok(s.as_bytes()),
+        Some(Object::Bytes(bytes)) => Ok(bytes.as_slice()),
+        Some(other) => Err(Object::error(format!(
+            "{} expects a string or bytes, got {:?}",
+            name, other
+        ))),
+        None => Err(Object::error(format!("{} expects 1 or 2 arguments (data, [options])", name))),
+    }
+}
+
+/// Read a boolean flag from the optional options object in `args[1]`.
+fn flag(args: &[Object], key: &str, name: &str) -> Result<bool, Object> {
+    match args {
+        [_] => Ok(false),
+        [_, Object::Object(options)] => match options.get(key) {
+            None => Ok(false),
+            Some(Object::Boolean(b)) => Ok(*b),
+            Some(_) => Err(Object::error(format!("{} option {} must be a boolean", name, key))),
+        },
+        _ => Err(Object::error(format!("{} expects 1 or 2 arguments (data, [options])", name))),
+    }
+}
+
+/// Hand decoded data back as `Ok(string)`, or `Ok(bytes)` with `{ bytes: true }`.
+fn decoded(data: Result<Vec<u8>, String>, args: &[Object], name: &str) -> Object {
+    let as_bytes = match flag(args, "bytes", name) {
+        Ok(b) => b,
+        Err(e) => return e,
+    };
+    let value = data.and_then(|data| {
+        if as_bytes {
+            Ok(Object::bytes(data))
+        } else {
+            String::from_utf8(data).map(Object::String).map_err(|_| {
+                "decoded data is not valid UTF-8; pass { bytes: true } to get bytes".to_string()
+            })
+        }
+    });
+    match value {
+        Ok(value) => Object::ResultOk(Box::new(value)),
+        Err(e) => Object::ResultErr(Box::new(Object::String(e))),
+    }
+}
+
+/// The text to decode, which must be a string.
+fn input_text<'a>(args: &'a [Object], name: &str) -> Result<&'a str, Object> {
+    match args.first() {
+        Some(Object::String(s)) => Ok(s),
+        Some(other) => Err(Object::error(format!("{} expects a string, got {:?}", name, other))),
+        None => Err(Object::error(format!("{} expects 1 or 2 arguments (text, [options])", name))),
+    }
+}
+
+/// Encoding::base64Encode(data, [{ urlSafe }]) -> string
+/// Standard alphabet with padding, or the URL-safe alphabet without padding.
+pub(crate) fn encoding_base64_encode(args: Vec<Object>, _env: EnvRef) -> Object {
+    let name = "Encoding::base64Encode";
+    let (data, url_safe) = match (input_bytes(&args, name), flag(&args, "urlSafe", name)) {
+        (Ok(data), Ok(url_safe)) => (data, url_safe),
+        (Err(e), _) | (_, Err(e)) => return e,
+    };
+    let engine = if url_safe { URL_SAFE_NO_PAD } else { STANDARD };
+    Object::String(engine.encode(data))
+}
+
+/// Encoding::base64Decode(text, [{ urlSafe, bytes }]) -> Result<string | bytes>
+/// The URL-safe alphabet accepts input with or without padding.
+pub(crate) fn encoding_base64_decode(args: Vec<Object>, _env: EnvRef) -> Object {
+    let name = "Encoding::base64Decode";
+    let (text, url_safe) = match (input_text(&args, name), flag(&args, "urlSafe", name)) {
+        (Ok(text), Ok(url_safe)) => (text, url_safe),
+        (Err(e), _) | (_, Err(e)) => return e,
+    };
+    let data = if url_safe {
+        let engine = if text.ends_with('=') { URL_SAFE } else { URL_SAFE_NO_PAD };
+        engine.decode(text)
+    } else {
+        STANDARD.decode(text)
+    };
+    decoded(data.map_err(|e| format!("invalid base64: {}", e)), &args, name)
+}
+
+/// Encoding::hexEncode(data) -> lowercase hex string
+pub(crate) fn encoding_hex_encode(args: Vec<Object>, _env: EnvRef) -> Object {
+    let name = "Encoding::hexEncode";
+    if args.len() != 1 {
+        return Object::error(format!("{} expects 1 argument (data)", name));
+    }
+    match input_bytes(&args, name) {
+        Ok(data) => Object::String(data.iter().map(|b| format!("{:02x}", b)).collect()),
+        Err(e) => e,
+    }
+}
+
+/// Encoding::hexDecode(text, [{ bytes }]) -> Result<string | bytes>
+/// Accepts upper- and lowercase digits.
+pub(crate) fn encoding_hex_decode(args: Vec<Object>, _env: EnvRef) -> Object {
+    let name = "Encoding::hexDecode";
+    let text = match input_text(&args, name) {
+        Ok(text) => text,
+        Err(e) => return e,
+    };
+    decoded(decode_hex(text), &args, name)
+}
+
+fn decode_hex(text: &str) -> Result<Vec<u8>, String> {
+    if !text.len().is_multiple_of(2) {
+        return Err("invalid hex: odd number of digits".to_string());
+    }
+    text.as_bytes()
+        .chunks(2)
+        .enumerate()
+        .map(|(i, pair)| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| format!("invalid hex digit near offset {}", i * 2))
+        })
+        .collect()
+}
+
+/// Encoding::urlEncode(data) -> string
+/// Percent-encodes everything except letters, digits and `-_.~`, so the
+/// result is safe in paths and query strings alike.
+pub(crate) fn encoding_url_encode(args: Vec<Object>, _env: EnvRef) -> Object {
+    let name = "Encoding::urlEncode";
+    if args.len() != 1 {
+        return Object::error(format!("{} expects 1 argument (data)", name));
+    }
+    match input_bytes(&args, name) {
+        Ok(data) => Object::String(percent_encoding::percent_encode(data, URL_ESCAPED).to_string()),
+        Err(e) => e,
+    }
+}
+
+/// Encoding::urlDecode(text, [{ bytes }]) -> Result<string | bytes>
+/// Decodes `%XX` escapes; `+` is left alone rather than read as a space.
+pub(crate) fn encoding_url_decode(args: Vec<Object>, _env: EnvRef) -> Object {
+    let name = "Encoding::urlDecode";
+    let text = match input_text(&args, name) {
+        Ok(text) => text,
+        Err(e) => return e,
+    };
+    decoded(decode_percent(text), &args, name)
+}
+
+fn decode_percent(text: &str) -> Result<Vec<u8>, String> {
+    // percent_encoding passes malformed escapes through untouched; reject them.
+    let bytes = text.as_bytes();
+    for (i, b) in bytes.iter().enumerate() {
+        if *b == b'%' {
+            let escape = bytes.get(i + 1..i + 3);
+            if !escape.is_some_and(|hex| hex.iter().all(u8::is_ascii_hexdigit)) {
+                return Err(format!("invalid percent escape at offset {}", i));
+            }
+        }
+    }
+    Ok(percent_encoding::percent_decode(bytes).collect())
+}
@@ -1,15 +1,21 @@
 use std::cell::RefCell;
 use std::fs::OpenOptions;
 use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
 use std::rc::Rc;
+use crate::capability;
 use crate::env::EnvRef;
 use crate::object::Object;
-use crate::object::types::{FileHandle, FileRef};
+use crate::object::types::{FileHandle, FileRef, TextEncoding};
 
 // Builtin functions
 
-pub fn builtin_open(args: Vec<Object>, _env: EnvRef) -> Object {
-    if args.len() != 2 {
+pub fn builtin_open(args: Vec<Object>, env: EnvRef) -> Object {
+    capability::audited("File::open", args, env, open_file)
+}
+
+fn open_file(args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.len() != 2 && args.len() != 3 {
         return Object::Error("wrong number of arguments".into());
     }
 
@@ -23,6 +29,15 @@ pub fn builtin_open(args: Vec<Object>, _env: EnvRef) -> Object {
         _ => return Object::Error("expected string argument".into()),
     };
 
+    // A `b` anywhere in the mode ("rb", "w+b") selects binary reads.
+    let binary = mode.contains('b');
+    let mode = mode.replacen('b', "", 1);
+
+    let encoding = match encoding_option(args.get(2), binary) {
+        Ok(encoding) => encoding,
+        Err(e) => return e,
+    };
+
     let mut opts = OpenOptions::new();
     match mode.as_ref() {
         "r" => { opts.read(true); },
@@ -34,14 +49,37 @@ pub fn builtin_open(args: Vec<Object>, _env: EnvRef) -> Object {
         _ => return Object::Error("invalid mode".into()),
     }
 
+    if capability::is_dry_run() && mode != "r" {
+        // Don't create or truncate anything; expose the existing contents
+        // (if any) read-only so reads behave sensibly.
+        capability::log_dry_run(
+            "File::open",
+            &format!("would open {} with mode \"{}\"", path, mode),
+        );
+        let existing = if Path::new(&path).exists() {
+            OpenOptions::new().read(true).open(&path).ok()
+        } else {
+            None
+        };
+        let mut handle = FileHandle::new_dry_run(existing);
+        handle.binary = binary;
+        handle.encoding = encoding;
+        return Object::File(Rc::new(RefCell::new(handle)));
+    }
+
     match opts.open(path) {
-        Ok(file) => Object::File(Rc::new(RefCell::new(FileHandle::new(file)))),
+        Ok(file) => {
+            let mut handle = FileHandle::new(file);
+            handle.binary = binary;
+            handle.encoding = encoding;
+            Object::File(Rc::new(RefCell::new(handle)))
+        }
         Err(e) => Object::Error(format!("failed to open file: {}", e))
     }
 }
 
 pub fn builtin_read(args: Vec<Object>, _env: EnvRef) -> Object {
-    if args.len() < 1 || args.len() > 2 {
+    if args.is_empty() || args.len() > 2 {
         return Object::Error("wrong number of arguments".into());
     }
 
@@ -51,10 +89,24 @@ pub fn builtin_read(args: Vec<Object>, _env: EnvRef) -> Object {
     };
 
     let mut guard = file_reference.borrow_mut();
+    let dry_run = guard.dry_run;
+    let binary = guard.binary;
+    let encoding = guard.encoding;
     let file = match guard.inner.as_mut() {
         Some(f) => f,
+        None if dry_run && binary => return Object::bytes(Vec::new()),
+        None if dry_run => return Object::String(String::new()),
         None => return Object::Error("file is already closed".into()),
     };
+    let decode = |buf: Vec<u8>| {
+        if binary {
+            return Object::bytes(buf);
+        }
+        match encoding.decode(buf) {
+            Ok(s) => Object::String(s),
+            Err(e) => Object::Error(e),
+        }
+    };
 
     let mut buf = Vec::new();
     if args.len() == 2 {
@@ -71,33 +123,43 @@ pub fn builtin_read(args: Vec<Object>, _env: EnvRef) -> Object {
         match file.read(&mut chunk) {
             Ok(read) => {
                 chunk.truncate(read);
-                match String::from_utf8(chunk) {
-                    Ok(s) => Object::String(s),
-                    Err(e) => Object::Error(format!("failed to decode UTF-8: {}", e)),
-                }
+                decode(chunk)
             },
             Err(e) => Object::Error(format!("failed to read from file: {}", e)),
         }
     } else {
         match file.read_to_end(&mut buf) {
-            Ok(_) => match String::from_utf8(buf) {
-                Ok(s) => Object::String(s),
-                Err(e) => Object::Error(format!("failed to decode UTF-8: {}", e)),
-            },
+            Ok(_) => decode(buf),
             Err(e) => Object::Error(format!("failed to read from file: {}", e)),
         }
     }
 }
 
-pub fn builtin_write(args: Vec<Object>, _env: EnvRef) -> Object {
+pub fn builtin_write(args: Vec<Object>, env: EnvRef) -> Object {
+    capability::audited("File::write", args, env, write_file)
+}
+
+fn write_file(args: Vec<Object>, _env: EnvRef) -> Object {
     if args.len() != 2 { return Object::Error("write(file, data) expects 2 args".into()) }
     let file_reference = match expect_file(&args[0]) { Ok(f) => f, Err(e) => return e };
-    let data = match &args[1] { Object::String(s) => s.clone(), _ => return Object::Error("write: data must be string".into()) };
-
     let mut guard = file_reference.borrow_mut();
+    let data = match &args[1] {
+        Object::String(s) if guard.binary => s.as_bytes().to_vec(),
+        Object::String(s) => match guard.encoding.encode(s) {
+            Ok(data) => data,
+            Err(e) => return Object::Error(format!("write: {}", e)),
+        },
+        Object::Bytes(b) => b.to_vec(),
+        _ => return Object::Error("write: data must be string or bytes".into()),
+    };
+
+    if guard.dry_run {
+        capability::log_dry_run("File::write", &format!("would write {} bytes", data.len()));
+        return Object::Integer(data.len() as i64);
+    }
     let file = match guard.inner.as_mut() { Some(f) => f, None => return Object::Error("write: file is closed".into()) };
 
-    match file.write(data.as_bytes()) {
+    match file.write(&data) {
         Ok(w) => Object::Integer(w as i64),
         Err(e) => Object::Error(format!("write: {}", e)),
     }
@@ -110,7 +172,12 @@ pub fn builtin_seek(args: Vec<Object>, _env: EnvRef) -> Object {
     let whence = match &args[2] { Object::String(s) => s.as_str(), _ => return Object::Error("seek: whence must be string".into()) };
 
     let mut guard = file_reference.borrow_mut();
-    let file = match guard.inner.as_mut() { Some(f) => f, None => return Object::Error("seek: file is closed".into()) };
+    let dry_run = guard.dry_run;
+    let file = match guard.inner.as_mut() {
+        Some(f) => f,
+        None if dry_run => return Object::Integer(0),
+        None => return Object::Error("seek: file is closed".into()),
+    };
 
     let seek_from = match whence {
         "start" => SeekFrom::Start(offset as u64),
@@ -134,11 +201,32 @@ pub fn builtin_close(args: Vec<Object>, _env: EnvRef) -> Object {
 
     let mut guard = file_reference.borrow_mut();
     guard.inner = None; // drop the File => closes it
+    guard.dry_run = false;
     Object::Null
 }
 
 // Helpers
 
+/// The encoding from an optional `{ encoding: "latin-1" }` argument; text
+/// mode only.
+fn encoding_option(options: Option<&Object>, binary: bool) -> Result<TextEncoding, Object> {
+    match options {
+        None => Ok(TextEncoding::Utf8),
+        Some(Object::Object(options)) => match options.get("encoding") {
+            None => Ok(TextEncoding::Utf8),
+            Some(Object::String(name)) => match TextEncoding::from_name(name) {
+                Some(_) if binary => {
+                    Err(Object::Error("encoding does not apply to binary mode".into()))
+                }
+                Some(encoding) => Ok(encoding),
+                None => Err(Object::Error(format!("unknown encoding: {}", name))),
+            },
+            Some(_) => Err(Object::Error("encoding must be a string".into())),
+        },
+        Some(_) => Err(Object::Error("options must be an object".into())),
+    }
+}
+
 fn expect_file(obj: &Object) -> Result<FileRef, Object> {
     if let Object::File(file_reference) = obj {
         if file_reference.borrow().is_closed() {
@@ -156,49 +244,338 @@ fn expect_file(obj: &Object) -> Result<FileRef, Object> {
 // These convert the raw file_* results into `Result::Ok(...)` / `Result::Err("...")`
 // values for easier composition in Slang:
 //
-//   File::open(path, mode)
+//   File::open(path, mode, options?)
 //   File::read(file)
 //   File::write(file, data)
 //   File::seek(file, offset, whence)
 //   File::close(file)
 //
 
-pub(crate) fn file_open_result(args: Vec<Object>, env: EnvRef) -> Object {
-    let res = builtin_open(args, env);
+fn into_result(res: Object) -> Object {
     match res {
         Object::Error(msg) => Object::ResultErr(Box::new(Object::String(msg))),
         other => Object::ResultOk(Box::new(other)),
     }
 }
 
+pub(crate) fn file_open_result(args: Vec<Object>, env: EnvRef) -> Object {
+    into_result(builtin_open(args, env))
+}
+
 pub(crate) fn file_read_result(args: Vec<Object>, env: EnvRef) -> Object {
-    let res = builtin_read(args, env);
-    match res {
-        Object::Error(msg) => Object::ResultErr(Box::new(Object::String(msg))),
-        other => Object::ResultOk(Box::new(other)),
-    }
+    into_result(builtin_read(args, env))
 }
 
 pub(crate) fn file_write_result(args: Vec<Object>, env: EnvRef) -> Object {
-    let res = builtin_write(args, env);
-    match res {
-        Object::Error(msg) => Object::ResultErr(Box::new(Object::String(msg))),
-        other => Object::ResultOk(Box::new(other)),
-    }
+    into_result(builtin_write(args, env))
 }
 
 pub(crate) fn file_seek_result(args: Vec<Object>, env: EnvRef) -> Object {
-    let res = builtin_seek(args, env);
-    match res {
-        Object::Error(msg) => Object::ResultErr(Box::new(Object::String(msg))),
-        other => Object::ResultOk(Box::new(other)),
-    }
+    into_result(builtin_seek(args, env))
 }
 
 pub(crate) fn file_close_result(args: Vec<Object>, env: EnvRef) -> Object {
-    let res = builtin_close(args, env);
-    match res {
-        Object::Error(msg) => Object::ResultErr(Box::new(Object::String(msg))),
-        other => Object::ResultOk(Box::new(other)),
+    into_result(builtin_close(args, env))
+}
+// ----- Streaming and path-based helpers -----
+//
+// Also Result-based. Handle functions read from the current position:
+//
+//   File::readLine(file), File::readLines(file | path), File::readBytes(file, n)
+//
+// and the rest take paths:
+//
+//   File::exists(path), File::size(path | file), File::readToString(path, options?),
+//   File::writeString(path, s, options?), File::append(path, data),
+//   File::delete(path), File::copy(from, to)
+//
+
+/// File::readLine(file) -> Result<Option<string>> (None at end of file)
+pub(crate) fn file_read_line(args: Vec<Object>, _env: EnvRef) -> Object {
+    into_result(read_line(args))
+}
+
+fn read_line(args: Vec<Object>) -> Object {
+    if args.len() != 1 {
+        return Object::Error("File::readLine expects 1 argument (file)".into());
+    }
+    let file_reference = match expect_file(&args[0]) {
+        Ok(f) => f,
+        Err(e) => return e,
+    };
+
+    let mut guard = file_reference.borrow_mut();
+    if guard.binary {
+        return Object::Error("File::readLine needs a file opened in text mode".into());
+    }
+    let encoding = guard.encoding;
+    let file = match guard.inner.as_mut() {
+        Some(f) => f,
+        None => return Object::OptionNone, // dry-run handle for a file that doesn't exist
+    };
+
+    // Newlines are one byte, or one aligned code unit in UTF-16.
+    let newline: &[u8] = match encoding {
+        TextEncoding::Utf16 | TextEncoding::Utf16Le => &[b'\n', 0],
+        TextEncoding::Utf16Be => &[0, b'\n'],
+        TextEncoding::Utf8 | TextEncoding::Latin1 => b"\n",
+    };
+
+    let mut line = Vec::new();
+    let mut searched = 0;
+    let mut chunk = [0u8; 4096];
+    loop {
+        let read = match file.read(&mut chunk) {
+            Ok(read) => read,
+            Err(e) => return Object::Error(format!("failed to read from file: {}", e)),
+        };
+        if read == 0 {
+            if line.is_empty() {
+                return Object::OptionNone;
+            }
+            break;
+        }
+        line.extend_from_slice(&chunk[..read]);
+
+        while searched + newline.len() <= line.len() {
+            if &line[searched..searched + newline.len()] == newline {
+                // Leave the file positioned just after the newline.
+                let excess = line.len() - (searched + newline.len());
+                if let Err(e) = file.seek(SeekFrom::Current(-(excess as i64))) {
+                    return Object::Error(format!("failed to seek in file: {}", e));
+                }
+                line.truncate(searched);
+                return decode_line(line, encoding);
+            }
+            searched += newline.len();
+        }
+    }
+    decode_line(line, encoding)
+}
+
+fn decode_line(line: Vec<u8>, encoding: TextEncoding) -> Object {
+    match encoding.decode(line) {
+        Ok(mut text) => {
+            if text.ends_with('\r') {
+                text.pop();
+            }
+            Object::OptionSome(Box::new(Object::String(text)))
+        }
+        Err(e) => Object::Error(e),
+    }
+}
+
+/// File::readLines(file | path) -> Result<array of strings>
+/// A handle is read from its current position to the end.
+pub(crate) fn file_read_lines(args: Vec<Object>, env: EnvRef) -> Object {
+    if args.len() != 1 {
+        return into_result(Object::Error("File::readLines expects 1 argument (file or path)".into()));
+    }
+    let text = match &args[0] {
+        Object::String(path) => match std::fs::read_to_string(path) {
+            Ok(text) => Object::String(text),
+            Err(e) => Object::Error(format!("failed to read {}: {}", path, e)),
+        },
+        _ => builtin_read(args, env),
+    };
+    into_result(match text {
+        Object::String(text) => {
+            Object::array(text.lines().map(|line| Object::String(line.to_string())).collect())
+        }
+        Object::Bytes(_) => Object::Error("File::readLines needs a file opened in text mode".into()),
+        other => other,
+    })
+}
+
+/// File::readBytes(file, n) -> Result<bytes> (at most n bytes, in any mode)
+pub(crate) fn file_read_bytes(args: Vec<Object>, _env: EnvRef) -> Object {
+    into_result(read_bytes(args))
+}
+
+fn read_bytes(args: Vec<Object>) -> Object {
+    if args.len() != 2 {
+        return Object::Error("File::readBytes expects 2 arguments (file, n)".into());
+    }
+    let file_reference = match expect_file(&args[0]) {
+        Ok(f) => f,
+        Err(e) => return e,
+    };
+    let n = match &args[1] {
+        Object::Integer(n) if *n >= 0 => *n as usize,
+        _ => return Object::Error("File::readBytes expects a non-negative integer count".into()),
+    };
+
+    let mut guard = file_reference.borrow_mut();
+    let Some(file) = guard.inner.as_mut() else {
+        return Object::bytes(Vec::new());
+    };
+    let mut buf = Vec::with_capacity(n.min(1 << 16));
+    match file.take(n as u64).read_to_end(&mut buf) {
+        Ok(_) => Object::bytes(buf),
+        Err(e) => Object::Error(format!("failed to read from file: {}", e)),
+    }
+}
+
+/// File::exists(path) -> Result<bool>
+pub(crate) fn file_exists(args: Vec<Object>, _env: EnvRef) -> Object {
+    into_result(match args.as_slice() {
+        [Object::String(path)] => match Path::new(path).try_exists() {
+            Ok(exists) => Object::Boolean(exists),
+            Err(e) => Object::Error(format!("failed to check {}: {}", path, e)),
+        },
+        _ => Object::Error("File::exists expects 1 argument (path)".into()),
+    })
+}
+
+/// File::size(path | file) -> Result<integer> (size in bytes)
+pub(crate) fn file_size(args: Vec<Object>, _env: EnvRef) -> Object {
+    let metadata = match args.as_slice() {
+        [Object::String(path)] => std::fs::metadata(path),
+        [file @ Object::File(_)] => match expect_file(file) {
+            Ok(file_reference) => match file_reference.borrow().inner.as_ref() {
+                Some(f) => f.metadata(),
+                None => return into_result(Object::Integer(0)),
+            },
+            Err(e) => return into_result(e),
+        },
+        _ => return into_result(Object::Error("File::size expects 1 argument (path or file)".into())),
+    };
+    into_result(match metadata {
+        Ok(metadata) => Object::Integer(metadata.len() as i64),
+        Err(e) => Object::Error(format!("failed to stat file: {}", e)),
+    })
+}
+
+/// File::readToString(path, options?) -> Result<string>
+pub(crate) fn file_read_to_string(args: Vec<Object>, _env: EnvRef) -> Object {
+    into_result(read_to_string(args))
+}
+
+fn read_to_string(args: Vec<Object>) -> Object {
+    let path = match args.first() {
+        Some(Object::String(path)) if args.len() <= 2 => path,
+        _ => {
+            return Object::Error(
+                "File::readToString expects 1 or 2 arguments (path, options?)".into(),
+            )
+        }
+    };
+    let encoding = match encoding_option(args.get(1), false) {
+        Ok(encoding) => encoding,
+        Err(e) => return e,
+    };
+    match std::fs::read(path) {
+        Ok(data) => match encoding.decode(data) {
+            Ok(text) => Object::String(text),
+            Err(e) => Object::Error(e),
+        },
+        Err(e) => Object::Error(format!("failed to read {}: {}", path, e)),
     }
-}
\ No newline at end of file
+}
+
+/// File::writeString(path, s, options?) -> Result<integer> (bytes written)
+/// Creates the file or replaces its contents.
+pub(crate) fn file_write_string(args: Vec<Object>, env: EnvRef) -> Object {
+    into_result(capability::audited("File::writeString", args, env, write_string))
+}
+
+fn write_string(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (path, text) = match args.as_slice() {
+        [Object::String(path), Object::String(text)]
+        | [Object::String(path), Object::String(text), _] => (path, text),
+        _ => {
+            return Object::Error(
+                "File::writeString expects 2 or 3 arguments (path, string, options?)".into(),
+            )
+        }
+    };
+    let data = match encoding_option(args.get(2), false).map(|encoding| encoding.encode(text)) {
+        Ok(Ok(data)) => data,
+        Ok(Err(e)) => return Object::Error(e),
+        Err(e) => return e,
+    };
+    if capability::is_dry_run() {
+        capability::log_dry_run(
+            "File::writeString",
+            &format!("would write {} bytes to {}", data.len(), path),
+        );
+        return Object::Integer(data.len() as i64);
+    }
+    match std::fs::write(path, &data) {
+        Ok(()) => Object::Integer(data.len() as i64),
+        Err(e) => Object::Error(format!("failed to write {}: {}", path, e)),
+    }
+}
+
+/// File::append(path, data) -> Result<integer> (bytes written)
+/// Appends a UTF-8 string or bytes, creating the file if needed.
+pub(crate) fn file_append(args: Vec<Object>, env: EnvRef) -> Object {
+    into_result(capability::audited("File::append", args, env, append_file))
+}
+
+fn append_file(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (path, data) = match args.as_slice() {
+        [Object::String(path), Object::String(text)] => (path, text.as_bytes()),
+        [Object::String(path), Object::Bytes(bytes)] => (path, bytes.as_slice()),
+        _ => return Object::Error("File::append expects 2 arguments (path, string or bytes)".into()),
+    };
+    if capability::is_dry_run() {
+        capability::log_dry_run(
+            "File::append",
+            &format!("would append {} bytes to {}", data.len(), path),
+        );
+        return Object::Integer(data.len() as i64);
+    }
+    let written = OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(data));
+    match written {
+        Ok(()) => Object::Integer(data.len() as i64),
+        Err(e) => Object::Error(format!("failed to append to {}: {}", path, e)),
+    }
+}
+
+/// File::delete(path) -> Result<null>
+pub(crate) fn file_delete(args: Vec<Object>, env: EnvRef) -> Object {
+    into_result(capability::audited("File::delete", args, env, delete_file))
+}
+
+fn delete_file(args: Vec<Object>, _env: EnvRef) -> Object {
+    let path = match args.as_slice() {
+        [Object::String(path)] => path,
+        _ => return Object::Error("File::delete expects 1 argument (path)".into()),
+    };
+    if capability::is_dry_run() {
+        capability::log_dry_run("File::delete", &format!("would delete {}", path));
+        return Object::Null;
+    }
+    match std::fs::remove_file(path) {
+        Ok(()) => Object::Null,
+        Err(e) => Object::Error(format!("failed to delete {}: {}", path, e)),
+    }
+}
+
+/// File::copy(from, to) -> Result<integer> (bytes copied)
+pub(crate) fn file_copy(args: Vec<Object>, env: EnvRef) -> Object {
+    into_result(capability::audited("File::copy", args, env, copy_file))
+}
+
+fn copy_file(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (from, to) = match args.as_slice() {
+        [Object::String(from), Object::String(to)] => (from, to),
+        _ => return Object::Error("File::copy expects 2 arguments (from, to)".into()),
+    };
+    if capability::is_dry_run() {
+        capability::log_dry_run("File::copy", &format!("would copy {} to {}", from, to));
+        return match std::fs::metadata(from) {
+            Ok(metadata) => Object::Integer(metadata.len() as i64),
+            Err(e) => Object::Error(format!("failed to copy {}: {}", from, e)),
+        };
+    }
+    match std::fs::copy(from, to) {
+        Ok(copied) => Object::Integer(copied as i64),
+        Err(e) => Object::Error(format!("failed to copy {} to {}: {}", from, to, e)),
+    }
+}
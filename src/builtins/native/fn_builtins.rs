@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::rc::Rc;
 
 use crate::env::EnvRef;
 use crate::evaluator::core::expr::apply_function_with_this;
@@ -21,14 +21,7 @@ pub(crate) fn fn_constant(mut args: Vec<Object>, _env: EnvRef) -> Object {
     }
 
     let value = args.pop().unwrap();
-
-    // Create a closure that captures the value
-    // We'll return a special object that holds the constant value
-    let mut captured = HashMap::new();
-    captured.insert("__constant_value__".to_string(), value);
-    captured.insert("__is_constant_fn__".to_string(), Object::Boolean(true));
-    
-    Object::Object(captured)
+    Object::native_fn("Fn::constant", move |_args, _env| value.clone())
 }
 
 /// Fn::compose(f, g) -> fn(x) -> f(g(x))
@@ -42,26 +35,20 @@ pub(crate) fn fn_compose(mut args: Vec<Object>, _env: EnvRef) -> Object {
     let f = args.pop().unwrap();
 
     // Validate both are callable
-    if !is_callable(&f) {
+    if !f.is_callable() {
         return Object::error(format!(
             "Fn::compose first argument must be callable, got {:?}",
             f
         ));
     }
-    if !is_callable(&g) {
+    if !g.is_callable() {
         return Object::error(format!(
             "Fn::compose second argument must be callable, got {:?}",
             g
         ));
     }
 
-    // Store both functions in an object
-    let mut composed = HashMap::new();
-    composed.insert("__compose_f__".to_string(), f);
-    composed.insert("__compose_g__".to_string(), g);
-    composed.insert("__is_composed__".to_string(), Object::Boolean(true));
-
-    Object::Object(composed)
+    composed("Fn::compose", f, g)
 }
 
 /// Fn::pipe(g, f) -> fn(x) -> f(g(x))
@@ -75,26 +62,31 @@ pub(crate) fn fn_pipe(mut args: Vec<Object>, _env: EnvRef) -> Object {
     let g = args.pop().unwrap();
 
     // Validate both are callable
-    if !is_callable(&g) {
+    if !g.is_callable() {
         return Object::error(format!(
             "Fn::pipe first argument must be callable, got {:?}",
             g
         ));
     }
-    if !is_callable(&f) {
+    if !f.is_callable() {
         return Object::error(format!(
             "Fn::pipe second argument must be callable, got {:?}",
             f
         ));
     }
 
-    // Store both functions in an object (same as compose but reversed)
-    let mut piped = HashMap::new();
-    piped.insert("__compose_f__".to_string(), f);
-    piped.insert("__compose_g__".to_string(), g);
-    piped.insert("__is_composed__".to_string(), Object::Boolean(true));
+    composed("Fn::pipe", f, g)
+}
 
-    Object::Object(piped)
+/// A native function computing `f(g(...args))`.
+fn composed(name: &str, f: Object, g: Object) -> Object {
+    Object::native_fn(name, move |args, env| {
+        let g_result = apply_function_with_this(g.clone(), args, None, env.clone());
+        if g_result.is_error() {
+            return g_result;
+        }
+        apply_function_with_this(f.clone(), vec![g_result], None, env)
+    })
 }
 
 /// Fn::apply(f, args) -> f(...args)
@@ -107,8 +99,8 @@ pub(crate) fn fn_apply(mut args: Vec<Object>, env: EnvRef) -> Object {
     let fn_args = args.pop().unwrap();
     let func = args.pop().unwrap();
 
-    let mut args_vec = match fn_args {
-        Object::Array(arr) => arr,
+    let args_vec = match fn_args {
+        Object::Array(arr) => Rc::unwrap_or_clone(arr),
         other => {
             return Object::error(format!(
                 "Fn::apply second argument must be an array, got {:?}",
@@ -117,64 +109,7 @@ pub(crate) fn fn_apply(mut args: Vec<Object>, env: EnvRef) -> Object {
         }
     };
 
-    // Handle special function objects
-    if let Object::Object(ref map) = func {
-        // Handle composed functions
-        if map.get("__is_composed__") == Some(&Object::Boolean(true)) {
-            if let (Some(f), Some(g)) = (map.get("__compose_f__"), map.get("__compose_g__")) {
-                let g_result = apply_function_with_this(g.clone(), args_vec, None, env.clone());
-                if matches!(g_result, Object::Error(_)) {
-                    return g_result;
-                }
-                return apply_function_with_this(f.clone(), vec![g_result], None, env);
-            }
-        }
-
-        // Handle constant functions
-        if map.get("__is_constant_fn__") == Some(&Object::Boolean(true)) {
-            if let Some(value) = map.get("__constant_value__") {
-                return value.clone();
-            }
-        }
-
-        // Handle negated functions
-        if map.get("__is_negated__") == Some(&Object::Boolean(true)) {
-            if let Some(pred) = map.get("__negated_fn__") {
-                let result = apply_function_with_this(pred.clone(), args_vec, None, env);
-                return match result {
-                    Object::Boolean(b) => Object::Boolean(!b),
-                    Object::Error(_) => result,
-                    other => Object::error(format!(
-                        "Negated function must return boolean, got {:?}",
-                        other
-                    )),
-                };
-            }
-        }
-
-        // Handle flipped functions
-        if map.get("__is_flipped__") == Some(&Object::Boolean(true)) {
-            if let Some(inner_func) = map.get("__flipped_fn__") {
-                if args_vec.len() >= 2 {
-                    args_vec.swap(0, 1);
-                }
-                return apply_function_with_this(inner_func.clone(), args_vec, None, env);
-            }
-        }
-
-        // Handle partial application
-        if map.get("__is_partial__") == Some(&Object::Boolean(true)) {
-            if let (Some(inner_func), Some(Object::Array(bound))) =
-                (map.get("__partial_fn__"), map.get("__partial_args__"))
-            {
-                let mut all_args = bound.clone();
-                all_args.extend(args_vec);
-                return apply_function_with_this(inner_func.clone(), all_args, None, env);
-            }
-        }
-    }
-
-    if !is_callable(&func) {
+    if !func.is_callable() {
         return Object::error(format!(
             "Fn::apply first argument must be callable, got {:?}",
             func
@@ -193,64 +128,7 @@ pub(crate) fn fn_call(mut args: Vec<Object>, env: EnvRef) -> Object {
 
     let func = args.remove(0);
 
-    // Handle special function objects
-    if let Object::Object(ref map) = func {
-        // Handle composed functions
-        if map.get("__is_composed__") == Some(&Object::Boolean(true)) {
-            if let (Some(f), Some(g)) = (map.get("__compose_f__"), map.get("__compose_g__")) {
-                let g_result = apply_function_with_this(g.clone(), args, None, env.clone());
-                if matches!(g_result, Object::Error(_)) {
-                    return g_result;
-                }
-                return apply_function_with_this(f.clone(), vec![g_result], None, env);
-            }
-        }
-
-        // Handle constant functions
-        if map.get("__is_constant_fn__") == Some(&Object::Boolean(true)) {
-            if let Some(value) = map.get("__constant_value__") {
-                return value.clone();
-            }
-        }
-
-        // Handle negated functions
-        if map.get("__is_negated__") == Some(&Object::Boolean(true)) {
-            if let Some(pred) = map.get("__negated_fn__") {
-                let result = apply_function_with_this(pred.clone(), args, None, env);
-                return match result {
-                    Object::Boolean(b) => Object::Boolean(!b),
-                    Object::Error(_) => result,
-                    other => Object::error(format!(
-                        "Negated function must return boolean, got {:?}",
-                        other
-                    )),
-                };
-            }
-        }
-
-        // Handle flipped functions
-        if map.get("__is_flipped__") == Some(&Object::Boolean(true)) {
-            if let Some(inner_func) = map.get("__flipped_fn__") {
-                if args.len() >= 2 {
-                    args.swap(0, 1);
-                }
-                return apply_function_with_this(inner_func.clone(), args, None, env);
-            }
-        }
-
-        // Handle partial application
-        if map.get("__is_partial__") == Some(&Object::Boolean(true)) {
-            if let (Some(inner_func), Some(Object::Array(bound))) =
-                (map.get("__partial_fn__"), map.get("__partial_args__"))
-            {
-                let mut all_args = bound.clone();
-                all_args.extend(args);
-                return apply_function_with_this(inner_func.clone(), all_args, None, env);
-            }
-        }
-    }
-
-    if !is_callable(&func) {
+    if !func.is_callable() {
         return Object::error(format!(
             "Fn::call first argument must be callable, got {:?}",
             func
@@ -269,18 +147,23 @@ pub(crate) fn fn_negate(mut args: Vec<Object>, _env: EnvRef) -> Object {
 
     let pred = args.pop().unwrap();
 
-    if !is_callable(&pred) {
+    if !pred.is_callable() {
         return Object::error(format!(
             "Fn::negate argument must be callable, got {:?}",
             pred
         ));
     }
 
-    let mut negated = HashMap::new();
-    negated.insert("__negated_fn__".to_string(), pred);
-    negated.insert("__is_negated__".to_string(), Object::Boolean(true));
-
-    Object::Object(negated)
+    Object::native_fn("Fn::negate", move |args, env| {
+        match apply_function_with_this(pred.clone(), args, None, env) {
+            Object::Boolean(b) => Object::Boolean(!b),
+            err @ Object::Error(_) => err,
+            other => Object::error(format!(
+                "Negated function must return boolean, got {:?}",
+                other
+            )),
+        }
+    })
 }
 
 /// Fn::flip(f) -> fn(a, b) -> f(b, a)
@@ -292,18 +175,19 @@ pub(crate) fn fn_flip(mut args: Vec<Object>, _env: EnvRef) -> Object {
 
     let func = args.pop().unwrap();
 
-    if !is_callable(&func) {
+    if !func.is_callable() {
         return Object::error(format!(
             "Fn::flip argument must be callable, got {:?}",
             func
         ));
     }
 
-    let mut flipped = HashMap::new();
-    flipped.insert("__flipped_fn__".to_string(), func);
-    flipped.insert("__is_flipped__".to_string(), Object::Boolean(true));
-
-    Object::Object(flipped)
+    Object::native_fn("Fn::flip", move |mut args, env| {
+        if args.len() >= 2 {
+            args.swap(0, 1);
+        }
+        apply_function_with_this(func.clone(), args, None, env)
+    })
 }
 
 /// Fn::partial(f, ...boundArgs) -> fn(...remainingArgs) -> f(...boundArgs, ...remainingArgs)
@@ -316,19 +200,18 @@ pub(crate) fn fn_partial(mut args: Vec<Object>, _env: EnvRef) -> Object {
     let func = args.remove(0);
     let bound_args = args;
 
-    if !is_callable(&func) {
+    if !func.is_callable() {
         return Object::error(format!(
             "Fn::partial first argument must be callable, got {:?}",
             func
         ));
     }
 
-    let mut partial = HashMap::new();
-    partial.insert("__partial_fn__".to_string(), func);
-    partial.insert("__partial_args__".to_string(), Object::Array(bound_args));
-    partial.insert("__is_partial__".to_string(), Object::Boolean(true));
-
-    Object::Object(partial)
+    Object::native_fn("Fn::partial", move |args, env| {
+        let mut all_args = bound_args.clone();
+        all_args.extend(args);
+        apply_function_with_this(func.clone(), all_args, None, env)
+    })
 }
 
 /// Fn::isCallable(value) -> boolean
@@ -339,9 +222,5 @@ pub(crate) fn fn_is_callable(mut args: Vec<Object>, _env: EnvRef) -> Object {
     }
 
     let value = args.pop().unwrap();
-    Object::Boolean(is_callable(&value))
-}
-
-fn is_callable(obj: &Object) -> bool {
-    matches!(obj, Object::Function { .. } | Object::Builtin(_))
+    Object::Boolean(value.is_callable())
 }
@@ -0,0 +1,136 @@
+//! The `Yaml` and `Toml` namespaces. Both go through the same JSON values as
+//! `Json` and `slang diff-config`, so the three formats convert to and from
+//! Slang values the same way.
+
+use serde_json::Value;
+use yaml_rust2::yaml::Hash;
+use yaml_rust2::{Yaml, YamlEmitter};
+
+use crate::builtins::native::json_builtins::to_json_value;
+use crate::diff::core::{parse_toml, parse_yaml};
+use crate::env::EnvRef;
+use crate::object::Object;
+use crate::object::convert::json_to_object;
+
+/// The single string argument of `name`.
+fn text_arg<'a>(args: &'a [Object], name: &str) -> Result<&'a str, Object> {
+    match args {
+        [Object::String(s)] => Ok(s),
+        [other] => Err(Object::error(format!("{} expects a string, got {:?}", name, other))),
+        _ => Err(Object::error(format!("{} expects exactly 1 argument (string)", name))),
+    }
+}
+
+fn parsed(value: Result<Value, String>) -> Object {
+    match value {
+        Ok(v) => Object::ResultOk(Box::new(json_to_object(&v))),
+        Err(e) => Object::ResultErr(Box::new(Object::String(e))),
+    }
+}
+
+fn stringified(text: Result<String, String>) -> Object {
+    match text {
+        Ok(s) => Object::ResultOk(Box::new(Object::String(s))),
+        Err(e) => Object::ResultErr(Box::new(Object::String(e))),
+    }
+}
+
+/// Yaml::parse(s) -> Result::Ok(value) or Result::Err(errorString). Only the
+/// first document in `s` is read.
+pub(crate) fn yaml_parse(args: Vec<Object>, _env: EnvRef) -> Object {
+    match text_arg(&args, "Yaml::parse") {
+        Ok(s) => parsed(parse_yaml(s)),
+        Err(err) => err,
+    }
+}
+
+/// Yaml::stringify(value) -> Result::Ok(string) or Result::Err(errorString)
+pub(crate) fn yaml_stringify(args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.len() != 1 {
+        return Object::error("Yaml::stringify expects exactly 1 argument (value)");
+    }
+    let yaml = json_to_yaml(to_json_value(&args[0]));
+    let mut out = String::new();
+    if let Err(e) = YamlEmitter::new(&mut out).dump(&yaml) {
+        return stringified(Err(e.to_string()));
+    }
+    // The emitter starts every document with a `---` marker, which a single
+    // document doesn't need.
+    let body = out.strip_prefix("---").unwrap_or(&out).trim_start_matches([' ', '\n']);
+    stringified(Ok(format!("{}\n", body)))
+}
+
+fn json_to_yaml(value: Value) -> Yaml {
+    match value {
+        Value::Null => Yaml::Null,
+        Value::Bool(b) => Yaml::Boolean(b),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => Yaml::Integer(i),
+            // `{:?}` keeps the `.0` on whole floats so they read back as floats.
+            None => Yaml::Real(format!("{:?}", n.as_f64().unwrap_or(0.0))),
+        },
+        Value::String(s) => Yaml::String(s),
+        Value::Array(items) => Yaml::Array(items.into_iter().map(json_to_yaml).collect()),
+        Value::Object(map) => {
+            let mut hash = Hash::new();
+            for (key, value) in map {
+                hash.insert(Yaml::String(key), json_to_yaml(value));
+            }
+            Yaml::Hash(hash)
+        }
+    }
+}
+
+/// Toml::parse(s) -> Result::Ok(object) or Result::Err(errorString). Dates
+/// and times come back as strings.
+pub(crate) fn toml_parse(args: Vec<Object>, _env: EnvRef) -> Object {
+    match text_arg(&args, "Toml::parse") {
+        Ok(s) => parsed(parse_toml(s)),
+        Err(err) => err,
+    }
+}
+
+/// Toml::stringify(object) -> Result::Ok(string) or Result::Err(errorString).
+/// A TOML document is a table, and TOML has no null, so anything else fails.
+pub(crate) fn toml_stringify(args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.len() != 1 {
+        return Object::error("Toml::stringify expects exactly 1 argument (object)");
+    }
+    if !matches!(args[0], Object::Object(_)) {
+        return stringified(Err("a TOML document must be an object".to_string()));
+    }
+    let table = match json_to_toml(to_json_value(&args[0]), "") {
+        Ok(toml::Value::Table(table)) => table,
+        Ok(other) => unreachable!("an object converted to {:?}", other),
+        Err(e) => return stringified(Err(e)),
+    };
+    stringified(toml::to_string(&table).map_err(|e| e.to_string()))
+}
+
+/// Convert `value`, found at `path` (e.g. `server.ports`), to TOML.
+fn json_to_toml(value: Value, path: &str) -> Result<toml::Value, String> {
+    Ok(match value {
+        Value::Null => return Err(format!("TOML has no null value (at {})", path)),
+        Value::Bool(b) => toml::Value::Boolean(b),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => toml::Value::Integer(i),
+            None => toml::Value::Float(n.as_f64().unwrap_or(0.0)),
+        },
+        Value::String(s) => toml::Value::String(s),
+        Value::Array(items) => toml::Value::Array(
+            items
+                .into_iter()
+                .enumerate()
+                .map(|(i, item)| json_to_toml(item, &format!("{}[{}]", path, i)))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Object(map) => {
+            let mut table = toml::Table::new();
+            for (key, value) in map {
+                let child = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                table.insert(key, json_to_toml(value, &child)?);
+            }
+            toml::Value::Table(table)
+        }
+    })
+}
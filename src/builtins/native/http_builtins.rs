@@ -1,15 +1,18 @@
-use std::collections::HashMap;
+use std::io::Read;
+use std::rc::Rc;
 use std::time::Duration;
 
+use crate::capability;
 use crate::env::EnvRef;
-use crate::object::Object;
+use crate::object::{Object, ObjectMap};
+use crate::object::convert::json_to_object;
 
 /// Converts a slang Object (HashMap) to HTTP headers
 fn extract_headers(obj: &Object) -> Result<Vec<(String, String)>, String> {
     match obj {
         Object::Object(map) => {
             let mut headers = Vec::new();
-            for (key, value) in map {
+            for (key, value) in map.iter() {
                 match value {
                     Object::String(s) => headers.push((key.clone(), s.clone())),
                     other => {
@@ -26,242 +29,170 @@ fn extract_headers(obj: &Object) -> Result<Vec<(String, String)>, String> {
     }
 }
 
-/// Converts a ureq Response to a slang Object
-fn response_to_object(response: ureq::Response) -> Object {
-    let status = response.status();
-    let status_text = response.status_text().to_string();
-
-    // Collect headers
-    let mut headers_map = HashMap::new();
-    for name in response.headers_names() {
-        if let Some(value) = response.header(&name) {
-            headers_map.insert(name, Object::String(value.to_string()));
-        }
-    }
-
-    // Read body as string
-    let body = response.into_string().unwrap_or_default();
-
-    let mut result = HashMap::new();
-    result.insert("status".to_string(), Object::Integer(status as i64));
-    result.insert("statusText".to_string(), Object::String(status_text));
-    result.insert("headers".to_string(), Object::Object(headers_map));
-    result.insert("body".to_string(), Object::String(body));
-
-    Object::Object(result)
-}
-
-/// HTTP::get(url) -> Result<{ status, statusText, headers, body }>
-/// HTTP::get(url, options) -> Result<{ status, statusText, headers, body }>
-/// options: { headers: { ... }, timeout: ms }
-pub(crate) fn http_get(mut args: Vec<Object>, _env: EnvRef) -> Object {
-    if args.is_empty() || args.len() > 2 {
-        return Object::error("HTTP::get expects 1 or 2 arguments (url, [options])");
-    }
-
-    let options = if args.len() == 2 { args.pop() } else { None };
-    let url = args.pop().unwrap();
-
-    let url_str = match url {
-        Object::String(s) => s,
-        other => {
-            return Object::error(format!("HTTP::get expects string URL, got {:?}", other))
-        }
+/// Flattens an object into `(key, value)` pairs for a query string or form
+/// body. Keys come out sorted; an array value repeats its key per element.
+fn extract_params(obj: &Object, option: &str) -> Result<Vec<(String, String)>, String> {
+    let map = match obj {
+        Object::Object(map) => map,
+        other => return Err(format!("HTTP {} must be an object, got {:?}", option, other)),
     };
-
-    let mut request = ureq::get(&url_str);
-
-    // Apply options if provided
-    if let Some(opts) = options {
-        if let Object::Object(opts_map) = opts {
-            // Apply headers
-            if let Some(headers_obj) = opts_map.get("headers") {
-                match extract_headers(headers_obj) {
-                    Ok(headers) => {
-                        for (key, value) in headers {
-                            request = request.set(&key, &value);
-                        }
-                    }
-                    Err(e) => return Object::error(e),
+    let scalar = |key: &str, value: &Object| match value {
+        Object::String(s) => Ok(s.clone()),
+        Object::Integer(_) | Object::Float(_) | Object::Boolean(_) => Ok(value.to_string()),
+        other => Err(format!(
+            "HTTP {} value for key '{}' must be a string, number or boolean, got {:?}",
+            option, key, other
+        )),
+    };
+    let mut keys: Vec<&String> = map.keys().collect();
+    keys.sort();
+    let mut params = Vec::new();
+    for key in keys {
+        match &map[key] {
+            Object::Array(items) => {
+                for item in items.iter() {
+                    params.push((key.clone(), scalar(key, item)?));
                 }
             }
-
-            // Apply timeout
-            if let Some(Object::Integer(ms)) = opts_map.get("timeout") {
-                request = request.timeout(Duration::from_millis(*ms as u64));
-            }
+            value => params.push((key.clone(), scalar(key, value)?)),
         }
     }
+    Ok(params)
+}
 
-    match request.call() {
-        Ok(response) => Object::ResultOk(Box::new(response_to_object(response))),
-        Err(ureq::Error::Status(code, response)) => {
-            // HTTP error status (4xx, 5xx) - still return the response
-            let result = match response_to_object(response) {
-                Object::Object(mut map) => {
-                    map.insert("status".to_string(), Object::Integer(code as i64));
-                    Object::Object(map)
+/// `application/x-www-form-urlencoded`: spaces become `+` and everything but
+/// letters, digits and `*-._` is percent-encoded.
+fn form_encode(params: &[(String, String)]) -> String {
+    let encode = |s: &str| -> String {
+        s.bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'*' | b'-' | b'.' | b'_' => {
+                    (b as char).to_string()
                 }
-                other => other,
-            };
-            Object::ResultErr(Box::new(result))
-        }
-        Err(e) => Object::ResultErr(Box::new(Object::String(format!(
-            "HTTP request failed: {}",
-            e
-        )))),
-    }
+                b' ' => "+".to_string(),
+                _ => format!("%{:02X}", b),
+            })
+            .collect()
+    };
+    params
+        .iter()
+        .map(|(key, value)| format!("{}={}", encode(key), encode(value)))
+        .collect::<Vec<_>>()
+        .join("&")
 }
 
-/// HTTP::post(url, body) -> Result<{ status, statusText, headers, body }>
-/// HTTP::post(url, body, options) -> Result<{ status, statusText, headers, body }>
-pub(crate) fn http_post(mut args: Vec<Object>, _env: EnvRef) -> Object {
-    if args.len() < 2 || args.len() > 3 {
-        return Object::error("HTTP::post expects 2 or 3 arguments (url, body, [options])");
-    }
-
-    let options = if args.len() == 3 { args.pop() } else { None };
-    let body = args.pop().unwrap();
-    let url = args.pop().unwrap();
+/// How the response body is handed back to the script.
+#[derive(Clone, Copy, PartialEq)]
+enum BodyMode {
+    Text,
+    /// `{ bytes: true }`
+    Bytes,
+    /// `{ json: true }`
+    Json,
+}
 
-    let url_str = match url {
-        Object::String(s) => s,
-        other => {
-            return Object::error(format!("HTTP::post expects string URL, got {:?}", other))
-        }
+fn body_mode(options: Option<&Object>) -> Result<BodyMode, Object> {
+    let Some(Object::Object(opts)) = options else {
+        return Ok(BodyMode::Text);
     };
-
-    let body_str = match &body {
-        Object::String(s) => s.clone(),
-        // For objects/arrays, serialize to JSON
-        Object::Object(_) | Object::Array(_) => {
-            match serde_json::to_string(&object_to_json(&body)) {
-                Ok(s) => s,
-                Err(e) => return Object::error(format!("Failed to serialize body: {}", e)),
-            }
-        }
-        other => {
-            return Object::error(format!(
-                "HTTP::post body must be string or object, got {:?}",
-                other
-            ))
+    match (opts.get("bytes"), opts.get("json")) {
+        (Some(Object::Boolean(true)), Some(Object::Boolean(true))) => {
+            Err(Object::error("HTTP options bytes and json can't both be set"))
         }
-    };
-
-    let mut request = ureq::post(&url_str);
-
-    // Set content-type for JSON bodies
-    if matches!(body, Object::Object(_) | Object::Array(_)) {
-        request = request.set("Content-Type", "application/json");
+        (Some(Object::Boolean(true)), _) => Ok(BodyMode::Bytes),
+        (_, Some(Object::Boolean(true))) => Ok(BodyMode::Json),
+        _ => Ok(BodyMode::Text),
     }
+}
 
-    // Apply options if provided
-    if let Some(opts) = options {
-        if let Object::Object(opts_map) = opts {
-            if let Some(headers_obj) = opts_map.get("headers") {
-                match extract_headers(headers_obj) {
-                    Ok(headers) => {
-                        for (key, value) in headers {
-                            request = request.set(&key, &value);
-                        }
-                    }
-                    Err(e) => return Object::error(e),
-                }
-            }
+/// Applies the `headers`, `timeout` and `query` options to a request.
+fn apply_options(mut request: ureq::Request, options: Option<&Object>) -> Result<ureq::Request, Object> {
+    let Some(Object::Object(opts_map)) = options else {
+        return Ok(request);
+    };
 
-            if let Some(Object::Integer(ms)) = opts_map.get("timeout") {
-                request = request.timeout(Duration::from_millis(*ms as u64));
-            }
+    if let Some(headers_obj) = opts_map.get("headers") {
+        for (key, value) in extract_headers(headers_obj).map_err(Object::error)? {
+            request = request.set(&key, &value);
         }
     }
 
-    match request.send_string(&body_str) {
-        Ok(response) => Object::ResultOk(Box::new(response_to_object(response))),
-        Err(ureq::Error::Status(code, response)) => {
-            let result = match response_to_object(response) {
-                Object::Object(mut map) => {
-                    map.insert("status".to_string(), Object::Integer(code as i64));
-                    Object::Object(map)
-                }
-                other => other,
-            };
-            Object::ResultErr(Box::new(result))
-        }
-        Err(e) => Object::ResultErr(Box::new(Object::String(format!(
-            "HTTP request failed: {}",
-            e
-        )))),
+    if let Some(Object::Integer(ms)) = opts_map.get("timeout") {
+        request = request.timeout(Duration::from_millis(*ms as u64));
     }
-}
 
-/// HTTP::put(url, body) -> Result<{ status, statusText, headers, body }>
-/// HTTP::put(url, body, options) -> Result<{ status, statusText, headers, body }>
-pub(crate) fn http_put(mut args: Vec<Object>, _env: EnvRef) -> Object {
-    if args.len() < 2 || args.len() > 3 {
-        return Object::error("HTTP::put expects 2 or 3 arguments (url, body, [options])");
+    if let Some(query) = opts_map.get("query") {
+        for (key, value) in extract_params(query, "query").map_err(Object::error)? {
+            request = request.query(&key, &value);
+        }
     }
 
-    let options = if args.len() == 3 { args.pop() } else { None };
-    let body = args.pop().unwrap();
-    let url = args.pop().unwrap();
+    Ok(request)
+}
 
-    let url_str = match url {
-        Object::String(s) => s,
-        other => {
-            return Object::error(format!("HTTP::put expects string URL, got {:?}", other))
-        }
-    };
+/// Converts a ureq Response to a slang Object, with the body as the options
+/// asked for. A body that isn't valid JSON under `{ json: true }` is an error
+/// when `strict`, and is kept as a string otherwise.
+fn response_to_object(response: ureq::Response, mode: BodyMode, strict: bool) -> Result<Object, String> {
+    let status = response.status();
+    let status_text = response.status_text().to_string();
 
-    let body_str = match &body {
-        Object::String(s) => s.clone(),
-        Object::Object(_) | Object::Array(_) => {
-            match serde_json::to_string(&object_to_json(&body)) {
-                Ok(s) => s,
-                Err(e) => return Object::error(format!("Failed to serialize body: {}", e)),
-            }
-        }
-        other => {
-            return Object::error(format!(
-                "HTTP::put body must be string or object, got {:?}",
-                other
-            ))
+    // Collect headers
+    let mut headers_map = ObjectMap::new();
+    for name in response.headers_names() {
+        if let Some(value) = response.header(&name) {
+            headers_map.insert(name, Object::String(value.to_string()));
         }
-    };
-
-    let mut request = ureq::put(&url_str);
-
-    if matches!(body, Object::Object(_) | Object::Array(_)) {
-        request = request.set("Content-Type", "application/json");
     }
 
-    if let Some(opts) = options {
-        if let Object::Object(opts_map) = opts {
-            if let Some(headers_obj) = opts_map.get("headers") {
-                match extract_headers(headers_obj) {
-                    Ok(headers) => {
-                        for (key, value) in headers {
-                            request = request.set(&key, &value);
-                        }
-                    }
-                    Err(e) => return Object::error(e),
+    let body = match mode {
+        BodyMode::Bytes => {
+            let mut buf = Vec::new();
+            let _ = response.into_reader().read_to_end(&mut buf);
+            Object::bytes(buf)
+        }
+        BodyMode::Text => Object::String(response.into_string().unwrap_or_default()),
+        BodyMode::Json => {
+            let text = response.into_string().unwrap_or_default();
+            if text.trim().is_empty() {
+                Object::Null
+            } else {
+                match serde_json::from_str::<serde_json::Value>(&text) {
+                    Ok(value) => json_to_object(&value),
+                    Err(e) if strict => return Err(format!("response body is not valid JSON: {}", e)),
+                    Err(_) => Object::String(text),
                 }
             }
-
-            if let Some(Object::Integer(ms)) = opts_map.get("timeout") {
-                request = request.timeout(Duration::from_millis(*ms as u64));
-            }
         }
-    }
+    };
+
+    let mut result = ObjectMap::new();
+    result.insert("status".to_string(), Object::Integer(status as i64));
+    result.insert("statusText".to_string(), Object::String(status_text));
+    result.insert("headers".to_string(), Object::from_map(headers_map));
+    result.insert("body".to_string(), body);
+
+    Ok(Object::from_map(result))
+}
 
-    match request.send_string(&body_str) {
-        Ok(response) => Object::ResultOk(Box::new(response_to_object(response))),
+/// Wraps the outcome of a request: `Ok(response)` for 2xx/3xx, `Err(response)`
+/// for error statuses (keeping their body, which needn't be JSON), and
+/// `Err(message)` when the request couldn't be made.
+fn finish(outcome: Result<ureq::Response, ureq::Error>, mode: BodyMode) -> Object {
+    match outcome {
+        Ok(response) => match response_to_object(response, mode, true) {
+            Ok(result) => Object::ResultOk(Box::new(result)),
+            Err(e) => Object::ResultErr(Box::new(Object::String(e))),
+        },
         Err(ureq::Error::Status(code, response)) => {
-            let result = match response_to_object(response) {
-                Object::Object(mut map) => {
-                    map.insert("status".to_string(), Object::Integer(code as i64));
+            // HTTP error status (4xx, 5xx) - still return the response
+            let result = match response_to_object(response, mode, false) {
+                Ok(Object::Object(mut map)) => {
+                    Rc::make_mut(&mut map).insert("status".to_string(), Object::Integer(code as i64));
                     Object::Object(map)
                 }
-                other => other,
+                Ok(other) => other,
+                Err(e) => Object::String(e),
             };
             Object::ResultErr(Box::new(result))
         }
@@ -272,139 +203,176 @@ pub(crate) fn http_put(mut args: Vec<Object>, _env: EnvRef) -> Object {
     }
 }
 
-/// HTTP::delete(url) -> Result<{ status, statusText, headers, body }>
-/// HTTP::delete(url, options) -> Result<{ status, statusText, headers, body }>
-pub(crate) fn http_delete(mut args: Vec<Object>, _env: EnvRef) -> Object {
+/// Synthetic response returned for mutating requests in dry-run mode.
+fn dry_run_response(method: &str, url: &str, mode: BodyMode) -> Object {
+    capability::log_dry_run(
+        &format!("HTTP::{}", method.to_lowercase()),
+        &format!("would send {} {}", method, url),
+    );
+
+    let mut result = ObjectMap::new();
+    result.insert("status".to_string(), Object::Integer(200));
+    result.insert("statusText".to_string(), Object::String("OK (dry run)".to_string()));
+    result.insert("headers".to_string(), Object::from_map(ObjectMap::new()));
+    let body = match mode {
+        BodyMode::Text => Object::String(String::new()),
+        BodyMode::Bytes => Object::bytes(Vec::new()),
+        BodyMode::Json => Object::Null,
+    };
+    result.insert("body".to_string(), body);
+
+    Object::ResultOk(Box::new(Object::from_map(result)))
+}
+
+/// Shared body of the requests without a body: GET and DELETE.
+fn send_without_body(method: &str, mut args: Vec<Object>) -> Object {
+    let name = format!("HTTP::{}", method.to_lowercase());
     if args.is_empty() || args.len() > 2 {
-        return Object::error("HTTP::delete expects 1 or 2 arguments (url, [options])");
+        return Object::error(format!("{} expects 1 or 2 arguments (url, [options])", name));
     }
 
     let options = if args.len() == 2 { args.pop() } else { None };
+    let mode = match body_mode(options.as_ref()) {
+        Ok(mode) => mode,
+        Err(e) => return e,
+    };
     let url = args.pop().unwrap();
 
     let url_str = match url {
         Object::String(s) => s,
         other => {
-            return Object::error(format!("HTTP::delete expects string URL, got {:?}", other))
+            return Object::error(format!("{} expects string URL, got {:?}", name, other))
         }
     };
 
-    let mut request = ureq::delete(&url_str);
-
-    if let Some(opts) = options {
-        if let Object::Object(opts_map) = opts {
-            if let Some(headers_obj) = opts_map.get("headers") {
-                match extract_headers(headers_obj) {
-                    Ok(headers) => {
-                        for (key, value) in headers {
-                            request = request.set(&key, &value);
-                        }
-                    }
-                    Err(e) => return Object::error(e),
-                }
-            }
-
-            if let Some(Object::Integer(ms)) = opts_map.get("timeout") {
-                request = request.timeout(Duration::from_millis(*ms as u64));
-            }
-        }
+    if method != "GET" && capability::is_dry_run() {
+        return dry_run_response(method, &url_str, mode);
     }
 
-    match request.call() {
-        Ok(response) => Object::ResultOk(Box::new(response_to_object(response))),
-        Err(ureq::Error::Status(code, response)) => {
-            let result = match response_to_object(response) {
-                Object::Object(mut map) => {
-                    map.insert("status".to_string(), Object::Integer(code as i64));
-                    Object::Object(map)
-                }
-                other => other,
-            };
-            Object::ResultErr(Box::new(result))
-        }
-        Err(e) => Object::ResultErr(Box::new(Object::String(format!(
-            "HTTP request failed: {}",
-            e
-        )))),
+    match apply_options(ureq::request(method, &url_str), options.as_ref()) {
+        Ok(request) => finish(request.call(), mode),
+        Err(e) => e,
     }
 }
 
-/// HTTP::patch(url, body) -> Result<{ status, statusText, headers, body }>
-/// HTTP::patch(url, body, options) -> Result<{ status, statusText, headers, body }>
-pub(crate) fn http_patch(mut args: Vec<Object>, _env: EnvRef) -> Object {
+/// Shared body of the requests with a body: POST, PUT and PATCH.
+fn send_with_body(method: &str, mut args: Vec<Object>) -> Object {
+    let name = format!("HTTP::{}", method.to_lowercase());
     if args.len() < 2 || args.len() > 3 {
-        return Object::error("HTTP::patch expects 2 or 3 arguments (url, body, [options])");
+        return Object::error(format!("{} expects 2 or 3 arguments (url, body, [options])", name));
     }
 
     let options = if args.len() == 3 { args.pop() } else { None };
+    let mode = match body_mode(options.as_ref()) {
+        Ok(mode) => mode,
+        Err(e) => return e,
+    };
+    let form = match &options {
+        Some(Object::Object(opts)) => matches!(opts.get("form"), Some(Object::Boolean(true))),
+        _ => false,
+    };
     let body = args.pop().unwrap();
     let url = args.pop().unwrap();
 
     let url_str = match url {
         Object::String(s) => s,
         other => {
-            return Object::error(format!("HTTP::patch expects string URL, got {:?}", other))
+            return Object::error(format!("{} expects string URL, got {:?}", name, other))
         }
     };
 
-    let body_str = match &body {
-        Object::String(s) => s.clone(),
+    if capability::is_dry_run() {
+        return dry_run_response(method, &url_str, mode);
+    }
+
+    let (body_data, content_type) = match &body {
+        Object::Object(_) if form => match extract_params(&body, "form body") {
+            Ok(params) => (form_encode(&params).into_bytes(), Some("application/x-www-form-urlencoded")),
+            Err(e) => return Object::error(e),
+        },
+        _ if form => {
+            return Object::error(format!(
+                "{} with {{ form: true }} needs an object body, got {:?}",
+                name, body
+            ))
+        }
+        Object::String(s) => (s.clone().into_bytes(), None),
+        Object::Bytes(data) => (data.to_vec(), Some("application/octet-stream")),
+        // For objects/arrays, serialize to JSON
         Object::Object(_) | Object::Array(_) => {
             match serde_json::to_string(&object_to_json(&body)) {
-                Ok(s) => s,
+                Ok(s) => (s.into_bytes(), Some("application/json")),
                 Err(e) => return Object::error(format!("Failed to serialize body: {}", e)),
             }
         }
         other => {
             return Object::error(format!(
-                "HTTP::patch body must be string or object, got {:?}",
-                other
+                "{} body must be string, bytes or object, got {:?}",
+                name, other
             ))
         }
     };
 
-    let mut request = ureq::patch(&url_str);
+    let mut request = ureq::request(method, &url_str);
+    if let Some(content_type) = content_type {
+        request = request.set("Content-Type", content_type);
+    }
 
-    if matches!(body, Object::Object(_) | Object::Array(_)) {
-        request = request.set("Content-Type", "application/json");
+    // Options come after the default content type so headers can override it.
+    match apply_options(request, options.as_ref()) {
+        Ok(request) => finish(request.send_bytes(&body_data), mode),
+        Err(e) => e,
     }
+}
 
-    if let Some(opts) = options {
-        if let Object::Object(opts_map) = opts {
-            if let Some(headers_obj) = opts_map.get("headers") {
-                match extract_headers(headers_obj) {
-                    Ok(headers) => {
-                        for (key, value) in headers {
-                            request = request.set(&key, &value);
-                        }
-                    }
-                    Err(e) => return Object::error(e),
-                }
-            }
+/// HTTP::get(url) -> Result<{ status, statusText, headers, body }>
+/// HTTP::get(url, options) -> Result<{ status, statusText, headers, body }>
+/// options: { headers: { ... }, query: { ... }, timeout: ms, bytes: bool, json: bool }
+/// With `bytes: true` the response body is bytes instead of a string, and with
+/// `json: true` it is parsed as JSON.
+pub(crate) fn http_get(args: Vec<Object>, _env: EnvRef) -> Object {
+    send_without_body("GET", args)
+}
 
-            if let Some(Object::Integer(ms)) = opts_map.get("timeout") {
-                request = request.timeout(Duration::from_millis(*ms as u64));
-            }
-        }
-    }
+/// HTTP::post(url, body) -> Result<{ status, statusText, headers, body }>
+/// HTTP::post(url, body, options) -> Result<{ status, statusText, headers, body }>
+/// With `form: true` in the options an object body is sent form-encoded.
+pub(crate) fn http_post(args: Vec<Object>, env: EnvRef) -> Object {
+    capability::audited("HTTP::post", args, env, send_post)
+}
 
-    match request.send_string(&body_str) {
-        Ok(response) => Object::ResultOk(Box::new(response_to_object(response))),
-        Err(ureq::Error::Status(code, response)) => {
-            let result = match response_to_object(response) {
-                Object::Object(mut map) => {
-                    map.insert("status".to_string(), Object::Integer(code as i64));
-                    Object::Object(map)
-                }
-                other => other,
-            };
-            Object::ResultErr(Box::new(result))
-        }
-        Err(e) => Object::ResultErr(Box::new(Object::String(format!(
-            "HTTP request failed: {}",
-            e
-        )))),
-    }
+fn send_post(args: Vec<Object>, _env: EnvRef) -> Object {
+    send_with_body("POST", args)
+}
+
+/// HTTP::put(url, body) -> Result<{ status, statusText, headers, body }>
+/// HTTP::put(url, body, options) -> Result<{ status, statusText, headers, body }>
+pub(crate) fn http_put(args: Vec<Object>, env: EnvRef) -> Object {
+    capability::audited("HTTP::put", args, env, send_put)
+}
+
+fn send_put(args: Vec<Object>, _env: EnvRef) -> Object {
+    send_with_body("PUT", args)
+}
+
+/// HTTP::delete(url) -> Result<{ status, statusText, headers, body }>
+/// HTTP::delete(url, options) -> Result<{ status, statusText, headers, body }>
+pub(crate) fn http_delete(args: Vec<Object>, env: EnvRef) -> Object {
+    capability::audited("HTTP::delete", args, env, send_delete)
+}
+
+fn send_delete(args: Vec<Object>, _env: EnvRef) -> Object {
+    send_without_body("DELETE", args)
+}
+
+/// HTTP::patch(url, body) -> Result<{ status, statusText, headers, body }>
+/// HTTP::patch(url, body, options) -> Result<{ status, statusText, headers, body }>
+pub(crate) fn http_patch(args: Vec<Object>, env: EnvRef) -> Object {
+    capability::audited("HTTP::patch", args, env, send_patch)
+}
+
+fn send_patch(args: Vec<Object>, _env: EnvRef) -> Object {
+    send_with_body("PATCH", args)
 }
 
 /// HTTP::head(url) -> Result<{ status, statusText, headers }>
@@ -423,62 +391,46 @@ pub(crate) fn http_head(mut args: Vec<Object>, _env: EnvRef) -> Object {
         }
     };
 
-    let mut request = ureq::head(&url_str);
-
-    if let Some(opts) = options {
-        if let Object::Object(opts_map) = opts {
-            if let Some(headers_obj) = opts_map.get("headers") {
-                match extract_headers(headers_obj) {
-                    Ok(headers) => {
-                        for (key, value) in headers {
-                            request = request.set(&key, &value);
-                        }
-                    }
-                    Err(e) => return Object::error(e),
-                }
-            }
-
-            if let Some(Object::Integer(ms)) = opts_map.get("timeout") {
-                request = request.timeout(Duration::from_millis(*ms as u64));
-            }
-        }
-    }
+    let request = match apply_options(ureq::head(&url_str), options.as_ref()) {
+        Ok(request) => request,
+        Err(e) => return e,
+    };
 
     match request.call() {
         Ok(response) => {
             let status = response.status();
             let status_text = response.status_text().to_string();
 
-            let mut headers_map = HashMap::new();
+            let mut headers_map = ObjectMap::new();
             for name in response.headers_names() {
                 if let Some(value) = response.header(&name) {
                     headers_map.insert(name, Object::String(value.to_string()));
                 }
             }
 
-            let mut result = HashMap::new();
+            let mut result = ObjectMap::new();
             result.insert("status".to_string(), Object::Integer(status as i64));
             result.insert("statusText".to_string(), Object::String(status_text));
-            result.insert("headers".to_string(), Object::Object(headers_map));
+            result.insert("headers".to_string(), Object::from_map(headers_map));
 
-            Object::ResultOk(Box::new(Object::Object(result)))
+            Object::ResultOk(Box::new(Object::from_map(result)))
         }
         Err(ureq::Error::Status(code, response)) => {
             let status_text = response.status_text().to_string();
 
-            let mut headers_map = HashMap::new();
+            let mut headers_map = ObjectMap::new();
             for name in response.headers_names() {
                 if let Some(value) = response.header(&name) {
                     headers_map.insert(name, Object::String(value.to_string()));
                 }
             }
 
-            let mut result = HashMap::new();
+            let mut result = ObjectMap::new();
             result.insert("status".to_string(), Object::Integer(code as i64));
             result.insert("statusText".to_string(), Object::String(status_text));
-            result.insert("headers".to_string(), Object::Object(headers_map));
+            result.insert("headers".to_string(), Object::from_map(headers_map));
 
-            Object::ResultErr(Box::new(Object::Object(result)))
+            Object::ResultErr(Box::new(Object::from_map(result)))
         }
         Err(e) => Object::ResultErr(Box::new(Object::String(format!(
             "HTTP request failed: {}",
@@ -512,4 +464,3 @@ fn object_to_json(obj: &Object) -> serde_json::Value {
         _ => serde_json::Value::Null,
     }
 }
-
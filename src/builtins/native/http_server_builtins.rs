@@ -0,0 +1,362 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::env::EnvRef;
+use crate::evaluator::core::budget;
+use crate::evaluator::core::expr::apply_function_with_this;
+use crate::event_loop;
+use crate::object::{Object, ObjectMap};
+use crate::object::convert::from_slang;
+
+/// How long `listen` waits for a connection before looking for a signal.
+const ACCEPT_POLL: Duration = Duration::from_millis(50);
+
+/// Largest request body accepted; bigger requests get a 413.
+const MAX_BODY: usize = 16 * 1024 * 1024;
+
+/// Longest a client may take to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+struct Settings {
+    host: String,
+    max_requests: Option<usize>,
+    bytes: bool,
+}
+
+fn settings(options: Option<&Object>) -> Result<Settings, Object> {
+    let mut settings = Settings { host: "127.0.0.1".to_string(), max_requests: None, bytes: false };
+    let opts = match options {
+        None => return Ok(settings),
+        Some(Object::Object(opts)) => opts,
+        Some(other) => {
+            return Err(Object::error(format!(
+                "HttpServer::listen expects an options object, got {:?}",
+                other
+            )))
+        }
+    };
+    match opts.get("host") {
+        None => {}
+        Some(Object::String(host)) => settings.host = host.clone(),
+        Some(_) => return Err(Object::error("HttpServer::listen option host must be a string")),
+    }
+    match opts.get("maxRequests") {
+        None => {}
+        Some(Object::Integer(n)) if *n > 0 => settings.max_requests = Some(*n as usize),
+        Some(_) => {
+            return Err(Object::error(
+                "HttpServer::listen option maxRequests must be a positive integer",
+            ))
+        }
+    }
+    match opts.get("bytes") {
+        None => {}
+        Some(Object::Boolean(b)) => settings.bytes = *b,
+        Some(_) => return Err(Object::error("HttpServer::listen option bytes must be a boolean")),
+    }
+    Ok(settings)
+}
+
+/// HttpServer::listen(port, handler, [options]) -> Result<null>
+/// Serves HTTP/1.1 on `port`, calling `handler(request)` for each request
+/// with `{ method, path, query, headers, body }`. The handler returns
+/// `{ status, headers, body }` (each optional), or a string, bytes or array
+/// as the body alone; a body that is an object or array is sent as JSON. A
+/// handler error becomes a 500 response and the server keeps going.
+/// options: { host: string (default "127.0.0.1"), maxRequests: n, bytes: bool }
+/// Blocks until `maxRequests` requests have been handled, or forever.
+pub(crate) fn http_server_listen(mut args: Vec<Object>, env: EnvRef) -> Object {
+    if args.len() < 2 || args.len() > 3 {
+        return Object::error("HttpServer::listen expects 2 or 3 arguments (port, handler, [options])");
+    }
+    let options = if args.len() == 3 { args.pop() } else { None };
+    let handler = args.pop().unwrap();
+    let port = match args.pop().unwrap() {
+        Object::Integer(port) if (0..=65535).contains(&port) => port as u16,
+        other => {
+            return Object::error(format!(
+                "HttpServer::listen expects a port from 0 to 65535, got {:?}",
+                other
+            ))
+        }
+    };
+    if !handler.is_callable() {
+        return Object::error(format!(
+            "HttpServer::listen expects a function as handler, got {:?}",
+            handler
+        ));
+    }
+    let settings = match settings(options.as_ref()) {
+        Ok(settings) => settings,
+        Err(e) => return e,
+    };
+
+    let listener = match TcpListener::bind((settings.host.as_str(), port))
+        .and_then(|listener| listener.set_nonblocking(true).map(|()| listener))
+    {
+        Ok(listener) => listener,
+        Err(e) => {
+            return Object::ResultErr(Box::new(Object::String(format!(
+                "failed to listen on {}:{}: {}",
+                settings.host, port, e
+            ))))
+        }
+    };
+
+    let mut handled = 0;
+    while settings.max_requests.is_none_or(|max| handled < max) {
+        event_loop::check_signal(&env);
+        let stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                if let Some(abort) = budget::sleep(ACCEPT_POLL) {
+                    return abort;
+                }
+                continue;
+            }
+            Err(e) => {
+                return Object::ResultErr(Box::new(Object::String(format!(
+                    "failed to accept a connection: {}",
+                    e
+                ))))
+            }
+        };
+        // A client that hangs up or sends garbage only loses its own request.
+        if let Err(e) = serve(stream, &handler, &settings, &env) {
+            eprintln!("[HttpServer] {}", e);
+        }
+        handled += 1;
+    }
+    Object::ResultOk(Box::new(Object::Null))
+}
+
+/// Read one request from `stream`, run the handler and write its response.
+fn serve(stream: TcpStream, handler: &Object, settings: &Settings, env: &EnvRef) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    let response = match read_request(&mut BufReader::new(stream), settings.bytes)? {
+        Ok(request) => {
+            let result = apply_function_with_this(handler.clone(), vec![request], None, Rc::clone(env));
+            match result {
+                Object::Error(msg) => {
+                    eprintln!("[HttpServer] handler failed: {}", msg);
+                    Response::text(500, "Internal Server Error")
+                }
+                value => Response::from_object(value)
+                    .unwrap_or_else(|msg| {
+                        eprintln!("[HttpServer] {}", msg);
+                        Response::text(500, "Internal Server Error")
+                    }),
+            }
+        }
+        Err(response) => response,
+    };
+    response.write_to(&mut writer)
+}
+
+/// Parse a request into the object handed to the handler, or the error
+/// response to send when it can't be served.
+fn read_request(reader: &mut impl BufRead, bytes: bool) -> io::Result<Result<Object, Response>> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target), Some(_version)) = (parts.next(), parts.next(), parts.next()) else {
+        return Ok(Err(Response::text(400, "Bad Request")));
+    };
+    let (method, target) = (method.to_string(), target.to_string());
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(Err(Response::text(400, "Bad Request")));
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            return Ok(Err(Response::text(400, "Bad Request")));
+        };
+        headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+    }
+
+    if headers.contains_key("transfer-encoding") {
+        return Ok(Err(Response::text(411, "Length Required")));
+    }
+    let length = match headers.get("content-length").map(|len| len.parse::<usize>()) {
+        None => 0,
+        Some(Ok(len)) if len <= MAX_BODY => len,
+        Some(Ok(_)) => return Ok(Err(Response::text(413, "Payload Too Large"))),
+        Some(Err(_)) => return Ok(Err(Response::text(400, "Bad Request"))),
+    };
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    let body = if bytes {
+        Object::bytes(body)
+    } else {
+        match String::from_utf8(body) {
+            Ok(text) => Object::String(text),
+            Err(_) => return Ok(Err(Response::text(400, "Request body is not valid UTF-8"))),
+        }
+    };
+
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+    let mut request = ObjectMap::new();
+    request.insert("method".to_string(), Object::String(method.clone()));
+    request.insert("path".to_string(), Object::String(path.to_string()));
+    request.insert("query".to_string(), parse_query(query));
+    request.insert(
+        "headers".to_string(),
+        Object::from_map(headers.into_iter().map(|(k, v)| (k, Object::String(v))).collect()),
+    );
+    request.insert("body".to_string(), body);
+    Ok(Ok(Object::from_map(request)))
+}
+
+/// `a=1&b=x+y&a=2` -> `{ a: ["1", "2"], b: "x y" }`: values are decoded
+/// strings, and a repeated key collects its values into an array.
+fn parse_query(query: &str) -> Object {
+    let mut params: ObjectMap = ObjectMap::new();
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let (key, value) = (form_decode(key), Object::String(form_decode(value)));
+        match params.shift_remove(&key) {
+            None => params.insert(key, value),
+            Some(Object::Array(items)) => {
+                let mut items = items.to_vec();
+                items.push(value);
+                params.insert(key, Object::array(items))
+            }
+            Some(first) => params.insert(key, Object::array(vec![first, value])),
+        };
+    }
+    Object::from_map(params)
+}
+
+/// Decode `+` as a space and `%XX` escapes; malformed escapes are kept as-is.
+fn form_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' => {
+                let hex = bytes.get(i + 1..i + 3).and_then(|hex| std::str::from_utf8(hex).ok());
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(b) => {
+                        out.push(b);
+                        i += 2;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+struct Response {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn text(status: u16, body: &str) -> Response {
+        Response {
+            status,
+            headers: vec![("Content-Type".to_string(), "text/plain; charset=utf-8".to_string())],
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    /// Build a response from the handler's return value.
+    fn from_object(value: Object) -> Result<Response, String> {
+        let Object::Object(map) = &value else {
+            return Self::with_body(200, Vec::new(), value);
+        };
+        let status = match map.get("status") {
+            None => 200,
+            Some(Object::Integer(status)) if (100..=999).contains(status) => *status as u16,
+            Some(other) => return Err(format!("handler returned an invalid status {}", other)),
+        };
+        let mut headers = Vec::new();
+        match map.get("headers") {
+            None => {}
+            Some(Object::Object(fields)) => {
+                for (name, value) in fields.iter() {
+                    match value {
+                        Object::String(value) => headers.push((name.clone(), value.clone())),
+                        other => {
+                            return Err(format!(
+                                "handler returned a non-string value for header '{}': {}",
+                                name, other
+                            ))
+                        }
+                    }
+                }
+            }
+            Some(other) => return Err(format!("handler returned headers that aren't an object: {}", other)),
+        }
+        Self::with_body(status, headers, map.get("body").cloned().unwrap_or(Object::Null))
+    }
+
+    fn with_body(status: u16, mut headers: Vec<(String, String)>, body: Object) -> Result<Response, String> {
+        let (body, content_type) = match body {
+            Object::Null => (Vec::new(), None),
+            Object::String(text) => (text.into_bytes(), Some("text/plain; charset=utf-8")),
+            Object::Bytes(data) => (data.to_vec(), Some("application/octet-stream")),
+            value @ (Object::Object(_) | Object::Array(_) | Object::Integer(_) | Object::Float(_) | Object::Boolean(_)) => {
+                let json = from_slang::<serde_json::Value>(&value)
+                    .map_err(|e| format!("handler returned a body that can't be sent as JSON: {}", e))?;
+                (json.to_string().into_bytes(), Some("application/json"))
+            }
+            other => return Err(format!("handler returned a body that can't be sent: {}", other)),
+        };
+        let has_content_type = headers.iter().any(|(name, _)| name.eq_ignore_ascii_case("content-type"));
+        if let (Some(content_type), false) = (content_type, has_content_type) {
+            headers.push(("Content-Type".to_string(), content_type.to_string()));
+        }
+        Ok(Response { status, headers, body })
+    }
+
+    fn write_to(&self, out: &mut impl Write) -> io::Result<()> {
+        let mut head = format!("HTTP/1.1 {} {}\r\n", self.status, reason(self.status));
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        head.push_str(&format!("Content-Length: {}\r\nConnection: close\r\n\r\n", self.body.len()));
+        out.write_all(head.as_bytes())?;
+        out.write_all(&self.body)?;
+        out.flush()
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        204 => "No Content",
+        301 => "Moved Permanently",
+        302 => "Found",
+        304 => "Not Modified",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        500 => "Internal Server Error",
+        _ => "",
+    }
+}
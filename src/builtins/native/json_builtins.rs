@@ -1,39 +1,9 @@
 use crate::env::EnvRef;
 use crate::object::Object;
-
-/// Convert a serde_json::Value into a Slang Object.
-fn from_json_value(v: &serde_json::Value) -> Object {
-    use serde_json::Value;
-
-    match v {
-        Value::Null => Object::Null,
-        Value::Bool(b) => Object::Boolean(*b),
-        Value::Number(n) => {
-            if let Some(i) = n.as_i64() {
-                Object::Integer(i)
-            } else if let Some(f) = n.as_f64() {
-                Object::Float(f)
-            } else {
-                Object::Null
-            }
-        }
-        Value::String(s) => Object::String(s.clone()),
-        Value::Array(arr) => {
-            let elements = arr.iter().map(from_json_value).collect();
-            Object::Array(elements)
-        }
-        Value::Object(map) => {
-            let mut out = std::collections::HashMap::new();
-            for (k, v) in map {
-                out.insert(k.clone(), from_json_value(v));
-            }
-            Object::Object(out)
-        }
-    }
-}
+use crate::object::convert::json_to_object;
 
 /// Convert a Slang Object into a serde_json::Value.
-fn to_json_value(obj: &Object) -> serde_json::Value {
+pub(crate) fn to_json_value(obj: &Object) -> serde_json::Value {
     use serde_json::Value;
 
     match obj {
@@ -51,7 +21,7 @@ fn to_json_value(obj: &Object) -> serde_json::Value {
         }
         Object::Object(map) => {
             let mut out = serde_json::Map::new();
-            for (k, v) in map {
+            for (k, v) in map.iter() {
                 out.insert(k.clone(), to_json_value(v));
             }
             Value::Object(out)
@@ -78,7 +48,7 @@ pub(crate) fn json_parse(args: Vec<Object>, _env: EnvRef) -> Object {
     };
 
     match serde_json::from_str::<serde_json::Value>(s) {
-        Ok(v) => Object::ResultOk(Box::new(from_json_value(&v))),
+        Ok(v) => Object::ResultOk(Box::new(json_to_object(&v))),
         Err(e) => Object::ResultErr(Box::new(Object::String(e.to_string()))),
     }
 }
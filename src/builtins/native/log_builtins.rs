@@ -0,0 +1,190 @@
+use std::cell::Cell;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::builtins::native::json_builtins::to_json_value;
+use crate::env::EnvRef;
+use crate::object::Object;
+
+/// Severity of a log record; records below the current level are dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Level {
+    Debug,
+    Info,
+    Warn,
+    Error,
+    /// Only usable as a threshold: silences every record.
+    Off,
+}
+
+impl Level {
+    fn parse(name: &str) -> Option<Level> {
+        match name.to_ascii_lowercase().as_str() {
+            "debug" => Some(Level::Debug),
+            "info" => Some(Level::Info),
+            "warn" | "warning" => Some(Level::Warn),
+            "error" => Some(Level::Error),
+            "off" => Some(Level::Off),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Level::Debug => "debug",
+            Level::Info => "info",
+            Level::Warn => "warn",
+            Level::Error => "error",
+            Level::Off => "off",
+        }
+    }
+}
+
+thread_local! {
+    /// Set by `Log::setLevel`; until then `SLANG_LOG` decides.
+    static LEVEL: Cell<Option<Level>> = const { Cell::new(None) };
+    /// Set by `Log::setFormat`; until then `SLANG_LOG_FORMAT` decides.
+    static JSON: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+/// The threshold: `Log::setLevel`, else `$SLANG_LOG`, else info.
+fn current_level() -> Level {
+    LEVEL.with(|level| level.get()).unwrap_or_else(|| {
+        std::env::var("SLANG_LOG")
+            .ok()
+            .and_then(|name| Level::parse(&name))
+            .unwrap_or(Level::Info)
+    })
+}
+
+fn json_output() -> bool {
+    JSON.with(|json| json.get()).unwrap_or_else(|| {
+        std::env::var("SLANG_LOG_FORMAT").is_ok_and(|format| format.eq_ignore_ascii_case("json"))
+    })
+}
+
+/// One record as a line: `LEVEL message key=value ...`, or a JSON object
+/// with `ts` (Unix milliseconds), `level`, `msg` and the fields.
+fn render(level: Level, message: &Object, fields: Option<&Object>) -> String {
+    let message = match message {
+        Object::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    let mut fields: Vec<(&String, &Object)> = match fields {
+        Some(Object::Object(map)) => map.iter().collect(),
+        _ => Vec::new(),
+    };
+    fields.sort_by(|a, b| a.0.cmp(b.0));
+
+    if json_output() {
+        let ts = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let mut record = serde_json::json!({
+            "ts": ts,
+            "level": level.name(),
+            "msg": message,
+        });
+        for (key, value) in fields {
+            record[key.as_str()] = to_json_value(value);
+        }
+        return record.to_string();
+    }
+
+    let mut line = format!("{:<5} {}", level.name().to_ascii_uppercase(), message);
+    for (key, value) in fields {
+        line.push_str(&format!(" {}={}", key, value));
+    }
+    line
+}
+
+/// Shared shape of `Log::debug/info/warn/error(msg, [fields])`: records go
+/// to stderr so they never mix with a script's output.
+fn log_at(level: Level, args: Vec<Object>, name: &str) -> Object {
+    let (message, fields) = match args.as_slice() {
+        [message] => (message, None),
+        [message, fields @ Object::Object(_)] => (message, Some(fields)),
+        [_, other] => {
+            return Object::error(format!("{} expects fields to be an object, got {:?}", name, other))
+        }
+        _ => return Object::error(format!("{} expects 1 or 2 arguments (message, [fields])", name)),
+    };
+    if level >= current_level() {
+        eprintln!("{}", render(level, message, fields));
+    }
+    Object::Null
+}
+
+/// Log::debug(msg, [fields])
+pub(crate) fn log_debug(args: Vec<Object>, _env: EnvRef) -> Object {
+    log_at(Level::Debug, args, "Log::debug")
+}
+
+/// Log::info(msg, [fields])
+pub(crate) fn log_info(args: Vec<Object>, _env: EnvRef) -> Object {
+    log_at(Level::Info, args, "Log::info")
+}
+
+/// Log::warn(msg, [fields])
+pub(crate) fn log_warn(args: Vec<Object>, _env: EnvRef) -> Object {
+    log_at(Level::Warn, args, "Log::warn")
+}
+
+/// Log::error(msg, [fields])
+pub(crate) fn log_error(args: Vec<Object>, _env: EnvRef) -> Object {
+    log_at(Level::Error, args, "Log::error")
+}
+
+/// Log::setLevel(level) – one of "debug", "info", "warn", "error", "off".
+/// Overrides `SLANG_LOG` for the rest of the run.
+pub(crate) fn log_set_level(args: Vec<Object>, _env: EnvRef) -> Object {
+    match args.as_slice() {
+        [Object::String(name)] => match Level::parse(name) {
+            Some(level) => {
+                LEVEL.with(|current| current.set(Some(level)));
+                Object::Null
+            }
+            None => Object::error(format!(
+                "Log::setLevel: unknown level '{}' (expected debug, info, warn, error or off)",
+                name
+            )),
+        },
+        _ => Object::error("Log::setLevel expects 1 argument (level name)"),
+    }
+}
+
+/// Log::level() -> string
+pub(crate) fn log_level(args: Vec<Object>, _env: EnvRef) -> Object {
+    if !args.is_empty() {
+        return Object::error("Log::level expects no arguments");
+    }
+    Object::String(current_level().name().to_string())
+}
+
+/// Log::enabled(level) -> bool
+/// Whether a record at `level` would be written, to skip building costly
+/// messages that would be dropped anyway.
+pub(crate) fn log_enabled(args: Vec<Object>, _env: EnvRef) -> Object {
+    match args.as_slice() {
+        [Object::String(name)] => match Level::parse(name) {
+            Some(Level::Off) | None => Object::error(format!("Log::enabled: unknown level '{}'", name)),
+            Some(level) => Object::Boolean(level >= current_level()),
+        },
+        _ => Object::error("Log::enabled expects 1 argument (level name)"),
+    }
+}
+
+/// Log::setFormat(format) – "text" (the default) or "json" for one JSON
+/// object per line. Overrides `SLANG_LOG_FORMAT`.
+pub(crate) fn log_set_format(args: Vec<Object>, _env: EnvRef) -> Object {
+    let json = match args.as_slice() {
+        [Object::String(format)] if format.eq_ignore_ascii_case("json") => true,
+        [Object::String(format)] if format.eq_ignore_ascii_case("text") => false,
+        [Object::String(format)] => {
+            return Object::error(format!("Log::setFormat: unknown format '{}' (expected text or json)", format))
+        }
+        _ => return Object::error("Log::setFormat expects 1 argument (\"text\" or \"json\")"),
+    };
+    JSON.with(|current| current.set(Some(json)));
+    Object::Null
+}
@@ -35,51 +35,176 @@ pub(crate) fn math_abs(args: Vec<Object>, _env: EnvRef) -> Object {
     }
 }
 
-pub(crate) fn math_floor(args: Vec<Object>, _env: EnvRef) -> Object {
-    let x = match expect_one_arg(args, "Math::floor") {
+/// Shared shape of the integer rounding functions: integers pass through,
+/// floats are rounded with `round` and converted. NaN, infinities and floats
+/// outside the i64 range are errors rather than silently saturating.
+fn round_to_int(args: Vec<Object>, name: &str, round: fn(f64) -> f64) -> Object {
+    let x = match expect_one_arg(args, name) {
         Ok(v) => v,
         Err(e) => return e,
     };
 
     match x {
         Object::Integer(i) => Object::Integer(i),
-        Object::Float(f) => Object::Integer(f.floor() as i64),
+        Object::Float(f) => match float_to_int(round(f)) {
+            Some(i) => Object::Integer(i),
+            None => Object::error(format!("{name}: {} does not fit in an integer", f)),
+        },
         other => Object::error(format!(
-            "Math::floor expects integer or float, got {:?}",
+            "{name} expects integer or float, got {:?}",
             other
         )),
     }
 }
 
+/// A whole float as an i64, or `None` when it's non-finite or out of range.
+pub(crate) fn float_to_int(f: f64) -> Option<i64> {
+    // i64::MAX as f64 rounds up to 2^63, which is itself out of range.
+    if f.is_finite() && f >= i64::MIN as f64 && f < i64::MAX as f64 {
+        Some(f as i64)
+    } else {
+        None
+    }
+}
+
+/// Math::floor(x) -> integer (rounds toward negative infinity)
+pub(crate) fn math_floor(args: Vec<Object>, _env: EnvRef) -> Object {
+    round_to_int(args, "Math::floor", f64::floor)
+}
+
+/// Math::ceil(x) -> integer (rounds toward positive infinity)
 pub(crate) fn math_ceil(args: Vec<Object>, _env: EnvRef) -> Object {
-    let x = match expect_one_arg(args, "Math::ceil") {
+    round_to_int(args, "Math::ceil", f64::ceil)
+}
+
+/// Math::round(x) -> integer (halfway cases round away from zero)
+pub(crate) fn math_round(args: Vec<Object>, _env: EnvRef) -> Object {
+    round_to_int(args, "Math::round", f64::round)
+}
+
+/// Math::trunc(x) -> integer (drops the fractional part, rounding toward zero)
+pub(crate) fn math_trunc(args: Vec<Object>, _env: EnvRef) -> Object {
+    round_to_int(args, "Math::trunc", f64::trunc)
+}
+
+/// Math::roundTo(x, digits) -> x rounded to `digits` decimal places
+/// Halfway cases round away from zero. Negative `digits` round to tens,
+/// hundreds and so on. Integers stay integers and floats stay floats.
+pub(crate) fn math_round_to(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (x, digits) = match expect_two_args(args, "Math::roundTo") {
         Ok(v) => v,
         Err(e) => return e,
     };
+    let digits = match digits {
+        Object::Integer(d) => d.clamp(-308, 308) as i32,
+        other => {
+            return Object::error(format!(
+                "Math::roundTo expects integer digits, got {:?}",
+                other
+            ))
+        }
+    };
 
     match x {
-        Object::Integer(i) => Object::Integer(i),
-        Object::Float(f) => Object::Integer(f.ceil() as i64),
+        Object::Integer(i) if digits >= 0 => Object::Integer(i),
+        Object::Integer(i) => {
+            let unit = 10_i64.checked_pow(digits.unsigned_abs());
+            match unit {
+                Some(unit) => {
+                    let half = unit / 2;
+                    let rounded = if i >= 0 { i.checked_add(half) } else { i.checked_sub(half) }
+                        .map(|shifted| shifted / unit * unit);
+                    match rounded {
+                        Some(r) => Object::Integer(r),
+                        None => Object::error("Math::roundTo: result does not fit in an integer"),
+                    }
+                }
+                None => Object::Integer(0),
+            }
+        }
+        Object::Float(f) => {
+            let scale = 10_f64.powi(digits);
+            let rounded = (f * scale).round() / scale;
+            // Very large scales overflow to infinity; x is already that precise.
+            Object::Float(if rounded.is_finite() { rounded } else { f })
+        }
         other => Object::error(format!(
-            "Math::ceil expects integer or float, got {:?}",
+            "Math::roundTo expects numeric first argument, got {:?}",
             other
         )),
     }
 }
 
-pub(crate) fn math_round(args: Vec<Object>, _env: EnvRef) -> Object {
-    let x = match expect_one_arg(args, "Math::round") {
+/// Floor division and its remainder, shared by `floorDiv` and `divmod`.
+/// The quotient rounds toward negative infinity, so the remainder has the
+/// sign of the divisor: `-7 floorDiv 2` is -4 with remainder 1.
+fn floor_div_mod(a: Object, b: Object, name: &str) -> Result<(Object, Object), Object> {
+    match (a, b) {
+        (Object::Integer(_), Object::Integer(0)) => {
+            Err(Object::error(format!("{name}: division by zero")))
+        }
+        (Object::Integer(a), Object::Integer(b)) => {
+            let (q, r) = match (a.checked_div(b), a.checked_rem(b)) {
+                (Some(q), Some(r)) => (q, r),
+                _ => return Err(Object::error(format!("{name}: result does not fit in an integer"))),
+            };
+            if r != 0 && (r < 0) != (b < 0) {
+                Ok((Object::Integer(q - 1), Object::Integer(r + b)))
+            } else {
+                Ok((Object::Integer(q), Object::Integer(r)))
+            }
+        }
+        (a, b) => {
+            let (a, b) = match (as_number(&a), as_number(&b)) {
+                (Some(a), Some(b)) => (a, b),
+                _ => {
+                    return Err(Object::error(format!(
+                        "{name} expects numeric arguments, got {:?} and {:?}",
+                        a, b
+                    )))
+                }
+            };
+            if b == 0.0 {
+                return Err(Object::error(format!("{name}: division by zero")));
+            }
+            let q = (a / b).floor();
+            Ok((Object::Float(q), Object::Float(a - q * b)))
+        }
+    }
+}
+
+fn as_number(o: &Object) -> Option<f64> {
+    match o {
+        Object::Integer(i) => Some(*i as f64),
+        Object::Float(f) => Some(*f),
+        _ => None,
+    }
+}
+
+/// Math::floorDiv(a, b) -> a / b rounded toward negative infinity
+/// The function form of `a div b`: `-7 / 2` is -3.5 while
+/// `Math::floorDiv(-7, 2)` is -4. Integer arguments give an integer;
+/// otherwise a whole float.
+pub(crate) fn math_floor_div(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (a, b) = match expect_two_args(args, "Math::floorDiv") {
         Ok(v) => v,
         Err(e) => return e,
     };
+    match floor_div_mod(a, b, "Math::floorDiv") {
+        Ok((q, _)) => q,
+        Err(e) => e,
+    }
+}
 
-    match x {
-        Object::Integer(i) => Object::Integer(i),
-        Object::Float(f) => Object::Integer(f.round() as i64),
-        other => Object::error(format!(
-            "Math::round expects integer or float, got {:?}",
-            other
-        )),
+/// Math::divmod(a, b) -> [q, r] with q = Math::floorDiv(a, b) and a == q * b + r
+pub(crate) fn math_divmod(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (a, b) = match expect_two_args(args, "Math::divmod") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    match floor_div_mod(a, b, "Math::divmod") {
+        Ok((q, r)) => Object::array(vec![q, r]),
+        Err(e) => e,
     }
 }
 
@@ -263,32 +388,6 @@ pub(crate) fn math_tanh(args: Vec<Object>, _env: EnvRef) -> Object {
     unary_f64(args, "Math::tanh", f64::tanh)
 }
 
-// Mathematical constants
-
-/// Math::PI() -> π ≈ 3.14159...
-pub(crate) fn math_pi(args: Vec<Object>, _env: EnvRef) -> Object {
-    if !args.is_empty() {
-        return Object::error("Math::PI expects no arguments");
-    }
-    Object::Float(std::f64::consts::PI)
-}
-
-/// Math::E() -> e ≈ 2.71828...
-pub(crate) fn math_e(args: Vec<Object>, _env: EnvRef) -> Object {
-    if !args.is_empty() {
-        return Object::error("Math::E expects no arguments");
-    }
-    Object::Float(std::f64::consts::E)
-}
-
-/// Math::TAU() -> τ = 2π ≈ 6.28318...
-pub(crate) fn math_tau(args: Vec<Object>, _env: EnvRef) -> Object {
-    if !args.is_empty() {
-        return Object::error("Math::TAU expects no arguments");
-    }
-    Object::Float(std::f64::consts::TAU)
-}
-
 // Utility functions
 
 /// Math::sign(x) -> -1, 0, or 1
@@ -465,3 +564,33 @@ pub(crate) fn math_random_int(args: Vec<Object>, _env: EnvRef) -> Object {
     Object::Integer(result)
 }
 
+
+/// Longest string `Math::randomHex` makes in one call.
+const MAX_RANDOM_HEX: i64 = 1 << 20;
+
+/// Math::randomHex(n) -> string of n random lowercase hex digits
+/// Drawn from the operating system's secure generator, so unlike
+/// `Math::random` it's fit for tokens and identifiers.
+pub(crate) fn math_random_hex(args: Vec<Object>, _env: EnvRef) -> Object {
+    let n = match expect_one_arg(args, "Math::randomHex") {
+        Ok(Object::Integer(n)) if (0..=MAX_RANDOM_HEX).contains(&n) => n as usize,
+        Ok(Object::Integer(n)) if n > MAX_RANDOM_HEX => {
+            return Object::error(format!("Math::randomHex length must be at most {}", MAX_RANDOM_HEX))
+        }
+        Ok(Object::Integer(_)) => return Object::error("Math::randomHex length must be non-negative"),
+        Ok(other) => {
+            return Object::error(format!(
+                "Math::randomHex expects integer length, got {:?}",
+                other
+            ))
+        }
+        Err(e) => return e,
+    };
+    let mut bytes = vec![0u8; n.div_ceil(2)];
+    if let Err(e) = getrandom::getrandom(&mut bytes) {
+        return Object::error(format!("Math::randomHex: {}", e));
+    }
+    let mut hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    hex.truncate(n);
+    Object::String(hex)
+}
@@ -14,6 +14,13 @@ use crate::object::Object;
 //   Option::isNone(opt)
 //   Option::unwrapOr(opt, default)
 //
+//   Option::unwrap(opt) / Option::expect(opt, "msg")
+//   Option::okOr(opt, err)
+//   Option::filter(opt, pred)
+//   Option::zip(a, b)
+//   Option::flatten(opt)
+//   Option::toArray(opt)
+//
 //   Option::map(opt, f)
 //   Option::andThen(opt, f) / Option::bind(opt, f)
 //
@@ -22,7 +29,10 @@ use crate::object::Object;
 //   Result::isOk(res)
 //   Result::isErr(res)
 //   Result::unwrapOr(res, default)
+//   Result::unwrap(res) / Result::expect(res, "msg")
+//   Result::ok(res)
 //   Result::map(res, f)
+//   Result::mapErr(res, f)
 //   Result::andThen(res, f) / Result::bind(res, f)
 //
 
@@ -275,3 +285,252 @@ pub(crate) fn result_fmap(args: Vec<Object>, env: EnvRef) -> Object {
 
 
 
+
+/// How an Err payload reads in an error message: strings without quotes.
+fn describe_err(err: &Object) -> String {
+    match err {
+        Object::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Option::unwrap(opt) – the value inside Some; an error for None.
+pub(crate) fn option_unwrap(args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.len() != 1 {
+        return Object::error("Option::unwrap expects exactly 1 argument (an Option)");
+    }
+
+    match args.into_iter().next().unwrap() {
+        Object::OptionSome(inner) => *inner,
+        Object::OptionNone => Object::error("Option::unwrap called on None"),
+        other => Object::error(format!(
+            "Option::unwrap expects an Option value, got {:?}",
+            other
+        )),
+    }
+}
+
+/// Option::expect(opt, msg) – the value inside Some; an error reading `msg`
+/// for None.
+pub(crate) fn option_expect(mut args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.len() != 2 {
+        return Object::error("Option::expect expects exactly 2 arguments (option, message)");
+    }
+
+    let message = match args.pop().unwrap() {
+        Object::String(s) => s,
+        other => {
+            return Object::error(format!(
+                "Option::expect expects a string message, got {:?}",
+                other
+            ))
+        }
+    };
+
+    match args.pop().unwrap() {
+        Object::OptionSome(inner) => *inner,
+        Object::OptionNone => Object::error(message),
+        other => Object::error(format!(
+            "Option::expect expects an Option value as first argument, got {:?}",
+            other
+        )),
+    }
+}
+
+/// Option::okOr(opt, err) – Some(v) becomes Ok(v), None becomes Err(err).
+pub(crate) fn option_ok_or(mut args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.len() != 2 {
+        return Object::error("Option::okOr expects exactly 2 arguments (option, err)");
+    }
+
+    let err = args.pop().unwrap();
+    let opt = args.pop().unwrap();
+
+    match opt {
+        Object::OptionSome(inner) => Object::ResultOk(inner),
+        Object::OptionNone => Object::ResultErr(Box::new(err)),
+        other => Object::error(format!(
+            "Option::okOr expects an Option value as first argument, got {:?}",
+            other
+        )),
+    }
+}
+
+/// Option::filter(opt, pred) – Some(v) if pred(v) is true, otherwise None.
+pub(crate) fn option_filter(mut args: Vec<Object>, env: EnvRef) -> Object {
+    if args.len() != 2 {
+        return Object::error("Option::filter expects exactly 2 arguments (option, fn)");
+    }
+
+    let func = args.pop().unwrap();
+    let opt = args.pop().unwrap();
+
+    match opt {
+        Object::OptionSome(inner) => {
+            match apply_function_with_this(func, vec![(*inner).clone()], None, env) {
+                Object::Boolean(true) => Object::OptionSome(inner),
+                Object::Boolean(false) => Object::OptionNone,
+                err @ Object::Error(_) => err,
+                other => Object::error(format!(
+                    "Option::filter predicate must return boolean, got {:?}",
+                    other
+                )),
+            }
+        }
+        Object::OptionNone => Object::OptionNone,
+        other => Object::error(format!(
+            "Option::filter expects an Option value as first argument, got {:?}",
+            other
+        )),
+    }
+}
+
+/// Option::zip(a, b) – Some([x, y]) when both are Some, otherwise None.
+pub(crate) fn option_zip(mut args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.len() != 2 {
+        return Object::error("Option::zip expects exactly 2 arguments (option, option)");
+    }
+
+    let second = args.pop().unwrap();
+    let first = args.pop().unwrap();
+
+    match (first, second) {
+        (Object::OptionSome(a), Object::OptionSome(b)) => {
+            Object::OptionSome(Box::new(Object::array(vec![*a, *b])))
+        }
+        (Object::OptionSome(_) | Object::OptionNone, Object::OptionSome(_) | Object::OptionNone) => {
+            Object::OptionNone
+        }
+        (a, b) => Object::error(format!(
+            "Option::zip expects two Option values, got {:?} and {:?}",
+            a, b
+        )),
+    }
+}
+
+/// Option::flatten(opt) – Some(Some(v)) becomes Some(v); Some(None) and None
+/// become None.
+pub(crate) fn option_flatten(args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.len() != 1 {
+        return Object::error("Option::flatten expects exactly 1 argument (an Option)");
+    }
+
+    match args.into_iter().next().unwrap() {
+        Object::OptionSome(inner) => match *inner {
+            inner @ (Object::OptionSome(_) | Object::OptionNone) => inner,
+            other => Object::error(format!(
+                "Option::flatten expects an Option holding an Option, got Some({:?})",
+                other
+            )),
+        },
+        Object::OptionNone => Object::OptionNone,
+        other => Object::error(format!(
+            "Option::flatten expects an Option value, got {:?}",
+            other
+        )),
+    }
+}
+
+/// Option::toArray(opt) – [v] for Some(v), [] for None.
+pub(crate) fn option_to_array(args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.len() != 1 {
+        return Object::error("Option::toArray expects exactly 1 argument (an Option)");
+    }
+
+    match args.into_iter().next().unwrap() {
+        Object::OptionSome(inner) => Object::array(vec![*inner]),
+        Object::OptionNone => Object::array(Vec::new()),
+        other => Object::error(format!(
+            "Option::toArray expects an Option value, got {:?}",
+            other
+        )),
+    }
+}
+
+/// Result::unwrap(res) – the value inside Ok; an error for Err(e).
+pub(crate) fn result_unwrap(args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.len() != 1 {
+        return Object::error("Result::unwrap expects exactly 1 argument (a Result)");
+    }
+
+    match args.into_iter().next().unwrap() {
+        Object::ResultOk(inner) => *inner,
+        Object::ResultErr(err) => Object::error(format!(
+            "Result::unwrap called on Err: {}",
+            describe_err(&err)
+        )),
+        other => Object::error(format!(
+            "Result::unwrap expects a Result value, got {:?}",
+            other
+        )),
+    }
+}
+
+/// Result::expect(res, msg) – the value inside Ok; for Err(e), an error
+/// reading `msg: e`.
+pub(crate) fn result_expect(mut args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.len() != 2 {
+        return Object::error("Result::expect expects exactly 2 arguments (result, message)");
+    }
+
+    let message = match args.pop().unwrap() {
+        Object::String(s) => s,
+        other => {
+            return Object::error(format!(
+                "Result::expect expects a string message, got {:?}",
+                other
+            ))
+        }
+    };
+
+    match args.pop().unwrap() {
+        Object::ResultOk(inner) => *inner,
+        Object::ResultErr(err) => Object::error(format!("{}: {}", message, describe_err(&err))),
+        other => Object::error(format!(
+            "Result::expect expects a Result value as first argument, got {:?}",
+            other
+        )),
+    }
+}
+
+/// Result::ok(res) – Ok(v) becomes Some(v), Err(_) becomes None.
+pub(crate) fn result_to_option(args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.len() != 1 {
+        return Object::error("Result::ok expects exactly 1 argument (a Result)");
+    }
+
+    match args.into_iter().next().unwrap() {
+        Object::ResultOk(inner) => Object::OptionSome(inner),
+        Object::ResultErr(_) => Object::OptionNone,
+        other => Object::error(format!(
+            "Result::ok expects a Result value, got {:?}",
+            other
+        )),
+    }
+}
+
+/// Result::mapErr(res, f) – if Err(e), returns Err(f(e)); if Ok(v), returns Ok(v).
+pub(crate) fn result_map_err(mut args: Vec<Object>, env: EnvRef) -> Object {
+    if args.len() != 2 {
+        return Object::error("Result::mapErr expects exactly 2 arguments (result, fn)");
+    }
+
+    let func = args.pop().unwrap();
+    let res = args.pop().unwrap();
+
+    match res {
+        Object::ResultOk(inner) => Object::ResultOk(inner),
+        Object::ResultErr(err) => {
+            let result = apply_function_with_this(func, vec![*err], None, env);
+            if result.is_error() {
+                result
+            } else {
+                Object::ResultErr(Box::new(result))
+            }
+        }
+        other => Object::error(format!(
+            "Result::mapErr expects a Result value as first argument, got {:?}",
+            other
+        )),
+    }
+}
@@ -0,0 +1,131 @@
+use std::rc::Rc;
+
+use crate::env::EnvRef;
+use crate::object::Object;
+
+/// Separators used when parsing a formatted number.
+struct NumberFormat {
+    thousands: String,
+    decimal: String,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        NumberFormat {
+            thousands: ",".to_string(),
+            decimal: ".".to_string(),
+        }
+    }
+}
+
+fn number_format_from_options(options: Object) -> Result<NumberFormat, Object> {
+    let map = match options {
+        Object::Object(map) => map,
+        other => {
+            return Err(Object::error(format!(
+                "Number::parse options must be an object, got {:?}",
+                other
+            )))
+        }
+    };
+
+    let mut format = NumberFormat::default();
+    for (key, value) in Rc::unwrap_or_clone(map) {
+        let sep = match value {
+            Object::String(s) => s,
+            other => {
+                return Err(Object::error(format!(
+                    "Number::parse option {} must be a string, got {:?}",
+                    key, other
+                )))
+            }
+        };
+        match key.as_str() {
+            "thousandsSep" => format.thousands = sep,
+            "decimalSep" => format.decimal = sep,
+            other => {
+                return Err(Object::error(format!(
+                    "Number::parse got unknown option {} (expected thousandsSep or decimalSep)",
+                    other
+                )))
+            }
+        }
+    }
+
+    if format.decimal.is_empty() {
+        return Err(Object::error("Number::parse decimalSep must not be empty"));
+    }
+    if format.thousands == format.decimal {
+        return Err(Object::error(
+            "Number::parse thousandsSep and decimalSep must differ",
+        ));
+    }
+    Ok(format)
+}
+
+/// Parse `text` using `format`, returning `None` if it isn't a well-formed
+/// number. Thousands separators are optional but, when used, must split the
+/// integer part into groups of three digits.
+fn parse_formatted_number(text: &str, format: &NumberFormat) -> Option<Object> {
+    let text = text.trim();
+    let (negative, unsigned) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+
+    let (int_part, frac_part) = match unsigned.split_once(format.decimal.as_str()) {
+        Some((int_part, frac_part)) => (int_part, Some(frac_part)),
+        None => (unsigned, None),
+    };
+
+    let groups: Vec<&str> = if format.thousands.is_empty() {
+        vec![int_part]
+    } else {
+        int_part.split(format.thousands.as_str()).collect()
+    };
+    let all_digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    if !groups.iter().all(|g| all_digits(g)) {
+        return None;
+    }
+    if groups.len() > 1 && (groups[0].len() > 3 || groups[1..].iter().any(|g| g.len() != 3)) {
+        return None;
+    }
+    let digits: String = groups.concat();
+    let sign = if negative { "-" } else { "" };
+
+    match frac_part {
+        None => format!("{}{}", sign, digits).parse().ok().map(Object::Integer),
+        Some(frac) if all_digits(frac) => format!("{}{}.{}", sign, digits, frac)
+            .parse()
+            .ok()
+            .map(Object::Float),
+        Some(_) => None,
+    }
+}
+
+/// Number::parse(s[, { thousandsSep, decimalSep }]) -> Option<int | float>
+pub(crate) fn number_parse(mut args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.is_empty() || args.len() > 2 {
+        return Object::error("Number::parse expects 1 or 2 arguments (string[, options])");
+    }
+
+    let format = if args.len() == 2 {
+        match number_format_from_options(args.pop().unwrap()) {
+            Ok(format) => format,
+            Err(e) => return e,
+        }
+    } else {
+        NumberFormat::default()
+    };
+
+    match args.pop().unwrap() {
+        Object::String(s) => match parse_formatted_number(&s, &format) {
+            Some(value) => Object::OptionSome(Box::new(value)),
+            None => Object::OptionNone,
+        },
+        other => Object::error(format!(
+            "Number::parse expects a string as first argument, got {:?}",
+            other
+        )),
+    }
+}
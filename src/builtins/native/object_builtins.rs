@@ -1,7 +1,11 @@
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
+use std::rc::{Rc, Weak};
 
+use crate::builtins::native::ref_builtins::{check_no_cycle, expect_ref};
 use crate::env::EnvRef;
-use crate::object::Object;
+use crate::evaluator::core::expr::apply_function_with_this;
+use crate::object::{Object, ObjectMap};
 
 fn expect_one_arg(mut args: Vec<Object>, name: &str) -> Result<Object, Object> {
     if args.len() != 1 {
@@ -47,7 +51,7 @@ pub(crate) fn object_keys(args: Vec<Object>, _env: EnvRef) -> Object {
                 .keys()
                 .map(|k| Object::String(k.clone()))
                 .collect();
-            Object::Array(keys)
+            Object::array(keys)
         }
         other => Object::error(format!(
             "Object::keys expects an object, got {:?}",
@@ -67,7 +71,7 @@ pub(crate) fn object_values(args: Vec<Object>, _env: EnvRef) -> Object {
     match obj {
         Object::Object(map) => {
             let values: Vec<Object> = map.values().cloned().collect();
-            Object::Array(values)
+            Object::array(values)
         }
         other => Object::error(format!(
             "Object::values expects an object, got {:?}",
@@ -86,11 +90,11 @@ pub(crate) fn object_entries(args: Vec<Object>, _env: EnvRef) -> Object {
 
     match obj {
         Object::Object(map) => {
-            let entries: Vec<Object> = map
+            let entries: Vec<Object> = Rc::unwrap_or_clone(map)
                 .into_iter()
-                .map(|(k, v)| Object::Array(vec![Object::String(k), v]))
+                .map(|(k, v)| Object::array(vec![Object::String(k), v]))
                 .collect();
-            Object::Array(entries)
+            Object::array(entries)
         }
         other => Object::error(format!(
             "Object::entries expects an object, got {:?}",
@@ -109,9 +113,9 @@ pub(crate) fn object_from_entries(args: Vec<Object>, _env: EnvRef) -> Object {
 
     match arr {
         Object::Array(entries) => {
-            let mut map = HashMap::new();
+            let mut map = ObjectMap::new();
 
-            for entry in entries {
+            for entry in Rc::unwrap_or_clone(entries) {
                 match entry {
                     Object::Array(pair) if pair.len() == 2 => {
                         let key = match &pair[0] {
@@ -135,7 +139,7 @@ pub(crate) fn object_from_entries(args: Vec<Object>, _env: EnvRef) -> Object {
                 }
             }
 
-            Object::Object(map)
+            Object::from_map(map)
         }
         other => Object::error(format!(
             "Object::fromEntries expects an array, got {:?}",
@@ -221,7 +225,7 @@ pub(crate) fn object_set(args: Vec<Object>, _env: EnvRef) -> Object {
 
     match obj {
         Object::Object(mut map) => {
-            map.insert(key_str, value);
+            unfrozen_mut(&mut map).insert(key_str, value);
             Object::Object(map)
         }
         other => Object::error(format!(
@@ -231,6 +235,48 @@ pub(crate) fn object_set(args: Vec<Object>, _env: EnvRef) -> Object {
     }
 }
 
+/// Object::setMut(ref, key, value) -> value
+/// Sets the key on the object held by ref in place, so every holder of the
+/// ref sees the change.
+pub(crate) fn object_set_mut(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (target, key, value) = match expect_three_args(args, "Object::setMut") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let cell = match expect_ref(target, "Object::setMut") {
+        Ok(cell) => cell,
+        Err(e) => return e,
+    };
+
+    let key_str = match key {
+        Object::String(s) => s,
+        other => {
+            return Object::error(format!(
+                "Object::setMut expects string key, got {:?}",
+                other
+            ))
+        }
+    };
+    if let Err(e) = check_no_cycle(&cell, &value, "Object::setMut") {
+        return e;
+    }
+
+    let mut held = cell.borrow_mut();
+    match &mut *held {
+        Object::Object(map) => {
+            if is_frozen(map) {
+                return Object::error(format!("Object::setMut: cannot set {} on a frozen object", key_str));
+            }
+            Rc::make_mut(map).insert(key_str, value.clone());
+            value
+        }
+        other => Object::error(format!(
+            "Object::setMut expects a ref holding an object, got ref to {}",
+            other.type_name()
+        )),
+    }
+}
+
 /// Object::delete(obj, key) -> Object
 /// Returns a new object with the key removed (immutable).
 pub(crate) fn object_delete(args: Vec<Object>, _env: EnvRef) -> Object {
@@ -251,7 +297,7 @@ pub(crate) fn object_delete(args: Vec<Object>, _env: EnvRef) -> Object {
 
     match obj {
         Object::Object(mut map) => {
-            map.remove(&key_str);
+            unfrozen_mut(&mut map).shift_remove(&key_str);
             Object::Object(map)
         }
         other => Object::error(format!(
@@ -291,8 +337,9 @@ pub(crate) fn object_merge(args: Vec<Object>, _env: EnvRef) -> Object {
     };
 
     let mut result = map1;
-    for (k, v) in map2 {
-        result.insert(k, v);
+    let fields = unfrozen_mut(&mut result);
+    for (k, v) in map2.iter() {
+        fields.insert(k.clone(), v.clone());
     }
 
     Object::Object(result)
@@ -332,3 +379,358 @@ pub(crate) fn object_len(args: Vec<Object>, _env: EnvRef) -> Object {
     }
 }
 
+
+/// The object passed as the first argument of `name`.
+fn expect_object(obj: Object, name: &str) -> Result<Rc<ObjectMap>, Object> {
+    match obj {
+        Object::Object(map) => Ok(map),
+        other => Err(Object::error(format!(
+            "{} expects an object as first argument, got {:?}",
+            name, other
+        ))),
+    }
+}
+
+/// The array of string keys passed to `name`.
+fn expect_keys(keys: Object, name: &str) -> Result<Vec<String>, Object> {
+    let Object::Array(items) = keys else {
+        return Err(Object::error(format!(
+            "{} expects an array of keys as second argument, got {:?}",
+            name, keys
+        )));
+    };
+    items
+        .iter()
+        .map(|item| match item {
+            Object::String(key) => Ok(key.clone()),
+            other => Err(Object::error(format!("{} expects string keys, got {:?}", name, other))),
+        })
+        .collect()
+}
+
+/// Object::map(obj, f) -> Object
+/// Calls f(key, value) for each entry; f returns the `[key, value]` pair to
+/// put in the new object.
+pub(crate) fn object_map(args: Vec<Object>, env: EnvRef) -> Object {
+    let (obj, func) = match expect_two_args(args, "Object::map") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let map = match expect_object(obj, "Object::map") {
+        Ok(map) => map,
+        Err(e) => return e,
+    };
+
+    let mut out = ObjectMap::with_capacity(map.len());
+    for (key, value) in map.iter() {
+        let pair = apply_function_with_this(
+            func.clone(),
+            vec![Object::String(key.clone()), value.clone()],
+            None,
+            Rc::clone(&env),
+        );
+        match pair {
+            Object::Array(pair) if pair.len() == 2 => match (&pair[0], &pair[1]) {
+                (Object::String(k), v) => {
+                    out.insert(k.clone(), v.clone());
+                }
+                (other, _) => {
+                    return Object::error(format!("Object::map function must return a string key, got {:?}", other))
+                }
+            },
+            err @ Object::Error(_) => return err,
+            other => {
+                return Object::error(format!(
+                    "Object::map function must return a [key, value] pair, got {:?}",
+                    other
+                ))
+            }
+        }
+    }
+    Object::from_map(out)
+}
+
+/// Object::mapValues(obj, f) -> Object
+/// Returns an object with the same keys and f(value) as each value.
+pub(crate) fn object_map_values(args: Vec<Object>, env: EnvRef) -> Object {
+    let (obj, func) = match expect_two_args(args, "Object::mapValues") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let map = match expect_object(obj, "Object::mapValues") {
+        Ok(map) => map,
+        Err(e) => return e,
+    };
+
+    let mut out = ObjectMap::with_capacity(map.len());
+    for (key, value) in map.iter() {
+        let result = apply_function_with_this(func.clone(), vec![value.clone()], None, Rc::clone(&env));
+        if result.is_error() {
+            return result;
+        }
+        out.insert(key.clone(), result);
+    }
+    Object::from_map(out)
+}
+
+/// Object::filter(obj, f) -> Object
+/// Returns an object with the entries for which f(key, value) is true.
+pub(crate) fn object_filter(args: Vec<Object>, env: EnvRef) -> Object {
+    let (obj, func) = match expect_two_args(args, "Object::filter") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let map = match expect_object(obj, "Object::filter") {
+        Ok(map) => map,
+        Err(e) => return e,
+    };
+
+    let mut out = ObjectMap::new();
+    for (key, value) in map.iter() {
+        let keep = apply_function_with_this(
+            func.clone(),
+            vec![Object::String(key.clone()), value.clone()],
+            None,
+            Rc::clone(&env),
+        );
+        match keep {
+            Object::Boolean(true) => {
+                out.insert(key.clone(), value.clone());
+            }
+            Object::Boolean(false) => {}
+            err @ Object::Error(_) => return err,
+            other => {
+                return Object::error(format!(
+                    "Object::filter predicate must return boolean, got {:?}",
+                    other
+                ))
+            }
+        }
+    }
+    Object::from_map(out)
+}
+
+/// Object::pick(obj, keys) -> Object
+/// Returns an object with only the listed keys; keys the object doesn't
+/// have are skipped.
+pub(crate) fn object_pick(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (obj, keys) = match expect_two_args(args, "Object::pick") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let (map, keys) = match (expect_object(obj, "Object::pick"), expect_keys(keys, "Object::pick")) {
+        (Ok(map), Ok(keys)) => (map, keys),
+        (Err(e), _) | (_, Err(e)) => return e,
+    };
+    Object::from_map(
+        map.iter()
+            .filter(|(key, _)| keys.contains(key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect(),
+    )
+}
+
+/// Object::omit(obj, keys) -> Object
+/// Returns an object without the listed keys.
+pub(crate) fn object_omit(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (obj, keys) = match expect_two_args(args, "Object::omit") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let (map, keys) = match (expect_object(obj, "Object::omit"), expect_keys(keys, "Object::omit")) {
+        (Ok(map), Ok(keys)) => (map, keys),
+        (Err(e), _) | (_, Err(e)) => return e,
+    };
+    Object::from_map(
+        map.iter()
+            .filter(|(key, _)| !keys.contains(key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect(),
+    )
+}
+
+fn merge_deep(base: &mut ObjectMap, overrides: &ObjectMap) {
+    for (key, value) in overrides.iter() {
+        match (base.get_mut(key), value) {
+            (Some(Object::Object(existing)), Object::Object(nested)) => {
+                merge_deep(unfrozen_mut(existing), nested);
+            }
+            _ => {
+                base.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Object::mergeDeep(obj1, obj2) -> Object
+/// Like `Object::merge`, but where both objects hold an object under the
+/// same key, those are merged too. Any other value from obj2 (including an
+/// array) replaces the one in obj1.
+pub(crate) fn object_merge_deep(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (obj1, obj2) = match expect_two_args(args, "Object::mergeDeep") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let mut result = match expect_object(obj1, "Object::mergeDeep") {
+        Ok(map) => Rc::unwrap_or_clone(map),
+        Err(e) => return e,
+    };
+    match obj2 {
+        Object::Object(overrides) => merge_deep(&mut result, &overrides),
+        other => {
+            return Object::error(format!(
+                "Object::mergeDeep expects an object as second argument, got {:?}",
+                other
+            ))
+        }
+    }
+    Object::from_map(result)
+}
+
+// ----- Freezing -----
+//
+// Objects and arrays are copy-on-write, so a frozen value is simply one
+// whose shared storage must never be written. `Object::freeze` copies the
+// value into storage of its own, so other bindings of the original stay
+// writable, and remembers those allocations by address; the weak handle
+// keeps the allocation (not its contents) alive, so the address can't be
+// reused by another value while it is in the table.
+
+/// The allocation behind a frozen object or array.
+enum FrozenHandle {
+    Object(Weak<ObjectMap>),
+    Array(Weak<Vec<Object>>),
+}
+
+impl FrozenHandle {
+    fn is_live(&self) -> bool {
+        match self {
+            FrozenHandle::Object(weak) => weak.strong_count() > 0,
+            FrozenHandle::Array(weak) => weak.strong_count() > 0,
+        }
+    }
+}
+
+thread_local! {
+    static FROZEN: RefCell<HashMap<*const (), FrozenHandle>> = RefCell::new(HashMap::new());
+    /// Table size at which entries for dropped values are swept out.
+    static SWEEP_AT: Cell<usize> = const { Cell::new(64) };
+}
+
+fn is_frozen_ptr(ptr: *const ()) -> bool {
+    FROZEN.with(|frozen| frozen.borrow().contains_key(&ptr))
+}
+
+fn mark_frozen(ptr: *const (), handle: FrozenHandle) {
+    FROZEN.with(|frozen| {
+        let mut frozen = frozen.borrow_mut();
+        frozen.insert(ptr, handle);
+        if frozen.len() >= SWEEP_AT.get() {
+            frozen.retain(|_, handle| handle.is_live());
+            SWEEP_AT.set((frozen.len() * 2).max(64));
+        }
+    });
+}
+
+/// Whether `map` was frozen by `Object::freeze`.
+pub(crate) fn is_frozen(map: &Rc<ObjectMap>) -> bool {
+    is_frozen_ptr(Rc::as_ptr(map).cast())
+}
+
+/// Whether `items` was frozen by `Object::freeze`.
+pub(crate) fn is_frozen_array(items: &Rc<Vec<Object>>) -> bool {
+    is_frozen_ptr(Rc::as_ptr(items).cast())
+}
+
+/// The fields of `map` for writing. Like `Rc::make_mut`, except that a
+/// frozen map is always copied, so functional updates never write to (or
+/// hand back) a frozen allocation.
+pub(crate) fn unfrozen_mut(map: &mut Rc<ObjectMap>) -> &mut ObjectMap {
+    if is_frozen(map) {
+        *map = Rc::new(ObjectMap::clone(map));
+    }
+    Rc::make_mut(map)
+}
+
+/// The elements of `items` for writing; the array counterpart of
+/// [`unfrozen_mut`].
+pub(crate) fn unfrozen_array_mut(items: &mut Rc<Vec<Object>>) -> &mut Vec<Object> {
+    if is_frozen_array(items) {
+        *items = Rc::new(Vec::clone(items));
+    }
+    Rc::make_mut(items)
+}
+
+/// A frozen copy of `value`: every object and array in it, however deeply
+/// nested, is copied into new storage that is marked frozen.
+fn freeze_deep(value: &Object) -> Object {
+    match value {
+        Object::Object(map) => {
+            let map = Rc::new(map.iter().map(|(k, v)| (k.clone(), freeze_deep(v))).collect::<ObjectMap>());
+            mark_frozen(Rc::as_ptr(&map).cast(), FrozenHandle::Object(Rc::downgrade(&map)));
+            Object::Object(map)
+        }
+        Object::Array(items) => {
+            let items = Rc::new(items.iter().map(freeze_deep).collect::<Vec<_>>());
+            mark_frozen(Rc::as_ptr(&items).cast(), FrozenHandle::Array(Rc::downgrade(&items)));
+            Object::Array(items)
+        }
+        Object::OptionSome(inner) => Object::OptionSome(Box::new(freeze_deep(inner))),
+        Object::ResultOk(inner) => Object::ResultOk(Box::new(freeze_deep(inner))),
+        Object::ResultErr(inner) => Object::ResultErr(Box::new(freeze_deep(inner))),
+        other => other.clone(),
+    }
+}
+
+/// Object::freeze(value) -> Object | Array
+/// Returns a frozen copy of an object or array, along with every object and
+/// array nested in it: assigning to one of its properties or indices,
+/// `Object::setMut` or `Array::pushMut` on it, is an error. `value` itself
+/// is left as it was. Functional updates such as `Object::set` or
+/// `Array::push` still work and return an unfrozen copy.
+pub(crate) fn object_freeze(args: Vec<Object>, _env: EnvRef) -> Object {
+    match expect_one_arg(args, "Object::freeze") {
+        Ok(value @ (Object::Object(_) | Object::Array(_))) => freeze_deep(&value),
+        Ok(other) => Object::error(format!(
+            "Object::freeze expects an object or array, got {}",
+            other.type_name()
+        )),
+        Err(e) => e,
+    }
+}
+
+/// Object::isFrozen(value) -> bool
+pub(crate) fn object_is_frozen(args: Vec<Object>, _env: EnvRef) -> Object {
+    match expect_one_arg(args, "Object::isFrozen") {
+        Ok(Object::Object(map)) => Object::Boolean(is_frozen(&map)),
+        Ok(Object::Array(items)) => Object::Boolean(is_frozen_array(&items)),
+        Ok(_) => Object::Boolean(false),
+        Err(e) => e,
+    }
+}
+
+/// A copy of `value` that shares nothing with it: arrays, objects and the
+/// insides of Options, Results and refs are copied all the way down.
+fn clone_deep(value: &Object) -> Object {
+    match value {
+        Object::Array(items) => Object::array(items.iter().map(clone_deep).collect()),
+        Object::Object(map) => {
+            Object::from_map(map.iter().map(|(k, v)| (k.clone(), clone_deep(v))).collect())
+        }
+        Object::OptionSome(inner) => Object::OptionSome(Box::new(clone_deep(inner))),
+        Object::ResultOk(inner) => Object::ResultOk(Box::new(clone_deep(inner))),
+        Object::ResultErr(inner) => Object::ResultErr(Box::new(clone_deep(inner))),
+        Object::Ref(cell) => Object::Ref(Rc::new(RefCell::new(clone_deep(&cell.borrow())))),
+        other => other.clone(),
+    }
+}
+
+/// Object::clone(value) -> value
+/// Returns a deep copy of `value`. The copy is never frozen, and refs in it
+/// are new refs, so writes through them don't reach the original.
+pub(crate) fn object_clone(args: Vec<Object>, _env: EnvRef) -> Object {
+    match expect_one_arg(args, "Object::clone") {
+        Ok(value) => clone_deep(&value),
+        Err(e) => e,
+    }
+}
@@ -0,0 +1,82 @@
+use std::path::{Path, PathBuf};
+
+use crate::env::EnvRef;
+use crate::object::Object;
+
+fn expect_path<'a>(args: &'a [Object], name: &str) -> Result<&'a Path, Object> {
+    match args {
+        [Object::String(path)] => Ok(Path::new(path)),
+        _ => Err(Object::error(format!("{} expects 1 argument (path)", name))),
+    }
+}
+
+/// `Option::Some(part)`, or `Option::None()` when the path has no such part.
+fn optional(part: Option<&std::ffi::OsStr>) -> Object {
+    match part {
+        Some(part) => Object::OptionSome(Box::new(Object::String(part.to_string_lossy().into_owned()))),
+        None => Object::OptionNone,
+    }
+}
+
+/// Path::join(a, b, ...) -> string
+/// Uses the platform separator; an absolute segment replaces what came before.
+pub(crate) fn path_join(args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.is_empty() {
+        return Object::error("Path::join expects at least 1 argument (segments)");
+    }
+    let mut joined = PathBuf::new();
+    for arg in &args {
+        match arg {
+            Object::String(segment) => joined.push(segment),
+            other => {
+                return Object::error(format!(
+                    "Path::join expects string segments, got {:?}",
+                    other
+                ))
+            }
+        }
+    }
+    Object::String(joined.to_string_lossy().into_owned())
+}
+
+/// Path::basename(path) -> Option<string> (the last component)
+pub(crate) fn path_basename(args: Vec<Object>, _env: EnvRef) -> Object {
+    match expect_path(&args, "Path::basename") {
+        Ok(path) => optional(path.file_name()),
+        Err(e) => e,
+    }
+}
+
+/// Path::dirname(path) -> Option<string> (everything before the last component)
+pub(crate) fn path_dirname(args: Vec<Object>, _env: EnvRef) -> Object {
+    match expect_path(&args, "Path::dirname") {
+        Ok(path) => optional(path.parent().map(Path::as_os_str)),
+        Err(e) => e,
+    }
+}
+
+/// Path::extension(path) -> Option<string> (without the dot)
+pub(crate) fn path_extension(args: Vec<Object>, _env: EnvRef) -> Object {
+    match expect_path(&args, "Path::extension") {
+        Ok(path) => optional(path.extension()),
+        Err(e) => e,
+    }
+}
+
+/// Path::absolute(path) -> Result<string>
+/// Resolves against the current directory without touching the file, so the
+/// path doesn't have to exist and symlinks are kept.
+pub(crate) fn path_absolute(args: Vec<Object>, _env: EnvRef) -> Object {
+    let path = match expect_path(&args, "Path::absolute") {
+        Ok(path) => path,
+        Err(e) => return e,
+    };
+    match std::path::absolute(path) {
+        Ok(abs) => Object::ResultOk(Box::new(Object::String(abs.to_string_lossy().into_owned()))),
+        Err(e) => Object::ResultErr(Box::new(Object::String(format!(
+            "failed to resolve {}: {}",
+            path.display(),
+            e
+        )))),
+    }
+}
@@ -0,0 +1,424 @@
+//! The `Process` namespace: child processes that scripts can stream output
+//! from, write input to, wait for and kill.
+//!
+//! Handles are integers, like timer handles, and belong to the thread that
+//! spawned the process. Each output pipe is drained by a reader thread into a
+//! queue, so a child never stalls on a full pipe while the script is busy or
+//! waiting, and `readStdout`/`readStderr` return whatever has arrived since
+//! the last read.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+use crate::capability;
+use crate::env::EnvRef;
+use crate::event_loop;
+use crate::object::{Object, ObjectMap};
+
+/// How long a blocked read or wait goes between checks for a signal.
+const POLL: Duration = Duration::from_millis(10);
+
+/// One output pipe, as chunks sent by its reader thread.
+struct Stream {
+    chunks: Receiver<Vec<u8>>,
+    /// Bytes of a UTF-8 character split across chunks.
+    partial: Vec<u8>,
+}
+
+impl Stream {
+    fn new(pipe: Option<impl Read + Send + 'static>) -> Stream {
+        let (sender, chunks) = mpsc::channel();
+        if let Some(mut pipe) = pipe {
+            thread::spawn(move || {
+                let mut buf = [0u8; 8192];
+                while let Ok(n) = pipe.read(&mut buf) {
+                    if n == 0 || sender.send(buf[..n].to_vec()).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        Stream { chunks, partial: Vec::new() }
+    }
+
+    /// Everything that has arrived, waiting for something if `wait` is set.
+    /// `None` once the pipe is closed and drained.
+    fn read(&mut self, wait: bool, env: &EnvRef) -> Option<String> {
+        let mut bytes = std::mem::take(&mut self.partial);
+        let mut closed = false;
+        if wait {
+            loop {
+                match self.chunks.recv_timeout(POLL) {
+                    Ok(chunk) => {
+                        bytes.extend(chunk);
+                        break;
+                    }
+                    Err(RecvTimeoutError::Timeout) => event_loop::check_signal(env),
+                    Err(RecvTimeoutError::Disconnected) => {
+                        closed = true;
+                        break;
+                    }
+                }
+            }
+        }
+        while !closed {
+            match self.chunks.try_recv() {
+                Ok(chunk) => bytes.extend(chunk),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => closed = true,
+            }
+        }
+        if closed && bytes.is_empty() {
+            return None;
+        }
+        // Hold back an incomplete character at the end for the next read.
+        if !closed && let Err(e) = std::str::from_utf8(&bytes) && e.error_len().is_none() {
+            self.partial = bytes.split_off(e.valid_up_to());
+        }
+        Some(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+struct Process {
+    /// `None` for the stand-in a dry run spawns.
+    child: Option<Child>,
+    stdin: Option<ChildStdin>,
+    stdout: Stream,
+    stderr: Stream,
+    status: Option<i64>,
+}
+
+impl Process {
+    /// Wait for the process to exit; -1 if a signal ended it.
+    fn wait(&mut self, env: &EnvRef) -> Result<i64, String> {
+        // Waiting for a child that is still reading its input would never end.
+        self.stdin = None;
+        while self.status.is_none() {
+            let child = self.child.as_mut().expect("a running process has a child");
+            match child.try_wait() {
+                Ok(Some(status)) => self.status = Some(status.code().map_or(-1, i64::from)),
+                Ok(None) => {
+                    event_loop::check_signal(env);
+                    thread::sleep(POLL);
+                }
+                Err(e) => return Err(e.to_string()),
+            }
+        }
+        Ok(self.status.unwrap())
+    }
+}
+
+thread_local! {
+    static PROCESSES: RefCell<HashMap<i64, Process>> = RefCell::new(HashMap::new());
+    static NEXT_ID: Cell<i64> = const { Cell::new(1) };
+}
+
+/// Run `f` on the process behind the handle in `args[0]`.
+fn with_process<T>(
+    args: &[Object],
+    name: &str,
+    f: impl FnOnce(&mut Process) -> T,
+) -> Result<T, Object> {
+    let id = match args.first() {
+        Some(Object::Integer(id)) => *id,
+        Some(other) => {
+            return Err(Object::error(format!(
+                "{} expects a process handle, got {}",
+                name,
+                other.type_name()
+            )))
+        }
+        None => return Err(Object::error(format!("{} expects a process handle", name))),
+    };
+    // Taken out of the table while `f` runs, since a blocking read or wait
+    // may run shutdown hooks that use the table too.
+    let Some(mut process) = PROCESSES.with(|processes| processes.borrow_mut().remove(&id)) else {
+        return Err(Object::error(format!("{}: no process {}", name, id)));
+    };
+    let result = f(&mut process);
+    PROCESSES.with(|processes| processes.borrow_mut().insert(id, process));
+    Ok(result)
+}
+
+/// What to run: `(cmd, args?, {cwd, env, stdin}?)`.
+struct Spec {
+    cmd: String,
+    args: Vec<String>,
+    cwd: Option<String>,
+    env: Vec<(String, String)>,
+    stdin: Option<String>,
+}
+
+impl Spec {
+    fn parse(args: &[Object], name: &str) -> Result<Spec, Object> {
+        let usage = || Object::error(format!("{} expects (cmd, args?, options?)", name));
+        let cmd = match args.first() {
+            Some(Object::String(cmd)) => cmd.clone(),
+            _ => return Err(usage()),
+        };
+        let (list, options) = match &args[1..] {
+            [] => (None, None),
+            [Object::Array(list)] => (Some(list), None),
+            [Object::Object(options)] => (None, Some(options)),
+            [Object::Array(list), Object::Object(options)] => (Some(list), Some(options)),
+            _ => return Err(usage()),
+        };
+        let strings = |values: &mut dyn Iterator<Item = &Object>, what: &str| {
+            values
+                .map(|v| match v {
+                    Object::String(s) => Ok(s.clone()),
+                    other => Err(Object::error(format!(
+                        "{} {} must be strings, got {}",
+                        name,
+                        what,
+                        other.type_name()
+                    ))),
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let mut spec = Spec {
+            cmd,
+            args: strings(&mut list.iter().flat_map(|l| l.iter()), "arguments")?,
+            cwd: None,
+            env: Vec::new(),
+            stdin: None,
+        };
+        for (key, value) in options.iter().flat_map(|o| o.iter()) {
+            match (key.as_str(), value) {
+                ("cwd", Object::String(dir)) => spec.cwd = Some(dir.clone()),
+                ("stdin", Object::String(input)) => spec.stdin = Some(input.clone()),
+                ("env", Object::Object(vars)) => {
+                    let values = strings(&mut vars.values(), "environment values")?;
+                    spec.env = vars.keys().cloned().zip(values).collect();
+                }
+                ("cwd" | "stdin", other) => {
+                    return Err(Object::error(format!(
+                        "{} option `{}` must be a string, got {}",
+                        name,
+                        key,
+                        other.type_name()
+                    )))
+                }
+                ("env", other) => {
+                    return Err(Object::error(format!(
+                        "{} option `env` must be an object, got {}",
+                        name,
+                        other.type_name()
+                    )))
+                }
+                _ => return Err(Object::error(format!("{}: unknown option `{}`", name, key))),
+            }
+        }
+        Ok(spec)
+    }
+
+    fn describe(&self) -> String {
+        std::iter::once(&self.cmd).chain(&self.args).cloned().collect::<Vec<_>>().join(" ")
+    }
+
+    fn start(self, name: &str) -> Result<Process, String> {
+        if capability::is_dry_run() {
+            capability::log_dry_run(name, &format!("would run `{}`", self.describe()));
+            return Ok(Process {
+                child: None,
+                stdin: None,
+                stdout: Stream::new(None::<std::io::Empty>),
+                stderr: Stream::new(None::<std::io::Empty>),
+                status: Some(0),
+            });
+        }
+        let mut command = Command::new(&self.cmd);
+        command
+            .args(&self.args)
+            .envs(self.env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Some(dir) = &self.cwd {
+            command.current_dir(dir);
+        }
+        let mut child = command
+            .spawn()
+            .map_err(|e| format!("failed to start `{}`: {}", self.cmd, e))?;
+        let mut stdin = child.stdin.take();
+        if let Some(input) = self.stdin {
+            // Given input is all the child gets, so its stdin is closed after it.
+            if let Some(mut pipe) = stdin.take() {
+                thread::spawn(move || {
+                    let _ = pipe.write_all(input.as_bytes());
+                });
+            }
+        }
+        Ok(Process {
+            stdout: Stream::new(child.stdout.take()),
+            stderr: Stream::new(child.stderr.take()),
+            stdin,
+            child: Some(child),
+            status: None,
+        })
+    }
+}
+
+/// Process::spawn(cmd, args?, {cwd, env, stdin}?) -> Result::Ok(handle) or Result::Err(message)
+pub(crate) fn process_spawn(args: Vec<Object>, env: EnvRef) -> Object {
+    capability::audited("Process::spawn", args, env, spawn_process)
+}
+
+fn spawn_process(args: Vec<Object>, _env: EnvRef) -> Object {
+    let spec = match Spec::parse(&args, "Process::spawn") {
+        Ok(spec) => spec,
+        Err(e) => return e,
+    };
+    match spec.start("Process::spawn") {
+        Ok(process) => {
+            let id = NEXT_ID.with(|next| next.replace(next.get() + 1));
+            PROCESSES.with(|processes| processes.borrow_mut().insert(id, process));
+            Object::ResultOk(Box::new(Object::Integer(id)))
+        }
+        Err(e) => Object::ResultErr(Box::new(Object::String(e))),
+    }
+}
+
+/// Process::run(cmd, args?, {cwd, env, stdin}?) -> Result of { status, stdout, stderr }:
+/// Ok when the exit status is 0, Err otherwise; Err(message) if it can't start.
+pub(crate) fn process_run(args: Vec<Object>, env: EnvRef) -> Object {
+    capability::audited("Process::run", args, env, run_process)
+}
+
+fn run_process(args: Vec<Object>, env: EnvRef) -> Object {
+    let spec = match Spec::parse(&args, "Process::run") {
+        Ok(spec) => spec,
+        Err(e) => return e,
+    };
+    let mut process = match spec.start("Process::run") {
+        Ok(process) => process,
+        Err(e) => return Object::ResultErr(Box::new(Object::String(e))),
+    };
+    let status = match process.wait(&env) {
+        Ok(status) => status,
+        Err(e) => return Object::ResultErr(Box::new(Object::String(e))),
+    };
+    let drain = |stream: &mut Stream| {
+        let mut out = String::new();
+        while let Some(text) = stream.read(true, &env) {
+            out.push_str(&text);
+        }
+        Object::String(out)
+    };
+    let mut map = ObjectMap::new();
+    map.insert("status".to_string(), Object::Integer(status));
+    map.insert("stdout".to_string(), drain(&mut process.stdout));
+    map.insert("stderr".to_string(), drain(&mut process.stderr));
+    let result = Box::new(Object::from_map(map));
+    if status == 0 {
+        Object::ResultOk(result)
+    } else {
+        Object::ResultErr(result)
+    }
+}
+
+fn read_output(args: Vec<Object>, env: EnvRef, name: &str, stderr: bool) -> Object {
+    if args.len() != 1 {
+        return Object::error(format!("{} expects exactly 1 argument (process)", name));
+    }
+    let result = with_process(&args, name, |process| {
+        let stream = if stderr { &mut process.stderr } else { &mut process.stdout };
+        stream.read(true, &env)
+    });
+    match result {
+        Ok(Some(text)) => Object::OptionSome(Box::new(Object::String(text))),
+        Ok(None) => Object::OptionNone,
+        Err(e) => e,
+    }
+}
+
+/// Process::readStdout(p) -> Some(text) with the output that has arrived since
+/// the last read (waiting for some), or None once the output has ended
+pub(crate) fn process_read_stdout(args: Vec<Object>, env: EnvRef) -> Object {
+    read_output(args, env, "Process::readStdout", false)
+}
+
+/// Process::readStderr(p) -> like readStdout, for the error output
+pub(crate) fn process_read_stderr(args: Vec<Object>, env: EnvRef) -> Object {
+    read_output(args, env, "Process::readStderr", true)
+}
+
+/// Process::writeStdin(p, text) -> Result::Ok(null) or Result::Err(message)
+pub(crate) fn process_write_stdin(args: Vec<Object>, _env: EnvRef) -> Object {
+    let text = match args.as_slice() {
+        [_, Object::String(text)] => text.clone(),
+        [_, other] => {
+            return Object::error(format!(
+                "Process::writeStdin expects a string, got {}",
+                other.type_name()
+            ))
+        }
+        _ => return Object::error("Process::writeStdin expects 2 arguments (process, text)"),
+    };
+    let result = with_process(&args, "Process::writeStdin", |process| match &mut process.stdin {
+        Some(stdin) => stdin
+            .write_all(text.as_bytes())
+            .and_then(|_| stdin.flush())
+            .map_err(|e| e.to_string()),
+        None if process.child.is_none() => Ok(()),
+        None => Err("the process's input is closed".to_string()),
+    });
+    match result {
+        Ok(Ok(())) => Object::ResultOk(Box::new(Object::Null)),
+        Ok(Err(e)) => Object::ResultErr(Box::new(Object::String(e))),
+        Err(e) => e,
+    }
+}
+
+/// Process::closeStdin(p) -> null (the process sees the end of its input)
+pub(crate) fn process_close_stdin(args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.len() != 1 {
+        return Object::error("Process::closeStdin expects exactly 1 argument (process)");
+    }
+    match with_process(&args, "Process::closeStdin", |process| process.stdin = None) {
+        Ok(()) => Object::Null,
+        Err(e) => e,
+    }
+}
+
+/// Process::wait(p) -> the exit status (-1 if a signal ended the process).
+/// Closes the process's input first; unread output can still be read.
+pub(crate) fn process_wait(args: Vec<Object>, env: EnvRef) -> Object {
+    if args.len() != 1 {
+        return Object::error("Process::wait expects exactly 1 argument (process)");
+    }
+    match with_process(&args, "Process::wait", |process| process.wait(&env)) {
+        Ok(Ok(status)) => Object::Integer(status),
+        Ok(Err(e)) => Object::error(format!("Process::wait failed: {}", e)),
+        Err(e) => e,
+    }
+}
+
+/// Process::kill(p) -> bool (false if the process had already exited)
+pub(crate) fn process_kill(args: Vec<Object>, env: EnvRef) -> Object {
+    capability::audited("Process::kill", args, env, kill_process)
+}
+
+fn kill_process(args: Vec<Object>, env: EnvRef) -> Object {
+    if args.len() != 1 {
+        return Object::error("Process::kill expects exactly 1 argument (process)");
+    }
+    let result = with_process(&args, "Process::kill", |process| {
+        if process.status.is_some() {
+            return false;
+        }
+        let child = process.child.as_mut().expect("a running process has a child");
+        let killed = matches!(child.try_wait(), Ok(None)) && child.kill().is_ok();
+        let _ = process.wait(&env);
+        killed
+    });
+    match result {
+        Ok(killed) => Object::Boolean(killed),
+        Err(e) => e,
+    }
+}
@@ -0,0 +1,79 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::env::EnvRef;
+use crate::object::types::SharedRef;
+use crate::object::Object;
+
+/// Unwrap the ref handle passed as the first argument of `name`.
+pub(crate) fn expect_ref(obj: Object, name: &str) -> Result<SharedRef, Object> {
+    match obj {
+        Object::Ref(cell) => Ok(cell),
+        other => Err(Object::error(format!(
+            "{} expects a ref as first argument, got {}",
+            name,
+            other.type_name()
+        ))),
+    }
+}
+
+/// Whether `value` holds `target`, directly or through nested arrays,
+/// objects, options, results or other refs.
+fn contains_ref(value: &Object, target: &SharedRef) -> bool {
+    match value {
+        Object::Ref(cell) => Rc::ptr_eq(cell, target) || contains_ref(&cell.borrow(), target),
+        Object::Array(items) => items.iter().any(|item| contains_ref(item, target)),
+        Object::Object(map) => map.values().any(|item| contains_ref(item, target)),
+        Object::OptionSome(inner) | Object::ResultOk(inner) | Object::ResultErr(inner) => {
+            contains_ref(inner, target)
+        }
+        _ => false,
+    }
+}
+
+/// Refuse to store a value that holds `cell` itself: the cycle would leak
+/// and could never be printed.
+pub(crate) fn check_no_cycle(cell: &SharedRef, value: &Object, name: &str) -> Result<(), Object> {
+    if contains_ref(value, cell) {
+        return Err(Object::error(format!("{} cannot store a ref inside itself", name)));
+    }
+    Ok(())
+}
+
+/// Ref::new(value) -> ref
+pub(crate) fn ref_new(args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.len() != 1 {
+        return Object::error("Ref::new expects exactly 1 argument (value)");
+    }
+    let value = args.into_iter().next().unwrap();
+    Object::Ref(Rc::new(RefCell::new(value)))
+}
+
+/// Ref::get(ref) -> value
+pub(crate) fn ref_get(args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.len() != 1 {
+        return Object::error("Ref::get expects exactly 1 argument (ref)");
+    }
+    match expect_ref(args.into_iter().next().unwrap(), "Ref::get") {
+        Ok(cell) => cell.borrow().clone(),
+        Err(e) => e,
+    }
+}
+
+/// Ref::set(ref, value) -> value
+pub(crate) fn ref_set(args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.len() != 2 {
+        return Object::error("Ref::set expects exactly 2 arguments (ref, value)");
+    }
+    let mut args = args.into_iter();
+    let cell = match expect_ref(args.next().unwrap(), "Ref::set") {
+        Ok(cell) => cell,
+        Err(e) => return e,
+    };
+    let value = args.next().unwrap();
+    if let Err(e) = check_no_cycle(&cell, &value, "Ref::set") {
+        return e;
+    }
+    *cell.borrow_mut() = value.clone();
+    value
+}
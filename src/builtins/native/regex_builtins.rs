@@ -1,6 +1,8 @@
 use crate::env::EnvRef;
-use crate::object::Object;
-use regex::Regex;
+use crate::object::{Object, ObjectMap};
+use regex::{Captures, Regex};
+use std::cell::RefCell;
+use std::collections::HashMap;
 
 // ----- Regex builtins -----
 //
@@ -18,6 +20,79 @@ use regex::Regex;
 //   Regex::replace(text, pattern, replacement)
 //   Regex::match(text, pattern)
 //
+// The namespace also has helpers with no legacy alias:
+//
+//   Regex::findAll(text, pattern)  -> [matched_string, ...]
+//   Regex::matchAll(text, pattern) -> [match, ...]
+//   Regex::captures(text, pattern) -> Option::Some(match) | Option::None()
+//   Regex::split(text, pattern)    -> [piece, ...]
+//
+// where each `match` is an object
+// `{ match, start, end, groups: [full, g1, ...], named: { name: group } }`.
+// Groups that did not take part in the match are `null`. Replacements may
+// refer to groups as `$1` or `${name}`; `$$` is a literal dollar sign.
+
+/// Compiled patterns are kept per thread, so a loop calling `Regex::*` with
+/// the same pattern compiles it once. The cache is emptied when it fills up.
+const CACHE_LIMIT: usize = 256;
+
+thread_local! {
+    static CACHE: RefCell<HashMap<String, Regex>> = RefCell::new(HashMap::new());
+}
+
+/// Compile `pattern`, or reuse the copy compiled last time it was seen.
+pub(crate) fn compile(pattern: &str) -> Result<Regex, regex::Error> {
+    if let Some(re) = CACHE.with(|cache| cache.borrow().get(pattern).cloned()) {
+        return Ok(re);
+    }
+    let re = Regex::new(pattern)?;
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if cache.len() >= CACHE_LIMIT {
+            cache.clear();
+        }
+        cache.insert(pattern.to_string(), re.clone());
+    });
+    Ok(re)
+}
+
+/// The (text, pattern) arguments of the namespace-only helpers, with the
+/// pattern already compiled.
+fn text_and_pattern<'a>(args: &'a [Object], name: &str) -> Result<(&'a str, Regex), Object> {
+    let (text, pattern) = match args {
+        [Object::String(text), Object::String(pattern)] => (text, pattern),
+        [_, _] => return Err(Object::error(format!("{} expects two strings (text, pattern)", name))),
+        _ => return Err(Object::error(format!("{} expects exactly 2 arguments (text, pattern)", name))),
+    };
+    match compile(pattern) {
+        Ok(re) => Ok((text, re)),
+        Err(e) => Err(Object::error(format!("invalid regex pattern: {}", e))),
+    }
+}
+
+fn group_value(m: Option<regex::Match>) -> Object {
+    match m {
+        Some(m) => Object::String(m.as_str().to_string()),
+        None => Object::Null,
+    }
+}
+
+/// The `{ match, start, end, groups, named }` object for one match.
+fn match_object(re: &Regex, caps: &Captures) -> Object {
+    let full = caps.get(0).expect("group 0 always takes part in a match");
+    let groups = caps.iter().map(group_value).collect();
+    let mut named = ObjectMap::new();
+    for name in re.capture_names().flatten() {
+        named.insert(name.to_string(), group_value(caps.name(name)));
+    }
+    let mut fields = ObjectMap::new();
+    fields.insert("match".to_string(), Object::String(full.as_str().to_string()));
+    fields.insert("start".to_string(), Object::Integer(full.start() as i64));
+    fields.insert("end".to_string(), Object::Integer(full.end() as i64));
+    fields.insert("groups".to_string(), Object::array(groups));
+    fields.insert("named".to_string(), Object::from_map(named));
+    Object::from_map(fields)
+}
 
 pub(crate) fn builtin_regex_is_match(args: Vec<Object>, _env: EnvRef) -> Object {
     if args.len() != 2 {
@@ -44,7 +119,7 @@ pub(crate) fn builtin_regex_is_match(args: Vec<Object>, _env: EnvRef) -> Object
         }
     };
 
-    let re = match Regex::new(pattern) {
+    let re = match compile(pattern) {
         Ok(r) => r,
         Err(e) => return Object::error(format!("invalid regex pattern: {}", e)),
     };
@@ -77,7 +152,7 @@ pub(crate) fn builtin_regex_find(args: Vec<Object>, _env: EnvRef) -> Object {
         }
     };
 
-    let re = match Regex::new(pattern) {
+    let re = match compile(pattern) {
         Ok(r) => r,
         Err(e) => return Object::error(format!("invalid regex pattern: {}", e)),
     };
@@ -124,7 +199,7 @@ pub(crate) fn builtin_regex_replace(args: Vec<Object>, _env: EnvRef) -> Object {
         }
     };
 
-    let re = match Regex::new(pattern) {
+    let re = match compile(pattern) {
         Ok(r) => r,
         Err(e) => return Object::error(format!("invalid regex pattern: {}", e)),
     };
@@ -158,7 +233,7 @@ pub(crate) fn builtin_regex_match(args: Vec<Object>, _env: EnvRef) -> Object {
         }
     };
 
-    let re = match Regex::new(pattern) {
+    let re = match compile(pattern) {
         Ok(r) => r,
         Err(e) => return Object::error(format!("invalid regex pattern: {}", e)),
     };
@@ -173,10 +248,45 @@ pub(crate) fn builtin_regex_match(args: Vec<Object>, _env: EnvRef) -> Object {
                     groups.push(Object::Null);
                 }
             }
-            Object::OptionSome(Box::new(Object::Array(groups)))
+            Object::OptionSome(Box::new(Object::array(groups)))
         }
         None => Object::OptionNone,
     }
 }
 
 
+
+pub(crate) fn builtin_regex_find_all(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (text, re) = match text_and_pattern(&args, "Regex::findAll") {
+        Ok(v) => v,
+        Err(err) => return err,
+    };
+    Object::array(re.find_iter(text).map(|m| Object::String(m.as_str().to_string())).collect())
+}
+
+pub(crate) fn builtin_regex_match_all(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (text, re) = match text_and_pattern(&args, "Regex::matchAll") {
+        Ok(v) => v,
+        Err(err) => return err,
+    };
+    Object::array(re.captures_iter(text).map(|caps| match_object(&re, &caps)).collect())
+}
+
+pub(crate) fn builtin_regex_captures(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (text, re) = match text_and_pattern(&args, "Regex::captures") {
+        Ok(v) => v,
+        Err(err) => return err,
+    };
+    match re.captures(text) {
+        Some(caps) => Object::OptionSome(Box::new(match_object(&re, &caps))),
+        None => Object::OptionNone,
+    }
+}
+
+pub(crate) fn builtin_regex_split(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (text, re) = match text_and_pattern(&args, "Regex::split") {
+        Ok(v) => v,
+        Err(err) => return err,
+    };
+    Object::array(re.split(text).map(|piece| Object::String(piece.to_string())).collect())
+}
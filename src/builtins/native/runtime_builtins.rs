@@ -0,0 +1,166 @@
+use std::time::Duration;
+
+use crate::env::{register_subscription, EnvRef};
+use crate::event_loop;
+use crate::object::Object;
+
+/// Parse the `(ms, fn)` arguments shared by `Timer::after` and `Timer::every`.
+fn timer_args(args: Vec<Object>, name: &str) -> Result<(Duration, Object), Object> {
+    if args.len() != 2 {
+        return Err(Object::error(format!(
+            "{} expects 2 arguments (ms, fn)",
+            name
+        )));
+    }
+    let mut args = args.into_iter();
+    let ms = match args.next().unwrap() {
+        Object::Integer(ms) if ms >= 0 => ms as u64,
+        Object::Integer(_) => {
+            return Err(Object::error(format!(
+                "{} milliseconds must be non-negative",
+                name
+            )))
+        }
+        other => {
+            return Err(Object::error(format!(
+                "{} expects integer milliseconds, got {:?}",
+                name, other
+            )))
+        }
+    };
+    let callback = args.next().unwrap();
+    if !callback.is_callable() {
+        return Err(Object::error(format!(
+            "{} expects a function as second argument, got {}",
+            name,
+            callback.type_name()
+        )));
+    }
+    Ok((Duration::from_millis(ms), callback))
+}
+
+/// Timer::after(ms, fn) -> handle
+pub(crate) fn timer_after(args: Vec<Object>, _env: EnvRef) -> Object {
+    match timer_args(args, "Timer::after") {
+        Ok((delay, callback)) => Object::Integer(event_loop::schedule(delay, None, callback)),
+        Err(e) => e,
+    }
+}
+
+/// Timer::every(ms, fn) -> handle
+pub(crate) fn timer_every(args: Vec<Object>, _env: EnvRef) -> Object {
+    match timer_args(args, "Timer::every") {
+        Ok((interval, _)) if interval.is_zero() => {
+            Object::error("Timer::every interval must be positive")
+        }
+        Ok((interval, callback)) => {
+            Object::Integer(event_loop::schedule(interval, Some(interval), callback))
+        }
+        Err(e) => e,
+    }
+}
+
+/// Timer::cancel(handle) -> bool (false if the timer already fired or was cancelled)
+pub(crate) fn timer_cancel(args: Vec<Object>, _env: EnvRef) -> Object {
+    match args.as_slice() {
+        [Object::Integer(id)] => Object::Boolean(event_loop::cancel(*id)),
+        [other] => Object::error(format!(
+            "Timer::cancel expects a timer handle, got {:?}",
+            other
+        )),
+        _ => Object::error("Timer::cancel expects exactly 1 argument"),
+    }
+}
+
+/// Runtime::run() -> null (dispatches timers until none are pending)
+pub(crate) fn runtime_run(args: Vec<Object>, env: EnvRef) -> Object {
+    if !args.is_empty() {
+        return Object::error("Runtime::run expects no arguments");
+    }
+    event_loop::run(env)
+}
+
+/// Runtime::onShutdown(fn) -> null (hooks run last-registered-first on exit)
+pub(crate) fn runtime_on_shutdown(args: Vec<Object>, _env: EnvRef) -> Object {
+    match args.as_slice() {
+        [callback] if callback.is_callable() => {
+            event_loop::on_shutdown(args.into_iter().next().unwrap());
+            Object::Null
+        }
+        [other] => Object::error(format!(
+            "Runtime::onShutdown expects a function, got {}",
+            other.type_name()
+        )),
+        _ => Object::error("Runtime::onShutdown expects exactly 1 argument"),
+    }
+}
+
+/// A tag argument: `"Tag"`, or `":Tag"` as tags are written elsewhere.
+fn tag_arg(arg: &Object, name: &str) -> Result<String, Object> {
+    match arg {
+        Object::String(tag) => {
+            let tag = tag.strip_prefix(':').unwrap_or(tag);
+            if tag.is_empty() {
+                Err(Object::error(format!("{} expects a non-empty tag", name)))
+            } else {
+                Ok(tag.to_string())
+            }
+        }
+        other => Err(Object::error(format!(
+            "{} expects a tag string, got {}",
+            name,
+            other.type_name()
+        ))),
+    }
+}
+
+/// Events::subscribe(tag, fn) -> null (like tagging a function with `(:Tag)`)
+pub(crate) fn events_subscribe(args: Vec<Object>, env: EnvRef) -> Object {
+    let [tag, callback] = args.as_slice() else {
+        return Object::error("Events::subscribe expects 2 arguments (tag, fn)");
+    };
+    let tag = match tag_arg(tag, "Events::subscribe") {
+        Ok(tag) => tag,
+        Err(e) => return e,
+    };
+    if !callback.is_callable() {
+        return Object::error(format!(
+            "Events::subscribe expects a function as second argument, got {}",
+            callback.type_name()
+        ));
+    }
+    register_subscription(&tag, callback.clone(), env);
+    Object::Null
+}
+
+/// Events::publish(tag, ...values) -> null (queued until Events::run or Runtime::run)
+pub(crate) fn events_publish(args: Vec<Object>, _env: EnvRef) -> Object {
+    let mut args = args.into_iter();
+    let tag = match args.next() {
+        Some(tag) => tag,
+        None => return Object::error("Events::publish expects a tag and values"),
+    };
+    match tag_arg(&tag, "Events::publish") {
+        Ok(tag) => {
+            event_loop::publish(&tag, args.collect());
+            Object::Null
+        }
+        Err(e) => e,
+    }
+}
+
+/// Events::run() -> null (delivers queued events until none are left)
+pub(crate) fn events_run(args: Vec<Object>, env: EnvRef) -> Object {
+    if !args.is_empty() {
+        return Object::error("Events::run expects no arguments");
+    }
+    event_loop::run_events(&env)
+}
+
+/// Events::pending() -> integer (events published but not yet delivered)
+pub(crate) fn events_pending(args: Vec<Object>, _env: EnvRef) -> Object {
+    if !args.is_empty() {
+        return Object::error("Events::pending expects no arguments");
+    }
+    Object::Integer(event_loop::pending_events() as i64)
+}
@@ -0,0 +1,439 @@
+use std::rc::Rc;
+
+use crate::builtins::native::object_builtins::unfrozen_mut;
+#[cfg(feature = "regex")]
+use crate::builtins::native::regex_builtins::compile;
+
+use crate::env::EnvRef;
+use crate::object::{Object, ObjectMap};
+
+/// Key holding a schema's kind. Schemas are plain objects, so they can be
+/// stored, merged and passed around like any other value.
+const KIND_KEY: &str = "schema";
+
+type Schema = ObjectMap;
+
+fn is_schema(obj: &Object) -> bool {
+    matches!(obj, Object::Object(map) if matches!(map.get(KIND_KEY), Some(Object::String(_))))
+}
+
+fn expect_schema<'a>(obj: &'a Object, name: &str) -> Result<&'a Schema, Object> {
+    match obj {
+        Object::Object(map) if is_schema(obj) => Ok(map),
+        other => Err(Object::error(format!(
+            "{} expects a schema (e.g. from Schema::string), got {}",
+            name,
+            other.type_name()
+        ))),
+    }
+}
+
+/// Build a schema of `kind` from an optional options object, rejecting
+/// options the kind doesn't understand.
+fn build_schema(
+    name: &str,
+    kind: &str,
+    options: Option<Object>,
+    allowed: &[&str],
+) -> Result<Schema, Object> {
+    let mut schema = match options {
+        None => ObjectMap::new(),
+        Some(Object::Object(map)) => Rc::unwrap_or_clone(map),
+        Some(other) => {
+            return Err(Object::error(format!(
+                "{} options must be an object, got {}",
+                name,
+                other.type_name()
+            )))
+        }
+    };
+
+    for (key, value) in &schema {
+        if !allowed.contains(&key.as_str()) && key != "optional" {
+            return Err(Object::error(format!(
+                "{} got unknown option {} (expected one of: {})",
+                name,
+                key,
+                allowed.join(", ")
+            )));
+        }
+        let valid = match key.as_str() {
+            "min" | "max" => matches!(value, Object::Integer(_) | Object::Float(_)),
+            "optional" | "strict" => matches!(value, Object::Boolean(_)),
+            #[cfg(feature = "regex")]
+            "pattern" => matches!(value, Object::String(s) if compile(s).is_ok()),
+            #[cfg(not(feature = "regex"))]
+            "pattern" => {
+                return Err(Object::error(format!(
+                    "{} option pattern needs slang built with the `regex` feature",
+                    name
+                )))
+            }
+            _ => true,
+        };
+        if !valid {
+            return Err(Object::error(format!(
+                "{} option {} has an invalid value: {}",
+                name, key, value
+            )));
+        }
+    }
+
+    schema.insert(KIND_KEY.to_string(), Object::String(kind.to_string()));
+    Ok(schema)
+}
+
+/// Shared body of the scalar constructors, which take only an options object.
+fn scalar_schema(args: Vec<Object>, name: &str, kind: &str, allowed: &[&str]) -> Object {
+    if args.len() > 1 {
+        return Object::error(format!("{} expects at most 1 argument (options)", name));
+    }
+    match build_schema(name, kind, args.into_iter().next(), allowed) {
+        Ok(schema) => Object::from_map(schema),
+        Err(e) => e,
+    }
+}
+
+/// Schema::string([{ min, max, pattern, optional }]) -> schema
+pub(crate) fn schema_string(args: Vec<Object>, _env: EnvRef) -> Object {
+    scalar_schema(args, "Schema::string", "string", &["min", "max", "pattern"])
+}
+
+/// Schema::int([{ min, max, optional }]) -> schema
+pub(crate) fn schema_int(args: Vec<Object>, _env: EnvRef) -> Object {
+    scalar_schema(args, "Schema::int", "int", &["min", "max"])
+}
+
+/// Schema::float([{ min, max, optional }]) -> schema (integers are accepted too)
+pub(crate) fn schema_float(args: Vec<Object>, _env: EnvRef) -> Object {
+    scalar_schema(args, "Schema::float", "float", &["min", "max"])
+}
+
+/// Schema::bool([{ optional }]) -> schema
+pub(crate) fn schema_bool(args: Vec<Object>, _env: EnvRef) -> Object {
+    scalar_schema(args, "Schema::bool", "bool", &[])
+}
+
+/// Schema::any([{ optional }]) -> schema
+pub(crate) fn schema_any(args: Vec<Object>, _env: EnvRef) -> Object {
+    scalar_schema(args, "Schema::any", "any", &[])
+}
+
+/// Schema::array(itemSchema[, { min, max, optional }]) -> schema
+pub(crate) fn schema_array(args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.is_empty() || args.len() > 2 {
+        return Object::error("Schema::array expects 1 or 2 arguments (itemSchema[, options])");
+    }
+    let mut args = args.into_iter();
+    let items = args.next().unwrap();
+    if let Err(e) = expect_schema(&items, "Schema::array") {
+        return e;
+    }
+    match build_schema("Schema::array", "array", args.next(), &["min", "max"]) {
+        Ok(mut schema) => {
+            schema.insert("items".to_string(), items);
+            Object::from_map(schema)
+        }
+        Err(e) => e,
+    }
+}
+
+/// Schema::object({ field: schema, ... }[, { strict, optional }]) -> schema
+///
+/// Fields missing from the value are null, so they only pass if their schema
+/// is optional. With `strict: true`, fields not in the schema are errors.
+pub(crate) fn schema_object(args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.is_empty() || args.len() > 2 {
+        return Object::error("Schema::object expects 1 or 2 arguments (fields[, options])");
+    }
+    let mut args = args.into_iter();
+    let fields = match args.next().unwrap() {
+        Object::Object(fields) => fields,
+        other => {
+            return Object::error(format!(
+                "Schema::object expects an object of field schemas, got {}",
+                other.type_name()
+            ))
+        }
+    };
+    for (field, schema) in fields.iter() {
+        if !is_schema(schema) {
+            return Object::error(format!(
+                "Schema::object field {} is not a schema, got {}",
+                field,
+                schema.type_name()
+            ));
+        }
+    }
+    match build_schema("Schema::object", "object", args.next(), &["strict"]) {
+        Ok(mut schema) => {
+            schema.insert("fields".to_string(), Object::Object(fields));
+            Object::from_map(schema)
+        }
+        Err(e) => e,
+    }
+}
+
+/// Schema::optional(schema) -> schema that also accepts null
+pub(crate) fn schema_optional(args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.len() != 1 {
+        return Object::error("Schema::optional expects exactly 1 argument (schema)");
+    }
+    match expect_schema(&args[0], "Schema::optional") {
+        Ok(schema) => {
+            let mut schema = schema.clone();
+            schema.insert("optional".to_string(), Object::Boolean(true));
+            Object::from_map(schema)
+        }
+        Err(e) => e,
+    }
+}
+
+/// Schema::validate(schema, value) -> Result<value, [error]>
+pub(crate) fn schema_validate(args: Vec<Object>, _env: EnvRef) -> Object {
+    run_schema(args, "Schema::validate", false)
+}
+
+/// Schema::coerce(schema, value) -> Result<converted value, [error]>
+///
+/// Like `validate`, but first converts values that have an obvious reading
+/// as the expected type: numeric strings to numbers, `"true"`/`"false"` to
+/// booleans, numbers and booleans to strings, integers to floats.
+pub(crate) fn schema_coerce(args: Vec<Object>, _env: EnvRef) -> Object {
+    run_schema(args, "Schema::coerce", true)
+}
+
+fn run_schema(args: Vec<Object>, name: &str, coerce: bool) -> Object {
+    if args.len() != 2 {
+        return Object::error(format!("{} expects 2 arguments (schema, value)", name));
+    }
+    let mut args = args.into_iter();
+    let schema = args.next().unwrap();
+    let value = args.next().unwrap();
+    let schema = match expect_schema(&schema, name) {
+        Ok(schema) => schema,
+        Err(e) => return e,
+    };
+
+    let mut checker = Checker {
+        coerce,
+        errors: Vec::new(),
+    };
+    let value = checker.check(schema, value, "");
+    if checker.errors.is_empty() {
+        Object::ResultOk(Box::new(value))
+    } else {
+        let errors = checker.errors.into_iter().map(Object::String).collect();
+        Object::ResultErr(Box::new(Object::array(errors)))
+    }
+}
+
+struct Checker {
+    coerce: bool,
+    /// `"path: message"` for every failed check, in the order found.
+    errors: Vec<String>,
+}
+
+impl Checker {
+    fn fail(&mut self, path: &str, message: String) {
+        let path = if path.is_empty() { "value" } else { path };
+        self.errors.push(format!("{}: {}", path, message));
+    }
+
+    /// Check `value` against `schema`, returning it (converted, when
+    /// coercing) so object and array results can be rebuilt.
+    fn check(&mut self, schema: &Schema, value: Object, path: &str) -> Object {
+        if value == Object::Null {
+            if !matches!(schema.get("optional"), Some(Object::Boolean(true))) {
+                self.fail(path, "is required".to_string());
+            }
+            return value;
+        }
+
+        let kind = match schema.get(KIND_KEY) {
+            Some(Object::String(kind)) => kind.as_str(),
+            _ => "",
+        };
+        match kind {
+            "any" => value,
+            "string" => self.check_string(schema, value, path),
+            "int" | "float" => self.check_number(schema, kind, value, path),
+            "bool" => self.check_bool(value, path),
+            "array" => self.check_array(schema, value, path),
+            "object" => self.check_object(schema, value, path),
+            other => {
+                self.fail(path, format!("invalid schema kind {:?}", other));
+                value
+            }
+        }
+    }
+
+    fn check_string(&mut self, schema: &Schema, value: Object, path: &str) -> Object {
+        let value = match value {
+            Object::Integer(_) | Object::Float(_) | Object::Boolean(_) if self.coerce => {
+                Object::String(value.to_string())
+            }
+            other => other,
+        };
+        let Object::String(s) = &value else {
+            self.fail(path, format!("expected string, got {}", value.type_name()));
+            return value;
+        };
+
+        let len = s.chars().count() as f64;
+        if let Some(min) = number_option(schema, "min") && len < min {
+            self.fail(path, format!("length must be at least {}", schema["min"]));
+        }
+        if let Some(max) = number_option(schema, "max") && len > max {
+            self.fail(path, format!("length must be at most {}", schema["max"]));
+        }
+        #[cfg(feature = "regex")]
+        if let Some(Object::String(pattern)) = schema.get("pattern")
+            && let Ok(re) = compile(pattern)
+            && !re.is_match(s)
+        {
+            self.fail(path, format!("must match pattern {}", pattern));
+        }
+        value
+    }
+
+    fn check_number(&mut self, schema: &Schema, kind: &str, value: Object, path: &str) -> Object {
+        let value = if self.coerce {
+            coerce_number(kind, value)
+        } else {
+            value
+        };
+        let n = match (&value, kind) {
+            (Object::Integer(i), _) => *i as f64,
+            (Object::Float(f), "float") => *f,
+            _ => {
+                self.fail(path, format!("expected {}, got {}", kind, value.type_name()));
+                return value;
+            }
+        };
+
+        if let Some(min) = number_option(schema, "min") && n < min {
+            self.fail(path, format!("must be at least {}", schema["min"]));
+        }
+        if let Some(max) = number_option(schema, "max") && n > max {
+            self.fail(path, format!("must be at most {}", schema["max"]));
+        }
+        value
+    }
+
+    fn check_bool(&mut self, value: Object, path: &str) -> Object {
+        let value = match value {
+            Object::String(s) if self.coerce && (s == "true" || s == "false") => {
+                Object::Boolean(s == "true")
+            }
+            other => other,
+        };
+        if !matches!(value, Object::Boolean(_)) {
+            self.fail(path, format!("expected bool, got {}", value.type_name()));
+        }
+        value
+    }
+
+    fn check_array(&mut self, schema: &Schema, value: Object, path: &str) -> Object {
+        let items = match value {
+            Object::Array(items) => items,
+            other => {
+                self.fail(path, format!("expected array, got {}", other.type_name()));
+                return other;
+            }
+        };
+
+        let len = items.len() as f64;
+        if let Some(min) = number_option(schema, "min") && len < min {
+            self.fail(path, format!("length must be at least {}", schema["min"]));
+        }
+        if let Some(max) = number_option(schema, "max") && len > max {
+            self.fail(path, format!("length must be at most {}", schema["max"]));
+        }
+
+        let Some(Object::Object(item_schema)) = schema.get("items") else {
+            return Object::Array(items);
+        };
+        let checked = Rc::unwrap_or_clone(items)
+            .into_iter()
+            .enumerate()
+            .map(|(i, item)| self.check(item_schema, item, &format!("{}[{}]", path, i)))
+            .collect();
+        Object::array(checked)
+    }
+
+    fn check_object(&mut self, schema: &Schema, value: Object, path: &str) -> Object {
+        let mut map = match value {
+            Object::Object(map) => map,
+            other => {
+                self.fail(path, format!("expected object, got {}", other.type_name()));
+                return other;
+            }
+        };
+        let Some(Object::Object(fields)) = schema.get("fields") else {
+            return Object::Object(map);
+        };
+
+        // Sorted so errors come out in a stable order.
+        let mut names: Vec<&String> = fields.keys().collect();
+        names.sort();
+        for name in names {
+            let Object::Object(field_schema) = &fields[name] else {
+                continue;
+            };
+            let field_path = if path.is_empty() {
+                name.clone()
+            } else {
+                format!("{}.{}", path, name)
+            };
+            match unfrozen_mut(&mut map).shift_remove(name) {
+                Some(field) => {
+                    let checked = self.check(field_schema, field, &field_path);
+                    unfrozen_mut(&mut map).insert(name.clone(), checked);
+                }
+                None => {
+                    self.check(field_schema, Object::Null, &field_path);
+                }
+            }
+        }
+
+        if matches!(schema.get("strict"), Some(Object::Boolean(true))) {
+            let mut extra: Vec<&String> = map.keys().filter(|k| !fields.contains_key(*k)).collect();
+            extra.sort();
+            for name in extra {
+                let field_path = if path.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{}.{}", path, name)
+                };
+                self.fail(&field_path, "is not an allowed field".to_string());
+            }
+        }
+        Object::Object(map)
+    }
+}
+
+fn number_option(schema: &Schema, key: &str) -> Option<f64> {
+    match schema.get(key) {
+        Some(Object::Integer(i)) => Some(*i as f64),
+        Some(Object::Float(f)) => Some(*f),
+        _ => None,
+    }
+}
+
+/// Convert `value` towards `kind` ("int" or "float") where that's lossless.
+fn coerce_number(kind: &str, value: Object) -> Object {
+    match (kind, value) {
+        ("int", Object::String(s)) => match s.trim().parse::<i64>() {
+            Ok(i) => Object::Integer(i),
+            Err(_) => Object::String(s),
+        },
+        ("int", Object::Float(f)) if f.fract() == 0.0 => Object::Integer(f as i64),
+        ("float", Object::String(s)) => match s.trim().parse::<f64>() {
+            Ok(f) => Object::Float(f),
+            Err(_) => Object::String(s),
+        },
+        ("float", Object::Integer(i)) => Object::Float(i as f64),
+        (_, other) => other,
+    }
+}
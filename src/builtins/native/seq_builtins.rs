@@ -0,0 +1,279 @@
+use std::rc::Rc;
+
+use crate::builtins::native::array_builtins;
+use crate::env::{new_enclosed_env, EnvRef};
+use crate::evaluator::core::expr::apply_function_with_this;
+use crate::evaluator::core::generator::Generator;
+use crate::object::Object;
+use crate::object::types::{SeqNode, SeqRef};
+
+type SeqIter = Box<dyn Iterator<Item = Object>>;
+
+/// Pull the values of `seq` one at a time. An error from a callback is
+/// yielded as an `Object::Error` and ends the sequence.
+pub(crate) fn iterate(seq: &SeqRef, env: &EnvRef) -> SeqIter {
+    let env = Rc::clone(env);
+    let iter: SeqIter = match &**seq {
+        SeqNode::Array(items) => {
+            let items = Rc::clone(items);
+            Box::new((0..items.len()).map(move |i| items[i].clone()))
+        }
+        SeqNode::Range { start, end, step } => {
+            let (end, step) = (*end, *step);
+            let values = std::iter::successors(Some(*start), move |n| n.checked_add(step));
+            Box::new(
+                values
+                    .take_while(move |n| match end {
+                        Some(end) if step > 0 => *n < end,
+                        Some(end) => *n > end,
+                        None => true,
+                    })
+                    .map(Object::Integer),
+            )
+        }
+        SeqNode::Map(source, func) => {
+            let func = func.clone();
+            Box::new(iterate(source, &env).map(move |value| {
+                if value.is_error() {
+                    return value;
+                }
+                apply_function_with_this(func.clone(), vec![value], None, Rc::clone(&env))
+            }))
+        }
+        SeqNode::Filter(source, pred) => {
+            let pred = pred.clone();
+            Box::new(iterate(source, &env).filter_map(move |value| {
+                match test(&pred, &value, "Seq::filter", &env) {
+                    Ok(true) => Some(value),
+                    Ok(false) => None,
+                    Err(e) => Some(e),
+                }
+            }))
+        }
+        SeqNode::Take(source, n) => Box::new(iterate(source, &env).take(*n)),
+        SeqNode::Drop(source, n) => Box::new(iterate(source, &env).skip(*n)),
+        SeqNode::TakeWhile(source, pred) => {
+            let pred = pred.clone();
+            let mut done = false;
+            Box::new(iterate(source, &env).map_while(move |value| {
+                if done {
+                    return None;
+                }
+                match test(&pred, &value, "Seq::takeWhile", &env) {
+                    Ok(true) => Some(value),
+                    Ok(false) => None,
+                    Err(e) => {
+                        done = true;
+                        Some(e)
+                    }
+                }
+            }))
+        }
+        SeqNode::DropWhile(source, pred) => {
+            let pred = pred.clone();
+            let mut dropping = true;
+            Box::new(iterate(source, &env).filter_map(move |value| {
+                if !dropping {
+                    return Some(value);
+                }
+                match test(&pred, &value, "Seq::dropWhile", &env) {
+                    Ok(true) => None,
+                    Ok(false) => {
+                        dropping = false;
+                        Some(value)
+                    }
+                    Err(e) => Some(e),
+                }
+            }))
+        }
+        SeqNode::Enumerate(source) => {
+            Box::new(iterate(source, &env).enumerate().map(|(i, value)| {
+                if value.is_error() {
+                    return value;
+                }
+                Object::array(vec![Object::Integer(i as i64), value])
+            }))
+        }
+        SeqNode::Product(arrays) => Box::new(array_builtins::product(arrays.clone())),
+        SeqNode::Combinations(items, k) => Box::new(array_builtins::combinations(Rc::clone(items), *k)),
+        SeqNode::Permutations(items, k) => Box::new(array_builtins::permutations(Rc::clone(items), *k)),
+        SeqNode::Generator { body, env: scope } => {
+            Box::new(Generator::new(Rc::clone(body), new_enclosed_env(Rc::clone(scope))))
+        }
+    };
+
+    // Stop right after the first error so consumers never look past it.
+    let mut failed = false;
+    Box::new(iter.map_while(move |value| {
+        if failed {
+            return None;
+        }
+        failed = value.is_error();
+        Some(value)
+    }))
+}
+
+/// Run a predicate on `value`; errors (including the value itself being one)
+/// come back as `Err`.
+fn test(pred: &Object, value: &Object, name: &str, env: &EnvRef) -> Result<bool, Object> {
+    if value.is_error() {
+        return Err(value.clone());
+    }
+    match apply_function_with_this(pred.clone(), vec![value.clone()], None, Rc::clone(env)) {
+        Object::Boolean(b) => Ok(b),
+        err @ Object::Error(_) => Err(err),
+        other => Err(Object::error(format!(
+            "{} predicate must return boolean, got {:?}",
+            name, other
+        ))),
+    }
+}
+
+fn expect_seq(obj: Object, name: &str) -> Result<SeqRef, Object> {
+    match obj {
+        Object::Seq(seq) => Ok(seq),
+        other => Err(Object::error(format!(
+            "{} expects a seq as first argument, got {:?}",
+            name, other
+        ))),
+    }
+}
+
+/// Shared shape of `Seq::map(seq, f)` and the predicate adapters.
+fn with_function(
+    mut args: Vec<Object>,
+    name: &str,
+    build: fn(SeqRef, Object) -> SeqNode,
+) -> Object {
+    if args.len() != 2 {
+        return Object::error(format!("{} expects exactly 2 arguments (seq, fn)", name));
+    }
+    let func = args.pop().unwrap();
+    let seq = match expect_seq(args.pop().unwrap(), name) {
+        Ok(seq) => seq,
+        Err(e) => return e,
+    };
+    if !func.is_callable() {
+        return Object::error(format!(
+            "{} expects a function as second argument, got {:?}",
+            name, func
+        ));
+    }
+    Object::Seq(Rc::new(build(seq, func)))
+}
+
+/// Shared shape of `Seq::take(seq, n)` and `Seq::drop(seq, n)`.
+fn with_count(mut args: Vec<Object>, name: &str, build: fn(SeqRef, usize) -> SeqNode) -> Object {
+    if args.len() != 2 {
+        return Object::error(format!("{} expects exactly 2 arguments (seq, n)", name));
+    }
+    let n = args.pop().unwrap();
+    let seq = match expect_seq(args.pop().unwrap(), name) {
+        Ok(seq) => seq,
+        Err(e) => return e,
+    };
+    match n {
+        Object::Integer(n) if n >= 0 => Object::Seq(Rc::new(build(seq, n as usize))),
+        Object::Integer(_) => Object::error(format!("{} count must be non-negative", name)),
+        other => Object::error(format!(
+            "{} expects integer as second argument, got {:?}",
+            name, other
+        )),
+    }
+}
+
+/// Seq::from(arr) -> seq over the array's elements
+/// (also available as Seq::fromArray)
+pub(crate) fn seq_from(args: Vec<Object>, _env: EnvRef) -> Object {
+    match <[Object; 1]>::try_from(args) {
+        Ok([Object::Array(items)]) => Object::Seq(Rc::new(SeqNode::Array(items))),
+        Ok([Object::Seq(seq)]) => Object::Seq(seq),
+        Ok([other]) => Object::error(format!("Seq::from expects an array, got {:?}", other)),
+        Err(_) => Object::error("Seq::from expects exactly 1 argument (array)"),
+    }
+}
+
+/// Seq::range(start, [end], [step]) -> seq of integers; no end means unbounded
+pub(crate) fn seq_range(args: Vec<Object>, _env: EnvRef) -> Object {
+    let ints: Option<Vec<i64>> = args
+        .iter()
+        .map(|arg| match arg {
+            Object::Integer(i) => Some(*i),
+            _ => None,
+        })
+        .collect();
+    let (start, end, step) = match ints.as_deref() {
+        Some([start]) => (*start, None, 1),
+        Some([start, end]) => (*start, Some(*end), 1),
+        Some([start, end, step]) => (*start, Some(*end), *step),
+        Some(_) => return Object::error("Seq::range expects 1 to 3 arguments (start, [end], [step])"),
+        None => return Object::error("Seq::range expects integer arguments"),
+    };
+    if step == 0 {
+        return Object::error("Seq::range step cannot be zero");
+    }
+    Object::Seq(Rc::new(SeqNode::Range { start, end, step }))
+}
+
+/// Seq::map(seq, f) -> seq of f(x), applied as values are pulled
+pub(crate) fn seq_map(args: Vec<Object>, _env: EnvRef) -> Object {
+    with_function(args, "Seq::map", SeqNode::Map)
+}
+
+/// Seq::filter(seq, pred) -> seq of the values where pred(x) is true
+pub(crate) fn seq_filter(args: Vec<Object>, _env: EnvRef) -> Object {
+    with_function(args, "Seq::filter", SeqNode::Filter)
+}
+
+/// Seq::take(seq, n) -> seq of at most the first n values
+pub(crate) fn seq_take(args: Vec<Object>, _env: EnvRef) -> Object {
+    with_count(args, "Seq::take", SeqNode::Take)
+}
+
+/// Seq::drop(seq, n) -> seq without the first n values
+pub(crate) fn seq_drop(args: Vec<Object>, _env: EnvRef) -> Object {
+    with_count(args, "Seq::drop", SeqNode::Drop)
+}
+
+/// Seq::takeWhile(seq, pred) -> seq of leading values while pred(x) holds
+pub(crate) fn seq_take_while(args: Vec<Object>, _env: EnvRef) -> Object {
+    with_function(args, "Seq::takeWhile", SeqNode::TakeWhile)
+}
+
+/// Seq::dropWhile(seq, pred) -> seq from the first value where pred(x) fails
+/// (also available as Seq::skipWhile)
+pub(crate) fn seq_drop_while(args: Vec<Object>, _env: EnvRef) -> Object {
+    with_function(args, "Seq::dropWhile", SeqNode::DropWhile)
+}
+
+/// Seq::enumerate(seq) -> seq of [index, value] pairs
+pub(crate) fn seq_enumerate(args: Vec<Object>, _env: EnvRef) -> Object {
+    match <[Object; 1]>::try_from(args) {
+        Ok([obj]) => match expect_seq(obj, "Seq::enumerate") {
+            Ok(seq) => Object::Seq(Rc::new(SeqNode::Enumerate(seq))),
+            Err(e) => e,
+        },
+        Err(_) => Object::error("Seq::enumerate expects exactly 1 argument (seq)"),
+    }
+}
+
+/// Seq::toArray(seq) -> array of every value (never returns for unbounded seqs)
+/// (also available as Seq::collect)
+pub(crate) fn seq_to_array(args: Vec<Object>, env: EnvRef) -> Object {
+    let seq = match <[Object; 1]>::try_from(args) {
+        Ok([obj]) => match expect_seq(obj, "Seq::toArray") {
+            Ok(seq) => seq,
+            Err(e) => return e,
+        },
+        Err(_) => return Object::error("Seq::toArray expects exactly 1 argument (seq)"),
+    };
+
+    let mut out = Vec::new();
+    for value in iterate(&seq, &env) {
+        if value.is_error() {
+            return value;
+        }
+        out.push(value);
+    }
+    Object::array(out)
+}
@@ -106,7 +106,7 @@ pub(crate) fn string_split(args: Vec<Object>, _env: EnvRef) -> Object {
             .collect()
     };
 
-    Object::Array(parts)
+    Object::array(parts)
 }
 
 /// String::join(arr, sep) – joins an array of strings with a separator.
@@ -137,9 +137,9 @@ pub(crate) fn string_join(args: Vec<Object>, _env: EnvRef) -> Object {
     };
 
     let mut out_parts = Vec::new();
-    for el in elements {
+    for el in elements.iter() {
         match el {
-            Object::String(s) => out_parts.push(s),
+            Object::String(s) => out_parts.push(s.as_str()),
             other => {
                 return Object::error(format!(
                     "String::join expects array of strings, found element {:?}",
@@ -505,8 +505,7 @@ pub(crate) fn string_pad_left(args: Vec<Object>, _env: EnvRef) -> Object {
         return Object::String(s_val);
     }
 
-    let padding: String = std::iter::repeat(pad_char_val)
-        .take(target_len - current_len)
+    let padding: String = std::iter::repeat_n(pad_char_val, target_len - current_len)
         .collect();
 
     Object::String(format!("{}{}", padding, s_val))
@@ -566,8 +565,7 @@ pub(crate) fn string_pad_right(args: Vec<Object>, _env: EnvRef) -> Object {
         return Object::String(s_val);
     }
 
-    let padding: String = std::iter::repeat(pad_char_val)
-        .take(target_len - current_len)
+    let padding: String = std::iter::repeat_n(pad_char_val, target_len - current_len)
         .collect();
 
     Object::String(format!("{}{}", s_val, padding))
@@ -587,7 +585,7 @@ pub(crate) fn string_chars(args: Vec<Object>, _env: EnvRef) -> Object {
                 .chars()
                 .map(|c| Object::String(c.to_string()))
                 .collect();
-            Object::Array(chars)
+            Object::array(chars)
         }
         other => Object::error(format!("String::chars expects a string, got {:?}", other)),
     }
@@ -805,7 +803,7 @@ pub(crate) fn string_char_codes(args: Vec<Object>, _env: EnvRef) -> Object {
                 .chars()
                 .map(|c| Object::Integer(c as i64))
                 .collect();
-            Object::Array(codes)
+            Object::array(codes)
         }
         other => Object::error(format!("String::charCodes expects a string, got {:?}", other)),
     }
@@ -838,3 +836,609 @@ pub(crate) fn string_len(args: Vec<Object>, _env: EnvRef) -> Object {
         other => Object::error(format!("String::len expects a string, got {:?}", other)),
     }
 }
+
+/// Unwrap the string arguments of a two-string builtin like `naturalCompare`.
+fn expect_two_strings(args: Vec<Object>, name: &str) -> Result<(String, String), Object> {
+    match expect_two_args(args, name)? {
+        (Object::String(a), Object::String(b)) => Ok((a, b)),
+        (a, b) => Err(Object::error(format!(
+            "{name} expects two strings, got {:?} and {:?}",
+            a, b
+        ))),
+    }
+}
+
+/// String::words(s) -> array
+/// Splits text into words: runs of letters, digits, `_` and apostrophes, so
+/// punctuation and whitespace are dropped ("don't stop!" -> ["don't", "stop"]).
+pub(crate) fn string_words(args: Vec<Object>, _env: EnvRef) -> Object {
+    let s = match expect_one_arg(args, "String::words") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    match s {
+        Object::String(val) => Object::array(
+            val.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '\''))
+                .map(|w| w.trim_matches('\''))
+                .filter(|w| !w.is_empty())
+                .map(|w| Object::String(w.to_string()))
+                .collect(),
+        ),
+        other => Object::error(format!("String::words expects a string, got {:?}", other)),
+    }
+}
+
+/// String::lines(s) -> array
+/// Splits on `\n` or `\r\n`; a trailing newline does not produce an empty last line.
+pub(crate) fn string_lines(args: Vec<Object>, _env: EnvRef) -> Object {
+    let s = match expect_one_arg(args, "String::lines") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    match s {
+        Object::String(val) => Object::array(
+            val.lines()
+                .map(|line| Object::String(line.to_string()))
+                .collect(),
+        ),
+        other => Object::error(format!("String::lines expects a string, got {:?}", other)),
+    }
+}
+
+/// Compare runs of digits by numeric value and everything else by character,
+/// so "file2" < "file10". Ties (e.g. "01" vs "1") fall back to plain ordering.
+fn natural_cmp(a: &str, b: &str) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+
+    let mut xs = a.chars().peekable();
+    let mut ys = b.chars().peekable();
+    loop {
+        match (xs.peek().copied(), ys.peek().copied()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let take_digits = |it: &mut std::iter::Peekable<std::str::Chars>| {
+                    let mut run = String::new();
+                    while let Some(c) = it.next_if(|c| c.is_ascii_digit()) {
+                        run.push(c);
+                    }
+                    run
+                };
+                let (run_x, run_y) = (take_digits(&mut xs), take_digits(&mut ys));
+                let (num_x, num_y) = (run_x.trim_start_matches('0'), run_y.trim_start_matches('0'));
+                let ord = num_x.len().cmp(&num_y.len()).then_with(|| num_x.cmp(num_y));
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(&y);
+                }
+                xs.next();
+                ys.next();
+            }
+        }
+    }
+}
+
+/// String::naturalCompare(a, b) -> integer
+/// Returns -1, 0 or 1, comparing embedded numbers by value. Usable directly
+/// as an `Array::sortBy` comparator.
+pub(crate) fn string_natural_compare(args: Vec<Object>, _env: EnvRef) -> Object {
+    match expect_two_strings(args, "String::naturalCompare") {
+        Ok((a, b)) => Object::Integer(natural_cmp(&a, &b) as i64),
+        Err(e) => e,
+    }
+}
+
+/// String::levenshtein(a, b) -> integer
+/// Returns the edit distance (insertions, deletions, substitutions) between
+/// two strings, counted in characters.
+pub(crate) fn string_levenshtein(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (a, b) = match expect_two_strings(args, "String::levenshtein") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut row = Vec::with_capacity(b.len() + 1);
+        row.push(i + 1);
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            row.push(substitution.min(prev[j + 1] + 1).min(row[j] + 1));
+        }
+        prev = row;
+    }
+    Object::Integer(prev[b.len()] as i64)
+}
+
+/// Split an identifier-like string into words for case conversion. Words
+/// break at separators (anything that isn't a letter or digit), at a
+/// lower-to-upper change ("fooBar") and before the last capital of an
+/// acronym ("HTTPServer" -> "HTTP", "Server").
+fn case_words(s: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let chars: Vec<char> = s.chars().collect();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if c.is_uppercase() && !current.is_empty() {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_is_lower) {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// Uppercase the first character and lowercase the rest.
+fn title_word(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.as_str().to_lowercase().chars()).collect(),
+        None => String::new(),
+    }
+}
+
+/// Shared body of the `String::to*Case` builtins.
+fn convert_case(args: Vec<Object>, name: &str, convert: fn(Vec<String>) -> String) -> Object {
+    match expect_one_arg(args, name) {
+        Ok(Object::String(s)) => Object::String(convert(case_words(&s))),
+        Ok(other) => Object::error(format!("{name} expects a string, got {:?}", other)),
+        Err(e) => e,
+    }
+}
+
+/// String::toCamelCase(s) – "user_id", "User ID" and "userID" all become "userId".
+pub(crate) fn string_to_camel_case(args: Vec<Object>, _env: EnvRef) -> Object {
+    convert_case(args, "String::toCamelCase", |words| {
+        words
+            .iter()
+            .enumerate()
+            .map(|(i, w)| if i == 0 { w.to_lowercase() } else { title_word(w) })
+            .collect()
+    })
+}
+
+/// String::toSnakeCase(s) – "parseHTTPResponse" becomes "parse_http_response".
+pub(crate) fn string_to_snake_case(args: Vec<Object>, _env: EnvRef) -> Object {
+    convert_case(args, "String::toSnakeCase", |words| {
+        words.iter().map(|w| w.to_lowercase()).collect::<Vec<_>>().join("_")
+    })
+}
+
+/// String::toKebabCase(s) – "parseHTTPResponse" becomes "parse-http-response".
+pub(crate) fn string_to_kebab_case(args: Vec<Object>, _env: EnvRef) -> Object {
+    convert_case(args, "String::toKebabCase", |words| {
+        words.iter().map(|w| w.to_lowercase()).collect::<Vec<_>>().join("-")
+    })
+}
+
+/// String::toTitleCase(s) – "max_retry-count" becomes "Max Retry Count".
+pub(crate) fn string_to_title_case(args: Vec<Object>, _env: EnvRef) -> Object {
+    convert_case(args, "String::toTitleCase", |words| {
+        words.iter().map(|w| title_word(w)).collect::<Vec<_>>().join(" ")
+    })
+}
+
+/// String::capitalize(s) – uppercases the first character and leaves the rest
+/// alone, so acronyms survive ("nASA rocks" -> "NASA rocks").
+pub(crate) fn string_capitalize(args: Vec<Object>, _env: EnvRef) -> Object {
+    match expect_one_arg(args, "String::capitalize") {
+        Ok(Object::String(s)) => {
+            let mut chars = s.chars();
+            Object::String(match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            })
+        }
+        Ok(other) => Object::error(format!("String::capitalize expects a string, got {:?}", other)),
+        Err(e) => e,
+    }
+}
+
+/// String::truncate(s, len[, ellipsis]) -> string
+/// Shortens s to at most len characters, ending it with ellipsis (default
+/// "...") when anything was cut. If len is too small to fit the ellipsis,
+/// the first len characters are returned without one.
+pub(crate) fn string_truncate(mut args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.len() != 2 && args.len() != 3 {
+        return Object::error("String::truncate expects 2 or 3 arguments (s, len[, ellipsis])");
+    }
+
+    let ellipsis = if args.len() == 3 {
+        match args.pop().unwrap() {
+            Object::String(v) => v,
+            other => {
+                return Object::error(format!(
+                    "String::truncate expects string as third argument, got {:?}",
+                    other
+                ))
+            }
+        }
+    } else {
+        "...".to_string()
+    };
+
+    let len_val = match args.pop().unwrap() {
+        Object::Integer(i) if i >= 0 => i as usize,
+        Object::Integer(_) => return Object::error("String::truncate length must be non-negative"),
+        other => {
+            return Object::error(format!(
+                "String::truncate expects integer as second argument, got {:?}",
+                other
+            ))
+        }
+    };
+
+    let s_val = match args.pop().unwrap() {
+        Object::String(v) => v,
+        other => {
+            return Object::error(format!(
+                "String::truncate expects string as first argument, got {:?}",
+                other
+            ))
+        }
+    };
+
+    if s_val.chars().count() <= len_val {
+        return Object::String(s_val);
+    }
+
+    let ellipsis_len = ellipsis.chars().count();
+    if len_val <= ellipsis_len {
+        return Object::String(s_val.chars().take(len_val).collect());
+    }
+    let kept: String = s_val.chars().take(len_val - ellipsis_len).collect();
+    Object::String(kept.trim_end().to_string() + &ellipsis)
+}
+
+/// Greedily wrap one line of text to `width` characters, splitting words
+/// that are longer than a whole line.
+fn wrap_line(line: &str, width: usize, out: &mut Vec<String>) {
+    let mut current = String::new();
+    let mut current_len = 0;
+
+    for word in line.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        if current_len > 0 && current_len + 1 + word.len() <= width {
+            current.push(' ');
+            current.extend(&word);
+            current_len += 1 + word.len();
+            continue;
+        }
+        if current_len > 0 {
+            out.push(std::mem::take(&mut current));
+        }
+        while word.len() > width {
+            out.push(word.drain(..width).collect());
+        }
+        current_len = word.len();
+        current = word.into_iter().collect();
+    }
+
+    if current_len > 0 || out.is_empty() {
+        out.push(current);
+    }
+}
+
+/// String::wrap(s, width) -> array
+/// Word-wraps s into lines of at most width characters. Existing line
+/// breaks are kept, so blank lines still separate paragraphs.
+pub(crate) fn string_wrap(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (s, width) = match expect_two_args(args, "String::wrap") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let s_val = match s {
+        Object::String(v) => v,
+        other => {
+            return Object::error(format!(
+                "String::wrap expects string as first argument, got {:?}",
+                other
+            ))
+        }
+    };
+
+    let width_val = match width {
+        Object::Integer(i) if i > 0 => i as usize,
+        Object::Integer(_) => return Object::error("String::wrap width must be positive"),
+        other => {
+            return Object::error(format!(
+                "String::wrap expects integer as second argument, got {:?}",
+                other
+            ))
+        }
+    };
+
+    let mut lines = Vec::new();
+    for line in s_val.lines() {
+        let mut wrapped = Vec::new();
+        wrap_line(line, width_val, &mut wrapped);
+        lines.extend(wrapped);
+    }
+    Object::array(lines.into_iter().map(Object::String).collect())
+}
+
+/// String::indent(s, prefix) -> string
+/// Puts prefix in front of every non-blank line of s.
+pub(crate) fn string_indent(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (s_val, prefix) = match expect_two_strings(args, "String::indent") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let indented: String = s_val
+        .split_inclusive('\n')
+        .map(|line| {
+            if line.trim().is_empty() {
+                line.to_string()
+            } else {
+                format!("{}{}", prefix, line)
+            }
+        })
+        .collect();
+    Object::String(indented)
+}
+
+/// The `:...` part of a `String::format` placeholder:
+/// `[[fill]align][+][0][width][.precision][type]`.
+struct FormatSpec {
+    fill: char,
+    align: Option<char>,
+    plus: bool,
+    zero: bool,
+    width: usize,
+    precision: Option<usize>,
+    kind: Option<char>,
+}
+
+/// Largest width or precision a placeholder may ask for; Rust's formatter
+/// takes no larger precision, and a huge width would be a huge allocation.
+const MAX_FORMAT_SIZE: usize = u16::MAX as usize;
+
+/// A width or precision from a format spec, checked against
+/// [`MAX_FORMAT_SIZE`].
+fn format_size(digits: &str, what: &str) -> Result<usize, String> {
+    match digits.parse::<usize>() {
+        Ok(n) if n <= MAX_FORMAT_SIZE => Ok(n),
+        _ => Err(format!("{} {} is too large (at most {})", what, digits, MAX_FORMAT_SIZE)),
+    }
+}
+
+fn parse_format_spec(spec: &str) -> Result<FormatSpec, String> {
+    let chars: Vec<char> = spec.chars().collect();
+    let mut out = FormatSpec {
+        fill: ' ',
+        align: None,
+        plus: false,
+        zero: false,
+        width: 0,
+        precision: None,
+        kind: None,
+    };
+    let mut i = 0;
+    let is_align = |c: &char| matches!(c, '<' | '^' | '>');
+
+    if chars.get(1).is_some_and(is_align) {
+        out.fill = chars[0];
+        out.align = Some(chars[1]);
+        i = 2;
+    } else if chars.first().is_some_and(is_align) {
+        out.align = Some(chars[0]);
+        i = 1;
+    }
+    if chars.get(i) == Some(&'+') {
+        out.plus = true;
+        i += 1;
+    }
+    if chars.get(i) == Some(&'0') {
+        out.zero = true;
+        i += 1;
+    }
+    let digits = |i: &mut usize| {
+        let start = *i;
+        while chars.get(*i).is_some_and(char::is_ascii_digit) {
+            *i += 1;
+        }
+        chars[start..*i].iter().collect::<String>()
+    };
+    let width = digits(&mut i);
+    if !width.is_empty() {
+        out.width = format_size(&width, "width")?;
+    }
+    if chars.get(i) == Some(&'.') {
+        i += 1;
+        let precision = digits(&mut i);
+        if precision.is_empty() {
+            return Err(format!("missing precision after '.' in {{:{}}}", spec));
+        }
+        out.precision = Some(format_size(&precision, "precision")?);
+    }
+    match chars.get(i) {
+        None => {}
+        Some(c @ ('x' | 'X' | 'b' | 'o' | 'e' | '?')) if i + 1 == chars.len() => out.kind = Some(*c),
+        Some(_) => return Err(format!("invalid format spec {{:{}}}", spec)),
+    }
+    Ok(out)
+}
+
+/// Lay out one argument according to `spec`. Strings go in as they are
+/// (no quotes); `{:?}` shows a value as `print` would.
+fn format_value(value: &Object, spec: &FormatSpec) -> Result<String, String> {
+    let (sign, body) = match (spec.kind, value) {
+        (Some('?'), v) => (String::new(), v.to_string()),
+        (Some(kind @ ('x' | 'X' | 'b' | 'o')), Object::Integer(n)) => {
+            let abs = n.unsigned_abs();
+            let digits = match kind {
+                'x' => format!("{:x}", abs),
+                'X' => format!("{:X}", abs),
+                'b' => format!("{:b}", abs),
+                _ => format!("{:o}", abs),
+            };
+            (number_sign(*n < 0, spec.plus), digits)
+        }
+        (Some(kind @ ('x' | 'X' | 'b' | 'o')), other) => {
+            return Err(format!("{{:{}}} expects an integer, got {}", kind, other.type_name()));
+        }
+        (Some('e'), Object::Integer(_) | Object::Float(_)) => {
+            let x = number_value(value);
+            let digits = match spec.precision {
+                Some(p) => format!("{:.*e}", p, x.abs()),
+                None => format!("{:e}", x.abs()),
+            };
+            (number_sign(x.is_sign_negative() && x != 0.0, spec.plus), digits)
+        }
+        (Some('e'), other) => {
+            return Err(format!("{{:e}} expects a number, got {}", other.type_name()));
+        }
+        (_, Object::Integer(n)) if spec.precision.is_none() => {
+            (number_sign(*n < 0, spec.plus), n.unsigned_abs().to_string())
+        }
+        (_, Object::Integer(_) | Object::Float(_)) => {
+            let x = number_value(value);
+            let digits = match spec.precision {
+                Some(p) => format!("{:.*}", p, x.abs()),
+                None => Object::Float(x.abs()).to_string(),
+            };
+            (number_sign(x.is_sign_negative() && x != 0.0, spec.plus), digits)
+        }
+        (_, Object::String(s)) => match spec.precision {
+            Some(p) => (String::new(), s.chars().take(p).collect()),
+            None => (String::new(), s.clone()),
+        },
+        (_, other) => (String::new(), other.to_string()),
+    };
+
+    let numeric = matches!(value, Object::Integer(_) | Object::Float(_)) && spec.kind != Some('?');
+    let len = sign.chars().count() + body.chars().count();
+    let pad = spec.width.saturating_sub(len);
+    if spec.zero && numeric && spec.align.is_none() {
+        return Ok(format!("{}{}{}", sign, "0".repeat(pad), body));
+    }
+
+    let text = sign + &body;
+    let fill = |n: usize| spec.fill.to_string().repeat(n);
+    // Numbers line up on the right by default, everything else on the left.
+    Ok(match spec.align.unwrap_or(if numeric { '>' } else { '<' }) {
+        '<' => text + &fill(pad),
+        '>' => fill(pad) + &text,
+        _ => fill(pad / 2) + &text + &fill(pad - pad / 2),
+    })
+}
+
+fn number_value(value: &Object) -> f64 {
+    match value {
+        Object::Integer(n) => *n as f64,
+        Object::Float(x) => *x,
+        _ => 0.0,
+    }
+}
+
+fn number_sign(negative: bool, plus: bool) -> String {
+    match (negative, plus) {
+        (true, _) => "-".to_string(),
+        (false, true) => "+".to_string(),
+        (false, false) => String::new(),
+    }
+}
+
+/// Fill the `{}` placeholders in `template` from `args`. Placeholders take
+/// the next argument (`{}`), a numbered one (`{1}`), or a field of a single
+/// object argument (`{name}`), optionally followed by `:spec`. `{{` and
+/// `}}` stand for literal braces.
+pub(crate) fn format_template(template: &str, args: &[Object]) -> Result<String, String> {
+    let mut out = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+    let mut next_arg = 0;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                out.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                out.push('}');
+            }
+            '}' => return Err("unmatched '}' (write '}}' for a literal brace)".to_string()),
+            '{' => {
+                let mut inner = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => inner.push(c),
+                        None => return Err("unclosed '{' (write '{{' for a literal brace)".to_string()),
+                    }
+                }
+                let (selector, spec) = match inner.split_once(':') {
+                    Some((selector, spec)) => (selector.trim(), parse_format_spec(spec)?),
+                    None => (inner.trim(), parse_format_spec("")?),
+                };
+
+                let value = if selector.is_empty() {
+                    next_arg += 1;
+                    args.get(next_arg - 1).ok_or_else(|| {
+                        format!("placeholder {} has no argument (got {})", next_arg, args.len())
+                    })?
+                } else if let Ok(index) = selector.parse::<usize>() {
+                    args.get(index).ok_or_else(|| {
+                        format!("placeholder {{{}}} has no argument (got {})", index, args.len())
+                    })?
+                } else {
+                    match args {
+                        [Object::Object(fields)] => fields
+                            .get(selector)
+                            .ok_or_else(|| format!("no field '{}' for placeholder {{{}}}", selector, selector))?,
+                        _ => {
+                            return Err(format!(
+                                "named placeholder {{{}}} needs a single object argument",
+                                selector
+                            ))
+                        }
+                    }
+                };
+                out.push_str(&format_value(value, &spec)?);
+            }
+            c => out.push(c),
+        }
+    }
+    Ok(out)
+}
+
+/// String::format(template, ...args) -> string
+/// Rust-style placeholders: `{}`, `{0}`, `{name}`, each optionally with a
+/// spec such as `{:>8}`, `{:.2}`, `{:08.3}`, `{:*^10}`, `{:+}` or `{:x}`.
+pub(crate) fn string_format(args: Vec<Object>, _env: EnvRef) -> Object {
+    match args.split_first() {
+        Some((Object::String(template), rest)) => match format_template(template, rest) {
+            Ok(text) => Object::String(text),
+            Err(e) => Object::error(format!("String::format: {}", e)),
+        },
+        Some((other, _)) => Object::error(format!(
+            "String::format expects a template string as first argument, got {:?}",
+            other
+        )),
+        None => Object::error("String::format expects a template string and arguments"),
+    }
+}
@@ -1,9 +1,11 @@
-use std::collections::HashMap;
 use std::env;
 use std::process::{Command, exit};
 
+use crate::build_info::build_info;
+use crate::capability;
 use crate::env::EnvRef;
-use crate::object::Object;
+use crate::event_loop;
+use crate::object::{Object, ObjectMap};
 
 fn expect_one_arg(mut args: Vec<Object>, name: &str) -> Result<Object, Object> {
     if args.len() != 1 {
@@ -17,11 +19,11 @@ fn expect_one_arg(mut args: Vec<Object>, name: &str) -> Result<Object, Object> {
 pub(crate) fn sys_env(args: Vec<Object>, _env: EnvRef) -> Object {
     if args.is_empty() {
         // Return all environment variables as an object
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         for (key, value) in env::vars() {
             map.insert(key, Object::String(value));
         }
-        return Object::Object(map);
+        return Object::from_map(map);
     }
 
     if args.len() != 1 {
@@ -45,7 +47,11 @@ pub(crate) fn sys_env(args: Vec<Object>, _env: EnvRef) -> Object {
 }
 
 /// Sys::setEnv(name, value) -> null (sets an environment variable)
-pub(crate) fn sys_set_env(mut args: Vec<Object>, _env: EnvRef) -> Object {
+pub(crate) fn sys_set_env(args: Vec<Object>, env: EnvRef) -> Object {
+    capability::audited("Sys::setEnv", args, env, set_env_var)
+}
+
+fn set_env_var(mut args: Vec<Object>, _env: EnvRef) -> Object {
     if args.len() != 2 {
         return Object::error("Sys::setEnv expects exactly 2 arguments (name, value)");
     }
@@ -87,12 +93,12 @@ pub(crate) fn sys_args(args: Vec<Object>, _env: EnvRef) -> Object {
         return Object::error("Sys::args expects no arguments");
     }
 
-    let args: Vec<Object> = env::args().map(|a| Object::String(a)).collect();
-    Object::Array(args)
+    let args: Vec<Object> = env::args().map(Object::String).collect();
+    Object::array(args)
 }
 
 /// Sys::exit(code) -> never returns (exits the process)
-pub(crate) fn sys_exit(args: Vec<Object>, _env: EnvRef) -> Object {
+pub(crate) fn sys_exit(args: Vec<Object>, env: EnvRef) -> Object {
     let code = match expect_one_arg(args, "Sys::exit") {
         Ok(v) => v,
         Err(e) => return e,
@@ -108,6 +114,12 @@ pub(crate) fn sys_exit(args: Vec<Object>, _env: EnvRef) -> Object {
         }
     };
 
+    // The process ends here, so record the call and run the shutdown hooks
+    // before exiting.
+    capability::record_audit("Sys::exit", &[code_val.to_string()], "ok", None);
+    if let Object::Error(msg) = event_loop::shutdown(env) {
+        eprintln!("{}", msg);
+    }
     exit(code_val);
 }
 
@@ -124,7 +136,11 @@ pub(crate) fn sys_cwd(args: Vec<Object>, _env: EnvRef) -> Object {
 }
 
 /// Sys::setCwd(path) -> Result (changes the current working directory)
-pub(crate) fn sys_set_cwd(args: Vec<Object>, _env: EnvRef) -> Object {
+pub(crate) fn sys_set_cwd(args: Vec<Object>, env: EnvRef) -> Object {
+    capability::audited("Sys::setCwd", args, env, change_cwd)
+}
+
+fn change_cwd(args: Vec<Object>, _env: EnvRef) -> Object {
     let path = match expect_one_arg(args, "Sys::setCwd") {
         Ok(v) => v,
         Err(e) => return e,
@@ -151,7 +167,11 @@ pub(crate) fn sys_set_cwd(args: Vec<Object>, _env: EnvRef) -> Object {
 
 /// Sys::exec(command) -> Result({ stdout, stderr, code })
 /// Executes a shell command and returns the result
-pub(crate) fn sys_exec(args: Vec<Object>, _env: EnvRef) -> Object {
+pub(crate) fn sys_exec(args: Vec<Object>, env: EnvRef) -> Object {
+    capability::audited("Sys::exec", args, env, exec_command)
+}
+
+fn exec_command(args: Vec<Object>, _env: EnvRef) -> Object {
     let cmd = match expect_one_arg(args, "Sys::exec") {
         Ok(v) => v,
         Err(e) => return e,
@@ -167,6 +187,15 @@ pub(crate) fn sys_exec(args: Vec<Object>, _env: EnvRef) -> Object {
         }
     };
 
+    if capability::is_dry_run() {
+        capability::log_dry_run("Sys::exec", &format!("would run `{}`", cmd_str));
+        let mut map = ObjectMap::new();
+        map.insert("stdout".to_string(), Object::String(String::new()));
+        map.insert("stderr".to_string(), Object::String(String::new()));
+        map.insert("code".to_string(), Object::Integer(0));
+        return Object::ResultOk(Box::new(Object::from_map(map)));
+    }
+
     // Use shell to execute the command
     let output = if cfg!(target_os = "windows") {
         Command::new("cmd").args(["/C", &cmd_str]).output()
@@ -176,7 +205,7 @@ pub(crate) fn sys_exec(args: Vec<Object>, _env: EnvRef) -> Object {
 
     match output {
         Ok(result) => {
-            let mut map = HashMap::new();
+            let mut map = ObjectMap::new();
             map.insert(
                 "stdout".to_string(),
                 Object::String(String::from_utf8_lossy(&result.stdout).to_string()),
@@ -191,9 +220,9 @@ pub(crate) fn sys_exec(args: Vec<Object>, _env: EnvRef) -> Object {
             );
 
             if result.status.success() {
-                Object::ResultOk(Box::new(Object::Object(map)))
+                Object::ResultOk(Box::new(Object::from_map(map)))
             } else {
-                Object::ResultErr(Box::new(Object::Object(map)))
+                Object::ResultErr(Box::new(Object::from_map(map)))
             }
         }
         Err(e) => Object::ResultErr(Box::new(Object::String(format!(
@@ -203,21 +232,72 @@ pub(crate) fn sys_exec(args: Vec<Object>, _env: EnvRef) -> Object {
     }
 }
 
-/// Sys::platform() -> string (operating system name)
-pub(crate) fn sys_platform(args: Vec<Object>, _env: EnvRef) -> Object {
+/// Sys::arch() -> string (CPU architecture)
+pub(crate) fn sys_arch(args: Vec<Object>, _env: EnvRef) -> Object {
+    if !args.is_empty() {
+        return Object::error("Sys::arch expects no arguments");
+    }
+
+    Object::String(env::consts::ARCH.to_string())
+}
+
+/// Sys::dryRun() -> boolean (true when side-effecting builtins are being simulated)
+pub(crate) fn sys_dry_run(args: Vec<Object>, _env: EnvRef) -> Object {
     if !args.is_empty() {
-        return Object::error("Sys::platform expects no arguments");
+        return Object::error("Sys::dryRun expects no arguments");
     }
 
-    Object::String(env::consts::OS.to_string())
+    Object::Boolean(capability::is_dry_run())
 }
 
-/// Sys::arch() -> string (CPU architecture)
-pub(crate) fn sys_arch(args: Vec<Object>, _env: EnvRef) -> Object {
+/// Sys::version() -> string (semver of the running interpreter)
+pub(crate) fn sys_version(args: Vec<Object>, _env: EnvRef) -> Object {
     if !args.is_empty() {
-        return Object::error("Sys::arch expects no arguments");
+        return Object::error("Sys::version expects no arguments");
     }
 
-    Object::String(env::consts::ARCH.to_string())
+    Object::String(env!("CARGO_PKG_VERSION").to_string())
 }
 
+/// Sys::hasNamespace(name) -> boolean (true if `name` is bound to a namespace)
+pub(crate) fn sys_has_namespace(args: Vec<Object>, env: EnvRef) -> Object {
+    let name = match expect_one_arg(args, "Sys::hasNamespace") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    let name_str = match name {
+        Object::String(s) => s,
+        other => {
+            return Object::error(format!(
+                "Sys::hasNamespace expects string name, got {:?}",
+                other
+            ))
+        }
+    };
+
+    let bound = env.borrow().get(&name_str);
+    Object::Boolean(matches!(bound, Some(Object::Object(_))))
+}
+
+/// Sys::features() -> array of strings (enabled cargo features and active runtime modes)
+pub(crate) fn sys_features(args: Vec<Object>, _env: EnvRef) -> Object {
+    if !args.is_empty() {
+        return Object::error("Sys::features expects no arguments");
+    }
+
+    let mut features = build_info().features;
+    if capability::is_dry_run() {
+        features.push("dry-run");
+    }
+    if capability::is_auditing() {
+        features.push("audit");
+    }
+
+    Object::array(
+        features
+            .into_iter()
+            .map(|f| Object::String(f.to_string()))
+            .collect(),
+    )
+}
@@ -0,0 +1,127 @@
+//! The `Task` and `Channel` namespaces; see [`crate::task`] for how values
+//! move between tasks.
+
+use crate::env::EnvRef;
+use crate::object::Object;
+use crate::task::core as task;
+
+/// The handle argument of `name`.
+fn handle_arg(args: &[Object], name: &str, what: &str) -> Result<i64, Object> {
+    match args.first() {
+        Some(Object::Integer(id)) => Ok(*id),
+        Some(other) => Err(Object::error(format!(
+            "{} expects a {} handle, got {}",
+            name,
+            what,
+            other.type_name()
+        ))),
+        None => Err(Object::error(format!("{} expects a {} handle", name, what))),
+    }
+}
+
+/// Task::spawn(fn, ...args) -> handle (runs fn(args...) on its own thread)
+pub(crate) fn task_spawn(args: Vec<Object>, env: EnvRef) -> Object {
+    let Some((callback, rest)) = args.split_first() else {
+        return Object::error("Task::spawn expects a function and its arguments");
+    };
+    if !callback.is_callable() {
+        return Object::error(format!(
+            "Task::spawn expects a function, got {}",
+            callback.type_name()
+        ));
+    }
+    match task::spawn(callback, rest, &env) {
+        Ok(id) => Object::Integer(id),
+        Err(e) => Object::error(e),
+    }
+}
+
+/// Task::join(handle) -> the task's return value (waits for it to finish)
+pub(crate) fn task_join(args: Vec<Object>, env: EnvRef) -> Object {
+    if args.len() != 1 {
+        return Object::error("Task::join expects exactly 1 argument (handle)");
+    }
+    match handle_arg(&args, "Task::join", "task") {
+        Ok(id) => task::join(id, &env),
+        Err(e) => e,
+    }
+}
+
+/// Task::isDone(handle) -> bool
+pub(crate) fn task_is_done(args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.len() != 1 {
+        return Object::error("Task::isDone expects exactly 1 argument (handle)");
+    }
+    let id = match handle_arg(&args, "Task::isDone", "task") {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+    match task::is_done(id) {
+        Some(done) => Object::Boolean(done),
+        None => Object::error(format!("Task::isDone: no running task {}", id)),
+    }
+}
+
+/// Channel::new() -> handle
+pub(crate) fn channel_new(args: Vec<Object>, _env: EnvRef) -> Object {
+    if !args.is_empty() {
+        return Object::error("Channel::new expects no arguments");
+    }
+    Object::Integer(task::channel_new())
+}
+
+/// Channel::send(ch, value) -> null
+pub(crate) fn channel_send(args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.len() != 2 {
+        return Object::error("Channel::send expects 2 arguments (channel, value)");
+    }
+    let id = match handle_arg(&args, "Channel::send", "channel") {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+    match task::channel_send(id, &args[1]) {
+        Ok(()) => Object::Null,
+        Err(e) => Object::error(e),
+    }
+}
+
+fn receive(args: Vec<Object>, env: EnvRef, name: &str, wait: bool) -> Object {
+    if args.len() != 1 {
+        return Object::error(format!("{} expects exactly 1 argument (channel)", name));
+    }
+    let id = match handle_arg(&args, name, "channel") {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+    match task::channel_recv(id, wait, &env) {
+        Ok(Some(value)) => Object::OptionSome(Box::new(value)),
+        Ok(None) => Object::OptionNone,
+        Err(e) => Object::error(e),
+    }
+}
+
+/// Channel::recv(ch) -> Some(value), or None once the channel is closed and
+/// drained. Waits while the channel is open and empty.
+pub(crate) fn channel_recv(args: Vec<Object>, env: EnvRef) -> Object {
+    receive(args, env, "Channel::recv", true)
+}
+
+/// Channel::tryRecv(ch) -> Some(value) or None, without waiting
+pub(crate) fn channel_try_recv(args: Vec<Object>, env: EnvRef) -> Object {
+    receive(args, env, "Channel::tryRecv", false)
+}
+
+/// Channel::close(ch) -> null
+pub(crate) fn channel_close(args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.len() != 1 {
+        return Object::error("Channel::close expects exactly 1 argument (channel)");
+    }
+    let id = match handle_arg(&args, "Channel::close", "channel") {
+        Ok(id) => id,
+        Err(e) => return e,
+    };
+    match task::channel_close(id) {
+        Ok(()) => Object::Null,
+        Err(e) => Object::error(e),
+    }
+}
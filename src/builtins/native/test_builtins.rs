@@ -1,16 +1,89 @@
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde_json::Value;
+
+use crate::builtins::native::json_builtins::to_json_value;
+use crate::diff::diff_values;
 use crate::env::EnvRef;
-use crate::object::Object;
+use crate::evaluator::core::expr::apply_function_with_this;
+use crate::object::{Object, from_slang};
+
+/// Longest rendering of a value in a failure message, in characters.
+const MAX_VALUE_CHARS: usize = 200;
+/// Longest single diff line, in characters.
+const MAX_DIFF_LINE_CHARS: usize = 160;
+/// Differences listed before the rest are summarised as a count.
+const MAX_DIFF_LINES: usize = 20;
+
+thread_local! {
+    /// Assertions made since the test runner last reset the count.
+    static ASSERTIONS: Cell<usize> = const { Cell::new(0) };
+    /// The snapshot file `Test::assertSnapshot` checks against, while a test
+    /// run has one open.
+    static SNAPSHOTS: RefCell<Option<Snapshots>> = const { RefCell::new(None) };
+}
+
+/// Stored snapshots for one script: a JSON object of snapshot names to values.
+struct Snapshots {
+    path: PathBuf,
+    update: bool,
+    stored: Result<BTreeMap<String, Value>, String>,
+    written: usize,
+}
+
+/// Open the snapshot file at `path` for the tests about to run. With
+/// `update`, mismatched snapshots are overwritten instead of failing.
+pub fn begin_snapshots(path: PathBuf, update: bool) {
+    let stored = match std::fs::read_to_string(&path) {
+        Ok(text) => serde_json::from_str(&text)
+            .map_err(|e| format!("can't read snapshots from {}: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(e) => Err(format!("can't read snapshots from {}: {}", path.display(), e)),
+    };
+    SNAPSHOTS.with(|s| {
+        *s.borrow_mut() = Some(Snapshots { path, update, stored, written: 0 });
+    });
+}
+
+/// Close the snapshot file, saving it if any snapshot was written. Returns
+/// how many were written.
+pub fn finish_snapshots() -> Result<usize, String> {
+    let Some(snapshots) = SNAPSHOTS.with(|s| s.borrow_mut().take()) else {
+        return Ok(0);
+    };
+    let stored = match snapshots.stored {
+        Ok(stored) if snapshots.written > 0 => stored,
+        _ => return Ok(0),
+    };
+    let text = serde_json::to_string_pretty(&stored).map_err(|e| e.to_string())? + "\n";
+    std::fs::write(&snapshots.path, text)
+        .map(|()| snapshots.written)
+        .map_err(|e| format!("can't write snapshots to {}: {}", snapshots.path.display(), e))
+}
+
+/// Start counting assertions afresh, e.g. at the start of a test.
+pub fn reset_assertion_count() {
+    ASSERTIONS.with(|count| count.set(0));
+}
+
+/// Assertions made since the last [`reset_assertion_count`].
+pub fn assertion_count() -> usize {
+    ASSERTIONS.with(|count| count.get())
+}
+
+fn count_assertion() {
+    ASSERTIONS.with(|count| count.set(count.get() + 1));
+}
 
 fn is_truthy(obj: &Object) -> bool {
-    match obj {
-        Object::Boolean(false) => false,
-        Object::Null => false,
-        _ => true,
-    }
+    !matches!(obj, Object::Boolean(false) | Object::Null)
 }
 
 pub fn test_assert(args: Vec<Object>, _env: EnvRef) -> Object {
-    if args.len() == 0 || args.len() > 2 {
+    count_assertion();
+    if args.is_empty() || args.len() > 2 {
         return Object::error("Test::assert expects 1 or 2 arguments (condition, optional message)");
     }
 
@@ -34,6 +107,7 @@ pub fn test_assert(args: Vec<Object>, _env: EnvRef) -> Object {
 }
 
 pub fn test_assert_eq(args: Vec<Object>, _env: EnvRef) -> Object {
+    count_assertion();
     if args.len() < 2 || args.len() > 3 {
         return Object::error(
             "Test::assertEq expects 2 or 3 arguments (expected, actual, optional message)",
@@ -51,16 +125,21 @@ pub fn test_assert_eq(args: Vec<Object>, _env: EnvRef) -> Object {
     if expected == actual {
         Object::Null
     } else {
-        let base = format!("Assertion failed: expected {:?}, got {:?}", expected, actual);
+        let base = format!(
+            "Assertion failed: expected {}, got {}",
+            truncate(expected.to_string(), MAX_VALUE_CHARS),
+            truncate(actual.to_string(), MAX_VALUE_CHARS)
+        );
         let full = match message {
             Some(msg) => format!("{} - {}", base, msg),
             None => base,
         };
-        Object::Error(full)
+        Object::Error(full + &structural_diff(expected, actual))
     }
 }
 
 pub fn test_assert_not_eq(args: Vec<Object>, _env: EnvRef) -> Object {
+    count_assertion();
     if args.len() < 2 || args.len() > 3 {
         return Object::error(
             "Test::assertNotEq expects 2 or 3 arguments (not_expected, actual, optional message)",
@@ -79,8 +158,8 @@ pub fn test_assert_not_eq(args: Vec<Object>, _env: EnvRef) -> Object {
         Object::Null
     } else {
         let base = format!(
-            "Assertion failed: values are equal but expected inequality: {:?}",
-            actual
+            "Assertion failed: values are equal but expected inequality: {}",
+            truncate(actual.to_string(), MAX_VALUE_CHARS)
         );
         let full = match message {
             Some(msg) => format!("{} - {}", base, msg),
@@ -90,4 +169,285 @@ pub fn test_assert_not_eq(args: Vec<Object>, _env: EnvRef) -> Object {
     }
 }
 
+/// Split `args` into the `count` values an assertion checks and its
+/// optional trailing message.
+fn with_message(mut args: Vec<Object>, count: usize, usage: &str) -> Result<(Vec<Object>, Option<String>), Object> {
+    count_assertion();
+    if args.len() != count && args.len() != count + 1 {
+        return Err(Object::error(usage.to_string()));
+    }
+    let message = if args.len() > count {
+        args.pop().map(|m| m.to_string())
+    } else {
+        None
+    };
+    Ok((args, message))
+}
+
+/// An assertion failure: `Assertion failed: <detail>[ - message]`.
+fn failure(detail: String, message: Option<String>) -> Object {
+    match message {
+        Some(msg) => Object::Error(format!("Assertion failed: {} - {}", detail, msg)),
+        None => Object::Error(format!("Assertion failed: {}", detail)),
+    }
+}
+
+fn shown(value: &Object) -> String {
+    truncate(value.to_string(), MAX_VALUE_CHARS)
+}
+
+/// Test::assertTrue(value, [message]) – passes only for the boolean `true`,
+/// unlike `Test::assert`, which accepts any truthy value.
+pub fn test_assert_true(args: Vec<Object>, _env: EnvRef) -> Object {
+    assert_bool(args, true, "Test::assertTrue expects 1 or 2 arguments (value, optional message)")
+}
+
+/// Test::assertFalse(value, [message]) – passes only for the boolean `false`.
+pub fn test_assert_false(args: Vec<Object>, _env: EnvRef) -> Object {
+    assert_bool(args, false, "Test::assertFalse expects 1 or 2 arguments (value, optional message)")
+}
+
+fn assert_bool(args: Vec<Object>, want: bool, usage: &str) -> Object {
+    let (values, message) = match with_message(args, 1, usage) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    match &values[0] {
+        Object::Boolean(b) if *b == want => Object::Null,
+        other => failure(format!("expected {}, got {}", want, shown(other)), message),
+    }
+}
+
+/// Test::assertApproxEq(expected, actual, epsilon, [message]) – numbers
+/// within `epsilon` of each other, for float results.
+pub fn test_assert_approx_eq(args: Vec<Object>, _env: EnvRef) -> Object {
+    let usage = "Test::assertApproxEq expects 3 or 4 arguments (expected, actual, epsilon, optional message)";
+    let (values, message) = match with_message(args, 3, usage) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let number = |obj: &Object| match obj {
+        Object::Integer(i) => Some(*i as f64),
+        Object::Float(f) => Some(*f),
+        _ => None,
+    };
+    let (Some(expected), Some(actual), Some(epsilon)) = (number(&values[0]), number(&values[1]), number(&values[2])) else {
+        return Object::error(format!(
+            "Test::assertApproxEq expects numbers, got {}, {} and {}",
+            values[0].type_name(),
+            values[1].type_name(),
+            values[2].type_name()
+        ));
+    };
+    let diff = (expected - actual).abs();
+    if diff <= epsilon {
+        Object::Null
+    } else {
+        failure(
+            format!(
+                "expected {} ± {}, got {} (off by {})",
+                values[0], values[2], values[1], diff
+            ),
+            message,
+        )
+    }
+}
+
+/// Test::assertContains(haystack, needle, [message]) – a substring of a
+/// string, an element of an array, or a key of an object.
+pub fn test_assert_contains(args: Vec<Object>, _env: EnvRef) -> Object {
+    let usage = "Test::assertContains expects 2 or 3 arguments (haystack, needle, optional message)";
+    let (values, message) = match with_message(args, 2, usage) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let (haystack, needle) = (&values[0], &values[1]);
+    let found = match (haystack, needle) {
+        (Object::String(s), Object::String(sub)) => s.contains(sub.as_str()),
+        (Object::Array(items), needle) => items.contains(needle),
+        (Object::Object(map), Object::String(key)) => map.contains_key(key),
+        (haystack, needle) => {
+            return Object::error(format!(
+                "Test::assertContains can't look for a {} in a {}",
+                needle.type_name(),
+                haystack.type_name()
+            ))
+        }
+    };
+    if found {
+        return Object::Null;
+    }
+    let what = if matches!(haystack, Object::Object(_)) { "have key" } else { "contain" };
+    failure(format!("expected {} to {} {}", shown(haystack), what, shown(needle)), message)
+}
+
+/// Test::assertThrows(fn, [message]) -> error message
+/// Calls fn with no arguments and passes if it fails, returning the error
+/// message so the test can check it.
+pub fn test_assert_throws(args: Vec<Object>, env: EnvRef) -> Object {
+    let usage = "Test::assertThrows expects 1 or 2 arguments (function, optional message)";
+    let (mut values, message) = match with_message(args, 1, usage) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let func = values.pop().unwrap();
+    if !func.is_callable() {
+        return Object::error(format!("Test::assertThrows expects a function, got {}", func.type_name()));
+    }
+    match apply_function_with_this(func, Vec::new(), None, env) {
+        Object::Error(msg) => Object::String(msg),
+        other => failure(format!("expected the function to fail, but it returned {}", shown(&other)), message),
+    }
+}
+
+/// Test::assertMatch(text, pattern, [message]) – text matches the regex
+/// pattern somewhere (anchor it with `^...$` to match all of it).
+#[cfg(feature = "regex")]
+pub fn test_assert_match(args: Vec<Object>, _env: EnvRef) -> Object {
+    let usage = "Test::assertMatch expects 2 or 3 arguments (text, pattern, optional message)";
+    let (values, message) = match with_message(args, 2, usage) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let (Object::String(text), Object::String(pattern)) = (&values[0], &values[1]) else {
+        return Object::error("Test::assertMatch expects a string and a pattern string");
+    };
+    match crate::builtins::native::regex_builtins::compile(pattern) {
+        Ok(re) if re.is_match(text) => Object::Null,
+        Ok(_) => failure(format!("expected {} to match /{}/", shown(&values[0]), pattern), message),
+        Err(e) => Object::error(format!("Test::assertMatch: invalid pattern: {}", e)),
+    }
+}
+
+/// Shared shape of the Option/Result assertions: pass and hand back the
+/// inner value when `unwrap` accepts the value.
+fn assert_variant(args: Vec<Object>, name: &str, want: &str, unwrap: fn(Object) -> Result<Object, Object>) -> Object {
+    let usage = format!("Test::{} expects 1 or 2 arguments (value, optional message)", name);
+    let (mut values, message) = match with_message(args, 1, &usage) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    match unwrap(values.pop().unwrap()) {
+        Ok(inner) => inner,
+        Err(other) => failure(format!("expected {}, got {}", want, shown(&other)), message),
+    }
+}
+
+/// Test::assertSome(option, [message]) -> inner value
+pub fn test_assert_some(args: Vec<Object>, _env: EnvRef) -> Object {
+    assert_variant(args, "assertSome", "Some(...)", |v| match v {
+        Object::OptionSome(inner) => Ok(*inner),
+        other => Err(other),
+    })
+}
 
+/// Test::assertNone(option, [message])
+pub fn test_assert_none(args: Vec<Object>, _env: EnvRef) -> Object {
+    assert_variant(args, "assertNone", "None", |v| match v {
+        Object::OptionNone => Ok(Object::Null),
+        other => Err(other),
+    })
+}
+
+/// Test::assertOk(result, [message]) -> inner value
+pub fn test_assert_ok(args: Vec<Object>, _env: EnvRef) -> Object {
+    assert_variant(args, "assertOk", "Ok(...)", |v| match v {
+        Object::ResultOk(inner) => Ok(*inner),
+        other => Err(other),
+    })
+}
+
+/// Test::assertErr(result, [message]) -> error value
+pub fn test_assert_err(args: Vec<Object>, _env: EnvRef) -> Object {
+    assert_variant(args, "assertErr", "Err(...)", |v| match v {
+        Object::ResultErr(inner) => Ok(*inner),
+        other => Err(other),
+    })
+}
+
+/// Test::assertSnapshot(name, value, [message])
+/// Compares value, as JSON, with the snapshot stored under name in the
+/// script's `.snap` file. A snapshot seen for the first time is recorded and
+/// passes; `slang test --update-snapshots` re-records mismatches.
+pub fn test_assert_snapshot(args: Vec<Object>, _env: EnvRef) -> Object {
+    let usage = "Test::assertSnapshot expects 2 or 3 arguments (name, value, optional message)";
+    let (values, message) = match with_message(args, 2, usage) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+    let Object::String(name) = &values[0] else {
+        return Object::error(format!(
+            "Test::assertSnapshot expects a string name, got {}",
+            values[0].type_name()
+        ));
+    };
+    let actual = to_json_value(&values[1]);
+
+    SNAPSHOTS.with(|s| {
+        let mut guard = s.borrow_mut();
+        let Some(snapshots) = guard.as_mut() else {
+            return Object::error("Test::assertSnapshot only works under `slang test`");
+        };
+        let update = snapshots.update;
+        let stored = match &mut snapshots.stored {
+            Ok(stored) => stored,
+            Err(e) => return Object::error(e.clone()),
+        };
+        match stored.get(name) {
+            Some(expected) if *expected == actual => Object::Null,
+            Some(expected) if !update => {
+                let detail = format!(
+                    "snapshot \"{}\" doesn't match{}\n    run `slang test --update-snapshots` to accept the new value",
+                    name,
+                    json_diff(expected, &actual, "snapshot", "actual")
+                );
+                failure(detail, message)
+            }
+            _ => {
+                stored.insert(name.clone(), actual);
+                snapshots.written += 1;
+                Object::Null
+            }
+        }
+    })
+}
+
+/// For two arrays or objects, one line per differing path (`-` missing from
+/// `actual`, `+` extra in `actual`, `~` changed), each on its own indented
+/// line. Empty for scalars and for values with no JSON shape.
+fn structural_diff(expected: &Object, actual: &Object) -> String {
+    let structured = |obj: &Object| matches!(obj, Object::Array(_) | Object::Object(_));
+    if !structured(expected) || !structured(actual) {
+        return String::new();
+    }
+    let (Ok(old), Ok(new)) = (
+        from_slang::<serde_json::Value>(expected),
+        from_slang::<serde_json::Value>(actual),
+    ) else {
+        return String::new();
+    };
+    json_diff(&old, &new, "expected", "actual")
+}
+
+fn json_diff(old: &Value, new: &Value, old_name: &str, new_name: &str) -> String {
+    let changes = diff_values(old, new);
+    let mut out = format!("\n    diff (- {}, + {}):", old_name, new_name);
+    for change in changes.iter().take(MAX_DIFF_LINES) {
+        out.push_str("\n    ");
+        out.push_str(&truncate(change.to_string(), MAX_DIFF_LINE_CHARS));
+    }
+    if changes.len() > MAX_DIFF_LINES {
+        out.push_str(&format!(
+            "\n    ... and {} more differences",
+            changes.len() - MAX_DIFF_LINES
+        ));
+    }
+    out
+}
+
+fn truncate(text: String, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((cut, _)) => format!("{}... ({} chars)", &text[..cut], text.chars().count()),
+        None => text,
+    }
+}
@@ -1,8 +1,11 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use std::thread;
 
 use crate::env::EnvRef;
-use crate::object::Object;
+use crate::evaluator::core::budget;
+use crate::object::{Object, ObjectMap};
 
 fn expect_one_arg(mut args: Vec<Object>, name: &str) -> Result<Object, Object> {
     if args.len() != 1 {
@@ -58,72 +61,26 @@ pub(crate) fn time_sleep(args: Vec<Object>, _env: EnvRef) -> Object {
         return Object::error("Time::sleep milliseconds must be non-negative");
     }
 
-    thread::sleep(Duration::from_millis(ms_val as u64));
-    Object::Null
+    // Under a timeout this wakes at the deadline and reports the abort.
+    match budget::sleep(Duration::from_millis(ms_val as u64)) {
+        Some(abort) => abort,
+        None => Object::Null,
+    }
 }
 
 // Helper to get components from a Unix timestamp in milliseconds
 fn timestamp_to_components(ts_ms: i64) -> (i32, u32, u32, u32, u32, u32, u32) {
-    // Convert to seconds
-    let ts_secs = ts_ms / 1000;
-    
-    // Days since Unix epoch
-    let days = (ts_secs / 86400) as i32;
-    let time_of_day = (ts_secs % 86400) as u32;
-    
-    let hour = time_of_day / 3600;
-    let minute = (time_of_day % 3600) / 60;
-    let second = time_of_day % 60;
-    let millis = (ts_ms % 1000) as u32;
-    
-    // Calculate year, month, day from days since epoch
-    // This is a simplified calculation
-    let mut year = 1970i32;
-    let mut remaining_days = days;
-    
-    loop {
-        let days_in_year = if is_leap_year(year) { 366 } else { 365 };
-        if remaining_days < days_in_year {
-            break;
-        }
-        remaining_days -= days_in_year;
-        year += 1;
-    }
-    
-    // Handle negative timestamps (before 1970)
-    if days < 0 {
-        year = 1970;
-        remaining_days = 0;
-    }
-    
-    let days_in_months: [u32; 12] = if is_leap_year(year) {
-        [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-    } else {
-        [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-    };
-    
-    let mut month = 1u32;
-    let mut day = remaining_days as u32 + 1;
-    
-    for (i, &days_in_month) in days_in_months.iter().enumerate() {
-        if day <= days_in_month {
-            month = (i + 1) as u32;
-            break;
-        }
-        day -= days_in_month;
-    }
-    
-    (year, month, day, hour, minute, second, millis)
+    let civil = Civil::at(ts_ms);
+    (civil.year as i32, civil.month, civil.day, civil.hour, civil.minute, civil.second, civil.millis)
 }
 
-fn is_leap_year(year: i32) -> bool {
+fn is_leap_year(year: i64) -> bool {
     (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
 }
 
 fn day_of_week(ts_ms: i64) -> u32 {
     // Jan 1, 1970 was a Thursday (day 4, where Sunday = 0)
-    let days = (ts_ms / 86400000) as i32;
-    ((days + 4) % 7).abs() as u32
+    (ts_ms.div_euclid(MS_PER_DAY) + 4).rem_euclid(7) as u32
 }
 
 /// Time::year(ts) -> integer
@@ -335,7 +292,7 @@ pub(crate) fn time_to_object(args: Vec<Object>, _env: EnvRef) -> Object {
     let (year, month, day, hour, minute, second, _) = timestamp_to_components(ts_val);
     let dow = day_of_week(ts_val);
 
-    let mut map = std::collections::HashMap::new();
+    let mut map = ObjectMap::new();
     map.insert("year".to_string(), Object::Integer(year as i64));
     map.insert("month".to_string(), Object::Integer(month as i64));
     map.insert("day".to_string(), Object::Integer(day as i64));
@@ -344,6 +301,713 @@ pub(crate) fn time_to_object(args: Vec<Object>, _env: EnvRef) -> Object {
     map.insert("second".to_string(), Object::Integer(second as i64));
     map.insert("dayOfWeek".to_string(), Object::Integer(dow as i64));
 
-    Object::Object(map)
+    Object::from_map(map)
+}
+
+
+// ----- Calendar arithmetic -----
+//
+// Timestamps stay plain integers (milliseconds since the Unix epoch, UTC).
+// The helpers below convert between those and civil dates using the
+// proleptic Gregorian calendar, so dates before 1970 work too.
+
+const MS_PER_DAY: i64 = 86_400_000;
+
+/// Days since 1970-01-01 for a civil date, or `None` if that overflows.
+fn days_from_civil(year: i64, month: u32, day: u32) -> Option<i64> {
+    let y = if month <= 2 { year.checked_sub(1)? } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era.checked_mul(146_097)?.checked_add(doe - 719_468)
+}
+
+/// The civil date `days` after 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Date and time fields of a moment, as seen from some UTC offset.
+struct Civil {
+    year: i64,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+    second: u32,
+    millis: u32,
+}
+
+impl Civil {
+    fn at(ts_ms: i64) -> Self {
+        let (year, month, day) = civil_from_days(ts_ms.div_euclid(MS_PER_DAY));
+        let ms = ts_ms.rem_euclid(MS_PER_DAY);
+        Civil {
+            year,
+            month,
+            day,
+            hour: (ms / 3_600_000) as u32,
+            minute: (ms / 60_000 % 60) as u32,
+            second: (ms / 1000 % 60) as u32,
+            millis: (ms % 1000) as u32,
+        }
+    }
+
+    /// Milliseconds since the epoch, or `None` if the date is too far away
+    /// to be represented.
+    fn timestamp(&self) -> Option<i64> {
+        let time_of_day = self.hour as i64 * 3_600_000
+            + self.minute as i64 * 60_000
+            + self.second as i64 * 1000
+            + self.millis as i64;
+        days_from_civil(self.year, self.month, self.day)?
+            .checked_mul(MS_PER_DAY)?
+            .checked_add(time_of_day)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !(1..=12).contains(&self.month) {
+            return Err(format!("month must be 1-12, got {}", self.month));
+        }
+        let last = days_in_month(self.year, self.month);
+        if self.day < 1 || self.day > last {
+            return Err(format!("day must be 1-{} for {}-{:02}, got {}", last, self.year, self.month, self.day));
+        }
+        if self.hour > 23 || self.minute > 59 || self.second > 59 || self.millis > 999 {
+            return Err(format!(
+                "time {:02}:{:02}:{:02}.{:03} is out of range",
+                self.hour, self.minute, self.second, self.millis
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn timestamp_arg(value: &Object, name: &str) -> Result<i64, Object> {
+    match value {
+        Object::Integer(i) => Ok(*i),
+        other => Err(Object::error(format!("{} expects integer timestamp, got {:?}", name, other))),
+    }
+}
+
+/// Time::fromComponents({ year, month, day, hour, minute, second, millis, zone | offset }) -> integer
+/// `month` and `day` default to 1 and the time fields to 0. The fields are
+/// read as UTC unless `offset` (minutes east of UTC) or `zone` is given, so
+/// the objects returned by `Time::toObject` and `Time::toZone` round-trip.
+pub(crate) fn time_from_components(args: Vec<Object>, _env: EnvRef) -> Object {
+    let fields = match expect_one_arg(args, "Time::fromComponents") {
+        Ok(Object::Object(fields)) => fields,
+        Ok(other) => return Object::error(format!("Time::fromComponents expects an object, got {:?}", other)),
+        Err(e) => return e,
+    };
+    let mut civil = Civil { year: 0, month: 1, day: 1, hour: 0, minute: 0, second: 0, millis: 0 };
+    let mut offset = None;
+    let mut zone = None;
+    let mut has_year = false;
+    for (key, value) in fields.iter() {
+        let number = match (key.as_str(), value) {
+            ("zone", Object::String(name)) => {
+                zone = Some(name.clone());
+                continue;
+            }
+            ("zone", other) => {
+                return Object::error(format!("Time::fromComponents: zone must be a string, got {:?}", other))
+            }
+            // Extra fields from `Time::toObject` / `Time::toZone`.
+            ("dayOfWeek" | "abbreviation", _) => continue,
+            (_, Object::Integer(i)) => *i,
+            _ => {
+                return Object::error(format!("Time::fromComponents: {} must be an integer, got {:?}", key, value))
+            }
+        };
+        let field = match key.as_str() {
+            "year" => {
+                has_year = true;
+                civil.year = number;
+                continue;
+            }
+            "offset" => {
+                match number.checked_mul(60) {
+                    Some(seconds) => offset = Some(seconds),
+                    None => return Object::error("integer overflow in Time::fromComponents"),
+                }
+                continue;
+            }
+            "month" => &mut civil.month,
+            "day" => &mut civil.day,
+            "hour" => &mut civil.hour,
+            "minute" => &mut civil.minute,
+            "second" => &mut civil.second,
+            "millis" => &mut civil.millis,
+            other => return Object::error(format!("Time::fromComponents: unknown field {}", other)),
+        };
+        *field = match u32::try_from(number) {
+            Ok(n) => n,
+            Err(_) => return Object::error(format!("Time::fromComponents: {} must not be negative, got {}", key, number)),
+        };
+    }
+    if !has_year {
+        return Object::error("Time::fromComponents: year is required");
+    }
+    if let Err(e) = civil.validate() {
+        return Object::error(format!("Time::fromComponents: {}", e));
+    }
+    let Some(local) = civil.timestamp() else {
+        return Object::error("integer overflow in Time::fromComponents");
+    };
+    let utc = match (offset, zone) {
+        (Some(offset), _) => offset.checked_mul(1000).and_then(|ms| local.checked_sub(ms)),
+        (None, Some(name)) => match Zone::load(&name) {
+            Ok(zone) => zone.to_utc(local),
+            Err(e) => return Object::error(format!("Time::fromComponents: {}", e)),
+        },
+        (None, None) => Some(local),
+    };
+    match utc {
+        Some(ts) => Object::Integer(ts),
+        None => Object::error("integer overflow in Time::fromComponents"),
+    }
+}
+
+/// Time::add(ts, { years, months, weeks, days, hours, minutes, seconds, millis }) -> integer
+/// Years and months move along the calendar (clamping the day, so Jan 31 plus
+/// one month is the last day of February); the other units are exact
+/// durations. Any field may be negative.
+pub(crate) fn time_add(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (ts, amounts) = match args.as_slice() {
+        [ts, Object::Object(amounts)] => match timestamp_arg(ts, "Time::add") {
+            Ok(ts) => (ts, amounts.clone()),
+            Err(e) => return e,
+        },
+        [_, other] => return Object::error(format!("Time::add expects an object of amounts, got {:?}", other)),
+        _ => return Object::error("Time::add expects exactly 2 arguments (timestamp, amounts)"),
+    };
+    let mut months = 0i64;
+    let mut millis = 0i64;
+    for (key, value) in amounts.iter() {
+        let Object::Integer(n) = value else {
+            return Object::error(format!("Time::add: {} must be an integer, got {:?}", key, value));
+        };
+        let (total, unit) = match key.as_str() {
+            "years" => (&mut months, 12),
+            "months" => (&mut months, 1),
+            "weeks" => (&mut millis, 7 * MS_PER_DAY),
+            "days" => (&mut millis, MS_PER_DAY),
+            "hours" => (&mut millis, 3_600_000),
+            "minutes" => (&mut millis, 60_000),
+            "seconds" => (&mut millis, 1000),
+            "millis" => (&mut millis, 1),
+            other => return Object::error(format!("Time::add: unknown unit {}", other)),
+        };
+        match n.checked_mul(unit).and_then(|amount| total.checked_add(amount)) {
+            Some(sum) => *total = sum,
+            None => return Object::error("integer overflow in Time::add"),
+        }
+    }
+    let mut civil = Civil::at(ts);
+    if months != 0 {
+        let Some(index) = (civil.year * 12 + civil.month as i64 - 1).checked_add(months) else {
+            return Object::error("integer overflow in Time::add");
+        };
+        civil.year = index.div_euclid(12);
+        civil.month = (index.rem_euclid(12) + 1) as u32;
+        civil.day = civil.day.min(days_in_month(civil.year, civil.month));
+    }
+    match civil.timestamp().and_then(|ts| ts.checked_add(millis)) {
+        Some(ts) => Object::Integer(ts),
+        None => Object::error("integer overflow in Time::add"),
+    }
+}
+
+/// Time::diff(a, b) -> { millis, seconds, minutes, hours, days, weeks }
+/// The time from `b` to `a` in each unit, truncated towards zero.
+pub(crate) fn time_diff(args: Vec<Object>, _env: EnvRef) -> Object {
+    if args.len() != 2 {
+        return Object::error("Time::diff expects exactly 2 arguments (a, b)");
+    }
+    let (a, b) = match (timestamp_arg(&args[0], "Time::diff"), timestamp_arg(&args[1], "Time::diff")) {
+        (Ok(a), Ok(b)) => (a, b),
+        (Err(e), _) | (_, Err(e)) => return e,
+    };
+    let Some(delta) = a.checked_sub(b) else {
+        return Object::error("integer overflow in Time::diff");
+    };
+    let mut map = ObjectMap::new();
+    for (unit, size) in [
+        ("millis", 1),
+        ("seconds", 1000),
+        ("minutes", 60_000),
+        ("hours", 3_600_000),
+        ("days", MS_PER_DAY),
+        ("weeks", 7 * MS_PER_DAY),
+    ] {
+        map.insert(unit.to_string(), Object::Integer(delta / size));
+    }
+    Object::from_map(map)
+}
+
+/// Time::parse(text, fmt) -> Result::Ok(timestamp) or Result::Err(errorString)
+/// Understands %Y, %m, %d, %H, %M, %S, %f (fraction of a second), %z (`Z`,
+/// `+02:00` or `+0200`) and %%. Without %z the text is read as UTC.
+pub(crate) fn time_parse(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (text, fmt) = match args.as_slice() {
+        [Object::String(text), Object::String(fmt)] => (text, fmt),
+        [_, _] => return Object::error("Time::parse expects two strings (text, format)"),
+        _ => return Object::error("Time::parse expects exactly 2 arguments (text, format)"),
+    };
+    match parse_with_format(text, fmt) {
+        Ok(ts) => Object::ResultOk(Box::new(Object::Integer(ts))),
+        Err(e) => Object::ResultErr(Box::new(Object::String(format!(
+            "Time::parse: could not parse {:?} as {:?}: {}",
+            text, fmt, e
+        )))),
+    }
+}
+
+fn parse_with_format(text: &str, fmt: &str) -> Result<i64, String> {
+    fn digits(rest: &mut &str, min: usize, max: usize, what: &str) -> Result<u32, String> {
+        let len = rest.bytes().take(max).take_while(u8::is_ascii_digit).count();
+        if len < min {
+            return Err(format!("expected {} at {:?}", what, rest));
+        }
+        let (number, tail) = rest.split_at(len);
+        *rest = tail;
+        Ok(number.parse().expect("only ascii digits"))
+    }
+
+    let mut civil = Civil { year: 1970, month: 1, day: 1, hour: 0, minute: 0, second: 0, millis: 0 };
+    let mut offset = 0i64;
+    let mut rest = text;
+    let mut directives = fmt.chars();
+    while let Some(c) = directives.next() {
+        if c != '%' {
+            rest = rest.strip_prefix(c).ok_or_else(|| format!("expected {:?} at {:?}", c, rest))?;
+            continue;
+        }
+        match directives.next() {
+            Some('Y') => civil.year = digits(&mut rest, 4, 4, "a 4-digit year")? as i64,
+            Some('m') => civil.month = digits(&mut rest, 1, 2, "a month")?,
+            Some('d') => civil.day = digits(&mut rest, 1, 2, "a day")?,
+            Some('H') => civil.hour = digits(&mut rest, 1, 2, "an hour")?,
+            Some('M') => civil.minute = digits(&mut rest, 1, 2, "a minute")?,
+            Some('S') => civil.second = digits(&mut rest, 1, 2, "a second")?,
+            Some('f') => {
+                let before = rest.len();
+                let fraction = digits(&mut rest, 1, 9, "a fraction of a second")?;
+                let len = before - rest.len();
+                civil.millis = (fraction as u64 * 1000 / 10u64.pow(len as u32)) as u32;
+            }
+            Some('z') => {
+                if let Some(tail) = rest.strip_prefix('Z') {
+                    rest = tail;
+                    offset = 0;
+                    continue;
+                }
+                let sign = match rest.chars().next() {
+                    Some('+') => 1,
+                    Some('-') => -1,
+                    _ => return Err(format!("expected a UTC offset at {:?}", rest)),
+                };
+                rest = &rest[1..];
+                let hours = digits(&mut rest, 2, 2, "offset hours")? as i64;
+                rest = rest.strip_prefix(':').unwrap_or(rest);
+                let minutes = digits(&mut rest, 2, 2, "offset minutes")? as i64;
+                offset = sign * (hours * 3600 + minutes * 60);
+            }
+            Some('%') => rest = rest.strip_prefix('%').ok_or_else(|| format!("expected '%' at {:?}", rest))?,
+            Some(other) => return Err(format!("unknown directive %{}", other)),
+            None => return Err("format ends with a lone %".to_string()),
+        }
+    }
+    if !rest.is_empty() {
+        return Err(format!("unexpected trailing text {:?}", rest));
+    }
+    civil.validate()?;
+    // Four-digit years are always in range.
+    Ok(civil.timestamp().expect("four-digit year") - offset * 1000)
+}
+
+/// Time::toZone(ts, zone) -> { year, month, day, hour, minute, second, millis, dayOfWeek, offset, abbreviation, zone }
+/// `zone` is an IANA name such as "Europe/Paris", "UTC", or a fixed offset
+/// like "+05:30". `offset` is in minutes east of UTC.
+pub(crate) fn time_to_zone(args: Vec<Object>, _env: EnvRef) -> Object {
+    let (ts, name) = match args.as_slice() {
+        [ts, Object::String(name)] => match timestamp_arg(ts, "Time::toZone") {
+            Ok(ts) => (ts, name),
+            Err(e) => return e,
+        },
+        [_, other] => return Object::error(format!("Time::toZone expects a zone name string, got {:?}", other)),
+        _ => return Object::error("Time::toZone expects exactly 2 arguments (timestamp, zone)"),
+    };
+    let zone = match Zone::load(name) {
+        Ok(zone) => zone,
+        Err(e) => return Object::error(format!("Time::toZone: {}", e)),
+    };
+    let local = zone.local_type(ts.div_euclid(1000));
+    let Some(shifted) = ts.checked_add(local.offset * 1000) else {
+        return Object::error("integer overflow in Time::toZone");
+    };
+    let civil = Civil::at(shifted);
+    let mut map = ObjectMap::new();
+    map.insert("year".to_string(), Object::Integer(civil.year));
+    map.insert("month".to_string(), Object::Integer(civil.month as i64));
+    map.insert("day".to_string(), Object::Integer(civil.day as i64));
+    map.insert("hour".to_string(), Object::Integer(civil.hour as i64));
+    map.insert("minute".to_string(), Object::Integer(civil.minute as i64));
+    map.insert("second".to_string(), Object::Integer(civil.second as i64));
+    map.insert("millis".to_string(), Object::Integer(civil.millis as i64));
+    map.insert("dayOfWeek".to_string(), Object::Integer(day_of_week(shifted) as i64));
+    map.insert("offset".to_string(), Object::Integer(local.offset / 60));
+    map.insert("abbreviation".to_string(), Object::String(local.abbreviation.clone()));
+    map.insert("zone".to_string(), Object::String(name.clone()));
+    Object::from_map(map)
+}
+
+// ----- Time zones -----
+//
+// Zones are read from the system tz database (`$TZDIR`, or
+// /usr/share/zoneinfo) in the TZif format described in RFC 8536: a table of
+// transitions, plus a POSIX TZ rule (e.g. `CET-1CEST,M3.5.0,M10.5.0/3`) for
+// moments after the last transition. Loaded zones are cached per thread.
+
+#[derive(Clone, Debug)]
+struct LocalType {
+    /// Seconds east of UTC.
+    offset: i64,
+    abbreviation: String,
+}
+
+/// When a POSIX rule switches between standard and daylight time: a day of
+/// the year and the local wall-clock second of that day.
+type Change = (RuleDay, i64);
+
+#[derive(Clone, Copy, Debug)]
+enum RuleDay {
+    /// `Jn`: day 1-365, never counting February 29.
+    Julian(i64),
+    /// `n`: day 0-365, counting February 29.
+    Zero(i64),
+    /// `Mm.w.d`: weekday `d` (0 = Sunday) of week `w` (5 = last) of month `m`.
+    Month(u32, u32, u32),
+}
+
+#[derive(Clone, Debug)]
+struct Rule {
+    standard: LocalType,
+    /// Daylight time with its start and end, if the zone observes it.
+    daylight: Option<(LocalType, Change, Change)>,
+}
+
+#[derive(Debug)]
+struct Zone {
+    /// (UTC second, index into `types`), sorted.
+    transitions: Vec<(i64, usize)>,
+    types: Vec<LocalType>,
+    rule: Option<Rule>,
+}
+
+thread_local! {
+    static ZONES: RefCell<HashMap<String, Rc<Zone>>> = RefCell::new(HashMap::new());
+}
+
+impl Zone {
+    fn fixed(offset: i64, abbreviation: &str) -> Zone {
+        Zone {
+            transitions: Vec::new(),
+            types: vec![LocalType { offset, abbreviation: abbreviation.to_string() }],
+            rule: None,
+        }
+    }
+
+    fn load(name: &str) -> Result<Rc<Zone>, String> {
+        if let Some(zone) = ZONES.with(|zones| zones.borrow().get(name).cloned()) {
+            return Ok(zone);
+        }
+        let zone = Rc::new(Zone::read(name)?);
+        ZONES.with(|zones| zones.borrow_mut().insert(name.to_string(), zone.clone()));
+        Ok(zone)
+    }
+
+    fn read(name: &str) -> Result<Zone, String> {
+        if matches!(name, "UTC" | "Z" | "GMT") {
+            return Ok(Zone::fixed(0, name));
+        }
+        if name.starts_with(['+', '-']) {
+            if let Ok(ts) = parse_with_format(&format!("1970-01-01{}", name), "%Y-%m-%d%z") {
+                return Ok(Zone::fixed(-ts / 1000, name));
+            }
+            return Err(format!("invalid UTC offset {:?}", name));
+        }
+        let unknown = || format!("unknown time zone {:?}", name);
+        if name.is_empty() || name.starts_with('/') || name.split('/').any(|part| part == ".." || part.is_empty()) {
+            return Err(unknown());
+        }
+        let dir = std::env::var_os("TZDIR").unwrap_or_else(|| "/usr/share/zoneinfo".into());
+        let bytes = std::fs::read(std::path::Path::new(&dir).join(name)).map_err(|_| unknown())?;
+        parse_tzif(&bytes).ok_or_else(|| format!("time zone {:?} is not valid TZif data", name))
+    }
+
+    /// The local time type in effect at UTC second `at`.
+    fn local_type(&self, at: i64) -> LocalType {
+        let after = self.transitions.partition_point(|&(t, _)| t <= at);
+        if after == self.transitions.len() && let Some(rule) = &self.rule {
+            return rule.local_type(at);
+        }
+        match after.checked_sub(1) {
+            Some(i) => self.types[self.transitions[i].1].clone(),
+            None => self.types[0].clone(),
+        }
+    }
+
+    /// The UTC timestamp for local wall-clock time `local_ms`. Times skipped
+    /// by a forward jump use the offset from before the jump; times repeated
+    /// by a backward jump resolve to the earlier of the two. `None` if the
+    /// result does not fit in a timestamp.
+    fn to_utc(&self, local_ms: i64) -> Option<i64> {
+        let local = local_ms.div_euclid(1000);
+        // No zone changes its offset twice within a day, so these are the
+        // only two offsets that can apply.
+        let before = self.local_type(local - 86_400).offset;
+        let after = self.local_type(local + 86_400).offset;
+        let fits = |offset: i64| self.local_type(local - offset).offset == offset;
+        let offset = match (fits(before), fits(after)) {
+            (true, true) => before.max(after),
+            (false, true) => after,
+            _ => before,
+        };
+        local_ms.checked_sub(offset * 1000)
+    }
 }
 
+impl Rule {
+    fn local_type(&self, at: i64) -> LocalType {
+        let Some((daylight, start, end)) = &self.daylight else {
+            return self.standard.clone();
+        };
+        let year = civil_from_days((at + self.standard.offset).div_euclid(86_400)).0;
+        // Transitions happen at local wall-clock times: the start in
+        // standard time, the end in daylight time.
+        let starts = rule_second(year, start.0) + start.1 - self.standard.offset;
+        let ends = rule_second(year, end.0) + end.1 - daylight.offset;
+        let in_daylight = if starts < ends {
+            starts <= at && at < ends
+        } else {
+            // Southern hemisphere: daylight time spans the new year.
+            !(ends <= at && at < starts)
+        };
+        if in_daylight { daylight.clone() } else { self.standard.clone() }
+    }
+}
+
+/// Midnight (as seconds since the epoch, local) of `day` in `year`.
+fn rule_second(year: i64, day: RuleDay) -> i64 {
+    // `year` comes from a timestamp in seconds, so it is far inside the range
+    // `days_from_civil` can represent.
+    let jan1 = days_from_civil(year, 1, 1).expect("rule year in range");
+    let days = match day {
+        RuleDay::Julian(n) => jan1 + n - 1 + if is_leap_year(year) && n >= 60 { 1 } else { 0 },
+        RuleDay::Zero(n) => jan1 + n,
+        RuleDay::Month(month, week, weekday) => {
+            let first = days_from_civil(year, month, 1).expect("rule year in range");
+            let first_weekday = (first + 4).rem_euclid(7);
+            let mut day = first + (weekday as i64 - first_weekday).rem_euclid(7) + (week as i64 - 1) * 7;
+            while day >= first + days_in_month(year, month) as i64 {
+                day -= 7;
+            }
+            day
+        }
+    };
+    days * 86_400
+}
+
+fn parse_tzif(bytes: &[u8]) -> Option<Zone> {
+    struct Reader<'a>(&'a [u8]);
+    impl<'a> Reader<'a> {
+        fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+            if self.0.len() < n {
+                return None;
+            }
+            let (head, tail) = self.0.split_at(n);
+            self.0 = tail;
+            Some(head)
+        }
+        fn u32(&mut self) -> Option<usize> {
+            Some(u32::from_be_bytes(self.take(4)?.try_into().ok()?) as usize)
+        }
+        fn i64(&mut self, wide: bool) -> Option<i64> {
+            if wide {
+                Some(i64::from_be_bytes(self.take(8)?.try_into().ok()?))
+            } else {
+                Some(i32::from_be_bytes(self.take(4)?.try_into().ok()?) as i64)
+            }
+        }
+    }
+
+    let mut reader = Reader(bytes);
+    let mut wide = false;
+    loop {
+        if reader.take(4)? != b"TZif" {
+            return None;
+        }
+        let version = reader.take(1)?[0];
+        reader.take(15)?;
+        let [utc_count, std_count, leap_count, time_count, type_count, char_count] =
+            [reader.u32()?, reader.u32()?, reader.u32()?, reader.u32()?, reader.u32()?, reader.u32()?];
+        let time_size = if wide { 8 } else { 4 };
+        if !wide && version >= b'2' {
+            // Skip the 32-bit block; the 64-bit one that follows has the
+            // same data without the year-2038 limit.
+            reader.take(
+                time_count * 5 + type_count * 6 + char_count + leap_count * (time_size + 4) + std_count + utc_count,
+            )?;
+            wide = true;
+            continue;
+        }
+        let mut times = Vec::with_capacity(time_count);
+        for _ in 0..time_count {
+            times.push(reader.i64(wide)?);
+        }
+        let indices = reader.take(time_count)?;
+        let mut raw_types = Vec::with_capacity(type_count);
+        for _ in 0..type_count {
+            let entry = reader.take(6)?;
+            let offset = i32::from_be_bytes(entry[..4].try_into().ok()?) as i64;
+            raw_types.push((offset, entry[5] as usize));
+        }
+        let chars = reader.take(char_count)?;
+        reader.take(leap_count * (time_size + 4) + std_count + utc_count)?;
+        let types = raw_types
+            .into_iter()
+            .map(|(offset, start)| {
+                let tail = chars.get(start..).unwrap_or_default();
+                let end = tail.iter().position(|&b| b == 0).unwrap_or(tail.len());
+                LocalType { offset, abbreviation: String::from_utf8_lossy(&tail[..end]).into_owned() }
+            })
+            .collect::<Vec<_>>();
+        if types.is_empty() || indices.iter().any(|&i| i as usize >= types.len()) {
+            return None;
+        }
+        let transitions = times.into_iter().zip(indices.iter().map(|&i| i as usize)).collect();
+        let rule = if wide {
+            std::str::from_utf8(reader.0)
+                .ok()
+                .and_then(|footer| footer.trim_matches('\n').lines().next().map(str::to_string))
+                .and_then(|footer| parse_posix_rule(&footer))
+        } else {
+            None
+        };
+        return Some(Zone { transitions, types, rule });
+    }
+}
+
+/// Parse a POSIX TZ string such as `EST5EDT,M3.2.0,M11.1.0` or `<+0530>-5:30`.
+fn parse_posix_rule(text: &str) -> Option<Rule> {
+    fn name(rest: &mut &str) -> Option<String> {
+        if let Some(quoted) = rest.strip_prefix('<') {
+            let end = quoted.find('>')?;
+            let name = quoted[..end].to_string();
+            *rest = &quoted[end + 1..];
+            return Some(name);
+        }
+        let len = rest.bytes().take_while(u8::is_ascii_alphabetic).count();
+        if len < 3 {
+            return None;
+        }
+        let (name, tail) = rest.split_at(len);
+        *rest = tail;
+        Some(name.to_string())
+    }
+    fn number(rest: &mut &str) -> Option<i64> {
+        let len = rest.bytes().take_while(u8::is_ascii_digit).count();
+        let (digits, tail) = rest.split_at(len);
+        *rest = tail;
+        digits.parse().ok()
+    }
+    /// `[+-]hh[:mm[:ss]]` as seconds.
+    fn clock(rest: &mut &str) -> Option<i64> {
+        let sign = match rest.chars().next() {
+            Some('-') => -1,
+            Some('+') => 1,
+            _ => 0,
+        };
+        if sign != 0 {
+            *rest = &rest[1..];
+        }
+        let mut seconds = number(rest)? * 3600;
+        for scale in [60, 1] {
+            match rest.strip_prefix(':') {
+                Some(tail) => {
+                    *rest = tail;
+                    seconds += number(rest)? * scale;
+                }
+                None => break,
+            }
+        }
+        Some(if sign < 0 { -seconds } else { seconds })
+    }
+    fn change(rest: &mut &str) -> Option<Change> {
+        *rest = rest.strip_prefix(',')?;
+        let day = if let Some(tail) = rest.strip_prefix('M') {
+            *rest = tail;
+            let month = number(rest)? as u32;
+            *rest = rest.strip_prefix('.')?;
+            let week = number(rest)? as u32;
+            *rest = rest.strip_prefix('.')?;
+            let weekday = number(rest)? as u32;
+            if !(1..=12).contains(&month) || !(1..=5).contains(&week) || weekday > 6 {
+                return None;
+            }
+            RuleDay::Month(month, week, weekday)
+        } else if let Some(tail) = rest.strip_prefix('J') {
+            *rest = tail;
+            RuleDay::Julian(number(rest)?)
+        } else {
+            RuleDay::Zero(number(rest)?)
+        };
+        let time = match rest.strip_prefix('/') {
+            Some(tail) => {
+                *rest = tail;
+                clock(rest)?
+            }
+            None => 7200,
+        };
+        Some((day, time))
+    }
+
+    let mut rest = text;
+    // POSIX offsets count hours west of UTC, the opposite of TZif.
+    let standard_name = name(&mut rest)?;
+    let standard = LocalType { offset: -clock(&mut rest)?, abbreviation: standard_name };
+    if rest.is_empty() {
+        return Some(Rule { standard, daylight: None });
+    }
+    let daylight_name = name(&mut rest)?;
+    let daylight_offset = if rest.starts_with(',') { standard.offset + 3600 } else { -clock(&mut rest)? };
+    let start = change(&mut rest)?;
+    let end = change(&mut rest)?;
+    if !rest.is_empty() {
+        return None;
+    }
+    let daylight = LocalType { offset: daylight_offset, abbreviation: daylight_name };
+    Some(Rule { standard, daylight: Some((daylight, start, end)) })
+}
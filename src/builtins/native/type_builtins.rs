@@ -1,6 +1,6 @@
+use crate::builtins::native::math_builtins::float_to_int;
 use crate::env::EnvRef;
 use crate::object::Object;
-use std::i64;
 
 fn expect_one_arg(mut args: Vec<Object>, name: &str) -> Result<Object, Object> {
     if args.len() != 1 {
@@ -173,6 +173,47 @@ pub(crate) fn type_bool(args: Vec<Object>, _env: EnvRef) -> Object {
     wrap_result(convert_to_bool(value))
 }
 
+/// Type::toInt(x) -> integer
+/// Numbers only: integers pass through and floats truncate toward zero, so
+/// `Type::toInt(-2.7)` is -2. NaN, infinities and floats outside the integer
+/// range are errors. Use `Type::int` to parse strings.
+pub(crate) fn type_to_int(args: Vec<Object>, _env: EnvRef) -> Object {
+    let value = match expect_one_arg(args, "Type::toInt") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    match value {
+        Object::Integer(i) => Object::Integer(i),
+        Object::Float(f) => match float_to_int(f.trunc()) {
+            Some(i) => Object::Integer(i),
+            None => Object::error(format!("Type::toInt: {} does not fit in an integer", f)),
+        },
+        other => Object::error(format!(
+            "Type::toInt expects integer or float, got {:?}",
+            other
+        )),
+    }
+}
+
+/// Type::toFloat(x) -> float
+/// Numbers only. Integers beyond 2^53 become the nearest representable float.
+pub(crate) fn type_to_float(args: Vec<Object>, _env: EnvRef) -> Object {
+    let value = match expect_one_arg(args, "Type::toFloat") {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
+
+    match value {
+        Object::Integer(i) => Object::Float(i as f64),
+        Object::Float(f) => Object::Float(f),
+        other => Object::error(format!(
+            "Type::toFloat expects integer or float, got {:?}",
+            other
+        )),
+    }
+}
+
 // Type inspection functions
 
 /// Type::of(value) -> string type name
@@ -182,27 +223,7 @@ pub(crate) fn type_of(args: Vec<Object>, _env: EnvRef) -> Object {
         Err(e) => return e,
     };
 
-    let type_name = match value {
-        Object::Integer(_) => "integer",
-        Object::Float(_) => "float",
-        Object::Boolean(_) => "boolean",
-        Object::String(_) => "string",
-        Object::Array(_) => "array",
-        Object::Object(_) => "object",
-        Object::Function { .. } => "function",
-        Object::Builtin(_) => "function",
-        Object::Class { .. } => "class",
-        Object::ReturnValue(_) => "return",
-        Object::File(_) => "file",
-        Object::Error(_) => "error",
-        Object::OptionSome(_) => "option",
-        Object::OptionNone => "option",
-        Object::ResultOk(_) => "result",
-        Object::ResultErr(_) => "result",
-        Object::Null => "null",
-    };
-
-    Object::String(type_name.to_string())
+    Object::String(value.type_name().to_string())
 }
 
 /// Type::isInt(value) -> bool
@@ -282,10 +303,7 @@ pub(crate) fn type_is_callable(args: Vec<Object>, _env: EnvRef) -> Object {
         Err(e) => return e,
     };
 
-    Object::Boolean(matches!(
-        value,
-        Object::Function { .. } | Object::Builtin(_)
-    ))
+    Object::Boolean(value.is_callable())
 }
 
 /// Type::isIterable(value) -> bool (array or string)
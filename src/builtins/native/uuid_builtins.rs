@@ -0,0 +1,79 @@
+use std::cell::Cell;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::env::EnvRef;
+use crate::object::Object;
+
+thread_local! {
+    /// The millisecond and 12-bit counter of the last v7 UUID, so UUIDs made
+    /// within the same millisecond still sort in creation order.
+    static LAST_V7: Cell<(u64, u16)> = const { Cell::new((0, 0)) };
+}
+
+fn random_16() -> Result<[u8; 16], Object> {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes)
+        .map(|()| bytes)
+        .map_err(|e| Object::error(format!("failed to generate a UUID: {}", e)))
+}
+
+/// Set the version nibble and the RFC 9562 variant bits, then format as
+/// `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx`.
+fn finish(mut bytes: [u8; 16], version: u8) -> Object {
+    bytes[6] = (bytes[6] & 0x0f) | (version << 4);
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    Object::String(format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    ))
+}
+
+/// Uuid::v4() -> random UUID string
+pub(crate) fn uuid_v4(args: Vec<Object>, _env: EnvRef) -> Object {
+    if !args.is_empty() {
+        return Object::error("Uuid::v4 expects no arguments");
+    }
+    match random_16() {
+        Ok(bytes) => finish(bytes, 4),
+        Err(e) => e,
+    }
+}
+
+/// Uuid::v7() -> time-ordered UUID string
+/// Starts with the Unix time in milliseconds, so v7 UUIDs sort by creation
+/// time; ones made in the same millisecond are ordered by a counter.
+pub(crate) fn uuid_v7(args: Vec<Object>, _env: EnvRef) -> Object {
+    if !args.is_empty() {
+        return Object::error("Uuid::v7 expects no arguments");
+    }
+    let mut bytes = match random_16() {
+        Ok(bytes) => bytes,
+        Err(e) => return e,
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    let (millis, counter) = LAST_V7.with(|last| {
+        let (last_millis, last_counter) = last.get();
+        let next = if now > last_millis {
+            // Seed the counter low so there is room to count up.
+            (now, u16::from_be_bytes([bytes[6], bytes[7]]) & 0x01ff)
+        } else if last_counter < 0x0fff {
+            (last_millis, last_counter + 1)
+        } else {
+            // Counter exhausted: borrow the next millisecond.
+            (last_millis + 1, 0)
+        };
+        last.set(next);
+        next
+    });
+    bytes[..6].copy_from_slice(&millis.to_be_bytes()[2..]);
+    bytes[6..8].copy_from_slice(&counter.to_be_bytes());
+    finish(bytes, 7)
+}
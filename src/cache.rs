@@ -0,0 +1,2 @@
+pub mod core;
+pub use core::{cache_dir, cache_dir_override, is_enabled, load, set_cache_dir, set_enabled, store};
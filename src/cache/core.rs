@@ -0,0 +1,264 @@
+//! On-disk cache of parsed modules.
+//!
+//! Imported files are parsed once and the resulting AST is stored, keyed by
+//! the module's canonical path, under the cache directory. An entry is reused
+//! while the file's size and mtime are unchanged; if those differ but the
+//! content hash still matches (e.g. after a `touch`) the entry is reused and
+//! refreshed. Entries written by a different interpreter version are ignored.
+//!
+//! Cache failures never fail an import: a missing, stale or unreadable entry
+//! just means the module is parsed from source.
+
+use std::cell::{Cell, RefCell};
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::ast::Program;
+use crate::debug_log;
+
+/// Bump when the layout of `CacheHeader` itself changes.
+const CACHE_FORMAT: u32 = 2;
+
+thread_local! {
+    static ENABLED: Cell<bool> = const { Cell::new(true) };
+    static DIR_OVERRIDE: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+}
+
+/// An entry is this header followed by the program. The header is read and
+/// checked first, so the program of an entry written with a different AST
+/// layout is never decoded.
+#[derive(Serialize, Deserialize)]
+struct CacheHeader {
+    format: u32,
+    interpreter_version: String,
+    ast_fingerprint: [u8; 32],
+    source_len: u64,
+    source_mtime_nanos: u128,
+    source_hash: [u8; 32],
+}
+
+/// Turn the module cache on or off for this thread (on by default, unless
+/// `SLANG_NO_CACHE` is set).
+pub fn set_enabled(enabled: bool) {
+    ENABLED.with(|flag| flag.set(enabled));
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.with(|flag| flag.get()) && std::env::var_os("SLANG_NO_CACHE").is_none()
+}
+
+/// Store cache entries in `dir` instead of the default location.
+pub fn set_cache_dir(dir: Option<PathBuf>) {
+    DIR_OVERRIDE.with(|d| *d.borrow_mut() = dir);
+}
+
+/// The directory set with [`set_cache_dir`], if any.
+pub fn cache_dir_override() -> Option<PathBuf> {
+    DIR_OVERRIDE.with(|d| d.borrow().clone())
+}
+
+/// The directory cache entries live in: an explicit override, then
+/// `$SLANG_CACHE_DIR`, then `$XDG_CACHE_HOME/slang/ast`, then
+/// `~/.cache/slang/ast`.
+pub fn cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = cache_dir_override() {
+        return Some(dir);
+    }
+    if let Some(dir) = std::env::var_os("SLANG_CACHE_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+    if let Some(dir) = std::env::var_os("XDG_CACHE_HOME") {
+        return Some(PathBuf::from(dir).join("slang").join("ast"));
+    }
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(".cache").join("slang").join("ast"))
+}
+
+/// Return the cached AST for the module at `path` if it is still valid.
+pub fn load(path: &Path) -> Option<Program> {
+    if !is_enabled() {
+        return None;
+    }
+
+    let entry_path = entry_path(path)?;
+    let mut reader = BufReader::new(File::open(&entry_path).ok()?);
+    let header: CacheHeader = match bincode::deserialize_from(&mut reader) {
+        Ok(header) => header,
+        Err(e) => {
+            debug_log!("module cache: unreadable entry {}: {}", entry_path.display(), e);
+            return None;
+        }
+    };
+
+    if header.format != CACHE_FORMAT
+        || header.interpreter_version != env!("CARGO_PKG_VERSION")
+        || header.ast_fingerprint != ast_fingerprint()
+    {
+        return None;
+    }
+
+    let program: Program = match bincode::deserialize_from(&mut reader) {
+        Ok(program) => program,
+        Err(e) => {
+            debug_log!("module cache: unreadable entry {}: {}", entry_path.display(), e);
+            return None;
+        }
+    };
+
+    let (len, mtime) = file_stamp(path)?;
+    if header.source_len == len && header.source_mtime_nanos == mtime {
+        debug_log!("module cache: hit for {}", path.display());
+        return Some(program);
+    }
+
+    // Metadata changed; the content may not have.
+    let source = fs::read_to_string(path).ok()?;
+    if hash_source(&source) != header.source_hash {
+        debug_log!("module cache: stale entry for {}", path.display());
+        return None;
+    }
+
+    debug_log!("module cache: refreshed entry for {}", path.display());
+    store(path, &source, &program);
+    Some(program)
+}
+
+/// Record the parsed AST for `path`, whose current contents are `source`.
+pub fn store(path: &Path, source: &str, program: &Program) {
+    if !is_enabled() {
+        return;
+    }
+
+    let (Some(entry_path), Some((len, mtime))) = (entry_path(path), file_stamp(path)) else {
+        return;
+    };
+
+    let header = CacheHeader {
+        format: CACHE_FORMAT,
+        interpreter_version: env!("CARGO_PKG_VERSION").to_string(),
+        ast_fingerprint: ast_fingerprint(),
+        source_len: len,
+        source_mtime_nanos: mtime,
+        source_hash: hash_source(source),
+    };
+
+    if let Err(e) = write_entry(&entry_path, &header, program) {
+        debug_log!("module cache: failed to write {}: {}", entry_path.display(), e);
+    }
+}
+
+fn write_entry(
+    entry_path: &Path,
+    header: &CacheHeader,
+    program: &Program,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(dir) = entry_path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    // Write to a temporary file and rename so readers never see a partial entry.
+    let tmp_path = entry_path.with_extension(format!("tmp{}", std::process::id()));
+    {
+        let mut writer = BufWriter::new(File::create(&tmp_path)?);
+        bincode::serialize_into(&mut writer, header)?;
+        bincode::serialize_into(&mut writer, program)?;
+    }
+    fs::rename(&tmp_path, entry_path)?;
+    Ok(())
+}
+
+fn entry_path(path: &Path) -> Option<PathBuf> {
+    let key = Sha256::digest(path.to_string_lossy().as_bytes());
+    let name: String = key.iter().map(|b| format!("{:02x}", b)).collect();
+    Some(cache_dir()?.join(format!("{}.ast", name)))
+}
+
+fn file_stamp(path: &Path) -> Option<(u64, u128)> {
+    let meta = fs::metadata(path).ok()?;
+    let mtime = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    Some((meta.len(), mtime.as_nanos()))
+}
+
+/// A hash of the AST definitions, so entries written by a build with a
+/// different AST layout are never deserialized into the current one.
+pub(crate) fn ast_fingerprint() -> [u8; 32] {
+    static FINGERPRINT: OnceLock<[u8; 32]> = OnceLock::new();
+    *FINGERPRINT.get_or_init(|| hash_source(include_str!("../ast/nodes.rs")))
+}
+
+fn hash_source(source: &str) -> [u8; 32] {
+    Sha256::digest(source.as_bytes()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{
+        entry_path, file_stamp, hash_source, load, set_cache_dir, store, CacheHeader, CACHE_FORMAT,
+    };
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+    use crate::test_support::temp_dir;
+
+    #[test]
+    fn entries_are_reused_until_the_source_changes() {
+        let dir = temp_dir("slang_ast_cache");
+        set_cache_dir(Some(dir.join("cache")));
+
+        let module = dir.join("mod.sl");
+        let source = "let x = 1;";
+        fs::write(&module, source).unwrap();
+        assert!(load(&module).is_none());
+
+        let program = Parser::new(Lexer::new(source)).parse_program();
+        store(&module, source, &program);
+        let cached = load(&module).expect("fresh entry should load");
+        assert_eq!(cached.statements, program.statements);
+
+        fs::write(&module, "let x = 22;").unwrap();
+        assert!(load(&module).is_none(), "edited source must invalidate the entry");
+
+        set_cache_dir(None);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn entries_from_another_ast_layout_are_not_decoded() {
+        let dir = temp_dir("slang_ast_cache_layout");
+        set_cache_dir(Some(dir.join("cache")));
+
+        let module = dir.join("mod.sl");
+        let source = "let x = 1;";
+        fs::write(&module, source).unwrap();
+        let program = Parser::new(Lexer::new(source)).parse_program();
+        store(&module, source, &program);
+
+        // Another build's entry: a different fingerprint, then bytes that
+        // would decode as an enormous length under this layout.
+        let entry = entry_path(&module).unwrap();
+        let (len, mtime) = file_stamp(&module).unwrap();
+        let header = CacheHeader {
+            format: CACHE_FORMAT,
+            interpreter_version: env!("CARGO_PKG_VERSION").to_string(),
+            ast_fingerprint: [0; 32],
+            source_len: len,
+            source_mtime_nanos: mtime,
+            source_hash: hash_source(source),
+        };
+        let mut bytes = bincode::serialize(&header).unwrap();
+        bytes.extend([0xff; 16]);
+        fs::write(&entry, bytes).unwrap();
+        assert!(load(&module).is_none());
+
+        set_cache_dir(None);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
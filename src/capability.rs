@@ -0,0 +1,16 @@
+pub mod core;
+pub(crate) use core::{audit_log, set_audit_log};
+pub use core::{
+    Capabilities,
+    Group,
+    audited,
+    disable_audit,
+    disable_dry_run,
+    enable_audit,
+    enable_dry_run,
+    is_auditing,
+    is_dry_run,
+    log_dry_run,
+    record_audit,
+    summarize_args,
+};
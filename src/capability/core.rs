@@ -0,0 +1,212 @@
+//! Capability controls for side-effecting builtins.
+//!
+//! Builtins that reach outside the interpreter (file writes, process
+//! execution, mutating HTTP calls) consult this layer before acting. The
+//! settings are thread-local, matching the single-threaded evaluator, so an
+//! embedder (or a test) can toggle them without affecting other interpreters.
+
+use std::cell::{Cell, RefCell};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::env::EnvRef;
+use crate::object::types::BuiltinFunction;
+use crate::object::Object;
+
+/// Longest rendering of a single argument kept in an audit record.
+const AUDIT_ARG_MAX_CHARS: usize = 120;
+
+thread_local! {
+    static DRY_RUN: Cell<bool> = const { Cell::new(false) };
+    static AUDIT_LOG: RefCell<Option<File>> = const { RefCell::new(None) };
+}
+
+/// Turn on dry-run mode: side-effecting builtins log what they would do and
+/// return synthetic `Ok` results instead of performing the operation.
+pub fn enable_dry_run() {
+    DRY_RUN.with(|flag| flag.set(true));
+}
+
+pub fn disable_dry_run() {
+    DRY_RUN.with(|flag| flag.set(false));
+}
+
+pub fn is_dry_run() -> bool {
+    DRY_RUN.with(|flag| flag.get())
+}
+
+/// Report an operation that was skipped because dry-run mode is active.
+/// Goes to stderr so script output on stdout is unaffected.
+pub fn log_dry_run(op: &str, detail: &str) {
+    eprintln!("[dry-run] {}: {}", op, detail);
+}
+
+/// Start recording side-effecting builtin calls to `path` as JSON lines.
+/// The file is appended to, so several runs can share one log.
+pub fn enable_audit(path: &Path) -> io::Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    AUDIT_LOG.with(|log| *log.borrow_mut() = Some(file));
+    Ok(())
+}
+
+pub fn disable_audit() {
+    AUDIT_LOG.with(|log| *log.borrow_mut() = None);
+}
+
+/// Another handle on the audit log, for a task thread to write to.
+pub(crate) fn audit_log() -> Option<File> {
+    AUDIT_LOG.with(|log| log.borrow().as_ref().and_then(|f| f.try_clone().ok()))
+}
+
+pub(crate) fn set_audit_log(file: Option<File>) {
+    AUDIT_LOG.with(|log| *log.borrow_mut() = file);
+}
+
+pub fn is_auditing() -> bool {
+    AUDIT_LOG.with(|log| log.borrow().is_some())
+}
+
+/// Run a side-effecting builtin, recording the call in the audit log when
+/// auditing is enabled.
+pub fn audited(op: &str, args: Vec<Object>, env: EnvRef, func: BuiltinFunction) -> Object {
+    if !is_auditing() {
+        return func(args, env);
+    }
+
+    let summary = summarize_args(&args);
+    let result = func(args, env);
+    let (status, error) = match &result {
+        Object::Error(msg) => ("error", Some(msg.clone())),
+        Object::ResultErr(inner) => ("error", Some(inner.to_string())),
+        _ if is_dry_run() => ("dry-run", None),
+        _ => ("ok", None),
+    };
+    record_audit(op, &summary, status, error.as_deref());
+    result
+}
+
+/// Append one record to the audit log, if auditing is enabled. Write
+/// failures are reported on stderr rather than failing the script.
+pub fn record_audit(op: &str, args: &[String], status: &str, error: Option<&str>) {
+    AUDIT_LOG.with(|log| {
+        let mut log = log.borrow_mut();
+        let Some(file) = log.as_mut() else {
+            return;
+        };
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let mut record = serde_json::json!({
+            "timestamp": timestamp,
+            "op": op,
+            "args": args,
+            "status": status,
+        });
+        if let Some(err) = error {
+            record["error"] = serde_json::Value::String(err.to_string());
+        }
+
+        if let Err(e) = writeln!(file, "{}", record) {
+            eprintln!("[audit] failed to write audit record: {}", e);
+        }
+    });
+}
+
+/// Render each argument for the audit log, truncating long values.
+pub fn summarize_args(args: &[Object]) -> Vec<String> {
+    args.iter()
+        .map(|arg| {
+            let rendered = arg.to_string();
+            if rendered.chars().count() > AUDIT_ARG_MAX_CHARS {
+                let truncated: String = rendered.chars().take(AUDIT_ARG_MAX_CHARS).collect();
+                format!("{}...", truncated)
+            } else {
+                rendered
+            }
+        })
+        .collect()
+}
+
+/// Groups of builtins that reach outside the interpreter, which an embedder
+/// can switch off per environment (see `EnvBuilder`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Group {
+    Fs,
+    Net,
+    Process,
+}
+
+impl Group {
+    pub fn description(self) -> &'static str {
+        match self {
+            Group::Fs => "filesystem access",
+            Group::Net => "network access",
+            Group::Process => "process access",
+        }
+    }
+}
+
+/// Namespaces, namespace members (`Ns::member`) and global builtins that
+/// belong to a capability group.
+const GATED: &[(&str, Group)] = &[
+    ("File", Group::Fs),
+    ("file_open", Group::Fs),
+    ("file_read", Group::Fs),
+    ("file_write", Group::Fs),
+    ("file_seek", Group::Fs),
+    ("file_close", Group::Fs),
+    ("Sys::cwd", Group::Fs),
+    ("Sys::setCwd", Group::Fs),
+    ("Dir", Group::Fs),
+    ("Path::absolute", Group::Fs),
+    ("HTTP", Group::Net),
+    ("HttpServer", Group::Net),
+    ("Sys::exec", Group::Process),
+    ("Process", Group::Process),
+    ("Sys::exit", Group::Process),
+    ("Sys::env", Group::Process),
+    ("Sys::setEnv", Group::Process),
+    ("Sys::args", Group::Process),
+];
+
+/// The capability groups an environment may use. Everything is allowed by
+/// default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    pub fs: bool,
+    pub net: bool,
+    pub process: bool,
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Capabilities { fs: true, net: true, process: true }
+    }
+}
+
+impl Capabilities {
+    pub fn allows_group(&self, group: Group) -> bool {
+        match group {
+            Group::Fs => self.fs,
+            Group::Net => self.net,
+            Group::Process => self.process,
+        }
+    }
+
+    /// The disabled group `path` belongs to, if any.
+    pub fn denied_group(&self, path: &str) -> Option<Group> {
+        GATED
+            .iter()
+            .find(|(gated, _)| *gated == path)
+            .map(|(_, group)| *group)
+            .filter(|group| !self.allows_group(*group))
+    }
+
+    pub fn allows(&self, path: &str) -> bool {
+        self.denied_group(path).is_none()
+    }
+}
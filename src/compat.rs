@@ -0,0 +1,5 @@
+pub mod core;
+
+pub use core::{
+    call_constant, fix_source, replacement_for, resolve_legacy, Fix, CONSTANT_CALLS, LEGACY_NAMES,
+};
@@ -0,0 +1,489 @@
+//! Compatibility shims for the v2 builtin layout.
+//!
+//! The builtin surface grew a few duplicate spellings over time: global
+//! helpers that shadow namespace members (`regexFind` vs `Regex::find`,
+//! `first` vs `Array::first`) and the `Obj` namespace, which is now `Object`
+//! to match the other full-word namespace names. The legacy spellings keep
+//! working but print a one-time deprecation warning, and `slang fix`
+//! rewrites scripts to the v2 names.
+//!
+//! Namespace constants (`Math::PI`, `Sys::platform`, ...) used to be
+//! zero-argument functions. Calling one the old way still returns the value,
+//! with the same kind of warning, and `slang fix` drops the parentheses.
+//!
+//! `len` and the `int`/`float`/`str`/`bool` conversions stay global: `len`
+//! works on both strings and arrays, and the conversions return raw values
+//! where their `Type::` counterparts return `Result`s.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashSet};
+
+use crate::ast::nodes::{FunctionLiteral, FunctionStatement};
+use crate::ast::{BlockStatement, Expression, Program, Statement};
+use crate::env::EnvRef;
+use crate::lexer::{block_comment_end, string_end, Lexer};
+use crate::object::Object;
+use crate::parser::Parser;
+
+/// Legacy name and its v2 replacement, as a `Namespace` or `Namespace::member` path.
+pub const LEGACY_NAMES: &[(&str, &str)] = &[
+    ("Obj", "Object"),
+    ("first", "Array::first"),
+    ("last", "Array::last"),
+    ("rest", "Array::rest"),
+    ("push", "Array::push"),
+    ("regexIsMatch", "Regex::isMatch"),
+    ("regexFind", "Regex::find"),
+    ("regexReplace", "Regex::replace"),
+    ("regexMatch", "Regex::match"),
+    ("test_assert", "Test::assert"),
+    ("test_assert_eq", "Test::assertEq"),
+    ("test_assert_not_eq", "Test::assertNotEq"),
+];
+
+/// Namespace constants that were once zero-argument functions, as the legacy
+/// call and the plain value it became.
+pub const CONSTANT_CALLS: &[(&str, &str)] = &[
+    ("Math::PI()", "Math::PI"),
+    ("Math::E()", "Math::E"),
+    ("Math::TAU()", "Math::TAU"),
+    ("Sys::platform()", "Sys::platform"),
+];
+
+thread_local! {
+    static WARNED: RefCell<HashSet<&'static str>> = RefCell::new(HashSet::new());
+}
+
+/// The v2 replacement for a legacy name, if `name` is one.
+pub fn replacement_for(name: &str) -> Option<&'static str> {
+    LEGACY_NAMES
+        .iter()
+        .find(|(legacy, _)| *legacy == name)
+        .map(|(_, replacement)| *replacement)
+}
+
+/// Resolve a legacy name through its v2 path in `env`, warning (once per
+/// name) that the old spelling is deprecated.
+pub fn resolve_legacy(name: &str, env: &EnvRef) -> Option<Object> {
+    let (legacy, replacement) = LEGACY_NAMES.iter().find(|(legacy, _)| *legacy == name)?;
+
+    let mut parts = replacement.split("::");
+    let mut value = env.borrow().get(parts.next()?)?;
+    for member in parts {
+        value = match value {
+            Object::Object(map) => map.get(member)?.clone(),
+            _ => return None,
+        };
+    }
+
+    WARNED.with(|warned| {
+        if warned.borrow_mut().insert(legacy) {
+            eprintln!(
+                "warning: `{}` is deprecated, use `{}` instead (run `slang fix` to update scripts)",
+                legacy, replacement
+            );
+        }
+    });
+
+    Some(value)
+}
+
+/// The result of calling the constant `namespace::member` (whose value is
+/// `value`) with `args`, if it is one of the [`CONSTANT_CALLS`]: the value
+/// itself for the legacy `()` form, with a one-time warning, or an error when
+/// arguments are passed.
+pub fn call_constant(namespace: &str, member: &str, value: &Object, args: &[Object]) -> Option<Object> {
+    let path = format!("{}::{}", namespace, member);
+    let (legacy, replacement) = CONSTANT_CALLS.iter().find(|(_, constant)| *constant == path)?;
+
+    if !args.is_empty() {
+        return Some(Object::error(format!(
+            "{} is a constant, not a function; use `{}`",
+            replacement, replacement
+        )));
+    }
+
+    WARNED.with(|warned| {
+        if warned.borrow_mut().insert(legacy) {
+            eprintln!(
+                "warning: `{}` is deprecated, use `{}` instead (run `slang fix` to update scripts)",
+                legacy, replacement
+            );
+        }
+    });
+
+    Some(value.clone())
+}
+
+/// One kind of rewrite applied by [`fix_source`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fix {
+    pub legacy: &'static str,
+    pub replacement: &'static str,
+    pub count: usize,
+}
+
+/// Rewrite legacy names in `source` to their v2 spellings, and legacy
+/// constant calls like `Math::PI()` to plain `Math::PI`.
+///
+/// The source is parsed first so that only names the program actually
+/// references (and doesn't redeclare itself) are touched; the rewrite is then
+/// applied to the original text so comments and layout survive. Returns the
+/// parser errors if the source doesn't parse.
+pub fn fix_source(source: &str) -> Result<(String, Vec<Fix>), Vec<String>> {
+    let lexer = Lexer::new(source);
+    let mut parser = Parser::new(lexer);
+    let program = parser.parse_program();
+    if !parser.errors.is_empty() {
+        return Err(parser.errors);
+    }
+
+    let mut names = Names::default();
+    names.program(&program);
+    let targets: HashSet<&str> = names
+        .referenced
+        .iter()
+        .filter(|name| !names.declared.contains(*name))
+        .filter_map(|name| LEGACY_NAMES.iter().find(|(legacy, _)| legacy == name))
+        .map(|(legacy, _)| *legacy)
+        .collect();
+
+    let mut output = String::with_capacity(source.len());
+    let mut counts: BTreeMap<&'static str, usize> = BTreeMap::new();
+    let chars: Vec<char> = source.chars().collect();
+    let mut prev_significant: Option<char> = None;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let ch = chars[i];
+
+        if ch == '"' {
+            let start = i;
+            i = string_end(&chars, i).unwrap_or(chars.len());
+            output.extend(&chars[start..i]);
+            prev_significant = Some('"');
+            continue;
+        }
+
+        if ch == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                output.push(chars[i]);
+                i += 1;
+            }
+            continue;
+        }
+
+        if ch == '/' && chars.get(i + 1) == Some(&'*') {
+            let start = i;
+            i = block_comment_end(&chars, i).unwrap_or(chars.len());
+            output.extend(&chars[start..i]);
+            continue;
+        }
+
+        if ch.is_alphabetic() || ch == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+
+            // Members (`x.first`, `Ns::first`), tags (`:first`) and object
+            // keys (`{ first: 1 }`) are not references to the legacy global.
+            let is_member = matches!(prev_significant, Some('.') | Some(':'));
+            let next = chars[i..].iter().position(|c| !c.is_whitespace()).map(|off| i + off);
+            let is_key = next.is_some_and(|n| {
+                chars[n] == ':' && chars.get(n + 1) != Some(&':')
+            });
+
+            match LEGACY_NAMES.iter().find(|(legacy, _)| *legacy == word) {
+                Some((legacy, replacement))
+                    if targets.contains(legacy) && !is_member && !is_key =>
+                {
+                    output.push_str(replacement);
+                    *counts.entry(legacy).or_default() += 1;
+                }
+                _ => output.push_str(&word),
+            }
+            prev_significant = Some('a');
+
+            // `Math::PI()` -> `Math::PI`, unless the script declares its own `Math`.
+            let constant = CONSTANT_CALLS.iter().find(|(_, constant)| {
+                output.ends_with(constant)
+                    && !output[..output.len() - constant.len()]
+                        .ends_with(|c: char| c.is_alphanumeric() || c == '_' || c == '.' || c == ':')
+                    && constant
+                        .split("::")
+                        .next()
+                        .is_some_and(|ns| !names.declared.contains(ns))
+            });
+            if let Some((legacy, _)) = constant {
+                let mut j = i;
+                let skip_ws = |mut j: usize| {
+                    while j < chars.len() && chars[j].is_whitespace() {
+                        j += 1;
+                    }
+                    j
+                };
+                j = skip_ws(j);
+                if chars.get(j) == Some(&'(') {
+                    j = skip_ws(j + 1);
+                    if chars.get(j) == Some(&')') {
+                        i = j + 1;
+                        prev_significant = Some(')');
+                        *counts.entry(legacy).or_default() += 1;
+                    }
+                }
+            }
+            continue;
+        }
+
+        if !ch.is_whitespace() {
+            prev_significant = Some(ch);
+        }
+        output.push(ch);
+        i += 1;
+    }
+
+    let fixes = counts
+        .into_iter()
+        .map(|(legacy, count)| Fix {
+            legacy,
+            replacement: replacement_for(legacy)
+                .or_else(|| {
+                    CONSTANT_CALLS
+                        .iter()
+                        .find(|(call, _)| *call == legacy)
+                        .map(|(_, constant)| *constant)
+                })
+                .unwrap_or(legacy),
+            count,
+        })
+        .collect();
+
+    Ok((output, fixes))
+}
+
+/// Identifiers a program references and the names it binds itself.
+#[derive(Default)]
+struct Names {
+    referenced: HashSet<String>,
+    declared: HashSet<String>,
+}
+
+impl Names {
+    fn program(&mut self, program: &Program) {
+        for stmt in &program.statements {
+            self.statement(stmt);
+        }
+    }
+
+    fn block(&mut self, block: &BlockStatement) {
+        for stmt in &block.statements {
+            self.statement(stmt);
+        }
+    }
+
+    fn function(&mut self, literal: &FunctionLiteral) {
+        for param in literal.params.iter().chain(literal.rest.iter()) {
+            self.declared.insert(param.value.clone());
+        }
+        for default in literal.defaults.iter().flatten() {
+            self.expression(default);
+        }
+        for clause in literal.requires.iter().chain(&literal.ensures) {
+            self.expression(clause);
+        }
+        self.block(&literal.body);
+    }
+
+    fn function_statement(&mut self, fs: &FunctionStatement) {
+        self.declared.insert(fs.name.value.clone());
+        self.function(&fs.literal);
+    }
+
+    fn statement(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::Let(ls) => {
+                self.declared.insert(ls.name.value.clone());
+                self.expression(&ls.value);
+            }
+            Statement::Destructure(ds) => {
+                for name in ds.pattern.bindings() {
+                    self.declared.insert(name.value);
+                }
+                self.expression(&ds.value);
+            }
+            Statement::Return(rs) => self.expression(&rs.return_value),
+            Statement::Expression(es) => self.expression(&es.expression),
+            Statement::While(ws) => {
+                self.expression(&ws.condition);
+                self.block(&ws.body);
+            }
+            Statement::For(fs) => {
+                if let Some(init) = &fs.init {
+                    self.statement(init);
+                }
+                if let Some(cond) = &fs.condition {
+                    self.expression(cond);
+                }
+                if let Some(post) = &fs.post {
+                    self.statement(post);
+                }
+                self.block(&fs.body);
+            }
+            Statement::ForIn(fs) => {
+                self.declared.insert(fs.name.value.clone());
+                self.expression(&fs.iterable);
+                self.block(&fs.body);
+            }
+            Statement::Yield(ys) => self.expression(&ys.value),
+            Statement::Use(us) => {
+                if let Some(name) = us.binding() {
+                    self.declared.insert(name.value.clone());
+                }
+            }
+            Statement::Breakpoint => {}
+            Statement::Switch(ss) => {
+                self.expression(&ss.subject);
+                for case in &ss.cases {
+                    for value in &case.values {
+                        self.expression(value);
+                    }
+                    self.block(&case.body);
+                }
+                if let Some(default) = &ss.default {
+                    self.block(default);
+                }
+            }
+            Statement::Function(fs) => self.function_statement(fs),
+            Statement::Test(ts) => self.block(&ts.body),
+            Statement::Describe(ds) => self.block(&ds.body),
+            Statement::Hook(hs) => self.block(&hs.body),
+            Statement::Namespace(ns) => {
+                self.declared.insert(ns.name.value.clone());
+                self.block(&ns.body);
+            }
+            Statement::Import(is) => {
+                for name in &is.names {
+                    self.declared.insert(name.value.clone());
+                }
+            }
+            Statement::Export(es) => {
+                if let Some(decl) = &es.declaration {
+                    self.statement(decl);
+                }
+            }
+            Statement::Class(cs) => {
+                self.declared.insert(cs.name.value.clone());
+                for method in &cs.methods {
+                    self.function(&method.literal);
+                }
+            }
+        }
+    }
+
+    fn expression(&mut self, expr: &Expression) {
+        match expr {
+            Expression::Identifier(ident) => {
+                self.referenced.insert(ident.value.clone());
+            }
+            Expression::IntegerLiteral(_)
+            | Expression::BooleanLiteral(_)
+            | Expression::FloatLiteral(_)
+            | Expression::StringLiteral(_)
+            | Expression::BytesLiteral(_)
+            | Expression::NullLiteral => {}
+            Expression::Infix(infix) => {
+                self.expression(&infix.left);
+                self.expression(&infix.right);
+            }
+            Expression::If(ifexpr) => {
+                self.expression(&ifexpr.condition);
+                self.block(&ifexpr.consequence);
+                if let Some(alt) = &ifexpr.alternative {
+                    self.block(alt);
+                }
+            }
+            Expression::Prefix(p) => self.expression(&p.right),
+            Expression::Postfix(p) => self.expression(&p.left),
+            Expression::FunctionLiteral(fl) => self.function(fl),
+            Expression::CallExpression(call) => {
+                self.expression(&call.function);
+                for arg in &call.arguments {
+                    self.expression(arg);
+                }
+            }
+            Expression::ArrayLiteral(al) => {
+                for el in &al.elements {
+                    self.expression(el);
+                }
+            }
+            Expression::IndexExpression(ix) => {
+                self.expression(&ix.left);
+                self.expression(&ix.index);
+            }
+            Expression::ObjectLiteral(ol) => {
+                for (_, value) in &ol.properties {
+                    self.expression(value);
+                }
+            }
+            Expression::PropertyAccess(pa) => self.expression(&pa.object),
+            Expression::Publish(pubexpr) => {
+                for arg in &pubexpr.args {
+                    self.expression(arg);
+                }
+            }
+            Expression::New(new_expr) => {
+                self.referenced.insert(new_expr.class_name.value.clone());
+                for arg in &new_expr.arguments {
+                    self.expression(arg);
+                }
+            }
+            Expression::Spread(spread) => self.expression(&spread.value),
+            Expression::Builtin(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fix_source;
+
+    #[test]
+    fn fix_rewrites_legacy_names_and_keeps_layout() {
+        let source = "// uses Obj and first\n/* Obj /* first([0]); */ */\nlet keys = Obj::keys({ first: 1 });\nfirst([1, 2]); // first\n";
+        let (fixed, fixes) = fix_source(source).expect("source should parse");
+
+        assert_eq!(
+            fixed,
+            "// uses Obj and first\n/* Obj /* first([0]); */ */\nlet keys = Object::keys({ first: 1 });\nArray::first([1, 2]); // first\n"
+        );
+        let summary: Vec<(&str, usize)> = fixes.iter().map(|f| (f.legacy, f.count)).collect();
+        assert_eq!(summary, vec![("Obj", 1), ("first", 1)]);
+    }
+
+    #[test]
+    fn fix_skips_names_the_script_declares() {
+        let source = "let first = fn(xs) { xs[0]; };\nfirst([1]);\nlet s = \"regexFind\";\n";
+        let (fixed, fixes) = fix_source(source).expect("source should parse");
+        assert_eq!(fixed, source);
+        assert!(fixes.is_empty());
+    }
+
+    #[test]
+    fn fix_drops_calls_on_namespace_constants() {
+        let source = "let area = Math::PI( ) * r * r;\nlet os = Sys::platform();\nlet f = Math::PI;\n";
+        let (fixed, fixes) = fix_source(source).expect("source should parse");
+        assert_eq!(
+            fixed,
+            "let area = Math::PI * r * r;\nlet os = Sys::platform;\nlet f = Math::PI;\n"
+        );
+        let summary: Vec<(&str, &str)> = fixes.iter().map(|f| (f.legacy, f.replacement)).collect();
+        assert_eq!(summary, vec![("Math::PI()", "Math::PI"), ("Sys::platform()", "Sys::platform")]);
+    }
+
+    #[test]
+    fn fix_reports_parse_errors() {
+        assert!(fix_source("let = ;").is_err());
+    }
+}
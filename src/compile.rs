@@ -0,0 +1,2 @@
+pub mod core;
+pub use core::{compile_source, is_compiled, load_compiled, MAGIC};
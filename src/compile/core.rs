@@ -0,0 +1,113 @@
+//! Precompiled scripts (`.slc` files).
+//!
+//! `slang compile` parses a script once, rejecting it if there are any parse
+//! errors, and writes the AST to a compact binary artifact. `slang run` loads
+//! the artifact and evaluates it without lexing or parsing. Artifacts are tied
+//! to the interpreter build that wrote them; loading one from another build is
+//! an error rather than a silent misread.
+
+use serde::{Deserialize, Serialize};
+
+use crate::ast::Program;
+use crate::cache::core::ast_fingerprint;
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+
+/// Leading bytes of every `.slc` file.
+pub const MAGIC: &[u8; 4] = b"SLC\0";
+
+/// Bump when the layout of `ArtifactHeader` itself changes.
+const ARTIFACT_FORMAT: u32 = 1;
+
+/// An artifact is this header followed by the program, so the build that
+/// wrote it is checked before the program is decoded.
+#[derive(Serialize, Deserialize)]
+struct ArtifactHeader {
+    format: u32,
+    interpreter_version: String,
+    ast_fingerprint: [u8; 32],
+}
+
+/// Parse `source` and serialize it as a `.slc` artifact, or return the
+/// parser's errors.
+pub fn compile_source(source: &str) -> Result<Vec<u8>, Vec<String>> {
+    let lexer = Lexer::new(source);
+    let mut parser = Parser::new(lexer);
+    let program = parser.parse_program();
+    if !parser.errors.is_empty() {
+        return Err(parser.errors);
+    }
+
+    let header = ArtifactHeader {
+        format: ARTIFACT_FORMAT,
+        interpreter_version: env!("CARGO_PKG_VERSION").to_string(),
+        ast_fingerprint: ast_fingerprint(),
+    };
+
+    let mut bytes = MAGIC.to_vec();
+    bincode::serialize_into(&mut bytes, &header)
+        .and_then(|()| bincode::serialize_into(&mut bytes, &program))
+        .map_err(|e| vec![format!("failed to serialize program: {}", e)])?;
+    Ok(bytes)
+}
+
+/// Whether `bytes` look like a `.slc` artifact.
+pub fn is_compiled(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+/// Load the program stored in a `.slc` artifact.
+pub fn load_compiled(bytes: &[u8]) -> Result<Program, String> {
+    let body = bytes
+        .strip_prefix(MAGIC.as_slice())
+        .ok_or_else(|| "not a compiled Slang file".to_string())?;
+
+    let mut reader = body;
+    let header: ArtifactHeader = bincode::deserialize_from(&mut reader)
+        .map_err(|e| format!("corrupt compiled file: {}", e))?;
+
+    if header.format != ARTIFACT_FORMAT
+        || header.interpreter_version != env!("CARGO_PKG_VERSION")
+        || header.ast_fingerprint != ast_fingerprint()
+    {
+        return Err(format!(
+            "compiled by a different Slang build ({}); recompile it with this version ({})",
+            header.interpreter_version,
+            env!("CARGO_PKG_VERSION")
+        ));
+    }
+
+    bincode::deserialize_from(&mut reader).map_err(|e| format!("corrupt compiled file: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compile_source, is_compiled, load_compiled};
+    use crate::env::new_env;
+    use crate::evaluator::eval;
+    use crate::object::Object;
+
+    #[test]
+    fn compiled_programs_round_trip() {
+        let bytes = compile_source("let x = 20; x + 22;").expect("source should compile");
+        assert!(is_compiled(&bytes));
+
+        let program = load_compiled(&bytes).expect("artifact should load");
+        assert_eq!(eval(&program, new_env()), Object::Integer(42));
+    }
+
+    #[test]
+    fn compile_rejects_parse_errors() {
+        let errors = compile_source("let = 1;").unwrap_err();
+        assert!(!errors.is_empty());
+    }
+
+    #[test]
+    fn load_rejects_foreign_or_damaged_files() {
+        assert!(load_compiled(b"let x = 1;").is_err());
+
+        let mut bytes = compile_source("1;").unwrap();
+        bytes.truncate(bytes.len() / 2);
+        assert!(load_compiled(&bytes).is_err());
+    }
+}
@@ -0,0 +1,2 @@
+pub mod core;
+pub use core::{build_report, install, set_program, watch_env};
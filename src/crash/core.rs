@@ -0,0 +1,235 @@
+//! Local crash reports for interpreter panics.
+//!
+//! A panic means a bug in Slang itself, not in the script. [`install`] adds a
+//! panic hook that writes what we know at that point (the script, the
+//! top-level statement being evaluated and the names bound in the global
+//! environment) to a file in the temp directory, and asks the user to attach
+//! it to a bug report. Nothing is sent anywhere. Binding values are left out
+//! of the report since they may hold secrets.
+//!
+//! The report identifies the top-level statement by its index and line in
+//! the script and prints it back from the AST.
+
+use std::cell::RefCell;
+use std::fmt::Write as _;
+use std::panic::{self, PanicHookInfo};
+use std::path::PathBuf;
+use std::rc::{Rc, Weak};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::ast::Program;
+use crate::build_info::build_info;
+use crate::env::{EnvRef, Environment};
+
+const BUG_REPORT_URL: &str = "https://github.com/damonswayn/slang/issues";
+
+thread_local! {
+    static PROGRAM: RefCell<Option<Rc<Program>>> = const { RefCell::new(None) };
+    static ENV: RefCell<Weak<RefCell<Environment>>> = const { RefCell::new(Weak::new()) };
+    /// Index of the statement being evaluated by each active `eval` call,
+    /// outermost (the main script) first.
+    static STATEMENTS: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Install the crash-report panic hook. `script` is the file being run, if
+/// any. The default hook still runs first, so the panic message and
+/// backtrace setting behave as usual.
+pub fn install(script: Option<PathBuf>) {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+        report_panic(info, script.as_ref());
+    }));
+}
+
+/// The program the main script evaluates, so a report can show the
+/// statement that was running.
+pub fn set_program(program: Rc<Program>) {
+    PROGRAM.with(|p| *p.borrow_mut() = Some(program));
+}
+
+/// The global environment to summarise in a report. Only a weak handle is
+/// kept, so this doesn't extend the environment's lifetime.
+pub fn watch_env(env: &EnvRef) {
+    ENV.with(|e| *e.borrow_mut() = Rc::downgrade(env));
+}
+
+/// Marks one `eval` call on the statement stack; dropped when it returns.
+pub(crate) struct EvalFrame(());
+
+impl EvalFrame {
+    pub(crate) fn enter() -> Self {
+        STATEMENTS.with(|s| s.borrow_mut().push(0));
+        EvalFrame(())
+    }
+
+    pub(crate) fn at_statement(&self, index: usize) {
+        STATEMENTS.with(|s| {
+            if let Some(top) = s.borrow_mut().last_mut() {
+                *top = index;
+            }
+        });
+    }
+}
+
+impl Drop for EvalFrame {
+    fn drop(&mut self) {
+        STATEMENTS.with(|s| {
+            s.borrow_mut().pop();
+        });
+    }
+}
+
+fn report_panic(info: &PanicHookInfo<'_>, script: Option<&PathBuf>) {
+    let message = if let Some(s) = info.payload().downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = info.payload().downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "(non-string panic payload)".to_string()
+    };
+    let location = info
+        .location()
+        .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+    let backtrace = std::backtrace::Backtrace::force_capture().to_string();
+
+    let report = build_report(&message, location.as_deref(), script, &backtrace);
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let path = std::env::temp_dir().join(format!(
+        "slang-crash-{}-{}.txt",
+        timestamp,
+        std::process::id()
+    ));
+
+    eprintln!("\nslang crashed. This is a bug in the interpreter, not in your script.");
+    match std::fs::write(&path, &report) {
+        Ok(()) => {
+            eprintln!("A crash report was written to {}", path.display());
+            eprintln!(
+                "Please file a bug at {} and attach it. It names your script's variables but not their values; review it before sharing.",
+                BUG_REPORT_URL
+            );
+        }
+        Err(e) => {
+            eprintln!("Failed to write a crash report to {}: {}", path.display(), e);
+            eprintln!("Please file a bug at {} including the report below.\n", BUG_REPORT_URL);
+            eprintln!("{}", report);
+        }
+    }
+}
+
+/// Render a crash report from the recorded interpreter state.
+pub fn build_report(
+    message: &str,
+    location: Option<&str>,
+    script: Option<&PathBuf>,
+    backtrace: &str,
+) -> String {
+    let mut out = String::from("Slang crash report\n==================\n\n");
+    let info = build_info();
+    let _ = writeln!(out, "interpreter: {}", info.summary());
+    let _ = writeln!(out, "platform:    {} {}", std::env::consts::OS, std::env::consts::ARCH);
+    let args: Vec<String> = std::env::args().collect();
+    let _ = writeln!(out, "command:     {}", args.join(" "));
+    let _ = writeln!(
+        out,
+        "script:      {}",
+        script.map(|p| p.display().to_string()).unwrap_or_else(|| "(none)".to_string())
+    );
+
+    let _ = writeln!(out, "\npanic: {}", message);
+    if let Some(location) = location {
+        let _ = writeln!(out, "at:    {}", location);
+    }
+
+    out.push_str("\nstatement:\n");
+    let stack = STATEMENTS.with(|s| s.borrow().clone());
+    match stack.first() {
+        Some(&index) => {
+            let (statement, line) = PROGRAM.with(|p| match p.borrow().as_ref() {
+                Some(program) => (
+                    program.statements.get(index).map(|s| s.to_string()),
+                    program.lines.get(index).copied(),
+                ),
+                None => (None, None),
+            });
+            match line {
+                Some(line) => {
+                    let _ = writeln!(out, "  top-level statement #{} (line {})", index + 1, line);
+                }
+                None => {
+                    let _ = writeln!(out, "  top-level statement #{}", index + 1);
+                }
+            }
+            if let Some(statement) = statement {
+                let _ = writeln!(out, "  {}", statement);
+            }
+            if stack.len() > 1 {
+                let _ = writeln!(out, "  (inside {} nested module evaluation(s))", stack.len() - 1);
+            }
+        }
+        None => out.push_str("  (not evaluating)\n"),
+    }
+
+    out.push_str("\nenvironment:\n");
+    match ENV.with(|e| e.borrow().upgrade()) {
+        Some(env) => match env.try_borrow() {
+            Ok(env) => {
+                let mut bindings: Vec<(String, &'static str)> = env
+                    .snapshot()
+                    .into_iter()
+                    .map(|(name, value)| (name, value.type_name()))
+                    .collect();
+                bindings.sort();
+                let _ = writeln!(out, "  {} global binding(s)", bindings.len());
+                for (name, type_name) in bindings {
+                    let _ = writeln!(out, "  {}: {}", name, type_name);
+                }
+            }
+            Err(_) => out.push_str("  (busy at the time of the crash)\n"),
+        },
+        None => out.push_str("  (unavailable)\n"),
+    }
+
+    let _ = writeln!(out, "\nbacktrace:\n{}", backtrace);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::rc::Rc;
+
+    use super::{EvalFrame, build_report, set_program, watch_env};
+    use crate::env::new_env;
+    use crate::lexer::Lexer;
+    use crate::object::Object;
+    use crate::parser::Parser;
+
+    #[test]
+    fn report_names_the_running_statement_and_bindings() {
+        let program = Parser::new(Lexer::new("let secret = \"hunter2\"; let n = 1; n + 1;")).parse_program();
+        set_program(Rc::new(program));
+        let env = new_env();
+        env.borrow_mut().set("secret".to_string(), Object::String("hunter2".to_string()));
+        watch_env(&env);
+
+        let frame = EvalFrame::enter();
+        frame.at_statement(2);
+        let report = build_report("boom", Some("src/x.rs:1:1"), Some(&PathBuf::from("main.sl")), "");
+        drop(frame);
+
+        assert!(report.contains("panic: boom"), "{}", report);
+        assert!(report.contains("script:      main.sl"));
+        assert!(report.contains("top-level statement #3 (line 1)\n  (n + 1)"), "{}", report);
+        assert!(report.contains("secret: string"));
+        assert!(!report.contains("hunter2"));
+
+        let idle = build_report("boom", None, None, "");
+        assert!(idle.contains("(not evaluating)"));
+    }
+}
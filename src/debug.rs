@@ -1,3 +1,7 @@
 pub mod core;
+pub mod debugger;
+mod console;
+pub mod dap;
 
-pub use core::{DEBUG_MODE, enable_debug_mode, disable_debug_mode};
\ No newline at end of file
+pub use core::{DEBUG_MODE, enable_debug_mode, disable_debug_mode};
+pub use debugger::Debugger;
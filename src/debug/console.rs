@@ -0,0 +1,257 @@
+//! The terminal front end of `slang debug`: a command prompt on stdin.
+
+use std::io::{BufRead, Write};
+
+use super::debugger::{Frontend, Resume, State, StopReason};
+
+/// Lines of source shown either side of the current line by `list`.
+const LIST_CONTEXT: usize = 3;
+
+const HELP: &str = "\
+Commands:
+  c, continue      run to the next breakpoint
+  s, step          run to the next line, stepping into calls
+  n, next          run to the next line in this function
+  o, out           run until this function returns
+  b, break LINE    set a breakpoint (no LINE: list them)
+  d, delete LINE   remove a breakpoint
+  bt, backtrace    show the call stack
+  f, frame N       inspect frame N of the backtrace
+  locals           show the variables in the current frame
+  p, print EXPR    evaluate EXPR in the current frame
+  l, list          show the source around the current line
+  q, quit          stop the script
+  h, help          show this list";
+
+pub(crate) struct Console {
+    source: Vec<String>,
+    input: Box<dyn BufRead>,
+    output: Box<dyn Write>,
+    /// Index into the frames that `locals`, `print` and `list` look at.
+    selected: usize,
+}
+
+impl Console {
+    pub(crate) fn new(source: &str, input: impl BufRead + 'static, output: impl Write + 'static) -> Self {
+        Console {
+            source: source.lines().map(str::to_string).collect(),
+            input: Box::new(input),
+            output: Box::new(output),
+            selected: 0,
+        }
+    }
+
+    /// Run one command; `None` means prompt for another.
+    fn command(&mut self, state: &mut State, command: &str) -> Option<Resume> {
+        let (name, arg) = match command.split_once(char::is_whitespace) {
+            Some((name, arg)) => (name, arg.trim()),
+            None => (command, ""),
+        };
+        let depth = state.frames.len();
+        match name {
+            "" => {}
+            "c" | "continue" => return Some(Resume::Continue),
+            "s" | "step" => return Some(Resume::StepIn),
+            "n" | "next" => return Some(Resume::Next),
+            "o" | "out" => return Some(Resume::Out),
+            "q" | "quit" => return Some(Resume::Quit),
+            "b" | "break" if arg.is_empty() => {
+                let lines: Vec<String> = state.breakpoints.iter().map(|l| l.to_string()).collect();
+                if lines.is_empty() {
+                    self.say("No breakpoints");
+                } else {
+                    self.say(&format!("Breakpoints at lines {}", lines.join(", ")));
+                }
+            }
+            "b" | "break" => match arg.parse::<usize>() {
+                Ok(line) if line > 0 => {
+                    state.breakpoints.insert(line);
+                    self.say(&format!("Breakpoint set at line {}", line));
+                }
+                _ => self.say("Usage: break LINE"),
+            },
+            "d" | "delete" => match arg.parse::<usize>() {
+                Ok(line) if state.breakpoints.remove(&line) => {
+                    self.say(&format!("Breakpoint at line {} removed", line));
+                }
+                Ok(line) => self.say(&format!("No breakpoint at line {}", line)),
+                Err(_) => self.say("Usage: delete LINE"),
+            },
+            "bt" | "backtrace" => self.backtrace(state),
+            "f" | "frame" => match arg.parse::<usize>() {
+                Ok(n) if n < depth => {
+                    self.selected = depth - 1 - n;
+                    self.backtrace(state);
+                }
+                _ => self.say(&format!("Usage: frame N, where N is 0 to {}", depth - 1)),
+            },
+            "locals" => {
+                let vars = state.locals(self.selected);
+                if vars.is_empty() {
+                    self.say("No local variables");
+                } else {
+                    let lines: Vec<String> = vars
+                        .iter()
+                        .map(|(name, value)| format!("{} = {}", name, value))
+                        .collect();
+                    self.say(&lines.join("\n"));
+                }
+            }
+            "p" | "print" if !arg.is_empty() => match state.evaluate(self.selected, arg) {
+                Ok(value) => self.say(&format!("= {}", value)),
+                Err(e) => self.say(&e),
+            },
+            "l" | "list" => self.list(state),
+            "h" | "help" => self.say(HELP),
+            _ => self.say(&format!("Unknown command '{}'; type 'help' for a list", command)),
+        }
+        None
+    }
+
+    fn say(&mut self, text: &str) {
+        let _ = writeln!(self.output, "{}", text);
+    }
+
+    fn show_line(&mut self, line: usize) {
+        if let Some(text) = self.source.get(line.wrapping_sub(1)) {
+            let text = format!("{:>4} | {}", line, text.trim_end());
+            self.say(&text);
+        }
+    }
+
+    fn backtrace(&mut self, state: &State) {
+        let mut out = Vec::new();
+        for (n, frame) in state.frames.iter().rev().enumerate() {
+            let marker = if state.frames.len() - 1 - n == self.selected { ">" } else { " " };
+            let line = match frame.line {
+                Some(line) if frame.stoppable => format!("line {}", line),
+                _ => "imported code".to_string(),
+            };
+            out.push(format!("{} #{} {} ({})", marker, n, frame.name, line));
+        }
+        self.say(&out.join("\n"));
+    }
+
+    fn list(&mut self, state: &State) {
+        let current = state.frames[self.selected].line.unwrap_or(1);
+        let first = current.saturating_sub(LIST_CONTEXT).max(1);
+        let last = (current + LIST_CONTEXT).min(self.source.len());
+        let mut out = Vec::new();
+        for line in first..=last {
+            let marker = if line == current {
+                ">"
+            } else if state.breakpoints.contains(&line) {
+                "*"
+            } else {
+                " "
+            };
+            out.push(format!("{}{:>4} | {}", marker, line, self.source[line - 1].trim_end()));
+        }
+        self.say(&out.join("\n"));
+    }
+}
+
+impl Frontend for Console {
+    fn stopped(&mut self, state: &mut State, reason: StopReason) -> Resume {
+        self.selected = state.frames.len() - 1;
+        let frame = &state.frames[self.selected];
+        let line = frame.line.unwrap_or(0);
+        let what = if reason == StopReason::Breakpoint { "breakpoint on line" } else { "line" };
+        let message = format!("Paused at {} {} in {}", what, line, frame.name);
+        self.say(&message);
+        self.show_line(line);
+
+        loop {
+            let _ = write!(self.output, "(slang debug) ");
+            let _ = self.output.flush();
+            let mut command = String::new();
+            match self.input.read_line(&mut command) {
+                Ok(0) | Err(_) => {
+                    // No more commands: let the script finish on its own.
+                    self.say("");
+                    return Resume::Detach;
+                }
+                Ok(_) => {}
+            }
+            if let Some(resume) = self.command(state, command.trim()) {
+                return resume;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::io::{Cursor, Write};
+    use std::rc::Rc;
+
+    use crate::debug::Debugger;
+    use crate::env::new_env;
+    use crate::lexer::Lexer;
+    use crate::object::Object;
+    use crate::parser::Parser;
+
+    #[derive(Clone, Default)]
+    struct Output(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Output {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    const SCRIPT: &str = "function add(a, b) {\n    let sum = a + b;\n    return sum;\n}\nlet x = 1;\nlet y = add(x, 2);\nbreakpoint;\ny * 2;\n";
+
+    fn debug(commands: &str, breaks: &[usize]) -> (Object, String) {
+        let output = Output::default();
+        let mut debugger = Debugger::new(SCRIPT, Cursor::new(commands.to_string()), output.clone())
+            .stop_on_entry(breaks.is_empty());
+        for line in breaks {
+            debugger = debugger.break_at(*line);
+        }
+        let program = Parser::new(Lexer::new(SCRIPT)).parse_program();
+        let result = debugger.run(&program, new_env());
+        let text = String::from_utf8(output.0.borrow().clone()).unwrap();
+        (result, text)
+    }
+
+    #[test]
+    fn steps_into_calls_and_inspects_frames() {
+        let (result, out) = debug("n\nn\ns\nlocals\nbt\np a * 10\nframe 1\nlocals\nc\nc\n", &[]);
+        assert_eq!(result, Object::Integer(6));
+        assert!(out.starts_with("Paused at line 1 in <script>\n   1 | function add(a, b) {"), "{}", out);
+        assert!(out.contains("Paused at line 2 in add\n   2 |     let sum = a + b;"), "{}", out);
+        assert!(out.contains("(slang debug) a = 1\nb = 2\n"), "{}", out);
+        assert!(out.contains("> #0 add (line 2)\n  #1 <script> (line 6)"), "{}", out);
+        assert!(out.contains("= 10\n"), "{}", out);
+        assert!(out.contains("add = <user fn>\nx = 1\n"), "{}", out);
+        assert!(out.contains("Paused at breakpoint on line 7 in <script>"), "{}", out);
+    }
+
+    #[test]
+    fn runs_to_line_breakpoints_and_steps_over_calls() {
+        let (result, out) = debug("n\nn\nq\n", &[6]);
+        assert_eq!(result, Object::error("debugger: quit"));
+        let stops: Vec<&str> = out.lines().filter(|l| l.contains("Paused")).collect();
+        assert_eq!(
+            stops,
+            vec![
+                "Paused at breakpoint on line 6 in <script>",
+                "(slang debug) Paused at breakpoint on line 7 in <script>",
+                "(slang debug) Paused at line 8 in <script>",
+            ]
+        );
+    }
+
+    #[test]
+    fn finishes_the_script_when_input_ends() {
+        let (result, _) = debug("", &[]);
+        assert_eq!(result, Object::Integer(6));
+    }
+}
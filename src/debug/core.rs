@@ -14,7 +14,7 @@ pub fn disable_debug_mode() {
 macro_rules! debug_log {
     ($($arg:tt)*) => {
         if cfg!(feature = "debug-logging")
-            && crate::debug::DEBUG_MODE.load(std::sync::atomic::Ordering::Relaxed)
+            && $crate::debug::DEBUG_MODE.load(std::sync::atomic::Ordering::Relaxed)
         {
             println!($($arg)*);
         }
@@ -0,0 +1,623 @@
+//! `slang dap`: the debugger over the Debug Adapter Protocol, for VS Code
+//! and other editors.
+//!
+//! Messages are JSON with a `Content-Length` header, read from one stream
+//! and written to another (stdin and stdout for `slang dap`). There is one
+//! thread, id 1, and one script per session, named by the `program`
+//! argument of `launch`. What the script prints arrives as `output` events
+//! so it can't corrupt the protocol stream.
+
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use serde_json::{Value, json};
+
+use super::debugger::{Debugger, Frontend, Resume, State, StopReason};
+use crate::ast::Program;
+use crate::builtins::native::redirect_stdout;
+use crate::env::new_env;
+use crate::lexer::Lexer;
+use crate::object::{Object, ObjectMap};
+use crate::parser::Parser;
+use crate::protocol::{read_message, write_message};
+
+const THREAD_ID: i64 = 1;
+
+/// Both ends of the protocol stream.
+struct Connection {
+    input: Box<dyn BufRead>,
+    output: Box<dyn Write>,
+    seq: i64,
+    /// Set once the client has asked to disconnect.
+    closed: bool,
+}
+
+type Shared = Rc<RefCell<Connection>>;
+
+impl Connection {
+    fn read(&mut self) -> io::Result<Option<Value>> {
+        read_message(&mut *self.input)
+    }
+
+    fn send(&mut self, mut message: Value) {
+        self.seq += 1;
+        message["seq"] = json!(self.seq);
+        let _ = write_message(&mut *self.output, &message);
+    }
+
+    fn respond(&mut self, request: &Value, body: Value) {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": request["command"],
+            "success": true,
+            "body": body,
+        }));
+    }
+
+    fn fail(&mut self, request: &Value, message: &str) {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": request["command"],
+            "success": false,
+            "message": message,
+        }));
+    }
+
+    fn event(&mut self, event: &str, body: Value) {
+        self.send(json!({ "type": "event", "event": event, "body": body }));
+    }
+}
+
+/// The script named by `launch`, parsed and waiting for `configurationDone`.
+struct Launch {
+    path: PathBuf,
+    program: Program,
+    stop_on_entry: bool,
+}
+
+/// Serve one debug session, reading requests from `input` and writing
+/// responses and events to `output`, until the client disconnects or the
+/// input ends.
+pub fn serve(input: impl BufRead + 'static, output: impl Write + 'static) -> io::Result<()> {
+    let conn: Shared = Rc::new(RefCell::new(Connection {
+        input: Box::new(input),
+        output: Box::new(output),
+        seq: 0,
+        closed: false,
+    }));
+    // Breakpoints set before the script runs, by the path they were set in.
+    let mut breakpoints: Vec<(PathBuf, BTreeSet<usize>)> = Vec::new();
+    let mut launch: Option<Launch> = None;
+    let mut configured = false;
+
+    loop {
+        let Some(request) = conn.borrow_mut().read()? else {
+            return Ok(());
+        };
+        let args = &request["arguments"];
+        let command = request["command"].as_str().unwrap_or("");
+        match command {
+            "initialize" => {
+                let mut c = conn.borrow_mut();
+                c.respond(&request, json!({
+                    "supportsConfigurationDoneRequest": true,
+                    "supportsEvaluateForHovers": true,
+                }));
+                c.event("initialized", json!({}));
+            }
+            "launch" => match load(args) {
+                Ok(script) => {
+                    launch = Some(script);
+                    conn.borrow_mut().respond(&request, json!({}));
+                }
+                Err(message) => conn.borrow_mut().fail(&request, &message),
+            },
+            "setBreakpoints" => {
+                let path = PathBuf::from(args["source"]["path"].as_str().unwrap_or(""));
+                let lines = requested_lines(args);
+                let body = breakpoints_body(&lines, true);
+                breakpoints.retain(|(p, _)| !same_file(p, &path));
+                breakpoints.push((path, lines));
+                conn.borrow_mut().respond(&request, body);
+            }
+            "configurationDone" => {
+                configured = true;
+                conn.borrow_mut().respond(&request, json!({}));
+            }
+            "threads" => conn.borrow_mut().respond(&request, threads_body()),
+            "disconnect" | "terminate" => {
+                conn.borrow_mut().respond(&request, json!({}));
+                return Ok(());
+            }
+            _ => conn.borrow_mut().fail(&request, &format!("'{}' is not supported", command)),
+        }
+
+        if !configured {
+            continue;
+        }
+        if let Some(script) = launch.take() {
+            let lines: BTreeSet<usize> = breakpoints
+                .iter()
+                .filter(|(path, _)| same_file(path, &script.path))
+                .flat_map(|(_, lines)| lines.iter().copied())
+                .collect();
+            run(&conn, script, lines);
+            if conn.borrow().closed {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Read and parse the script named by a `launch` request's arguments.
+fn load(args: &Value) -> Result<Launch, String> {
+    let Some(path) = args["program"].as_str() else {
+        return Err("launch needs a 'program' to debug".to_string());
+    };
+    let source = std::fs::read_to_string(path).map_err(|e| format!("{}: failed to read: {}", path, e))?;
+    let mut parser = Parser::new(Lexer::new(&source));
+    let program = parser.parse_program();
+    if !parser.errors.is_empty() {
+        return Err(format!("{}: parse errors:\n  {}", path, parser.errors.join("\n  ")));
+    }
+    Ok(Launch {
+        path: PathBuf::from(path),
+        program,
+        stop_on_entry: args["stopOnEntry"].as_bool().unwrap_or(false),
+    })
+}
+
+/// Run the launched script to the end, then report how it exited.
+fn run(conn: &Shared, script: Launch, breakpoints: BTreeSet<usize>) {
+    let env = new_env();
+    env.borrow_mut()
+        .set_module_dir(script.path.parent().map(|p| p.to_path_buf()));
+
+    let sink = Rc::clone(conn);
+    redirect_stdout(Some(Box::new(move |text: &str| {
+        sink.borrow_mut().event("output", json!({ "category": "stdout", "output": text }));
+    })));
+    let frontend = DapFrontend {
+        conn: Rc::clone(conn),
+        path: script.path,
+        handles: Vec::new(),
+    };
+    let mut debugger = Debugger::with_frontend(Box::new(frontend)).stop_on_entry(script.stop_on_entry);
+    for line in breakpoints {
+        debugger = debugger.break_at(line);
+    }
+    let result = debugger.run(&script.program, env);
+    redirect_stdout(None);
+
+    let mut c = conn.borrow_mut();
+    if c.closed {
+        return;
+    }
+    if result.is_error() {
+        c.event("output", json!({ "category": "stderr", "output": format!("{}\n", result) }));
+    }
+    c.event("exited", json!({ "exitCode": if result.is_error() { 1 } else { 0 } }));
+    c.event("terminated", json!({}));
+}
+
+/// Something a client can expand in the Variables view.
+enum Handle {
+    /// The local variables of a frame.
+    Scope(usize),
+    /// The elements of an array.
+    Elements(Rc<Vec<Object>>),
+    /// The fields of an object.
+    Fields(Rc<ObjectMap>),
+}
+
+struct DapFrontend {
+    conn: Shared,
+    path: PathBuf,
+    /// Indexed by `variablesReference - 1`; only valid while stopped.
+    handles: Vec<Handle>,
+}
+
+impl DapFrontend {
+    fn handle(&mut self, handle: Handle) -> usize {
+        self.handles.push(handle);
+        self.handles.len()
+    }
+
+    /// A `Variable` for `value`, expandable if it has elements.
+    fn variable(&mut self, name: String, value: Object) -> Value {
+        let reference = match &value {
+            Object::Array(items) if !items.is_empty() => self.handle(Handle::Elements(Rc::clone(items))),
+            Object::Object(fields) if !fields.is_empty() => self.handle(Handle::Fields(Rc::clone(fields))),
+            _ => 0,
+        };
+        json!({
+            "name": name,
+            "value": value.to_string(),
+            "type": value.type_name(),
+            "variablesReference": reference,
+        })
+    }
+
+    fn variables(&mut self, state: &State, reference: usize) -> Option<Vec<Value>> {
+        let vars = match self.handles.get(reference.checked_sub(1)?)? {
+            Handle::Scope(frame) => state.locals(*frame),
+            Handle::Elements(items) => items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| (i.to_string(), item.clone()))
+                .collect(),
+            Handle::Fields(fields) => {
+                let mut fields: Vec<(String, Object)> =
+                    fields.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+                fields.sort_by(|a, b| a.0.cmp(&b.0));
+                fields
+            }
+        };
+        Some(vars.into_iter().map(|(name, value)| self.variable(name, value)).collect())
+    }
+
+    fn stack_trace(&self, state: &State) -> Value {
+        let source = json!({
+            "name": self.path.file_name().map(|n| n.to_string_lossy()),
+            "path": self.path,
+        });
+        let frames: Vec<Value> = state
+            .frames
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(i, frame)| match frame.line {
+                Some(line) if frame.stoppable => json!({
+                    "id": i + 1,
+                    "name": frame.name,
+                    "source": source,
+                    "line": line,
+                    "column": 1,
+                }),
+                _ => json!({
+                    "id": i + 1,
+                    "name": frame.name,
+                    "line": 0,
+                    "column": 0,
+                    "presentationHint": "subtle",
+                }),
+            })
+            .collect();
+        json!({ "stackFrames": frames, "totalFrames": state.frames.len() })
+    }
+
+    /// The frame a request's `frameId` names, defaulting to the innermost.
+    fn frame(state: &State, args: &Value) -> Option<usize> {
+        match args["frameId"].as_u64() {
+            Some(id) => (id as usize).checked_sub(1).filter(|&i| i < state.frames.len()),
+            None => Some(state.frames.len() - 1),
+        }
+    }
+
+    /// Handle one request; `Some` resumes execution.
+    fn request(&mut self, state: &mut State, request: &Value) -> Option<Resume> {
+        let args = &request["arguments"];
+        let command = request["command"].as_str().unwrap_or("");
+        let resume = match command {
+            "continue" => Resume::Continue,
+            "next" => Resume::Next,
+            "stepIn" => Resume::StepIn,
+            "stepOut" => Resume::Out,
+            "disconnect" | "terminate" => {
+                let mut c = self.conn.borrow_mut();
+                c.respond(request, json!({}));
+                c.closed = true;
+                return Some(Resume::Quit);
+            }
+            _ => {
+                self.inspect(state, request, command, args);
+                return None;
+            }
+        };
+        self.conn.borrow_mut().respond(request, json!({ "allThreadsContinued": true }));
+        Some(resume)
+    }
+
+    /// Answer a request that leaves execution stopped.
+    fn inspect(&mut self, state: &mut State, request: &Value, command: &str, args: &Value) {
+        let body = match command {
+            "threads" => threads_body(),
+            "stackTrace" => self.stack_trace(state),
+            "scopes" => match Self::frame(state, args) {
+                Some(frame) => {
+                    let reference = self.handle(Handle::Scope(frame));
+                    json!({ "scopes": [{
+                        "name": "Locals",
+                        "variablesReference": reference,
+                        "expensive": false,
+                    }] })
+                }
+                None => return self.conn.borrow_mut().fail(request, "no such frame"),
+            },
+            "variables" => {
+                let reference = args["variablesReference"].as_u64().unwrap_or(0) as usize;
+                match self.variables(state, reference) {
+                    Some(variables) => json!({ "variables": variables }),
+                    None => return self.conn.borrow_mut().fail(request, "no such variable"),
+                }
+            }
+            "evaluate" => {
+                let Some(frame) = Self::frame(state, args) else {
+                    return self.conn.borrow_mut().fail(request, "no such frame");
+                };
+                let expression = args["expression"].as_str().unwrap_or("");
+                match state.evaluate(frame, expression) {
+                    Ok(value) if value.is_error() => {
+                        return self.conn.borrow_mut().fail(request, &value.to_string());
+                    }
+                    Ok(value) => {
+                        let variable = self.variable(String::new(), value);
+                        json!({
+                            "result": variable["value"],
+                            "type": variable["type"],
+                            "variablesReference": variable["variablesReference"],
+                        })
+                    }
+                    Err(message) => return self.conn.borrow_mut().fail(request, &message),
+                }
+            }
+            "setBreakpoints" => {
+                let path = Path::new(args["source"]["path"].as_str().unwrap_or(""));
+                let lines = requested_lines(args);
+                let ours = same_file(path, &self.path);
+                if ours {
+                    state.breakpoints = lines.clone();
+                }
+                breakpoints_body(&lines, ours)
+            }
+            "pause" => json!({}),
+            _ => {
+                let message = format!("'{}' is not supported", command);
+                return self.conn.borrow_mut().fail(request, &message);
+            }
+        };
+        self.conn.borrow_mut().respond(request, body);
+    }
+}
+
+impl Frontend for DapFrontend {
+    fn stopped(&mut self, state: &mut State, reason: StopReason) -> Resume {
+        self.handles.clear();
+        let reason = match reason {
+            StopReason::Entry => "entry",
+            StopReason::Breakpoint => "breakpoint",
+            StopReason::Step => "step",
+        };
+        self.conn.borrow_mut().event("stopped", json!({
+            "reason": reason,
+            "threadId": THREAD_ID,
+            "allThreadsStopped": true,
+        }));
+        loop {
+            // Don't hold the connection while handling the request: evaluating
+            // an expression can print, which sends an output event.
+            let request = self.conn.borrow_mut().read();
+            match request {
+                Ok(Some(request)) => {
+                    if let Some(resume) = self.request(state, &request) {
+                        return resume;
+                    }
+                }
+                // The client has gone; finish the script without it.
+                Ok(None) | Err(_) => {
+                    self.conn.borrow_mut().closed = true;
+                    return Resume::Detach;
+                }
+            }
+        }
+    }
+}
+
+fn threads_body() -> Value {
+    json!({ "threads": [{ "id": THREAD_ID, "name": "main" }] })
+}
+
+/// The lines of a `setBreakpoints` request.
+fn requested_lines(args: &Value) -> BTreeSet<usize> {
+    args["breakpoints"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|b| b["line"].as_u64())
+        .map(|line| line as usize)
+        .filter(|&line| line > 0)
+        .collect()
+}
+
+fn breakpoints_body(lines: &BTreeSet<usize>, verified: bool) -> Value {
+    let breakpoints: Vec<Value> = lines
+        .iter()
+        .map(|line| {
+            if verified {
+                json!({ "verified": true, "line": line })
+            } else {
+                json!({
+                    "verified": false,
+                    "line": line,
+                    "message": "only breakpoints in the launched script are supported",
+                })
+            }
+        })
+        .collect();
+    json!({ "breakpoints": breakpoints })
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::io::{Cursor, Write};
+    use std::path::PathBuf;
+    use std::rc::Rc;
+
+    use serde_json::{Value, json};
+
+    #[derive(Clone, Default)]
+    struct Output(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Output {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    const SCRIPT: &str = "function add(a, b) {\n    let sum = a + b;\n    return sum;\n}\nlet xs = [1, 2];\nprint(add(xs[0], 2));\n";
+
+    fn script_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "{}_{}.sl",
+            name,
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::write(&path, SCRIPT).expect("failed to write script");
+        path
+    }
+
+    /// Serve `requests` in order and return every message sent back.
+    fn session(requests: Vec<Value>) -> Vec<Value> {
+        let mut input = String::new();
+        for (seq, mut request) in requests.into_iter().enumerate() {
+            request["seq"] = json!(seq + 1);
+            request["type"] = json!("request");
+            let body = request.to_string();
+            input.push_str(&format!("Content-Length: {}\r\n\r\n{}", body.len(), body));
+        }
+        let output = Output::default();
+        super::serve(Cursor::new(input), output.clone()).unwrap();
+        let text = String::from_utf8(output.0.borrow().clone()).unwrap();
+        text.split("Content-Length: ")
+            .skip(1)
+            .map(|m| serde_json::from_str(m.split_once("\r\n\r\n").unwrap().1).unwrap())
+            .collect()
+    }
+
+    fn response<'a>(messages: &'a [Value], command: &str) -> &'a Value {
+        messages
+            .iter()
+            .find(|m| m["type"] == "response" && m["command"] == command)
+            .unwrap_or_else(|| panic!("no {} response in {:#?}", command, messages))
+    }
+
+    fn events<'a>(messages: &'a [Value], event: &str) -> Vec<&'a Value> {
+        messages.iter().filter(|m| m["event"] == event).collect()
+    }
+
+    #[test]
+    fn stops_at_breakpoints_and_inspects_the_stack() {
+        let path = script_path("slang_dap_breakpoints");
+        let messages = session(vec![
+            json!({ "command": "initialize", "arguments": { "adapterID": "slang" } }),
+            json!({ "command": "launch", "arguments": { "program": path } }),
+            json!({ "command": "setBreakpoints", "arguments": {
+                "source": { "path": path }, "breakpoints": [{ "line": 3 }] } }),
+            json!({ "command": "configurationDone" }),
+            json!({ "command": "stackTrace", "arguments": { "threadId": 1 } }),
+            json!({ "command": "scopes", "arguments": { "frameId": 2 } }),
+            json!({ "command": "variables", "arguments": { "variablesReference": 1 } }),
+            json!({ "command": "evaluate", "arguments": { "expression": "sum * 10", "frameId": 2 } }),
+            json!({ "command": "continue", "arguments": { "threadId": 1 } }),
+            json!({ "command": "disconnect" }),
+        ]);
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(response(&messages, "initialize")["body"]["supportsConfigurationDoneRequest"], true);
+        assert_eq!(events(&messages, "initialized").len(), 1);
+        assert_eq!(response(&messages, "setBreakpoints")["body"]["breakpoints"][0]["verified"], true);
+        let stopped = events(&messages, "stopped");
+        assert_eq!(stopped.len(), 1);
+        assert_eq!(stopped[0]["body"]["reason"], "breakpoint");
+
+        let frames = &response(&messages, "stackTrace")["body"]["stackFrames"];
+        assert_eq!(frames[0]["name"], "add");
+        assert_eq!(frames[0]["line"], 3);
+        assert_eq!(frames[0]["id"], 2);
+        assert_eq!(frames[1]["name"], "<script>");
+        assert_eq!(frames[1]["line"], 6);
+
+        let vars = &response(&messages, "variables")["body"]["variables"];
+        let shown: Vec<String> = vars
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| format!("{}={}", v["name"].as_str().unwrap(), v["value"].as_str().unwrap()))
+            .collect();
+        assert_eq!(shown, vec!["a=1", "b=2", "sum=3"]);
+        assert_eq!(response(&messages, "evaluate")["body"]["result"], "30");
+
+        let output: Vec<&Value> = events(&messages, "output");
+        assert_eq!(output[0]["body"]["output"], "3\n");
+        assert_eq!(events(&messages, "exited")[0]["body"]["exitCode"], 0);
+        assert_eq!(events(&messages, "terminated").len(), 1);
+        assert_eq!(response(&messages, "disconnect")["success"], true);
+    }
+
+    #[test]
+    fn steps_from_entry_and_expands_arrays() {
+        let path = script_path("slang_dap_entry");
+        let messages = session(vec![
+            json!({ "command": "initialize" }),
+            json!({ "command": "launch", "arguments": { "program": path, "stopOnEntry": true } }),
+            json!({ "command": "configurationDone" }),
+            json!({ "command": "next", "arguments": { "threadId": 1 } }),
+            json!({ "command": "next", "arguments": { "threadId": 1 } }),
+            json!({ "command": "scopes", "arguments": { "frameId": 1 } }),
+            json!({ "command": "variables", "arguments": { "variablesReference": 1 } }),
+            json!({ "command": "variables", "arguments": { "variablesReference": 2 } }),
+            json!({ "command": "disconnect" }),
+        ]);
+        let _ = std::fs::remove_file(&path);
+
+        let reasons: Vec<&Value> = events(&messages, "stopped").iter().map(|e| &e["body"]["reason"]).collect();
+        assert_eq!(reasons, vec!["entry", "step", "step"]);
+        let responses: Vec<&Value> = messages
+            .iter()
+            .filter(|m| m["command"] == "variables")
+            .collect();
+        let locals = &responses[0]["body"]["variables"];
+        assert_eq!(locals[1]["name"], "xs");
+        assert_eq!(locals[1]["value"], "[1, 2]");
+        assert_eq!(locals[1]["variablesReference"], 2);
+        let items = &responses[1]["body"]["variables"];
+        assert_eq!(items[0]["name"], "0");
+        assert_eq!(items[1]["value"], "2");
+        // Disconnecting while stopped ends the script without running the rest.
+        assert!(events(&messages, "output").is_empty());
+        assert!(events(&messages, "exited").is_empty());
+    }
+
+    #[test]
+    fn reports_launch_failures() {
+        let messages = session(vec![
+            json!({ "command": "initialize" }),
+            json!({ "command": "launch", "arguments": { "program": "/no/such/script.sl" } }),
+            json!({ "command": "disconnect" }),
+        ]);
+        let launch = response(&messages, "launch");
+        assert_eq!(launch["success"], false);
+        assert!(launch["message"].as_str().unwrap().contains("failed to read"), "{}", launch);
+    }
+}
@@ -0,0 +1,324 @@
+//! The step debugger core shared by `slang debug` and `slang dap`.
+//!
+//! A [`Debugger`] evaluates a program with hooks switched on: the evaluator
+//! calls [`before_statement`] ahead of every statement it runs from a block
+//! and [`enter_function`] for every call to a user function. When one of
+//! those lands on a breakpoint, or a step command is due to stop, the
+//! debugger hands control to its [`Frontend`] – the terminal prompt in
+//! [`super::console`] or the DAP server in [`super::dap`] – until told to
+//! carry on.
+//!
+//! Only code from the script being debugged stops. Imported modules, and
+//! functions defined in them, run without pausing since their line numbers
+//! belong to other files.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{BufRead, Write};
+use std::rc::Rc;
+
+use crate::ast::{Program, Statement};
+use crate::env::EnvRef;
+use crate::lexer::Lexer;
+use crate::object::Object;
+use crate::parser::Parser;
+
+use super::console::Console;
+
+thread_local! {
+    static ACTIVE: Cell<bool> = const { Cell::new(false) };
+    static SESSION: RefCell<Option<Session>> = const { RefCell::new(None) };
+}
+
+/// When to stop next, apart from breakpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// Only at breakpoints.
+    Run,
+    /// At the next line, in any frame.
+    Step,
+    /// At the next line in a frame at most this deep.
+    Next(usize),
+    /// At the next line in a frame shallower than this.
+    Out(usize),
+    /// Never again; the front end has gone away.
+    Detached,
+}
+
+/// Why execution stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StopReason {
+    /// Before the first statement.
+    Entry,
+    /// At a `breakpoint;` statement or a line breakpoint.
+    Breakpoint,
+    /// A step command finished.
+    Step,
+}
+
+/// How a front end asks stopped execution to carry on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Resume {
+    /// Run to the next breakpoint.
+    Continue,
+    /// Stop at the next line, stepping into calls.
+    StepIn,
+    /// Stop at the next line in this function or its callers.
+    Next,
+    /// Stop once this function returns.
+    Out,
+    /// Run to the end without stopping again.
+    Detach,
+    /// End the script now.
+    Quit,
+}
+
+/// What the user sees and drives while execution is stopped.
+pub(crate) trait Frontend {
+    /// Execution stopped for `reason`; handle requests until one resumes it.
+    fn stopped(&mut self, state: &mut State, reason: StopReason) -> Resume;
+}
+
+/// A call on the debugger's stack.
+pub(crate) struct Frame {
+    pub(crate) name: String,
+    pub(crate) line: Option<usize>,
+    /// Whether the code belongs to the script being debugged.
+    pub(crate) stoppable: bool,
+    /// The scope the call started in; `locals` stops here.
+    base: EnvRef,
+    /// The scope of the statement running in this frame.
+    env: EnvRef,
+}
+
+/// What a front end can inspect and change while execution is stopped.
+pub(crate) struct State {
+    pub(crate) breakpoints: BTreeSet<usize>,
+    /// The call stack, outermost (the script) first.
+    pub(crate) frames: Vec<Frame>,
+    mode: Mode,
+    /// The global scope of the script, to tell its functions from imported ones.
+    global: EnvRef,
+    /// Set once execution has stopped for the first time.
+    started: bool,
+}
+
+impl State {
+    /// Variables visible in `frames[frame]`, from its innermost scope out to
+    /// the scope the call started in, sorted by name.
+    pub(crate) fn locals(&self, frame: usize) -> Vec<(String, Object)> {
+        let frame = &self.frames[frame];
+        let mut vars: BTreeMap<String, Object> = BTreeMap::new();
+        let mut env = Rc::clone(&frame.env);
+        loop {
+            for (name, value) in env.borrow().snapshot() {
+                vars.entry(name).or_insert(value);
+            }
+            if Rc::ptr_eq(&env, &frame.base) {
+                break;
+            }
+            let outer = env.borrow().outer();
+            match outer {
+                Some(outer) => env = outer,
+                None => break,
+            }
+        }
+        vars.into_iter().collect()
+    }
+
+    /// Evaluate `source` in the scope of `frames[frame]`.
+    pub(crate) fn evaluate(&self, frame: usize, source: &str) -> Result<Object, String> {
+        let mut parser = Parser::new(Lexer::new(source));
+        let program = parser.parse_program();
+        if let Some(err) = parser.errors.first() {
+            return Err(format!("Parse error: {}", err));
+        }
+        let env = Rc::clone(&self.frames[frame].env);
+        Ok(crate::evaluator::eval(&program, env))
+    }
+}
+
+struct Session {
+    state: State,
+    frontend: Box<dyn Frontend>,
+}
+
+/// Runs a program under the debugger.
+pub struct Debugger {
+    frontend: Box<dyn Frontend>,
+    breakpoints: BTreeSet<usize>,
+    stop_on_entry: bool,
+}
+
+impl Debugger {
+    /// A terminal debugger for the script `source`, reading commands from
+    /// `input` and writing to `output`. It stops before the first statement
+    /// unless told otherwise with [`Debugger::stop_on_entry`].
+    pub fn new(source: &str, input: impl BufRead + 'static, output: impl Write + 'static) -> Self {
+        Self::with_frontend(Box::new(Console::new(source, input, output)))
+    }
+
+    pub(crate) fn with_frontend(frontend: Box<dyn Frontend>) -> Self {
+        Debugger {
+            frontend,
+            breakpoints: BTreeSet::new(),
+            stop_on_entry: true,
+        }
+    }
+
+    /// Stop whenever execution reaches `line` (counting from 1).
+    pub fn break_at(mut self, line: usize) -> Self {
+        self.breakpoints.insert(line);
+        self
+    }
+
+    /// Whether to stop before the first statement.
+    pub fn stop_on_entry(mut self, stop: bool) -> Self {
+        self.stop_on_entry = stop;
+        self
+    }
+
+    /// Evaluate `program` in `env`, pausing as breakpoints and step commands
+    /// require, and return its result.
+    pub fn run(self, program: &Program, env: EnvRef) -> Object {
+        let state = State {
+            breakpoints: self.breakpoints,
+            frames: vec![Frame {
+                name: "<script>".to_string(),
+                line: None,
+                stoppable: true,
+                base: Rc::clone(&env),
+                env: Rc::clone(&env),
+            }],
+            mode: if self.stop_on_entry { Mode::Step } else { Mode::Run },
+            global: Rc::clone(&env),
+            started: false,
+        };
+        let session = Session { state, frontend: self.frontend };
+        SESSION.with(|s| *s.borrow_mut() = Some(session));
+        ACTIVE.with(|a| a.set(true));
+        let result = crate::evaluator::eval(program, env);
+        ACTIVE.with(|a| a.set(false));
+        SESSION.with(|s| s.borrow_mut().take());
+        result
+    }
+}
+
+/// Whether a debugger is running; the evaluator's hooks do nothing otherwise.
+#[inline]
+pub(crate) fn is_active() -> bool {
+    ACTIVE.with(|a| a.get())
+}
+
+/// Run `f` with the debugger's hooks switched off, e.g. while evaluating an
+/// imported module.
+pub(crate) fn suspended<T>(f: impl FnOnce() -> T) -> T {
+    let was_active = ACTIVE.with(|a| a.replace(false));
+    let result = f();
+    ACTIVE.with(|a| a.set(was_active));
+    result
+}
+
+/// Pops the frame pushed by [`enter_function`] when the call returns.
+pub(crate) struct CallGuard(());
+
+impl Drop for CallGuard {
+    fn drop(&mut self) {
+        SESSION.with(|s| {
+            if let Some(session) = s.borrow_mut().as_mut() {
+                session.state.frames.pop();
+            }
+        });
+    }
+}
+
+/// Record a call to the user function `name`, whose body runs in `env`.
+pub(crate) fn enter_function(name: &str, env: &EnvRef) -> Option<CallGuard> {
+    if !is_active() {
+        return None;
+    }
+    SESSION.with(|s| {
+        let mut guard = s.borrow_mut();
+        let state = &mut guard.as_mut()?.state;
+        let stoppable = Rc::ptr_eq(&root_of(env), &root_of(&state.global));
+        state.frames.push(Frame {
+            name: name.to_string(),
+            line: None,
+            stoppable,
+            base: Rc::clone(env),
+            env: Rc::clone(env),
+        });
+        Some(CallGuard(()))
+    })
+}
+
+/// Called before `stmt`, written on `line`, runs in `env`. Hands over to the
+/// front end if execution should stop; returns an error to end the script
+/// when asked to quit.
+pub(crate) fn before_statement(stmt: &Statement, line: Option<usize>, env: &EnvRef) -> Option<Object> {
+    SESSION.with(|s| {
+        let mut guard = s.borrow_mut();
+        let session = guard.as_mut()?;
+        let reason = session.state.should_stop(stmt, line, env)?;
+        // Front ends run code (e.g. to evaluate an expression), which
+        // mustn't re-enter the hooks.
+        let resume = suspended(|| session.frontend.stopped(&mut session.state, reason));
+        let depth = session.state.frames.len();
+        session.state.mode = match resume {
+            Resume::Continue => Mode::Run,
+            Resume::StepIn => Mode::Step,
+            Resume::Next => Mode::Next(depth),
+            Resume::Out => Mode::Out(depth),
+            Resume::Detach => Mode::Detached,
+            Resume::Quit => return Some(Object::error("debugger: quit")),
+        };
+        None
+    })
+}
+
+fn root_of(env: &EnvRef) -> EnvRef {
+    let mut env = Rc::clone(env);
+    loop {
+        let outer = env.borrow().outer();
+        match outer {
+            Some(outer) => env = outer,
+            None => return env,
+        }
+    }
+}
+
+impl State {
+    fn should_stop(&mut self, stmt: &Statement, line: Option<usize>, env: &EnvRef) -> Option<StopReason> {
+        let depth = self.frames.len();
+        let frame = self.frames.last_mut()?;
+        frame.env = Rc::clone(env);
+        if !frame.stoppable || self.mode == Mode::Detached {
+            return None;
+        }
+        // Several statements on one line, or a one-line loop body, count as
+        // a single stop.
+        let arrived = line.is_some() && line != frame.line;
+        frame.line = line;
+
+        let at_breakpoint = matches!(stmt, Statement::Breakpoint)
+            || (arrived && line.is_some_and(|l| self.breakpoints.contains(&l)));
+        let stepped = arrived
+            && match self.mode {
+                Mode::Step => true,
+                Mode::Next(d) => depth <= d,
+                Mode::Out(d) => depth < d,
+                Mode::Run | Mode::Detached => false,
+            };
+        let reason = if !self.started && stepped {
+            StopReason::Entry
+        } else if at_breakpoint {
+            StopReason::Breakpoint
+        } else if stepped {
+            StopReason::Step
+        } else {
+            return None;
+        };
+        self.started = true;
+        Some(reason)
+    }
+}
@@ -0,0 +1,2 @@
+pub mod core;
+pub use core::{Change, diff_values, load_config};
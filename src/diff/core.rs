@@ -0,0 +1,255 @@
+//! Structural diffs of config files for `slang diff-config`.
+//!
+//! JSON, YAML and TOML files are all loaded into a `serde_json::Value`, so the
+//! comparison doesn't care which format either side was written in. Objects
+//! are compared key by key (in sorted order) and arrays index by index; each
+//! difference is reported against the path of the value that changed.
+//! YAML and TOML need the `formats` cargo feature.
+
+use std::fmt;
+use std::path::Path;
+
+#[cfg(feature = "formats")]
+use serde_json::{Map, Number};
+use serde_json::Value;
+#[cfg(feature = "formats")]
+use yaml_rust2::{Yaml, YamlLoader};
+
+/// A single difference between two documents.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    Added { path: String, value: Value },
+    Removed { path: String, value: Value },
+    Changed { path: String, old: Value, new: Value },
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::Added { path, value } => write!(f, "+ {}: {}", display_path(path), value),
+            Change::Removed { path, value } => write!(f, "- {}: {}", display_path(path), value),
+            Change::Changed { path, old, new } => {
+                write!(f, "~ {}: {} -> {}", display_path(path), old, new)
+            }
+        }
+    }
+}
+
+fn display_path(path: &str) -> &str {
+    if path.is_empty() { "(root)" } else { path }
+}
+
+/// Load a JSON, YAML or TOML file, picking the format from its extension.
+pub fn load_config(path: &Path) -> Result<Value, String> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+    let source = std::fs::read_to_string(path)
+        .map_err(|e| format!("{}: failed to read: {}", path.display(), e))?;
+
+    let parsed = match ext.as_str() {
+        "json" => serde_json::from_str(&source).map_err(|e| e.to_string()),
+        #[cfg(feature = "formats")]
+        "yaml" | "yml" => parse_yaml(&source),
+        #[cfg(feature = "formats")]
+        "toml" => parse_toml(&source),
+        #[cfg(not(feature = "formats"))]
+        "yaml" | "yml" | "toml" => {
+            return Err(format!(
+                "{}: YAML and TOML need slang built with the `formats` feature",
+                path.display()
+            ))
+        }
+        _ => {
+            return Err(format!(
+                "{}: unsupported config format (expected .json, .yaml, .yml or .toml)",
+                path.display()
+            ))
+        }
+    };
+    parsed.map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// The first document in a YAML string, as JSON.
+#[cfg(feature = "formats")]
+pub(crate) fn parse_yaml(source: &str) -> Result<Value, String> {
+    let docs = YamlLoader::load_from_str(source).map_err(|e| e.to_string())?;
+    match docs.into_iter().next() {
+        Some(doc) => yaml_to_json(doc),
+        None => Ok(Value::Null),
+    }
+}
+
+#[cfg(feature = "formats")]
+fn yaml_to_json(yaml: Yaml) -> Result<Value, String> {
+    Ok(match yaml {
+        Yaml::Null => Value::Null,
+        Yaml::Boolean(b) => Value::Bool(b),
+        Yaml::Integer(i) => Value::from(i),
+        Yaml::Real(s) => {
+            let f: f64 = s
+                .parse()
+                .map_err(|_| format!("invalid YAML number {}", s))?;
+            Number::from_f64(f)
+                .map(Value::Number)
+                .ok_or_else(|| format!("YAML number {} can't be represented", s))?
+        }
+        Yaml::String(s) => Value::String(s),
+        Yaml::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(yaml_to_json)
+                .collect::<Result<_, _>>()?,
+        ),
+        Yaml::Hash(hash) => {
+            let mut map = Map::new();
+            for (key, value) in hash {
+                let key = match key {
+                    Yaml::String(s) | Yaml::Real(s) => s,
+                    Yaml::Integer(i) => i.to_string(),
+                    Yaml::Boolean(b) => b.to_string(),
+                    other => return Err(format!("unsupported YAML key {:?}", other)),
+                };
+                map.insert(key, yaml_to_json(value)?);
+            }
+            Value::Object(map)
+        }
+        Yaml::Alias(_) => return Err("YAML aliases are not supported".to_string()),
+        Yaml::BadValue => return Err("invalid YAML value".to_string()),
+    })
+}
+
+/// A TOML document as JSON; dates and times become strings.
+#[cfg(feature = "formats")]
+pub(crate) fn parse_toml(source: &str) -> Result<Value, String> {
+    toml::from_str::<toml::Value>(source)
+        .map(toml_to_json)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(feature = "formats")]
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::from(i),
+        toml::Value::Float(f) => Number::from_f64(f).map(Value::Number).unwrap_or(Value::Null),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(dt) => Value::String(dt.to_string()),
+        toml::Value::Array(items) => Value::Array(items.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(k, v)| (k, toml_to_json(v)))
+                .collect(),
+        ),
+    }
+}
+
+/// Every difference between `old` and `new`, in path order.
+pub fn diff_values(old: &Value, new: &Value) -> Vec<Change> {
+    let mut changes = Vec::new();
+    diff_at(String::new(), old, new, &mut changes);
+    changes
+}
+
+fn diff_at(path: String, old: &Value, new: &Value, changes: &mut Vec<Change>) {
+    match (old, new) {
+        (Value::Object(a), Value::Object(b)) => {
+            let mut keys: Vec<&String> = a.keys().chain(b.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let child = key_path(&path, key);
+                match (a.get(key), b.get(key)) {
+                    (Some(x), Some(y)) => diff_at(child, x, y, changes),
+                    (Some(x), None) => changes.push(Change::Removed { path: child, value: x.clone() }),
+                    (None, Some(y)) => changes.push(Change::Added { path: child, value: y.clone() }),
+                    (None, None) => unreachable!(),
+                }
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for i in 0..a.len().max(b.len()) {
+                let child = format!("{}[{}]", path, i);
+                match (a.get(i), b.get(i)) {
+                    (Some(x), Some(y)) => diff_at(child, x, y, changes),
+                    (Some(x), None) => changes.push(Change::Removed { path: child, value: x.clone() }),
+                    (None, Some(y)) => changes.push(Change::Added { path: child, value: y.clone() }),
+                    (None, None) => unreachable!(),
+                }
+            }
+        }
+        _ if old == new => {}
+        _ => changes.push(Change::Changed {
+            path,
+            old: old.clone(),
+            new: new.clone(),
+        }),
+    }
+}
+
+/// `parent.key`, or `parent["key"]` when `key` isn't a plain identifier.
+fn key_path(parent: &str, key: &str) -> String {
+    let plain = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !plain {
+        format!("{}[{}]", parent, Value::String(key.to_string()))
+    } else if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_values, Change};
+    use serde_json::json;
+
+    #[test]
+    fn reports_added_removed_and_changed_paths() {
+        let old = json!({ "name": "app", "db": { "port": 5432, "hosts": ["a", "b"] }, "debug": true });
+        let new = json!({ "name": "app", "db": { "port": 6432, "hosts": ["a"] }, "log level": "info" });
+
+        let lines: Vec<String> = diff_values(&old, &new).iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "- db.hosts[1]: \"b\"",
+                "~ db.port: 5432 -> 6432",
+                "- debug: true",
+                "+ [\"log level\"]: \"info\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn identical_documents_have_no_changes() {
+        let doc = json!({ "a": [1, { "b": null }] });
+        assert!(diff_values(&doc, &doc.clone()).is_empty());
+        assert_eq!(
+            diff_values(&json!(1), &json!("1")),
+            vec![Change::Changed { path: String::new(), old: json!(1), new: json!("1") }]
+        );
+    }
+
+    #[cfg(feature = "formats")]
+    #[test]
+    fn yaml_and_toml_load_to_the_same_shape_as_json() {
+        use super::{parse_yaml, toml_to_json};
+
+        let expected = json!({ "server": { "port": 8080, "tags": ["web", "api"], "ratio": 0.5 } });
+
+        let yaml = parse_yaml("server:\n  port: 8080\n  tags: [web, api]\n  ratio: 0.5\n").unwrap();
+        assert_eq!(yaml, expected);
+
+        let toml: toml::Value =
+            toml::from_str("[server]\nport = 8080\ntags = [\"web\", \"api\"]\nratio = 0.5\n").unwrap();
+        assert_eq!(toml_to_json(toml), expected);
+    }
+}
@@ -1,8 +1,13 @@
 pub mod core;
 
 pub use core::{
+    EnvBuilder,
+    NamespaceBuilder,
     EnvRef,
     Environment,
+    ScopeLayout,
+    ModuleCache,
+    is_builtin_namespace,
     new_env,
     new_enclosed_env,
     register_subscription,
@@ -1,9 +1,15 @@
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::rc::Rc;
+use std::sync::{Arc, LazyLock};
 
-use crate::object::Object;
+use crate::ast::nodes::Binding;
+use crate::capability::Capabilities;
+use crate::object::types::BuiltinFunction;
+use crate::object::{Object, ObjectMap};
+use crate::profile;
+use crate::stdlib;
 use crate::builtins::native::monad_builtins::{
     option_some,
     option_none,
@@ -14,6 +20,13 @@ use crate::builtins::native::monad_builtins::{
     option_and_then,
     option_bind,
     option_fmap,
+    option_unwrap,
+    option_expect,
+    option_ok_or,
+    option_filter,
+    option_zip,
+    option_flatten,
+    option_to_array,
     result_ok,
     result_err,
     result_is_ok,
@@ -23,12 +36,21 @@ use crate::builtins::native::monad_builtins::{
     result_and_then,
     result_bind,
     result_fmap,
+    result_unwrap,
+    result_expect,
+    result_to_option,
+    result_map_err,
 };
+#[cfg(feature = "regex")]
 use crate::builtins::native::regex_builtins::{
     builtin_regex_is_match,
     builtin_regex_find,
     builtin_regex_replace,
     builtin_regex_match,
+    builtin_regex_find_all,
+    builtin_regex_match_all,
+    builtin_regex_captures,
+    builtin_regex_split,
 };
 use crate::builtins::native::file_builtins::{
     file_open_result,
@@ -36,15 +58,40 @@ use crate::builtins::native::file_builtins::{
     file_write_result,
     file_seek_result,
     file_close_result,
+    file_read_line,
+    file_read_lines,
+    file_read_bytes,
+    file_exists,
+    file_size,
+    file_read_to_string,
+    file_write_string,
+    file_append,
+    file_delete,
+    file_copy,
 };
 use crate::builtins::native::test_builtins::{
     test_assert,
     test_assert_eq,
     test_assert_not_eq,
+    test_assert_true,
+    test_assert_false,
+    test_assert_approx_eq,
+    test_assert_contains,
+    test_assert_throws,
+    test_assert_some,
+    test_assert_none,
+    test_assert_ok,
+    test_assert_err,
+    test_assert_snapshot,
 };
+#[cfg(feature = "regex")]
+use crate::builtins::native::test_builtins::test_assert_match;
+use crate::builtins::native::{builtin_first, builtin_last, builtin_push, builtin_rest};
 use crate::builtins::native::array_builtins::{
     array_map,
     array_filter,
+    array_par_map,
+    array_par_filter,
     array_reduce,
     array_find,
     array_some,
@@ -59,23 +106,41 @@ use crate::builtins::native::array_builtins::{
     array_slice,
     array_take,
     array_drop,
+    array_take_while,
+    array_drop_while,
     array_range,
     array_unique,
     array_flatten,
     array_zip,
     array_unzip,
     array_group_by,
+    array_count_by,
+    array_frequencies,
     array_partition,
     array_fill,
     array_is_empty,
     array_for_each,
     array_len,
+    array_binary_search,
+    array_binary_search_by,
+    array_insert_sorted,
+    array_min_by,
+    array_max_by,
+    array_sum_by,
+    array_push_mut,
+    array_product,
+    array_combinations,
+    array_permutations,
 };
 use crate::builtins::native::math_builtins::{
     math_abs,
     math_floor,
     math_ceil,
     math_round,
+    math_trunc,
+    math_round_to,
+    math_floor_div,
+    math_divmod,
     math_min,
     math_max,
     math_pow,
@@ -94,13 +159,11 @@ use crate::builtins::native::math_builtins::{
     math_sinh,
     math_cosh,
     math_tanh,
-    math_pi,
-    math_e,
-    math_tau,
     math_sign,
     math_clamp,
     math_random,
     math_random_int,
+    math_random_hex,
 };
 use crate::builtins::native::string_builtins::{
     string_trim,
@@ -127,6 +190,19 @@ use crate::builtins::native::string_builtins::{
     string_char_codes,
     string_is_empty,
     string_len,
+    string_words,
+    string_lines,
+    string_natural_compare,
+    string_levenshtein,
+    string_to_camel_case,
+    string_to_snake_case,
+    string_to_kebab_case,
+    string_to_title_case,
+    string_capitalize,
+    string_truncate,
+    string_wrap,
+    string_indent,
+    string_format,
 };
 use crate::builtins::native::json_builtins::{
     json_parse,
@@ -137,6 +213,8 @@ use crate::builtins::native::type_builtins::{
     type_float,
     type_str,
     type_bool,
+    type_to_int,
+    type_to_float,
     type_of,
     type_is_int,
     type_is_float,
@@ -159,10 +237,20 @@ use crate::builtins::native::object_builtins::{
     object_has,
     object_get,
     object_set,
+    object_set_mut,
     object_delete,
     object_merge,
     object_is_empty,
     object_len,
+    object_clone,
+    object_freeze,
+    object_is_frozen,
+    object_map,
+    object_map_values,
+    object_filter,
+    object_pick,
+    object_omit,
+    object_merge_deep,
 };
 use crate::builtins::native::time_builtins::{
     time_now,
@@ -177,6 +265,11 @@ use crate::builtins::native::time_builtins::{
     time_day_of_week,
     time_format,
     time_to_object,
+    time_from_components,
+    time_add,
+    time_diff,
+    time_parse,
+    time_to_zone,
 };
 use crate::builtins::native::system_builtins::{
     sys_env,
@@ -186,9 +279,23 @@ use crate::builtins::native::system_builtins::{
     sys_cwd,
     sys_set_cwd,
     sys_exec,
-    sys_platform,
     sys_arch,
+    sys_dry_run,
+    sys_version,
+    sys_has_namespace,
+    sys_features,
 };
+use crate::builtins::native::process_builtins::{
+    process_spawn,
+    process_run,
+    process_read_stdout,
+    process_read_stderr,
+    process_write_stdin,
+    process_close_stdin,
+    process_wait,
+    process_kill,
+};
+#[cfg(feature = "http")]
 use crate::builtins::native::http_builtins::{
     http_get,
     http_post,
@@ -197,6 +304,8 @@ use crate::builtins::native::http_builtins::{
     http_patch,
     http_head,
 };
+#[cfg(feature = "http")]
+use crate::builtins::native::http_server_builtins::http_server_listen;
 use crate::builtins::native::fn_builtins::{
     fn_identity,
     fn_constant,
@@ -209,42 +318,284 @@ use crate::builtins::native::fn_builtins::{
     fn_partial,
     fn_is_callable,
 };
+use crate::builtins::native::number_builtins::number_parse;
+use crate::builtins::native::runtime_builtins::{
+    timer_after,
+    timer_every,
+    timer_cancel,
+    runtime_run,
+    runtime_on_shutdown,
+    events_subscribe,
+    events_publish,
+    events_run,
+    events_pending,
+};
+use crate::builtins::native::task_builtins::{
+    task_spawn,
+    task_join,
+    task_is_done,
+    channel_new,
+    channel_send,
+    channel_recv,
+    channel_try_recv,
+    channel_close,
+};
+use crate::builtins::native::schema_builtins::{
+    schema_string,
+    schema_int,
+    schema_float,
+    schema_bool,
+    schema_any,
+    schema_array,
+    schema_object,
+    schema_optional,
+    schema_validate,
+    schema_coerce,
+};
+use crate::builtins::native::bloom_builtins::{
+    bloom_new,
+    bloom_add,
+    bloom_might_contain,
+};
+use crate::builtins::native::ref_builtins::{
+    ref_new,
+    ref_get,
+    ref_set,
+};
+use crate::builtins::native::dir_builtins::{
+    dir_list,
+    dir_create,
+    dir_remove,
+    dir_exists,
+    dir_walk,
+};
+use crate::builtins::native::path_builtins::{
+    path_join,
+    path_basename,
+    path_dirname,
+    path_extension,
+    path_absolute,
+};
+use crate::builtins::native::bytes_builtins::{
+    bytes_from_string,
+    bytes_to_string,
+    bytes_len,
+    bytes_slice,
+    bytes_concat,
+    bytes_from_array,
+    bytes_to_array,
+};
+use crate::builtins::native::debug_builtins::{
+    debug_inspect,
+    debug_pretty,
+};
+use crate::builtins::native::log_builtins::{
+    log_debug,
+    log_info,
+    log_warn,
+    log_error,
+    log_set_level,
+    log_level,
+    log_enabled,
+    log_set_format,
+};
+use crate::builtins::native::uuid_builtins::{
+    uuid_v4,
+    uuid_v7,
+};
+#[cfg(feature = "formats")]
+use crate::builtins::native::format_builtins::{
+    yaml_parse,
+    yaml_stringify,
+    toml_parse,
+    toml_stringify,
+};
+#[cfg(feature = "encoding")]
+use crate::builtins::native::encoding_builtins::{
+    encoding_base64_encode,
+    encoding_base64_decode,
+    encoding_hex_encode,
+    encoding_hex_decode,
+    encoding_url_encode,
+    encoding_url_decode,
+};
+#[cfg(feature = "crypto")]
+use crate::builtins::native::crypto_builtins::{
+    crypto_md5,
+    crypto_sha1,
+    crypto_sha256,
+    crypto_hmac_sha256,
+    crypto_random_bytes,
+};
+use crate::builtins::native::seq_builtins::{
+    seq_from,
+    seq_range,
+    seq_map,
+    seq_filter,
+    seq_take,
+    seq_drop,
+    seq_take_while,
+    seq_drop_while,
+    seq_enumerate,
+    seq_to_array,
+};
 
 /// Reference-counted, interior-mutable environment handle
 pub type EnvRef = Rc<RefCell<Environment>>;
 
+/// Evaluated modules keyed by canonical path, mapped to their public bindings.
+/// Shared between an importer and every module it (transitively) loads.
+pub type ModuleCache = Rc<RefCell<HashMap<PathBuf, ObjectMap>>>;
+
+/// Scopes with at most this many bindings are searched by comparing names
+/// rather than through an index.
+const SCAN_LIMIT: usize = 8;
+
+/// The names a function's scope binds, in slot order, as worked out by the
+/// resolver before the program runs. A call's scope holds its values in a
+/// vector in this order, so identifiers resolved to a slot skip the search.
+#[derive(Debug, Default, PartialEq)]
+pub struct ScopeLayout {
+    names: Vec<String>,
+    /// The slot of each name, for layouts too big to search `names`.
+    index: HashMap<String, usize>,
+}
+
+impl ScopeLayout {
+    /// A layout with `names` (already free of duplicates) in that order.
+    pub fn new(names: Vec<String>) -> Self {
+        let index = if names.len() > SCAN_LIMIT {
+            names.iter().enumerate().map(|(slot, name)| (name.clone(), slot)).collect()
+        } else {
+            HashMap::new()
+        };
+        ScopeLayout { names, index }
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// The slot of `name`, if the scope binds it.
+    pub fn slot(&self, name: &str) -> Option<usize> {
+        if self.names.len() <= SCAN_LIMIT {
+            self.names.iter().position(|n| n == name)
+        } else {
+            self.index.get(name).copied()
+        }
+    }
+}
+
+/// Where a name is bound within one scope.
+enum Slot {
+    /// In the scope's layout.
+    Fixed(usize),
+    /// Among the bindings outside the layout.
+    Extra(usize),
+}
+
 /// Simple lexical environment for variables
 #[derive(Debug, Clone, PartialEq)]
 pub struct Environment {
-    store: HashMap<String, Object>,
+    /// For a function call's scope, the names the resolver found its body
+    /// binds; `fixed` holds their values, `None` until bound.
+    layout: Option<Arc<ScopeLayout>>,
+    fixed: Vec<Option<Object>>,
+    /// Values of the other bindings in this scope, in the order they were
+    /// first bound.
+    slots: Vec<Object>,
+    /// The name bound at each of `slots`.
+    names: Vec<String>,
+    /// The slot of each name, for scopes too big to search `names`.
+    index: HashMap<String, usize>,
     outer: Option<EnvRef>,
     module_dir: Option<PathBuf>,
+    /// Extra directories searched for relative imports, before `SLANG_PATH`.
+    /// Inherited by enclosed scopes and imported modules.
+    import_paths: Vec<PathBuf>,
+    /// The modules being imported to reach this one, outermost first, ending
+    /// with this module's own path. The main program's chain is its script's
+    /// path, or empty when it didn't come from a file.
+    import_chain: Vec<PathBuf>,
     subscriptions: HashMap<String, Vec<Object>>,
+    modules: ModuleCache,
+    exports: Vec<String>,
+    /// Names declared with `const` in this scope, and `Ns::member` for the
+    /// `const` members of namespaces bound here.
+    constants: HashSet<String>,
+    /// Builtin namespaces not shadowed by a binding; `None` until first looked up.
+    lazy_namespaces: RefCell<HashMap<&'static str, Option<Object>>>,
+    /// Inherited by enclosed scopes and imported modules.
+    capabilities: Capabilities,
+    /// Whether the prelude's namespaces are bound. Inherited like `capabilities`.
+    prelude: bool,
 }
 
 impl Environment {
     pub fn new() -> EnvRef {
         Rc::new(RefCell::new(Environment {
-            store: HashMap::new(),
+            layout: None,
+            fixed: Vec::new(),
+            slots: Vec::new(),
+            names: Vec::new(),
+            index: HashMap::new(),
             outer: None,
             module_dir: None,
+            import_paths: Vec::new(),
+            import_chain: Vec::new(),
             subscriptions: HashMap::new(),
+            modules: Rc::new(RefCell::new(HashMap::new())),
+            exports: Vec::new(),
+            constants: HashSet::new(),
+            lazy_namespaces: RefCell::new(HashMap::new()),
+            capabilities: Capabilities::default(),
+            prelude: false,
         }))
     }
 
     pub fn new_enclosed(outer: EnvRef) -> EnvRef {
+        Environment::new_scope(outer, None)
+    }
+
+    /// The scope for a call to a function whose body binds the names in
+    /// `layout`.
+    pub fn new_function_scope(outer: EnvRef, layout: Arc<ScopeLayout>) -> EnvRef {
+        Environment::new_scope(outer, Some(layout))
+    }
+
+    fn new_scope(outer: EnvRef, layout: Option<Arc<ScopeLayout>>) -> EnvRef {
         let module_dir = outer.borrow().module_dir.clone();
+        let import_paths = outer.borrow().import_paths.clone();
+        let import_chain = outer.borrow().import_chain.clone();
+        let modules = Rc::clone(&outer.borrow().modules);
+        let capabilities = outer.borrow().capabilities;
+        let prelude = outer.borrow().prelude;
+        let fixed = layout.as_ref().map_or_else(Vec::new, |layout| vec![None; layout.names.len()]);
         Rc::new(RefCell::new(Environment {
-            store: HashMap::new(),
+            layout,
+            fixed,
+            slots: Vec::new(),
+            names: Vec::new(),
+            index: HashMap::new(),
             outer: Some(outer),
             module_dir,
+            import_paths,
+            import_chain,
             subscriptions: HashMap::new(),
+            modules,
+            exports: Vec::new(),
+            constants: HashSet::new(),
+            lazy_namespaces: RefCell::new(HashMap::new()),
+            capabilities,
+            prelude,
         }))
     }
 
     pub fn get(&self, name: &str) -> Option<Object> {
-        if let Some(val) = self.store.get(name) {
-            Some(val.clone())
+        if let Some(value) = self.binding(name) {
+            Some(value.clone())
+        } else if let Some(ns) = self.lazy_namespace(name) {
+            Some(ns)
         } else if let Some(ref outer) = self.outer {
             outer.borrow().get(name)
         } else {
@@ -252,12 +603,193 @@ impl Environment {
         }
     }
 
+    /// Like [`Environment::get`], for an identifier the resolver placed at
+    /// `binding`. A resolved lookup goes straight to the right scope, but
+    /// only through function scopes with no bindings besides their layout's;
+    /// anything else (an `import` inside a function, a scope the resolver
+    /// didn't see, a local not bound yet) falls back to the search by name.
+    pub fn get_resolved(&self, name: &str, binding: Binding) -> Option<Object> {
+        let found = match binding {
+            Binding::Dynamic => None,
+            Binding::Local { depth, slot } => self.scope_out(depth as usize, &|env: &Environment| {
+                let slot = slot as usize;
+                let layout = env.layout.as_ref()?;
+                if layout.names.get(slot)? != name {
+                    return None;
+                }
+                env.fixed[slot].clone().map(Some)
+            }),
+            Binding::Outer { depth } => self.scope_out(depth as usize, &|env: &Environment| Some(env.get(name))),
+        };
+        match found {
+            Some(value) => value,
+            None => self.get(name),
+        }
+    }
+
+    /// `f` applied to the scope `depth` scopes out from this one, if every
+    /// scope on the way is a function scope holding only its layout's names.
+    fn scope_out<T>(&self, depth: usize, f: &impl Fn(&Environment) -> Option<T>) -> Option<T> {
+        if depth == 0 {
+            return f(self);
+        }
+        if self.layout.is_none() || !self.names.is_empty() {
+            return None;
+        }
+        self.outer.as_ref()?.borrow().scope_out(depth - 1, f)
+    }
+
+    /// Where `name` is bound in this scope.
+    fn position(&self, name: &str) -> Option<Slot> {
+        if let Some(slot) = self.layout.as_ref().and_then(|layout| layout.slot(name)) {
+            // Names in the layout are never bound outside it.
+            return self.fixed[slot].is_some().then_some(Slot::Fixed(slot));
+        }
+        if self.names.len() <= SCAN_LIMIT {
+            self.names.iter().position(|n| n == name).map(Slot::Extra)
+        } else {
+            self.index.get(name).copied().map(Slot::Extra)
+        }
+    }
+
+    /// The value bound to `name` in this scope.
+    fn binding(&self, name: &str) -> Option<&Object> {
+        match self.position(name)? {
+            Slot::Fixed(slot) => self.fixed[slot].as_ref(),
+            Slot::Extra(slot) => Some(&self.slots[slot]),
+        }
+    }
+
+    /// Mutable access to a binding made in this scope (not outer scopes or
+    /// unbound builtin namespaces).
+    pub fn get_local_mut(&mut self, name: &str) -> Option<&mut Object> {
+        match self.position(name)? {
+            Slot::Fixed(slot) => self.fixed[slot].as_mut(),
+            Slot::Extra(slot) => Some(&mut self.slots[slot]),
+        }
+    }
+
     pub fn set(&mut self, name: String, value: Object) {
-        self.store.insert(name, value);
+        if let Some(slot) = self.layout.as_ref().and_then(|layout| layout.slot(&name)) {
+            self.fixed[slot] = Some(value);
+            return;
+        }
+        if let Some(Slot::Extra(slot)) = self.position(&name) {
+            self.slots[slot] = value;
+            return;
+        }
+        self.lazy_namespaces.get_mut().remove(name.as_str());
+        self.index.insert(name.clone(), self.slots.len());
+        self.names.push(name);
+        self.slots.push(value);
+    }
+
+    /// Bind `name` in this scope and mark it as a constant.
+    pub fn set_const(&mut self, name: String, value: Object) {
+        self.constants.insert(name.clone());
+        self.set(name, value);
+    }
+
+    /// Mark `name`, bound in this scope, as a constant. `Ns::member` marks a
+    /// member of the namespace `Ns` bound here.
+    pub fn mark_const(&mut self, name: String) {
+        self.constants.insert(name);
+    }
+
+    /// Whether `name` was declared with `const` in this scope.
+    pub fn is_local_const(&self, name: &str) -> bool {
+        self.constants.contains(name)
+    }
+
+    /// Whether the nearest binding of `name` was declared with `const`. For
+    /// `Ns::member`, whether that member of the namespace `Ns` was.
+    pub fn is_const(&self, name: &str) -> bool {
+        let binding = name.split("::").next().unwrap_or(name);
+        if self.position(binding).is_some() {
+            return self.constants.contains(name);
+        }
+        self.outer.as_ref().is_some_and(|outer| outer.borrow().is_const(name))
+    }
+
+    /// Names declared with `const` in this scope, including the `Ns::member`
+    /// names of constant namespace members.
+    pub fn constants(&self) -> impl Iterator<Item = &String> {
+        self.constants.iter()
+    }
+
+    /// The constant members of the namespace `namespace`, from the nearest
+    /// scope that binds it, without the `namespace::` prefix.
+    pub fn const_members(&self, namespace: &str) -> Vec<String> {
+        if self.position(namespace).is_none() {
+            return match &self.outer {
+                Some(outer) => outer.borrow().const_members(namespace),
+                None => Vec::new(),
+            };
+        }
+        let prefix = format!("{}::", namespace);
+        self.constants
+            .iter()
+            .filter_map(|name| name.strip_prefix(&prefix))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// The scope this one is nested in, if any.
+    pub fn outer(&self) -> Option<EnvRef> {
+        self.outer.clone()
+    }
+
+    /// Bindings made in this scope. Builtin namespaces are only included once
+    /// they've been rebound (e.g. by merging a user namespace into them).
+    pub fn snapshot(&self) -> ObjectMap {
+        let fixed = self.layout.iter().flat_map(|layout| {
+            layout.names.iter().zip(&self.fixed).filter_map(|(name, value)| Some((name.clone(), value.clone()?)))
+        });
+        fixed.chain(self.names.iter().cloned().zip(self.slots.iter().cloned())).collect()
+    }
+
+    /// Names bound directly in this scope, sorted, including builtin
+    /// namespaces that haven't been built yet.
+    pub fn names(&self) -> Vec<String> {
+        let mut names = self.names.clone();
+        if let Some(layout) = &self.layout {
+            let bound = layout.names.iter().zip(&self.fixed).filter(|(_, value)| value.is_some());
+            names.extend(bound.map(|(name, _)| name.clone()));
+        }
+        names.extend(self.lazy_namespaces.borrow().keys().map(|name| name.to_string()));
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Look up a lazily bound builtin namespace, building it on first use.
+    fn lazy_namespace(&self, name: &str) -> Option<Object> {
+        let mut lazy = self.lazy_namespaces.borrow_mut();
+        if lazy.is_empty() {
+            return None;
+        }
+        let slot = lazy.get_mut(name)?;
+        if slot.is_none() {
+            // The prelude's source re-opens a builtin namespace of the same
+            // name, so its members include the builtin ones.
+            let mut members = profile::time(&format!("namespace {}", name), || {
+                let prelude = if self.prelude { stdlib::core::load(name, self.capabilities) } else { None };
+                prelude.or_else(|| builtin_namespace_members(name))
+            })?;
+            members.retain(|member, _| self.capabilities.allows(&format!("{}::{}", name, member)));
+            *slot = Some(Object::from_map(members));
+        }
+        slot.clone()
     }
 
-    pub fn snapshot(&self) -> HashMap<String, Object> {
-        self.store.clone()
+    /// The capability groups code in this environment may use.
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    /// Whether the prelude's namespaces are bound.
+    pub fn has_prelude(&self) -> bool {
+        self.prelude
     }
 
     pub fn module_dir(&self) -> Option<PathBuf> {
@@ -268,6 +800,27 @@ impl Environment {
         self.module_dir = dir;
     }
 
+    /// Directories searched, in order, for a relative import that isn't
+    /// found next to the importing file.
+    pub fn import_paths(&self) -> &[PathBuf] {
+        &self.import_paths
+    }
+
+    pub fn add_import_path(&mut self, dir: impl Into<PathBuf>) {
+        let dir = dir.into();
+        if !self.import_paths.contains(&dir) {
+            self.import_paths.push(dir);
+        }
+    }
+
+    pub fn import_chain(&self) -> &[PathBuf] {
+        &self.import_chain
+    }
+
+    pub fn set_import_chain(&mut self, chain: Vec<PathBuf>) {
+        self.import_chain = chain;
+    }
+
     pub fn subscriptions(&self) -> &HashMap<String, Vec<Object>> {
         &self.subscriptions
     }
@@ -275,256 +828,884 @@ impl Environment {
     pub fn subscriptions_mut(&mut self) -> &mut HashMap<String, Vec<Object>> {
         &mut self.subscriptions
     }
+
+    pub fn module_cache(&self) -> ModuleCache {
+        Rc::clone(&self.modules)
+    }
+
+    pub fn set_module_cache(&mut self, modules: ModuleCache) {
+        self.modules = modules;
+    }
+
+    /// Names explicitly marked with `export` in this scope, in declaration order.
+    pub fn exports(&self) -> &[String] {
+        &self.exports
+    }
+
+    pub fn add_export(&mut self, name: String) {
+        if !self.exports.contains(&name) {
+            self.exports.push(name);
+        }
+    }
 }
 
-/// Create a new, top-level environment.
+/// Builds the members of one builtin namespace.
+type NamespaceInit = fn() -> ObjectMap;
+
+/// Builtin namespaces bound in every top-level environment, with the
+/// function that builds each one's members.
+///
+/// The maps are only built when a namespace is first looked up, so scripts
+/// don't pay for namespaces they never touch. Namespaces behind a cargo
+/// feature are only listed when it is enabled.
+static BUILTIN_NAMESPACES: LazyLock<Vec<(&str, NamespaceInit)>> = LazyLock::new(|| {
+    // Only pushed to below when an optional namespace is enabled.
+    #[allow(unused_mut)]
+    let mut namespaces: Vec<(&str, NamespaceInit)> = vec![
+        ("Option", option_namespace),
+        ("Result", result_namespace),
+        ("Type", type_namespace),
+        ("File", file_namespace),
+        ("Array", array_namespace),
+        ("Math", math_namespace),
+        ("String", string_namespace),
+        ("Json", json_namespace),
+        ("Test", test_namespace),
+        ("Object", object_namespace),
+        ("Time", time_namespace),
+        ("Sys", sys_namespace),
+        ("Process", process_namespace),
+        ("Fn", fn_namespace),
+        ("Number", number_namespace),
+        ("Timer", timer_namespace),
+        ("Runtime", runtime_namespace),
+        ("Events", events_namespace),
+        ("Task", task_namespace),
+        ("Channel", channel_namespace),
+        ("Schema", schema_namespace),
+        ("Bloom", bloom_namespace),
+        ("Ref", ref_namespace),
+        ("Seq", seq_namespace),
+        ("Iter", seq_namespace),
+        ("Dir", dir_namespace),
+        ("Path", path_namespace),
+        ("Bytes", bytes_namespace),
+        ("Uuid", uuid_namespace),
+        ("Debug", debug_namespace),
+        ("Log", log_namespace),
+    ];
+    #[cfg(feature = "regex")]
+    namespaces.push(("Regex", regex_namespace));
+    #[cfg(feature = "http")]
+    namespaces.push(("HTTP", http_namespace));
+    #[cfg(feature = "http")]
+    namespaces.push(("HttpServer", http_server_namespace));
+    #[cfg(feature = "encoding")]
+    namespaces.push(("Encoding", encoding_namespace));
+    #[cfg(feature = "crypto")]
+    namespaces.push(("Crypto", crypto_namespace));
+    #[cfg(feature = "formats")]
+    namespaces.push(("Yaml", yaml_namespace));
+    #[cfg(feature = "formats")]
+    namespaces.push(("Toml", toml_namespace));
+    namespaces
+});
+
+/// Whether `name` is one of the namespaces pre-bound by [`new_env`],
+/// including the prelude's.
+pub fn is_builtin_namespace(name: &str) -> bool {
+    BUILTIN_NAMESPACES.iter().any(|(ns, _)| *ns == name) || stdlib::names().any(|ns| ns == name)
+}
+
+/// The members of the builtin namespace `name`, as an unrestricted
+/// environment binds them.
+pub(crate) fn builtin_namespace_members(name: &str) -> Option<ObjectMap> {
+    let (_, build) = BUILTIN_NAMESPACES.iter().find(|(ns, _)| *ns == name)?;
+    Some(build())
+}
+
+/// Create a new, top-level environment with every builtin available.
 #[inline]
 pub fn new_env() -> EnvRef {
-    let env = Environment::new();
-
-    {
-        // Pre-bind namespaces Option, Result, Regex, File, Array, Math, String, Json and Test.
-        let mut inner = env.borrow_mut();
-
-        // Option = { Some, None, isSome, isNone, unwrapOr, map, andThen, bind, fmap }
-        let mut option_methods = HashMap::new();
-        option_methods.insert("Some".to_string(), Object::Builtin(option_some));
-        option_methods.insert("None".to_string(), Object::Builtin(option_none));
-        option_methods.insert("isSome".to_string(), Object::Builtin(option_is_some));
-        option_methods.insert("isNone".to_string(), Object::Builtin(option_is_none));
-        option_methods.insert("unwrapOr".to_string(), Object::Builtin(option_unwrap_or));
-        option_methods.insert("map".to_string(), Object::Builtin(option_map));
-        option_methods.insert("andThen".to_string(), Object::Builtin(option_and_then));
-        option_methods.insert("bind".to_string(), Object::Builtin(option_bind));
-        option_methods.insert("fmap".to_string(), Object::Builtin(option_fmap));
-        inner.set("Option".to_string(), Object::Object(option_methods));
-
-        // Result = { Ok, Err, isOk, isErr, unwrapOr, map, andThen, bind, fmap }
-        let mut result_methods = HashMap::new();
-        result_methods.insert("Ok".to_string(), Object::Builtin(result_ok));
-        result_methods.insert("Err".to_string(), Object::Builtin(result_err));
-        result_methods.insert("isOk".to_string(), Object::Builtin(result_is_ok));
-        result_methods.insert("isErr".to_string(), Object::Builtin(result_is_err));
-        result_methods.insert("unwrapOr".to_string(), Object::Builtin(result_unwrap_or));
-        result_methods.insert("map".to_string(), Object::Builtin(result_map));
-        result_methods.insert("andThen".to_string(), Object::Builtin(result_and_then));
-        result_methods.insert("bind".to_string(), Object::Builtin(result_bind));
-        result_methods.insert("fmap".to_string(), Object::Builtin(result_fmap));
-        inner.set("Result".to_string(), Object::Object(result_methods));
-
-        // Type = { int, float, str, bool, of, isInt, isFloat, isNumber, isBool, isString, isArray, isObject, isCallable, isIterable, isNull, isOption, isResult }
-        let mut type_methods = HashMap::new();
-        type_methods.insert("int".to_string(), Object::Builtin(type_int));
-        type_methods.insert("float".to_string(), Object::Builtin(type_float));
-        type_methods.insert("str".to_string(), Object::Builtin(type_str));
-        type_methods.insert("bool".to_string(), Object::Builtin(type_bool));
-        type_methods.insert("of".to_string(), Object::Builtin(type_of));
-        type_methods.insert("isInt".to_string(), Object::Builtin(type_is_int));
-        type_methods.insert("isFloat".to_string(), Object::Builtin(type_is_float));
-        type_methods.insert("isNumber".to_string(), Object::Builtin(type_is_number));
-        type_methods.insert("isBool".to_string(), Object::Builtin(type_is_bool));
-        type_methods.insert("isString".to_string(), Object::Builtin(type_is_string));
-        type_methods.insert("isArray".to_string(), Object::Builtin(type_is_array));
-        type_methods.insert("isObject".to_string(), Object::Builtin(type_is_object));
-        type_methods.insert("isCallable".to_string(), Object::Builtin(type_is_callable));
-        type_methods.insert("isIterable".to_string(), Object::Builtin(type_is_iterable));
-        type_methods.insert("isNull".to_string(), Object::Builtin(type_is_null));
-        type_methods.insert("isOption".to_string(), Object::Builtin(type_is_option));
-        type_methods.insert("isResult".to_string(), Object::Builtin(type_is_result));
-        inner.set("Type".to_string(), Object::Object(type_methods));
-
-        // Regex = { isMatch, find, replace, match }
-        let mut regex_methods = HashMap::new();
-        regex_methods.insert("isMatch".to_string(), Object::Builtin(builtin_regex_is_match));
-        regex_methods.insert("find".to_string(), Object::Builtin(builtin_regex_find));
-        regex_methods.insert("replace".to_string(), Object::Builtin(builtin_regex_replace));
-        regex_methods.insert("match".to_string(), Object::Builtin(builtin_regex_match));
-        inner.set("Regex".to_string(), Object::Object(regex_methods));
-
-        // File = { open, read, write, seek, close } – Result-based wrappers
-        let mut file_methods = HashMap::new();
-        file_methods.insert("open".to_string(), Object::Builtin(file_open_result));
-        file_methods.insert("read".to_string(), Object::Builtin(file_read_result));
-        file_methods.insert("write".to_string(), Object::Builtin(file_write_result));
-        file_methods.insert("seek".to_string(), Object::Builtin(file_seek_result));
-        file_methods.insert("close".to_string(), Object::Builtin(file_close_result));
-        inner.set("File".to_string(), Object::Object(file_methods));
-
-        // Array = { map, filter, reduce, find, some, every, flatMap, sort, sortBy, reverse, indexOf, includes, concat, slice, take, drop, range, unique, flatten, zip, unzip, groupBy, partition, fill, isEmpty, forEach, len }
-        let mut array_methods = HashMap::new();
-        array_methods.insert("map".to_string(), Object::Builtin(array_map));
-        array_methods.insert("filter".to_string(), Object::Builtin(array_filter));
-        array_methods.insert("reduce".to_string(), Object::Builtin(array_reduce));
-        array_methods.insert("find".to_string(), Object::Builtin(array_find));
-        array_methods.insert("some".to_string(), Object::Builtin(array_some));
-        array_methods.insert("every".to_string(), Object::Builtin(array_every));
-        array_methods.insert("flatMap".to_string(), Object::Builtin(array_flat_map));
-        array_methods.insert("sort".to_string(), Object::Builtin(array_sort));
-        array_methods.insert("sortBy".to_string(), Object::Builtin(array_sort_by));
-        array_methods.insert("reverse".to_string(), Object::Builtin(array_reverse));
-        array_methods.insert("indexOf".to_string(), Object::Builtin(array_index_of));
-        array_methods.insert("includes".to_string(), Object::Builtin(array_includes));
-        array_methods.insert("concat".to_string(), Object::Builtin(array_concat));
-        array_methods.insert("slice".to_string(), Object::Builtin(array_slice));
-        array_methods.insert("take".to_string(), Object::Builtin(array_take));
-        array_methods.insert("drop".to_string(), Object::Builtin(array_drop));
-        array_methods.insert("range".to_string(), Object::Builtin(array_range));
-        array_methods.insert("unique".to_string(), Object::Builtin(array_unique));
-        array_methods.insert("flatten".to_string(), Object::Builtin(array_flatten));
-        array_methods.insert("zip".to_string(), Object::Builtin(array_zip));
-        array_methods.insert("unzip".to_string(), Object::Builtin(array_unzip));
-        array_methods.insert("groupBy".to_string(), Object::Builtin(array_group_by));
-        array_methods.insert("partition".to_string(), Object::Builtin(array_partition));
-        array_methods.insert("fill".to_string(), Object::Builtin(array_fill));
-        array_methods.insert("isEmpty".to_string(), Object::Builtin(array_is_empty));
-        array_methods.insert("forEach".to_string(), Object::Builtin(array_for_each));
-        array_methods.insert("len".to_string(), Object::Builtin(array_len));
-        inner.set("Array".to_string(), Object::Object(array_methods));
-
-        // Math = { abs, floor, ceil, round, min, max, pow, sin, cos, tan, sqrt, log, log10, log2, exp, asin, acos, atan, atan2, sinh, cosh, tanh, PI, E, TAU, sign, clamp, random, randomInt }
-        let mut math_methods = HashMap::new();
-        math_methods.insert("abs".to_string(), Object::Builtin(math_abs));
-        math_methods.insert("floor".to_string(), Object::Builtin(math_floor));
-        math_methods.insert("ceil".to_string(), Object::Builtin(math_ceil));
-        math_methods.insert("round".to_string(), Object::Builtin(math_round));
-        math_methods.insert("min".to_string(), Object::Builtin(math_min));
-        math_methods.insert("max".to_string(), Object::Builtin(math_max));
-        math_methods.insert("pow".to_string(), Object::Builtin(math_pow));
-        math_methods.insert("sin".to_string(), Object::Builtin(math_sin));
-        math_methods.insert("cos".to_string(), Object::Builtin(math_cos));
-        math_methods.insert("tan".to_string(), Object::Builtin(math_tan));
-        math_methods.insert("sqrt".to_string(), Object::Builtin(math_sqrt));
-        math_methods.insert("log".to_string(), Object::Builtin(math_log));
-        math_methods.insert("log10".to_string(), Object::Builtin(math_log10));
-        math_methods.insert("log2".to_string(), Object::Builtin(math_log2));
-        math_methods.insert("exp".to_string(), Object::Builtin(math_exp));
-        math_methods.insert("asin".to_string(), Object::Builtin(math_asin));
-        math_methods.insert("acos".to_string(), Object::Builtin(math_acos));
-        math_methods.insert("atan".to_string(), Object::Builtin(math_atan));
-        math_methods.insert("atan2".to_string(), Object::Builtin(math_atan2));
-        math_methods.insert("sinh".to_string(), Object::Builtin(math_sinh));
-        math_methods.insert("cosh".to_string(), Object::Builtin(math_cosh));
-        math_methods.insert("tanh".to_string(), Object::Builtin(math_tanh));
-        math_methods.insert("PI".to_string(), Object::Builtin(math_pi));
-        math_methods.insert("E".to_string(), Object::Builtin(math_e));
-        math_methods.insert("TAU".to_string(), Object::Builtin(math_tau));
-        math_methods.insert("sign".to_string(), Object::Builtin(math_sign));
-        math_methods.insert("clamp".to_string(), Object::Builtin(math_clamp));
-        math_methods.insert("random".to_string(), Object::Builtin(math_random));
-        math_methods.insert("randomInt".to_string(), Object::Builtin(math_random_int));
-        inner.set("Math".to_string(), Object::Object(math_methods));
-
-        // String = { trim, toUpper, toLower, split, join, contains, startsWith, endsWith, indexOf, slice, replace, repeat, reverse, padLeft, padRight, chars, charCodeAt, fromCharCode, fromCharCodes, lastIndexOf, replaceAll, charCodes, isEmpty, len }
-        let mut string_methods = HashMap::new();
-        string_methods.insert("trim".to_string(), Object::Builtin(string_trim));
-        string_methods.insert("toUpper".to_string(), Object::Builtin(string_to_upper));
-        string_methods.insert("toLower".to_string(), Object::Builtin(string_to_lower));
-        string_methods.insert("split".to_string(), Object::Builtin(string_split));
-        string_methods.insert("join".to_string(), Object::Builtin(string_join));
-        string_methods.insert("contains".to_string(), Object::Builtin(string_contains));
-        string_methods.insert("startsWith".to_string(), Object::Builtin(string_starts_with));
-        string_methods.insert("endsWith".to_string(), Object::Builtin(string_ends_with));
-        string_methods.insert("indexOf".to_string(), Object::Builtin(string_index_of));
-        string_methods.insert("slice".to_string(), Object::Builtin(string_slice));
-        string_methods.insert("replace".to_string(), Object::Builtin(string_replace));
-        string_methods.insert("repeat".to_string(), Object::Builtin(string_repeat));
-        string_methods.insert("reverse".to_string(), Object::Builtin(string_reverse));
-        string_methods.insert("padLeft".to_string(), Object::Builtin(string_pad_left));
-        string_methods.insert("padRight".to_string(), Object::Builtin(string_pad_right));
-        string_methods.insert("chars".to_string(), Object::Builtin(string_chars));
-        string_methods.insert("charCodeAt".to_string(), Object::Builtin(string_char_code_at));
-        string_methods.insert("fromCharCode".to_string(), Object::Builtin(string_from_char_code));
-        string_methods.insert("fromCharCodes".to_string(), Object::Builtin(string_from_char_codes));
-        string_methods.insert("lastIndexOf".to_string(), Object::Builtin(string_last_index_of));
-        string_methods.insert("replaceAll".to_string(), Object::Builtin(string_replace_all));
-        string_methods.insert("charCodes".to_string(), Object::Builtin(string_char_codes));
-        string_methods.insert("isEmpty".to_string(), Object::Builtin(string_is_empty));
-        string_methods.insert("len".to_string(), Object::Builtin(string_len));
-        inner.set("String".to_string(), Object::Object(string_methods));
-
-        // Json = { parse, stringify }
-        let mut json_methods = HashMap::new();
-        json_methods.insert("parse".to_string(), Object::Builtin(json_parse));
-        json_methods.insert("stringify".to_string(), Object::Builtin(json_stringify));
-        inner.set("Json".to_string(), Object::Object(json_methods));
-
-        // Test = { assert, assertEq, assertNotEq }
-        let mut test_methods = HashMap::new();
-        test_methods.insert("assert".to_string(), Object::Builtin(test_assert));
-        test_methods.insert("assertEq".to_string(), Object::Builtin(test_assert_eq));
-        test_methods.insert("assertNotEq".to_string(), Object::Builtin(test_assert_not_eq));
-        inner.set("Test".to_string(), Object::Object(test_methods));
-
-        // Object = { keys, values, entries, fromEntries, has, get, set, delete, merge, isEmpty, len }
-        let mut obj_methods = HashMap::new();
-        obj_methods.insert("keys".to_string(), Object::Builtin(object_keys));
-        obj_methods.insert("values".to_string(), Object::Builtin(object_values));
-        obj_methods.insert("entries".to_string(), Object::Builtin(object_entries));
-        obj_methods.insert("fromEntries".to_string(), Object::Builtin(object_from_entries));
-        obj_methods.insert("has".to_string(), Object::Builtin(object_has));
-        obj_methods.insert("get".to_string(), Object::Builtin(object_get));
-        obj_methods.insert("set".to_string(), Object::Builtin(object_set));
-        obj_methods.insert("delete".to_string(), Object::Builtin(object_delete));
-        obj_methods.insert("merge".to_string(), Object::Builtin(object_merge));
-        obj_methods.insert("isEmpty".to_string(), Object::Builtin(object_is_empty));
-        obj_methods.insert("len".to_string(), Object::Builtin(object_len));
-        inner.set("Obj".to_string(), Object::Object(obj_methods));
-
-        // Time = { now, nowSecs, sleep, year, month, day, hour, minute, second, dayOfWeek, format, toObject }
-        let mut time_methods = HashMap::new();
-        time_methods.insert("now".to_string(), Object::Builtin(time_now));
-        time_methods.insert("nowSecs".to_string(), Object::Builtin(time_now_secs));
-        time_methods.insert("sleep".to_string(), Object::Builtin(time_sleep));
-        time_methods.insert("year".to_string(), Object::Builtin(time_year));
-        time_methods.insert("month".to_string(), Object::Builtin(time_month));
-        time_methods.insert("day".to_string(), Object::Builtin(time_day));
-        time_methods.insert("hour".to_string(), Object::Builtin(time_hour));
-        time_methods.insert("minute".to_string(), Object::Builtin(time_minute));
-        time_methods.insert("second".to_string(), Object::Builtin(time_second));
-        time_methods.insert("dayOfWeek".to_string(), Object::Builtin(time_day_of_week));
-        time_methods.insert("format".to_string(), Object::Builtin(time_format));
-        time_methods.insert("toObject".to_string(), Object::Builtin(time_to_object));
-        inner.set("Time".to_string(), Object::Object(time_methods));
-
-        // Sys = { env, setEnv, args, exit, cwd, setCwd, exec, platform, arch }
-        let mut sys_methods = HashMap::new();
-        sys_methods.insert("env".to_string(), Object::Builtin(sys_env));
-        sys_methods.insert("setEnv".to_string(), Object::Builtin(sys_set_env));
-        sys_methods.insert("args".to_string(), Object::Builtin(sys_args));
-        sys_methods.insert("exit".to_string(), Object::Builtin(sys_exit));
-        sys_methods.insert("cwd".to_string(), Object::Builtin(sys_cwd));
-        sys_methods.insert("setCwd".to_string(), Object::Builtin(sys_set_cwd));
-        sys_methods.insert("exec".to_string(), Object::Builtin(sys_exec));
-        sys_methods.insert("platform".to_string(), Object::Builtin(sys_platform));
-        sys_methods.insert("arch".to_string(), Object::Builtin(sys_arch));
-        inner.set("Sys".to_string(), Object::Object(sys_methods));
-
-        // HTTP = { get, post, put, delete, patch, head }
-        let mut http_methods = HashMap::new();
-        http_methods.insert("get".to_string(), Object::Builtin(http_get));
-        http_methods.insert("post".to_string(), Object::Builtin(http_post));
-        http_methods.insert("put".to_string(), Object::Builtin(http_put));
-        http_methods.insert("delete".to_string(), Object::Builtin(http_delete));
-        http_methods.insert("patch".to_string(), Object::Builtin(http_patch));
-        http_methods.insert("head".to_string(), Object::Builtin(http_head));
-        inner.set("HTTP".to_string(), Object::Object(http_methods));
-
-        // Fn = { identity, constant, compose, pipe, apply, call, negate, flip, partial, isCallable }
-        let mut fn_methods = HashMap::new();
-        fn_methods.insert("identity".to_string(), Object::Builtin(fn_identity));
-        fn_methods.insert("constant".to_string(), Object::Builtin(fn_constant));
-        fn_methods.insert("compose".to_string(), Object::Builtin(fn_compose));
-        fn_methods.insert("pipe".to_string(), Object::Builtin(fn_pipe));
-        fn_methods.insert("apply".to_string(), Object::Builtin(fn_apply));
-        fn_methods.insert("call".to_string(), Object::Builtin(fn_call));
-        fn_methods.insert("negate".to_string(), Object::Builtin(fn_negate));
-        fn_methods.insert("flip".to_string(), Object::Builtin(fn_flip));
-        fn_methods.insert("partial".to_string(), Object::Builtin(fn_partial));
-        fn_methods.insert("isCallable".to_string(), Object::Builtin(fn_is_callable));
-        inner.set("Fn".to_string(), Object::Object(fn_methods));
-    }
-
-    env
+    EnvBuilder::new().build()
+}
+
+/// Builds a top-level environment, optionally leaving out builtins that reach
+/// outside the interpreter, for embedding Slang as a config or extension
+/// language:
+///
+/// ```
+/// let env = slang::env::EnvBuilder::new().without_fs().without_net().without_process().build();
+/// ```
+///
+/// Disabled namespaces (`File`, `HTTP`) and builtins (`Sys::exec`,
+/// `file_open`, ...) are unbound, and the restrictions carry over to
+/// function scopes and imported modules. Without filesystem access `import`
+/// is refused too, since it reads scripts from disk.
+///
+/// Host functions are added with [`register_namespace`](Self::register_namespace)
+/// and [`func`](Self::func); they may capture state:
+///
+/// ```
+/// use std::cell::Cell;
+/// use std::rc::Rc;
+/// use slang::env::EnvBuilder;
+/// use slang::object::Object;
+///
+/// let spawned = Rc::new(Cell::new(0));
+/// let counter = Rc::clone(&spawned);
+/// let env = EnvBuilder::new()
+///     .register_namespace("Game", |ns| {
+///         ns.func("spawn", move |_args, _env| {
+///             counter.set(counter.get() + 1);
+///             Object::Integer(counter.get())
+///         })
+///         .value("MAX_PLAYERS", Object::Integer(4))
+///     })
+///     .build();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EnvBuilder {
+    capabilities: Capabilities,
+    bindings: Vec<(String, Object)>,
+    without_prelude: bool,
+}
+
+impl EnvBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drop `File`, the `file_*` builtins, `Sys::cwd`/`Sys::setCwd` and `import`.
+    pub fn without_fs(mut self) -> Self {
+        self.capabilities.fs = false;
+        self
+    }
+
+    /// Drop the `HTTP` and `HttpServer` namespaces.
+    pub fn without_net(mut self) -> Self {
+        self.capabilities.net = false;
+        self
+    }
+
+    /// Drop `Sys::exec`, `Sys::exit`, `Sys::args` and `Sys::env`/`Sys::setEnv`.
+    pub fn without_process(mut self) -> Self {
+        self.capabilities.process = false;
+        self
+    }
+
+    /// Leave out the namespaces written in Slang (`Queue`, and the Slang
+    /// members of `Fn`). `--no-prelude` does this for every environment.
+    pub fn without_prelude(mut self) -> Self {
+        self.without_prelude = true;
+        self
+    }
+
+    pub fn capabilities(mut self, capabilities: Capabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Bind a namespace of host functions and values as `name`. A namespace
+    /// with the same name as a builtin one replaces it.
+    pub fn register_namespace(
+        mut self,
+        name: &str,
+        build: impl FnOnce(NamespaceBuilder) -> NamespaceBuilder,
+    ) -> Self {
+        let namespace = build(NamespaceBuilder::new(name)).build();
+        self.bindings.push((name.to_string(), namespace));
+        self
+    }
+
+    /// Bind a global host function.
+    pub fn func(
+        mut self,
+        name: &str,
+        func: impl Fn(Vec<Object>, EnvRef) -> Object + 'static,
+    ) -> Self {
+        self.bindings.push((name.to_string(), Object::native_fn(name, func)));
+        self
+    }
+
+    /// Bind a global value.
+    pub fn value(mut self, name: &str, value: Object) -> Self {
+        self.bindings.push((name.to_string(), value));
+        self
+    }
+
+    pub fn build(self) -> EnvRef {
+        let env = Environment::new();
+        {
+            let mut inner = env.borrow_mut();
+            inner.capabilities = self.capabilities;
+            inner.prelude = !self.without_prelude && stdlib::is_enabled();
+            for (name, _) in BUILTIN_NAMESPACES.iter() {
+                if self.capabilities.allows(name) {
+                    inner.lazy_namespaces.get_mut().insert(name, None);
+                }
+            }
+            if inner.prelude {
+                for name in stdlib::names() {
+                    if self.capabilities.allows(name) {
+                        inner.lazy_namespaces.get_mut().insert(name, None);
+                    }
+                }
+            }
+            for (name, value) in self.bindings {
+                inner.set(name, value);
+            }
+        }
+        env
+    }
+}
+
+/// Collects the members of a host namespace for
+/// [`EnvBuilder::register_namespace`].
+#[derive(Debug, Clone)]
+pub struct NamespaceBuilder {
+    name: String,
+    members: ObjectMap,
+}
+
+impl NamespaceBuilder {
+    pub fn new(name: &str) -> Self {
+        NamespaceBuilder { name: name.to_string(), members: ObjectMap::new() }
+    }
+
+    /// Add a function that may capture host state.
+    pub fn func(
+        mut self,
+        name: &str,
+        func: impl Fn(Vec<Object>, EnvRef) -> Object + 'static,
+    ) -> Self {
+        let qualified = format!("{}::{}", self.name, name);
+        self.members.insert(name.to_string(), Object::native_fn(&qualified, func));
+        self
+    }
+
+    /// Add a plain `fn` builtin.
+    pub fn builtin(mut self, name: &str, func: BuiltinFunction) -> Self {
+        self.members.insert(name.to_string(), Object::Builtin(func));
+        self
+    }
+
+    /// Add a constant or any other value.
+    pub fn value(mut self, name: &str, value: Object) -> Self {
+        self.members.insert(name.to_string(), value);
+        self
+    }
+
+    /// The namespace object, as bound in the environment.
+    pub fn build(self) -> Object {
+        Object::from_map(self.members)
+    }
+}
+
+// Option = { Some, None, isSome, isNone, unwrapOr, map, andThen, bind, fmap, unwrap, expect, okOr, filter, zip, flatten, toArray }
+fn option_namespace() -> ObjectMap {
+    let mut option_methods = ObjectMap::new();
+    option_methods.insert("Some".to_string(), Object::Builtin(option_some));
+    option_methods.insert("None".to_string(), Object::Builtin(option_none));
+    option_methods.insert("isSome".to_string(), Object::Builtin(option_is_some));
+    option_methods.insert("isNone".to_string(), Object::Builtin(option_is_none));
+    option_methods.insert("unwrapOr".to_string(), Object::Builtin(option_unwrap_or));
+    option_methods.insert("map".to_string(), Object::Builtin(option_map));
+    option_methods.insert("andThen".to_string(), Object::Builtin(option_and_then));
+    option_methods.insert("bind".to_string(), Object::Builtin(option_bind));
+    option_methods.insert("fmap".to_string(), Object::Builtin(option_fmap));
+    option_methods.insert("unwrap".to_string(), Object::Builtin(option_unwrap));
+    option_methods.insert("expect".to_string(), Object::Builtin(option_expect));
+    option_methods.insert("okOr".to_string(), Object::Builtin(option_ok_or));
+    option_methods.insert("filter".to_string(), Object::Builtin(option_filter));
+    option_methods.insert("zip".to_string(), Object::Builtin(option_zip));
+    option_methods.insert("flatten".to_string(), Object::Builtin(option_flatten));
+    option_methods.insert("toArray".to_string(), Object::Builtin(option_to_array));
+    option_methods
+}
+
+// Result = { Ok, Err, isOk, isErr, unwrapOr, map, andThen, bind, fmap, unwrap, expect, ok, mapErr }
+fn result_namespace() -> ObjectMap {
+    let mut result_methods = ObjectMap::new();
+    result_methods.insert("Ok".to_string(), Object::Builtin(result_ok));
+    result_methods.insert("Err".to_string(), Object::Builtin(result_err));
+    result_methods.insert("isOk".to_string(), Object::Builtin(result_is_ok));
+    result_methods.insert("isErr".to_string(), Object::Builtin(result_is_err));
+    result_methods.insert("unwrapOr".to_string(), Object::Builtin(result_unwrap_or));
+    result_methods.insert("map".to_string(), Object::Builtin(result_map));
+    result_methods.insert("andThen".to_string(), Object::Builtin(result_and_then));
+    result_methods.insert("bind".to_string(), Object::Builtin(result_bind));
+    result_methods.insert("fmap".to_string(), Object::Builtin(result_fmap));
+    result_methods.insert("unwrap".to_string(), Object::Builtin(result_unwrap));
+    result_methods.insert("expect".to_string(), Object::Builtin(result_expect));
+    result_methods.insert("ok".to_string(), Object::Builtin(result_to_option));
+    result_methods.insert("mapErr".to_string(), Object::Builtin(result_map_err));
+    result_methods
+}
+
+// Type = { int, float, str, bool, of, isInt, isFloat, isNumber, isBool, isString, isArray, isObject, isCallable, isIterable, isNull, isOption, isResult }
+fn type_namespace() -> ObjectMap {
+    let mut type_methods = ObjectMap::new();
+    type_methods.insert("int".to_string(), Object::Builtin(type_int));
+    type_methods.insert("float".to_string(), Object::Builtin(type_float));
+    type_methods.insert("str".to_string(), Object::Builtin(type_str));
+    type_methods.insert("bool".to_string(), Object::Builtin(type_bool));
+    type_methods.insert("toInt".to_string(), Object::Builtin(type_to_int));
+    type_methods.insert("toFloat".to_string(), Object::Builtin(type_to_float));
+    type_methods.insert("of".to_string(), Object::Builtin(type_of));
+    type_methods.insert("isInt".to_string(), Object::Builtin(type_is_int));
+    type_methods.insert("isFloat".to_string(), Object::Builtin(type_is_float));
+    type_methods.insert("isNumber".to_string(), Object::Builtin(type_is_number));
+    type_methods.insert("isBool".to_string(), Object::Builtin(type_is_bool));
+    type_methods.insert("isString".to_string(), Object::Builtin(type_is_string));
+    type_methods.insert("isArray".to_string(), Object::Builtin(type_is_array));
+    type_methods.insert("isObject".to_string(), Object::Builtin(type_is_object));
+    type_methods.insert("isCallable".to_string(), Object::Builtin(type_is_callable));
+    type_methods.insert("isIterable".to_string(), Object::Builtin(type_is_iterable));
+    type_methods.insert("isNull".to_string(), Object::Builtin(type_is_null));
+    type_methods.insert("isOption".to_string(), Object::Builtin(type_is_option));
+    type_methods.insert("isResult".to_string(), Object::Builtin(type_is_result));
+    type_methods
+}
+
+// Regex = { isMatch, find, replace, match, findAll, matchAll, captures, split }
+#[cfg(feature = "regex")]
+fn regex_namespace() -> ObjectMap {
+    let mut regex_methods = ObjectMap::new();
+    regex_methods.insert("isMatch".to_string(), Object::Builtin(builtin_regex_is_match));
+    regex_methods.insert("find".to_string(), Object::Builtin(builtin_regex_find));
+    regex_methods.insert("replace".to_string(), Object::Builtin(builtin_regex_replace));
+    regex_methods.insert("match".to_string(), Object::Builtin(builtin_regex_match));
+    regex_methods.insert("findAll".to_string(), Object::Builtin(builtin_regex_find_all));
+    regex_methods.insert("matchAll".to_string(), Object::Builtin(builtin_regex_match_all));
+    regex_methods.insert("captures".to_string(), Object::Builtin(builtin_regex_captures));
+    regex_methods.insert("split".to_string(), Object::Builtin(builtin_regex_split));
+    regex_methods
+}
+
+// File = { open, read, write, seek, close, readLine, readLines, readBytes, exists, size, readToString, writeString, append, delete, copy } – Result-based wrappers
+fn file_namespace() -> ObjectMap {
+    let mut file_methods = ObjectMap::new();
+    file_methods.insert("open".to_string(), Object::Builtin(file_open_result));
+    file_methods.insert("read".to_string(), Object::Builtin(file_read_result));
+    file_methods.insert("write".to_string(), Object::Builtin(file_write_result));
+    file_methods.insert("seek".to_string(), Object::Builtin(file_seek_result));
+    file_methods.insert("close".to_string(), Object::Builtin(file_close_result));
+    file_methods.insert("readLine".to_string(), Object::Builtin(file_read_line));
+    file_methods.insert("readLines".to_string(), Object::Builtin(file_read_lines));
+    file_methods.insert("readBytes".to_string(), Object::Builtin(file_read_bytes));
+    file_methods.insert("exists".to_string(), Object::Builtin(file_exists));
+    file_methods.insert("size".to_string(), Object::Builtin(file_size));
+    file_methods.insert("readToString".to_string(), Object::Builtin(file_read_to_string));
+    file_methods.insert("writeString".to_string(), Object::Builtin(file_write_string));
+    file_methods.insert("append".to_string(), Object::Builtin(file_append));
+    file_methods.insert("delete".to_string(), Object::Builtin(file_delete));
+    file_methods.insert("copy".to_string(), Object::Builtin(file_copy));
+    file_methods
+}
+
+// Array = { map, filter, reduce, find, some, every, flatMap, sort, sortBy, reverse, indexOf, includes, concat, slice, take, drop, takeWhile, dropWhile, skipWhile, range, unique, flatten, zip, unzip, groupBy, countBy, frequencies, partition, fill, isEmpty, forEach, len, first, last, rest, push, binarySearch, binarySearchBy, insertSorted, minBy, maxBy, sumBy, pushMut, product, combinations, permutations }
+fn array_namespace() -> ObjectMap {
+    let mut array_methods = ObjectMap::new();
+    array_methods.insert("map".to_string(), Object::Builtin(array_map));
+    array_methods.insert("filter".to_string(), Object::Builtin(array_filter));
+    array_methods.insert("parMap".to_string(), Object::Builtin(array_par_map));
+    array_methods.insert("parFilter".to_string(), Object::Builtin(array_par_filter));
+    array_methods.insert("reduce".to_string(), Object::Builtin(array_reduce));
+    array_methods.insert("find".to_string(), Object::Builtin(array_find));
+    array_methods.insert("some".to_string(), Object::Builtin(array_some));
+    array_methods.insert("every".to_string(), Object::Builtin(array_every));
+    array_methods.insert("flatMap".to_string(), Object::Builtin(array_flat_map));
+    array_methods.insert("sort".to_string(), Object::Builtin(array_sort));
+    array_methods.insert("sortBy".to_string(), Object::Builtin(array_sort_by));
+    array_methods.insert("reverse".to_string(), Object::Builtin(array_reverse));
+    array_methods.insert("indexOf".to_string(), Object::Builtin(array_index_of));
+    array_methods.insert("includes".to_string(), Object::Builtin(array_includes));
+    array_methods.insert("concat".to_string(), Object::Builtin(array_concat));
+    array_methods.insert("slice".to_string(), Object::Builtin(array_slice));
+    array_methods.insert("take".to_string(), Object::Builtin(array_take));
+    array_methods.insert("drop".to_string(), Object::Builtin(array_drop));
+    array_methods.insert("takeWhile".to_string(), Object::Builtin(array_take_while));
+    array_methods.insert("dropWhile".to_string(), Object::Builtin(array_drop_while));
+    array_methods.insert("skipWhile".to_string(), Object::Builtin(array_drop_while));
+    array_methods.insert("range".to_string(), Object::Builtin(array_range));
+    array_methods.insert("unique".to_string(), Object::Builtin(array_unique));
+    array_methods.insert("flatten".to_string(), Object::Builtin(array_flatten));
+    array_methods.insert("zip".to_string(), Object::Builtin(array_zip));
+    array_methods.insert("unzip".to_string(), Object::Builtin(array_unzip));
+    array_methods.insert("groupBy".to_string(), Object::Builtin(array_group_by));
+    array_methods.insert("countBy".to_string(), Object::Builtin(array_count_by));
+    array_methods.insert("frequencies".to_string(), Object::Builtin(array_frequencies));
+    array_methods.insert("partition".to_string(), Object::Builtin(array_partition));
+    array_methods.insert("fill".to_string(), Object::Builtin(array_fill));
+    array_methods.insert("isEmpty".to_string(), Object::Builtin(array_is_empty));
+    array_methods.insert("forEach".to_string(), Object::Builtin(array_for_each));
+    array_methods.insert("len".to_string(), Object::Builtin(array_len));
+    array_methods.insert("first".to_string(), Object::Builtin(builtin_first));
+    array_methods.insert("last".to_string(), Object::Builtin(builtin_last));
+    array_methods.insert("rest".to_string(), Object::Builtin(builtin_rest));
+    array_methods.insert("push".to_string(), Object::Builtin(builtin_push));
+    array_methods.insert("binarySearch".to_string(), Object::Builtin(array_binary_search));
+    array_methods.insert("binarySearchBy".to_string(), Object::Builtin(array_binary_search_by));
+    array_methods.insert("insertSorted".to_string(), Object::Builtin(array_insert_sorted));
+    array_methods.insert("minBy".to_string(), Object::Builtin(array_min_by));
+    array_methods.insert("maxBy".to_string(), Object::Builtin(array_max_by));
+    array_methods.insert("sumBy".to_string(), Object::Builtin(array_sum_by));
+    array_methods.insert("pushMut".to_string(), Object::Builtin(array_push_mut));
+    array_methods.insert("product".to_string(), Object::Builtin(array_product));
+    array_methods.insert("combinations".to_string(), Object::Builtin(array_combinations));
+    array_methods.insert("permutations".to_string(), Object::Builtin(array_permutations));
+    array_methods
+}
+
+// Math = { abs, floor, ceil, round, trunc, roundTo, floorDiv, divmod, min, max, pow, sin, cos, tan, sqrt, log, log10, log2, exp, asin, acos, atan, atan2, sinh, cosh, tanh, PI, E, TAU, sign, clamp, random, randomInt, randomHex }
+fn math_namespace() -> ObjectMap {
+    let mut math_methods = ObjectMap::new();
+    math_methods.insert("abs".to_string(), Object::Builtin(math_abs));
+    math_methods.insert("floor".to_string(), Object::Builtin(math_floor));
+    math_methods.insert("ceil".to_string(), Object::Builtin(math_ceil));
+    math_methods.insert("round".to_string(), Object::Builtin(math_round));
+    math_methods.insert("trunc".to_string(), Object::Builtin(math_trunc));
+    math_methods.insert("roundTo".to_string(), Object::Builtin(math_round_to));
+    math_methods.insert("floorDiv".to_string(), Object::Builtin(math_floor_div));
+    math_methods.insert("divmod".to_string(), Object::Builtin(math_divmod));
+    math_methods.insert("min".to_string(), Object::Builtin(math_min));
+    math_methods.insert("max".to_string(), Object::Builtin(math_max));
+    math_methods.insert("pow".to_string(), Object::Builtin(math_pow));
+    math_methods.insert("sin".to_string(), Object::Builtin(math_sin));
+    math_methods.insert("cos".to_string(), Object::Builtin(math_cos));
+    math_methods.insert("tan".to_string(), Object::Builtin(math_tan));
+    math_methods.insert("sqrt".to_string(), Object::Builtin(math_sqrt));
+    math_methods.insert("log".to_string(), Object::Builtin(math_log));
+    math_methods.insert("log10".to_string(), Object::Builtin(math_log10));
+    math_methods.insert("log2".to_string(), Object::Builtin(math_log2));
+    math_methods.insert("exp".to_string(), Object::Builtin(math_exp));
+    math_methods.insert("asin".to_string(), Object::Builtin(math_asin));
+    math_methods.insert("acos".to_string(), Object::Builtin(math_acos));
+    math_methods.insert("atan".to_string(), Object::Builtin(math_atan));
+    math_methods.insert("atan2".to_string(), Object::Builtin(math_atan2));
+    math_methods.insert("sinh".to_string(), Object::Builtin(math_sinh));
+    math_methods.insert("cosh".to_string(), Object::Builtin(math_cosh));
+    math_methods.insert("tanh".to_string(), Object::Builtin(math_tanh));
+    math_methods.insert("PI".to_string(), Object::Float(std::f64::consts::PI));
+    math_methods.insert("E".to_string(), Object::Float(std::f64::consts::E));
+    math_methods.insert("TAU".to_string(), Object::Float(std::f64::consts::TAU));
+    math_methods.insert("sign".to_string(), Object::Builtin(math_sign));
+    math_methods.insert("clamp".to_string(), Object::Builtin(math_clamp));
+    math_methods.insert("random".to_string(), Object::Builtin(math_random));
+    math_methods.insert("randomInt".to_string(), Object::Builtin(math_random_int));
+    math_methods.insert("randomHex".to_string(), Object::Builtin(math_random_hex));
+    math_methods
+}
+
+// String = { trim, toUpper, toLower, split, join, contains, startsWith, endsWith, indexOf, slice, replace, repeat, reverse, padLeft, padRight, chars, charCodeAt, fromCharCode, fromCharCodes, lastIndexOf, replaceAll, charCodes, isEmpty, len, words, lines, naturalCompare, levenshtein, toCamelCase, toSnakeCase, toKebabCase, toTitleCase, capitalize, truncate, wrap, indent, format }
+fn string_namespace() -> ObjectMap {
+    let mut string_methods = ObjectMap::new();
+    string_methods.insert("trim".to_string(), Object::Builtin(string_trim));
+    string_methods.insert("toUpper".to_string(), Object::Builtin(string_to_upper));
+    string_methods.insert("toLower".to_string(), Object::Builtin(string_to_lower));
+    string_methods.insert("split".to_string(), Object::Builtin(string_split));
+    string_methods.insert("join".to_string(), Object::Builtin(string_join));
+    string_methods.insert("contains".to_string(), Object::Builtin(string_contains));
+    string_methods.insert("startsWith".to_string(), Object::Builtin(string_starts_with));
+    string_methods.insert("endsWith".to_string(), Object::Builtin(string_ends_with));
+    string_methods.insert("indexOf".to_string(), Object::Builtin(string_index_of));
+    string_methods.insert("slice".to_string(), Object::Builtin(string_slice));
+    string_methods.insert("replace".to_string(), Object::Builtin(string_replace));
+    string_methods.insert("repeat".to_string(), Object::Builtin(string_repeat));
+    string_methods.insert("reverse".to_string(), Object::Builtin(string_reverse));
+    string_methods.insert("padLeft".to_string(), Object::Builtin(string_pad_left));
+    string_methods.insert("padRight".to_string(), Object::Builtin(string_pad_right));
+    string_methods.insert("chars".to_string(), Object::Builtin(string_chars));
+    string_methods.insert("charCodeAt".to_string(), Object::Builtin(string_char_code_at));
+    string_methods.insert("fromCharCode".to_string(), Object::Builtin(string_from_char_code));
+    string_methods.insert("fromCharCodes".to_string(), Object::Builtin(string_from_char_codes));
+    string_methods.insert("lastIndexOf".to_string(), Object::Builtin(string_last_index_of));
+    string_methods.insert("replaceAll".to_string(), Object::Builtin(string_replace_all));
+    string_methods.insert("charCodes".to_string(), Object::Builtin(string_char_codes));
+    string_methods.insert("isEmpty".to_string(), Object::Builtin(string_is_empty));
+    string_methods.insert("len".to_string(), Object::Builtin(string_len));
+    string_methods.insert("words".to_string(), Object::Builtin(string_words));
+    string_methods.insert("lines".to_string(), Object::Builtin(string_lines));
+    string_methods.insert("naturalCompare".to_string(), Object::Builtin(string_natural_compare));
+    string_methods.insert("levenshtein".to_string(), Object::Builtin(string_levenshtein));
+    string_methods.insert("toCamelCase".to_string(), Object::Builtin(string_to_camel_case));
+    string_methods.insert("toSnakeCase".to_string(), Object::Builtin(string_to_snake_case));
+    string_methods.insert("toKebabCase".to_string(), Object::Builtin(string_to_kebab_case));
+    string_methods.insert("toTitleCase".to_string(), Object::Builtin(string_to_title_case));
+    string_methods.insert("capitalize".to_string(), Object::Builtin(string_capitalize));
+    string_methods.insert("truncate".to_string(), Object::Builtin(string_truncate));
+    string_methods.insert("wrap".to_string(), Object::Builtin(string_wrap));
+    string_methods.insert("indent".to_string(), Object::Builtin(string_indent));
+    string_methods.insert("format".to_string(), Object::Builtin(string_format));
+    string_methods
+}
+
+// Json = { parse, stringify }
+fn json_namespace() -> ObjectMap {
+    let mut json_methods = ObjectMap::new();
+    json_methods.insert("parse".to_string(), Object::Builtin(json_parse));
+    json_methods.insert("stringify".to_string(), Object::Builtin(json_stringify));
+    json_methods
+}
+
+// Test = { assert, assertEq, assertNotEq, assertTrue, assertFalse, assertApproxEq, assertContains, assertThrows, assertMatch, assertSome, assertNone, assertOk, assertErr, assertSnapshot }
+fn test_namespace() -> ObjectMap {
+    let mut test_methods = ObjectMap::new();
+    test_methods.insert("assert".to_string(), Object::Builtin(test_assert));
+    test_methods.insert("assertEq".to_string(), Object::Builtin(test_assert_eq));
+    test_methods.insert("assertNotEq".to_string(), Object::Builtin(test_assert_not_eq));
+    test_methods.insert("assertTrue".to_string(), Object::Builtin(test_assert_true));
+    test_methods.insert("assertFalse".to_string(), Object::Builtin(test_assert_false));
+    test_methods.insert("assertApproxEq".to_string(), Object::Builtin(test_assert_approx_eq));
+    test_methods.insert("assertContains".to_string(), Object::Builtin(test_assert_contains));
+    test_methods.insert("assertThrows".to_string(), Object::Builtin(test_assert_throws));
+    #[cfg(feature = "regex")]
+    test_methods.insert("assertMatch".to_string(), Object::Builtin(test_assert_match));
+    test_methods.insert("assertSome".to_string(), Object::Builtin(test_assert_some));
+    test_methods.insert("assertNone".to_string(), Object::Builtin(test_assert_none));
+    test_methods.insert("assertOk".to_string(), Object::Builtin(test_assert_ok));
+    test_methods.insert("assertErr".to_string(), Object::Builtin(test_assert_err));
+    test_methods.insert("assertSnapshot".to_string(), Object::Builtin(test_assert_snapshot));
+    test_methods
+}
+
+// Object = { keys, values, entries, fromEntries, has, get, set, setMut, delete, merge, isEmpty, len, clone, freeze, isFrozen, map, mapValues, filter, pick, omit, mergeDeep }
+// (the legacy `Obj` spelling resolves here via crate::compat)
+fn object_namespace() -> ObjectMap {
+    let mut obj_methods = ObjectMap::new();
+    obj_methods.insert("keys".to_string(), Object::Builtin(object_keys));
+    obj_methods.insert("values".to_string(), Object::Builtin(object_values));
+    obj_methods.insert("entries".to_string(), Object::Builtin(object_entries));
+    obj_methods.insert("fromEntries".to_string(), Object::Builtin(object_from_entries));
+    obj_methods.insert("has".to_string(), Object::Builtin(object_has));
+    obj_methods.insert("get".to_string(), Object::Builtin(object_get));
+    obj_methods.insert("set".to_string(), Object::Builtin(object_set));
+    obj_methods.insert("setMut".to_string(), Object::Builtin(object_set_mut));
+    obj_methods.insert("delete".to_string(), Object::Builtin(object_delete));
+    obj_methods.insert("merge".to_string(), Object::Builtin(object_merge));
+    obj_methods.insert("isEmpty".to_string(), Object::Builtin(object_is_empty));
+    obj_methods.insert("len".to_string(), Object::Builtin(object_len));
+    obj_methods.insert("clone".to_string(), Object::Builtin(object_clone));
+    obj_methods.insert("freeze".to_string(), Object::Builtin(object_freeze));
+    obj_methods.insert("isFrozen".to_string(), Object::Builtin(object_is_frozen));
+    obj_methods.insert("map".to_string(), Object::Builtin(object_map));
+    obj_methods.insert("mapValues".to_string(), Object::Builtin(object_map_values));
+    obj_methods.insert("filter".to_string(), Object::Builtin(object_filter));
+    obj_methods.insert("pick".to_string(), Object::Builtin(object_pick));
+    obj_methods.insert("omit".to_string(), Object::Builtin(object_omit));
+    obj_methods.insert("mergeDeep".to_string(), Object::Builtin(object_merge_deep));
+    obj_methods
+}
+
+// Time = { now, nowSecs, sleep, year, month, day, hour, minute, second, dayOfWeek, format, toObject, fromComponents, add, diff, parse, toZone }
+fn time_namespace() -> ObjectMap {
+    let mut time_methods = ObjectMap::new();
+    time_methods.insert("now".to_string(), Object::Builtin(time_now));
+    time_methods.insert("nowSecs".to_string(), Object::Builtin(time_now_secs));
+    time_methods.insert("sleep".to_string(), Object::Builtin(time_sleep));
+    time_methods.insert("year".to_string(), Object::Builtin(time_year));
+    time_methods.insert("month".to_string(), Object::Builtin(time_month));
+    time_methods.insert("day".to_string(), Object::Builtin(time_day));
+    time_methods.insert("hour".to_string(), Object::Builtin(time_hour));
+    time_methods.insert("minute".to_string(), Object::Builtin(time_minute));
+    time_methods.insert("second".to_string(), Object::Builtin(time_second));
+    time_methods.insert("dayOfWeek".to_string(), Object::Builtin(time_day_of_week));
+    time_methods.insert("format".to_string(), Object::Builtin(time_format));
+    time_methods.insert("toObject".to_string(), Object::Builtin(time_to_object));
+    time_methods.insert("fromComponents".to_string(), Object::Builtin(time_from_components));
+    time_methods.insert("add".to_string(), Object::Builtin(time_add));
+    time_methods.insert("diff".to_string(), Object::Builtin(time_diff));
+    time_methods.insert("parse".to_string(), Object::Builtin(time_parse));
+    time_methods.insert("toZone".to_string(), Object::Builtin(time_to_zone));
+    time_methods
+}
+
+// Sys = { env, setEnv, args, exit, cwd, setCwd, exec, platform, arch, dryRun, version, hasNamespace, features }
+fn sys_namespace() -> ObjectMap {
+    let mut sys_methods = ObjectMap::new();
+    sys_methods.insert("env".to_string(), Object::Builtin(sys_env));
+    sys_methods.insert("setEnv".to_string(), Object::Builtin(sys_set_env));
+    sys_methods.insert("args".to_string(), Object::Builtin(sys_args));
+    sys_methods.insert("exit".to_string(), Object::Builtin(sys_exit));
+    sys_methods.insert("cwd".to_string(), Object::Builtin(sys_cwd));
+    sys_methods.insert("setCwd".to_string(), Object::Builtin(sys_set_cwd));
+    sys_methods.insert("exec".to_string(), Object::Builtin(sys_exec));
+    sys_methods.insert("platform".to_string(), Object::String(std::env::consts::OS.to_string()));
+    sys_methods.insert("arch".to_string(), Object::Builtin(sys_arch));
+    sys_methods.insert("dryRun".to_string(), Object::Builtin(sys_dry_run));
+    sys_methods.insert("version".to_string(), Object::Builtin(sys_version));
+    sys_methods.insert("hasNamespace".to_string(), Object::Builtin(sys_has_namespace));
+    sys_methods.insert("features".to_string(), Object::Builtin(sys_features));
+    sys_methods
+}
+
+// Process = { spawn, run, readStdout, readStderr, writeStdin, closeStdin, wait, kill }
+fn process_namespace() -> ObjectMap {
+    let mut process_methods = ObjectMap::new();
+    process_methods.insert("spawn".to_string(), Object::Builtin(process_spawn));
+    process_methods.insert("run".to_string(), Object::Builtin(process_run));
+    process_methods.insert("readStdout".to_string(), Object::Builtin(process_read_stdout));
+    process_methods.insert("readStderr".to_string(), Object::Builtin(process_read_stderr));
+    process_methods.insert("writeStdin".to_string(), Object::Builtin(process_write_stdin));
+    process_methods.insert("closeStdin".to_string(), Object::Builtin(process_close_stdin));
+    process_methods.insert("wait".to_string(), Object::Builtin(process_wait));
+    process_methods.insert("kill".to_string(), Object::Builtin(process_kill));
+    process_methods
+}
+
+// HTTP = { get, post, put, delete, patch, head }
+#[cfg(feature = "http")]
+fn http_namespace() -> ObjectMap {
+    let mut http_methods = ObjectMap::new();
+    http_methods.insert("get".to_string(), Object::Builtin(http_get));
+    http_methods.insert("post".to_string(), Object::Builtin(http_post));
+    http_methods.insert("put".to_string(), Object::Builtin(http_put));
+    http_methods.insert("delete".to_string(), Object::Builtin(http_delete));
+    http_methods.insert("patch".to_string(), Object::Builtin(http_patch));
+    http_methods.insert("head".to_string(), Object::Builtin(http_head));
+    http_methods
+}
+
+// HttpServer = { listen }
+#[cfg(feature = "http")]
+fn http_server_namespace() -> ObjectMap {
+    let mut server_methods = ObjectMap::new();
+    server_methods.insert("listen".to_string(), Object::Builtin(http_server_listen));
+    server_methods
+}
+
+// Fn = { identity, constant, compose, pipe, apply, call, negate, flip, partial, isCallable }
+fn fn_namespace() -> ObjectMap {
+    let mut fn_methods = ObjectMap::new();
+    fn_methods.insert("identity".to_string(), Object::Builtin(fn_identity));
+    fn_methods.insert("constant".to_string(), Object::Builtin(fn_constant));
+    fn_methods.insert("compose".to_string(), Object::Builtin(fn_compose));
+    fn_methods.insert("pipe".to_string(), Object::Builtin(fn_pipe));
+    fn_methods.insert("apply".to_string(), Object::Builtin(fn_apply));
+    fn_methods.insert("call".to_string(), Object::Builtin(fn_call));
+    fn_methods.insert("negate".to_string(), Object::Builtin(fn_negate));
+    fn_methods.insert("flip".to_string(), Object::Builtin(fn_flip));
+    fn_methods.insert("partial".to_string(), Object::Builtin(fn_partial));
+    fn_methods.insert("isCallable".to_string(), Object::Builtin(fn_is_callable));
+    fn_methods
+}
+
+// Number = { parse }
+fn number_namespace() -> ObjectMap {
+    let mut number_methods = ObjectMap::new();
+    number_methods.insert("parse".to_string(), Object::Builtin(number_parse));
+    number_methods
+}
+
+// Timer = { after, every, cancel }
+fn timer_namespace() -> ObjectMap {
+    let mut timer_methods = ObjectMap::new();
+    timer_methods.insert("after".to_string(), Object::Builtin(timer_after));
+    timer_methods.insert("every".to_string(), Object::Builtin(timer_every));
+    timer_methods.insert("cancel".to_string(), Object::Builtin(timer_cancel));
+    timer_methods
+}
+
+// Events = { subscribe, publish, run, pending }
+fn events_namespace() -> ObjectMap {
+    let mut events_methods = ObjectMap::new();
+    events_methods.insert("subscribe".to_string(), Object::Builtin(events_subscribe));
+    events_methods.insert("publish".to_string(), Object::Builtin(events_publish));
+    events_methods.insert("run".to_string(), Object::Builtin(events_run));
+    events_methods.insert("pending".to_string(), Object::Builtin(events_pending));
+    events_methods
+}
+
+// Task = { spawn, join, isDone, sleep }
+fn task_namespace() -> ObjectMap {
+    let mut task_methods = ObjectMap::new();
+    task_methods.insert("spawn".to_string(), Object::Builtin(task_spawn));
+    task_methods.insert("join".to_string(), Object::Builtin(task_join));
+    task_methods.insert("isDone".to_string(), Object::Builtin(task_is_done));
+    task_methods.insert("sleep".to_string(), Object::Builtin(time_sleep));
+    task_methods
+}
+
+// Channel = { new, send, recv, tryRecv, close }
+fn channel_namespace() -> ObjectMap {
+    let mut channel_methods = ObjectMap::new();
+    channel_methods.insert("new".to_string(), Object::Builtin(channel_new));
+    channel_methods.insert("send".to_string(), Object::Builtin(channel_send));
+    channel_methods.insert("recv".to_string(), Object::Builtin(channel_recv));
+    channel_methods.insert("tryRecv".to_string(), Object::Builtin(channel_try_recv));
+    channel_methods.insert("close".to_string(), Object::Builtin(channel_close));
+    channel_methods
+}
+
+// Runtime = { run, onShutdown }
+fn runtime_namespace() -> ObjectMap {
+    let mut runtime_methods = ObjectMap::new();
+    runtime_methods.insert("run".to_string(), Object::Builtin(runtime_run));
+    runtime_methods.insert("onShutdown".to_string(), Object::Builtin(runtime_on_shutdown));
+    runtime_methods
+}
+
+// Schema = { string, int, float, bool, any, array, object, optional, validate, coerce }
+fn schema_namespace() -> ObjectMap {
+    let mut schema_methods = ObjectMap::new();
+    schema_methods.insert("string".to_string(), Object::Builtin(schema_string));
+    schema_methods.insert("int".to_string(), Object::Builtin(schema_int));
+    schema_methods.insert("float".to_string(), Object::Builtin(schema_float));
+    schema_methods.insert("bool".to_string(), Object::Builtin(schema_bool));
+    schema_methods.insert("any".to_string(), Object::Builtin(schema_any));
+    schema_methods.insert("array".to_string(), Object::Builtin(schema_array));
+    schema_methods.insert("object".to_string(), Object::Builtin(schema_object));
+    schema_methods.insert("optional".to_string(), Object::Builtin(schema_optional));
+    schema_methods.insert("validate".to_string(), Object::Builtin(schema_validate));
+    schema_methods.insert("coerce".to_string(), Object::Builtin(schema_coerce));
+    schema_methods
+}
+
+// Bloom = { new, add, mightContain }
+fn bloom_namespace() -> ObjectMap {
+    let mut bloom_methods = ObjectMap::new();
+    bloom_methods.insert("new".to_string(), Object::Builtin(bloom_new));
+    bloom_methods.insert("add".to_string(), Object::Builtin(bloom_add));
+    bloom_methods.insert("mightContain".to_string(), Object::Builtin(bloom_might_contain));
+    bloom_methods
+}
+
+// Ref = { new, get, set }
+fn ref_namespace() -> ObjectMap {
+    let mut ref_methods = ObjectMap::new();
+    ref_methods.insert("new".to_string(), Object::Builtin(ref_new));
+    ref_methods.insert("get".to_string(), Object::Builtin(ref_get));
+    ref_methods.insert("set".to_string(), Object::Builtin(ref_set));
+    ref_methods
+}
+
+// Seq = { from, fromArray, range, map, filter, take, drop, takeWhile, dropWhile, skipWhile, enumerate, toArray, collect }
+// Also bound as `Iter`, for readers coming from iterator-style APIs.
+fn seq_namespace() -> ObjectMap {
+    let mut seq_methods = ObjectMap::new();
+    seq_methods.insert("from".to_string(), Object::Builtin(seq_from));
+    seq_methods.insert("fromArray".to_string(), Object::Builtin(seq_from));
+    seq_methods.insert("range".to_string(), Object::Builtin(seq_range));
+    seq_methods.insert("map".to_string(), Object::Builtin(seq_map));
+    seq_methods.insert("filter".to_string(), Object::Builtin(seq_filter));
+    seq_methods.insert("take".to_string(), Object::Builtin(seq_take));
+    seq_methods.insert("drop".to_string(), Object::Builtin(seq_drop));
+    seq_methods.insert("takeWhile".to_string(), Object::Builtin(seq_take_while));
+    seq_methods.insert("dropWhile".to_string(), Object::Builtin(seq_drop_while));
+    seq_methods.insert("skipWhile".to_string(), Object::Builtin(seq_drop_while));
+    seq_methods.insert("enumerate".to_string(), Object::Builtin(seq_enumerate));
+    seq_methods.insert("toArray".to_string(), Object::Builtin(seq_to_array));
+    seq_methods.insert("collect".to_string(), Object::Builtin(seq_to_array));
+    seq_methods
+}
+
+// Dir = { list, create, remove, exists, walk }
+fn dir_namespace() -> ObjectMap {
+    let mut dir_methods = ObjectMap::new();
+    dir_methods.insert("list".to_string(), Object::Builtin(dir_list));
+    dir_methods.insert("create".to_string(), Object::Builtin(dir_create));
+    dir_methods.insert("remove".to_string(), Object::Builtin(dir_remove));
+    dir_methods.insert("exists".to_string(), Object::Builtin(dir_exists));
+    dir_methods.insert("walk".to_string(), Object::Builtin(dir_walk));
+    dir_methods
+}
+
+// Path = { join, basename, dirname, extension, absolute }
+fn path_namespace() -> ObjectMap {
+    let mut path_methods = ObjectMap::new();
+    path_methods.insert("join".to_string(), Object::Builtin(path_join));
+    path_methods.insert("basename".to_string(), Object::Builtin(path_basename));
+    path_methods.insert("dirname".to_string(), Object::Builtin(path_dirname));
+    path_methods.insert("extension".to_string(), Object::Builtin(path_extension));
+    path_methods.insert("absolute".to_string(), Object::Builtin(path_absolute));
+    path_methods
+}
+
+// Bytes = { fromString, toString, len, slice, concat, fromArray, toArray }
+fn bytes_namespace() -> ObjectMap {
+    let mut bytes_methods = ObjectMap::new();
+    bytes_methods.insert("fromString".to_string(), Object::Builtin(bytes_from_string));
+    bytes_methods.insert("toString".to_string(), Object::Builtin(bytes_to_string));
+    bytes_methods.insert("len".to_string(), Object::Builtin(bytes_len));
+    bytes_methods.insert("slice".to_string(), Object::Builtin(bytes_slice));
+    bytes_methods.insert("concat".to_string(), Object::Builtin(bytes_concat));
+    bytes_methods.insert("fromArray".to_string(), Object::Builtin(bytes_from_array));
+    bytes_methods.insert("toArray".to_string(), Object::Builtin(bytes_to_array));
+    bytes_methods
+}
+
+// Uuid = { v4, v7 }
+fn uuid_namespace() -> ObjectMap {
+    let mut uuid_methods = ObjectMap::new();
+    uuid_methods.insert("v4".to_string(), Object::Builtin(uuid_v4));
+    uuid_methods.insert("v7".to_string(), Object::Builtin(uuid_v7));
+    uuid_methods
+}
+
+// Debug = { inspect, pretty }
+fn debug_namespace() -> ObjectMap {
+    let mut debug_methods = ObjectMap::new();
+    debug_methods.insert("inspect".to_string(), Object::Builtin(debug_inspect));
+    debug_methods.insert("pretty".to_string(), Object::Builtin(debug_pretty));
+    debug_methods
+}
+
+// Log = { debug, info, warn, error, setLevel, level, enabled, setFormat }
+fn log_namespace() -> ObjectMap {
+    let mut log_methods = ObjectMap::new();
+    log_methods.insert("debug".to_string(), Object::Builtin(log_debug));
+    log_methods.insert("info".to_string(), Object::Builtin(log_info));
+    log_methods.insert("warn".to_string(), Object::Builtin(log_warn));
+    log_methods.insert("error".to_string(), Object::Builtin(log_error));
+    log_methods.insert("setLevel".to_string(), Object::Builtin(log_set_level));
+    log_methods.insert("level".to_string(), Object::Builtin(log_level));
+    log_methods.insert("enabled".to_string(), Object::Builtin(log_enabled));
+    log_methods.insert("setFormat".to_string(), Object::Builtin(log_set_format));
+    log_methods
+}
+
+// Yaml = { parse, stringify }
+#[cfg(feature = "formats")]
+fn yaml_namespace() -> ObjectMap {
+    let mut yaml_methods = ObjectMap::new();
+    yaml_methods.insert("parse".to_string(), Object::Builtin(yaml_parse));
+    yaml_methods.insert("stringify".to_string(), Object::Builtin(yaml_stringify));
+    yaml_methods
+}
+
+// Toml = { parse, stringify }
+#[cfg(feature = "formats")]
+fn toml_namespace() -> ObjectMap {
+    let mut toml_methods = ObjectMap::new();
+    toml_methods.insert("parse".to_string(), Object::Builtin(toml_parse));
+    toml_methods.insert("stringify".to_string(), Object::Builtin(toml_stringify));
+    toml_methods
+}
+
+// Encoding = { base64Encode, base64Decode, hexEncode, hexDecode, urlEncode, urlDecode }
+#[cfg(feature = "encoding")]
+fn encoding_namespace() -> ObjectMap {
+    let mut encoding_methods = ObjectMap::new();
+    encoding_methods.insert("base64Encode".to_string(), Object::Builtin(encoding_base64_encode));
+    encoding_methods.insert("base64Decode".to_string(), Object::Builtin(encoding_base64_decode));
+    encoding_methods.insert("hexEncode".to_string(), Object::Builtin(encoding_hex_encode));
+    encoding_methods.insert("hexDecode".to_string(), Object::Builtin(encoding_hex_decode));
+    encoding_methods.insert("urlEncode".to_string(), Object::Builtin(encoding_url_encode));
+    encoding_methods.insert("urlDecode".to_string(), Object::Builtin(encoding_url_decode));
+    encoding_methods
+}
+
+// Crypto = { md5, sha1, sha256, hmacSha256, randomBytes }
+#[cfg(feature = "crypto")]
+fn crypto_namespace() -> ObjectMap {
+    let mut crypto_methods = ObjectMap::new();
+    crypto_methods.insert("md5".to_string(), Object::Builtin(crypto_md5));
+    crypto_methods.insert("sha1".to_string(), Object::Builtin(crypto_sha1));
+    crypto_methods.insert("sha256".to_string(), Object::Builtin(crypto_sha256));
+    crypto_methods.insert("hmacSha256".to_string(), Object::Builtin(crypto_hmac_sha256));
+    crypto_methods.insert("randomBytes".to_string(), Object::Builtin(crypto_random_bytes));
+    crypto_methods
 }
 
 /// Create a new environment enclosed within an existing outer environment.
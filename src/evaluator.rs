@@ -1,3 +1,3 @@
 pub mod core;
 
-pub use core::eval;
\ No newline at end of file
+pub use core::{EvalAbort, EvalOptions, eval, eval_with_options};
\ No newline at end of file
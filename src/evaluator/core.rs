@@ -1,21 +1,43 @@
 use std::rc::Rc;
 
 use crate::ast::Program;
+#[cfg(feature = "crash-report")]
+use crate::crash::core::EvalFrame;
+use crate::debug::debugger;
 use crate::env::EnvRef;
 use crate::object::Object;
 
+pub(crate) mod budget;
 pub mod expr;
+pub(crate) mod generator;
+mod propagate;
+mod resolve;
 mod stmt;
 
+pub use budget::{EvalAbort, EvalOptions};
+
 use stmt::eval_statement;
 
 /// Entry point: evaluate a whole program
 pub fn eval(program: &Program, env: EnvRef) -> Object {
+    resolve::resolve(program);
+    #[cfg(feature = "crash-report")]
+    let frame = EvalFrame::enter();
     let mut result = Object::Null;
 
-    for stmt in &program.statements {
+    for (index, stmt) in program.statements.iter().enumerate() {
+        #[cfg(feature = "crash-report")]
+        frame.at_statement(index);
+        if debugger::is_active()
+            && let Some(quit) = debugger::before_statement(stmt, program.lines.get(index).copied(), &env)
+        {
+            return quit;
+        }
         result = eval_statement(stmt, Rc::clone(&env));
 
+        if let Some(value) = propagate::take() {
+            return Object::error(format!("? returned {} outside of a function", value));
+        }
         if let Object::ReturnValue(val) = result {
             return *val;
         }
@@ -27,5 +49,13 @@ pub fn eval(program: &Program, env: EnvRef) -> Object {
     result
 }
 
+/// Evaluate `program` like [`eval`], but stop once `options` runs out.
+///
+/// An abort is reported as `Err` so embedders can tell it apart from an
+/// error raised by the script itself, which comes back as `Ok(Object::Error)`.
+pub fn eval_with_options(program: &Program, env: EnvRef, options: EvalOptions) -> Result<Object, EvalAbort> {
+    budget::with_budget(options, || eval(program, env))
+}
+
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,131 @@
+//! Step and time limits for [`eval_with_options`](super::eval_with_options).
+//!
+//! The evaluator charges one step per statement and per loop iteration. Once
+//! a limit is hit the budget stays exhausted, so every later charge fails too
+//! and the abort can't be swallowed by script code that ignores errors.
+//!
+//! A single statement can block for a long time inside a builtin, so the
+//! builtins that wait (`Time::sleep`, the timer loop, `HttpServer::listen`)
+//! sleep through [`sleep`], which stops at the deadline.
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::object::Object;
+
+/// Limits for evaluating untrusted code. `None` means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EvalOptions {
+    pub max_steps: Option<u64>,
+    pub timeout: Option<Duration>,
+}
+
+/// Why an evaluation was stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvalAbort {
+    StepLimit(u64),
+    Timeout(Duration),
+}
+
+impl fmt::Display for EvalAbort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalAbort::StepLimit(steps) => {
+                write!(f, "evaluation aborted: step limit of {} exceeded", steps)
+            }
+            EvalAbort::Timeout(limit) => {
+                write!(f, "evaluation aborted: timeout of {:?} exceeded", limit)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EvalAbort {}
+
+struct Budget {
+    options: EvalOptions,
+    deadline: Option<Instant>,
+    steps: u64,
+    aborted: Option<EvalAbort>,
+}
+
+thread_local! {
+    static ACTIVE: Cell<bool> = const { Cell::new(false) };
+    static BUDGET: RefCell<Option<Budget>> = const { RefCell::new(None) };
+}
+
+/// Run `f` under `options`, restoring any enclosing budget afterwards.
+pub(super) fn with_budget(options: EvalOptions, f: impl FnOnce() -> Object) -> Result<Object, EvalAbort> {
+    let budget = Budget {
+        options,
+        deadline: options.timeout.map(|t| Instant::now() + t),
+        steps: 0,
+        aborted: None,
+    };
+    let previous = BUDGET.with(|b| b.borrow_mut().replace(budget));
+    let was_active = ACTIVE.with(|a| a.replace(true));
+
+    let result = f();
+
+    let finished = BUDGET.with(|b| std::mem::replace(&mut *b.borrow_mut(), previous));
+    ACTIVE.with(|a| a.set(was_active));
+    match finished.and_then(|b| b.aborted) {
+        Some(abort) => Err(abort),
+        None => Ok(result),
+    }
+}
+
+/// Count one step, returning the abort error once the budget is spent.
+pub(super) fn charge() -> Option<Object> {
+    if !ACTIVE.with(|a| a.get()) {
+        return None;
+    }
+    BUDGET.with(|b| {
+        let mut b = b.borrow_mut();
+        let budget = b.as_mut()?;
+        if budget.aborted.is_none() {
+            budget.steps += 1;
+            if let Some(max) = budget.options.max_steps && budget.steps > max {
+                budget.aborted = Some(EvalAbort::StepLimit(max));
+            }
+            budget.check_clock();
+        }
+        budget.aborted.map(|abort| Object::error(abort.to_string()))
+    })
+}
+
+/// Sleep for `duration`, or only until the deadline if that comes first.
+/// Returns the abort error once the deadline has passed, so a blocking
+/// builtin gives up instead of outliving the timeout.
+pub(crate) fn sleep(duration: Duration) -> Option<Object> {
+    let deadline = if ACTIVE.with(|a| a.get()) {
+        BUDGET.with(|b| b.borrow().as_ref().and_then(|budget| budget.deadline))
+    } else {
+        None
+    };
+    let Some(deadline) = deadline else {
+        thread::sleep(duration);
+        return None;
+    };
+    thread::sleep(duration.min(deadline.saturating_duration_since(Instant::now())));
+    BUDGET.with(|b| {
+        let mut b = b.borrow_mut();
+        let budget = b.as_mut()?;
+        if budget.aborted.is_none() {
+            budget.check_clock();
+        }
+        budget.aborted.map(|abort| Object::error(abort.to_string()))
+    })
+}
+
+impl Budget {
+    fn check_clock(&mut self) {
+        if let (Some(deadline), Some(limit)) = (self.deadline, self.options.timeout)
+            && Instant::now() >= deadline
+        {
+            self.aborted = Some(EvalAbort::Timeout(limit));
+        }
+    }
+}
@@ -1,27 +1,39 @@
 use std::rc::Rc;
+use std::sync::Arc;
 
 use crate::ast::nodes::{
-    InfixOp, NewExpression, ObjectLiteral, PostfixExpression, PostfixOp, PrefixExpression,
+    BuiltinRef, InfixOp, NewExpression, ObjectLiteral, PostfixExpression, PostfixOp, PrefixExpression,
     PrefixOp, PropertyAccess, PublishExpression,
 };
 use crate::ast::{
-    ArrayLiteral, CallExpression, Expression, FunctionLiteral, Identifier, IndexExpression,
+    ArrayLiteral, BlockStatement, CallExpression, Expression, FunctionLiteral, Identifier, IndexExpression,
     InfixExpression,
 };
-use crate::env::{new_enclosed_env, subscribers_for_tag, EnvRef};
-use crate::object::Object;
-use crate::{builtins, debug_log};
-
+use crate::capability::Capabilities;
+use crate::env::{new_enclosed_env, subscribers_for_tag, EnvRef, Environment};
+use crate::debug::debugger;
+use crate::object::{FunctionData, Object, ObjectMap};
+use crate::object::types::SeqNode;
+use crate::formatter::format_expression;
+use crate::{builtins, compat, debug_log};
+
+use super::propagate;
 use super::stmt::eval_if_expression;
 
 /// Evaluate an expression node.
 pub(super) fn eval_expression(expr: &Expression, env: EnvRef) -> Object {
+    // A `?` is leaving the function: nothing else in it runs.
+    if propagate::pending() {
+        return Object::Null;
+    }
     match expr {
         Expression::Identifier(ident) => eval_identifier(ident, env),
         Expression::IntegerLiteral(il) => Object::Integer(il.value),
         Expression::FloatLiteral(fl) => Object::Float(fl.value),
         Expression::BooleanLiteral(bl) => Object::Boolean(bl.value),
         Expression::StringLiteral(sl) => Object::String(sl.value.clone()),
+        Expression::BytesLiteral(bl) => Object::bytes(bl.value.clone()),
+        Expression::NullLiteral => Object::Null,
         Expression::Infix(infix) => eval_infix_expression(infix, env),
         Expression::If(ifexpr) => eval_if_expression(ifexpr, env),
         Expression::Prefix(p) => eval_prefix_expression(p, env),
@@ -34,18 +46,74 @@ pub(super) fn eval_expression(expr: &Expression, env: EnvRef) -> Object {
         Expression::PropertyAccess(pa) => eval_property_access(pa, env),
         Expression::Publish(pubexpr) => eval_publish_expression(pubexpr, env),
         Expression::New(new_expr) => eval_new_expression(new_expr, env),
+        Expression::Spread(_) => {
+            Object::error("spread (...) is only allowed in call arguments and array literals")
+        }
+        Expression::Builtin(builtin) => eval_builtin_ref(builtin, env),
+    }
+}
+
+/// A member the optimizer looked up ahead of time. Sandboxed environments
+/// look it up again so their restrictions apply, as do compiled scripts,
+/// which don't carry the function.
+fn eval_builtin_ref(builtin: &BuiltinRef, env: EnvRef) -> Object {
+    if let Some(func) = builtin.func && env.borrow().capabilities() == Capabilities::default() {
+        return Object::Builtin(func);
+    }
+    let namespace = Identifier::new(builtin.namespace.clone());
+    match eval_identifier(&namespace, env) {
+        Object::Object(map) => map.get(&builtin.member).cloned().unwrap_or(Object::Null),
+        err @ Object::Error(_) => err,
+        other => Object::error(format!("property access not supported on value: {:?}", other)),
+    }
+}
+
+/// Evaluate call arguments or array elements left to right, expanding any
+/// `...spread` into the array's elements.
+fn eval_expression_list(exprs: &[Expression], env: &EnvRef) -> Result<Vec<Object>, Object> {
+    let mut values = Vec::with_capacity(exprs.len());
+    for expr in exprs {
+        match expr {
+            Expression::Spread(spread) => match eval_expression(&spread.value, Rc::clone(env)) {
+                Object::Array(items) => values.extend(items.iter().cloned()),
+                err @ Object::Error(_) => return Err(err),
+                other => {
+                    return Err(Object::error(format!(
+                        "cannot spread {}, expected an array",
+                        other.type_name()
+                    )))
+                }
+            },
+            _ => values.push(eval_expression(expr, Rc::clone(env))),
+        }
     }
+    Ok(values)
 }
 
-fn eval_identifier(ident: &Identifier, env: EnvRef) -> Object {
+pub(super) fn eval_identifier(ident: &Identifier, env: EnvRef) -> Object {
     debug_log!("eval_identifier: looking up '{}'", ident.value);
 
     let env_borrow = env.borrow();
-    if let Some(val) = env_borrow.get(&ident.value) {
+    if let Some(val) = env_borrow.get_resolved(&ident.value, ident.binding.get()) {
         debug_log!("  found in env: {:?}", val);
         return val;
     }
 
+    drop(env_borrow);
+    if let Some(val) = compat::resolve_legacy(&ident.value, &env) {
+        debug_log!("  resolved as legacy alias");
+        return val;
+    }
+
+    let capabilities = env.borrow().capabilities();
+    if let Some(group) = capabilities.denied_group(&ident.value) {
+        return Object::error(format!(
+            "{} is not available: {} is disabled in this environment",
+            ident.value,
+            group.description()
+        ));
+    }
+
     if let Some(builtin_fn) = builtins::get(&ident.value) {
         debug_log!("  resolved as builtin");
         return Object::Builtin(builtin_fn);
@@ -56,35 +124,25 @@ fn eval_identifier(ident: &Identifier, env: EnvRef) -> Object {
 }
 
 fn eval_infix_expression(infix: &InfixExpression, env: EnvRef) -> Object {
-    let left = eval_expression(&infix.left, Rc::clone(&env));
-    let right = eval_expression(&infix.right, Rc::clone(&env));
-
     use InfixOp::*;
 
     match infix.operator {
         Assign => {
             // Simple variable assignment: `x = expr`
-            if let Expression::Identifier(Identifier { value: name }) = &*infix.left {
+            if let Expression::Identifier(Identifier { value: name, .. }) = &*infix.left {
+                if let Err(msg) = check_not_const(name, &env) {
+                    return Object::error(msg);
+                }
                 let value = eval_expression(&infix.right, Rc::clone(&env));
                 env.borrow_mut().set(name.clone(), value.clone());
                 return value;
             }
 
-            // Object property assignment: `obj.field = expr` or nested `obj.a.b = expr`
-            if let Expression::PropertyAccess(pa) = &*infix.left {
-                let value = eval_expression(&infix.right, Rc::clone(&env));
-                let result = assign_to_property_access(pa, Rc::clone(&env), value.clone());
-                return match result {
-                    Ok(()) => value,
-                    Err(msg) => Object::error(msg),
-                };
-            }
-
-            // Object index assignment: `obj["field"] = expr` or nested `obj["a"]["b"] = expr`
-            if let Expression::IndexExpression(_) = &*infix.left {
+            // Property / index assignment: `obj.a.b = expr`, `obj["k"] = expr`,
+            // `arr[0] = expr`, `matrix[i][j] = expr`, ...
+            if let Expression::PropertyAccess(_) | Expression::IndexExpression(_) = &*infix.left {
                 let value = eval_expression(&infix.right, Rc::clone(&env));
-                let result =
-                    assign_to_index_expression(&infix.left, Rc::clone(&env), value.clone());
+                let result = assign_to_path(&infix.left, Rc::clone(&env), value.clone());
                 return match result {
                     Ok(()) => value,
                     Err(msg) => Object::error(msg),
@@ -113,9 +171,46 @@ fn eval_infix_expression(infix: &InfixExpression, env: EnvRef) -> Object {
             let right = eval_expression(&infix.right, Rc::clone(&env));
             return Object::Boolean(is_truthy(&right));
         }
+        Coalesce => {
+            let left = eval_expression(&infix.left, Rc::clone(&env));
+            if left != Object::Null {
+                return left;
+            }
+            return eval_expression(&infix.right, Rc::clone(&env));
+        }
+        Pipeline => {
+            let left = eval_expression(&infix.left, Rc::clone(&env));
+            if left.is_error() {
+                return left;
+            }
+            // `x |> f(a, b)` is `f(x, a, b)`; anything else on the right is
+            // called with `x` alone.
+            return match &*infix.right {
+                Expression::CallExpression(call) => {
+                    let mut args = vec![left];
+                    match eval_expression_list(&call.arguments, &env) {
+                        Ok(rest) => args.extend(rest),
+                        Err(err) => return err,
+                    }
+                    call_with_args(&call.function, args, env)
+                }
+                function => call_with_args(function, vec![left], env),
+            };
+        }
         _ => {}
     }
 
+    // Assignment and the logical operators evaluate their own operands above,
+    // so side effects on the right-hand side only happen once.
+    let left = eval_expression(&infix.left, Rc::clone(&env));
+    let right = eval_expression(&infix.right, Rc::clone(&env));
+
+    if matches!(infix.operator, Equals | NotEquals) && (is_structured(&left) || is_structured(&right))
+        && !left.is_error() && !right.is_error()
+    {
+        return Object::Boolean(structurally_equal(&left, &right) == (infix.operator == Equals));
+    }
+
     match (left, right) {
         (Object::Integer(l), Object::Integer(r)) => eval_integer_infix(&infix.operator, l, r),
         (Object::Float(l), Object::Float(r)) => eval_float_infix(&infix.operator, l, r),
@@ -126,18 +221,84 @@ fn eval_infix_expression(infix: &InfixExpression, env: EnvRef) -> Object {
 
         (Object::Boolean(l), Object::Boolean(r)) => eval_boolean_infix(&infix.operator, l, r),
         (Object::String(l), Object::String(r)) => eval_string_infix(&infix.operator, &l, &r),
+        (Object::Bytes(l), Object::Bytes(r)) => eval_bytes_infix(&infix.operator, l, &r),
         (l, r) => Object::error(format!("type mismatch: {:?} {} {:?}", l, infix.operator, r)),
     }
 }
 
+/// Values `==` compares by structure rather than by a per-type operator.
+fn is_structured(value: &Object) -> bool {
+    matches!(
+        value,
+        Object::Array(_)
+            | Object::Object(_)
+            | Object::OptionSome(_)
+            | Object::OptionNone
+            | Object::ResultOk(_)
+            | Object::ResultErr(_)
+            | Object::Null
+    )
+}
+
+/// `==` for arrays, objects, Options, Results and `null`: equal when they have
+/// the same shape and their contents are `==`, so `[1] == [1.0]` like
+/// `1 == 1.0`. Object key order doesn't matter. Values of different kinds are
+/// simply unequal.
+fn structurally_equal(left: &Object, right: &Object) -> bool {
+    match (left, right) {
+        (Object::Integer(l), Object::Float(r)) | (Object::Float(r), Object::Integer(l)) => *l as f64 == *r,
+        (Object::Array(l), Object::Array(r)) => {
+            l.len() == r.len() && l.iter().zip(r.iter()).all(|(a, b)| structurally_equal(a, b))
+        }
+        (Object::Object(l), Object::Object(r)) => {
+            l.len() == r.len()
+                && l.iter().all(|(key, a)| r.get(key).is_some_and(|b| structurally_equal(a, b)))
+        }
+        (Object::OptionSome(l), Object::OptionSome(r))
+        | (Object::ResultOk(l), Object::ResultOk(r))
+        | (Object::ResultErr(l), Object::ResultErr(r)) => structurally_equal(l, r),
+        _ => left == right,
+    }
+}
+
 fn eval_integer_infix(op: &InfixOp, left: i64, right: i64) -> Object {
     use InfixOp::*;
     match op {
-        Plus => Object::Integer(left + right),
-        Minus => Object::Integer(left - right),
-        Multiply => Object::Integer(left * right),
+        Plus | Minus | Multiply => {
+            let value = match op {
+                Plus => left.checked_add(right),
+                Minus => left.checked_sub(right),
+                _ => left.checked_mul(right),
+            };
+            match value {
+                Some(value) => Object::Integer(value),
+                None => Object::error(format!("integer overflow in {} {} {}", left, op, right)),
+            }
+        }
         Divide => Object::Float(left as f64 / right as f64),
         Modulo => Object::Float(left as f64 % right as f64),
+        // A negative exponent can't stay an integer, so it falls back to floats.
+        Power if right < 0 => Object::Float((left as f64).powf(right as f64)),
+        Power => match u32::try_from(right).ok().and_then(|exp| left.checked_pow(exp)) {
+            Some(value) => Object::Integer(value),
+            None => Object::error(format!("integer overflow in {} ** {}", left, right)),
+        },
+        IntDivide => match left.checked_div(right) {
+            // Round toward negative infinity, so `-7 div 2` is -4.
+            Some(q) if left % right != 0 && (left < 0) != (right < 0) => Object::Integer(q - 1),
+            Some(q) => Object::Integer(q),
+            None if right == 0 => Object::error("integer division by zero"),
+            None => Object::error("integer overflow in div"),
+        },
+        BitAnd => Object::Integer(left & right),
+        BitOr => Object::Integer(left | right),
+        BitXor => Object::Integer(left ^ right),
+        ShiftLeft | ShiftRight if !(0..64).contains(&right) => Object::error(format!(
+            "shift amount must be between 0 and 63, got {}",
+            right
+        )),
+        ShiftLeft => Object::Integer(left << right),
+        ShiftRight => Object::Integer(left >> right),
 
         LessThan => Object::Boolean(left < right),
         LessEqual => Object::Boolean(left <= right),
@@ -157,6 +318,8 @@ fn eval_float_infix(op: &InfixOp, left: f64, right: f64) -> Object {
         Multiply => Object::Float(left * right),
         Divide => Object::Float(left / right),
         Modulo => Object::Float(left % right),
+        Power => Object::Float(left.powf(right)),
+        IntDivide => Object::Float((left / right).floor()),
 
         LessThan => Object::Boolean(left < right),
         LessEqual => Object::Boolean(left <= right),
@@ -189,6 +352,11 @@ fn eval_prefix_expression(pe: &PrefixExpression, env: EnvRef) -> Object {
             let right = eval_expression(&pe.right, Rc::clone(&env));
             eval_minus_prefix(right)
         }
+        BitNot => match eval_expression(&pe.right, Rc::clone(&env)) {
+            Object::Integer(i) => Object::Integer(!i),
+            err @ Object::Error(_) => err,
+            other => Object::error(format!("unknown operator: ~{}", other.type_name())),
+        },
         PreIncrement => eval_inc_dec_expression(&pe.right, Rc::clone(&env), true, true),
         PreDecrement => eval_inc_dec_expression(&pe.right, Rc::clone(&env), false, true),
     }
@@ -198,6 +366,21 @@ fn eval_postfix_expression(pe: &PostfixExpression, env: EnvRef) -> Object {
     match pe.operator {
         PostfixOp::Increment => eval_inc_dec_expression(&pe.left, env, true, false),
         PostfixOp::Decrement => eval_inc_dec_expression(&pe.left, env, false, false),
+        PostfixOp::Try => eval_try_expression(&pe.left, env),
+    }
+}
+
+/// `x?`: the value inside a `Some` or `Ok`, or return the `None` or `Err`
+/// from the enclosing function.
+fn eval_try_expression(left: &Expression, env: EnvRef) -> Object {
+    match eval_expression(left, env) {
+        Object::OptionSome(inner) | Object::ResultOk(inner) => *inner,
+        value @ (Object::OptionNone | Object::ResultErr(_)) => {
+            propagate::start(value);
+            Object::Null
+        }
+        err @ Object::Error(_) => err,
+        other => Object::error(format!("? expects an Option or Result, got {}", other.type_name())),
     }
 }
 
@@ -211,7 +394,10 @@ fn eval_bang_operator(obj: Object) -> Object {
 
 fn eval_minus_prefix(obj: Object) -> Object {
     match obj {
-        Object::Integer(i) => Object::Integer(-i),
+        Object::Integer(i) => match i.checked_neg() {
+            Some(value) => Object::Integer(value),
+            None => Object::error(format!("integer overflow in -({})", i)),
+        },
         Object::Float(f) => Object::Float(-f),
         _ => Object::Null,
     }
@@ -227,8 +413,8 @@ fn eval_inc_dec_expression(
         Expression::Identifier(ident) => {
             apply_inc_dec_to_identifier(ident, env, is_increment, is_prefix)
         }
-        Expression::PropertyAccess(pa) => {
-            apply_inc_dec_to_property(pa, env, is_increment, is_prefix)
+        Expression::PropertyAccess(_) | Expression::IndexExpression(_) => {
+            apply_inc_dec_to_path(target, env, is_increment, is_prefix)
         }
         _ => Object::error("invalid increment/decrement target"),
     }
@@ -244,6 +430,9 @@ fn apply_inc_dec_to_identifier(
     if current.is_error() {
         return current;
     }
+    if let Err(msg) = check_not_const(&ident.value, &env) {
+        return Object::error(msg);
+    }
 
     let new_value = match apply_inc_dec_to_numeric(&current, is_increment) {
         Ok(v) => v,
@@ -259,13 +448,13 @@ fn apply_inc_dec_to_identifier(
     }
 }
 
-fn apply_inc_dec_to_property(
-    pa: &PropertyAccess,
+fn apply_inc_dec_to_path(
+    target: &Expression,
     env: EnvRef,
     is_increment: bool,
     is_prefix: bool,
 ) -> Object {
-    let current = eval_property_access(pa, Rc::clone(&env));
+    let current = eval_expression(target, Rc::clone(&env));
     if current.is_error() {
         return current;
     }
@@ -275,7 +464,7 @@ fn apply_inc_dec_to_property(
         Err(msg) => return Object::error(msg),
     };
 
-    let result = assign_to_property_access(pa, Rc::clone(&env), new_value.clone());
+    let result = assign_to_path(target, Rc::clone(&env), new_value.clone());
     if let Err(msg) = result {
         return Object::error(msg);
     }
@@ -291,7 +480,9 @@ fn apply_inc_dec_to_numeric(value: &Object, is_increment: bool) -> Result<Object
     match value {
         Object::Integer(i) => {
             let delta = if is_increment { 1 } else { -1 };
-            Ok(Object::Integer(i + delta))
+            i.checked_add(delta)
+                .map(Object::Integer)
+                .ok_or_else(|| format!("integer overflow in {} {} 1", i, if is_increment { "+" } else { "-" }))
         }
         Object::Float(f) => {
             let delta = if is_increment { 1.0 } else { -1.0 };
@@ -305,30 +496,61 @@ fn apply_inc_dec_to_numeric(value: &Object, is_increment: bool) -> Result<Object
 }
 
 fn eval_function_literal(fl: &FunctionLiteral, env: EnvRef) -> Object {
-    Object::Function {
+    Object::Function(Rc::new(FunctionData {
+        name: None,
         params: fl.params.clone(),
+        defaults: fl.defaults.clone(),
+        rest: fl.rest.clone(),
+        requires: fl.requires.clone(),
+        ensures: fl.ensures.clone(),
         body: fl.body.clone(),
         env,
-    }
+        generator: fl.generator,
+    }))
 }
 
 fn eval_call_expression(call: &CallExpression, env: EnvRef) -> Object {
     // Evaluate arguments first (left-to-right)
-    let args: Vec<Object> = call
-        .arguments
-        .iter()
-        .map(|arg| eval_expression(arg, Rc::clone(&env)))
-        .collect();
+    let args = match eval_expression_list(&call.arguments, &env) {
+        Ok(args) => args,
+        Err(err) => return err,
+    };
+    call_with_args(&call.function, args, env)
+}
 
+/// Call the function `function` evaluates to with already evaluated `args`.
+fn call_with_args(function: &Expression, args: Vec<Object>, env: EnvRef) -> Object {
     // Special-case method calls: `obj.method(...)`
-    if let Expression::PropertyAccess(pa) = &*call.function {
+    if let Expression::PropertyAccess(pa) = function {
         let receiver = eval_expression(&pa.object, Rc::clone(&env));
         if receiver.is_error() {
             return receiver;
         }
+        if pa.optional && receiver == Object::Null {
+            return Object::Null;
+        }
 
         let method = match &receiver {
-            Object::Object(map) => map.get(&pa.property.value).cloned().unwrap_or(Object::Null),
+            Object::Object(map) => {
+                let member = map.get(&pa.property.value).cloned().unwrap_or(Object::Null);
+                if let (false, Expression::Identifier(namespace)) = (member.is_callable(), &*pa.object) {
+                    let constant = compat::call_constant(&namespace.value, &pa.property.value, &member, &args);
+                    if let Some(result) = constant {
+                        return result;
+                    }
+                }
+                member
+            }
+            Object::String(_)
+            | Object::Array(_)
+            | Object::Integer(_)
+            | Object::Float(_)
+            | Object::Bloom(_)
+            | Object::Ref(_)
+            | Object::Seq(_)
+            | Object::Bytes(_) => {
+                return call_primitive_method(receiver, &pa.property.value, args, env);
+            }
             other => {
                 return Object::error(format!("property call not supported on value: {:?}", other))
             }
@@ -338,10 +560,46 @@ fn eval_call_expression(call: &CallExpression, env: EnvRef) -> Object {
     }
 
     // Regular function call
-    let function = eval_expression(&call.function, Rc::clone(&env));
+    let function = eval_expression(function, Rc::clone(&env));
+    if function.is_error() {
+        return function;
+    }
     apply_function_with_this(function, args, None, env)
 }
 
+/// Call `receiver.name(args)` on a string, bytes, array, number, bloom filter, ref or seq by
+/// passing the receiver as the first argument to the matching namespace
+/// function, so `s.trim()` is `String::trim(s)` and `x.sqrt()` is `Math::sqrt(x)`.
+fn call_primitive_method(receiver: Object, name: &str, args: Vec<Object>, env: EnvRef) -> Object {
+    let namespace = match receiver {
+        Object::String(_) => "String",
+        Object::Array(_) => "Array",
+        Object::Bloom(_) => "Bloom",
+        Object::Ref(_) => "Ref",
+        Object::Seq(_) => "Seq",
+        Object::Bytes(_) => "Bytes",
+        _ => "Math",
+    };
+    let method = match env.borrow().get(namespace) {
+        Some(Object::Object(members)) => members.get(name).cloned(),
+        _ => None,
+    };
+    let Some(method) = method else {
+        return Object::error(format!(
+            "{} has no method {} (looked for {}::{})",
+            receiver.type_name(),
+            name,
+            namespace,
+            name
+        ));
+    };
+
+    let mut full_args = Vec::with_capacity(args.len() + 1);
+    full_args.push(receiver);
+    full_args.extend(args);
+    apply_function_with_this(method, full_args, None, env)
+}
+
 /// Apply a function or builtin value to arguments, optionally binding `this`
 /// for method-style calls. Exposed so native builtins can reuse the same
 /// calling convention when they receive higher-order function arguments.
@@ -352,36 +610,213 @@ pub fn apply_function_with_this(
     caller_env: EnvRef,
 ) -> Object {
     match func {
-        Object::Function { params, body, env } => {
-            let extended = new_enclosed_env(env);
+        Object::Function(function) => {
+            let FunctionData { name, params, defaults, rest, requires, ensures, body, env, generator } = &*function;
+            let extended = call_scope(body, Rc::clone(env));
 
-            {
-                let mut inner = extended.borrow_mut();
+            // Bind implicit `this` for method calls, if provided.
+            if let Some(this_val) = this {
+                extended.borrow_mut().set("this".to_string(), this_val);
+            }
 
-                // Bind implicit `this` for method calls, if provided.
-                if let Some(this_val) = this {
-                    inner.set("this".to_string(), this_val);
-                }
+            let signature = Signature {
+                name: name.as_deref(),
+                params,
+                defaults,
+                rest: rest.as_ref(),
+                requires,
+                ensures,
+            };
+            if let Err(err) = bind_arguments(&signature, args, &extended) {
+                return err;
+            }
+            if let Err(err) = signature.check_requires(&extended) {
+                return err;
+            }
 
-                for (param, arg) in params.iter().zip(args.into_iter()) {
-                    inner.set(param.value.clone(), arg);
-                }
+            // A generator's body only runs as its seq is consumed.
+            if *generator {
+                return Object::Seq(Rc::new(SeqNode::Generator {
+                    body: Rc::new(body.clone()),
+                    env: extended,
+                }));
             }
 
             // Execute function body and unwrap an explicit `return` value if present,
             // so callers see the inner value rather than a ReturnValue wrapper.
-            let result = super::stmt::eval_block_statement(&body, extended);
-            if let Object::ReturnValue(inner) = result {
+            let call = debugger::enter_function(name.as_deref().unwrap_or("<anonymous>"), &extended);
+            let result = super::stmt::eval_block_statement(body, Rc::clone(&extended));
+            drop(call);
+            let result = propagate::take().unwrap_or(result);
+            let result = if let Object::ReturnValue(inner) = result {
                 *inner
             } else {
                 result
+            };
+            if result.is_error() {
+                return result;
+            }
+            match signature.check_ensures(&result, &extended) {
+                Ok(()) => result,
+                Err(err) => err,
             }
         }
         Object::Builtin(f) => f(args, caller_env),
+        Object::NativeFn(closure) => closure.call(args, caller_env),
         other => Object::error(format!("not a function: {:?}", other)),
     }
 }
 
+/// The scope for a call to a function with `body`, closing over `env`.
+fn call_scope(body: &BlockStatement, env: EnvRef) -> EnvRef {
+    match body.scope.get() {
+        Some(layout) => Environment::new_function_scope(env, Arc::clone(layout)),
+        None => new_enclosed_env(env),
+    }
+}
+
+/// The parts of a user function needed to bind a call's arguments and check
+/// its contracts.
+struct Signature<'a> {
+    name: Option<&'a str>,
+    params: &'a [Identifier],
+    defaults: &'a [Option<Expression>],
+    rest: Option<&'a Identifier>,
+    requires: &'a [Expression],
+    ensures: &'a [Expression],
+}
+
+impl Signature<'_> {
+    /// Parameters without a default, which every call has to supply.
+    fn required(&self) -> usize {
+        self.params
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !matches!(self.defaults.get(*i), Some(Some(_))))
+            .count()
+    }
+
+    /// Check the argument count, e.g. "function add expected 2 arguments, got 1".
+    fn check_arity(&self, given: usize) -> Result<(), Object> {
+        let required = self.required();
+        let max = self.params.len();
+        if given >= required && (given <= max || self.rest.is_some()) {
+            return Ok(());
+        }
+
+        let expected = if self.rest.is_some() {
+            format!("at least {}", plural_args(required))
+        } else if required == max {
+            plural_args(max)
+        } else {
+            format!("{} to {} arguments", required, max)
+        };
+        Err(Object::error(format!("{} expected {}, got {}", self.describe(), expected, given)))
+    }
+
+    /// Check the `requires` clauses in the call scope, once the arguments are
+    /// bound.
+    fn check_requires(&self, scope: &EnvRef) -> Result<(), Object> {
+        for clause in self.requires {
+            check_contract(clause, Rc::clone(scope), || {
+                format!("{} requires {}", self.describe(), format_expression(clause))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Check the `ensures` clauses with the return value bound as `result`.
+    fn check_ensures(&self, result: &Object, scope: &EnvRef) -> Result<(), Object> {
+        if self.ensures.is_empty() {
+            return Ok(());
+        }
+        let scope = new_enclosed_env(Rc::clone(scope));
+        scope.borrow_mut().set("result".to_string(), result.clone());
+        for clause in self.ensures {
+            check_contract(clause, Rc::clone(&scope), || {
+                format!(
+                    "{} ensures {} (result was {})",
+                    self.describe(),
+                    format_expression(clause),
+                    result
+                )
+            })?;
+        }
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        match self.name {
+            Some(name) => format!("function {}", name),
+            None => "function".to_string(),
+        }
+    }
+}
+
+/// Evaluate one contract clause, failing with `describe()` when it is falsy.
+fn check_contract(
+    clause: &Expression,
+    scope: EnvRef,
+    describe: impl FnOnce() -> String,
+) -> Result<(), Object> {
+    let value = eval_expression(clause, scope);
+    if value.is_error() {
+        return Err(value);
+    }
+    if is_truthy(&value) {
+        Ok(())
+    } else {
+        Err(Object::error(format!("contract violated: {}", describe())))
+    }
+}
+
+fn plural_args(n: usize) -> String {
+    if n == 1 {
+        "1 argument".to_string()
+    } else {
+        format!("{} arguments", n)
+    }
+}
+
+/// Bind call arguments to parameters in a function's call scope.
+///
+/// The argument count has to fit the signature: every parameter without a
+/// default needs an argument, and extra arguments are only accepted by a rest
+/// parameter. Defaults are evaluated in the call scope so they can refer to
+/// earlier parameters.
+fn bind_arguments(
+    signature: &Signature,
+    args: Vec<Object>,
+    scope: &EnvRef,
+) -> Result<(), Object> {
+    signature.check_arity(args.len())?;
+    let mut args = args.into_iter();
+
+    for (i, param) in signature.params.iter().enumerate() {
+        let value = match (args.next(), signature.defaults.get(i)) {
+            (Some(arg), _) => arg,
+            (None, Some(Some(default))) => {
+                let value = eval_expression(default, Rc::clone(scope));
+                if value.is_error() {
+                    return Err(value);
+                }
+                value
+            }
+            // the arity check guarantees an argument for every required param
+            (None, _) => Object::Null,
+        };
+        scope.borrow_mut().set(param.value.clone(), value);
+    }
+
+    if let Some(rest) = signature.rest {
+        scope
+            .borrow_mut()
+            .set(rest.value.clone(), Object::array(args.collect()));
+    }
+
+    Ok(())
+}
+
 fn eval_string_infix(op: &InfixOp, left: &str, right: &str) -> Object {
     use InfixOp::*;
     match op {
@@ -397,19 +832,29 @@ fn eval_string_infix(op: &InfixOp, left: &str, right: &str) -> Object {
     }
 }
 
+fn eval_bytes_infix(op: &InfixOp, mut left: Rc<Vec<u8>>, right: &[u8]) -> Object {
+    use InfixOp::*;
+    match op {
+        Plus => {
+            Rc::make_mut(&mut left).extend_from_slice(right);
+            Object::Bytes(left)
+        }
+        Equals => Object::Boolean(left.as_slice() == right),
+        NotEquals => Object::Boolean(left.as_slice() != right),
+        _ => Object::error(format!("unknown operator: {} (bytes)", op)),
+    }
+}
+
 fn eval_array_literal(al: &ArrayLiteral, env: EnvRef) -> Object {
-    let elements = al
-        .elements
-        .iter()
-        .map(|e| eval_expression(e, Rc::clone(&env)))
-        .collect::<Vec<_>>();
-    Object::Array(elements)
+    match eval_expression_list(&al.elements, &env) {
+        Ok(elements) => Object::array(elements),
+        Err(err) => err,
+    }
 }
 
 fn eval_object_literal(ol: &ObjectLiteral, env: EnvRef) -> Object {
-    use std::collections::HashMap;
 
-    let mut map = HashMap::new();
+    let mut map = ObjectMap::new();
 
     for (ident, expr) in &ol.properties {
         let value = eval_expression(expr, Rc::clone(&env));
@@ -419,7 +864,7 @@ fn eval_object_literal(ol: &ObjectLiteral, env: EnvRef) -> Object {
         map.insert(ident.value.clone(), value);
     }
 
-    Object::Object(map)
+    Object::from_map(map)
 }
 
 fn eval_index_expression(ix: &IndexExpression, env: EnvRef) -> Object {
@@ -427,10 +872,21 @@ fn eval_index_expression(ix: &IndexExpression, env: EnvRef) -> Object {
     let index = eval_expression(&ix.index, Rc::clone(&env));
 
     match (left, index) {
-        (Object::Array(arr), Object::Integer(i)) => eval_array_index(arr, i),
+        (Object::Array(arr), Object::Integer(i)) => eval_array_index(&arr, i),
         (Object::Array(_), other) => {
             Object::error(format!("array index must be integer, got {:?}", other))
         }
+        (Object::String(s), Object::Integer(i)) => eval_string_index(&s, i),
+        (Object::String(_), other) => {
+            Object::error(format!("string index must be integer, got {:?}", other))
+        }
+        (Object::Bytes(bytes), Object::Integer(i)) => match resolve_index(i, bytes.len()) {
+            Some(idx) => Object::Integer(bytes[idx] as i64),
+            None => Object::Null,
+        },
+        (Object::Bytes(_), other) => {
+            Object::error(format!("bytes index must be integer, got {:?}", other))
+        }
         (Object::Object(map), Object::String(key)) => {
             map.get(&key).cloned().unwrap_or(Object::Null)
         }
@@ -441,16 +897,30 @@ fn eval_index_expression(ix: &IndexExpression, env: EnvRef) -> Object {
     }
 }
 
-fn eval_array_index(arr: Vec<Object>, index: i64) -> Object {
-    if index < 0 {
-        return Object::Null;
+/// Resolve a possibly negative index against a length: `-1` is the last
+/// element. Returns `None` when it falls outside `0..len`.
+fn resolve_index(index: i64, len: usize) -> Option<usize> {
+    let idx = if index < 0 {
+        len.checked_sub(index.unsigned_abs() as usize)?
+    } else {
+        index as usize
+    };
+    (idx < len).then_some(idx)
+}
+
+fn eval_array_index(arr: &[Object], index: i64) -> Object {
+    match resolve_index(index, arr.len()) {
+        Some(idx) => arr[idx].clone(),
+        None => Object::Null,
     }
+}
 
-    let idx = index as usize;
-    if idx >= arr.len() {
-        Object::Null
-    } else {
-        arr[idx].clone()
+/// `s[i]` is the i-th character (not byte) as a one-character string.
+fn eval_string_index(s: &str, index: i64) -> Object {
+    let chars: Vec<char> = s.chars().collect();
+    match resolve_index(index, chars.len()) {
+        Some(idx) => Object::String(chars[idx].to_string()),
+        None => Object::Null,
     }
 }
 
@@ -462,6 +932,7 @@ fn eval_property_access(pa: &PropertyAccess, env: EnvRef) -> Object {
 
     match obj {
         Object::Object(map) => map.get(&pa.property.value).cloned().unwrap_or(Object::Null),
+        Object::Null if pa.optional => Object::Null,
         other => Object::error(format!(
             "property access not supported on value: {:?}",
             other
@@ -509,7 +980,7 @@ fn eval_publish_expression(pubexpr: &PublishExpression, env: EnvRef) -> Object {
     Object::Null
 }
 
-fn build_args_for_subscriber(values: &[Object], func: &Object) -> Result<Vec<Object>, String> {
+pub(crate) fn build_args_for_subscriber(values: &[Object], func: &Object) -> Result<Vec<Object>, String> {
     let filtered: Vec<Object> = values
         .iter()
         .filter(|v| !matches!(v, Object::Null))
@@ -517,14 +988,14 @@ fn build_args_for_subscriber(values: &[Object], func: &Object) -> Result<Vec<Obj
         .collect();
 
     match func {
-        Object::Function { params, .. } => {
-            let n = params.len();
+        Object::Function(function) => {
+            let n = function.params.len();
             if n == 0 {
                 return Ok(vec![]);
             }
 
             if n == 1 {
-                return Ok(vec![Object::Array(filtered)]);
+                return Ok(vec![Object::array(filtered)]);
             }
 
             let mut args = Vec::with_capacity(n);
@@ -538,16 +1009,14 @@ fn build_args_for_subscriber(values: &[Object], func: &Object) -> Result<Vec<Obj
                     }
                 }
             } else {
-                for i in 0..n - 1 {
-                    args.push(filtered[i].clone());
-                }
+                args.extend(filtered[..n - 1].iter().cloned());
                 let rest: Vec<Object> = filtered[n - 1..].to_vec();
-                args.push(Object::Array(rest));
+                args.push(Object::array(rest));
             }
 
             Ok(args)
         }
-        Object::Builtin(_) => Ok(filtered),
+        Object::Builtin(_) | Object::NativeFn(_) => Ok(filtered),
         other => Err(format!("subscriber for tag is not callable: {:?}", other)),
     }
 }
@@ -569,14 +1038,13 @@ fn eval_new_expression(new_expr: &NewExpression, env: EnvRef) -> Object {
     };
 
     // Create instance as a plain Object with all methods copied
-    let instance = Object::Object(methods.clone());
+    let instance = Object::from_map(methods.clone());
 
     // Evaluate constructor arguments
-    let args: Vec<Object> = new_expr
-        .arguments
-        .iter()
-        .map(|arg| eval_expression(arg, Rc::clone(&env)))
-        .collect();
+    let args = match eval_expression_list(&new_expr.arguments, &env) {
+        Ok(args) => args,
+        Err(err) => return err,
+    };
 
     // Check for any errors in arguments
     for arg in &args {
@@ -585,204 +1053,211 @@ fn eval_new_expression(new_expr: &NewExpression, env: EnvRef) -> Object {
         }
     }
 
-    // If there's a 'construct' method, call it with `this` bound to instance
-    if let Some(constructor) = methods.get("construct").cloned() {
+    // If there's an `init` (or the older `construct`) method, call it with
+    // `this` bound to the instance.
+    let constructor = methods.get("init").or_else(|| methods.get("construct")).cloned();
+    if let Some(constructor) = constructor {
         match constructor {
-            Object::Function {
-                params,
-                body,
-                env: fn_env,
-            } => {
-                let extended = new_enclosed_env(fn_env);
-                {
-                    let mut inner = extended.borrow_mut();
-                    inner.set("this".to_string(), instance.clone());
-                    for (param, arg) in params.iter().zip(args) {
-                        inner.set(param.value.clone(), arg);
-                    }
+            Object::Function(function) => {
+                let FunctionData { name, params, defaults, rest, requires, ensures, body, env: fn_env, .. } = &*function;
+                let extended = call_scope(body, Rc::clone(fn_env));
+                extended
+                    .borrow_mut()
+                    .set("this".to_string(), instance.clone());
+                let signature = Signature {
+                    name: name.as_deref(),
+                    params,
+                    defaults,
+                    rest: rest.as_ref(),
+                    requires,
+                    ensures,
+                };
+                if let Err(err) = bind_arguments(&signature, args, &extended) {
+                    return err;
+                }
+                if let Err(err) = signature.check_requires(&extended) {
+                    return err;
                 }
 
-                let result = super::stmt::eval_block_statement(&body, Rc::clone(&extended));
+                let result = super::stmt::eval_block_statement(body, Rc::clone(&extended));
                 if result.is_error() {
                     return result;
                 }
+                // A `?` in the constructor makes `new` return the `None` or `Err`.
+                if let Some(value) = propagate::take() {
+                    return value;
+                }
 
-                // Get the modified `this` from the constructor's environment
+                // Get the modified `this` from the constructor's environment;
+                // a constructor's postconditions see the instance as `result`.
                 let modified_this = extended.borrow().get("this").unwrap_or(instance);
+                if let Err(err) = signature.check_ensures(&modified_this, &extended) {
+                    return err;
+                }
                 return modified_this;
             }
-            _ => return Object::error("construct is not a function"),
+            _ => return Object::error(format!("constructor of {} is not a function", class_name)),
         }
     }
 
     instance
 }
 
-/// Handle assignments like `obj.field = value` and nested `obj.a.b = value`.
-fn assign_to_property_access(
-    pa: &PropertyAccess,
-    env: EnvRef,
-    new_value: Object,
-) -> Result<(), String> {
-    // Collect property chain from the AST, e.g. for `obj.a.b` we get:
-    //   root_ident = "obj", props = ["a", "b"]
-    let mut props: Vec<String> = vec![pa.property.value.clone()];
-    let root_ident = match collect_property_chain(&pa.object, &mut props) {
-        Some(name) => name,
-        None => {
-            return Err(
-                "left side of assignment must be an object property (like x.y or x.y.z)"
-                    .to_string(),
-            )
-        }
-    };
+/// One step of an assignment target path: `.name` / `["name"]` or `[i]`.
+#[derive(Debug)]
+enum PathSegment {
+    Key(String),
+    Index(i64),
+}
 
-    debug_log!(
-        "assign_to_property_access: root = {}, props = {:?}",
-        root_ident,
-        props
-    );
+/// Handle assignments through property access and indexing, e.g.
+/// `obj.a.b = value`, `obj["field"] = value`, `arr[0] = value` and mixed
+/// chains like `matrix[i][j] = value` or `users[0].name = value`.
+fn assign_to_path(target: &Expression, env: EnvRef, new_value: Object) -> Result<(), String> {
+    // Collect the path from the AST, e.g. for `obj.a[0]` we get:
+    //   root_ident = "obj", path = [Key("a"), Index(0)]
+    let mut path: Vec<PathSegment> = Vec::new();
+    let root_ident = collect_assignment_path(target, Rc::clone(&env), &mut path)?;
+    path.reverse();
+
+    debug_log!("assign_to_path: root = {}, path = {:?}", root_ident, path);
+
+    // `Ns::member = ...` reaches into a namespace, whose `const` members are
+    // recorded as `Ns::member` (or `Outer::Inner::member`).
+    check_not_const(&root_ident, &env)?;
+    let mut member = root_ident.clone();
+    for segment in &path {
+        let PathSegment::Key(key) = segment else { break };
+        member = format!("{}::{}", member, key);
+        check_not_const(&member, &env)?;
+    }
 
-    // Get current root object value from environment
-    let current_root = {
-        let env_borrow = env.borrow();
-        match env_borrow.get(&root_ident) {
-            Some(obj) => obj,
-            None => {
-                return Err(format!(
-                    "identifier not found for property assignment: {}",
-                    root_ident
-                ))
-            }
+    // Update the binding in place when it lives in this scope, so values
+    // that aren't shared elsewhere are modified without being copied.
+    let mut env_mut = env.borrow_mut();
+    if let Some(root) = env_mut.get_local_mut(&root_ident) {
+        return assign_into_value(root, &path, &new_value);
+    }
+
+    // Otherwise copy it from an outer scope (or a builtin namespace) and
+    // bind the updated value in this scope.
+    let mut root = match env_mut.get(&root_ident) {
+        Some(obj) => obj,
+        None => {
+            return Err(format!(
+                "identifier not found for assignment: {}",
+                root_ident
+            ))
         }
     };
-
-    // Recursively build an updated root object with the new value applied
-    let updated_root =
-        assign_into_object(current_root.clone(), &props, &new_value).map_err(|e| e)?;
-
-    // Store updated root back into current environment scope
-    env.borrow_mut().set(root_ident, updated_root);
+    assign_into_value(&mut root, &path, &new_value)?;
+    env_mut.set(root_ident, root);
 
     Ok(())
 }
 
-/// Walks back through nested `PropertyAccess` to find the root identifier and
-/// complete property chain.
-fn collect_property_chain(expr: &Expression, props: &mut Vec<String>) -> Option<String> {
-    match expr {
-        Expression::Identifier(Identifier { value }) => {
-            // We have reached the root: reverse the collected props so they are
-            // in left-to-right order (outermost to innermost).
-            props.reverse();
-            Some(value.clone())
-        }
-        Expression::PropertyAccess(inner) => {
-            props.push(inner.property.value.clone());
-            collect_property_chain(&inner.object, props)
-        }
-        _ => None,
+/// Fails if `name` (a variable or an `Ns::member`) was declared with `const`.
+fn check_not_const(name: &str, env: &EnvRef) -> Result<(), String> {
+    if env.borrow().is_const(name) {
+        return Err(format!("cannot assign to constant {}", name));
     }
+    Ok(())
 }
 
-/// Handle assignments like `obj["field"] = value` and nested `obj["a"]["b"] = value`.
-fn assign_to_index_expression(
-    target: &Expression,
+/// Walks back through nested `PropertyAccess` / `IndexExpression` nodes to
+/// find the root identifier, pushing path segments innermost-first. Index
+/// expressions are evaluated along the way.
+fn collect_assignment_path(
+    expr: &Expression,
     env: EnvRef,
-    new_value: Object,
-) -> Result<(), String> {
-    let mut props: Vec<String> = Vec::new();
-    let root_ident = collect_index_chain(target, Rc::clone(&env), &mut props).ok_or_else(|| {
-        "left side of assignment must be an object index (like x[\"y\"] or x[\"a\"][\"b\"])"
-            .to_string()
-    })?;
-
-    debug_log!(
-        "assign_to_index_expression: root = {}, props = {:?}",
-        root_ident,
-        props
-    );
-
-    // Get current root object value from environment
-    let current_root = {
-        let env_borrow = env.borrow();
-        match env_borrow.get(&root_ident) {
-            Some(obj) => obj,
-            None => {
-                return Err(format!(
-                    "identifier not found for index assignment: {}",
-                    root_ident
-                ))
+    path: &mut Vec<PathSegment>,
+) -> Result<String, String> {
+    match expr {
+        Expression::Identifier(Identifier { value, .. }) => Ok(value.clone()),
+        Expression::PropertyAccess(pa) => {
+            if pa.optional {
+                return Err("cannot assign through optional chaining (?.)".to_string());
             }
+            path.push(PathSegment::Key(pa.property.value.clone()));
+            collect_assignment_path(&pa.object, env, path)
         }
-    };
-
-    // Recursively build an updated root object with the new value applied
-    let updated_root =
-        assign_into_object(current_root.clone(), &props, &new_value).map_err(|e| e)?;
-
-    // Store updated root back into current environment scope
-    env.borrow_mut().set(root_ident, updated_root);
-
-    Ok(())
-}
-
-/// Collect the root identifier and dynamic string key chain for an index-based
-/// assignment target, e.g. `obj["a"]["b"]` -> root `obj`, props = ["a", "b"].
-fn collect_index_chain(expr: &Expression, env: EnvRef, props: &mut Vec<String>) -> Option<String> {
-    match expr {
         Expression::IndexExpression(ix) => {
-            // Evaluate the index expression and ensure it is a string key.
-            let key_val = eval_expression(&ix.index, Rc::clone(&env));
-            match key_val {
-                Object::String(s) => props.push(s),
-                _ => {
-                    return None; // will be turned into a user-facing error by caller
+            match eval_expression(&ix.index, Rc::clone(&env)) {
+                Object::String(s) => path.push(PathSegment::Key(s)),
+                Object::Integer(i) => path.push(PathSegment::Index(i)),
+                Object::Error(msg) => return Err(msg),
+                other => {
+                    return Err(format!(
+                        "index in assignment must be a string or integer, got {}",
+                        other.type_name()
+                    ))
                 }
             }
-
-            collect_index_chain(&ix.left, env, props)
+            collect_assignment_path(&ix.left, env, path)
         }
-        Expression::Identifier(Identifier { value }) => {
-            // We have reached the root identifier; reverse collected props to
-            // be in left-to-right order (outermost to innermost).
-            props.reverse();
-            Some(value.clone())
-        }
-        _ => None,
+        _ => Err(
+            "left side of assignment must be a variable, property or index (like x.y, x[\"y\"] or x[0])"
+                .to_string(),
+        ),
     }
 }
 
-/// Given a root object and a property path, produces a new value with the
-/// property updated, preserving value semantics.
-fn assign_into_object(obj: Object, props: &[String], new_value: &Object) -> Result<Object, String> {
-    if props.is_empty() {
-        return Ok(new_value.clone());
-    }
-
-    match obj {
-        Object::Object(mut map) => {
-            let key = &props[0];
+/// Apply `new_value` at `path` inside `target`. Missing object keys along
+/// the way are created as empty objects; array indices must already exist.
+/// Nothing observable changes unless the whole assignment succeeds: shared
+/// storage is copied on write, and missing keys are only inserted once the
+/// value beneath them has been built.
+fn assign_into_value(
+    target: &mut Object,
+    path: &[PathSegment],
+    new_value: &Object,
+) -> Result<(), String> {
+    let Some((segment, rest)) = path.split_first() else {
+        *target = new_value.clone();
+        return Ok(());
+    };
 
-            if props.len() == 1 {
-                // Final property: just insert / overwrite
-                map.insert(key.clone(), new_value.clone());
-                Ok(Object::Object(map))
-            } else {
-                // Need to drill down into nested object
-                let child = map
-                    .remove(key)
-                    .unwrap_or_else(|| Object::Object(Default::default()));
-
-                let updated_child = assign_into_object(child, &props[1..], new_value)?;
-                map.insert(key.clone(), updated_child);
-                Ok(Object::Object(map))
+    match (target, segment) {
+        (Object::Object(map), PathSegment::Key(key)) => {
+            if builtins::native::object_builtins::is_frozen(map) {
+                return Err(format!("cannot assign to {} of a frozen object", key));
+            }
+            let fields = Rc::make_mut(map);
+            match fields.get_mut(key) {
+                Some(child) => assign_into_value(child, rest, new_value),
+                None => {
+                    let mut child = Object::Object(Default::default());
+                    assign_into_value(&mut child, rest, new_value)?;
+                    fields.insert(key.clone(), child);
+                    Ok(())
+                }
             }
         }
-        other => Err(format!(
+        (Object::Array(items), PathSegment::Index(index)) => {
+            let len = items.len();
+            let idx = resolve_index(*index, len).ok_or_else(|| {
+                format!("array index {} out of bounds for length {}", index, len)
+            })?;
+            if builtins::native::object_builtins::is_frozen_array(items) {
+                return Err(format!("cannot assign to index {} of a frozen array", index));
+            }
+            assign_into_value(&mut Rc::make_mut(items)[idx], rest, new_value)
+        }
+        (Object::Object(_), PathSegment::Index(_)) => {
+            Err("object index must be string, got integer".to_string())
+        }
+        (Object::Array(_), PathSegment::Key(_)) => {
+            Err("array index must be integer, got string".to_string())
+        }
+        (Object::Error(msg), _) => Err(format!("cannot assign into an error value: {}", msg)),
+        (other, PathSegment::Key(_)) => Err(format!(
             "cannot assign property on non-object value: {:?}",
             other
         )),
+        (other, PathSegment::Index(_)) => Err(format!(
+            "cannot assign index on {}",
+            other.type_name()
+        )),
     }
 }
 
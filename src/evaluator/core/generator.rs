@@ -0,0 +1,284 @@
+//! Step-by-step evaluation of generator bodies (`fn*`).
+//!
+//! The tree-walker can't pause in the middle of a call, so a generator runs
+//! its body on an explicit stack of frames instead. Only statements that
+//! contain a `yield` are stepped through frame by frame; everything else is
+//! handed to the ordinary evaluator in one go.
+
+use std::rc::Rc;
+
+use crate::ast::{BlockStatement, Expression, Statement};
+use crate::env::EnvRef;
+use crate::event_loop;
+use crate::object::Object;
+
+use super::{budget, propagate};
+use super::expr::{eval_expression, is_truthy};
+use super::stmt::{eval_statement, for_in_values, switch_matches};
+
+type Values = Box<dyn Iterator<Item = Object>>;
+
+enum Frame {
+    Block {
+        block: Rc<BlockStatement>,
+        next: usize,
+    },
+    While {
+        condition: Expression,
+        body: Rc<BlockStatement>,
+    },
+    For {
+        condition: Option<Expression>,
+        post: Option<Box<Statement>>,
+        body: Rc<BlockStatement>,
+        started: bool,
+    },
+    ForIn {
+        name: String,
+        values: Values,
+        body: Rc<BlockStatement>,
+    },
+}
+
+/// The values a generator body yields, produced on demand. A `return` ends
+/// the sequence; an error is yielded and then ends it.
+pub(crate) struct Generator {
+    frames: Vec<Frame>,
+    env: EnvRef,
+}
+
+impl Generator {
+    pub(crate) fn new(body: Rc<BlockStatement>, env: EnvRef) -> Self {
+        Generator {
+            frames: vec![Frame::Block { block: body, next: 0 }],
+            env,
+        }
+    }
+
+    fn finish(&mut self) {
+        self.frames.clear();
+    }
+
+    /// Advance the frame on top of the stack by one step, returning a value
+    /// if that step yielded one.
+    fn resume(&mut self) -> Option<Object> {
+        let env = Rc::clone(&self.env);
+        let frame = self.frames.last_mut()?;
+        let body = match frame {
+            Frame::Block { block, next } => {
+                let block = Rc::clone(block);
+                let Some(stmt) = block.statements.get(*next) else {
+                    self.frames.pop();
+                    return None;
+                };
+                *next += 1;
+                return self.step(stmt);
+            }
+            Frame::While { condition, body } => {
+                if let Some(err) = budget::charge() {
+                    return self.fail(err);
+                }
+                let cond = eval_expression(condition, env);
+                if cond.is_error() {
+                    return self.fail(cond);
+                }
+                if !is_truthy(&cond) {
+                    self.frames.pop();
+                    return None;
+                }
+                Rc::clone(body)
+            }
+            Frame::For { condition, post, body, started } => {
+                if let Some(err) = budget::charge() {
+                    return self.fail(err);
+                }
+                if *started && let Some(post) = post {
+                    let result = eval_statement(post, Rc::clone(&env));
+                    if result.is_error() {
+                        return self.fail(result);
+                    }
+                }
+                *started = true;
+                if let Some(condition) = condition {
+                    let cond = eval_expression(condition, env);
+                    if cond.is_error() {
+                        return self.fail(cond);
+                    }
+                    if !is_truthy(&cond) {
+                        self.frames.pop();
+                        return None;
+                    }
+                }
+                Rc::clone(body)
+            }
+            Frame::ForIn { name, values, body } => {
+                if let Some(err) = budget::charge() {
+                    return self.fail(err);
+                }
+                let Some(value) = values.next() else {
+                    self.frames.pop();
+                    return None;
+                };
+                if value.is_error() {
+                    return self.fail(value);
+                }
+                env.borrow_mut().set(name.clone(), value);
+                Rc::clone(body)
+            }
+        };
+        self.frames.push(Frame::Block { block: body, next: 0 });
+        None
+    }
+
+    /// Run one statement of a block, entering it if it contains a `yield`.
+    fn step(&mut self, stmt: &Statement) -> Option<Object> {
+        let env = Rc::clone(&self.env);
+        event_loop::check_signal(&env);
+
+        if !contains_yield(stmt) {
+            let result = eval_statement(stmt, env);
+            if let Object::ReturnValue(_) = result {
+                self.finish();
+                return None;
+            }
+            if result.is_error() {
+                return self.fail(result);
+            }
+            return None;
+        }
+
+        let frame = match stmt {
+            Statement::Yield(ys) => {
+                if let Some(err) = budget::charge() {
+                    return self.fail(err);
+                }
+                let value = eval_expression(&ys.value, env);
+                if value.is_error() {
+                    return self.fail(value);
+                }
+                return Some(value);
+            }
+            Statement::Expression(es) => {
+                // contains_yield only lets `if` through here.
+                let Expression::If(ifexpr) = &es.expression else {
+                    return None;
+                };
+                let cond = eval_expression(&ifexpr.condition, env);
+                if cond.is_error() {
+                    return self.fail(cond);
+                }
+                let block = if is_truthy(&cond) {
+                    &ifexpr.consequence
+                } else {
+                    match &ifexpr.alternative {
+                        Some(alt) => alt,
+                        None => return None,
+                    }
+                };
+                Frame::Block { block: Rc::new(block.clone()), next: 0 }
+            }
+            Statement::Switch(ss) => {
+                let subject = eval_expression(&ss.subject, Rc::clone(&env));
+                if subject.is_error() {
+                    return self.fail(subject);
+                }
+                let mut chosen = None;
+                'cases: for case in &ss.cases {
+                    for value in &case.values {
+                        let value = eval_expression(value, Rc::clone(&env));
+                        if value.is_error() {
+                            return self.fail(value);
+                        }
+                        if switch_matches(&subject, &value) {
+                            chosen = Some(&case.body);
+                            break 'cases;
+                        }
+                    }
+                }
+                match chosen.or(ss.default.as_ref()) {
+                    Some(body) => Frame::Block { block: Rc::new(body.clone()), next: 0 },
+                    None => return None,
+                }
+            }
+            Statement::While(ws) => Frame::While {
+                condition: ws.condition.clone(),
+                body: Rc::new(ws.body.clone()),
+            },
+            Statement::For(fs) => {
+                if let Some(init) = &fs.init {
+                    let result = eval_statement(init, env);
+                    if result.is_error() {
+                        return self.fail(result);
+                    }
+                }
+                Frame::For {
+                    condition: fs.condition.clone(),
+                    post: fs.post.clone(),
+                    body: Rc::new(fs.body.clone()),
+                    started: false,
+                }
+            }
+            Statement::ForIn(fs) => {
+                let iterable = eval_expression(&fs.iterable, Rc::clone(&env));
+                match for_in_values(iterable, &env) {
+                    Ok(values) => Frame::ForIn {
+                        name: fs.name.value.clone(),
+                        values,
+                        body: Rc::new(fs.body.clone()),
+                    },
+                    Err(err) => return self.fail(err),
+                }
+            }
+            _ => return None,
+        };
+        self.frames.push(frame);
+        None
+    }
+
+    fn fail(&mut self, err: Object) -> Option<Object> {
+        self.finish();
+        Some(err)
+    }
+}
+
+impl Iterator for Generator {
+    type Item = Object;
+
+    fn next(&mut self) -> Option<Object> {
+        while !self.frames.is_empty() {
+            if let Some(value) = self.resume() {
+                return Some(value);
+            }
+            // A `?` ends the generator, yielding the `None` or `Err` last.
+            if let Some(value) = propagate::take() {
+                self.finish();
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+/// Whether `stmt` yields, directly or from a nested block. Function bodies
+/// are their own scope and don't count.
+fn contains_yield(stmt: &Statement) -> bool {
+    let block_yields = |block: &BlockStatement| block.statements.iter().any(contains_yield);
+    match stmt {
+        Statement::Yield(_) => true,
+        Statement::While(ws) => block_yields(&ws.body),
+        Statement::For(fs) => block_yields(&fs.body),
+        Statement::ForIn(fs) => block_yields(&fs.body),
+        Statement::Switch(ss) => {
+            ss.cases.iter().any(|case| block_yields(&case.body))
+                || ss.default.as_ref().is_some_and(block_yields)
+        }
+        Statement::Expression(es) => match &es.expression {
+            Expression::If(ifexpr) => {
+                block_yields(&ifexpr.consequence)
+                    || ifexpr.alternative.as_ref().is_some_and(block_yields)
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
@@ -0,0 +1,47 @@
+//! The postfix `?` operator.
+//!
+//! `x?` on a `None` or `Err` has to leave the enclosing function from the
+//! middle of an expression, which the tree-walker has no direct way to do.
+//! Instead the value is parked here: while it is pending every expression
+//! evaluates to `null` and every statement and loop iteration unwinds like a
+//! `return`, until the function call that owns the `?` takes the value and
+//! returns it.
+
+use std::cell::{Cell, RefCell};
+
+use crate::object::Object;
+
+thread_local! {
+    static PENDING: Cell<bool> = const { Cell::new(false) };
+    static VALUE: RefCell<Option<Object>> = const { RefCell::new(None) };
+}
+
+/// Start returning `value` (a `None` or `Err`) from the current function.
+pub(super) fn start(value: Object) {
+    VALUE.with(|v| *v.borrow_mut() = Some(value));
+    PENDING.with(|p| p.set(true));
+}
+
+/// Whether a `?` is on its way out of the current function.
+pub(super) fn pending() -> bool {
+    PENDING.with(|p| p.get())
+}
+
+/// What a statement or loop iteration evaluates to while a `?` is pending:
+/// a return, so enclosing blocks and loops stop.
+pub(super) fn unwind() -> Option<Object> {
+    if pending() {
+        Some(Object::ReturnValue(Box::new(Object::Null)))
+    } else {
+        None
+    }
+}
+
+/// Finish propagating, handing back the value the `?` returned.
+pub(super) fn take() -> Option<Object> {
+    if !pending() {
+        return None;
+    }
+    PENDING.with(|p| p.set(false));
+    VALUE.with(|v| v.borrow_mut().take())
+}
@@ -0,0 +1,397 @@
+//! Works out, before a program runs, where each identifier it reads is bound,
+//! so a lookup can go straight to the right scope instead of searching every
+//! scope by name.
+//!
+//! Only function scopes take part: each function body gets a
+//! [`ScopeLayout`] of the names it binds, and each identifier a [`Binding`]
+//! counting function scopes out from where it is read. Code that runs in a
+//! scope the evaluator creates some other way (namespace bodies, generator
+//! bodies, `ensures` clauses, tests) is left to the search by name.
+
+use std::mem;
+use std::sync::Arc;
+
+use crate::ast::nodes::{
+    Binding, BlockStatement, Expression, FunctionLiteral, Identifier, InfixOp, PostfixOp, PrefixOp, Program,
+    Statement,
+};
+use crate::env::ScopeLayout;
+
+/// Resolve every identifier in `program`. Resolving a program again changes
+/// nothing, so programs evaluated more than once need no special care.
+pub(super) fn resolve(program: &Program) {
+    Resolver::default().statements(&program.statements);
+}
+
+#[derive(Default)]
+struct Resolver {
+    /// Layouts of the function scopes around the code being resolved,
+    /// innermost last.
+    scopes: Vec<Arc<ScopeLayout>>,
+}
+
+impl Resolver {
+    fn identifier(&self, ident: &Identifier) {
+        let local = self.scopes.iter().rev().enumerate().find_map(|(depth, layout)| {
+            let slot = layout.slot(&ident.value)?;
+            Some(Binding::Local {
+                depth: depth as u32,
+                slot: slot as u32,
+            })
+        });
+        let binding = match local {
+            Some(binding) => binding,
+            None if self.scopes.is_empty() => Binding::Dynamic,
+            None => Binding::Outer {
+                depth: self.scopes.len() as u32,
+            },
+        };
+        ident.binding.set(binding);
+    }
+
+    /// Resolve code that runs in a scope other than a function's, where none
+    /// of the enclosing layouts can be relied on.
+    fn barrier(&mut self, f: impl FnOnce(&mut Self)) {
+        let scopes = mem::take(&mut self.scopes);
+        f(self);
+        self.scopes = scopes;
+    }
+
+    fn function(&mut self, literal: &FunctionLiteral) {
+        // A generator's body runs in a scope of its own, on top of the call's.
+        if literal.generator {
+            self.barrier(|r| r.signature_and_body(literal));
+            return;
+        }
+        let layout = literal.body.scope.get_or_init(|| Arc::new(ScopeLayout::new(binders(literal))));
+        self.scopes.push(Arc::clone(layout));
+        self.signature_and_body(literal);
+        self.scopes.pop();
+    }
+
+    fn signature_and_body(&mut self, literal: &FunctionLiteral) {
+        for default in literal.defaults.iter().flatten() {
+            self.expression(default);
+        }
+        for clause in &literal.requires {
+            self.expression(clause);
+        }
+        self.block(&literal.body);
+        // `ensures` clauses run in a scope binding `result`.
+        self.barrier(|r| {
+            for clause in &literal.ensures {
+                r.expression(clause);
+            }
+        });
+    }
+
+    fn block(&mut self, block: &BlockStatement) {
+        self.statements(&block.statements);
+    }
+
+    fn statements(&mut self, statements: &[Statement]) {
+        for stmt in statements {
+            self.statement(stmt);
+        }
+    }
+
+    fn statement(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::Let(ls) => self.expression(&ls.value),
+            Statement::Destructure(ds) => self.expression(&ds.value),
+            Statement::Return(rs) => self.expression(&rs.return_value),
+            Statement::Yield(ys) => self.expression(&ys.value),
+            Statement::Expression(es) => self.expression(&es.expression),
+            Statement::While(ws) => {
+                self.expression(&ws.condition);
+                self.block(&ws.body);
+            }
+            Statement::For(fs) => {
+                if let Some(init) = &fs.init {
+                    self.statement(init);
+                }
+                if let Some(condition) = &fs.condition {
+                    self.expression(condition);
+                }
+                if let Some(post) = &fs.post {
+                    self.statement(post);
+                }
+                self.block(&fs.body);
+            }
+            Statement::ForIn(fs) => {
+                self.expression(&fs.iterable);
+                self.block(&fs.body);
+            }
+            Statement::Switch(ss) => {
+                self.expression(&ss.subject);
+                for case in &ss.cases {
+                    for value in &case.values {
+                        self.expression(value);
+                    }
+                    self.block(&case.body);
+                }
+                if let Some(default) = &ss.default {
+                    self.block(default);
+                }
+            }
+            Statement::Function(fs) => self.function(&fs.literal),
+            Statement::Class(cs) => {
+                for method in &cs.methods {
+                    self.function(&method.literal);
+                }
+            }
+            Statement::Test(ts) => self.barrier(|r| r.block(&ts.body)),
+            Statement::Describe(ds) => self.barrier(|r| r.block(&ds.body)),
+            Statement::Hook(hs) => self.barrier(|r| r.block(&hs.body)),
+            Statement::Namespace(ns) => self.barrier(|r| r.block(&ns.body)),
+            Statement::Export(es) => {
+                if let Some(decl) = &es.declaration {
+                    self.statement(decl);
+                }
+            }
+            Statement::Import(_) | Statement::Use(_) | Statement::Breakpoint => {}
+        }
+    }
+
+    fn expression(&mut self, expr: &Expression) {
+        match expr {
+            Expression::Identifier(ident) => self.identifier(ident),
+            Expression::IntegerLiteral(_)
+            | Expression::BooleanLiteral(_)
+            | Expression::FloatLiteral(_)
+            | Expression::StringLiteral(_)
+            | Expression::BytesLiteral(_)
+            | Expression::NullLiteral
+            | Expression::Builtin(_) => {}
+            Expression::Infix(infix) => {
+                self.expression(&infix.left);
+                self.expression(&infix.right);
+            }
+            Expression::If(ie) => {
+                self.expression(&ie.condition);
+                self.block(&ie.consequence);
+                if let Some(alternative) = &ie.alternative {
+                    self.block(alternative);
+                }
+            }
+            Expression::Prefix(pe) => self.expression(&pe.right),
+            Expression::Postfix(pe) => self.expression(&pe.left),
+            Expression::FunctionLiteral(fl) => self.function(fl),
+            Expression::CallExpression(call) => {
+                self.expression(&call.function);
+                for arg in &call.arguments {
+                    self.expression(arg);
+                }
+            }
+            Expression::ArrayLiteral(al) => {
+                for element in &al.elements {
+                    self.expression(element);
+                }
+            }
+            Expression::IndexExpression(ie) => {
+                self.expression(&ie.left);
+                self.expression(&ie.index);
+            }
+            Expression::ObjectLiteral(ol) => {
+                for (_, value) in &ol.properties {
+                    self.expression(value);
+                }
+            }
+            Expression::PropertyAccess(pa) => self.expression(&pa.object),
+            Expression::Publish(pe) => {
+                for arg in &pe.args {
+                    self.expression(arg);
+                }
+            }
+            Expression::New(ne) => {
+                for arg in &ne.arguments {
+                    self.expression(arg);
+                }
+            }
+            Expression::Spread(se) => self.expression(&se.value),
+        }
+    }
+}
+
+/// The names a call to `literal` binds in its own scope, in slot order:
+/// `this`, the parameters, then whatever the body declares or assigns.
+fn binders(literal: &FunctionLiteral) -> Vec<String> {
+    let mut names = Binders::default();
+    names.add("this");
+    for param in literal.params.iter().chain(&literal.rest) {
+        names.add(&param.value);
+    }
+    names.block(&literal.body);
+    names.0
+}
+
+/// Collects the names a function body binds. Nested functions, namespaces
+/// and tests bind in scopes of their own, so their bodies are skipped.
+#[derive(Default)]
+struct Binders(Vec<String>);
+
+impl Binders {
+    fn add(&mut self, name: &str) {
+        if !self.0.iter().any(|n| n == name) {
+            self.0.push(name.to_string());
+        }
+    }
+
+    /// Assigning to `x`, `x.y` or `x[i]` binds `x` in the current scope.
+    fn target(&mut self, target: &Expression) {
+        match target {
+            Expression::Identifier(ident) => self.add(&ident.value),
+            Expression::PropertyAccess(pa) if !pa.namespaced => self.target(&pa.object),
+            Expression::IndexExpression(ie) => self.target(&ie.left),
+            _ => {}
+        }
+    }
+
+    fn block(&mut self, block: &BlockStatement) {
+        for stmt in &block.statements {
+            self.statement(stmt);
+        }
+    }
+
+    fn statement(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::Let(ls) => {
+                self.expression(&ls.value);
+                self.add(&ls.name.value);
+            }
+            Statement::Destructure(ds) => {
+                self.expression(&ds.value);
+                for name in ds.pattern.bindings() {
+                    self.add(&name.value);
+                }
+            }
+            Statement::Return(rs) => self.expression(&rs.return_value),
+            Statement::Yield(ys) => self.expression(&ys.value),
+            Statement::Expression(es) => self.expression(&es.expression),
+            Statement::While(ws) => {
+                self.expression(&ws.condition);
+                self.block(&ws.body);
+            }
+            Statement::For(fs) => {
+                if let Some(init) = &fs.init {
+                    self.statement(init);
+                }
+                if let Some(condition) = &fs.condition {
+                    self.expression(condition);
+                }
+                if let Some(post) = &fs.post {
+                    self.statement(post);
+                }
+                self.block(&fs.body);
+            }
+            Statement::ForIn(fs) => {
+                self.expression(&fs.iterable);
+                self.add(&fs.name.value);
+                self.block(&fs.body);
+            }
+            Statement::Switch(ss) => {
+                self.expression(&ss.subject);
+                for case in &ss.cases {
+                    for value in &case.values {
+                        self.expression(value);
+                    }
+                    self.block(&case.body);
+                }
+                if let Some(default) = &ss.default {
+                    self.block(default);
+                }
+            }
+            Statement::Function(fs) => self.add(&fs.name.value),
+            Statement::Class(cs) => self.add(&cs.name.value),
+            Statement::Namespace(ns) => self.add(&ns.name.value),
+            Statement::Import(is) => {
+                for name in &is.names {
+                    self.add(&name.value);
+                }
+            }
+            Statement::Use(us) => {
+                if let Some(name) = us.binding() {
+                    self.add(&name.value);
+                }
+            }
+            Statement::Export(es) => {
+                if let Some(decl) = &es.declaration {
+                    self.statement(decl);
+                }
+            }
+            Statement::Test(_) | Statement::Describe(_) | Statement::Hook(_) | Statement::Breakpoint => {}
+        }
+    }
+
+    fn expression(&mut self, expr: &Expression) {
+        match expr {
+            Expression::Identifier(_)
+            | Expression::IntegerLiteral(_)
+            | Expression::BooleanLiteral(_)
+            | Expression::FloatLiteral(_)
+            | Expression::StringLiteral(_)
+            | Expression::BytesLiteral(_)
+            | Expression::NullLiteral
+            | Expression::Builtin(_)
+            | Expression::FunctionLiteral(_) => {}
+            Expression::Infix(infix) => {
+                if infix.operator == InfixOp::Assign {
+                    self.target(&infix.left);
+                }
+                self.expression(&infix.left);
+                self.expression(&infix.right);
+            }
+            Expression::If(ie) => {
+                self.expression(&ie.condition);
+                self.block(&ie.consequence);
+                if let Some(alternative) = &ie.alternative {
+                    self.block(alternative);
+                }
+            }
+            Expression::Prefix(pe) => {
+                if matches!(pe.operator, PrefixOp::PreIncrement | PrefixOp::PreDecrement) {
+                    self.target(&pe.right);
+                }
+                self.expression(&pe.right);
+            }
+            Expression::Postfix(pe) => {
+                if matches!(pe.operator, PostfixOp::Increment | PostfixOp::Decrement) {
+                    self.target(&pe.left);
+                }
+                self.expression(&pe.left);
+            }
+            Expression::CallExpression(call) => {
+                self.expression(&call.function);
+                for arg in &call.arguments {
+                    self.expression(arg);
+                }
+            }
+            Expression::ArrayLiteral(al) => {
+                for element in &al.elements {
+                    self.expression(element);
+                }
+            }
+            Expression::IndexExpression(ie) => {
+                self.expression(&ie.left);
+                self.expression(&ie.index);
+            }
+            Expression::ObjectLiteral(ol) => {
+                for (_, value) in &ol.properties {
+                    self.expression(value);
+                }
+            }
+            Expression::PropertyAccess(pa) => self.expression(&pa.object),
+            Expression::Publish(pe) => {
+                for arg in &pe.args {
+                    self.expression(arg);
+                }
+            }
+            Expression::New(ne) => {
+                for arg in &ne.arguments {
+                    self.expression(arg);
+                }
+            }
+            Expression::Spread(se) => self.expression(&se.value),
+        }
+    }
+}
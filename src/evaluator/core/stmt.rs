@@ -1,47 +1,143 @@
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
 use crate::ast::nodes::{
-    ClassStatement, ForStatement, FunctionStatement, NamespaceStatement, ReturnStatement,
-    TestStatement,
+    ClassStatement, DestructurePattern, DestructureStatement, ExportStatement, ForInStatement, ForStatement, FunctionStatement, NamespaceStatement,
+    ReturnStatement, SwitchStatement, TestStatement, UseStatement,
 };
 use crate::ast::{
-    BlockStatement, IfExpression, ImportStatement, LetStatement, Statement, WhileStatement,
+    BlockStatement, IfExpression, ImportStatement, LetStatement, Program, Statement,
+    WhileStatement,
 };
-use crate::env::{new_enclosed_env, new_env, register_subscription, EnvRef};
+use crate::env::{is_builtin_namespace, new_enclosed_env, register_subscription, EnvBuilder, EnvRef};
+use crate::debug::debugger;
+use crate::{builtins, event_loop, package};
 use crate::lexer::Lexer;
-use crate::object::Object;
+use crate::object::{FunctionData, Object, ObjectMap};
 use crate::parser::Parser;
 
-use super::expr::{eval_expression, is_truthy};
+use super::expr::{eval_expression, eval_identifier, is_truthy};
+use super::{budget, propagate};
 
 pub(super) fn eval_statement(stmt: &Statement, env: EnvRef) -> Object {
+    // A pending SIGINT/SIGTERM is handled between statements.
+    event_loop::check_signal(&env);
+    if let Some(err) = budget::charge() {
+        return err;
+    }
+    if let Some(unwind) = propagate::unwind() {
+        return unwind;
+    }
+
     match stmt {
         Statement::Let(ls) => eval_let_statement(ls, Rc::clone(&env)),
         Statement::Return(rs) => eval_return_statement(rs, Rc::clone(&env)),
         Statement::While(ws) => eval_while_statement(ws, Rc::clone(&env)),
         Statement::For(fs) => eval_for_statement(fs, Rc::clone(&env)),
+        Statement::ForIn(fs) => eval_for_in_statement(fs, Rc::clone(&env)),
+        Statement::Switch(ss) => eval_switch_statement(ss, Rc::clone(&env)),
         Statement::Expression(es) => eval_expression(&es.expression, Rc::clone(&env)),
         Statement::Function(fs) => eval_function_statement(fs, Rc::clone(&env)),
         Statement::Test(ts) => eval_test_statement(ts, Rc::clone(&env)),
+        // Like `test` blocks, groups and hooks only run under the test runner.
+        Statement::Describe(_) | Statement::Hook(_) => Object::Null,
         Statement::Namespace(ns) => eval_namespace_statement(ns, Rc::clone(&env)),
         Statement::Import(is) => eval_import_statement(is, Rc::clone(&env)),
+        Statement::Use(us) => eval_use_statement(us, Rc::clone(&env)),
+        Statement::Export(es) => eval_export_statement(es, Rc::clone(&env)),
         Statement::Class(cs) => eval_class_statement(cs, Rc::clone(&env)),
+        Statement::Destructure(ds) => eval_destructure_statement(ds, Rc::clone(&env)),
+        // Generator bodies handle `yield` themselves (see generator.rs).
+        Statement::Yield(_) => {
+            Object::error("yield can only be used inside a generator function (fn*)")
+        }
+        // The debugger pauses on these before they run (see debug::debugger).
+        Statement::Breakpoint => Object::Null,
     }
 }
 
 fn eval_let_statement(ls: &LetStatement, env: EnvRef) -> Object {
+    // A `const` may run again (in a loop body, say), but `let` can't rebind one.
+    if !ls.constant && let Some(err) = redeclared_const(&ls.name.value, &env) {
+        return err;
+    }
     let val = eval_expression(&ls.value, Rc::clone(&env));
-    env.borrow_mut().set(ls.name.value.clone(), val.clone());
+    if ls.constant {
+        env.borrow_mut().set_const(ls.name.value.clone(), val);
+    } else {
+        env.borrow_mut().set(ls.name.value.clone(), val);
+    }
     // let itself doesn't produce a useful value
     Object::Null
 }
 
+/// The error for declaring `name` again in a scope where it is a `const`.
+fn redeclared_const(name: &str, env: &EnvRef) -> Option<Object> {
+    if env.borrow().is_local_const(name) {
+        return Some(Object::error(format!("cannot redeclare constant {}", name)));
+    }
+    None
+}
+
+fn eval_destructure_statement(ds: &DestructureStatement, env: EnvRef) -> Object {
+    let val = eval_expression(&ds.value, Rc::clone(&env));
+    if val.is_error() {
+        return val;
+    }
+
+    let mut bindings: Vec<(String, Object)> = Vec::new();
+    match (&ds.pattern, val) {
+        (DestructurePattern::Array { names, rest }, Object::Array(elements)) => {
+            // Missing elements bind null, as missing call arguments do.
+            for (i, name) in names.iter().enumerate() {
+                let value = elements.get(i).cloned().unwrap_or(Object::Null);
+                bindings.push((name.value.clone(), value));
+            }
+            if let Some(rest) = rest {
+                let remaining = elements.get(names.len()..).unwrap_or(&[]).to_vec();
+                bindings.push((rest.value.clone(), Object::array(remaining)));
+            }
+        }
+        (DestructurePattern::Object { names }, Object::Object(map)) => {
+            for name in names {
+                let value = map.get(&name.value).cloned().unwrap_or(Object::Null);
+                bindings.push((name.value.clone(), value));
+            }
+        }
+        (DestructurePattern::Array { .. }, other) => {
+            return Object::error(format!(
+                "cannot destructure {} with an array pattern",
+                other.type_name()
+            ));
+        }
+        (DestructurePattern::Object { .. }, other) => {
+            return Object::error(format!(
+                "cannot destructure {} with an object pattern",
+                other.type_name()
+            ));
+        }
+    }
+
+    if let Some(err) = bindings.iter().find_map(|(name, _)| redeclared_const(name, &env)) {
+        return err;
+    }
+    let mut env_mut = env.borrow_mut();
+    for (name, value) in bindings {
+        env_mut.set(name, value);
+    }
+    Object::Null
+}
+
 pub(super) fn eval_block_statement(block: &BlockStatement, env: EnvRef) -> Object {
     let mut result = Object::Null;
 
-    for stmt in &block.statements {
+    for (index, stmt) in block.statements.iter().enumerate() {
+        if debugger::is_active()
+            && let Some(quit) = debugger::before_statement(stmt, block.lines.get(index).copied(), &env)
+        {
+            return quit;
+        }
         result = eval_statement(stmt, Rc::clone(&env));
 
         if let Object::ReturnValue(_) = result {
@@ -76,6 +172,12 @@ fn eval_while_statement(ws: &WhileStatement, env: EnvRef) -> Object {
     let mut result = Object::Null;
 
     loop {
+        if let Some(err) = budget::charge() {
+            return err;
+        }
+        if let Some(unwind) = propagate::unwind() {
+            return unwind;
+        }
         let cond = eval_expression(&ws.condition, Rc::clone(&env));
         if !is_truthy(&cond) {
             break;
@@ -104,6 +206,13 @@ fn eval_for_statement(fs: &ForStatement, env: EnvRef) -> Object {
     let mut result = Object::Null;
 
     loop {
+        if let Some(err) = budget::charge() {
+            return err;
+        }
+        if let Some(unwind) = propagate::unwind() {
+            return unwind;
+        }
+
         // condition
         if let Some(cond_expr) = &fs.condition {
             let cond = eval_expression(cond_expr, Rc::clone(&env));
@@ -130,13 +239,108 @@ fn eval_for_statement(fs: &ForStatement, env: EnvRef) -> Object {
     result
 }
 
+fn eval_for_in_statement(fs: &ForInStatement, env: EnvRef) -> Object {
+    let iterable = eval_expression(&fs.iterable, Rc::clone(&env));
+    let values = match for_in_values(iterable, &env) {
+        Ok(values) => values,
+        Err(err) => return err,
+    };
+
+    let mut result = Object::Null;
+    for value in values {
+        if let Some(err) = budget::charge() {
+            return err;
+        }
+        if let Some(unwind) = propagate::unwind() {
+            return unwind;
+        }
+        if value.is_error() {
+            return value;
+        }
+        env.borrow_mut().set(fs.name.value.clone(), value);
+
+        result = eval_block_statement(&fs.body, Rc::clone(&env));
+        if let Object::ReturnValue(_) = result {
+            return result;
+        }
+        if result.is_error() {
+            return result;
+        }
+    }
+
+    result
+}
+
+/// The values a `for (x in ...)` loop walks: an array's elements or a seq's
+/// values, pulled one at a time.
+pub(super) fn for_in_values(
+    iterable: Object,
+    env: &EnvRef,
+) -> Result<Box<dyn Iterator<Item = Object>>, Object> {
+    match iterable {
+        Object::Array(items) => Ok(Box::new((0..items.len()).map(move |i| items[i].clone()))),
+        Object::Seq(seq) => Ok(builtins::native::seq_builtins::iterate(&seq, env)),
+        err @ Object::Error(_) => Err(err),
+        other => Err(Object::error(format!(
+            "for-in expects an array or seq, got {}",
+            other.type_name()
+        ))),
+    }
+}
+
+fn eval_switch_statement(ss: &SwitchStatement, env: EnvRef) -> Object {
+    let subject = eval_expression(&ss.subject, Rc::clone(&env));
+    if subject.is_error() {
+        return subject;
+    }
+
+    // Case values are evaluated in order, stopping at the first match.
+    for case in &ss.cases {
+        for value in &case.values {
+            let value = eval_expression(value, Rc::clone(&env));
+            if value.is_error() {
+                return value;
+            }
+            if switch_matches(&subject, &value) {
+                return eval_block_statement(&case.body, Rc::clone(&env));
+            }
+        }
+    }
+
+    match &ss.default {
+        Some(body) => eval_block_statement(body, Rc::clone(&env)),
+        None => Object::Null,
+    }
+}
+
+/// Case matching: numbers compare by value across int and float, anything
+/// else must be the same type and equal. Unlike `==`, a case of a different
+/// type is simply not a match rather than an error.
+pub(super) fn switch_matches(subject: &Object, value: &Object) -> bool {
+    match (subject, value) {
+        (Object::Integer(a), Object::Float(b)) | (Object::Float(b), Object::Integer(a)) => {
+            *a as f64 == *b
+        }
+        _ => subject == value,
+    }
+}
+
 fn eval_function_statement(fs: &FunctionStatement, env: EnvRef) -> Object {
+    if let Some(err) = redeclared_const(&fs.name.value, &env) {
+        return err;
+    }
     // Build the same Object::Function your eval_function_literal creates
-    let func_obj = Object::Function {
+    let func_obj = Object::Function(Rc::new(FunctionData {
+        name: Some(fs.name.value.clone()),
         params: fs.literal.params.clone(),
+        defaults: fs.literal.defaults.clone(),
+        rest: fs.literal.rest.clone(),
+        requires: fs.literal.requires.clone(),
+        ensures: fs.literal.ensures.clone(),
         body: fs.literal.body.clone(),
         env: Rc::clone(&env), // capture defining env for closures/recursion
-    };
+        generator: fs.literal.generator,
+    }));
 
     env.borrow_mut()
         .set(fs.name.value.clone(), func_obj.clone());
@@ -160,6 +364,18 @@ fn eval_test_statement(_ts: &TestStatement, _env: EnvRef) -> Object {
 fn eval_namespace_statement(ns: &NamespaceStatement, env: EnvRef) -> Object {
     // Evaluate within an enclosed environment to avoid leaking locals.
     let ns_env = new_enclosed_env(Rc::clone(&env));
+    // Re-opening a namespace (including a builtin one) starts from the members
+    // it already has, so the body adds to them and can use them unqualified.
+    let existing = env.borrow().get(&ns.name.value);
+    if let Some(Object::Object(members)) = existing {
+        let mut ns_mut = ns_env.borrow_mut();
+        for (name, value) in members.iter() {
+            ns_mut.set(name.clone(), value.clone());
+        }
+        for name in env.borrow().const_members(&ns.name.value) {
+            ns_mut.mark_const(name);
+        }
+    }
     let result = eval_block_statement(&ns.body, Rc::clone(&ns_env));
 
     if result.is_error() {
@@ -170,21 +386,33 @@ fn eval_namespace_statement(ns: &NamespaceStatement, env: EnvRef) -> Object {
     }
 
     let exported = ns_env.borrow().snapshot();
-    env.borrow_mut()
-        .set(ns.name.value.clone(), Object::Object(exported));
+    let mut env_mut = env.borrow_mut();
+    env_mut.set(ns.name.value.clone(), Object::from_map(exported));
+    for name in ns_env.borrow().constants() {
+        env_mut.mark_const(format!("{}::{}", ns.name.value, name));
+    }
 
     Object::Null
 }
 
 fn eval_class_statement(cs: &ClassStatement, env: EnvRef) -> Object {
-    let mut methods = std::collections::HashMap::new();
+    if let Some(err) = redeclared_const(&cs.name.value, &env) {
+        return err;
+    }
+    let mut methods = ObjectMap::new();
 
     for method in &cs.methods {
-        let func_obj = Object::Function {
+        let func_obj = Object::Function(Rc::new(FunctionData {
+            name: Some(format!("{}.{}", cs.name.value, method.name.value)),
             params: method.literal.params.clone(),
+            defaults: method.literal.defaults.clone(),
+            rest: method.literal.rest.clone(),
+            requires: method.literal.requires.clone(),
+            ensures: method.literal.ensures.clone(),
             body: method.literal.body.clone(),
             env: Rc::clone(&env), // Capture class definition environment
-        };
+            generator: method.literal.generator,
+        }));
         methods.insert(method.name.value.clone(), func_obj);
     }
 
@@ -198,7 +426,53 @@ fn eval_class_statement(cs: &ClassStatement, env: EnvRef) -> Object {
     Object::Null
 }
 
+/// Where a relative import lives: next to the importer if it exists there,
+/// otherwise in the first of the environment's import paths and then the
+/// `SLANG_PATH` directories that has it. `./` and `../` paths are only looked
+/// up next to the importer. When no candidate exists the importer-relative
+/// path is returned, so the read error names it.
+fn search_import_paths(path: &Path, base: &Path, env: &EnvRef) -> PathBuf {
+    let local = base.join(path);
+    if local.exists() || path.starts_with(".") || path.starts_with("..") {
+        return local;
+    }
+    let slang_path = std::env::var_os("SLANG_PATH").unwrap_or_default();
+    let search = env.borrow().import_paths().to_vec();
+    search
+        .into_iter()
+        .chain(std::env::split_paths(&slang_path))
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .map(|dir| dir.join(path))
+        .find(|candidate| candidate.exists())
+        .unwrap_or(local)
+}
+
+/// `circular import: a.sl -> b.sl -> a.sl`, from the first import of `path`
+/// in `chain` round to `path` again.
+fn circular_import_message(chain: &[PathBuf], path: &Path) -> String {
+    let start = chain.iter().position(|p| p == path).unwrap_or(0);
+    let name = |p: &Path| {
+        p.file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| p.display().to_string())
+    };
+    let cycle: Vec<String> = chain[start..]
+        .iter()
+        .map(|p| name(p))
+        .chain(std::iter::once(name(path)))
+        .collect();
+    format!("circular import: {}", cycle.join(" -> "))
+}
+
 fn eval_import_statement(is: &ImportStatement, env: EnvRef) -> Object {
+    let capabilities = env.borrow().capabilities();
+    if !capabilities.fs {
+        return Object::error(format!(
+            "cannot import '{}': filesystem access is disabled in this environment",
+            is.path
+        ));
+    }
+
     let path = Path::new(&is.path);
     let resolved = if path.is_absolute() {
         path.to_path_buf()
@@ -207,14 +481,21 @@ fn eval_import_statement(is: &ImportStatement, env: EnvRef) -> Object {
             .borrow()
             .module_dir()
             .or_else(|| std::env::current_dir().ok());
-        match base_dir {
-            Some(base) => base.join(path),
+        let base = match base_dir {
+            Some(base) => base,
             None => return Object::error("unable to resolve import: no base directory"),
+        };
+        match is.path.strip_prefix("pkg:") {
+            Some(spec) => match package::resolve_import(spec, &base) {
+                Ok(p) => p,
+                Err(e) => return Object::error(e),
+            },
+            None => search_import_paths(path, &base, &env),
         }
     };
 
-    let source = match fs::read_to_string(&resolved) {
-        Ok(s) => s,
+    let canonical = match fs::canonicalize(&resolved) {
+        Ok(p) => p,
         Err(err) => {
             return Object::error(format!(
                 "failed to read import '{}': {}",
@@ -224,36 +505,212 @@ fn eval_import_statement(is: &ImportStatement, env: EnvRef) -> Object {
         }
     };
 
+    // Each module is evaluated at most once per program; later imports reuse
+    // the public bindings produced by the first evaluation.
+    let cache = env.borrow().module_cache();
+    let cached = cache.borrow().get(&canonical).cloned();
+    let bindings = match cached {
+        Some(bindings) => bindings,
+        None if env.borrow().import_chain().contains(&canonical) => {
+            return Object::error(circular_import_message(env.borrow().import_chain(), &canonical))
+        }
+        None => match load_module(&canonical, &env) {
+            Ok(bindings) => {
+                cache.borrow_mut().insert(canonical.clone(), bindings.clone());
+                bindings
+            }
+            Err(err) => return err,
+        },
+    };
+
+    if is.names.is_empty() {
+        for (name, value) in bindings {
+            match value {
+                Object::Object(ns_obj) => {
+                    merge_namespace_into_env(&name, Rc::unwrap_or_clone(ns_obj), Rc::clone(&env))
+                }
+                other => env.borrow_mut().set(name, other),
+            }
+        }
+        return Object::Null;
+    }
+
+    for name in &is.names {
+        match bindings.get(&name.value) {
+            Some(value) => env.borrow_mut().set(name.value.clone(), value.clone()),
+            None => {
+                return Object::error(format!(
+                    "module '{}' does not export '{}'",
+                    is.path, name.value
+                ))
+            }
+        }
+    }
+
+    Object::Null
+}
+
+fn eval_use_statement(us: &UseStatement, env: EnvRef) -> Object {
+    let mut value = eval_identifier(&us.path[0], Rc::clone(&env));
+    if value.is_error() {
+        return value;
+    }
+    let mut path = us.path[0].value.clone();
+    for name in &us.path[1..] {
+        let member = match &value {
+            Object::Object(members) => members.get(&name.value).cloned(),
+            _ => None,
+        };
+        value = match member {
+            Some(member) => member,
+            None => return Object::error(format!("{}::{} is not a member of {}", path, name.value, path)),
+        };
+        path = format!("{}::{}", path, name.value);
+    }
+
+    // Constants stay constant under their new names.
+    let root = &us.path[0].value;
+    let relative = path.strip_prefix(root.as_str()).unwrap_or("").trim_start_matches("::");
+    let const_members = env.borrow().const_members(root);
+    let is_const = if relative.is_empty() {
+        env.borrow().is_const(root)
+    } else {
+        const_members.iter().any(|name| name == relative)
+    };
+    let prefix = if relative.is_empty() { String::new() } else { format!("{}::", relative) };
+    let inner: Vec<&str> = const_members.iter().filter_map(|name| name.strip_prefix(&prefix)).collect();
+
+    let Some(binding) = us.binding() else {
+        // `use A::*`
+        let Object::Object(members) = value else {
+            return Object::error(format!("use {}::* expects a namespace, got {}", path, value.type_name()));
+        };
+        if let Some(err) = members.keys().find_map(|name| redeclared_const(name, &env)) {
+            return err;
+        }
+        let mut env_mut = env.borrow_mut();
+        for (name, member) in members.iter() {
+            env_mut.set(name.clone(), member.clone());
+        }
+        for name in inner {
+            env_mut.mark_const(name.to_string());
+        }
+        return Object::Null;
+    };
+
+    if let Some(err) = redeclared_const(&binding.value, &env) {
+        return err;
+    }
+    let mut env_mut = env.borrow_mut();
+    env_mut.set(binding.value.clone(), value);
+    if is_const {
+        env_mut.mark_const(binding.value.clone());
+    }
+    for name in inner {
+        env_mut.mark_const(format!("{}::{}", binding.value, name));
+    }
+    Object::Null
+}
+
+/// Evaluate the module at `path` in a fresh environment and collect its public bindings.
+fn load_module(path: &Path, importer: &EnvRef) -> Result<ObjectMap, Object> {
+    #[cfg(feature = "cache")]
+    let program = match crate::cache::load(path) {
+        Some(program) => program,
+        None => parse_module(path)?,
+    };
+    #[cfg(not(feature = "cache"))]
+    let program = parse_module(path)?;
+
+    let mut builder = EnvBuilder::new().capabilities(importer.borrow().capabilities());
+    if !importer.borrow().has_prelude() {
+        builder = builder.without_prelude();
+    }
+    let module_env = builder.build();
+    {
+        let mut module_mut = module_env.borrow_mut();
+        module_mut.set_module_dir(path.parent().map(|p| p.to_path_buf()));
+        module_mut.set_module_cache(importer.borrow().module_cache());
+        let mut chain = importer.borrow().import_chain().to_vec();
+        chain.push(path.to_path_buf());
+        module_mut.set_import_chain(chain);
+        for dir in importer.borrow().import_paths() {
+            module_mut.add_import_path(dir.clone());
+        }
+    }
+    // Module code has its own line numbers, so the debugger runs it without stopping.
+    let eval_result = debugger::suspended(|| crate::evaluator::eval(&program, Rc::clone(&module_env)));
+    if eval_result.is_error() {
+        return Err(eval_result);
+    }
+
+    let module = module_env.borrow();
+    let store = module.snapshot();
+
+    // Modules without any `export` keep the original behaviour: only namespaces are public.
+    if module.exports().is_empty() {
+        return Ok(store
+            .into_iter()
+            .filter(|(name, value)| !is_builtin_namespace(name) && matches!(value, Object::Object(_)))
+            .collect());
+    }
+
+    let mut bindings = ObjectMap::new();
+    for name in module.exports() {
+        match store.get(name) {
+            Some(value) => {
+                bindings.insert(name.clone(), value.clone());
+            }
+            None => {
+                return Err(Object::error(format!(
+                    "module '{}' exports '{}' but never defines it",
+                    path.display(),
+                    name
+                )))
+            }
+        }
+    }
+    Ok(bindings)
+}
+
+/// Read and parse a module from source, recording the AST in the on-disk cache.
+fn parse_module(path: &Path) -> Result<Program, Object> {
+    let source = fs::read_to_string(path).map_err(|err| {
+        Object::error(format!(
+            "failed to read import '{}': {}",
+            path.display(),
+            err
+        ))
+    })?;
+
     let lexer = Lexer::new(&source);
     let mut parser = Parser::new(lexer);
     let program = parser.parse_program();
 
     if !parser.errors.is_empty() {
-        return Object::error(format!(
+        return Err(Object::error(format!(
             "parse errors in import '{}': {:?}",
-            resolved.display(),
+            path.display(),
             parser.errors
-        ));
+        )));
     }
 
-    // Evaluate imported file in a fresh environment; only namespaces are exported.
-    let module_env = new_env();
-    let parent_dir = resolved.parent().map(|p| p.to_path_buf());
-    module_env.borrow_mut().set_module_dir(parent_dir);
-    let eval_result = crate::evaluator::eval(&program, Rc::clone(&module_env));
-    if eval_result.is_error() {
-        return eval_result;
-    }
+    #[cfg(feature = "cache")]
+    crate::cache::store(path, &source, &program);
+    Ok(program)
+}
 
-    let module_store = module_env.borrow().snapshot();
-    for (name, value) in module_store {
-        if is_builtin_namespace(&name) {
-            continue;
+fn eval_export_statement(es: &ExportStatement, env: EnvRef) -> Object {
+    if let Some(decl) = &es.declaration {
+        let result = eval_statement(decl, Rc::clone(&env));
+        if result.is_error() {
+            return result;
         }
+    }
 
-        if let Object::Object(ns_obj) = value {
-            merge_namespace_into_env(&name, ns_obj, Rc::clone(&env));
-        }
+    let mut env_mut = env.borrow_mut();
+    for name in &es.names {
+        env_mut.add_export(name.value.clone());
     }
 
     Object::Null
@@ -261,27 +718,20 @@ fn eval_import_statement(is: &ImportStatement, env: EnvRef) -> Object {
 
 fn merge_namespace_into_env(
     name: &str,
-    ns_obj: std::collections::HashMap<String, Object>,
+    ns_obj: ObjectMap,
     env: EnvRef,
 ) {
     let mut env_mut = env.borrow_mut();
     match env_mut.get(name) {
         Some(Object::Object(existing)) => {
-            let mut merged = existing.clone();
+            let mut merged = Rc::unwrap_or_clone(existing);
             for (k, v) in ns_obj {
                 merged.insert(k, v);
             }
-            env_mut.set(name.to_string(), Object::Object(merged));
+            env_mut.set(name.to_string(), Object::from_map(merged));
         }
         _ => {
-            env_mut.set(name.to_string(), Object::Object(ns_obj));
+            env_mut.set(name.to_string(), Object::from_map(ns_obj));
         }
     }
 }
-
-fn is_builtin_namespace(name: &str) -> bool {
-    matches!(
-        name,
-        "Option" | "Result" | "Regex" | "File" | "Array" | "Math" | "String" | "Json" | "Test"
-    )
-}
@@ -483,6 +483,104 @@ fn test_array_group_by() {
     }
 }
 
+#[test]
+fn test_array_count_by_and_frequencies() {
+    let input = r#"
+        let items = [
+            { name: "apple", type: "fruit" },
+            { name: "carrot", type: "vegetable" },
+            { name: "banana", type: "fruit" }
+        ];
+
+        let byType = Array::countBy(items, fn(item) { item.type; });
+        let byLength = Array::countBy(["a", "bb", "cc"], fn(s) { len(s); });
+        let freq = Array::frequencies(["x", "y", "x", "x"]);
+        let flags = Array::frequencies([true, false, true]);
+        let empty = Array::frequencies([]);
+        // Keys are strings, so values with the same text are counted together.
+        let mixed = Array::frequencies([1, "1", true, "true"]);
+
+        [byType.fruit, byType.vegetable, byLength["1"], byLength["2"], freq.x, freq.y, flags["true"], len(Object::keys(empty)), mixed["1"], mixed["true"], len(Object::keys(mixed))];
+    "#;
+
+    match eval_input(input) {
+        Object::Array(vals) => {
+            let expected: Vec<Object> = [2, 1, 1, 2, 3, 1, 2, 0, 2, 2, 2].into_iter().map(Object::Integer).collect();
+            assert_eq!(*vals, expected);
+        }
+        other => panic!("expected array from Array::countBy test, got {:?}", other),
+    }
+
+    let errors = vec![
+        (
+            "Array::countBy([1], fn(x) { [x]; });",
+            "Array::countBy key function must return string/int/bool, got Array([Integer(1)])",
+        ),
+        (
+            "Array::frequencies([1.5]);",
+            "Array::frequencies expects string/int/bool elements, got Float(1.5)",
+        ),
+        ("Array::frequencies(1);", "Array::frequencies expects an array, got Integer(1)"),
+    ];
+    for (input, expected) in errors {
+        match eval_input(input) {
+            Object::Error(msg) => assert_eq!(msg, expected),
+            other => panic!("expected error for {}, got {:?}", input, other),
+        }
+    }
+}
+
+#[test]
+fn test_array_min_max_sum_by() {
+    let tests = vec![
+        (
+            r#"let people = [{ name: "ann", age: 31 }, { name: "bob", age: 25 }, { name: "cy", age: 25 }];
+               Option::unwrapOr(Array::minBy(people, fn(p) { p.age; }), {}).name;"#,
+            "\"bob\"",
+        ),
+        (
+            r#"let people = [{ name: "ann", age: 31 }, { name: "bob", age: 25 }];
+               Option::unwrapOr(Array::maxBy(people, fn(p) { p.age; }), {}).name;"#,
+            "\"ann\"",
+        ),
+        (r#"Array::maxBy(["pear", "fig", "apple"], fn(s) { s; });"#, r#"Some("pear")"#),
+        ("Array::minBy([3, 1.5, 2], fn(x) { x; });", "Some(1.5)"),
+        ("Array::minBy([], fn(x) { x; });", "None"),
+        (r#"Array::sumBy([{ n: 1 }, { n: 2 }, { n: 3 }], fn(o) { o.n; });"#, "6"),
+        ("Array::sumBy([1, 2], fn(x) { x * 1.5; });", "4.5"),
+        ("Array::sumBy([], fn(x) { x; });", "0"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_input(input).to_string(), expected, "input: {}", input);
+    }
+
+    let errors = vec![
+        (
+            r#"Array::minBy([1, "a"], fn(x) { x; });"#,
+            "Array::minBy keys must be all numbers or all strings",
+        ),
+        (
+            "Array::maxBy([[1]], fn(x) { x; });",
+            "Array::maxBy key function must return a number or string, got Array([Integer(1)])",
+        ),
+        (
+            r#"Array::sumBy(["a"], fn(x) { x; });"#,
+            "Array::sumBy key function must return a number, got String(\"a\")",
+        ),
+        (
+            "Array::sumBy([1, 1], fn(x) { 9223372036854775807; });",
+            "integer overflow in Array::sumBy",
+        ),
+    ];
+    for (input, expected) in errors {
+        match eval_input(input) {
+            Object::Error(msg) => assert_eq!(msg, expected),
+            other => panic!("expected error for {}, got {:?}", input, other),
+        }
+    }
+}
+
 #[test]
 fn test_array_partition() {
     let input = r#"
@@ -607,3 +705,174 @@ fn test_array_extras_error_handling() {
         "Array::partition with non-bool predicate should error"
     );
 }
+
+#[test]
+fn test_array_first_last_rest_push() {
+    let input = r#"
+        let xs = [1, 2, 3];
+        [Array::first(xs), Array::last(xs), Array::rest(xs), Array::push(xs, 4), first(xs)];
+    "#;
+
+    let obj = eval_input(input);
+    assert_eq!(
+        obj,
+        Object::array(vec![
+            Object::Integer(1),
+            Object::Integer(3),
+            Object::array(vec![Object::Integer(2), Object::Integer(3)]),
+            Object::array(vec![
+                Object::Integer(1),
+                Object::Integer(2),
+                Object::Integer(3),
+                Object::Integer(4),
+            ]),
+            Object::Integer(1),
+        ])
+    );
+}
+
+#[test]
+fn test_array_binary_search_and_insert_sorted() {
+    let tests = vec![
+        ("Array::binarySearch([1, 3, 5, 7, 9], 7);", "Some(3)"),
+        ("Array::binarySearch([1, 3, 5, 7, 9], 4);", "None"),
+        ("Array::binarySearch([], 4);", "None"),
+        (r#"Array::binarySearch(["a", "c", "e"], "a");"#, "Some(0)"),
+        (
+            r#"
+            let people = [{ age: 20 }, { age: 31 }, { age: 45 }];
+            Array::binarySearchBy(people, fn(p) { p.age - 31; });
+            "#,
+            "Some(1)",
+        ),
+        ("Array::insertSorted([1, 3, 5], 4);", "[1, 3, 4, 5]"),
+        ("Array::insertSorted([1, 3, 5], 0);", "[0, 1, 3, 5]"),
+        ("Array::insertSorted([1, 3, 5], 9);", "[1, 3, 5, 9]"),
+        ("Array::insertSorted([], 1);", "[1]"),
+        ("[1, 2, 2, 3].insertSorted(2.5);", "[1, 2, 2, 2.5, 3]"),
+        (
+            r#"
+            let byKey = fn(a, b) { a.k - b.k; };
+            let xs = Array::insertSorted([{ k: 1, t: "a" }, { k: 2, t: "c" }], { k: 1, t: "b" }, byKey);
+            xs.map(fn(x) { x.t; });
+            "#,
+            r#"["a", "b", "c"]"#,
+        ),
+        (
+            "Array::insertSorted([5, 3, 1], 4, fn(a, b) { b - a; });",
+            "[5, 4, 3, 1]",
+        ),
+    ];
+    for (input, expected) in tests {
+        assert_eq!(eval_input(input).to_string(), expected, "input: {}", input);
+    }
+
+    let obj = eval_input(r#"Array::binarySearchBy([1, 2], fn(x) { "no"; });"#);
+    match obj {
+        Object::Error(msg) => assert!(msg.contains("comparator must return integer"), "{}", msg),
+        other => panic!("expected comparator error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_array_product_combinations_permutations() {
+    let tests = vec![
+        ("Array::product([1, 2], [\"a\", \"b\"]);", r#"[[1, "a"], [1, "b"], [2, "a"], [2, "b"]]"#),
+        ("Array::product([1, 2]);", "[[1], [2]]"),
+        ("Array::product([1, 2], [], [3]);", "[]"),
+        ("len(Seq::toArray(Array::product([1, 2], [3, 4, 5], [6, 7])));", "12"),
+        ("Array::combinations([1, 2, 3, 4], 2);", "[[1, 2], [1, 3], [1, 4], [2, 3], [2, 4], [3, 4]]"),
+        ("Array::combinations([1, 2, 3], 3);", "[[1, 2, 3]]"),
+        ("Array::combinations([1, 2], 0);", "[[]]"),
+        ("Array::combinations([1, 2], 3);", "[]"),
+        ("len(Seq::toArray(Array::combinations(Array::range(0, 10), 4)));", "210"),
+        ("Array::permutations([1, 2, 3]);", "[[1, 2, 3], [1, 3, 2], [2, 1, 3], [2, 3, 1], [3, 1, 2], [3, 2, 1]]"),
+        ("Array::permutations([1, 2, 3], 2);", "[[1, 2], [1, 3], [2, 1], [2, 3], [3, 1], [3, 2]]"),
+        ("Array::permutations([]);", "[[]]"),
+        ("Array::permutations([1], 2);", "[]"),
+        ("len(Seq::toArray(Array::permutations(Array::range(0, 6), 4)));", "360"),
+    ];
+
+    for (input, expected) in tests {
+        let input = match input.strip_prefix("len(") {
+            Some(_) => input.to_string(),
+            None => format!("Seq::toArray({});", input.trim_end_matches(';')),
+        };
+        assert_eq!(eval_input(&input).to_string(), expected, "input: {}", input);
+    }
+
+    // The results are lazy, so only the values taken are ever built.
+    let input = r#"
+        let xs = Array::range(0, 100);
+        [
+            Type::of(Array::combinations(xs, 3)),
+            Seq::toArray(Seq::take(Array::permutations(Array::range(0, 20)), 2)),
+            Seq::toArray(Seq::take(Array::product(xs, xs, xs, xs, xs), 1)),
+            Seq::toArray(Seq::take(Seq::drop(Array::combinations(xs, 50), 1), 1))[0][49]
+        ];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        "[\"seq\", [[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19], \
+         [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 19, 18]], [[0, 0, 0, 0, 0]], 50]"
+    );
+
+    let errors = vec![
+        ("Array::product();", "Array::product expects at least 1 array"),
+        ("Array::product([1], 2);", "Array::product expects arrays, got Integer(2)"),
+        ("Array::combinations([1], -1);", "Array::combinations size must not be negative, got -1"),
+        ("Array::permutations([1], \"2\");", "Array::permutations expects an integer size, got String(\"2\")"),
+    ];
+    for (input, expected) in errors {
+        match eval_input(input) {
+            Object::Error(msg) => assert_eq!(msg, expected),
+            other => panic!("expected error for {}, got {:?}", input, other),
+        }
+    }
+}
+
+#[test]
+fn test_array_take_while_and_drop_while() {
+    let input = r#"
+        let xs = [2, 4, 5, 6];
+        let even = fn(x) { x % 2 == 0 };
+        [
+            Array::takeWhile(xs, even),
+            xs.dropWhile(even),
+            Array::skipWhile(xs, fn(x) { true }),
+            Array::takeWhile([], even)
+        ];
+    "#;
+    assert_eq!(eval_input(input).to_string(), "[[2, 4], [5, 6], [], []]");
+}
+
+#[test]
+fn test_array_par_map_and_par_filter() {
+    let tests = vec![
+        ("Array::parMap([1, 2, 3, 4], fn(x) { x * x; });", "[1, 4, 9, 16]"),
+        ("Array::parFilter([1, 2, 3, 4, 5], fn(x) { x % 2 == 1; });", "[1, 3, 5]"),
+        ("Array::parMap([], fn(x) { x; });", "[]"),
+        ("Array::parMap([\"a\", \"b\"], String::toUpper);", "[\"A\", \"B\"]"),
+        // A callback that can see a ref runs in order on this thread.
+        (
+            "let seen = Ref::new([]); Array::parMap([1, 2], fn(x) { Ref::set(seen, Array::push(Ref::get(seen), x)); x; }); Ref::get(seen);",
+            "[1, 2]",
+        ),
+    ];
+    for (input, expected) in tests {
+        assert_eq!(eval_input(input).to_string(), expected, "input: {}", input);
+    }
+
+    let errors = vec![
+        ("Array::parMap([1]);", "Array::parMap expects exactly 2 arguments (array, fn)"),
+        ("Array::parFilter(1, fn(x) { x; });", "Array::parFilter expects an Array value as first argument, got Integer(1)"),
+        ("Array::parFilter([1, 2], fn(x) { x; });", "Array::parFilter predicate must return boolean, got Integer(1)"),
+        ("Array::parMap([1, 2], fn(x) { nope; });", "identifier not found: nope"),
+    ];
+    for (input, expected) in errors {
+        match eval_input(input) {
+            Object::Error(msg) => assert_eq!(msg, expected, "input: {}", input),
+            other => panic!("expected error for {}, got {:?}", input, other),
+        }
+    }
+}
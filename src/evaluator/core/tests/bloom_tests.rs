@@ -0,0 +1,66 @@
+use crate::object::Object;
+use crate::test_support::eval_input;
+
+#[test]
+fn test_bloom_add_and_might_contain() {
+    let input = r#"
+        let seen = Bloom::new(100, 0.01);
+        let first = Bloom::add(seen, "GET /index");
+        let again = Bloom::add(seen, "GET /index");
+        [first, again, Bloom::mightContain(seen, "GET /index"), Bloom::mightContain(seen, "GET /other")];
+    "#;
+    assert_eq!(
+        eval_input(input),
+        Object::array(vec![
+            Object::Boolean(true),
+            Object::Boolean(false),
+            Object::Boolean(true),
+            Object::Boolean(false),
+        ])
+    );
+}
+
+#[test]
+fn test_bloom_is_shared_by_reference_and_supports_methods() {
+    // Adding through a second binding is visible through the first, and the
+    // item's type is part of its identity.
+    let input = r#"
+        let a = Bloom::new(10, 0.01);
+        let b = a;
+        b.add(1);
+        [a.mightContain(1), a.mightContain("1"), Type::of(a)];
+    "#;
+    assert_eq!(
+        eval_input(input),
+        Object::array(vec![
+            Object::Boolean(true),
+            Object::Boolean(false),
+            Object::String("bloom".to_string()),
+        ])
+    );
+}
+
+#[test]
+fn test_bloom_argument_errors() {
+    let cases = [
+        ("Bloom::new(0, 0.01);", "Bloom::new expectedItems must be positive"),
+        (
+            "Bloom::new(10, 1.5);",
+            "Bloom::new falsePositiveRate must be between 0 and 1 (exclusive)",
+        ),
+        (
+            "Bloom::add([], 1);",
+            "Bloom::add expects a bloom filter as first argument, got array",
+        ),
+        (
+            "Bloom::add(Bloom::new(10, 0.1), [1]);",
+            "Bloom::add expects a string, number or boolean item, got array",
+        ),
+    ];
+    for (input, expected) in cases {
+        match eval_input(input) {
+            Object::Error(msg) => assert_eq!(msg, expected, "input: {}", input),
+            other => panic!("expected error for {}, got {:?}", input, other),
+        }
+    }
+}
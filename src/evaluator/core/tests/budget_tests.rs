@@ -0,0 +1,102 @@
+use std::time::{Duration, Instant};
+
+use crate::ast::Program;
+use crate::env::new_env;
+use crate::evaluator::{EvalAbort, EvalOptions, eval_with_options};
+use crate::lexer::Lexer;
+use crate::object::Object;
+use crate::parser::Parser;
+use crate::test_support::check_errors;
+
+fn parse(input: &str) -> Program {
+    let mut p = Parser::new(Lexer::new(input));
+    let program = p.parse_program();
+    check_errors(&p);
+    program
+}
+
+fn steps(max: u64) -> EvalOptions {
+    EvalOptions { max_steps: Some(max), ..EvalOptions::default() }
+}
+
+#[test]
+fn test_eval_within_budget_returns_result() {
+    let program = parse("let total = 0; for (let i = 0; i < 10; i++) { total = total + i; } total;");
+    assert_eq!(eval_with_options(&program, new_env(), steps(1_000)), Ok(Object::Integer(45)));
+    assert_eq!(
+        eval_with_options(&program, new_env(), EvalOptions::default()),
+        Ok(Object::Integer(45))
+    );
+}
+
+#[test]
+fn test_step_limit_aborts_infinite_loops() {
+    let program = parse("let x = 0; while (true) { x = x + 1; }");
+    assert_eq!(
+        eval_with_options(&program, new_env(), steps(1_000)),
+        Err(EvalAbort::StepLimit(1_000))
+    );
+
+    // An empty body still costs a step per iteration.
+    let program = parse("while (true) { }");
+    assert_eq!(
+        eval_with_options(&program, new_env(), steps(50)),
+        Err(EvalAbort::StepLimit(50))
+    );
+}
+
+#[test]
+fn test_step_limit_covers_callbacks_and_recursion() {
+    let program = parse(
+        "function spin(n) { if (n > 0) { spin(n - 1); } else { 0; } }
+         Array::map(Array::range(0, 100), fn(x) { spin(50); });",
+    );
+    assert_eq!(
+        eval_with_options(&program, new_env(), steps(500)),
+        Err(EvalAbort::StepLimit(500))
+    );
+}
+
+#[test]
+fn test_timeout_aborts_evaluation() {
+    let limit = Duration::from_millis(20);
+    let program = parse("while (true) { 1; }");
+    let options = EvalOptions { timeout: Some(limit), ..EvalOptions::default() };
+    assert_eq!(eval_with_options(&program, new_env(), options), Err(EvalAbort::Timeout(limit)));
+}
+
+#[test]
+fn test_timeout_covers_slow_statements() {
+    let limit = Duration::from_millis(150);
+    let options = EvalOptions { timeout: Some(limit), ..EvalOptions::default() };
+
+    // A handful of statements that each block: the step count stays tiny, so
+    // only the clock can stop it, and it has to stop mid-sleep.
+    let program = parse("for (let i = 0; i < 20; i++) { Time::sleep(100); } 1;");
+    let started = Instant::now();
+    assert_eq!(eval_with_options(&program, new_env(), options), Err(EvalAbort::Timeout(limit)));
+    assert!(started.elapsed() < Duration::from_millis(1_000), "took {:?}", started.elapsed());
+
+    let program = parse("Timer::after(2000, fn() { 1; }); Runtime::run();");
+    let started = Instant::now();
+    assert_eq!(eval_with_options(&program, new_env(), options), Err(EvalAbort::Timeout(limit)));
+    assert!(started.elapsed() < Duration::from_millis(1_000), "took {:?}", started.elapsed());
+}
+
+#[test]
+fn test_script_errors_are_not_aborts() {
+    let program = parse("1 + \"a\";");
+    let result = eval_with_options(&program, new_env(), steps(100)).unwrap();
+    assert!(result.is_error());
+}
+
+#[test]
+fn test_budget_ends_with_the_evaluation() {
+    let program = parse("let i = 0; while (i < 100) { i = i + 1; } i;");
+    assert!(eval_with_options(&program, new_env(), steps(10)).is_err());
+    assert_eq!(crate::evaluator::eval(&program, new_env()), Object::Integer(100));
+    assert_eq!(
+        EvalAbort::StepLimit(10).to_string(),
+        "evaluation aborted: step limit of 10 exceeded"
+    );
+}
@@ -0,0 +1,68 @@
+use crate::object::Object;
+use crate::test_support::eval_input;
+
+#[test]
+fn test_bytes_string_conversions() {
+    let input = r#"
+        let utf8 = Bytes::fromString("héllo");
+        let latin = Bytes::fromString("héllo", "latin1");
+        [
+            utf8,
+            latin,
+            Bytes::toString(utf8),
+            Bytes::toString(latin, "latin1"),
+            Bytes::toString(latin),
+            Type::of(utf8)
+        ];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        r#"[b"h\xc3\xa9llo", b"h\xe9llo", Ok("héllo"), Ok("héllo"), Err("failed to decode UTF-8: invalid utf-8 sequence of 1 bytes from index 1"), "bytes"]"#
+    );
+
+    assert!(eval_input(r#"Bytes::fromString("€", "latin1");"#).is_error());
+    assert!(eval_input(r#"Bytes::toString(b"x", "ebcdic");"#).is_error());
+    assert!(eval_input(r#"Bytes::toString("x");"#).is_error());
+}
+
+#[test]
+fn test_bytes_len_slice_and_concat() {
+    let input = r#"
+        let data = b"\x00\x01\x02\x03\x04";
+        [
+            Bytes::len(data),
+            Bytes::slice(data, 1, 3),
+            Bytes::slice(data, -2),
+            Bytes::slice(data, 4, 1),
+            Bytes::concat(b"ab", b"", b"\xff"),
+            data.slice(0, 2) == b"\x00\x01",
+            data.len(),
+            data[-1]
+        ];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        r#"[5, b"\x01\x02", b"\x03\x04", b"", b"ab\xff", true, 5, 4]"#
+    );
+
+    let obj = eval_input(r#"Bytes::concat(b"a", "b");"#);
+    assert!(obj.is_error(), "expected error, got {:?}", obj);
+}
+
+#[test]
+fn test_bytes_array_conversions() {
+    let input = r#"
+        let data = Bytes::fromArray([104, 105, 0, 255]);
+        [data, Bytes::toArray(data), Bytes::toArray(b"")];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        r#"[b"hi\x00\xff", [104, 105, 0, 255], []]"#
+    );
+
+    let obj = eval_input("Bytes::fromArray([1, 256]);");
+    match obj {
+        Object::Error(msg) => assert!(msg.contains("0 to 255"), "{}", msg),
+        other => panic!("expected error for out-of-range byte, got {:?}", other),
+    }
+}
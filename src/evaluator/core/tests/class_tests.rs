@@ -273,3 +273,22 @@ fn test_class_with_complex_method() {
     let obj = eval_input(input);
     assert_eq!(obj, Object::Integer(120));
 }
+
+#[test]
+fn test_init_and_method_shorthand() {
+    let input = r#"
+        class Point {
+            init(x, y) {
+                this.x = x;
+                this.y = y;
+            }
+            method area() { this.x * this.y; }
+            scaled(k) { this.x * k; }
+        }
+        let p = new Point(3, 4);
+        [p.area(), p.scaled(2)];
+    "#;
+
+    let obj = eval_input(input);
+    assert_eq!(obj, Object::array(vec![Object::Integer(12), Object::Integer(6)]));
+}
@@ -1,5 +1,6 @@
 use std::fs;
 use std::path::PathBuf;
+use std::rc::Rc;
 
 use crate::env::new_env;
 use crate::evaluator::eval;
@@ -28,6 +29,80 @@ fn test_integer_arithmetic() {
     }
 }
 
+#[test]
+fn test_integer_division_and_bitwise_operators() {
+    let tests = vec![
+        ("10 div 2;", Object::Integer(5)),
+        ("7 div 2;", Object::Integer(3)),
+        ("-7 div 2;", Object::Integer(-4)),
+        ("7 div -2;", Object::Integer(-4)),
+        ("-7 div -2;", Object::Integer(3)),
+        ("7.5 div 2;", Object::Float(3.0)),
+        ("12 & 10;", Object::Integer(8)),
+        ("12 | 3;", Object::Integer(15)),
+        ("12 ^ 10;", Object::Integer(6)),
+        ("~5;", Object::Integer(-6)),
+        ("1 << 10;", Object::Integer(1024)),
+        ("-16 >> 2;", Object::Integer(-4)),
+        ("let div = fn(a, b) { a div b; }; div(9, 2);", Object::Integer(4)),
+    ];
+    for (input, expected) in tests {
+        assert_eq!(eval_input(input), expected, "input: {}", input);
+    }
+
+    let errors = vec![
+        ("1 div 0;", "integer division by zero"),
+        ("1 << 64;", "shift amount must be between 0 and 63, got 64"),
+        ("~1.5;", "unknown operator: ~float"),
+        ("1.0 & 2.0;", "unknown operator: & (floats)"),
+    ];
+    for (input, expected) in errors {
+        match eval_input(input) {
+            Object::Error(msg) => assert_eq!(msg, expected, "input: {}", input),
+            other => panic!("expected error for {}, got {:?}", input, other),
+        }
+    }
+}
+
+#[test]
+fn test_power_operator() {
+    let tests = vec![
+        ("2 ** 10;", Object::Integer(1024)),
+        ("2 ** 3 ** 2;", Object::Integer(512)),
+        ("-2 ** 2;", Object::Integer(-4)),
+        ("(-2) ** 3;", Object::Integer(-8)),
+        ("2 ** -1;", Object::Float(0.5)),
+        ("9 ** 0.5;", Object::Float(3.0)),
+        ("1.5 ** 2;", Object::Float(2.25)),
+        ("0xF0 | 0b1111;", Object::Integer(255)),
+    ];
+    for (input, expected) in tests {
+        assert_eq!(eval_input(input), expected, "input: {}", input);
+    }
+
+    match eval_input("10 ** 20;") {
+        Object::Error(msg) => assert_eq!(msg, "integer overflow in 10 ** 20"),
+        other => panic!("expected overflow error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_integer_overflow_is_an_error() {
+    let tests = vec![
+        ("9223372036854775807 + 1;", "integer overflow in 9223372036854775807 + 1"),
+        ("-9223372036854775807 - 2;", "integer overflow in -9223372036854775807 - 2"),
+        ("4611686018427387904 * 2;", "integer overflow in 4611686018427387904 * 2"),
+        ("let x = 9223372036854775807; x++;", "integer overflow in 9223372036854775807 + 1"),
+        ("let m = -9223372036854775807 - 1; -m;", "integer overflow in -(-9223372036854775808)"),
+    ];
+    for (input, expected) in tests {
+        match eval_input(input) {
+            Object::Error(msg) => assert_eq!(msg, expected, "input: {}", input),
+            other => panic!("expected overflow error for {}, got {:?}", input, other),
+        }
+    }
+}
+
 #[test]
 fn test_let_and_identifier() {
     let input = r#"
@@ -56,6 +131,70 @@ fn test_namespace_eval_and_call() {
     assert_eq!(obj, Object::Integer(12));
 }
 
+#[test]
+fn test_nested_and_reopened_namespaces() {
+    let tests = vec![
+        (
+            "namespace A { let X = 1; namespace B { function f() { X + 1; } } } [A::X, A::B::f()];",
+            "[1, 2]",
+        ),
+        (
+            "namespace A { let X = 1; } namespace A { function twice() { X * 2; } } [A::X, A::twice()];",
+            "[1, 2]",
+        ),
+        (
+            "namespace A { namespace B { let x = 1; } } namespace A { namespace B { let y = 2; } } [A::B::x, A::B::y];",
+            "[1, 2]",
+        ),
+        (
+            "namespace Math { function double(x) { x * 2; } } [Math::double(2), Math::abs(-3)];",
+            "[4, 3]",
+        ),
+    ];
+    for (input, expected) in tests {
+        assert_eq!(eval_input(input).to_string(), expected, "input: {}", input);
+    }
+
+    let reopened_const = "namespace A { namespace B { const Y = 1; } } namespace A { let z = 2; } A::B::Y = 3;";
+    match eval_input(reopened_const) {
+        Object::Error(msg) => assert_eq!(msg, "cannot assign to constant A::B::Y"),
+        other => panic!("expected error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_use_statements() {
+    let geo = "namespace Geo { const R = 10; namespace Units { function double(x) { x * 2; } const K = 1; } }";
+    let tests = vec![
+        ("use Geo::Units as U; U::double(3);", "6"),
+        ("use Geo::Units::double; double(4);", "8"),
+        ("use Geo::*; [R, Units::K];", "[10, 1]"),
+        ("use Math::*; abs(-2);", "2"),
+        ("let G = Geo; G::R;", "10"),
+        ("function f() { use Geo::R as radius; radius; } [f(), Type::of(radius)];", "[10, \"error\"]"),
+    ];
+    for (input, expected) in tests {
+        let input = format!("{} {}", geo, input);
+        assert_eq!(eval_input(&input).to_string(), expected, "input: {}", input);
+    }
+
+    let errors = vec![
+        ("use Geo::Units as U; U::K = 2;", "cannot assign to constant U::K"),
+        ("use Geo::R; R = 2;", "cannot assign to constant R"),
+        ("use Geo::*; R = 2;", "cannot assign to constant R"),
+        ("use Geo::Nope;", "Geo::Nope is not a member of Geo"),
+        ("use Geo::R::*;", "use Geo::R::* expects a namespace, got integer"),
+        ("use Nowhere::x;", "identifier not found: Nowhere"),
+    ];
+    for (input, expected) in errors {
+        let input = format!("{} {}", geo, input);
+        match eval_input(&input) {
+            Object::Error(msg) => assert_eq!(msg, expected, "input: {}", input),
+            other => panic!("expected error for {}, got {:?}", input, other),
+        }
+    }
+}
+
 #[test]
 fn test_import_exports_namespaces_only() {
     let mut module_path: PathBuf = std::env::temp_dir();
@@ -142,6 +281,286 @@ fn test_import_relative_to_module_dir() {
     let _ = fs::remove_dir(&base_dir);
 }
 
+fn temp_module_dir(prefix: &str) -> PathBuf {
+    let mut dir: PathBuf = std::env::temp_dir();
+    dir.push(format!(
+        "{}_{}",
+        prefix,
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    fs::create_dir_all(&dir).expect("failed to create temp dir");
+    dir
+}
+
+#[test]
+fn test_named_imports_respect_exports() {
+    let base_dir = temp_module_dir("slang_named_import");
+    fs::write(
+        base_dir.join("lib.sl"),
+        r#"
+            export let answer = 42;
+            export function double(x) { x * 2; }
+            let secret = 7;
+            namespace Hidden {
+                function f() { 1; }
+            }
+        "#,
+    )
+    .expect("failed to write module file");
+
+    let ok = format!(
+        r#"
+            import {{ answer, double }} from "{}";
+            double(answer);
+        "#,
+        base_dir.join("lib.sl").display()
+    );
+    assert_eq!(eval_input(&ok), Object::Integer(84));
+
+    let private = format!(
+        r#"import {{ secret }} from "{}";"#,
+        base_dir.join("lib.sl").display()
+    );
+    match eval_input(&private) {
+        Object::Error(msg) => assert!(msg.contains("does not export 'secret'"), "{}", msg),
+        other => panic!("expected error importing private binding, got {:?}", other),
+    }
+
+    // Once a module uses `export`, its namespaces are only public if exported too.
+    let whole = format!(
+        r#"
+            import "{}";
+            Hidden::f();
+        "#,
+        base_dir.join("lib.sl").display()
+    );
+    match eval_input(&whole) {
+        Object::Error(msg) => assert!(msg.contains("Hidden"), "{}", msg),
+        other => panic!("expected Hidden to be unbound, got {:?}", other),
+    }
+
+    let _ = fs::remove_dir_all(&base_dir);
+}
+
+#[test]
+fn test_export_list_and_missing_export() {
+    let base_dir = temp_module_dir("slang_export_list");
+    fs::write(
+        base_dir.join("list.sl"),
+        r#"
+            namespace Geometry {
+                function area(w, h) { w * h; }
+            }
+            let unit = 1;
+            export { Geometry, unit };
+        "#,
+    )
+    .expect("failed to write module file");
+    fs::write(base_dir.join("broken.sl"), "export { nope };").expect("failed to write module file");
+
+    let ok = format!(
+        r#"
+            import "{}";
+            Geometry::area(2, 3) + unit;
+        "#,
+        base_dir.join("list.sl").display()
+    );
+    assert_eq!(eval_input(&ok), Object::Integer(7));
+
+    let broken = format!(r#"import "{}";"#, base_dir.join("broken.sl").display());
+    match eval_input(&broken) {
+        Object::Error(msg) => assert!(msg.contains("never defines it"), "{}", msg),
+        other => panic!("expected error for undefined export, got {:?}", other),
+    }
+
+    let _ = fs::remove_dir_all(&base_dir);
+}
+
+#[test]
+fn test_pkg_imports_load_from_slang_modules() {
+    let root = temp_module_dir("slang_pkg_import");
+    fs::write(root.join("slang.toml"), "").expect("failed to write manifest");
+    let utils = root.join("slang_modules").join("utils");
+    fs::create_dir_all(&utils).expect("failed to create package dir");
+    fs::write(
+        utils.join("strings.sl"),
+        "namespace Strings { function shout(s) { s + \"!\"; } }",
+    )
+    .expect("failed to write module file");
+    fs::write(utils.join("lib.sl"), "export let version = 3;").expect("failed to write module file");
+    let src = root.join("src");
+    fs::create_dir_all(&src).expect("failed to create src dir");
+
+    let run = |source: &str| {
+        let lexer = Lexer::new(source);
+        let mut parser = Parser::new(lexer);
+        let program = parser.parse_program();
+        assert!(parser.errors.is_empty(), "parser errors: {:?}", parser.errors);
+        let env = new_env();
+        env.borrow_mut().set_module_dir(Some(src.clone()));
+        eval(&program, env)
+    };
+
+    assert_eq!(
+        run(r#"import "pkg:utils/strings"; Strings::shout("hi");"#).to_string(),
+        "\"hi!\""
+    );
+    assert_eq!(run(r#"import { version } from "pkg:utils"; version;"#), Object::Integer(3));
+    match run(r#"import "pkg:other/x";"#) {
+        Object::Error(msg) => assert!(msg.contains("package 'other' is not installed"), "{}", msg),
+        other => panic!("expected error for a missing package, got {:?}", other),
+    }
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn test_imports_search_added_import_paths() {
+    let lib_dir = temp_module_dir("slang_import_path_lib");
+    fs::create_dir_all(lib_dir.join("text")).expect("failed to create lib dir");
+    fs::write(lib_dir.join("text").join("pad.sl"), "export function pad(s) { \" \" + s; }")
+        .expect("failed to write module file");
+    // Modules found on the search path can use it for their own imports.
+    fs::write(
+        lib_dir.join("shout.sl"),
+        "import { pad } from \"text/pad.sl\"; export function shout(s) { pad(s) + \"!\"; }",
+    )
+    .expect("failed to write module file");
+    let script_dir = temp_module_dir("slang_import_path_script");
+
+    let run = |source: &str, search: bool| {
+        let lexer = Lexer::new(source);
+        let mut parser = Parser::new(lexer);
+        let program = parser.parse_program();
+        assert!(parser.errors.is_empty(), "parser errors: {:?}", parser.errors);
+        let env = new_env();
+        env.borrow_mut().set_module_dir(Some(script_dir.clone()));
+        if search {
+            env.borrow_mut().add_import_path(&lib_dir);
+        }
+        eval(&program, env)
+    };
+
+    let source = r#"import { shout } from "shout.sl"; shout("hi");"#;
+    assert_eq!(run(source, true).to_string(), "\" hi!\"");
+    assert!(run(source, false).is_error());
+    // `./` paths stay relative to the importing file.
+    assert!(run(r#"import "./shout.sl";"#, true).is_error());
+
+    let _ = fs::remove_dir_all(&lib_dir);
+    let _ = fs::remove_dir_all(&script_dir);
+}
+
+#[test]
+fn test_circular_imports_are_reported() {
+    let base_dir = temp_module_dir("slang_circular_import");
+    fs::write(base_dir.join("a.sl"), "import \"b.sl\"; namespace A { let x = 1; }")
+        .expect("failed to write module file");
+    fs::write(base_dir.join("b.sl"), "import \"a.sl\"; namespace B { let y = 2; }")
+        .expect("failed to write module file");
+    fs::write(base_dir.join("self.sl"), "import \"self.sl\";").expect("failed to write module file");
+
+    let import = |name: &str| format!(r#"import "{}";"#, base_dir.join(name).display());
+    let cases = vec![
+        ("a.sl", "circular import: a.sl -> b.sl -> a.sl"),
+        ("b.sl", "circular import: b.sl -> a.sl -> b.sl"),
+        ("self.sl", "circular import: self.sl -> self.sl"),
+    ];
+    for (name, expected) in cases {
+        match eval_input(&import(name)) {
+            Object::Error(msg) => assert_eq!(msg, expected),
+            other => panic!("expected a circular import error for {}, got {:?}", name, other),
+        }
+    }
+
+    let _ = fs::remove_dir_all(&base_dir);
+}
+
+#[test]
+fn test_modules_are_evaluated_once() {
+    let base_dir = temp_module_dir("slang_module_cache");
+    let log_path = base_dir.join("log.txt");
+
+    // Every evaluation of shared.sl appends a line to the log file.
+    fs::write(
+        base_dir.join("shared.sl"),
+        format!(
+            r#"
+                let f = Result::unwrapOr(File::open("{}", "a"), 0);
+                let _ = File::write(f, "x");
+                let _ = File::close(f);
+                export let value = 10;
+            "#,
+            log_path.display()
+        ),
+    )
+    .expect("failed to write module file");
+    fs::write(
+        base_dir.join("a.sl"),
+        r#"
+            import { value } from "shared.sl";
+            export let fromA = value + 1;
+        "#,
+    )
+    .expect("failed to write module file");
+
+    let importer_source = r#"
+        import { value } from "shared.sl";
+        import { fromA } from "a.sl";
+        import "./shared.sl";
+        value + fromA;
+    "#;
+    let lexer = Lexer::new(importer_source);
+    let mut parser = Parser::new(lexer);
+    let program = parser.parse_program();
+    assert!(parser.errors.is_empty(), "parser errors: {:?}", parser.errors);
+
+    let env = new_env();
+    env.borrow_mut().set_module_dir(Some(base_dir.clone()));
+    assert_eq!(eval(&program, env), Object::Integer(21));
+
+    let log = fs::read_to_string(&log_path).expect("module should have written its log");
+    assert_eq!(log, "x");
+
+    let _ = fs::remove_dir_all(&base_dir);
+}
+
+#[cfg(feature = "cache")]
+#[test]
+fn test_imports_use_the_ast_cache() {
+    let base_dir = temp_module_dir("slang_ast_cache_import");
+    crate::cache::set_cache_dir(Some(base_dir.join("cache")));
+
+    let module = base_dir.join("cached.sl");
+    fs::write(&module, "export let value = 1;").expect("failed to write module file");
+    let importer = format!(
+        r#"
+            import {{ value }} from "{}";
+            value;
+        "#,
+        module.display()
+    );
+
+    assert_eq!(eval_input(&importer), Object::Integer(1));
+    let entries = fs::read_dir(base_dir.join("cache"))
+        .expect("import should create the cache dir")
+        .count();
+    assert_eq!(entries, 1);
+
+    // A cache hit evaluates the same program.
+    assert_eq!(eval_input(&importer), Object::Integer(1));
+
+    // Editing the module invalidates its entry.
+    fs::write(&module, "export let value = 22;").expect("failed to write module file");
+    assert_eq!(eval_input(&importer), Object::Integer(22));
+
+    crate::cache::set_cache_dir(None);
+    let _ = fs::remove_dir_all(&base_dir);
+}
+
 #[test]
 fn test_boolean_expressions() {
     let tests = vec![
@@ -170,7 +589,7 @@ fn test_boolean_expressions() {
 
 #[test]
 fn test_float_arithmetic_and_comparisons() {
-    let tests = vec![
+    let tests = [
         ("1.5 + 2.25;", 3.75),
         ("10.0 - 3.5;", 6.5),
         ("2.0 * 4.5;", 9.0),
@@ -372,6 +791,76 @@ fn test_closure_capture() {
     assert_eq!(obj, Object::Integer(15));
 }
 
+#[test]
+fn test_lookups_see_bindings_added_while_running() {
+    // The same `x` reads the global first, then the local bound after it,
+    // and the same `n` is found at different depths on each call.
+    let input = r#"
+        let x = "g";
+        let n = "0";
+        function f(shadow) {
+            let out = "";
+            let i = 0;
+            while (i < 2) {
+                out = out + x;
+                let x = "l";
+                if (shadow) { let n = "5"; }
+                out = out + n;
+                i = i + 1;
+            }
+            out;
+        }
+        f(false) + f(true) + f(false);
+    "#;
+
+    let obj = eval_input(input);
+    assert_eq!(obj.to_string(), "\"g0l0g5l5g0l0\"");
+}
+
+#[test]
+fn test_resolver_places_identifiers_before_running() {
+    let mut parser = Parser::new(Lexer::new(
+        "let g = 1; function f(a) { let b = a; fn() { b + g; }; }",
+    ));
+    let program = parser.parse_program();
+    eval(&program, new_env());
+
+    // `this` takes slot 0 of every function scope, then the parameters.
+    let resolved = format!("{:?}", program);
+    for expected in [
+        r#"Identifier { value: "a", binding: Local { depth: 0, slot: 1 } }"#,
+        r#"Identifier { value: "b", binding: Local { depth: 1, slot: 2 } }"#,
+        r#"Identifier { value: "g", binding: Outer { depth: 2 } }"#,
+    ] {
+        assert!(resolved.contains(expected), "missing {} in {}", expected, resolved);
+    }
+}
+
+#[test]
+fn test_resolved_lookups_fall_back_to_the_search_by_name() {
+    // Names bound where the resolver can't see them: through `this` in a
+    // nested closure, inside a namespace and a generator, and by a closure
+    // assigning to a name it also reads from outside.
+    let input = r#"
+        let total = 10;
+        class Counter {
+            init() { this.n = 1; }
+            get() { let read = fn() { this.n; }; read(); }
+        }
+        namespace Ns {
+            let k = 2;
+            function twice() { k * 2; }
+        }
+        function* gen() { let i = total; yield i; yield i + 1; }
+        function bump() { let before = total; total = total + 5; before + total; }
+        let c = new Counter();
+        [c.get(), Ns::twice(), Seq::toArray(gen()), bump(), total];
+    "#;
+
+    let obj = eval_input(input);
+    assert_eq!(obj.to_string(), "[1, 4, [10, 11], 25, 10]");
+}
+
 #[test]
 fn test_higher_order_function_returning_function() {
     let input = r#"
@@ -465,6 +954,46 @@ fn test_while_with_return() {
     assert_eq!(obj, Object::Integer(3));
 }
 
+#[test]
+fn test_switch_statement() {
+    let input = r#"
+        let describe = fn(x) {
+            switch (x) {
+                case 1, 2:
+                    "small";
+                case "go":
+                    let verb = "going";
+                    verb;
+                case 3.0:
+                    "three";
+                default:
+                    "other";
+            }
+        };
+        [describe(2), describe("go"), describe(3), describe("1"), describe(true)];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        r#"["small", "going", "three", "other", "other"]"#
+    );
+
+    // Only the matching case runs, and a return leaves the enclosing function.
+    let input = r#"
+        let f = fn(x) {
+            let hits = [];
+            switch (x) {
+                case 1:
+                    let hits = push(hits, "one");
+                case 2:
+                    return "two";
+            }
+            hits;
+        };
+        [f(1), f(2), f(5)];
+    "#;
+    assert_eq!(eval_input(input).to_string(), r#"[["one"], "two", []]"#);
+}
+
 #[test]
 fn test_string_literal() {
     let input = r#""hello world";"#;
@@ -528,7 +1057,9 @@ fn test_array_indexing() {
         ("[1, 2, 3][2];", Some(3)),
         ("let a = [1, 2, 3]; a[1];", Some(2)),
         ("[1, 2, 3][3];", None),
-        ("[1, 2, 3][-1];", None),
+        ("[1, 2, 3][-1];", Some(3)),
+        ("[1, 2, 3][-3];", Some(1)),
+        ("[1, 2, 3][-4];", None),
     ];
 
     for (input, expected) in tests {
@@ -541,6 +1072,28 @@ fn test_array_indexing() {
     }
 }
 
+#[test]
+fn test_string_indexing() {
+    let tests = vec![
+        (r#""hello"[1];"#, Object::String("e".to_string())),
+        (r#""hello"[-1];"#, Object::String("o".to_string())),
+        (r#""naïve"[2];"#, Object::String("ï".to_string())),
+        (r#""naïve"[3];"#, Object::String("v".to_string())),
+        (r#""日本語"[-3];"#, Object::String("日".to_string())),
+        (r#""abc"[3];"#, Object::Null),
+        (r#""abc"[-4];"#, Object::Null),
+        (r#"let s = ""; s[0];"#, Object::Null),
+    ];
+    for (input, expected) in tests {
+        assert_eq!(eval_input(input), expected, "input: {}", input);
+    }
+
+    match eval_input(r#""abc"["x"];"#) {
+        Object::Error(msg) => assert!(msg.starts_with("string index must be integer"), "{}", msg),
+        other => panic!("expected error, got {:?}", other),
+    }
+}
+
 #[test]
 fn test_nested_array_indexing() {
     let input = "let a = [1, [2, 3], 4]; a[1][0];";
@@ -686,6 +1239,82 @@ fn test_object_index_assignment_with_variable_key() {
     }
 }
 
+#[test]
+fn test_array_index_assignment() {
+    let tests = vec![
+        ("let a = [1, 2, 3]; a[0] = 5; a;", "[5, 2, 3]"),
+        ("let a = [1, 2, 3]; a[-1] = 9; a;", "[1, 2, 9]"),
+        ("let m = [[0, 0], [0, 0]]; let i = 1; m[i][0] = 7; m;", "[[0, 0], [7, 0]]"),
+        ("let o = { items: [1, 2] }; o.items[1] = 3; o.items;", "[1, 3]"),
+        ("let o = { items: [1, 2] }; o[\"items\"][0] = 4; o.items;", "[4, 2]"),
+        ("let xs = [{ name: \"a\" }]; xs[0].name = \"b\"; xs;", "[{name: \"b\"}]"),
+        ("let a = [1, 2]; a[1]++; a;", "[1, 3]"),
+        ("let a = [1, 2]; let b = a; b[0] = 9; a;", "[1, 2]"),
+        ("let a = [1]; a[0] = 2;", "2"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_input(input).to_string(), expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_array_index_assignment_errors() {
+    let tests = vec![
+        ("let a = [1, 2, 3]; a[3] = 1;", "array index 3 out of bounds for length 3"),
+        ("let a = [1, 2, 3]; a[-4] = 1;", "array index -4 out of bounds for length 3"),
+        ("let a = [1]; a[\"x\"] = 1;", "array index must be integer, got string"),
+        ("let o = {}; o[0] = 1;", "object index must be string, got integer"),
+        ("let s = \"abc\"; s[0] = \"x\";", "cannot assign index on string"),
+        ("let a = [1]; a[true] = 1;", "index in assignment must be a string or integer, got boolean"),
+    ];
+
+    for (input, expected) in tests {
+        match eval_input(input) {
+            Object::Error(msg) => assert_eq!(msg, expected, "input: {}", input),
+            other => panic!("expected error for {}, got {:?}", input, other),
+        }
+    }
+}
+
+#[test]
+fn test_aggregates_are_copy_on_write() {
+    let l = Lexer::new("let a = [1, [2, 3]]; let b = a; let c = a; c[1][0] = 9;");
+    let mut p = Parser::new(l);
+    let program = p.parse_program();
+    let env = new_env();
+    eval(&program, Rc::clone(&env));
+
+    let get = |name: &str| match env.borrow().get(name) {
+        Some(Object::Array(items)) => items,
+        other => panic!("expected array for {}, got {:?}", name, other),
+    };
+    let (a, b, c) = (get("a"), get("b"), get("c"));
+
+    // Copies share storage until one of them is modified.
+    assert!(Rc::ptr_eq(&a, &b));
+    assert!(!Rc::ptr_eq(&a, &c));
+    assert_eq!(a.to_vec(), b.to_vec());
+    assert_eq!(Object::Array(c).to_string(), "[1, [9, 3]]");
+    assert_eq!(Object::Array(a).to_string(), "[1, [2, 3]]");
+}
+
+#[test]
+fn test_failed_assignment_leaves_value_unchanged() {
+    let targets = vec!["o.a.b = 1;", "o.x.y[0] = 1;", "o.a[5] = 1;", "o.a[0].z = 1;"];
+
+    for target in targets {
+        let input = format!("let o = {{ a: [1] }}; {}", target);
+        let mut p = Parser::new(Lexer::new(&input));
+        let program = p.parse_program();
+        let env = new_env();
+
+        assert!(eval(&program, Rc::clone(&env)).is_error(), "expected error for {}", input);
+        let o = env.borrow().get("o").unwrap();
+        assert_eq!(o.to_string(), "{a: [1]}", "input: {}", input);
+    }
+}
+
 #[test]
 fn test_method_call_with_this() {
     let input = r#"
@@ -867,3 +1496,285 @@ fn test_function_statement() {
     let obj = eval_input(input);
     assert_eq!(obj, Object::Integer(120));
 }
+
+#[test]
+fn test_destructuring_let() {
+    let input = r#"
+        let [a, b, ...rest] = [1, 2, 3, 4];
+        let [only, missing] = [9];
+        let { x, y } = { x: 10, y: 20, z: 30 };
+        [a, b, rest, only, missing, x + y];
+    "#;
+    assert_eq!(
+        eval_input(input),
+        Object::array(vec![
+            Object::Integer(1),
+            Object::Integer(2),
+            Object::array(vec![Object::Integer(3), Object::Integer(4)]),
+            Object::Integer(9),
+            Object::Null,
+            Object::Integer(30),
+        ])
+    );
+
+    match eval_input("let [a] = 5;") {
+        Object::Error(msg) => assert!(msg.contains("array pattern"), "{}", msg),
+        other => panic!("expected destructuring error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_default_and_rest_parameters() {
+    let input = r#"
+        let f = fn(a, b = a * 10, ...rest) { [a, b, rest]; };
+        function greet(name = "world") { "hello " + name; }
+        class Box {
+            init(value = 7) { this.value = value; }
+        }
+        [f(1), f(1, 2), f(1, 2, 3, 4), greet(), new Box().value];
+    "#;
+    assert_eq!(
+        eval_input(input),
+        Object::array(vec![
+            Object::array(vec![Object::Integer(1), Object::Integer(10), Object::array(vec![])]),
+            Object::array(vec![Object::Integer(1), Object::Integer(2), Object::array(vec![])]),
+            Object::array(vec![
+                Object::Integer(1),
+                Object::Integer(2),
+                Object::array(vec![Object::Integer(3), Object::Integer(4)]),
+            ]),
+            Object::String("hello world".to_string()),
+            Object::Integer(7),
+        ])
+    );
+}
+
+#[test]
+fn test_arity_errors() {
+    let tests = vec![
+        ("function add(a, b) { a + b; } add(1);", "function add expected 2 arguments, got 1"),
+        ("fn(a) { a; }(1, 2);", "function expected 1 argument, got 2"),
+        ("fn(a, b = 1) { a; }();", "function expected 1 to 2 arguments, got 0"),
+        ("fn(a, ...rest) { a; }();", "function expected at least 1 argument, got 0"),
+        (
+            "class P { init(x) { this.x = x; } } new P();",
+            "function P.init expected 1 argument, got 0",
+        ),
+        (
+            "class P { get() { 1; } } new P().get(5);",
+            "function P.get expected 0 arguments, got 1",
+        ),
+    ];
+
+    for (input, expected) in tests {
+        match eval_input(input) {
+            Object::Error(msg) => assert_eq!(msg, expected, "input: {}", input),
+            other => panic!("expected arity error for {}, got {:?}", input, other),
+        }
+    }
+}
+
+#[test]
+fn test_function_contracts() {
+    let passing = vec![
+        ("function sub(a, b) requires a >= b { a - b; } sub(7, 2);", 5),
+        (
+            "function abs(x) ensures result >= 0 { if (x < 0) { return -x; } x; } abs(-3);",
+            3,
+        ),
+        (
+            "class Counter { init(n) requires n >= 0 ensures result.n == n { this.n = n; } } new Counter(4).n;",
+            4,
+        ),
+    ];
+    for (input, expected) in passing {
+        assert_eq!(eval_input(input), Object::Integer(expected), "input: {}", input);
+    }
+
+    let failing = vec![
+        (
+            "function div(a, b) requires b != 0 { a / b; } div(1, 0);",
+            "contract violated: function div requires b != 0",
+        ),
+        (
+            "let f = fn(x) ensures result > x { x - 1; }; f(5);",
+            "contract violated: function ensures result > x (result was 4)",
+        ),
+        (
+            "class Counter { init(n) requires n >= 0 { this.n = n; } } new Counter(-1);",
+            "contract violated: function Counter.init requires n >= 0",
+        ),
+    ];
+    for (input, expected) in failing {
+        match eval_input(input) {
+            Object::Error(msg) => assert_eq!(msg, expected, "input: {}", input),
+            other => panic!("expected contract error for {}, got {:?}", input, other),
+        }
+    }
+}
+
+#[test]
+fn test_spread_in_calls_and_arrays() {
+    let tests = vec![
+        ("let xs = [2, 3]; [1, ...xs, 4];", "[1, 2, 3, 4]"),
+        ("[...[], ...[1], ...[]];", "[1]"),
+        ("function add(a, b, c) { a + b + c; } add(...[1, 2, 3]);", "6"),
+        ("function add(a, b, c) { a + b + c; } add(1, ...[2], 3);", "6"),
+        (
+            "function wrap(f, ...args) { f(...args); } wrap(fn(a, b) { a * b; }, 6, 7);",
+            "42",
+        ),
+        ("class P { init(x, y) { this.s = x + y; } } new P(...[1, 2]).s;", "3"),
+        ("Math::max(...[3, 9]);", "9"),
+    ];
+    for (input, expected) in tests {
+        assert_eq!(eval_input(input).to_string(), expected, "input: {}", input);
+    }
+
+    match eval_input("function f(a) { a; } f(...5);") {
+        Object::Error(msg) => assert_eq!(msg, "cannot spread integer, expected an array"),
+        other => panic!("expected spread error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_assignment_evaluates_right_side_once() {
+    let input = r#"
+        let h = 0;
+        h = Timer::after(1, fn() { 1; });
+        Timer::cancel(h);
+        Timer::cancel(h + 1);
+    "#;
+    // A second, stray timer would still be pending under the next handle.
+    assert_eq!(eval_input(input), Object::Boolean(false));
+}
+
+#[test]
+fn test_method_calls_on_primitives() {
+    let tests = vec![
+        (r#""abc".toUpper();"#, r#""ABC""#),
+        (
+            r#"" a, b ".split(",").map(fn(x) { x.trim(); });"#,
+            r#"["a", "b"]"#,
+        ),
+        ("[3, 1, 2].sort().first();", "1"),
+        ("let xs = [1, 2, 3]; xs.map(fn(x) { x * 2; }).len();", "3"),
+        ("(-9).abs().sqrt();", "3"),
+        ("2.5.floor();", "2"),
+    ];
+    for (input, expected) in tests {
+        assert_eq!(eval_input(input).to_string(), expected, "input: {}", input);
+    }
+
+    match eval_input(r#""abc".shout();"#) {
+        Object::Error(msg) => assert_eq!(msg, "string has no method shout (looked for String::shout)"),
+        other => panic!("expected missing method error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_byte_strings() {
+    let tests = vec![
+        (r#"b"\x01\x02" + b"\xff";"#, r#"b"\x01\x02\xff""#),
+        (r#"b"hi" == b"\x68\x69";"#, "true"),
+        (r#"b"hi" != b"hi";"#, "false"),
+        (r#"let b = b"\x00\xfe"; [b[0], b[1], b[-1], b[2]];"#, "[0, 254, 254, null]"),
+        (r#"len(b"\x00\x00\x00");"#, "3"),
+        (r#"Type::of(b"");"#, "\"bytes\""),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_input(input).to_string(), expected, "input: {}", input);
+    }
+
+    assert!(eval_input(r#"b"a" == "a";"#).is_error());
+}
+
+#[test]
+fn test_pipeline_operator() {
+    let tests = vec![
+        (
+            "let add = fn(a, b) { a + b; }; [1, 2, 3, 4] |> Array::filter(fn(x) { x % 2 == 0; }) |> Array::map(fn(x) { x * 10; }) |> Array::reduce(0, add);",
+            "60",
+        ),
+        ("let inc = fn(x) { x + 1; }; 1 |> inc |> fn(x) { x * 3; };", "6"),
+        ("let x = 2 + 3 |> Math::max(4); x;", "5"),
+        (r#""  hi  " |> String::trim() |> String::toUpper;"#, r#""HI""#),
+        ("let o = { scale: fn(x, k) { x * k; } }; 4 |> o.scale(2);", "8"),
+        ("[1, 2] |> len;", "2"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_input(input).to_string(), expected, "input: {}", input);
+    }
+
+    assert!(eval_input("1 |> 2;").is_error());
+}
+
+#[test]
+fn test_arrow_functions() {
+    let tests = vec![
+        ("let inc = x => x + 1; inc(1);", "2"),
+        ("let add = (a, b) => a + b; add(2, 3);", "5"),
+        ("(() => 42)();", "42"),
+        ("let f = (a, b = 10, ...more) => a + b + len(more); [f(1), f(1, 2, 3, 4)];", "[11, 5]"),
+        ("let f = x => { let y = x * 2; y + 1; }; f(5);", "11"),
+        ("let f = x => ({ v: x }); f(3).v;", "3"),
+        ("let add = a => b => a + b; add(1)(2);", "3"),
+        ("[1, 2, 3] |> Array::map(x => x * 2) |> Array::filter(x => x > 2);", "[4, 6]"),
+        ("let n = 10; let f = x => x + n; n = 20; f(1);", "21"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_input(input).to_string(), expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_null_literal() {
+    let tests = vec![
+        ("null;", "null"),
+        ("let x = null; x == null;", "true"),
+        ("[1][5] == null;", "true"),
+        ("0 == null;", "false"),
+        ("\"\" != null;", "true"),
+        ("[null == false, !null, null ?? 3];", "[false, true, 3]"),
+        ("let o = { a: null }; [o.a == null, Object::has(o, \"a\")];", "[true, true]"),
+        ("[Type::isNull(null), Type::of(null), Json::stringify(null)];", "[true, \"null\", Ok(\"null\")]"),
+        ("let f = fn(x = null) { x ?? \"default\"; }; [f(), f(null), f(1)];", "[\"default\", \"default\", 1]"),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_input(input).to_string(), expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_const_declarations() {
+    let tests = vec![
+        ("const LIMIT = 10; LIMIT * 2;", "20"),
+        ("const N = 1; let f = fn() { let N = 2; N; }; [f(), N];", "[2, 1]"),
+        ("let total = 0; for (x in [1, 2, 3]) { const doubled = x * 2; total = total + doubled; } total;", "12"),
+        ("namespace Config { const PORT = 8080; let name = \"app\"; } Config::name = \"api\"; [Config::PORT, Config::name];", "[8080, \"api\"]"),
+    ];
+    for (input, expected) in tests {
+        assert_eq!(eval_input(input).to_string(), expected, "input: {}", input);
+    }
+
+    let errors = vec![
+        ("const LIMIT = 10; LIMIT = 20;", "cannot assign to constant LIMIT"),
+        ("const LIMIT = 10; LIMIT++;", "cannot assign to constant LIMIT"),
+        ("const LIMIT = 10; let f = fn() { LIMIT = 1; }; f();", "cannot assign to constant LIMIT"),
+        ("const CFG = { port: 1 }; CFG.port = 2;", "cannot assign to constant CFG"),
+        ("const LIMIT = 10; let LIMIT = 20;", "cannot redeclare constant LIMIT"),
+        ("const LIMIT = 10; function LIMIT() { 1; }", "cannot redeclare constant LIMIT"),
+        ("namespace Config { const PORT = 8080; } Config::PORT = 1;", "cannot assign to constant Config::PORT"),
+        ("namespace Config { const DB = { port: 1 }; } Config::DB.port = 2;", "cannot assign to constant Config::DB"),
+    ];
+    for (input, expected) in errors {
+        match eval_input(input) {
+            Object::Error(msg) => assert_eq!(msg, expected, "input: {}", input),
+            other => panic!("expected error for {}, got {:?}", input, other),
+        }
+    }
+}
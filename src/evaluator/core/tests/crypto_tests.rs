@@ -0,0 +1,51 @@
+use crate::test_support::eval_input;
+
+#[test]
+fn test_hash_functions() {
+    let input = r#"
+        [
+            Crypto::md5("abc"),
+            Crypto::sha1("abc"),
+            Crypto::sha256("abc"),
+            Crypto::sha256(b"abc") == Crypto::sha256("abc"),
+            Bytes::len(Crypto::sha256("", { bytes: true }))
+        ];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        r#"["900150983cd24fb0d6963f7d28e17f72", "a9993e364706816aba3e25717850c26c9cd0d89d", "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad", true, 32]"#
+    );
+    assert!(eval_input("Crypto::sha256(42);").is_error());
+    assert!(eval_input(r#"Crypto::md5("abc", { bytes: 1 });"#).is_error());
+}
+
+#[test]
+fn test_hmac_sha256() {
+    // RFC 4231 test case 2.
+    let input = r#"Crypto::hmacSha256("Jefe", "what do ya want for nothing?");"#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        r#""5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843""#
+    );
+
+    let input = r#"
+        let key = Crypto::hmacSha256("secret", "2026-10-16", { bytes: true });
+        [Bytes::len(key), Crypto::hmacSha256(key, "us-east-1") == Crypto::hmacSha256(key, b"us-east-1")];
+    "#;
+    assert_eq!(eval_input(input).to_string(), "[32, true]");
+    assert!(eval_input(r#"Crypto::hmacSha256("key");"#).is_error());
+}
+
+#[test]
+fn test_random_bytes() {
+    let input = r#"
+        let a = Crypto::randomBytes(16);
+        [Bytes::len(a), Bytes::len(Crypto::randomBytes(0)), a == Crypto::randomBytes(16)];
+    "#;
+    assert_eq!(eval_input(input).to_string(), "[16, 0, false]");
+    assert!(eval_input("Crypto::randomBytes(-1);").is_error());
+    assert_eq!(
+        eval_input("Crypto::randomBytes(9223372036854775807);").to_string(),
+        "Crypto::randomBytes count must be at most 1048576"
+    );
+}
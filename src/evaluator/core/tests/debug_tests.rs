@@ -0,0 +1,45 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::builtins::native::redirect_stdout;
+use crate::object::Object;
+use crate::test_support::eval_input;
+
+#[test]
+fn test_debug_pretty_breaks_long_values_over_lines() {
+    let input = r#"
+        Debug::pretty({
+            name: "slang",
+            tags: ["interpreter", "scripting"],
+            deps: [{ name: "regex", optional: true }, { name: "ureq", optional: true }]
+        });
+    "#;
+    let expected = "{\n  name: \"slang\",\n  tags: [\"interpreter\", \"scripting\"],\n  deps: [\n    {name: \"regex\", optional: true},\n    {name: \"ureq\", optional: true},\n  ],\n}";
+    match eval_input(input) {
+        Object::String(s) => assert_eq!(s, expected),
+        other => panic!("expected string, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_debug_inspect_returns_its_argument() {
+    assert_eq!(eval_input("Debug::inspect([1, 2]).map(fn(x) { x * 2 });").to_string(), "[2, 4]");
+    assert_eq!(eval_input("Debug::pretty({ b: 1, a: [] });").to_string(), "\"{b: 1, a: []}\"");
+    assert_eq!(
+        eval_input("Debug::inspect(1, 2);").to_string(),
+        "Debug::inspect expects exactly 1 argument"
+    );
+    assert_eq!(eval_input("eprint([2]);").to_string(), "null");
+}
+
+#[test]
+fn test_println_puts_each_value_on_its_own_line() {
+    let printed = Rc::new(RefCell::new(String::new()));
+    let sink = Rc::clone(&printed);
+    redirect_stdout(Some(Box::new(move |text: &str| sink.borrow_mut().push_str(text))));
+    let result = eval_input(r#"print(1, "a"); println(1, "a", [2]); println();"#);
+    redirect_stdout(None);
+
+    assert_eq!(result, Object::Null);
+    assert_eq!(*printed.borrow(), "1 \"a\"\n1\n\"a\"\n[2]\n\n");
+}
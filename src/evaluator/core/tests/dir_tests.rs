@@ -0,0 +1,60 @@
+use crate::test_support::eval_input;
+
+#[test]
+fn test_dir_create_list_walk_and_remove() {
+    let root = std::env::temp_dir().join("slang_dir_tests");
+    let _ = std::fs::remove_dir_all(&root);
+
+    let input = format!(
+        r#"
+        let root = "{}";
+        let before = Result::unwrapOr(Dir::exists(root), "ERR");
+        let created = Dir::create(Path::join(root, "b", "deep"));
+        let _ = File::writeString(Path::join(root, "a.txt"), "a");
+        let _ = File::writeString(Path::join(root, "b", "c.txt"), "c");
+        let listed = Result::unwrapOr(Dir::list(root), []);
+        let walked = Array::map(Result::unwrapOr(Dir::walk(root), []), fn(p) {{
+            String::replace(p, root, "")
+        }});
+        let notEmpty = Result::isErr(Dir::remove(root));
+        let removed = Result::isOk(Dir::remove(root, {{ recursive: true }}));
+        [
+            before, Result::isOk(created), listed, Array::len(walked), notEmpty, removed,
+            Result::unwrapOr(Dir::exists(root), "ERR"),
+            Result::isErr(Dir::list(root)),
+            Result::unwrapOr(Dir::exists(Option::unwrapOr(Path::dirname(root), "")), "ERR")
+        ];
+    "#,
+        root.display()
+    );
+    let obj = eval_input(&input);
+    let _ = std::fs::remove_dir_all(&root);
+
+    assert_eq!(
+        obj.to_string(),
+        r#"[false, true, ["a.txt", "b"], 4, true, true, false, true, true]"#
+    );
+}
+
+#[test]
+fn test_dir_walk_is_depth_first_in_name_order() {
+    let root = std::env::temp_dir().join("slang_dir_walk_order");
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("b").join("x")).unwrap();
+    std::fs::write(root.join("a.txt"), "").unwrap();
+    std::fs::write(root.join("b").join("x").join("y.txt"), "").unwrap();
+    std::fs::write(root.join("c.txt"), "").unwrap();
+
+    let input = format!(
+        r#"Result::unwrapOr(Dir::walk("{}"), []);"#,
+        root.display()
+    );
+    let obj = eval_input(&input);
+    let _ = std::fs::remove_dir_all(&root);
+
+    let expected: Vec<String> = ["a.txt", "b", "b/x", "b/x/y.txt", "c.txt"]
+        .iter()
+        .map(|rel| format!("{:?}", root.join(rel).display().to_string()))
+        .collect();
+    assert_eq!(obj.to_string(), format!("[{}]", expected.join(", ")));
+}
@@ -0,0 +1,139 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::env::{EnvBuilder, EnvRef};
+use crate::evaluator::eval;
+use crate::lexer::Lexer;
+use crate::object::Object;
+use crate::parser::Parser;
+use crate::test_support::check_errors;
+
+fn eval_in(input: &str, env: EnvRef) -> Object {
+    let mut p = Parser::new(Lexer::new(input));
+    let program = p.parse_program();
+    check_errors(&p);
+    eval(&program, env)
+}
+
+#[test]
+fn test_registered_namespace_closures_share_host_state() {
+    let spawned: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+    let log = Rc::clone(&spawned);
+
+    let env = EnvBuilder::new()
+        .register_namespace("Game", |ns| {
+            ns.func("spawn", move |args, _env| match args.as_slice() {
+                [Object::String(kind)] => {
+                    log.borrow_mut().push(kind.clone());
+                    Object::Integer(log.borrow().len() as i64)
+                }
+                _ => Object::error("Game::spawn expects a string"),
+            })
+            .value("MAX_PLAYERS", Object::Integer(4))
+        })
+        .build();
+
+    let result = eval_in(
+        r#"
+        Game::spawn("orc");
+        let ids = Array::map(["elf", "imp"], Game::spawn);
+        [ids, Game::MAX_PLAYERS, Type::isCallable(Game::spawn), str(Game::spawn)];
+        "#,
+        env,
+    );
+    assert_eq!(result.to_string(), "[[2, 3], 4, true, \"<native fn Game::spawn>\"]");
+    assert_eq!(*spawned.borrow(), vec!["orc", "elf", "imp"]);
+}
+
+#[test]
+fn test_registered_globals_and_namespace_errors() {
+    let env = EnvBuilder::new()
+        .func("double", |args, _env| match args.as_slice() {
+            [Object::Integer(n)] => Object::Integer(n * 2),
+            _ => Object::error("double expects an integer"),
+        })
+        .value("VERSION", Object::String("1.2".to_string()))
+        .build();
+
+    assert_eq!(
+        eval_in("[double(21), VERSION];", Rc::clone(&env)).to_string(),
+        "[42, \"1.2\"]"
+    );
+    assert_eq!(
+        eval_in("double(\"x\");", env),
+        Object::error("double expects an integer")
+    );
+}
+
+#[test]
+fn test_native_fns_returned_by_host_functions_and_set_later() {
+    let env = EnvBuilder::new()
+        .func("counter", |_args, _env| {
+            let count = Rc::new(RefCell::new(0));
+            Object::native_fn("counter", move |_args, _env| {
+                *count.borrow_mut() += 1;
+                Object::Integer(*count.borrow())
+            })
+        })
+        .build();
+    let prefix = String::from(">> ");
+    env.borrow_mut().set(
+        "shout".to_string(),
+        Object::native_fn("shout", move |args, _env| {
+            Object::String(format!("{}{}", prefix, args[0]))
+        }),
+    );
+
+    let result = eval_in(
+        r#"
+        let a = counter();
+        let b = counter();
+        a(); a();
+        [a(), b(), shout(1)];
+        "#,
+        env,
+    );
+    assert_eq!(result.to_string(), "[3, 1, \">> 1\"]");
+}
+
+#[test]
+fn test_host_functions_convert_arguments_with_from_slang() {
+    use std::collections::HashMap;
+
+    use crate::object::{FromSlang, ToSlang};
+
+    let env = EnvBuilder::new()
+        .func("tally", |mut args, _env| {
+            match HashMap::<String, Vec<i64>>::from_slang(args.remove(0)) {
+                Ok(groups) => groups
+                    .into_iter()
+                    .map(|(k, v)| (k, v.iter().sum::<i64>()))
+                    .collect::<HashMap<_, _>>()
+                    .to_slang(),
+                Err(e) => Object::error(format!("tally: {}", e)),
+            }
+        })
+        .build();
+
+    assert_eq!(
+        eval_in("tally({ a: [1, 2], b: [] }).a;", Rc::clone(&env)),
+        Object::Integer(3)
+    );
+    assert_eq!(
+        eval_in("tally({ a: [1, \"2\"] });", env),
+        Object::error("tally: expected integer, found string")
+    );
+}
+
+#[test]
+fn test_env_without_prelude_keeps_builtin_members_only() {
+    let source = "[Type::of(Queue), Fn::isCallable(Fn::tap), Fn::isCallable(Fn::compose)];";
+    assert_eq!(
+        eval_in(source, EnvBuilder::new().build()).to_string(),
+        "[\"object\", true, true]"
+    );
+    assert_eq!(
+        eval_in(source, EnvBuilder::new().without_prelude().build()).to_string(),
+        "[\"error\", false, true]"
+    );
+}
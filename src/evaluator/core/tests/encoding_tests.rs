@@ -0,0 +1,62 @@
+use crate::test_support::eval_input;
+
+#[test]
+fn test_base64_round_trips_strings_and_bytes() {
+    let input = r#"
+        [
+            Encoding::base64Encode("hello, world"),
+            Encoding::base64Decode("aGVsbG8sIHdvcmxk"),
+            Encoding::base64Encode(b"\xfb\xff"),
+            Encoding::base64Encode(b"\xfb\xff", { urlSafe: true }),
+            Encoding::base64Decode("-_8", { urlSafe: true, bytes: true }),
+            Encoding::base64Decode("-_8=", { urlSafe: true, bytes: true }),
+            Encoding::base64Decode("+/8=", { bytes: true })
+        ];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        r#"["aGVsbG8sIHdvcmxk", Ok("hello, world"), "+/8=", "-_8", Ok(b"\xfb\xff"), Ok(b"\xfb\xff"), Ok(b"\xfb\xff")]"#
+    );
+
+    let input = r#"[Result::isErr(Encoding::base64Decode("not base64!")), Result::isErr(Encoding::base64Decode("+/8="))];"#;
+    assert_eq!(eval_input(input).to_string(), "[true, true]");
+    assert!(eval_input("Encoding::base64Encode(42);").is_error());
+}
+
+#[test]
+fn test_hex_encoding() {
+    let input = r#"
+        [
+            Encoding::hexEncode("Hi!"),
+            Encoding::hexEncode(b"\x00\xff"),
+            Encoding::hexDecode("486921"),
+            Encoding::hexDecode("00FF", { bytes: true }),
+            Result::isErr(Encoding::hexDecode("abc")),
+            Result::isErr(Encoding::hexDecode("zz"))
+        ];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        r#"["486921", "00ff", Ok("Hi!"), Ok(b"\x00\xff"), true, true]"#
+    );
+}
+
+#[test]
+fn test_url_encoding() {
+    let input = r#"
+        [
+            Encoding::urlEncode("a b&c=d/é~"),
+            Encoding::urlDecode("a%20b%26c%3Dd%2F%C3%A9~"),
+            Encoding::urlDecode("a+b"),
+            Encoding::urlEncode(b"\xff"),
+            Encoding::urlDecode("%ff", { bytes: true }),
+            Result::isErr(Encoding::urlDecode("%ff")),
+            Result::isErr(Encoding::urlDecode("100%"))
+        ];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        r#"["a%20b%26c%3Dd%2F%C3%A9~", Ok("a b&c=d/é~"), Ok("a+b"), "%FF", Ok(b"\xff"), true, true]"#
+    );
+    assert!(eval_input(r#"Encoding::urlDecode("x", { bytes: 1 });"#).is_error());
+}
@@ -87,3 +87,219 @@ fn test_file_namespace_read_write_errors() {
         ),
     }
 }
+
+#[test]
+fn test_file_dry_run_does_not_create_or_write() {
+    let path = std::env::temp_dir().join("slang_dry_run_file.txt");
+    let _ = std::fs::remove_file(&path);
+
+    crate::capability::enable_dry_run();
+    let input = format!(
+        r#"
+        let f = Result::unwrapOr(File::open("{}", "w+"), 0);
+        let written = Result::unwrapOr(File::write(f, "data"), -1);
+        let contents = Result::unwrapOr(File::read(f), "ERR");
+        let _ = File::close(f);
+        [written, contents];
+    "#,
+        path.display()
+    );
+    let obj = eval_input(&input);
+    crate::capability::disable_dry_run();
+
+    assert_eq!(
+        obj,
+        Object::array(vec![Object::Integer(4), Object::String(String::new())])
+    );
+    assert!(!path.exists(), "dry-run should not create the file");
+}
+
+#[test]
+fn test_file_binary_mode_round_trips_bytes() {
+    let input = r#"
+        let f = Result::unwrapOr(File::open("tmp_file_namespace_binary.txt", "w+b"), 0);
+        let _ = File::write(f, b"\x89PNG\x00\xff");
+        let _ = File::write(f, "!");
+        let _ = File::seek(f, 0, "start");
+        let all = Result::unwrapOr(File::read(f), "ERR");
+        let _ = File::seek(f, 0, "start");
+        let head = file_read(f, 2);
+        File::close(f);
+        [all, head];
+    "#;
+
+    assert_eq!(
+        eval_input(input).to_string(),
+        r#"[b"\x89PNG\x00\xff!", b"\x89P"]"#
+    );
+}
+
+#[test]
+fn test_file_encodings_round_trip_and_reject_invalid_data() {
+    let input = r#"
+        let path = "tmp_file_namespace_encoding.txt";
+        let f = Result::unwrapOr(File::open(path, "w", { encoding: "latin-1" }), 0);
+        let _ = File::write(f, "café");
+        let euro = File::write(f, "€");
+        File::close(f);
+        let raw = Result::unwrapOr(File::read(Result::unwrapOr(File::open(path, "rb"), 0)), b"");
+        let latin = Result::unwrapOr(File::read(Result::unwrapOr(File::open(path, "r", { encoding: "latin-1" }), 0)), "ERR");
+        let asUtf8 = File::read(Result::unwrapOr(File::open(path, "r"), 0));
+
+        let g = Result::unwrapOr(File::open(path, "w+", { encoding: "utf-16" }), 0);
+        let _ = File::write(g, "hé€");
+        let _ = File::seek(g, 0, "start");
+        let wide = Result::unwrapOr(File::read(g), "ERR");
+        File::close(g);
+        let wideRaw = Result::unwrapOr(File::read(Result::unwrapOr(File::open(path, "rb"), 0)), b"");
+
+        let a = Result::unwrapOr(File::open(path, "a", { encoding: "utf-8" }), 0);
+        let _ = File::write(a, "!");
+        File::close(a);
+        let oddUtf16 = File::read(Result::unwrapOr(File::open(path, "r", { encoding: "utf-16" }), 0));
+
+        [
+            raw, latin, Result::isErr(euro), Result::isErr(asUtf8), wide, wideRaw,
+            Result::isErr(oddUtf16),
+            Result::isErr(File::open(path, "r", { encoding: "klingon" })),
+            Result::isErr(File::open(path, "rb", { encoding: "utf-8" }))
+        ];
+    "#;
+
+    assert_eq!(
+        eval_input(input).to_string(),
+        r#"[b"caf\xe9", "café", true, true, "hé€", b"h\x00\xe9\x00\xac ", true, true, true]"#
+    );
+}
+
+#[test]
+fn test_file_line_and_chunk_reads() {
+    let path = std::env::temp_dir().join("slang_file_lines.txt");
+    std::fs::write(&path, "first\r\nsecond\n\nlast").unwrap();
+
+    let input = format!(
+        r#"
+        let path = "{}";
+        let f = Result::unwrapOr(File::open(path, "r"), 0);
+        let a = Result::unwrapOr(File::readLine(f), "ERR");
+        let head = Result::unwrapOr(File::readBytes(f, 3), b"");
+        let rest = Result::unwrapOr(File::readLines(f), []);
+        let eof = Result::unwrapOr(File::readLine(f), "ERR");
+        File::close(f);
+        let g = Result::unwrapOr(File::open(path, "r"), 0);
+        let lines = [];
+        let line = Result::unwrapOr(File::readLine(g), Option::None());
+        while (Option::isSome(line)) {{
+            lines = Array::push(lines, Option::unwrapOr(line, ""));
+            line = Result::unwrapOr(File::readLine(g), Option::None());
+        }}
+        [a, head, rest, eof, lines, Result::unwrapOr(File::readLines(path), [])];
+    "#,
+        path.display()
+    );
+    let obj = eval_input(&input);
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(
+        obj.to_string(),
+        r#"[Some("first"), b"sec", ["ond", "", "last"], None, ["first", "second", "", "last"], ["first", "second", "", "last"]]"#
+    );
+}
+
+#[test]
+fn test_file_read_line_handles_long_lines_and_utf16() {
+    let path = std::env::temp_dir().join("slang_file_lines_utf16.txt");
+    let long = "x".repeat(5000);
+    let text = format!("{}\nhé\n", long);
+    let utf16: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    std::fs::write(&path, utf16).unwrap();
+
+    let input = format!(
+        r#"
+        let f = Result::unwrapOr(File::open("{}", "r", {{ encoding: "utf-16" }}), 0);
+        let a = Result::unwrapOr(File::readLine(f), "ERR");
+        let b = Result::unwrapOr(File::readLine(f), "ERR");
+        let c = Result::unwrapOr(File::readLine(f), "ERR");
+        [len(Option::unwrapOr(a, "")), b, c];
+    "#,
+        path.display()
+    );
+    let obj = eval_input(&input);
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(obj.to_string(), r#"[5000, Some("hé"), None]"#);
+}
+
+#[test]
+fn test_file_path_helpers() {
+    let dir = std::env::temp_dir();
+    let src = dir.join("slang_file_helpers_src.txt");
+    let dst = dir.join("slang_file_helpers_dst.txt");
+    let _ = std::fs::remove_file(&src);
+    let _ = std::fs::remove_file(&dst);
+
+    let input = format!(
+        r#"
+        let src = "{}";
+        let dst = "{}";
+        let before = Result::unwrapOr(File::exists(src), "ERR");
+        let written = Result::unwrapOr(File::writeString(src, "héllo"), -1);
+        let appended = Result::unwrapOr(File::append(src, b"!\x00"), -1);
+        let size = Result::unwrapOr(File::size(src), -1);
+        let copied = Result::unwrapOr(File::copy(src, dst), -1);
+        let _ = File::writeString(src, "café", {{ encoding: "latin-1" }});
+        let latin = Result::unwrapOr(File::readToString(src, {{ encoding: "latin-1" }}), "ERR");
+        let invalid = Result::isErr(File::readToString(src));
+        let _ = File::delete(src);
+        [
+            before, written, appended, size, copied, latin, invalid,
+            Result::unwrapOr(File::exists(src), "ERR"),
+            Result::isErr(File::delete(src)),
+            Result::isErr(File::size(src))
+        ];
+    "#,
+        src.display(),
+        dst.display()
+    );
+    let obj = eval_input(&input);
+    let copied = std::fs::read(&dst).unwrap_or_default();
+    let _ = std::fs::remove_file(&dst);
+
+    assert_eq!(
+        obj.to_string(),
+        r#"[false, 6, 2, 8, 8, "café", true, false, true, true]"#
+    );
+    assert_eq!(copied, "héllo!\0".as_bytes());
+}
+
+#[test]
+fn test_file_path_helpers_respect_dry_run() {
+    let dir = std::env::temp_dir();
+    let kept = dir.join("slang_dry_run_kept.txt");
+    let created = dir.join("slang_dry_run_created.txt");
+    std::fs::write(&kept, "keep").unwrap();
+    let _ = std::fs::remove_file(&created);
+
+    crate::capability::enable_dry_run();
+    let input = format!(
+        r#"
+        let kept = "{}";
+        let created = "{}";
+        [
+            Result::unwrapOr(File::writeString(created, "abc"), -1),
+            Result::unwrapOr(File::append(kept, "more"), -1),
+            Result::unwrapOr(File::copy(kept, created), -1),
+            Result::isOk(File::delete(kept))
+        ];
+    "#,
+        kept.display(),
+        created.display()
+    );
+    let obj = eval_input(&input);
+    crate::capability::disable_dry_run();
+
+    assert_eq!(obj.to_string(), "[3, 4, 4, true]");
+    assert_eq!(std::fs::read_to_string(&kept).unwrap(), "keep");
+    assert!(!created.exists());
+    let _ = std::fs::remove_file(&kept);
+}
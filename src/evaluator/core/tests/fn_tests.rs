@@ -147,6 +147,32 @@ fn test_fn_partial() {
     assert_eq!(obj, Object::Integer(18));
 }
 
+#[test]
+fn test_fn_combinators_return_callable_functions() {
+    let input = r#"
+        let add = fn(a, b) { a + b };
+        let inc = Fn::partial(add, 1);
+        let isOdd = Fn::negate(fn(x) { x % 2 == 0 });
+        let incThenDouble = Fn::pipe(inc, fn(x) { x * 2 });
+        [
+            inc(41),
+            Array::map([1, 2, 3], inc),
+            Array::filter([1, 2, 3, 4], isOdd),
+            incThenDouble(4),
+            Fn::flip(fn(a, b) { a - b })(1, 10),
+            Fn::constant("x")(),
+            Type::of(inc),
+            Fn::isCallable(isOdd)
+        ];
+    "#;
+
+    let obj = eval_input(input);
+    assert_eq!(
+        obj.to_string(),
+        "[42, [2, 3, 4], [1, 3], 10, 9, \"x\", \"function\", true]"
+    );
+}
+
 #[test]
 fn test_fn_is_callable() {
     let input = r#"
@@ -203,3 +229,16 @@ fn test_fn_error_handling() {
         ),
     }
 }
+
+#[test]
+fn test_fn_prelude_helpers() {
+    let input = r#"
+        let seen = Ref::new([]);
+        let total = [1, 2, 3] |> Fn::tap(xs => Ref::set(seen, xs)) |> len;
+        let calls = Ref::new(0);
+        let init = Fn::once(() => { Ref::set(calls, Ref::get(calls) + 1); "ready"; });
+        [total, Ref::get(seen), Fn::times(4, i => i * i), init(), init(), Ref::get(calls)];
+    "#;
+
+    assert_eq!(eval_input(input).to_string(), "[3, [1, 2, 3], [0, 1, 4, 9], \"ready\", \"ready\", 1]");
+}
@@ -0,0 +1,60 @@
+use crate::test_support::eval_input;
+
+const CONFIG: &str = r#"let cfg = { name: "app", replicas: 3, ratio: 0.5, tags: ["a", "b"], db: { host: "localhost", port: 5432 }, debug: false };"#;
+
+#[test]
+fn test_yaml_round_trips_values_in_key_order() {
+    let input = format!(
+        r#"{}
+        let text = Result::unwrapOr(Yaml::stringify(cfg), "ERR");
+        [text, Result::unwrapOr(Yaml::parse(text), 0)];"#,
+        CONFIG
+    );
+    assert_eq!(
+        eval_input(&input).to_string(),
+        "[\"name: app\nreplicas: 3\nratio: 0.5\ntags:\n  - a\n  - b\ndb:\n  host: localhost\n  port: 5432\ndebug: false\n\", \
+         {name: \"app\", replicas: 3, ratio: 0.5, tags: [\"a\", \"b\"], db: {host: \"localhost\", port: 5432}, debug: false}]"
+    );
+}
+
+#[test]
+fn test_yaml_parse_reads_block_documents_and_reports_errors() {
+    let input = "Yaml::parse(\"items:\n  - 1\n  - two\nempty: ~\n\");";
+    assert_eq!(eval_input(input).to_string(), r#"Ok({items: [1, "two"], empty: null})"#);
+
+    let input = r#"[Result::isErr(Yaml::parse("a: [1, 2")), Yaml::stringify("hello")];"#;
+    assert_eq!(eval_input(input).to_string(), "[true, Ok(\"hello\n\")]");
+    assert!(eval_input("Yaml::parse(42);").is_error());
+}
+
+#[test]
+fn test_toml_round_trips_tables() {
+    let input = format!(
+        r#"{}
+        let text = Result::unwrapOr(Toml::stringify(cfg), "ERR");
+        [text, Result::unwrapOr(Toml::parse(text), 0)];"#,
+        CONFIG
+    );
+    // Sub-tables have to come after a table's plain values.
+    assert_eq!(
+        eval_input(&input).to_string(),
+        "[\"name = \"app\"\nreplicas = 3\nratio = 0.5\ntags = [\"a\", \"b\"]\ndebug = false\n\n[db]\nhost = \"localhost\"\nport = 5432\n\", \
+         {name: \"app\", replicas: 3, ratio: 0.5, tags: [\"a\", \"b\"], debug: false, db: {host: \"localhost\", port: 5432}}]"
+    );
+}
+
+#[test]
+fn test_toml_rejects_what_it_cannot_represent() {
+    let input = r#"
+        [
+            Toml::stringify([1, 2]),
+            Toml::stringify({ a: { b: [1, Result::unwrapOr(Json::parse("null"), 0)] } }),
+            Result::isErr(Toml::parse("a = ")),
+            Toml::parse("when = 2024-01-02")
+        ];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        r#"[Err("a TOML document must be an object"), Err("TOML has no null value (at a.b[1])"), true, Ok({when: "2024-01-02"})]"#
+    );
+}
@@ -0,0 +1,69 @@
+use crate::test_support::eval_input;
+
+#[test]
+fn test_generator_yields_lazily_and_restarts() {
+    let input = r#"
+        let steps = Ref::new(0);
+        fn* count(n) {
+            let i = 0;
+            while (i < n) {
+                steps.set(steps.get() + 1);
+                yield i;
+                i = i + 1;
+            }
+        }
+        let c = count(1000000);
+        [Iter::collect(Iter::take(c, 3)), Iter::collect(Iter::take(c, 2)), steps.get()];
+    "#;
+    // Each consumption runs the body afresh and only as far as needed.
+    assert_eq!(eval_input(input).to_string(), "[[0, 1, 2], [0, 1], 5]");
+}
+
+#[test]
+fn test_generator_control_flow() {
+    let input = r#"
+        function* evens() {
+            for (let k = 0; ; k = k + 2) {
+                if (k > 6) { return 0; }
+                yield k;
+            }
+        }
+        function* label(v) {
+            switch (v) {
+                case 1: yield "one";
+                default: yield "other";
+            }
+            for (x in [v, v * 10]) { yield x; }
+        }
+        [Iter::collect(evens()), Iter::collect(label(1)), Iter::collect(label(2))];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        "[[0, 2, 4, 6], [\"one\", 1, 10], [\"other\", 2, 20]]"
+    );
+}
+
+#[test]
+fn test_for_in_over_arrays_seqs_and_generators() {
+    let input = r#"
+        let seen = Ref::new([]);
+        let pairs = fn*(xs) { for (x in xs) { yield [x, x * x]; } };
+        for (p in pairs([1, 2])) { Array::pushMut(seen, p); }
+        for (n in Iter::range(5, 7)) { Array::pushMut(seen, n); }
+        seen.get();
+    "#;
+    assert_eq!(eval_input(input).to_string(), "[[1, 1], [2, 4], 5, 6]");
+}
+
+#[test]
+fn test_generator_errors() {
+    let stray = eval_input("yield 1;");
+    assert!(stray.is_error());
+    assert!(stray.to_string().contains("generator function"));
+
+    let failing = eval_input(r#"fn* g() { yield 1; yield 1 / "x"; yield 3; } Iter::collect(g());"#);
+    assert!(failing.is_error());
+
+    let not_iterable = eval_input("for (x in 5) { x; }");
+    assert_eq!(not_iterable.to_string(), "for-in expects an array or seq, got integer");
+}
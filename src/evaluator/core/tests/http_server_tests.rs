@@ -0,0 +1,90 @@
+use std::thread;
+use std::time::Duration;
+
+use crate::object::Object;
+use crate::test_support::eval_input;
+
+fn free_port() -> u16 {
+    std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+}
+
+/// Send one request once the server is up, returning status, content type and body.
+fn request(method: &str, url: String, body: Option<&str>) -> thread::JoinHandle<(u16, String, String)> {
+    let method = method.to_string();
+    let body = body.map(str::to_string);
+    thread::spawn(move || {
+        for _ in 0..100 {
+            let request = ureq::request(&method, &url);
+            let outcome = match &body {
+                Some(body) => request.send_string(body),
+                None => request.call(),
+            };
+            let response = match outcome {
+                Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+                Err(_) => {
+                    thread::sleep(Duration::from_millis(20));
+                    continue;
+                }
+            };
+            let content_type = response.header("content-type").unwrap_or("").to_string();
+            return (response.status(), content_type, response.into_string().unwrap());
+        }
+        panic!("server never came up");
+    })
+}
+
+#[test]
+fn test_http_server_serves_requests() {
+    let port = free_port();
+    let base = format!("http://127.0.0.1:{}", port);
+    let client = thread::spawn(move || {
+        [
+            request("GET", format!("{}/hello?name=slang&tag=a&tag=b", base), None).join().unwrap(),
+            request("POST", format!("{}/echo", base), Some("ping")).join().unwrap(),
+            request("GET", format!("{}/fail", base), None).join().unwrap(),
+        ]
+    });
+
+    let input = format!(
+        r#"
+        let seen = Ref::new([]);
+        let result = HttpServer::listen({port}, fn(req) {{
+            Array::pushMut(seen, req.method + " " + req.path);
+            if (req.path == "/hello") {{
+                return {{ body: {{ greeting: "hi " + req.query.name, tags: req.query.tag }} }};
+            }}
+            if (req.path == "/echo") {{
+                return {{ status: 201, headers: {{ Location: "/echo/1" }}, body: req.body }};
+            }}
+            return Math::sqrt("not a number");
+        }}, {{ maxRequests: 3 }});
+        [result, seen.get()];
+        "#
+    );
+    assert_eq!(
+        eval_input(&input).to_string(),
+        r#"[Ok(null), ["GET /hello", "POST /echo", "GET /fail"]]"#
+    );
+
+    let [hello, echo, fail] = client.join().unwrap();
+    assert_eq!(hello.0, 200);
+    assert_eq!(hello.1, "application/json");
+    let hello: serde_json::Value = serde_json::from_str(&hello.2).unwrap();
+    assert_eq!(hello, serde_json::json!({ "greeting": "hi slang", "tags": ["a", "b"] }));
+    assert_eq!(echo, (201, "text/plain; charset=utf-8".to_string(), "ping".to_string()));
+    assert_eq!(fail.0, 500);
+}
+
+#[test]
+fn test_http_server_argument_errors() {
+    assert!(eval_input("HttpServer::listen(70000, fn(req) { req; });").is_error());
+    assert!(eval_input("HttpServer::listen(8080, 42);").is_error());
+    assert!(eval_input("HttpServer::listen(8080, fn(req) { req; }, { maxRequests: 0 });").is_error());
+
+    let port = free_port();
+    let _taken = std::net::TcpListener::bind(("127.0.0.1", port)).unwrap();
+    match eval_input(&format!("HttpServer::listen({}, fn(req) {{ req; }});", port)) {
+        Object::ResultErr(_) => {}
+        other => panic!("expected Err when the port is taken, got {:?}", other),
+    }
+}
@@ -96,6 +96,92 @@ fn test_http_head_error_handling() {
     }
 }
 
+/// Serve `requests` connections on a local port, answering each with the
+/// request body (or `\xff\x00A` when there is none) and echoing the request's
+/// Content-Type back in `X-Content-Type` and its target in `X-Target`.
+fn spawn_echo_server(requests: usize) -> String {
+    use std::io::{BufRead, BufReader, Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().take(requests) {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let (mut length, mut content_type) = (0, String::new());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let target = request_line.split(' ').nth(1).unwrap_or("").to_string();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+                let (name, value) = line.split_once(':').unwrap_or((line, ""));
+                match name.to_ascii_lowercase().as_str() {
+                    "content-length" => length = value.trim().parse().unwrap(),
+                    "content-type" => content_type = value.trim().to_string(),
+                    _ => {}
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            if body.is_empty() {
+                body = vec![0xff, 0x00, b'A'];
+            }
+            let head = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nX-Content-Type: {}\r\nX-Target: {}\r\nConnection: close\r\n\r\n",
+                body.len(),
+                content_type,
+                target
+            );
+            stream.write_all(head.as_bytes()).unwrap();
+            stream.write_all(&body).unwrap();
+        }
+    });
+    format!("http://{}", addr)
+}
+
+#[test]
+fn test_http_bytes_bodies() {
+    let url = spawn_echo_server(2);
+    let input = format!(
+        r#"
+        let got = Result::unwrapOr(HTTP::get("{url}", {{ bytes: true }}), 0);
+        let sent = Result::unwrapOr(HTTP::post("{url}", b"\x00\x01\xfe", {{ bytes: true }}), 0);
+        [got.body, sent.body, sent.headers["x-content-type"]];
+        "#
+    );
+    assert_eq!(
+        eval_input(&input).to_string(),
+        r#"[b"\xff\x00A", b"\x00\x01\xfe", "application/octet-stream"]"#
+    );
+}
+
+#[test]
+fn test_http_json_form_and_query_options() {
+    let url = spawn_echo_server(4);
+    let input = format!(
+        r#"
+        let parsed = Result::unwrapOr(HTTP::post("{url}", {{ name: "slang", tags: [1, 2] }}, {{ json: true }}), 0);
+        let form = Result::unwrapOr(HTTP::post("{url}", {{ q: "a b&c", n: 2, tag: ["x", "y"] }}, {{ form: true }}), 0);
+        let query = Result::unwrapOr(HTTP::get("{url}/search", {{ query: {{ q: "rust & slang", page: 1 }} }}), 0);
+        let notJson = HTTP::post("{url}", "not json", {{ json: true }});
+        [parsed.body.name, parsed.body.tags, form.body, form.headers["x-content-type"], query.headers["x-target"], Result::isErr(notJson)];
+        "#
+    );
+    assert_eq!(
+        eval_input(&input).to_string(),
+        r#"["slang", [1, 2], "n=2&q=a+b%26c&tag=x&tag=y", "application/x-www-form-urlencoded", "/search?page=1&q=rust+%26+slang", true]"#
+    );
+
+    assert!(eval_input(r#"HTTP::post("http://localhost:1", "text", { form: true });"#).is_error());
+    assert!(eval_input(r#"HTTP::get("http://localhost:1", { query: { nested: {} } });"#).is_error());
+    assert!(eval_input(r#"HTTP::get("http://localhost:1", { json: true, bytes: true });"#).is_error());
+}
+
 // Network tests - these require actual network access
 // Run with: cargo test -- --ignored
 
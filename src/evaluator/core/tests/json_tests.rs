@@ -43,3 +43,19 @@ fn test_json_namespace_parse_and_stringify() {
         other => panic!("expected array from Json namespace test, got {:?}", other),
     }
 }
+
+#[test]
+fn test_json_round_trip_keeps_key_order() {
+    let input = r#"
+        let obj = { z: 1, a: { y: true, b: "x" }, m: [1, 2] };
+        let text = Result::unwrapOr(Json::stringify(obj), "ERR");
+        let parsed = Result::unwrapOr(Json::parse(text), 0);
+        [text, Obj::keys(parsed), Result::unwrapOr(Json::stringify(parsed), "ERR") == text];
+    "#;
+
+    let obj = eval_input(input);
+    assert_eq!(
+        obj.to_string(),
+        r#"["{"z":1,"a":{"y":true,"b":"x"},"m":[1,2]}", ["z", "a", "m"], true]"#
+    );
+}
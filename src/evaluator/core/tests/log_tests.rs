@@ -0,0 +1,33 @@
+use crate::test_support::eval_input;
+
+#[test]
+fn test_log_level_filtering() {
+    let input = r#"
+        Log::setLevel("info");
+        let before = [Log::level(), Log::enabled("debug"), Log::enabled("warn")];
+        Log::setLevel("WARN");
+        let after = [Log::level(), Log::enabled("info"), Log::enabled("error")];
+        Log::setLevel("off");
+        [before, after, Log::enabled("error"), Log::error("dropped", { code: 1 })];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        "[[\"info\", false, true], [\"warn\", false, true], false, null]"
+    );
+}
+
+#[test]
+fn test_log_argument_errors() {
+    let tests = vec![
+        (r#"Log::info("x", 5);"#, "Log::info expects fields to be an object, got Integer(5)"),
+        (r#"Log::warn();"#, "Log::warn expects 1 or 2 arguments (message, [fields])"),
+        (
+            r#"Log::setLevel("loud");"#,
+            "Log::setLevel: unknown level 'loud' (expected debug, info, warn, error or off)",
+        ),
+        (r#"Log::setFormat("xml");"#, "Log::setFormat: unknown format 'xml' (expected text or json)"),
+    ];
+    for (input, expected) in tests {
+        assert_eq!(eval_input(input).to_string(), expected, "input: {}", input);
+    }
+}
@@ -67,7 +67,7 @@ fn test_math_logarithms() {
 
             match &vals[3] {
                 Object::Float(f) => {
-                    assert!((*f - 2.718281828).abs() < 0.0001, "exp(1) should be ~e")
+                    assert!((*f - std::f64::consts::E).abs() < 0.0001, "exp(1) should be ~e")
                 }
                 other => panic!("expected float, got {:?}", other),
             }
@@ -214,9 +214,9 @@ fn test_math_expansions_error_handling() {
 #[test]
 fn test_math_constants() {
     let input = r#"
-        let pi = Math::PI();
-        let e = Math::E();
-        let tau = Math::TAU();
+        let pi = Math::PI;
+        let e = Math::E;
+        let tau = Math::TAU;
 
         let tauCheck = tau - 2.0 * pi;
 
@@ -256,6 +256,21 @@ fn test_math_constants() {
     }
 }
 
+#[test]
+fn test_math_constants_still_accept_the_call_form() {
+    let input = r#"
+        [Math::PI() == Math::PI, Math::TAU() == Math::TAU, Type::of(Math::E), Type::isFloat(Math::PI)];
+    "#;
+    assert_eq!(eval_input(input).to_string(), "[true, true, \"float\", true]");
+
+    let obj = eval_input("Math::PI(2);");
+    assert!(obj.is_error());
+    assert_eq!(
+        obj.to_string(),
+        "Math::PI is a constant, not a function; use `Math::PI`"
+    );
+}
+
 #[test]
 fn test_math_sign() {
     let input = r#"
@@ -342,6 +357,27 @@ fn test_math_random() {
     }
 }
 
+#[test]
+fn test_math_random_hex() {
+    match eval_input("[Math::randomHex(7), Math::randomHex(0), Math::randomHex(32) == Math::randomHex(32)];") {
+        Object::Array(vals) => match (&vals[0], &vals[1]) {
+            (Object::String(hex), Object::String(empty)) => {
+                assert_eq!(hex.len(), 7);
+                assert!(hex.chars().all(|c| c.is_ascii_hexdigit() && !c.is_ascii_uppercase()));
+                assert!(empty.is_empty());
+                assert_eq!(vals[2], Object::Boolean(false));
+            }
+            other => panic!("expected strings from Math::randomHex, got {:?}", other),
+        },
+        other => panic!("expected array from Math::randomHex test, got {:?}", other),
+    }
+    assert!(eval_input("Math::randomHex(-1);").is_error());
+    assert_eq!(
+        eval_input("Math::randomHex(9223372036854775807);").to_string(),
+        "Math::randomHex length must be at most 1048576"
+    );
+}
+
 #[test]
 fn test_math_random_int() {
     let input = r#"
@@ -393,3 +429,55 @@ fn test_math_extras_error_handling() {
     let obj4 = eval_input(input4);
     assert!(obj4.is_error(), "Math::PI with argument should error");
 }
+
+#[test]
+fn test_math_trunc_and_round_to() {
+    let input = r#"
+        [
+            Math::trunc(2.7),
+            Math::trunc(-2.7),
+            Math::floor(-2.5),
+            Math::round(-2.5),
+            Math::roundTo(3.14159, 2),
+            Math::roundTo(-2.5, 0),
+            Math::roundTo(1250, -2),
+            Math::roundTo(-1250, -2),
+            Math::roundTo(42, 3),
+            Math::roundTo(1234.5, -2),
+            Type::of(Math::roundTo(1234.5, -2))
+        ];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        "[2, -2, -3, -3, 3.14, -3, 1300, -1300, 42, 1200, \"float\"]"
+    );
+
+    assert!(eval_input("Math::trunc(0.0 / 0.0);").is_error());
+    assert!(eval_input("Math::floor(1e300);").is_error());
+    assert!(eval_input("Math::roundTo(1.5, 0.5);").is_error());
+}
+
+#[test]
+fn test_math_floor_div_and_divmod() {
+    let input = r#"
+        [
+            Math::floorDiv(7, 2),
+            Math::floorDiv(-7, 2),
+            -7 / 2,
+            -7 div 2,
+            Math::divmod(-7, 2),
+            Math::divmod(7, -2),
+            Math::divmod(6, 3),
+            Math::divmod(7.5, 2)
+        ];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        "[3, -4, -3.5, -4, [-4, 1], [-4, -1], [2, 0], [3, 1.5]]"
+    );
+
+    let obj = eval_input("Math::floorDiv(1, 0);");
+    assert!(obj.is_error(), "expected division by zero error, got {:?}", obj);
+    assert!(eval_input("Math::divmod(1.0, 0);").is_error());
+    assert!(eval_input(r#"Math::divmod("7", 2);"#).is_error());
+}
@@ -1,15 +1,48 @@
 mod array_tests;
+mod bloom_tests;
+mod budget_tests;
+mod bytes_tests;
 mod class_tests;
 mod core_tests;
+#[cfg(feature = "crypto")]
+mod crypto_tests;
+mod debug_tests;
+mod dir_tests;
+mod embed_tests;
+#[cfg(feature = "encoding")]
+mod encoding_tests;
 mod file_tests;
 mod fn_tests;
+#[cfg(feature = "formats")]
+mod format_tests;
+mod generator_tests;
+#[cfg(feature = "http")]
+mod http_server_tests;
+#[cfg(feature = "http")]
 mod http_tests;
 mod json_tests;
+mod log_tests;
 mod math_tests;
 mod monad_tests;
+mod number_tests;
 mod object_tests;
+mod path_tests;
+#[cfg(unix)]
+mod process_tests;
+mod queue_tests;
+mod ref_tests;
+#[cfg(feature = "regex")]
 mod regex_tests;
+mod runtime_tests;
+mod sandbox_tests;
+// The shared fixture uses a `pattern` rule.
+#[cfg(feature = "regex")]
+mod schema_tests;
+mod seq_tests;
 mod string_tests;
 mod system_tests;
+mod task_tests;
+mod testing_tests;
 mod time_tests;
 mod type_tests;
+mod uuid_tests;
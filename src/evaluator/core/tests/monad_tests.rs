@@ -197,3 +197,109 @@ fn test_result_map_and_then() {
         ),
     }
 }
+
+#[test]
+fn test_option_extensions() {
+    let input = r#"
+        let some = Option::Some(4);
+        let none = Option::None();
+        [
+            Option::unwrap(some),
+            Option::expect(some, "missing"),
+            Option::okOr(some, "missing"),
+            Option::okOr(none, "missing"),
+            Option::filter(some, fn(x) { x > 3; }),
+            Option::filter(some, fn(x) { x > 5; }),
+            Option::zip(some, Option::Some("a")),
+            Option::zip(some, none),
+            Option::flatten(Option::Some(some)),
+            Option::flatten(Option::Some(none)),
+            Option::toArray(some),
+            Option::toArray(none)
+        ];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        r#"[4, 4, Ok(4), Err("missing"), Some(4), None, Some([4, "a"]), None, Some(4), None, [4], []]"#
+    );
+
+    assert_eq!(
+        eval_input(r#"Option::expect(Option::None(), "no user");"#),
+        Object::Error("no user".to_string())
+    );
+    assert!(eval_input("Option::unwrap(Option::None());").is_error());
+    assert!(eval_input("Option::flatten(Option::Some(1));").is_error());
+}
+
+#[test]
+fn test_result_extensions() {
+    let input = r#"
+        let ok = Result::Ok(1);
+        let err = Result::Err("boom");
+        [
+            Result::unwrap(ok),
+            Result::expect(ok, "loading"),
+            Result::ok(ok),
+            Result::ok(err),
+            Result::mapErr(err, fn(e) { "wrapped: " + e; }),
+            Result::mapErr(ok, fn(e) { "wrapped: " + e; })
+        ];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        r#"[1, 1, Some(1), None, Err("wrapped: boom"), Ok(1)]"#
+    );
+
+    assert_eq!(
+        eval_input(r#"Result::expect(Result::Err("boom"), "loading config");"#),
+        Object::Error("loading config: boom".to_string())
+    );
+    assert_eq!(
+        eval_input(r#"Result::unwrap(Result::Err("boom"));"#),
+        Object::Error("Result::unwrap called on Err: boom".to_string())
+    );
+}
+
+#[test]
+fn test_question_mark_propagates_none_and_err() {
+    let input = r#"
+        let parse = fn(s) { if (s == "") { Result::Err("empty"); } else { Result::Ok(len(s)); } };
+        let total = fn(a, b) {
+            let x = parse(a)?;
+            let y = parse(b)?;
+            Result::Ok(x + y);
+        };
+        let first = fn(xs) {
+            for (x in xs) {
+                let v = x?;
+                if (v > 1) { return Option::Some(v); }
+            }
+            Option::Some(0);
+        };
+        let outer = fn() { Result::Ok(total("ab", "")? + 1); };
+        [
+            total("ab", "c"),
+            total("ab", ""),
+            outer(),
+            first([Option::Some(1), Option::Some(2)]),
+            first([Option::Some(1), Option::None(), Option::Some(3)]),
+            Array::map([Option::Some(1), Option::None()], fn(o) { Option::Some(o? * 10); })
+        ];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        r#"[Ok(3), Err("empty"), Err("empty"), Some(2), None, [Some(10), None]]"#
+    );
+}
+
+#[test]
+fn test_question_mark_errors() {
+    assert_eq!(
+        eval_input("let f = fn() { 5?; }; f();"),
+        Object::Error("? expects an Option or Result, got integer".to_string())
+    );
+    assert_eq!(
+        eval_input(r#"let x = Result::Err("boom")?; 1;"#),
+        Object::Error(r#"? returned Err("boom") outside of a function"#.to_string())
+    );
+}
@@ -0,0 +1,73 @@
+use crate::object::Object;
+use crate::test_support::eval_input;
+
+fn some(value: Object) -> Object {
+    Object::OptionSome(Box::new(value))
+}
+
+#[test]
+fn test_number_parse_default_format() {
+    let input = r#"
+        [
+            Number::parse("1,234.56"),
+            Number::parse("  -1,234,567 "),
+            Number::parse("42"),
+            Number::parse("+0.5")
+        ];
+    "#;
+
+    assert_eq!(
+        eval_input(input),
+        Object::array(vec![
+            some(Object::Float(1234.56)),
+            some(Object::Integer(-1234567)),
+            some(Object::Integer(42)),
+            some(Object::Float(0.5)),
+        ])
+    );
+}
+
+#[test]
+fn test_number_parse_locale_separators() {
+    let input = r#"
+        let de = { thousandsSep: ".", decimalSep: "," };
+        let fr = { thousandsSep: " ", decimalSep: "," };
+        [Number::parse("1.234,56", de), Number::parse("12 345,5", fr), Number::parse("1,5", de)];
+    "#;
+
+    assert_eq!(
+        eval_input(input),
+        Object::array(vec![
+            some(Object::Float(1234.56)),
+            some(Object::Float(12345.5)),
+            some(Object::Float(1.5)),
+        ])
+    );
+}
+
+#[test]
+fn test_number_parse_rejects_malformed_input() {
+    let input = r#"
+        [
+            Number::parse("1,23"),
+            Number::parse("1.234,56"),
+            Number::parse("12a"),
+            Number::parse(""),
+            Number::parse("1.")
+        ];
+    "#;
+
+    assert_eq!(
+        eval_input(input),
+        Object::array(vec![Object::OptionNone; 5])
+    );
+
+    let errors = [
+        r#"Number::parse(12);"#,
+        r#"Number::parse("1", { decimalSep: "." , thousandsSep: "." });"#,
+        r#"Number::parse("1", { sep: "," });"#,
+    ];
+    for input in errors {
+        assert!(eval_input(input).is_error(), "expected error for {}", input);
+    }
+}
@@ -217,3 +217,225 @@ fn test_object_namespace_error_handling() {
         "Obj::fromEntries with invalid entry should error"
     );
 }
+
+#[test]
+fn test_object_namespace_and_legacy_obj_alias() {
+    let input = r#"
+        let o = { a: 1, b: 2 };
+        [Object::len(o), Obj::len(o), Object::has(o, "a")];
+    "#;
+
+    let obj = eval_input(input);
+    assert_eq!(
+        obj,
+        Object::array(vec![
+            Object::Integer(2),
+            Object::Integer(2),
+            Object::Boolean(true),
+        ])
+    );
+
+    // A user binding named like a legacy alias shadows the shim.
+    let shadowed = eval_input("let Obj = 5; Obj;");
+    assert_eq!(shadowed, Object::Integer(5));
+}
+
+#[test]
+fn test_optional_chaining_and_coalesce() {
+    let input = r#"
+        let cfg = { db: { host: "localhost" }, retries: 0 };
+        [
+            cfg.db?.host,
+            cfg.cache?.host,
+            cfg.cache?.host?.len,
+            cfg.cache?.connect(),
+            cfg.cache?.port ?? 6379,
+            cfg.retries ?? 3,
+            cfg.missing ?? cfg.db.host
+        ];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        r#"["localhost", null, null, null, 6379, 0, "localhost"]"#
+    );
+
+    let errors = vec![
+        ("let o = {}; o.a.b;", "property access not supported on value: Null"),
+        ("let o = {}; o?.a = 1;", "cannot assign through optional chaining (?.)"),
+        (
+            "let f = fn(x) { x; }; f.items;",
+            r#"property access not supported on value: Function(FunctionData { name: None, params: ["x"], generator: false, .. })"#,
+        ),
+    ];
+    for (input, expected) in errors {
+        match eval_input(input) {
+            Object::Error(msg) => assert_eq!(msg, expected, "input: {}", input),
+            other => panic!("expected error for {}, got {:?}", input, other),
+        }
+    }
+}
+
+#[test]
+fn test_object_keys_keep_insertion_order() {
+    let input = r#"
+        let obj = { zeta: 1, alpha: 2, mid: 3 };
+        obj.beta = 4;
+        obj.alpha = 5;
+        obj = Obj::delete(obj, "zeta");
+        obj = Obj::set(obj, "zeta", 6);
+        [Obj::keys(obj), obj];
+    "#;
+
+    let obj = eval_input(input);
+    assert_eq!(
+        obj.to_string(),
+        r#"[["alpha", "mid", "beta", "zeta"], {alpha: 5, mid: 3, beta: 4, zeta: 6}]"#
+    );
+}
+
+#[test]
+fn test_structural_equality() {
+    let input = r#"
+        [
+            [1, [2, {a: 3}]] == [1, [2, {a: 3}]],
+            {a: 1, b: 2} == {b: 2, a: 1},
+            {a: 1} != {a: 1, b: 2},
+            [1, 2] == [2, 1],
+            [1] == [1.0],
+            [1] == ["1"],
+            Option::Some({x: [1]}) == Option::Some({x: [1]}),
+            Option::None() == Option::None(),
+            Result::Ok(1) == Result::Err(1),
+            [1] == 1,
+            {}.missing == {}.other
+        ];
+    "#;
+
+    assert_eq!(
+        eval_input(input).to_string(),
+        "[true, true, true, false, true, false, true, true, false, false, true]"
+    );
+}
+
+#[test]
+fn test_object_freeze() {
+    let input = r#"
+        let cfg = Object::freeze({ db: { host: "a" }, ports: [1, { p: 2 }] });
+        let s = Object::set(cfg, "x", 1);
+        [Object::isFrozen(cfg), Object::isFrozen(cfg.db), Object::isFrozen(cfg.ports[1]), Object::isFrozen({}), Object::isFrozen(s), s.x];
+    "#;
+    assert_eq!(eval_input(input).to_string(), "[true, true, true, false, false, 1]");
+
+    for assignment in ["cfg.x = 1;", "cfg.db.host = \"b\";", "cfg.ports[0] = 2;", "let db = cfg.db; db.host = \"b\";"] {
+        let input = format!("let cfg = Object::freeze({{ db: {{ host: \"a\" }}, ports: [1] }}); {}", assignment);
+        let result = eval_input(&input);
+        assert!(
+            matches!(&result, Object::Error(msg) if msg.contains("frozen object")),
+            "{} should fail, got {:?}",
+            assignment,
+            result
+        );
+    }
+
+    let input = r#"Object::setMut(Ref::new(Object::freeze({})), "a", 1);"#;
+    assert!(eval_input(input).is_error());
+    assert!(eval_input("Object::freeze(1);").is_error());
+}
+
+#[test]
+fn test_object_freeze_arrays() {
+    let input = r#"
+        let xs = Object::freeze([1, [2], { a: 3 }]);
+        let cfg = Object::freeze({ ports: [80] });
+        let ports = cfg.ports;
+        let pushed = Array::push(xs, 4);
+        pushed[0] = 9;
+        [Object::isFrozen(xs), Object::isFrozen(xs[1]), Object::isFrozen(xs[2]), Object::isFrozen(ports), Object::isFrozen(pushed), pushed, xs];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        "[true, true, true, true, false, [9, [2], {a: 3}, 4], [1, [2], {a: 3}]]"
+    );
+
+    for assignment in ["xs[0] = 2;", "xs[1][0] = 2;", "let inner = xs[1]; inner[0] = 2;", "let p = cfg.ports; p[0] = 2;"] {
+        let input = format!("let xs = Object::freeze([1, [2]]); let cfg = Object::freeze({{ ports: [80] }}); {}", assignment);
+        let result = eval_input(&input);
+        assert!(
+            matches!(&result, Object::Error(msg) if msg.contains("frozen array")),
+            "{} should fail, got {:?}",
+            assignment,
+            result
+        );
+    }
+
+    assert!(eval_input("Array::pushMut(Ref::new(Object::freeze([])), 1);").is_error());
+}
+
+#[test]
+fn test_object_freeze_leaves_other_bindings_writable() {
+    let input = r#"
+        let o = { a: 1, nested: { b: 2 } };
+        let f = Object::freeze(o);
+        o.a = 2;
+        o.nested.b = 3;
+        let fresh = Object::set(Object::freeze({ a: 1 }), "a", 5);
+        fresh.a = 6;
+        [o, f, Object::isFrozen(o), Object::isFrozen(f), fresh.a, Object::isFrozen(fresh)];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        "[{a: 2, nested: {b: 3}}, {a: 1, nested: {b: 2}}, false, true, 6, false]"
+    );
+
+    let input = "let xs = [1]; let f = Object::freeze(xs); xs[0] = 2; [xs, f];";
+    assert_eq!(eval_input(input).to_string(), "[[2], [1]]");
+}
+
+#[test]
+fn test_object_clone_is_deep() {
+    let input = r#"
+        let cfg = Object::freeze({ db: { host: "a" } });
+        let copy = Object::clone(cfg);
+        copy.db.host = "b";
+        let cell = Ref::new([1]);
+        let holder = Object::clone({ cell: cell });
+        Array::pushMut(holder.cell, 2);
+        [cfg.db.host, copy.db.host, Object::isFrozen(copy), Ref::get(cell), Ref::get(holder.cell)];
+    "#;
+    assert_eq!(eval_input(input).to_string(), r#"["a", "b", false, [1], [1, 2]]"#);
+}
+
+#[test]
+fn test_object_transformation_helpers() {
+    let input = r#"
+        let prices = { apple: 3, pear: 5, plum: 2 };
+        [
+            Object::map(prices, fn(k, v) { [String::toUpper(k), v * 10]; }),
+            Object::mapValues(prices, fn(v) { v + 1; }),
+            Object::filter(prices, fn(k, v) { v > 2; }),
+            Object::pick(prices, ["plum", "apple", "kiwi"]),
+            Object::omit(prices, ["pear"])
+        ];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        "[{APPLE: 30, PEAR: 50, PLUM: 20}, {apple: 4, pear: 6, plum: 3}, {apple: 3, pear: 5}, {apple: 3, plum: 2}, {apple: 3, plum: 2}]"
+    );
+
+    assert!(eval_input("Object::map({a: 1}, fn(k, v) { v; });").is_error());
+    assert!(eval_input("Object::filter({a: 1}, fn(k, v) { v; });").is_error());
+    assert!(eval_input("Object::pick({a: 1}, [1]);").is_error());
+}
+
+#[test]
+fn test_object_merge_deep() {
+    let input = r#"
+        let defaults = { db: { host: "localhost", port: 5432, opts: { ssl: false } }, tags: ["a"], debug: false };
+        let overrides = { db: { host: "prod", opts: { ssl: true } }, tags: ["b"], extra: 1 };
+        [Object::mergeDeep(defaults, overrides), defaults.db.host];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        r#"[{db: {host: "prod", port: 5432, opts: {ssl: true}}, tags: ["b"], debug: false, extra: 1}, "localhost"]"#
+    );
+}
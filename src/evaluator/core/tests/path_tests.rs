@@ -0,0 +1,46 @@
+use crate::test_support::eval_input;
+
+#[test]
+fn test_path_components() {
+    let input = r#"
+        let p = Path::join("data", "reports", "q1.csv");
+        [
+            Path::basename(p),
+            Path::dirname(p),
+            Path::extension(p),
+            Path::extension("Makefile"),
+            Path::basename("/"),
+            Path::dirname("file.txt"),
+            Path::extension("archive.tar.gz")
+        ];
+    "#;
+    let dir = std::path::Path::new("data").join("reports");
+    assert_eq!(
+        eval_input(input).to_string(),
+        format!(
+            "[Some(\"q1.csv\"), Some({:?}), Some(\"csv\"), None, None, Some(\"\"), Some(\"gz\")]",
+            dir.display().to_string()
+        )
+    );
+}
+
+#[test]
+fn test_path_join_and_absolute() {
+    let cwd = std::env::current_dir().unwrap();
+    let input = r#"
+        [
+            Path::join("a") == "a",
+            Path::join("a", "/abs") == "/abs",
+            Result::unwrapOr(Path::absolute("no/such/file.txt"), "ERR")
+        ];
+    "#;
+    let obj = eval_input(input);
+    let abs = cwd.join("no/such/file.txt");
+    assert!(
+        obj.to_string() == format!("[true, true, {:?}]", abs.display().to_string()),
+        "{}",
+        obj
+    );
+    assert!(eval_input("Path::join();").is_error());
+    assert!(eval_input("Path::join(\"a\", 1);").is_error());
+}
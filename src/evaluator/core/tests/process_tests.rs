@@ -0,0 +1,80 @@
+use crate::object::Object;
+use crate::test_support::eval_input;
+
+#[test]
+fn test_process_run() {
+    let input = r#"
+        [
+            Process::run("echo", ["hello", "world"]),
+            Process::run("sh", ["-c", "echo out; echo err >&2; exit 3"])
+        ];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        "[Ok({status: 0, stdout: \"hello world\n\", stderr: \"\"}), Err({status: 3, stdout: \"out\n\", stderr: \"err\n\"})]"
+    );
+}
+
+#[test]
+fn test_process_run_options() {
+    let dir = std::env::temp_dir().canonicalize().unwrap();
+    let input = format!(
+        r#"
+        let r = Result::unwrap(Process::run("sh", ["-c", "echo $GREETING; pwd"], {{env: {{GREETING: "hi"}}, cwd: {:?}}}));
+        [r.stdout, Result::unwrap(Process::run("tr", ["a-z", "A-Z"], {{stdin: "shout"}})).stdout];
+        "#,
+        dir.display().to_string()
+    );
+    assert_eq!(
+        eval_input(&input).to_string(),
+        format!("[\"hi\n{}\n\", \"SHOUT\"]", dir.display())
+    );
+}
+
+#[test]
+fn test_process_streams() {
+    let input = r#"
+        let p = Result::unwrap(Process::spawn("cat"));
+        Process::writeStdin(p, "one\n");
+        let first = Process::readStdout(p);
+        Process::writeStdin(p, "two\n");
+        Process::closeStdin(p);
+        [first, Process::readStdout(p), Process::readStdout(p), Process::readStderr(p), Process::wait(p)];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        "[Some(\"one\n\"), Some(\"two\n\"), None, None, 0]"
+    );
+}
+
+#[test]
+fn test_process_wait_and_kill() {
+    let input = r#"
+        let done = Result::unwrap(Process::spawn("sh", ["-c", "exit 4"]));
+        let slow = Result::unwrap(Process::spawn("sleep", ["10"]));
+        [Process::wait(done), Process::kill(done), Process::kill(slow), Process::wait(slow)];
+    "#;
+    assert_eq!(eval_input(input).to_string(), "[4, false, true, -1]");
+
+    let input = r#"Result::isErr(Process::spawn("slang-no-such-command"));"#;
+    assert_eq!(eval_input(input), Object::Boolean(true));
+}
+
+#[test]
+fn test_process_errors() {
+    let errors = vec![
+        ("Process::run();", "Process::run expects (cmd, args?, options?)"),
+        ("Process::run(\"echo\", [1]);", "Process::run arguments must be strings, got integer"),
+        ("Process::spawn(\"echo\", [], {shell: true});", "Process::spawn: unknown option `shell`"),
+        ("Process::run(\"echo\", [], {cwd: 1});", "Process::run option `cwd` must be a string, got integer"),
+        ("Process::run(\"echo\", [], {env: {A: 1}});", "Process::run environment values must be strings"),
+        ("Process::wait(\"p\");", "Process::wait expects a process handle, got string"),
+        ("Process::readStdout(999);", "Process::readStdout: no process 999"),
+    ];
+    for (input, expected) in errors {
+        match eval_input(input) {
+            Object::Error(msg) => assert!(msg.contains(expected), "input: {}\nerror: {}", input, msg),
+            other => panic!("expected error for {}, got {:?}", input, other),
+        }
+    }
+}
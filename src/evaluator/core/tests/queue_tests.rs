@@ -0,0 +1,29 @@
+use crate::test_support::eval_input;
+
+#[test]
+fn test_queue_push_and_pop_in_order() {
+    let input = r#"
+        let q = Queue::push(Queue::push(Queue::empty(), "a"), "b");
+        let [front, rest] = Option::unwrap(Queue::pop(q));
+        [front, Queue::toArray(rest), Queue::len(q), Queue::peek(rest)];
+    "#;
+
+    assert_eq!(eval_input(input).to_string(), "[\"a\", [\"b\"], 2, Some(\"b\")]");
+}
+
+#[test]
+fn test_queue_empty_and_from() {
+    let input = r#"
+        let q = Queue::from([1, 2, 3]);
+        [Queue::isEmpty(Queue::empty()), Queue::pop(Queue::empty()), Queue::peek(Queue::empty()), Queue::isEmpty(q), Queue::peek(q)];
+    "#;
+
+    assert_eq!(eval_input(input).to_string(), "[true, None, None, false, Some(1)]");
+}
+
+#[test]
+fn test_queue_functions_given_a_function_fail() {
+    for input in ["Queue::len(fn(x) { x; });", "Queue::toArray(fn(x) { x; });"] {
+        assert!(eval_input(input).is_error(), "{} should be an error", input);
+    }
+}
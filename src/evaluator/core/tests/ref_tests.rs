@@ -0,0 +1,77 @@
+use crate::object::Object;
+use crate::test_support::eval_input;
+
+#[test]
+fn test_values_are_copied_into_functions() {
+    let input = r#"
+        let arr = [1, 2];
+        let obj = { n: 1 };
+        fn mutate(a, o) { a[0] = 9; o.n = 9; }
+        mutate(arr, obj);
+        [arr[0], obj.n];
+    "#;
+
+    assert_eq!(eval_input(input).to_string(), "[1, 1]");
+}
+
+#[test]
+fn test_refs_share_mutations() {
+    let tests = vec![
+        (
+            r#"
+                let log = Ref::new([]);
+                fn record(r, msg) { Array::pushMut(r, msg); }
+                record(log, "a");
+                record(log, "b");
+                Ref::get(log);
+            "#,
+            r#"["a", "b"]"#,
+        ),
+        ("let r = Ref::new([1]); Array::pushMut(r, 2);", "2"),
+        (
+            r#"
+                let cfg = Ref::new({});
+                fn configure(c) { Object::setMut(c, "debug", true); }
+                configure(cfg);
+                cfg.get().debug;
+            "#,
+            "true",
+        ),
+        ("let r = Ref::new(1); let alias = r; alias.set(5); r.get();", "5"),
+        ("let r = Ref::new([1]); let snapshot = r.get(); Array::pushMut(r, 2); snapshot;", "[1]"),
+        ("Ref::new([1, 2]);", "Ref([1, 2])"),
+        ("Type::of(Ref::new(0));", r#""ref""#),
+    ];
+
+    for (input, expected) in tests {
+        assert_eq!(eval_input(input).to_string(), expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_ref_errors() {
+    let tests = vec![
+        ("Array::pushMut([1], 2);", "Array::pushMut expects a ref as first argument, got array"),
+        (
+            "Array::pushMut(Ref::new({}), 2);",
+            "Array::pushMut expects a ref holding an array, got ref to object",
+        ),
+        (
+            r#"Object::setMut(Ref::new([]), "k", 1);"#,
+            "Object::setMut expects a ref holding an object, got ref to array",
+        ),
+        ("let r = Ref::new(0); Ref::set(r, [r]);", "Ref::set cannot store a ref inside itself"),
+        (
+            "let r = Ref::new([]); let s = Ref::new([r]); Array::pushMut(r, s);",
+            "Array::pushMut cannot store a ref inside itself",
+        ),
+        ("Ref::get(1);", "Ref::get expects a ref as first argument, got integer"),
+    ];
+
+    for (input, expected) in tests {
+        match eval_input(input) {
+            Object::Error(msg) => assert_eq!(msg, expected, "input: {}", input),
+            other => panic!("expected error for {}, got {:?}", input, other),
+        }
+    }
+}
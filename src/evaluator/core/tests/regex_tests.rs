@@ -59,3 +59,56 @@ fn test_regex_builtins() {
         other => panic!("expected array from regex builtins test, got {:?}", other),
     }
 }
+
+#[test]
+fn test_regex_find_all_and_split() {
+    let input = r#"
+        let words = Regex::findAll("one 1, two 22, three 333", "\d+");
+        let none = Regex::findAll("abc", "\d+");
+        let parts = Regex::split("a, b,c ,d", "\s*,\s*");
+        [words, none, parts];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        r#"[["1", "22", "333"], [], ["a", "b", "c", "d"]]"#
+    );
+}
+
+#[test]
+fn test_regex_match_all_and_named_captures() {
+    let input = r#"
+        let all = Regex::matchAll("a=1; b=22", "(?P<key>\w+)=(?P<value>\d+)");
+        let second = all[1];
+        let date = Option::unwrapOr(Regex::captures("due 2024-05-06", "(?P<year>\d{4})-(?P<month>\d\d)-(\d\d)"), {});
+        let optional = Option::unwrapOr(Regex::captures("ab", "a(x)?b"), {});
+        [len(all), second.match, second.start, second.end, second.named.value, date.named.year, date.groups[3], optional.groups[1]];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        r#"[2, "b=22", 5, 9, "22", "2024", "06", null]"#
+    );
+}
+
+#[test]
+fn test_regex_replace_with_capture_references() {
+    let input = r#"
+        let swapped = Regex::replace("John Smith", "(\w+) (\w+)", "$2, $1");
+        let named = Regex::replace("2024-05-06", "(?P<y>\d+)-(?P<m>\d+)-(?P<d>\d+)", "${d}/${m}/${y}");
+        let dollars = Regex::replace("5", "(\d)", "$$${1}");
+        [swapped, named, dollars];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        r#"["Smith, John", "06/05/2024", "$5"]"#
+    );
+}
+
+#[test]
+fn test_regex_new_helpers_reject_bad_arguments() {
+    assert_eq!(
+        eval_input(r#"Regex::split("abc", "(");"#).to_string(),
+        eval_input(r#"Regex::find("abc", "(");"#).to_string()
+    );
+    assert!(eval_input(r#"Regex::findAll("abc", 1);"#).is_error());
+    assert!(eval_input(r#"Regex::matchAll("abc");"#).is_error());
+}
@@ -0,0 +1,153 @@
+use crate::env::new_env;
+use crate::event_loop;
+use crate::object::Object;
+use crate::test_support::eval_input;
+
+#[test]
+fn test_timer_cancel() {
+    let input = r#"
+        let h = Timer::after(1, fn() { 1; });
+        [Timer::cancel(h), Timer::cancel(h), Timer::cancel(12345)];
+    "#;
+    assert_eq!(
+        eval_input(input),
+        Object::array(vec![
+            Object::Boolean(true),
+            Object::Boolean(false),
+            Object::Boolean(false)
+        ])
+    );
+}
+
+#[test]
+fn test_runtime_run_dispatches_timers_in_due_order() {
+    // Callbacks that reference unbound names fail, so the error returned by
+    // Runtime::run shows which timer fired first.
+    let input = r#"
+        Timer::after(20, fn() { late_timer; });
+        Timer::after(1, fn() { early_timer; });
+        Runtime::run();
+    "#;
+    match eval_input(input) {
+        Object::Error(msg) => assert!(msg.contains("early_timer"), "got {}", msg),
+        other => panic!("expected the first timer's error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_runtime_run_returns_once_timers_are_done() {
+    let input = r#"
+        let h = Timer::every(1, fn() { 1; });
+        Timer::after(5, fn() { Timer::cancel(h); });
+        let cancelled = Timer::after(1, fn() { never; });
+        Timer::cancel(cancelled);
+        Runtime::run();
+    "#;
+    assert_eq!(eval_input(input), Object::Null);
+}
+
+#[test]
+fn test_timer_error_handling() {
+    let tests = vec![
+        ("Timer::after(1);", "Timer::after expects 2 arguments (ms, fn)"),
+        ("Timer::after(-1, fn() { 1; });", "Timer::after milliseconds must be non-negative"),
+        ("Timer::after(1, 2);", "Timer::after expects a function as second argument, got integer"),
+        ("Timer::every(0, fn() { 1; });", "Timer::every interval must be positive"),
+        ("Timer::cancel();", "Timer::cancel expects exactly 1 argument"),
+        ("Runtime::run(1);", "Runtime::run expects no arguments"),
+    ];
+    for (input, expected) in tests {
+        match eval_input(input) {
+            Object::Error(msg) => assert_eq!(msg, expected, "input: {}", input),
+            other => panic!("expected error for {}, got {:?}", input, other),
+        }
+    }
+}
+
+#[test]
+fn test_events_are_delivered_when_pumped() {
+    let input = r#"
+        let log = Ref::new([]);
+        function note(x) { Ref::set(log, Array::push(Ref::get(log), x)); }
+
+        (:Order)
+        function onOrder(id, qty) { note(["order", id]); Events::publish("Ship", id); }
+
+        Events::subscribe(":Ship", fn(ids) { note(["ship", ids[0]]); });
+        Events::publish("Order", 1, 2);
+        Events::publish("Order", 7);
+        let before = [Ref::get(log), Events::pending()];
+        Events::run();
+        [before, Ref::get(log), Events::pending()];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        "[[[], 2], [[\"order\", 1], [\"order\", 7], [\"ship\", 1], [\"ship\", 7]], 0]"
+    );
+}
+
+#[test]
+fn test_runtime_run_delivers_events_published_by_timers() {
+    let input = r#"
+        let seen = Ref::new([]);
+        Events::subscribe("Tick", fn(n) { Ref::set(seen, Array::push(Ref::get(seen), n[0])); });
+        Timer::after(2, fn() { Events::publish("Tick", 2); });
+        Events::publish("Tick", 1);
+        Runtime::run();
+        Ref::get(seen);
+    "#;
+    assert_eq!(eval_input(input).to_string(), "[1, 2]");
+
+    // A failing handler stops the pump; later events stay queued.
+    let input = r#"
+        Events::subscribe("Boom", fn() { missing_handler_name; });
+        Events::publish("Boom");
+        Events::publish("Later");
+        let result = Events::run();
+        [Type::of(result), Events::pending()];
+    "#;
+    assert_eq!(eval_input(input).to_string(), "[\"error\", 1]");
+    match eval_input(r#"Events::subscribe("Boom", fn() { missing_handler_name; }); Events::publish("Boom"); Events::run();"#) {
+        Object::Error(msg) => assert!(msg.contains("missing_handler_name"), "got {}", msg),
+        other => panic!("expected the handler's error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_events_error_handling() {
+    let tests = vec![
+        ("Events::subscribe(\"T\");", "Events::subscribe expects 2 arguments (tag, fn)"),
+        ("Events::subscribe(1, fn() { 1; });", "Events::subscribe expects a tag string, got integer"),
+        ("Events::subscribe(\"T\", 2);", "Events::subscribe expects a function as second argument, got integer"),
+        ("Events::publish();", "Events::publish expects a tag and values"),
+        ("Events::publish(\":\", 1);", "Events::publish expects a non-empty tag"),
+        ("Events::run(1);", "Events::run expects no arguments"),
+    ];
+    for (input, expected) in tests {
+        match eval_input(input) {
+            Object::Error(msg) => assert_eq!(msg, expected, "input: {}", input),
+            other => panic!("expected error for {}, got {:?}", input, other),
+        }
+    }
+}
+
+#[test]
+fn test_shutdown_hooks_run_last_registered_first() {
+    let input = r#"
+        Runtime::onShutdown(fn() { registered_first; });
+        Runtime::onShutdown(fn() { registered_second; });
+    "#;
+    assert_eq!(eval_input(input), Object::Null);
+
+    // Every hook runs even if one fails; the first failure is reported.
+    match event_loop::shutdown(new_env()) {
+        Object::Error(msg) => assert!(msg.contains("registered_second"), "got {}", msg),
+        other => panic!("expected a hook error, got {:?}", other),
+    }
+    assert_eq!(event_loop::shutdown(new_env()), Object::Null);
+
+    match eval_input("Runtime::onShutdown(1);") {
+        Object::Error(msg) => assert_eq!(msg, "Runtime::onShutdown expects a function, got integer"),
+        other => panic!("expected error, got {:?}", other),
+    }
+}
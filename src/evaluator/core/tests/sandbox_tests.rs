@@ -0,0 +1,74 @@
+use crate::env::{EnvBuilder, EnvRef};
+use crate::evaluator::eval;
+use crate::lexer::Lexer;
+use crate::object::Object;
+use crate::parser::Parser;
+use crate::test_support::check_errors;
+
+fn eval_in(input: &str, env: EnvRef) -> Object {
+    let mut p = Parser::new(Lexer::new(input));
+    let program = p.parse_program();
+    check_errors(&p);
+    eval(&program, env)
+}
+
+fn sandbox() -> EnvRef {
+    EnvBuilder::new().without_fs().without_net().without_process().build()
+}
+
+#[test]
+fn test_sandbox_unbinds_disabled_namespaces_and_builtins() {
+    let tests = vec![
+        ("File;", "File is not available: filesystem access is disabled in this environment"),
+        ("Dir::list(\".\");", "Dir is not available: filesystem access is disabled in this environment"),
+        ("file_open(\"x.txt\", \"r\");", "file_open is not available: filesystem access is disabled in this environment"),
+        ("HTTP::get(\"http://example.com\");", "HTTP is not available: network access is disabled in this environment"),
+        ("function fetch() { HTTP; } fetch();", "HTTP is not available: network access is disabled in this environment"),
+        ("import \"lib.sl\";", "cannot import 'lib.sl': filesystem access is disabled in this environment"),
+        ("Process::run(\"echo\");", "Process is not available: process access is disabled in this environment"),
+    ];
+
+    for (input, expected) in tests {
+        match eval_in(input, sandbox()) {
+            Object::Error(msg) => assert_eq!(msg, expected, "input: {}", input),
+            other => panic!("expected error for {}, got {:?}", input, other),
+        }
+    }
+}
+
+#[test]
+fn test_sandbox_keeps_safe_builtins() {
+    let input = r#"
+        [
+            Type::isNull(Sys::exec),
+            Type::isNull(Sys::cwd),
+            Type::isNull(Path::absolute),
+            Path::join("a", "b") == Path::join("a", "b"),
+            Sys::hasNamespace("HTTP"),
+            Sys::hasNamespace("Json"),
+            Type::isString(Sys::platform),
+            Array::sumBy([1, 2, 3], fn(x) { x; })
+        ];
+    "#;
+    assert_eq!(
+        eval_in(input, sandbox()).to_string(),
+        "[true, true, true, true, false, true, true, 6]"
+    );
+}
+
+#[test]
+fn test_groups_are_disabled_independently() {
+    let env = EnvBuilder::new().without_net().build();
+    assert_eq!(
+        eval_in("[Sys::hasNamespace(\"File\"), Sys::hasNamespace(\"HTTP\"), Type::isNull(Sys::exec)];", env)
+            .to_string(),
+        "[true, false, false]"
+    );
+
+    let env = EnvBuilder::new().without_process().build();
+    assert_eq!(
+        eval_in("[Sys::hasNamespace(\"HTTP\"), Type::isNull(Sys::exec), Type::isNull(Sys::cwd)];", env)
+            .to_string(),
+        format!("[{}, true, false]", cfg!(feature = "http"))
+    );
+}
@@ -0,0 +1,132 @@
+use crate::object::Object;
+use crate::test_support::eval_input;
+
+const USER_SCHEMA: &str = r#"
+    let User = Schema::object({
+        name: Schema::string({ min: 1 }),
+        age: Schema::int({ min: 0, max: 150 }),
+        tags: Schema::array(Schema::string(), { max: 2 }),
+        address: Schema::optional(Schema::object({ city: Schema::string() })),
+        email: Schema::optional(Schema::string({ pattern: "^[^@]+@[^@]+$" }))
+    }, { strict: true });
+"#;
+
+fn errors_of(result: Object) -> Vec<String> {
+    match result {
+        Object::ResultErr(inner) => match *inner {
+            Object::Array(items) => items
+                .iter()
+                .map(|item| match item {
+                    Object::String(s) => s.clone(),
+                    other => panic!("expected error string, got {:?}", other),
+                })
+                .collect(),
+            other => panic!("expected an array of errors, got {:?}", other),
+        },
+        other => panic!("expected Result::Err, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_schema_validate_accepts_matching_values() {
+    let input = format!(
+        r#"{}
+        let user = {{ name: "Ada", age: 36, tags: ["math"], address: {{ city: "London" }} }};
+        Result::isOk(Schema::validate(User, user));
+        "#,
+        USER_SCHEMA
+    );
+    assert_eq!(eval_input(&input), Object::Boolean(true));
+}
+
+#[test]
+fn test_schema_validate_reports_paths() {
+    let input = format!(
+        r#"{}
+        Schema::validate(User, {{
+            name: "",
+            age: "36",
+            tags: ["a", 2, "c"],
+            address: {{ city: 5 }},
+            email: "nope",
+            admin: true
+        }});
+        "#,
+        USER_SCHEMA
+    );
+    assert_eq!(
+        errors_of(eval_input(&input)),
+        vec![
+            "address.city: expected string, got integer",
+            "age: expected int, got string",
+            "email: must match pattern ^[^@]+@[^@]+$",
+            "name: length must be at least 1",
+            "tags: length must be at most 2",
+            "tags[1]: expected string, got integer",
+            "admin: is not an allowed field",
+        ]
+    );
+
+    assert_eq!(
+        errors_of(eval_input(&format!("{} Schema::validate(User, {{}});", USER_SCHEMA))),
+        vec!["age: is required", "name: is required", "tags: is required"]
+    );
+    assert_eq!(
+        errors_of(eval_input("Schema::validate(Schema::float({ max: 1 }), 1.5);")),
+        vec!["value: must be at most 1"]
+    );
+}
+
+#[test]
+fn test_schema_coerce_converts_values() {
+    let input = r#"
+        let Config = Schema::object({
+            port: Schema::int(),
+            ratio: Schema::float(),
+            debug: Schema::bool(),
+            name: Schema::string(),
+            hosts: Schema::array(Schema::int())
+        });
+        Schema::coerce(Config, { port: "8080", ratio: 1, debug: "true", name: 42, hosts: ["1", 2] });
+    "#;
+    match eval_input(input) {
+        Object::ResultOk(value) => match *value {
+            Object::Object(map) => {
+                assert_eq!(map["port"], Object::Integer(8080));
+                assert_eq!(map["ratio"], Object::Float(1.0));
+                assert_eq!(map["debug"], Object::Boolean(true));
+                assert_eq!(map["name"], Object::String("42".to_string()));
+                assert_eq!(
+                    map["hosts"],
+                    Object::array(vec![Object::Integer(1), Object::Integer(2)])
+                );
+            }
+            other => panic!("expected object, got {:?}", other),
+        },
+        other => panic!("expected Result::Ok, got {:?}", other),
+    }
+
+    assert_eq!(
+        errors_of(eval_input(r#"Schema::coerce(Schema::int(), "4.5");"#)),
+        vec!["value: expected int, got string"]
+    );
+}
+
+#[test]
+fn test_schema_error_handling() {
+    let tests = vec![
+        ("Schema::string({ size: 1 });", "Schema::string got unknown option size (expected one of: min, max, pattern)"),
+        ("Schema::int({ min: \"0\" });", "Schema::int option min has an invalid value: \"0\""),
+        ("Schema::string({ pattern: \"(\" });", "Schema::string option pattern has an invalid value: \"(\""),
+        ("Schema::object({ a: 1 });", "Schema::object field a is not a schema, got integer"),
+        ("Schema::array(5);", "Schema::array expects a schema (e.g. from Schema::string), got integer"),
+        ("Schema::validate({}, 1);", "Schema::validate expects a schema (e.g. from Schema::string), got object"),
+        ("Schema::validate(Schema::int());", "Schema::validate expects 2 arguments (schema, value)"),
+    ];
+    for (input, expected) in tests {
+        match eval_input(input) {
+            Object::Error(msg) => assert_eq!(msg, expected, "input: {}", input),
+            other => panic!("expected error for {}, got {:?}", input, other),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use crate::object::Object;
+use crate::test_support::eval_input;
+
+#[test]
+fn test_seq_adapters_are_lazy_and_reusable() {
+    let input = r#"
+        let calls = Ref::new(0);
+        let squares = Seq::range(1).map(fn(x) { Ref::set(calls, Ref::get(calls) + 1); x * x });
+        let small = squares.takeWhile(fn(x) { x < 30 }).toArray();
+        let again = Seq::toArray(Seq::take(squares, 3));
+        [small, again, Ref::get(calls)];
+    "#;
+    // takeWhile pulls one value past the run (36) to see it fail.
+    assert_eq!(
+        eval_input(input).to_string(),
+        "[[1, 4, 9, 16, 25], [1, 4, 9], 9]"
+    );
+}
+
+#[test]
+fn test_seq_drop_while_enumerate_and_filter() {
+    let input = r#"
+        let s = Seq::from([1, 2, 5, 1, 7]);
+        [
+            s.dropWhile(fn(x) { x < 3 }).toArray(),
+            s.skipWhile(fn(x) { x < 3 }).enumerate().toArray(),
+            Seq::range(10, 0, -3).filter(fn(x) { x % 2 == 0 }).toArray(),
+            Seq::range(0, 10).drop(8).toArray(),
+            Type::of(s)
+        ];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        "[[5, 1, 7], [[0, 5], [1, 1], [2, 7]], [10, 4], [8, 9], \"seq\"]"
+    );
+}
+
+#[test]
+fn test_seq_errors_stop_consumption() {
+    let bad_predicate = eval_input(r#"Seq::from([1, 2]).takeWhile(fn(x) { 1 }).toArray();"#);
+    assert_eq!(
+        bad_predicate,
+        Object::error("Seq::takeWhile predicate must return boolean, got Integer(1)")
+    );
+
+    let input = r#"
+        Seq::range(0)
+            .map(fn(x) { if (x == 2) { x + "a" } else { x } })
+            .enumerate()
+            .toArray();
+    "#;
+    assert!(eval_input(input).is_error());
+    assert!(eval_input("Seq::range(0, 5, 0);").is_error());
+    assert!(eval_input("Seq::take(Seq::range(0), -1);").is_error());
+}
+
+#[test]
+fn test_iter_is_seq_under_iterator_names() {
+    let input = r#"
+        let evens = Iter::range(0, 10000000)
+            .filter(fn(x) { x % 2 == 0 })
+            .map(fn(x) { x * 10 })
+            .take(3);
+        [Iter::collect(evens), Iter::fromArray(["a", "b"]).collect(), Type::of(evens), Seq::collect(evens)];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        r#"[[0, 20, 40], ["a", "b"], "seq", [0, 20, 40]]"#
+    );
+}
@@ -508,3 +508,141 @@ fn test_string_extras_error_handling() {
         ),
     }
 }
+
+#[test]
+fn test_string_words_and_lines() {
+    let tests = vec![
+        (r#"String::words("  Don't stop, believing! ok_1 ");"#, r#"["Don't", "stop", "believing", "ok_1"]"#),
+        (r#"String::words("...");"#, "[]"),
+        // String literals have no escapes, so the line breaks are embedded directly.
+        ("String::lines(\"a\r\nb\n\nc\n\");", r#"["a", "b", "", "c"]"#),
+        (r#"String::lines("");"#, "[]"),
+    ];
+    for (input, expected) in tests {
+        assert_eq!(eval_input(input).to_string(), expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_string_natural_compare_and_levenshtein() {
+    let tests = vec![
+        (r#"String::naturalCompare("file2", "file10");"#, "-1"),
+        (r#"String::naturalCompare("file10", "file2");"#, "1"),
+        (r#"String::naturalCompare("a1b", "a1b");"#, "0"),
+        (r#"String::naturalCompare("x01", "x1");"#, "-1"),
+        (
+            r#"Array::sortBy(["v10.txt", "v9.txt", "v1.txt", "a.txt"], String::naturalCompare);"#,
+            r#"["a.txt", "v1.txt", "v9.txt", "v10.txt"]"#,
+        ),
+        (r#"String::levenshtein("kitten", "sitting");"#, "3"),
+        (r#"String::levenshtein("", "abc");"#, "3"),
+        (r#"String::levenshtein("héllo", "hello");"#, "1"),
+        (r#""prnit".levenshtein("print");"#, "2"),
+    ];
+    for (input, expected) in tests {
+        assert_eq!(eval_input(input).to_string(), expected, "input: {}", input);
+    }
+
+    match eval_input(r#"String::levenshtein("a", 1);"#) {
+        Object::Error(msg) => assert!(msg.starts_with("String::levenshtein expects two strings"), "{}", msg),
+        other => panic!("expected error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_string_case_conversion() {
+    let tests = vec![
+        (r#"String::toCamelCase("user_id");"#, r#""userId""#),
+        (r#"String::toCamelCase("User ID");"#, r#""userId""#),
+        (r#"String::toCamelCase("parseHTTPResponse");"#, r#""parseHttpResponse""#),
+        (r#"String::toCamelCase("--max-retry-count--");"#, r#""maxRetryCount""#),
+        (r#"String::toSnakeCase("parseHTTPResponse");"#, r#""parse_http_response""#),
+        (r#"String::toSnakeCase("version2Beta");"#, r#""version2_beta""#),
+        (r#"String::toSnakeCase("ÉcoleNormale");"#, r#""école_normale""#),
+        (r#"String::toKebabCase("XMLHttpRequest");"#, r#""xml-http-request""#),
+        (r#"String::toTitleCase("max_retry-count");"#, r#""Max Retry Count""#),
+        (r#"String::toCamelCase("straße_nr");"#, r#""straßeNr""#),
+        (r#"String::capitalize("nASA rocks");"#, r#""NASA rocks""#),
+        (r#"String::capitalize("ärger");"#, r#""Ärger""#),
+        (r#"String::capitalize("");"#, r#""""#),
+        (r#""some_key".toCamelCase();"#, r#""someKey""#),
+    ];
+    for (input, expected) in tests {
+        assert_eq!(eval_input(input).to_string(), expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_string_truncate_wrap_indent() {
+    let tests = vec![
+        (r#"String::truncate("hello world", 8);"#, r#""hello...""#),
+        (r#"String::truncate("hello world", 8, "…");"#, r#""hello w…""#),
+        (r#"String::truncate("hello world", 7, "…");"#, r#""hello…""#),
+        (r#"String::truncate("short", 10);"#, r#""short""#),
+        (r#"String::truncate("héllo wörld", 2);"#, r#""hé""#),
+        (
+            r#"String::wrap("the quick brown fox jumps over the lazy dog", 10);"#,
+            r#"["the quick", "brown fox", "jumps over", "the lazy", "dog"]"#,
+        ),
+        (r#"String::wrap("abcdefghij kl", 4);"#, r#"["abcd", "efgh", "ij", "kl"]"#),
+        (r#"String::wrap("", 4);"#, "[]"),
+        ("String::wrap(\"one two\n\nthree\", 20);", r#"["one two", "", "three"]"#),
+        ("String::indent(\"a\n\nb\n\", \"  \");", "\"  a\n\n  b\n\""),
+    ];
+    for (input, expected) in tests {
+        assert_eq!(eval_input(input).to_string(), expected, "input: {}", input);
+    }
+
+    match eval_input(r#"String::wrap("x", 0);"#) {
+        Object::Error(msg) => assert_eq!(msg, "String::wrap width must be positive"),
+        other => panic!("expected error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_string_format() {
+    let tests = vec![
+        (r#"String::format("Hello {} you are {:.2} years", "Ada", 36.456);"#, r#""Hello Ada you are 36.46 years""#),
+        (r#"String::format("{1}-{0}-{1}", "a", "b");"#, r#""b-a-b""#),
+        (r#"String::format("{name} is {age}", { name: "Ada", age: 36 });"#, r#""Ada is 36""#),
+        (r#"String::format("[{:<6}|{:>6}|{:^6}]", "ab", "cd", "ef");"#, r#""[ab    |    cd|  ef  ]""#),
+        (r#"String::format("[{:5}|{:5}]", "ab", 42);"#, r#""[ab   |   42]""#),
+        (r#"String::format("{:*^9}", "mid");"#, r#""***mid***""#),
+        (r#"String::format("{:08.3} {:+} {:05}", -3.14159, 7, -42);"#, r#""-003.142 +7 -0042""#),
+        (r#"String::format("{:x} {:X} {:b} {:o}", 255, 255, 5, 8);"#, r#""ff FF 101 10""#),
+        (r#"String::format("{:.3} {:.1e} {}", "abcdef", 12345, 2.5);"#, r#""abc 1.2e4 2.5""#),
+        (r#"String::format("{:?} {} {{}}", "q", [1, "q"]);"#, r#"""q" [1, "q"] {}""#),
+        (r#""{} + {} = {}".format(1, 2, 3);"#, r#""1 + 2 = 3""#),
+    ];
+    for (input, expected) in tests {
+        assert_eq!(eval_input(input).to_string(), expected, "input: {}", input);
+    }
+
+    let errors = vec![
+        (r#"String::format("{} {}", 1);"#, "String::format: placeholder 2 has no argument (got 1)"),
+        (r#"String::format("{:x}", 1.5);"#, "String::format: {:x} expects an integer, got float"),
+        (r#"String::format("oops }", 1);"#, "String::format: unmatched '}' (write '}}' for a literal brace)"),
+        (r#"String::format("{:q}", 1);"#, "String::format: invalid format spec {:q}"),
+        (r#"String::format("{:.70000}", 1.0);"#, "String::format: precision 70000 is too large (at most 65535)"),
+        (r#"String::format("{:99999999999}", 1);"#, "String::format: width 99999999999 is too large (at most 65535)"),
+    ];
+    for (input, expected) in errors {
+        assert_eq!(eval_input(input).to_string(), expected, "input: {}", input);
+    }
+}
+
+#[test]
+fn test_string_literal_escapes() {
+    let input = r#"
+        let multi = """
+            a "b"
+              c
+            """;
+        [len("a\tb\n"), "\"q\"" == "\u{22}q" + r""""""", multi, String::split(r"x\y", "\\")];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        r#"[4, true, "a "b"
+  c", ["x", "y"]]"#
+    );
+}
@@ -15,12 +15,12 @@ fn test_sys_cwd() {
 #[test]
 fn test_sys_platform() {
     let input = r#"
-        let platform = Sys::platform();
-        Type::isString(platform);
+        let platform = Sys::platform;
+        [Type::isString(platform), Sys::platform() == platform];
     "#;
 
     let obj = eval_input(input);
-    assert_eq!(obj, Object::Boolean(true));
+    assert_eq!(obj.to_string(), "[true, true]");
 }
 
 #[test]
@@ -34,6 +34,54 @@ fn test_sys_arch() {
     assert_eq!(obj, Object::Boolean(true));
 }
 
+#[test]
+fn test_sys_version() {
+    let obj = eval_input("Sys::version();");
+    assert_eq!(obj, Object::String(env!("CARGO_PKG_VERSION").to_string()));
+}
+
+#[test]
+fn test_sys_has_namespace() {
+    let input = r#"
+        namespace Local {
+            function f() { 1; }
+        }
+        let notNamespace = 5;
+        [
+            Sys::hasNamespace("Json"),
+            Sys::hasNamespace("Local"),
+            Sys::hasNamespace("Missing"),
+            Sys::hasNamespace("notNamespace")
+        ];
+    "#;
+
+    let obj = eval_input(input);
+    assert_eq!(
+        obj,
+        Object::array(vec![
+            Object::Boolean(true),
+            Object::Boolean(true),
+            Object::Boolean(false),
+            Object::Boolean(false),
+        ])
+    );
+}
+
+#[test]
+fn test_sys_features() {
+    let obj = eval_input("Sys::features();");
+    match obj {
+        Object::Array(items) => {
+            assert!(items.iter().all(|i| matches!(i, Object::String(_))));
+            assert_eq!(
+                items.contains(&Object::String("debug-logging".to_string())),
+                cfg!(feature = "debug-logging")
+            );
+        }
+        other => panic!("expected array of features, got {:?}", other),
+    }
+}
+
 #[test]
 fn test_sys_args() {
     let input = r#"
@@ -151,3 +199,65 @@ fn test_sys_error_handling() {
         other => panic!("expected error from Sys::cwd with arg, got {:?}", other),
     }
 }
+
+#[test]
+fn test_sys_exec_dry_run_skips_command() {
+    let marker = std::env::temp_dir().join("slang_dry_run_exec_marker");
+    let _ = std::fs::remove_file(&marker);
+
+    crate::capability::enable_dry_run();
+    let input = format!(
+        r#"
+        let result = Sys::exec("touch {}");
+        let output = Result::unwrapOr(result, {{ code: -1, stdout: "", stderr: "" }});
+        [Sys::dryRun(), output.code];
+    "#,
+        marker.display()
+    );
+    let obj = eval_input(&input);
+    crate::capability::disable_dry_run();
+
+    assert_eq!(
+        obj,
+        Object::array(vec![Object::Boolean(true), Object::Integer(0)])
+    );
+    assert!(!marker.exists(), "dry-run should not execute the command");
+}
+
+#[test]
+fn test_audit_log_records_side_effects() {
+    let log_path = std::env::temp_dir().join(format!(
+        "slang_audit_{}.jsonl",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+
+    crate::capability::enable_audit(&log_path).expect("failed to open audit log");
+    eval_input(
+        r#"
+        let _ = Sys::exec("echo audited");
+        let _ = Sys::setCwd("/definitely/not/a/real/dir");
+        Sys::cwd();
+    "#,
+    );
+    crate::capability::disable_audit();
+
+    let log = std::fs::read_to_string(&log_path).expect("audit log should exist");
+    let _ = std::fs::remove_file(&log_path);
+    let records: Vec<serde_json::Value> = log
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("audit record should be JSON"))
+        .collect();
+
+    // Sys::cwd has no side effects and is not recorded.
+    assert_eq!(records.len(), 2, "records: {:?}", records);
+    assert_eq!(records[0]["op"], "Sys::exec");
+    assert_eq!(records[0]["args"][0], "\"echo audited\"");
+    assert_eq!(records[0]["status"], "ok");
+    assert!(records[0]["timestamp"].as_u64().is_some());
+    assert_eq!(records[1]["op"], "Sys::setCwd");
+    assert_eq!(records[1]["status"], "error");
+    assert!(records[1]["error"].is_string());
+}
@@ -0,0 +1,85 @@
+use crate::object::Object;
+use crate::test_support::eval_input;
+
+#[test]
+fn test_tasks_run_and_join() {
+    let input = r#"
+        const FACTOR = 3;
+        namespace Work { function scale(n) { n * FACTOR; } }
+        let handles = Array::map([1, 2, 3], fn(n) { Task::spawn(fn(x) { Work::scale(x); }, n); });
+        Array::map(handles, Task::join);
+    "#;
+    assert_eq!(eval_input(input).to_string(), "[3, 6, 9]");
+
+    let input = r#"
+        let t = Task::spawn(fn(a, b) { {sum: a + b, parts: Option::Some([a, b])}; }, 2, 5);
+        Task::join(t);
+    "#;
+    assert_eq!(eval_input(input).to_string(), "{sum: 7, parts: Some([2, 5])}");
+}
+
+#[test]
+fn test_tasks_run_in_parallel() {
+    let input = r#"
+        let start = Time::now();
+        let handles = Array::map([1, 2, 3, 4], fn(n) { Task::spawn(fn() { Task::sleep(100); n; }); });
+        [Array::map(handles, Task::join), Time::now() - start < 350];
+    "#;
+    assert_eq!(eval_input(input).to_string(), "[[1, 2, 3, 4], true]");
+}
+
+#[test]
+fn test_task_changes_stay_in_the_task() {
+    let input = r#"
+        let count = 1;
+        Task::join(Task::spawn(fn() { count = count + 10; count; })) + count;
+    "#;
+    assert_eq!(eval_input(input), Object::Integer(12));
+}
+
+#[test]
+fn test_channels_connect_producers_and_consumers() {
+    let input = r#"
+        let ch = Channel::new();
+        let producer = Task::spawn(fn(c) {
+            for (i in [1, 2, 3]) { Channel::send(c, i * 10); }
+            Channel::close(c);
+            "done";
+        }, ch);
+        let got = [];
+        let item = Channel::recv(ch);
+        while (Option::isSome(item)) {
+            got = Array::push(got, Option::unwrap(item));
+            item = Channel::recv(ch);
+        }
+        [got, Task::join(producer), Channel::recv(ch)];
+    "#;
+    assert_eq!(eval_input(input).to_string(), "[[10, 20, 30], \"done\", None]");
+
+    let input = r#"
+        let ch = Channel::new();
+        let before = Channel::tryRecv(ch);
+        Channel::send(ch, "x");
+        [before, Channel::tryRecv(ch)];
+    "#;
+    assert_eq!(eval_input(input).to_string(), "[None, Some(\"x\")]");
+}
+
+#[test]
+fn test_task_errors() {
+    let errors = vec![
+        ("Task::join(Task::spawn(fn() { missing; }));", "identifier not found: missing"),
+        ("let t = Task::spawn(fn() { 1; }); Task::join(t); Task::join(t);", "Task::join: no running task"),
+        ("Task::spawn(fn(r) { r; }, Ref::new(1));", "Task::spawn can't send a ref to another task"),
+        ("Task::join(Task::spawn(fn() { Ref::new(1); }));", "a task can't return a ref"),
+        ("Task::spawn(1);", "Task::spawn expects a function, got integer"),
+        ("let c = Channel::new(); Channel::close(c); Channel::send(c, 1);", "is closed"),
+        ("Channel::recv(\"nope\");", "Channel::recv expects a channel handle, got string"),
+    ];
+    for (input, expected) in errors {
+        match eval_input(input) {
+            Object::Error(msg) => assert!(msg.contains(expected), "input: {}\nerror: {}", input, msg),
+            other => panic!("expected error for {}, got {:?}", input, other),
+        }
+    }
+}
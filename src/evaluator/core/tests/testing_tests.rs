@@ -0,0 +1,197 @@
+use crate::lexer::Lexer;
+use crate::object::Object;
+use crate::parser::Parser;
+use crate::runtime::{run_tests, run_tests_with, TestOptions};
+use crate::test_support::eval_input;
+
+fn error_message(obj: Object) -> String {
+    match obj {
+        Object::Error(msg) => msg,
+        other => panic!("expected assertion failure, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_assert_eq_failures_show_a_structural_diff() {
+    let msg = error_message(eval_input(
+        r#"Test::assertEq({ name: "ada", tags: ["a", "b"] }, { name: "bob", tags: ["a"], admin: true });"#,
+    ));
+    let diff: Vec<&str> = msg.lines().skip(1).collect();
+    assert_eq!(
+        diff,
+        vec![
+            "    diff (- expected, + actual):",
+            "    + admin: true",
+            "    ~ name: \"ada\" -> \"bob\"",
+            "    - tags[1]: \"b\"",
+        ]
+    );
+
+    let scalar = error_message(eval_input(r#"Test::assertEq(1, 2, "sums");"#));
+    assert_eq!(scalar, "Assertion failed: expected 1, got 2 - \"sums\"");
+}
+
+#[test]
+fn test_assert_eq_truncates_huge_values_and_long_diffs() {
+    let msg = error_message(eval_input(
+        r#"
+        let a = Array::map(Array::range(0, 100), fn(i) { i });
+        let b = Array::map(Array::range(0, 100), fn(i) { i + 1 });
+        Test::assertEq(a, b);
+        "#,
+    ));
+    let lines: Vec<&str> = msg.lines().collect();
+    assert!(lines[0].contains("... (390 chars)"), "{}", lines[0]);
+    assert!(lines[0].len() < 500, "{}", lines[0]);
+    assert_eq!(lines.len(), 1 + 1 + 20 + 1);
+    assert_eq!(lines[2], "    ~ [0]: 0 -> 1");
+    assert_eq!(*lines.last().unwrap(), "    ... and 80 more differences");
+}
+
+#[test]
+fn test_run_tests_report_colours_results_and_diff_lines() {
+    let source = r#"
+        test "same" { Test::assertEq([1], [1]); }
+        test "differs" { Test::assertEq([1, 2], [1]); }
+    "#;
+    let mut parser = Parser::new(Lexer::new(source));
+    let program = parser.parse_program();
+    let summary = run_tests(&program);
+
+    assert_eq!(summary.failed, 1);
+    let colored = summary.colored();
+    assert!(colored.contains("\x1b[32mPASS: same (1 assertion)\x1b[0m"), "{}", colored);
+    assert!(colored.contains("\x1b[31mFAIL: differs"), "{}", colored);
+    assert!(colored.contains("\x1b[31m    - [1]: 2\x1b[0m"), "{}", colored);
+    assert!(!summary.output.contains('\x1b'));
+}
+
+#[test]
+fn test_run_tests_groups_hooks_and_filter() {
+    let source = r#"
+        let log = Ref::new([]);
+        beforeEach { Array::pushMut(log, "outer"); }
+        describe "math" {
+            let factor = 2;
+            beforeEach { Array::pushMut(log, "inner"); }
+            afterEach { Test::assertEq(["outer", "inner"], log.get()); }
+            test "doubles" { Test::assertEq(4, 2 * factor); }
+            describe "nested" {
+                test "breaks afterEach" { Array::pushMut(log, "extra"); }
+            }
+        }
+        test "top" { Test::assertEq(["outer"], log.get()); }
+    "#;
+    let mut parser = Parser::new(Lexer::new(source));
+    let program = parser.parse_program();
+
+    let summary = run_tests(&program);
+    let names: Vec<String> = summary.results.iter().map(|r| r.full_name()).collect();
+    assert_eq!(names, vec!["math > doubles", "math > nested > breaks afterEach", "top"]);
+    assert_eq!(summary.failed, 1);
+    assert_eq!(summary.results[0].assertions, 2);
+    let error = summary.results[1].error.as_deref().unwrap_or_default();
+    assert!(error.starts_with("afterEach: Assertion failed"), "{}", error);
+
+    let options = TestOptions { filter: Some("math >".to_string()), ..TestOptions::default() };
+    let filtered = run_tests_with(&program, &options);
+    assert_eq!(filtered.total, 2);
+    assert!(filtered.output.ends_with("Test results: 1/2 passed, 1 failed (1 filtered out)\n"), "{}", filtered.output);
+    assert!(filtered.to_junit("t.sl").contains(
+        "<testcase classname=\"math &gt; nested\" name=\"breaks afterEach\" assertions=\"1\""
+    ));
+}
+
+#[test]
+fn test_extra_assertions_pass() {
+    let result = eval_input(
+        r#"
+        Test::assertTrue(1 < 2);
+        Test::assertFalse(1 > 2);
+        Test::assertApproxEq(0.3, 0.1 + 0.2, 0.0001);
+        Test::assertContains("hello world", "lo w");
+        Test::assertContains([1, 2, 3], 2);
+        Test::assertContains({ name: "ada" }, "name");
+        Test::assertNone(Option::None());
+        Test::assertErr(Result::Err("bad"));
+        let msg = Test::assertThrows(fn() { Test::assert(false, "inner"); });
+        Test::assertContains(msg, "inner");
+        Test::assertSome(Option::Some(2)) + Test::assertOk(Result::Ok(3));
+    "#,
+    );
+    assert_eq!(result, Object::Integer(5));
+}
+
+#[test]
+fn test_extra_assertion_failure_messages() {
+    assert_eq!(
+        error_message(eval_input("Test::assertTrue(1);")),
+        "Assertion failed: expected true, got 1"
+    );
+    assert_eq!(
+        error_message(eval_input("Test::assertApproxEq(1.0, 1.5, 0.1, \"rounding\");")),
+        "Assertion failed: expected 1 ± 0.1, got 1.5 (off by 0.5) - \"rounding\""
+    );
+    assert_eq!(
+        error_message(eval_input("Test::assertContains([1, 2], 3);")),
+        "Assertion failed: expected [1, 2] to contain 3"
+    );
+    assert_eq!(
+        error_message(eval_input("Test::assertContains({ a: 1 }, \"b\");")),
+        "Assertion failed: expected {a: 1} to have key \"b\""
+    );
+    assert_eq!(
+        error_message(eval_input("Test::assertThrows(fn() { 42 });")),
+        "Assertion failed: expected the function to fail, but it returned 42"
+    );
+    assert_eq!(
+        error_message(eval_input("Test::assertSome(Option::None());")),
+        "Assertion failed: expected Some(...), got None"
+    );
+    assert_eq!(
+        error_message(eval_input("Test::assertOk(Result::Err(\"nope\"));")),
+        "Assertion failed: expected Ok(...), got Err(\"nope\")"
+    );
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn test_assert_match() {
+    assert_eq!(eval_input(r#"Test::assertMatch("v1.2.3", "^v[0-9]+[.][0-9]+");"#), Object::Null);
+    assert_eq!(
+        error_message(eval_input(r#"Test::assertMatch("abc", "^[0-9]+$");"#)),
+        "Assertion failed: expected \"abc\" to match /^[0-9]+$/"
+    );
+}
+
+#[test]
+fn test_run_tests_records_and_checks_snapshots() {
+    let path = std::env::temp_dir().join(format!("slang_snapshot_{}.snap", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let run = |value: &str, update: bool| {
+        let source = format!(r#"test "user" {{ Test::assertSnapshot("user", {}); }}"#, value);
+        let program = Parser::new(Lexer::new(&source)).parse_program();
+        let options = TestOptions { snapshots: Some(path.clone()), update_snapshots: update, ..TestOptions::default() };
+        run_tests_with(&program, &options)
+    };
+
+    let first = run(r#"{ name: "ada", tags: ["a"] }"#, false);
+    assert_eq!(first.failed, 0);
+    assert!(first.output.contains("Wrote 1 snapshot(s)"), "{}", first.output);
+    assert_eq!(run(r#"{ name: "ada", tags: ["a"] }"#, false).failed, 0);
+
+    let changed = run(r#"{ name: "bob", tags: ["a"] }"#, false);
+    let error = changed.results[0].error.as_deref().unwrap_or_default();
+    assert!(error.starts_with("Assertion failed: snapshot \"user\" doesn't match"), "{}", error);
+    assert!(error.contains("~ name: \"ada\" -> \"bob\""), "{}", error);
+
+    assert_eq!(run(r#"{ name: "bob", tags: ["a"] }"#, true).failed, 0);
+    let stored = std::fs::read_to_string(&path).unwrap();
+    assert!(stored.contains("\"bob\""), "{}", stored);
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(
+        error_message(eval_input(r#"Test::assertSnapshot("x", 1);"#)),
+        "Test::assertSnapshot only works under `slang test`"
+    );
+}
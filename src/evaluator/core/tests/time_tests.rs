@@ -184,3 +184,121 @@ fn test_time_error_handling() {
         ),
     }
 }
+
+#[test]
+fn test_time_from_components_and_before_epoch() {
+    let input = r#"
+        let ts = Time::fromComponents({year: 2024, month: 6, day: 15, hour: 13, minute: 30, second: 45});
+        let early = Time::fromComponents({year: 1969, month: 12, day: 31});
+        [ts, early, Time::toObject(early).day, Time::dayOfWeek(early), Time::fromComponents(Time::toObject(ts)) == ts];
+    "#;
+    assert_eq!(eval_input(input).to_string(), "[1718458245000, -86400000, 31, 3, true]");
+
+    assert!(eval_input("Time::fromComponents({year: 2023, month: 2, day: 29});").is_error());
+    assert!(eval_input("Time::fromComponents({month: 2});").is_error());
+    assert!(eval_input("Time::fromComponents({year: 2023, hours: 2});").is_error());
+}
+
+#[test]
+fn test_time_add_and_diff() {
+    let input = r#"
+        let jan31 = Time::fromComponents({year: 2024, month: 1, day: 31, hour: 12});
+        let f = "%Y-%m-%d %H:%M";
+        let later = Time::add(jan31, {days: 3, hours: 5});
+        [
+            Time::format(Time::add(jan31, {months: 1}), f),
+            Time::format(Time::add(jan31, {years: -1, months: 1}), f),
+            Time::format(Time::add(jan31, {weeks: -1, minutes: 90}), f),
+            Time::diff(later, jan31),
+            Time::diff(jan31, later).hours
+        ];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        r#"["2024-02-29 12:00", "2023-02-28 12:00", "2024-01-24 13:30", {millis: 277200000, seconds: 277200, minutes: 4620, hours: 77, days: 3, weeks: 0}, -77]"#
+    );
+    assert!(eval_input("Time::add(0, {fortnights: 1});").is_error());
+}
+
+#[test]
+fn test_time_parse() {
+    let input = r#"
+        [
+            Time::parse("2024-06-15 13:30:45", "%Y-%m-%d %H:%M:%S"),
+            Time::parse("2024-06-15T15:30:45.25+02:00", "%Y-%m-%dT%H:%M:%S.%f%z"),
+            Time::parse("15/06/2024", "%d/%m/%Y"),
+            Result::isErr(Time::parse("2024-02-30", "%Y-%m-%d")),
+            Result::isErr(Time::parse("2024-06-15 extra", "%Y-%m-%d"))
+        ];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        "[Ok(1718458245000), Ok(1718458245250), Ok(1718409600000), true, true]"
+    );
+}
+
+#[test]
+fn test_time_to_zone_with_fixed_offsets() {
+    let input = r#"
+        let india = Time::toZone(1718458245000, "+05:30");
+        let utc = Time::toZone(1718458245000, "UTC");
+        [india.hour, india.minute, india.offset, utc.hour, Time::fromComponents(india) == 1718458245000];
+    "#;
+    assert_eq!(eval_input(input).to_string(), "[19, 0, 330, 13, true]");
+    assert!(eval_input(r#"Time::toZone(0, "Nowhere/Land");"#).is_error());
+    assert!(eval_input(r#"Time::toZone(0, "../etc/passwd");"#).is_error());
+}
+
+#[test]
+fn test_time_arithmetic_overflow_is_an_error() {
+    for input in [
+        "Time::add(0, {weeks: 9223372036854775807});",
+        "Time::add(0, {days: 1, millis: 9223372036854775807});",
+        "Time::add(0, {years: 9223372036854775807});",
+        "Time::add(9223372036854775807, {millis: 1});",
+        "Time::diff(9223372036854775807, -1);",
+        "Time::fromComponents({year: 9223372036854775807});",
+        "Time::fromComponents({year: -9223372036854775807 - 1, month: 1});",
+        "Time::fromComponents({year: 2024, offset: 9223372036854775807});",
+        r#"Time::toZone(9223372036854775807, "+05:30");"#,
+    ] {
+        let result = eval_input(input);
+        assert!(result.to_string().contains("integer overflow"), "{}: {}", input, result);
+    }
+    // The extremes themselves still convert.
+    assert_eq!(
+        eval_input(r#"Time::toZone(9223372036854775807, "UTC").year;"#),
+        Object::Integer(292278994)
+    );
+}
+
+#[test]
+fn test_time_to_zone_with_tz_database() {
+    let dir = std::env::var("TZDIR").unwrap_or_else(|_| "/usr/share/zoneinfo".to_string());
+    if !std::path::Path::new(&dir).join("Europe/Paris").exists() {
+        return;
+    }
+    let input = r#"
+        let summer = Time::toZone(1718458245000, "Europe/Paris");
+        let winter = Time::toZone(1704067200000, "Europe/Paris");
+        // Past the table of transitions, so only the zone's rule applies.
+        let future = Time::toZone(Time::fromComponents({year: 2100, month: 7, day: 1}), "Europe/Paris");
+        let sydney = Time::toZone(1704067200000, "Australia/Sydney");
+        let paris = fn(hour, day, month) {
+            Time::fromComponents({year: 2024, month: month, day: day, hour: hour, minute: 30, zone: "Europe/Paris"});
+        };
+        [
+            [summer.hour, summer.offset, summer.abbreviation],
+            [winter.hour, winter.offset, winter.abbreviation],
+            [future.hour, future.abbreviation],
+            [sydney.hour, sydney.abbreviation],
+            Time::toZone(paris(2, 31, 3), "Europe/Paris").hour,
+            Time::toZone(paris(2, 27, 10), "Europe/Paris").offset,
+            Time::fromComponents(summer) == 1718458245000
+        ];
+    "#;
+    assert_eq!(
+        eval_input(input).to_string(),
+        r#"[[15, 120, "CEST"], [1, 60, "CET"], [2, "CEST"], [11, "AEDT"], 3, 120, true]"#
+    );    assert!(eval_input(r#"Time::toZone(9223372036854775807, "Europe/Paris");"#).is_error());
+}
@@ -162,3 +162,28 @@ fn test_type_is_special_checks() {
         ),
     }
 }
+
+#[test]
+fn test_type_to_int_and_to_float() {
+    let input = r#"
+        [
+            Type::toInt(7),
+            Type::toInt(2.7),
+            Type::toInt(-2.7),
+            Type::toFloat(3),
+            Type::toFloat(1.5),
+            Type::of(Type::toFloat(3))
+        ];
+    "#;
+    assert_eq!(eval_input(input).to_string(), r#"[7, 2, -2, 3, 1.5, "float"]"#);
+
+    for bad in [
+        r#"Type::toInt("3");"#,
+        "Type::toInt(1.0 / 0.0);",
+        "Type::toInt(1e300);",
+        "Type::toFloat(true);",
+    ] {
+        let obj = eval_input(bad);
+        assert!(obj.is_error(), "{} should error, got {:?}", bad, obj);
+    }
+}
@@ -0,0 +1,44 @@
+use crate::object::Object;
+use crate::test_support::eval_input;
+
+fn uuids(input: &str) -> Vec<String> {
+    match eval_input(input) {
+        Object::Array(vals) => vals
+            .iter()
+            .map(|v| match v {
+                Object::String(s) => s.clone(),
+                other => panic!("expected a UUID string, got {:?}", other),
+            })
+            .collect(),
+        other => panic!("expected an array of UUIDs, got {:?}", other),
+    }
+}
+
+fn assert_shape(uuid: &str, version: char) {
+    let groups: Vec<&str> = uuid.split('-').collect();
+    assert_eq!(groups.iter().map(|g| g.len()).collect::<Vec<_>>(), [8, 4, 4, 4, 12], "{}", uuid);
+    assert!(uuid.chars().all(|c| c == '-' || matches!(c, '0'..='9' | 'a'..='f')), "{}", uuid);
+    assert_eq!(groups[2].chars().next(), Some(version), "{}", uuid);
+    assert!(matches!(groups[3].chars().next(), Some('8' | '9' | 'a' | 'b')), "{}", uuid);
+}
+
+#[test]
+fn test_uuid_v4() {
+    let ids = uuids("[Uuid::v4(), Uuid::v4()];");
+    assert_shape(&ids[0], '4');
+    assert_shape(&ids[1], '4');
+    assert_ne!(ids[0], ids[1]);
+    assert!(eval_input("Uuid::v4(1);").is_error());
+}
+
+#[test]
+fn test_uuid_v7_sorts_in_creation_order() {
+    let ids = uuids("let ids = []; for (let i = 0; i < 50; i++) { ids = Array::push(ids, Uuid::v7()); } ids;");
+    for id in &ids {
+        assert_shape(id, '7');
+    }
+    let mut sorted = ids.clone();
+    sorted.sort();
+    sorted.dedup();
+    assert_eq!(sorted, ids);
+}
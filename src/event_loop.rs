@@ -0,0 +1,8 @@
+pub mod core;
+
+pub use core::{
+    cancel, check_signal, on_shutdown, pending_events, publish, run, run_events, schedule,
+    shutdown,
+};
+#[cfg(feature = "signals")]
+pub use core::install_signal_handler;
@@ -0,0 +1,225 @@
+//! The event loop behind `Timer::`, `Events::` and `Runtime::run()`, plus the
+//! shutdown hooks registered with `Runtime::onShutdown`.
+//!
+//! Timers and events published with `Events::publish` are queued per thread
+//! and only dispatched while [`run`] or [`run_events`] is pumping them, so
+//! neither interrupts the code that queued it. Events go to the same
+//! subscribers as `value -> :Tag`, but later; a handler may publish more
+//! events, which are delivered in the same pump. The loop is where socket and
+//! file readiness would be dispatched too.
+//!
+//! Shutdown hooks run in reverse registration order when a script finishes,
+//! calls `Sys::exit`, or receives SIGINT/SIGTERM. Signals arrive on another
+//! thread, so the handler only raises a flag; the interpreter notices it at
+//! the next statement (or while `Runtime::run` is waiting) and runs the hooks
+//! there.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::process;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::env::{subscribers_for_tag, EnvRef};
+use crate::evaluator::core::budget;
+use crate::evaluator::core::expr::{apply_function_with_this, build_args_for_subscriber};
+use crate::object::Object;
+
+struct Timer {
+    id: i64,
+    due: Instant,
+    /// Set for `Timer::every`; one-shot timers are dropped once they fire.
+    interval: Option<Duration>,
+    callback: Object,
+}
+
+/// A published value waiting for [`run_events`].
+struct Event {
+    tag: String,
+    values: Vec<Object>,
+}
+
+thread_local! {
+    static EVENTS: RefCell<VecDeque<Event>> = const { RefCell::new(VecDeque::new()) };
+    static TIMERS: RefCell<Vec<Timer>> = const { RefCell::new(Vec::new()) };
+    static NEXT_ID: Cell<i64> = const { Cell::new(1) };
+    static SHUTDOWN_HOOKS: RefCell<Vec<Object>> = const { RefCell::new(Vec::new()) };
+    static RUNNING_HOOKS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Set by the signal handler; checked by [`check_signal`].
+static SIGNALLED: AtomicBool = AtomicBool::new(false);
+/// Registered hooks, visible to the signal handler's thread.
+static HOOK_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Exit status after a SIGINT/SIGTERM, as shells report for SIGINT.
+const SIGNAL_EXIT_CODE: i32 = 130;
+
+/// Longest stretch `run` sleeps before looking for a pending signal.
+const SIGNAL_POLL: Duration = Duration::from_millis(50);
+
+/// Queue `callback` to run after `delay`, then every `interval` if one is
+/// given. Returns the handle used to cancel it.
+pub fn schedule(delay: Duration, interval: Option<Duration>, callback: Object) -> i64 {
+    let id = NEXT_ID.with(|next| {
+        let id = next.get();
+        next.set(id + 1);
+        id
+    });
+    let timer = Timer {
+        id,
+        due: Instant::now() + delay,
+        interval,
+        callback,
+    };
+    TIMERS.with(|timers| timers.borrow_mut().push(timer));
+    id
+}
+
+/// Drop a pending timer. Returns false if it already fired or was cancelled.
+pub fn cancel(id: i64) -> bool {
+    TIMERS.with(|timers| {
+        let mut timers = timers.borrow_mut();
+        let before = timers.len();
+        timers.retain(|t| t.id != id);
+        timers.len() != before
+    })
+}
+
+/// Queue `values` for the subscribers of `tag`.
+pub fn publish(tag: &str, values: Vec<Object>) {
+    EVENTS.with(|events| {
+        events.borrow_mut().push_back(Event { tag: tag.to_string(), values })
+    });
+}
+
+/// Events published but not yet delivered.
+pub fn pending_events() -> usize {
+    EVENTS.with(|events| events.borrow().len())
+}
+
+/// Deliver queued events, oldest first, until none are left. Each event's
+/// values reach every subscriber of its tag, shaped as for `-> :Tag`;
+/// handlers' return values are dropped. The first error a handler returns
+/// stops delivery (later events stay queued) and is returned.
+pub fn run_events(env: &EnvRef) -> Object {
+    while let Some(event) = EVENTS.with(|events| events.borrow_mut().pop_front()) {
+        let values: Vec<Object> =
+            event.values.into_iter().filter(|v| !matches!(v, Object::Null)).collect();
+        for func in subscribers_for_tag(&event.tag, Rc::clone(env)) {
+            let args = match build_args_for_subscriber(&values, &func) {
+                Ok(args) => args,
+                Err(msg) => return Object::error(msg),
+            };
+            let result = apply_function_with_this(func, args, None, Rc::clone(env));
+            if result.is_error() {
+                return result;
+            }
+        }
+    }
+    Object::Null
+}
+
+/// Dispatch queued events and timers until neither is left. Events are
+/// delivered before the next timer fires.
+///
+/// Callbacks run in `env` and may schedule or cancel timers and publish
+/// events themselves. The first error a callback returns stops the loop and
+/// is returned.
+pub fn run(env: EnvRef) -> Object {
+    loop {
+        let delivered = run_events(&env);
+        if delivered.is_error() {
+            return delivered;
+        }
+        let next = TIMERS.with(|timers| {
+            timers
+                .borrow()
+                .iter()
+                .min_by_key(|t| (t.due, t.id))
+                .map(|t| (t.id, t.due))
+        });
+        let Some((id, due)) = next else {
+            return Object::Null;
+        };
+
+        loop {
+            check_signal(&env);
+            let now = Instant::now();
+            if due <= now {
+                break;
+            }
+            if let Some(abort) = budget::sleep((due - now).min(SIGNAL_POLL)) {
+                return abort;
+            }
+        }
+
+        let callback = TIMERS.with(|timers| {
+            let mut timers = timers.borrow_mut();
+            let pos = timers.iter().position(|t| t.id == id)?;
+            match timers[pos].interval {
+                Some(every) => {
+                    timers[pos].due = due + every;
+                    Some(timers[pos].callback.clone())
+                }
+                None => Some(timers.remove(pos).callback),
+            }
+        });
+
+        if let Some(callback) = callback {
+            let result = apply_function_with_this(callback, Vec::new(), None, Rc::clone(&env));
+            if result.is_error() {
+                return result;
+            }
+        }
+    }
+}
+
+/// Register `callback` to run at shutdown.
+pub fn on_shutdown(callback: Object) {
+    SHUTDOWN_HOOKS.with(|hooks| hooks.borrow_mut().push(callback));
+    HOOK_COUNT.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Run the shutdown hooks, most recently registered first.
+///
+/// Each hook runs once; a hook that fails doesn't stop the others, and the
+/// first error is returned.
+pub fn shutdown(env: EnvRef) -> Object {
+    let was_running = RUNNING_HOOKS.with(|running| running.replace(true));
+    let mut first_error = None;
+    while let Some(hook) = SHUTDOWN_HOOKS.with(|hooks| hooks.borrow_mut().pop()) {
+        HOOK_COUNT.fetch_sub(1, Ordering::SeqCst);
+        let result = apply_function_with_this(hook, Vec::new(), None, Rc::clone(&env));
+        if result.is_error() && first_error.is_none() {
+            first_error = Some(result);
+        }
+    }
+    RUNNING_HOOKS.with(|running| running.set(was_running));
+    first_error.unwrap_or(Object::Null)
+}
+
+/// Route SIGINT/SIGTERM through the shutdown hooks.
+///
+/// Without hooks (or on a second signal) the process exits straight away.
+#[cfg(feature = "signals")]
+pub fn install_signal_handler() {
+    let _ = ctrlc::set_handler(|| {
+        if HOOK_COUNT.load(Ordering::SeqCst) == 0 || SIGNALLED.swap(true, Ordering::SeqCst) {
+            process::exit(SIGNAL_EXIT_CODE);
+        }
+    });
+}
+
+/// If a signal has arrived, run the shutdown hooks and exit.
+pub fn check_signal(env: &EnvRef) {
+    if !SIGNALLED.load(Ordering::Relaxed) || RUNNING_HOOKS.with(|running| running.get()) {
+        return;
+    }
+    let result = shutdown(Rc::clone(env));
+    if let Object::Error(msg) = result {
+        eprintln!("{}", msg);
+    }
+    process::exit(SIGNAL_EXIT_CODE);
+}
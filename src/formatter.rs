@@ -0,0 +1,3 @@
+pub mod core;
+
+pub use core::{format_expression, format_program, format_source, FormatOptions};
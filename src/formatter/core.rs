@@ -0,0 +1,1090 @@
+//! Pretty-printer that turns a parsed `Program` back into canonical Slang
+//! source.
+//!
+//! Unlike the AST `Display` impls (which are meant for debugging), the output
+//! here always re-parses to the same AST: statements go on their own lines,
+//! blocks are indented, and infix expressions only get the parentheses their
+//! precedence requires.
+//!
+//! The AST has no comments, so [`format_source`] collects them from the
+//! source and places them by line: a comment on its own line goes before the
+//! statement (or closing brace) that follows it, and a comment after code
+//! stays at the end of that line. A statement with a comment inside it
+//! (`[1, /* two */ 2]`, or a comment between the lines of an object literal)
+//! is left as written, since there is no way to say where in the reformatted
+//! expression the comment belongs.
+
+use std::collections::VecDeque;
+
+use crate::ast::nodes::{
+    escape_string, ClassStatement, ExportStatement, ForStatement, FunctionStatement, NewExpression,
+    PostfixExpression, PostfixOp, PrefixExpression, PropertyAccess, PublishExpression,
+    StringLiteral, SwitchStatement, TestStatement,
+};
+use crate::ast::{
+    BlockStatement, Expression, FunctionLiteral, IfExpression, ImportStatement, InfixExpression,
+    InfixOp, Program, Statement,
+};
+use crate::lexer::{block_comment_end, dedent, string_end, Lexer};
+use crate::parser::Parser;
+
+/// Formatting options for [`format_program`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatOptions {
+    /// Number of spaces per indentation level.
+    pub indent: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions { indent: 4 }
+    }
+}
+
+/// Parse `source` and return it formatted, or the parser errors.
+pub fn format_source(source: &str, options: &FormatOptions) -> Result<String, Vec<String>> {
+    let lexer = Lexer::new(source);
+    let mut parser = Parser::new(lexer);
+    let program = parser.parse_program();
+    if !parser.errors.is_empty() {
+        return Err(parser.errors);
+    }
+    let (comments, code_lines) = collect_comments(source);
+    let source = Source { lines: source.lines().map(str::to_string).collect(), code_lines };
+    Ok(print_program(&program, options, comments, Some(source)))
+}
+
+/// Render `program` as formatted Slang source, ending with a newline.
+pub fn format_program(program: &Program, options: &FormatOptions) -> String {
+    print_program(program, options, VecDeque::new(), None)
+}
+
+fn print_program(
+    program: &Program,
+    options: &FormatOptions,
+    comments: VecDeque<Comment>,
+    source: Option<Source>,
+) -> String {
+    let mut printer = Printer {
+        out: String::new(),
+        indent: " ".repeat(options.indent),
+        level: 0,
+        comments,
+        source,
+        last_close: None,
+    };
+    printer.statements(&program.statements, &program.lines, usize::MAX);
+    printer.comments_before(usize::MAX);
+    printer.trim_blank_line();
+    printer.out
+}
+
+/// Render a single expression the way [`format_program`] would.
+pub fn format_expression(expr: &Expression) -> String {
+    let mut printer = Printer {
+        out: String::new(),
+        indent: " ".repeat(FormatOptions::default().indent),
+        level: 0,
+        comments: VecDeque::new(),
+        source: None,
+        last_close: None,
+    };
+    printer.expression(expr, PREC_LOWEST);
+    printer.out
+}
+
+/// A `//` or `/* */` comment from the source.
+#[derive(Debug, Clone, PartialEq)]
+struct Comment {
+    /// Line the comment starts on, counting from 1.
+    line: usize,
+    text: String,
+    /// Code comes before it on the same line.
+    trailing: bool,
+    /// Something other than whitespace follows it on the same line.
+    code_after: bool,
+    /// A blank line separates it from the code or comment above.
+    blank_before: bool,
+    /// A blank line separates it from the code or comment below.
+    blank_after: bool,
+}
+
+/// The source a program was parsed from.
+struct Source {
+    lines: Vec<String>,
+    /// Whether each line (from 1) has code on it rather than only comments
+    /// and whitespace.
+    code_lines: Vec<bool>,
+}
+
+/// The comments in `source`, in order, skipping `//` and `/*` inside strings,
+/// and which of its lines have code on them.
+fn collect_comments(source: &str) -> (VecDeque<Comment>, Vec<bool>) {
+    let chars: Vec<char> = source.chars().collect();
+    let blank_line = |line: &[char]| line.iter().all(|c| c.is_whitespace());
+    let mut comments = VecDeque::new();
+    let mut code_lines = vec![false; source.lines().count() + 1];
+    let mut mark_code = |line: usize| {
+        if let Some(has_code) = code_lines.get_mut(line - 1) {
+            *has_code = true;
+        }
+    };
+    let mut line = 1;
+    let mut line_start = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        let end = match chars[i] {
+            '"' => {
+                let end = string_end(&chars, i).unwrap_or(chars.len());
+                mark_code(line);
+                for (offset, &c) in chars[i..end].iter().enumerate() {
+                    if c == '\n' {
+                        line += 1;
+                        line_start = i + offset + 1;
+                        mark_code(line);
+                    }
+                }
+                i = end;
+                continue;
+            }
+            '\n' => {
+                line += 1;
+                i += 1;
+                line_start = i;
+                continue;
+            }
+            '/' if chars.get(i + 1) == Some(&'/') => {
+                chars[i..].iter().position(|&c| c == '\n').map_or(chars.len(), |n| i + n)
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => block_comment_end(&chars, i).unwrap_or(chars.len()),
+            c => {
+                if !c.is_whitespace() {
+                    mark_code(line);
+                }
+                i += 1;
+                continue;
+            }
+        };
+        let trailing = !blank_line(&chars[line_start..i]);
+        // The line above (or below) the comment, if there is one.
+        let above = chars[..line_start].split(|&c| c == '\n').rev().nth(1);
+        let rest_of_line = chars[end..].iter().position(|&c| c == '\n').map_or(chars.len(), |n| end + n);
+        let below = chars.get(rest_of_line + 1..).and_then(|after| after.split(|&c| c == '\n').next());
+        let more_code = chars[rest_of_line..].iter().any(|c| !c.is_whitespace());
+        let text: String = chars[i..end].iter().collect();
+        comments.push_back(Comment {
+            line,
+            text: text.trim_end().to_string(),
+            trailing,
+            code_after: !blank_line(&chars[end..rest_of_line]),
+            blank_before: !trailing && above.is_some_and(blank_line),
+            blank_after: blank_line(&chars[end..rest_of_line]) && below.is_some_and(blank_line) && more_code,
+        });
+        line += text.matches('\n').count();
+        if let Some(n) = text.rfind('\n') {
+            line_start = i + text[..n].chars().count() + 1;
+        }
+        i = end;
+    }
+    (comments, code_lines)
+}
+
+// Binding strength of expressions, mirroring the parser's `Precedence`.
+const PREC_LOWEST: u8 = 0;
+const PREC_ASSIGN: u8 = 1;
+const PREC_PIPELINE: u8 = 2;
+const PREC_COALESCE: u8 = 3;
+const PREC_OR: u8 = 4;
+const PREC_AND: u8 = 5;
+const PREC_EQUALS: u8 = 6;
+const PREC_LESS_GREATER: u8 = 7;
+const PREC_BIT_OR: u8 = 8;
+const PREC_BIT_XOR: u8 = 9;
+const PREC_BIT_AND: u8 = 10;
+const PREC_SHIFT: u8 = 11;
+const PREC_SUM: u8 = 12;
+const PREC_PRODUCT: u8 = 13;
+const PREC_PREFIX: u8 = 14;
+const PREC_POWER: u8 = 15;
+const PREC_CALL: u8 = 16;
+const PREC_ATOM: u8 = 17;
+
+fn infix_precedence(op: &InfixOp) -> u8 {
+    match op {
+        InfixOp::Assign => PREC_ASSIGN,
+        InfixOp::Pipeline => PREC_PIPELINE,
+        InfixOp::Coalesce => PREC_COALESCE,
+        InfixOp::Or => PREC_OR,
+        InfixOp::And => PREC_AND,
+        InfixOp::Equals | InfixOp::NotEquals => PREC_EQUALS,
+        InfixOp::LessThan | InfixOp::LessEqual | InfixOp::GreaterThan | InfixOp::GreaterEqual => {
+            PREC_LESS_GREATER
+        }
+        InfixOp::BitOr => PREC_BIT_OR,
+        InfixOp::BitXor => PREC_BIT_XOR,
+        InfixOp::BitAnd => PREC_BIT_AND,
+        InfixOp::ShiftLeft | InfixOp::ShiftRight => PREC_SHIFT,
+        InfixOp::Plus | InfixOp::Minus => PREC_SUM,
+        InfixOp::Multiply | InfixOp::Divide | InfixOp::Modulo | InfixOp::IntDivide => {
+            PREC_PRODUCT
+        }
+        InfixOp::Power => PREC_POWER,
+    }
+}
+
+fn expression_precedence(expr: &Expression) -> u8 {
+    match expr {
+        Expression::Infix(infix) => infix_precedence(&infix.operator),
+        Expression::Prefix(_) => PREC_PREFIX,
+        Expression::Postfix(_)
+        | Expression::CallExpression(_)
+        | Expression::IndexExpression(_)
+        | Expression::PropertyAccess(_) => PREC_CALL,
+        Expression::Publish(_) => PREC_LOWEST,
+        // An arrow function's body runs to the end of the expression.
+        Expression::FunctionLiteral(fl) if fl.arrow => PREC_LOWEST,
+        _ => PREC_ATOM,
+    }
+}
+
+struct Printer {
+    out: String,
+    indent: String,
+    level: usize,
+    /// Comments not printed yet.
+    comments: VecDeque<Comment>,
+    /// The source being formatted, if there is one.
+    source: Option<Source>,
+    /// Where in `out` the last block's closing brace ended, and the source
+    /// line of that brace.
+    last_close: Option<(usize, usize)>,
+}
+
+impl Printer {
+    fn write_indent(&mut self) {
+        for _ in 0..self.level {
+            self.out.push_str(&self.indent);
+        }
+    }
+
+    /// Statements written on `lines` of the source, each on its own line.
+    /// `end` is the line that ends them (a closing brace, say).
+    fn statements(&mut self, statements: &[Statement], lines: &[usize], end: usize) {
+        for (i, stmt) in statements.iter().enumerate() {
+            // Declarations get a blank line on either side to stand out.
+            if i > 0 && (is_declaration(stmt) || is_declaration(&statements[i - 1])) {
+                self.out.push('\n');
+            }
+            let line = lines.get(i).copied();
+            if let Some(line) = line {
+                self.comments_before(line);
+            }
+            let start = self.out.len();
+            self.write_indent();
+            self.statement(stmt);
+            if let Some(line) = line
+                && !self.out[start..].contains('\n')
+                && let Some(written) = self.as_written(stmt, line, lines.get(i + 1).copied().unwrap_or(end))
+            {
+                self.out.truncate(start);
+                self.write_indent();
+                self.out.push_str(&written);
+                self.out.push('\n');
+                continue;
+            }
+            // A comment after several statements on one line goes with the last.
+            if line.is_none() || lines.get(i + 1) != line.as_ref() {
+                self.trailing_comments(start, line);
+            }
+            self.out.push('\n');
+        }
+    }
+
+    /// The source of `stmt`, which starts on `line` and ends before line
+    /// `next`, if it has a comment inside it. The statement's lines are taken
+    /// as they are (only the first is re-indented) along with the comments
+    /// on them, provided they hold exactly `stmt`; otherwise `None`, and the
+    /// comments move out of the way as usual.
+    fn as_written(&mut self, stmt: &Statement, line: usize, next: usize) -> Option<String> {
+        let source = self.source.as_ref()?;
+        let last = (line..next.min(source.code_lines.len() + 1))
+            .rev()
+            .find(|&l| source.code_lines.get(l - 1).copied().unwrap_or(false))?;
+        // Not a comment leading the first line or trailing the last one.
+        let inside = |c: &Comment| {
+            (line..=last).contains(&c.line)
+                && (c.line != line || c.trailing)
+                && (c.line != last || !c.trailing || c.code_after)
+        };
+        if !self.comments.iter().take_while(|c| c.line <= last).any(inside) {
+            return None;
+        }
+        let written = source.lines[line - 1..last].join("\n");
+        let mut parser = Parser::new(Lexer::new(&written));
+        let program = parser.parse_program();
+        if !parser.errors.is_empty() || program.statements.len() != 1 || program.statements[0] != *stmt {
+            return None;
+        }
+        while self.comments.front().is_some_and(|c| c.line <= last) {
+            self.comments.pop_front();
+        }
+        Some(written.trim_start().to_string())
+    }
+
+    /// Print, each on its own line, the comments that come before source
+    /// line `line`, or at its start.
+    fn comments_before(&mut self, line: usize) {
+        while let Some(comment) = self.comments.front() {
+            if comment.line > line || (comment.line == line && comment.trailing) {
+                break;
+            }
+            let comment = self.comments.pop_front().expect("front was just checked");
+            let opened_block = self.out.is_empty() || self.out.ends_with("{\n") || self.out.ends_with(":\n");
+            if comment.blank_before && !opened_block && !self.out.ends_with("\n\n") {
+                self.out.push('\n');
+            }
+            self.write_indent();
+            self.out.push_str(&comment.text);
+            self.out.push('\n');
+            if comment.blank_after {
+                self.out.push('\n');
+            }
+        }
+    }
+
+    /// Drop a blank line left by a comment's `blank_after` where nothing
+    /// follows it.
+    fn trim_blank_line(&mut self) {
+        if self.out.ends_with("\n\n") {
+            self.out.pop();
+        }
+    }
+
+    /// Append the comments that followed the code just printed (from `start`
+    /// in `out`) on its last source line: `line` if it stayed on one line,
+    /// else the line of a closing brace it ends with.
+    fn trailing_comments(&mut self, start: usize, line: Option<usize>) {
+        let last_line = if !self.out[start..].contains('\n') {
+            line
+        } else {
+            self.last_close
+                .filter(|&(end, _)| end >= start && !self.out[end..].contains('\n'))
+                .map(|(_, line)| line)
+        };
+        let Some(last_line) = last_line else { return };
+        while let Some(comment) = self.comments.front() {
+            if !comment.trailing || comment.line != last_line {
+                break;
+            }
+            let comment = self.comments.pop_front().expect("front was just checked");
+            self.out.push(' ');
+            self.out.push_str(&comment.text);
+        }
+    }
+
+    /// `{ ... }` with the body indented one level; the opening brace goes on
+    /// the current line and the closing brace is left unterminated.
+    fn block(&mut self, block: &BlockStatement) {
+        let has_comments = self
+            .comments
+            .front()
+            .is_some_and(|c| c.line < block.end_line || (c.line == block.end_line && !c.trailing));
+        if block.statements.is_empty() && !has_comments {
+            self.out.push_str("{}");
+        } else {
+            self.out.push_str("{\n");
+            self.level += 1;
+            self.statements(&block.statements, &block.lines, block.end_line);
+            self.comments_before(block.end_line);
+            self.trim_blank_line();
+            self.level -= 1;
+            self.write_indent();
+            self.out.push('}');
+        }
+        self.last_close = Some((self.out.len(), block.end_line));
+    }
+
+    fn statement(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::Let(ls) => {
+                let keyword = if ls.constant { "const" } else { "let" };
+                self.out.push_str(&format!("{} {} = ", keyword, ls.name));
+                self.expression(&ls.value, PREC_LOWEST);
+                self.out.push(';');
+            }
+            Statement::Destructure(ds) => {
+                self.out.push_str(&format!("let {} = ", ds.pattern));
+                self.expression(&ds.value, PREC_LOWEST);
+                self.out.push(';');
+            }
+            Statement::Return(rs) => {
+                self.out.push_str("return ");
+                self.expression(&rs.return_value, PREC_LOWEST);
+                self.out.push(';');
+            }
+            Statement::Expression(es) => {
+                self.expression(&es.expression, PREC_LOWEST);
+                if !matches!(es.expression, Expression::If(_)) {
+                    self.out.push(';');
+                }
+            }
+            Statement::While(ws) => {
+                self.out.push_str("while (");
+                self.expression(&ws.condition, PREC_LOWEST);
+                self.out.push_str(") ");
+                self.block(&ws.body);
+            }
+            Statement::For(fs) => self.for_statement(fs),
+            Statement::ForIn(fs) => {
+                self.out.push_str(&format!("for ({} in ", fs.name));
+                self.expression(&fs.iterable, PREC_LOWEST);
+                self.out.push_str(") ");
+                self.block(&fs.body);
+            }
+            Statement::Yield(ys) => {
+                self.out.push_str("yield ");
+                self.expression(&ys.value, PREC_LOWEST);
+                self.out.push(';');
+            }
+            Statement::Breakpoint => self.out.push_str("breakpoint;"),
+            Statement::Switch(ss) => self.switch_statement(ss),
+            Statement::Function(fs) => self.function_statement(fs),
+            Statement::Test(ts) => self.test_statement(ts),
+            Statement::Describe(ds) => {
+                self.out.push_str(&format!("describe \"{}\" ", ds.name));
+                self.block(&ds.body);
+            }
+            Statement::Hook(hs) => {
+                self.out.push_str(&format!("{} ", hs.kind.keyword()));
+                self.block(&hs.body);
+            }
+            Statement::Namespace(ns) => {
+                self.out.push_str(&format!("namespace {} ", ns.name));
+                self.block(&ns.body);
+            }
+            Statement::Import(is) => self.import_statement(is),
+            Statement::Use(us) => self.out.push_str(&us.to_string()),
+            Statement::Export(es) => self.export_statement(es),
+            Statement::Class(cs) => self.class_statement(cs),
+        }
+    }
+
+    fn for_statement(&mut self, fs: &ForStatement) {
+        self.out.push_str("for (");
+        match fs.init.as_deref() {
+            Some(Statement::Let(ls)) => {
+                self.out.push_str(&format!("let {} = ", ls.name));
+                self.expression(&ls.value, PREC_LOWEST);
+            }
+            Some(Statement::Expression(es)) => self.expression(&es.expression, PREC_LOWEST),
+            Some(other) => self.statement(other),
+            None => {}
+        }
+        self.out.push(';');
+        if let Some(cond) = &fs.condition {
+            self.out.push(' ');
+            self.expression(cond, PREC_LOWEST);
+        }
+        self.out.push(';');
+        match fs.post.as_deref() {
+            Some(Statement::Expression(es)) => {
+                self.out.push(' ');
+                self.expression(&es.expression, PREC_LOWEST);
+            }
+            Some(other) => {
+                self.out.push(' ');
+                self.statement(other);
+            }
+            None => {}
+        }
+        self.out.push_str(") ");
+        self.block(&fs.body);
+    }
+
+    fn switch_statement(&mut self, ss: &SwitchStatement) {
+        self.out.push_str("switch (");
+        self.expression(&ss.subject, PREC_LOWEST);
+        self.out.push_str(") {\n");
+        self.level += 1;
+        for case in &ss.cases {
+            self.write_indent();
+            self.out.push_str("case ");
+            self.comma_separated(&case.values);
+            self.out.push_str(":\n");
+            self.switch_case_body(&case.body);
+        }
+        if let Some(default) = &ss.default {
+            self.write_indent();
+            self.out.push_str("default:\n");
+            self.switch_case_body(default);
+        }
+        self.level -= 1;
+        self.write_indent();
+        self.out.push('}');
+    }
+
+    fn switch_case_body(&mut self, body: &BlockStatement) {
+        self.level += 1;
+        self.statements(&body.statements, &body.lines, body.end_line);
+        self.comments_before(body.end_line);
+        self.level -= 1;
+    }
+
+    fn function_statement(&mut self, fs: &FunctionStatement) {
+        if !fs.tags.is_empty() {
+            let tags: Vec<String> = fs.tags.iter().map(|t| format!(":{}", t)).collect();
+            self.out.push_str(&format!("({})\n", tags.join(", ")));
+            self.write_indent();
+        }
+        let star = if fs.literal.generator { "*" } else { "" };
+        self.out.push_str(&format!("function{} {}", star, fs.name));
+        self.function_signature_and_body(&fs.literal);
+    }
+
+    fn function_signature_and_body(&mut self, literal: &FunctionLiteral) {
+        self.parameters(literal);
+        for (keyword, clauses) in [("requires", &literal.requires), ("ensures", &literal.ensures)] {
+            for clause in clauses {
+                self.out.push_str(&format!(" {} ", keyword));
+                self.expression(clause, PREC_LOWEST);
+            }
+        }
+        self.out.push(' ');
+        self.block(&literal.body);
+    }
+
+    /// `x => x + 1`, keeping the braces unless the body is a single
+    /// expression. An object literal body is parenthesised so it doesn't read
+    /// as a block.
+    fn arrow_function(&mut self, literal: &FunctionLiteral) {
+        let single_param = literal.params.len() == 1
+            && literal.rest.is_none()
+            && literal.defaults.iter().all(Option::is_none);
+        if single_param {
+            self.out.push_str(&literal.params[0].value);
+        } else {
+            self.parameters(literal);
+        }
+        self.out.push_str(" => ");
+        match literal.body.statements.as_slice() {
+            [Statement::Expression(es)] if matches!(es.expression, Expression::ObjectLiteral(_)) => {
+                self.out.push('(');
+                self.bare_expression(&es.expression);
+                self.out.push(')');
+            }
+            [Statement::Expression(es)] => self.expression(&es.expression, PREC_LOWEST),
+            _ => self.block(&literal.body),
+        }
+    }
+
+    fn parameters(&mut self, literal: &FunctionLiteral) {
+        self.out.push('(');
+        for (i, param) in literal.params.iter().enumerate() {
+            if i > 0 {
+                self.out.push_str(", ");
+            }
+            self.out.push_str(&param.value);
+            if let Some(Some(default)) = literal.defaults.get(i) {
+                self.out.push_str(" = ");
+                self.expression(default, PREC_LOWEST);
+            }
+        }
+        if let Some(rest) = &literal.rest {
+            if !literal.params.is_empty() {
+                self.out.push_str(", ");
+            }
+            self.out.push_str(&format!("...{}", rest));
+        }
+        self.out.push(')');
+    }
+
+    fn test_statement(&mut self, ts: &TestStatement) {
+        self.out.push_str(&format!("test \"{}\" ", ts.name));
+        self.block(&ts.body);
+    }
+
+    fn import_statement(&mut self, is: &ImportStatement) {
+        if is.names.is_empty() {
+            self.out.push_str(&format!("import \"{}\";", is.path));
+        } else {
+            let names: Vec<String> = is.names.iter().map(|n| n.value.clone()).collect();
+            self.out
+                .push_str(&format!("import {{ {} }} from \"{}\";", names.join(", "), is.path));
+        }
+    }
+
+    fn export_statement(&mut self, es: &ExportStatement) {
+        match &es.declaration {
+            Some(decl) => {
+                self.out.push_str("export ");
+                self.statement(decl);
+            }
+            None => {
+                let names: Vec<String> = es.names.iter().map(|n| n.value.clone()).collect();
+                self.out.push_str(&format!("export {{ {} }};", names.join(", ")));
+            }
+        }
+    }
+
+    fn class_statement(&mut self, cs: &ClassStatement) {
+        let has_comments = self.comments.front().is_some_and(|c| c.line < cs.end_line);
+        if cs.methods.is_empty() && !has_comments {
+            self.out.push_str(&format!("class {} {{}}", cs.name));
+            return;
+        }
+        self.out.push_str(&format!("class {} {{\n", cs.name));
+        self.level += 1;
+        for (i, method) in cs.methods.iter().enumerate() {
+            if i > 0 {
+                self.out.push('\n');
+            }
+            let line = cs.lines.get(i).copied();
+            if let Some(line) = line {
+                self.comments_before(line);
+            }
+            let start = self.out.len();
+            self.write_indent();
+            self.function_statement(method);
+            self.trailing_comments(start, line);
+            self.out.push('\n');
+        }
+        self.comments_before(cs.end_line);
+        self.trim_blank_line();
+        self.level -= 1;
+        self.write_indent();
+        self.out.push('}');
+        self.last_close = Some((self.out.len(), cs.end_line));
+    }
+
+    /// Print `expr`, parenthesised if it binds looser than `min_prec`.
+    fn expression(&mut self, expr: &Expression, min_prec: u8) {
+        let needs_parens = expression_precedence(expr) < min_prec;
+        if needs_parens {
+            self.out.push('(');
+        }
+        self.bare_expression(expr);
+        if needs_parens {
+            self.out.push(')');
+        }
+    }
+
+    fn bare_expression(&mut self, expr: &Expression) {
+        match expr {
+            Expression::Identifier(ident) => self.out.push_str(&ident.value),
+            Expression::IntegerLiteral(il) => match &il.lexeme {
+                Some(lexeme) => self.out.push_str(lexeme),
+                None => self.out.push_str(&il.value.to_string()),
+            },
+            Expression::FloatLiteral(fl) => match &fl.lexeme {
+                Some(lexeme) => self.out.push_str(lexeme),
+                // Debug keeps the decimal point (`1.0`), so floats stay floats.
+                None => self.out.push_str(&format!("{:?}", fl.value)),
+            },
+            Expression::BooleanLiteral(bl) => self.out.push_str(&bl.value.to_string()),
+            Expression::StringLiteral(sl) => self.string_literal(sl),
+            Expression::BytesLiteral(bl) => self.out.push_str(&bl.to_string()),
+            Expression::NullLiteral => self.out.push_str("null"),
+            Expression::Infix(infix) => self.infix(infix),
+            Expression::If(ifexpr) => self.if_expression(ifexpr),
+            Expression::Prefix(p) => self.prefix(p),
+            Expression::Postfix(p) => self.postfix(p),
+            Expression::FunctionLiteral(fl) if fl.arrow => self.arrow_function(fl),
+            Expression::FunctionLiteral(fl) => {
+                self.out.push_str(if fl.generator { "fn*" } else { "fn" });
+                self.function_signature_and_body(fl);
+            }
+            Expression::CallExpression(call) => {
+                self.expression(&call.function, PREC_CALL);
+                self.arguments(&call.arguments);
+            }
+            Expression::ArrayLiteral(al) => {
+                self.out.push('[');
+                self.comma_separated(&al.elements);
+                self.out.push(']');
+            }
+            Expression::IndexExpression(ix) => {
+                self.expression(&ix.left, PREC_CALL);
+                self.out.push('[');
+                self.expression(&ix.index, PREC_LOWEST);
+                self.out.push(']');
+            }
+            Expression::ObjectLiteral(ol) => {
+                if ol.properties.is_empty() {
+                    self.out.push_str("{}");
+                    return;
+                }
+                self.out.push_str("{ ");
+                for (i, (name, value)) in ol.properties.iter().enumerate() {
+                    if i > 0 {
+                        self.out.push_str(", ");
+                    }
+                    self.out.push_str(&format!("{}: ", name));
+                    self.expression(value, PREC_LOWEST);
+                }
+                self.out.push_str(" }");
+            }
+            Expression::PropertyAccess(pa) => self.property_access(pa),
+            Expression::Publish(pubexpr) => self.publish(pubexpr),
+            Expression::New(new_expr) => self.new_expression(new_expr),
+            Expression::Spread(spread) => {
+                self.out.push_str("...");
+                self.expression(&spread.value, PREC_LOWEST);
+            }
+            Expression::Builtin(builtin) => self.out.push_str(&builtin.to_string()),
+        }
+    }
+
+    fn infix(&mut self, infix: &InfixExpression) {
+        let prec = infix_precedence(&infix.operator);
+        // Binary operators parse left-associatively, so a right operand of
+        // equal precedence needs parentheses to keep its grouping; `**` is
+        // the right-associative exception.
+        let (left_prec, right_prec) = if infix.operator == InfixOp::Power {
+            (prec + 1, prec)
+        } else {
+            (prec, prec + 1)
+        };
+        self.expression(&infix.left, left_prec);
+        self.out.push_str(&format!(" {} ", infix.operator));
+        self.expression(&infix.right, right_prec);
+    }
+
+    /// A string is printed as written, so raw strings and escapes survive. A
+    /// `"""` literal is re-indented one level past the current line, and a
+    /// string without a lexeme that has line breaks becomes one.
+    fn string_literal(&mut self, sl: &StringLiteral) {
+        match sl.lexeme.as_deref() {
+            Some(lexeme) if lexeme.starts_with("\"\"\"") || lexeme.starts_with("r\"\"\"") => {
+                let raw = lexeme.starts_with('r');
+                let inner = &lexeme[if raw { 4 } else { 3 }..lexeme.len() - 3];
+                self.multiline_string(if raw { "r" } else { "" }, &dedent(inner));
+            }
+            Some(lexeme) if !lexeme.contains('\n') => self.out.push_str(lexeme),
+            _ if !sl.value.contains('\n') => {
+                self.out.push_str(&format!("\"{}\"", escape_string(&sl.value, false)));
+            }
+            _ => self.multiline_string("", &escape_string(&sl.value, true)),
+        }
+    }
+
+    /// A `"""` literal around already-escaped `contents`, its lines indented
+    /// one level past the current one.
+    fn multiline_string(&mut self, prefix: &str, contents: &str) {
+        self.level += 1;
+        self.out.push_str(prefix);
+        self.out.push_str("\"\"\"\n");
+        for line in contents.split('\n') {
+            if !line.is_empty() {
+                self.write_indent();
+                self.out.push_str(line);
+            }
+            self.out.push('\n');
+        }
+        self.write_indent();
+        self.out.push_str("\"\"\"");
+        self.level -= 1;
+    }
+
+    fn prefix(&mut self, p: &PrefixExpression) {
+        self.out.push_str(&p.operator.to_string());
+        // `- -x` would lex as `--x`, so nested prefixes are parenthesised.
+        if matches!(*p.right, Expression::Prefix(_)) {
+            self.out.push('(');
+            self.bare_expression(&p.right);
+            self.out.push(')');
+        } else {
+            self.expression(&p.right, PREC_PREFIX);
+        }
+    }
+
+    fn postfix(&mut self, p: &PostfixExpression) {
+        self.expression(&p.left, PREC_CALL);
+        self.out.push_str(match p.operator {
+            PostfixOp::Increment => "++",
+            PostfixOp::Decrement => "--",
+            PostfixOp::Try => "?",
+        });
+    }
+
+    fn if_expression(&mut self, ifexpr: &IfExpression) {
+        self.out.push_str("if (");
+        self.expression(&ifexpr.condition, PREC_LOWEST);
+        self.out.push_str(") ");
+        self.block(&ifexpr.consequence);
+
+        if let Some(alt) = &ifexpr.alternative {
+            // The parser desugars `else if` into a block holding a single if.
+            if let [Statement::Expression(es)] = alt.statements.as_slice()
+                && let Expression::If(nested) = &es.expression
+            {
+                self.out.push_str(" else ");
+                self.if_expression(nested);
+                return;
+            }
+            self.out.push_str(" else ");
+            self.block(alt);
+        }
+    }
+
+    fn property_access(&mut self, pa: &PropertyAccess) {
+        self.expression(&pa.object, PREC_CALL);
+        self.out.push_str(pa.separator());
+        self.out.push_str(&pa.property.value);
+    }
+
+    fn publish(&mut self, pubexpr: &PublishExpression) {
+        self.comma_separated(&pubexpr.args);
+        for tags in &pubexpr.stages {
+            self.out.push_str(" -> ");
+            let rendered: Vec<String> = tags.iter().map(|t| format!(":{}", t)).collect();
+            if rendered.len() == 1 {
+                self.out.push_str(&rendered[0]);
+            } else {
+                self.out.push_str(&format!("({})", rendered.join(", ")));
+            }
+        }
+    }
+
+    fn new_expression(&mut self, new_expr: &NewExpression) {
+        self.out.push_str(&format!("new {}", new_expr.class_name));
+        self.arguments(&new_expr.arguments);
+    }
+
+    fn arguments(&mut self, args: &[Expression]) {
+        self.out.push('(');
+        self.comma_separated(args);
+        self.out.push(')');
+    }
+
+    fn comma_separated(&mut self, exprs: &[Expression]) {
+        for (i, expr) in exprs.iter().enumerate() {
+            if i > 0 {
+                self.out.push_str(", ");
+            }
+            self.expression(expr, PREC_LOWEST);
+        }
+    }
+}
+
+fn is_declaration(stmt: &Statement) -> bool {
+    match stmt {
+        Statement::Function(_)
+        | Statement::Test(_)
+        | Statement::Describe(_)
+        | Statement::Hook(_)
+        | Statement::Namespace(_)
+        | Statement::Class(_) => true,
+        Statement::Export(es) => es.declaration.as_deref().is_some_and(is_declaration),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_source, FormatOptions};
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+
+    fn parse_statements(source: &str) -> Vec<crate::ast::Statement> {
+        let mut parser = Parser::new(Lexer::new(source));
+        let program = parser.parse_program();
+        assert!(parser.errors.is_empty(), "parse errors: {:?}", parser.errors);
+        program.statements
+    }
+
+    #[test]
+    fn formats_blocks_and_minimal_parens() {
+        let source = "let x=(1+2)*3;function add(a,b){return a+b;}if(x>1){print(x);}else if(x==0){x++;}else{-(-x);}";
+        let formatted = format_source(source, &FormatOptions::default()).unwrap();
+        assert_eq!(
+            formatted,
+            "let x = (1 + 2) * 3;\n\
+             \n\
+             function add(a, b) {\n    return a + b;\n}\n\
+             \n\
+             if (x > 1) {\n    print(x);\n} else if (x == 0) {\n    x++;\n} else {\n    -(-x);\n}\n"
+        );
+    }
+
+    #[test]
+    fn keeps_arrow_functions() {
+        let source = "let f=(x)=>{x*2;};let g=(a,b)=>{let c=a;c+b;};let h=x=>({a:x});";
+        let formatted = format_source(source, &FormatOptions::default()).unwrap();
+        assert_eq!(
+            formatted,
+            "let f = x => x * 2;\n\
+             let g = (a, b) => {\n    let c = a;\n    c + b;\n};\n\
+             let h = x => ({ a: x });\n"
+        );
+    }
+
+    #[test]
+    fn respects_indent_option_and_namespaced_access() {
+        let source = "namespace M { function f(){ Math::sqrt(4.0); } }";
+        let formatted = format_source(source, &FormatOptions { indent: 2 }).unwrap();
+        assert_eq!(
+            formatted,
+            "namespace M {\n  function f() {\n    Math::sqrt(4.0);\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn output_round_trips_and_is_idempotent() {
+        let source = r#"
+            (:Log, :Audit)
+            function handler(x) { x * 2; }
+            let o = { a: 1, b: [1, 2.5, "s"] };
+            const LIMIT = 3;
+            o.a = 10 - (3 - 2);
+            let f = fn(y) { y; };
+            let g = fn(a, b = a * 2, ...more) { more; };
+            let all = [0, ...g(1, 2, 3), 4];
+            let piped = all |> Array::map(f) |> len;
+            let arrows = [x => x + 1, (a, b = 2) => { a; b; }, () => ({ k: 1 }), (x => x)(1)];
+            let port = o?.db?.port ?? (x || 5432);
+            let pw = (-2) ** 3 ** (a ** b) ** 2 + 1_000 * 0xFF;
+            let bits = (~x & 255) | (y << 2 + 1) ^ (z div 2 >> 1);
+            function div(a, b) requires b != 0 ensures result * b == a { a / b; }
+            let [q, ...r] = [1, 2];
+            f(1) -> :Log -> (:A, :B);
+            for (let i = 0; i < 3; i++) { print(i); }
+            while (!false && true || false) { return 1; }
+            switch (o.a) { case 1, 2: print("low"); case "x": default: print("?"); }
+            class P { function init(x) { this.x = x; } function get() { this.x; } }
+            let p = new P(1);
+            test "t" { Test::assert(p.get() == 1); }
+            import { a } from "m.sl";
+            use Geo::Units as U;
+            use Math::*;
+            export let z = o["a"];
+            export const w = null;
+        "#;
+
+        let options = FormatOptions::default();
+        let formatted = format_source(source, &options).unwrap();
+        assert_eq!(parse_statements(&formatted), parse_statements(source));
+        assert_eq!(format_source(&formatted, &options).unwrap(), formatted);
+    }
+
+    #[test]
+    fn keeps_comments_in_place() {
+        let source = r#"// Header.
+
+// Adds two numbers.
+function add(a,b){ // inline note
+  /* before */ return a+b; // why
+  // last in the body
+}
+let url="http://x"; let s="// not a comment"; let p=r"C:\"; // raw
+class C {
+  // first method
+  get() { 1; }
+  // trailing in the class
+}
+switch (x) { case 1: // one
+  a; /* end of one */ default: b; }
+let o = {
+  a: 1, // kept where it is
+};
+// The end.
+"#;
+        let expected = r#"// Header.
+
+// Adds two numbers.
+function add(a, b) {
+    // inline note
+    /* before */
+    return a + b; // why
+    // last in the body
+}
+
+let url = "http://x";
+let s = "// not a comment";
+let p = r"C:\"; // raw
+
+class C {
+    // first method
+    function get() {
+        1;
+    }
+    // trailing in the class
+}
+
+switch (x) {
+    case 1:
+        // one
+        a; /* end of one */
+    default:
+        b;
+}
+let o = {
+  a: 1, // kept where it is
+};
+// The end.
+"#;
+        let options = FormatOptions::default();
+        let formatted = format_source(source, &options).unwrap();
+        assert_eq!(formatted, expected);
+        assert_eq!(parse_statements(&formatted), parse_statements(source));
+        assert_eq!(format_source(&formatted, &options).unwrap(), formatted);
+    }
+
+    #[test]
+    fn keeps_comments_in_empty_blocks_and_after_closing_braces() {
+        let source = "if (x) {\n    // nothing yet\n} // done\nlet f = fn() {\n    1;\n}; // trailing\n";
+        let formatted = format_source(source, &FormatOptions::default()).unwrap();
+        assert_eq!(formatted, source);
+    }
+
+    #[test]
+    fn keeps_numeric_literals_as_written() {
+        let source = "let mask = 0xFF | 0b1010 | 0o17;\nlet big = 1_000_000;\nlet f = 1.5e3 + 2.50 + 1.0;\n";
+        let formatted = format_source(source, &FormatOptions::default()).unwrap();
+        assert_eq!(formatted, source);
+    }
+
+    #[test]
+    fn escapes_strings_and_keeps_multiline_ones() {
+        let source = "let a = \"tab\\t \\\"q\\\" \\\\n \\d\";\n\nfunction f() {\n    let t = \"\"\"\n        one\n          two \\\"\"\"\n        \"\"\";\n}\n";
+        let formatted = format_source(source, &FormatOptions::default()).unwrap();
+        assert_eq!(formatted, source);
+        assert_eq!(parse_statements(&formatted), parse_statements(source));
+    }
+
+    #[test]
+    fn keeps_raw_strings_and_unicode_escapes_as_written() {
+        let source = "let re = r\"\\d+\\.\\w*\";\nlet path = r\"C:\\Users\\me\";\nlet s = \"caf\\u{e9} \\u{1F600}\";\n";
+        let formatted = format_source(source, &FormatOptions::default()).unwrap();
+        assert_eq!(formatted, source);
+        assert_eq!(parse_statements(&formatted), parse_statements(source));
+    }
+
+    #[test]
+    fn reindents_multiline_raw_strings_without_escaping_them() {
+        let source = "function f() {\nlet t = r\"\"\"\n    a\\d \\u{41}\n      b\n    \"\"\";\n}\n";
+        let expected = "function f() {\n    let t = r\"\"\"\n        a\\d \\u{41}\n          b\n        \"\"\";\n}\n";
+        let formatted = format_source(source, &FormatOptions::default()).unwrap();
+        assert_eq!(formatted, expected);
+        // The lexemes differ by indentation, but the strings are the same.
+        let shown = |source: &str| parse_statements(source).iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(shown(&formatted), shown(source));
+        assert_eq!(format_source(&formatted, &FormatOptions::default()).unwrap(), formatted);
+    }
+
+    #[test]
+    fn leaves_statements_with_comments_inside_as_written() {
+        let source = "function f(){\n  let xs=[1, /* c */ 2];\n    return xs;\n}\nlet total = add(1,\n    /* b */ 2);   // sum\nlet y=3;\n";
+        let expected = "function f() {\n    let xs=[1, /* c */ 2];\n    return xs;\n}\n\nlet total = add(1,\n    /* b */ 2);   // sum\nlet y = 3;\n";
+        let options = FormatOptions::default();
+        let formatted = format_source(source, &options).unwrap();
+        assert_eq!(formatted, expected);
+        assert_eq!(parse_statements(&formatted), parse_statements(source));
+        assert_eq!(format_source(&formatted, &options).unwrap(), formatted);
+
+        // A statement that shares its line with others can't be cut out, so
+        // there the comment still goes to the end of the line.
+        let source = "let a = [1, /* c */ 2]; let b = 3;\n";
+        let formatted = format_source(source, &options).unwrap();
+        assert_eq!(formatted, "let a = [1, 2];\nlet b = 3; /* c */\n");
+    }
+}
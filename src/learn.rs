@@ -0,0 +1,2 @@
+pub mod core;
+pub use core::{Lesson, LESSONS, check_answer, lesson_env, run_learn};
@@ -0,0 +1,252 @@
+//! `slang learn`: an interactive tour of the language.
+//!
+//! Each lesson is an example program embedded in the binary with
+//! `include_str!`, plus a small exercise. The runner shows and evaluates the
+//! example, then reads answers at a REPL-style prompt until one evaluates to
+//! the expected value. Answers run in the example's environment, so they can
+//! use anything the example defined.
+
+use std::rc::Rc;
+
+use rustyline::DefaultEditor;
+use rustyline::error::ReadlineError;
+
+use crate::env::{EnvRef, new_env};
+use crate::evaluator::eval;
+use crate::lexer::Lexer;
+use crate::object::Object;
+use crate::parser::Parser;
+use crate::repl::is_input_complete;
+
+pub struct Lesson {
+    pub title: &'static str,
+    pub example: &'static str,
+    pub task: &'static str,
+    /// The answer's result, as the REPL would print it.
+    pub expected: &'static str,
+    /// Shown by `:hint`, and checked by the tests.
+    pub solution: &'static str,
+}
+
+pub const LESSONS: &[Lesson] = &[
+    Lesson {
+        title: "Values and variables",
+        example: include_str!("lessons/01_values.sl"),
+        task: "Bind `price` to 25 and `qty` to 4, then evaluate the total cost.",
+        expected: "100",
+        solution: "let price = 25; let qty = 4; price * qty;",
+    },
+    Lesson {
+        title: "Strings",
+        example: include_str!("lessons/02_strings.sl"),
+        task: "Build the string \"Slang rocks\" from `name` and evaluate its length.",
+        expected: "11",
+        solution: "String::len(name + \" rocks\");",
+    },
+    Lesson {
+        title: "Functions and closures",
+        example: include_str!("lessons/03_functions.sl"),
+        task: "Define `function cube(x)` and evaluate `cube(4)`.",
+        expected: "64",
+        solution: "function cube(x) { x * x * x; } cube(4);",
+    },
+    Lesson {
+        title: "Control flow",
+        example: include_str!("lessons/04_control_flow.sl"),
+        task: "Use a loop to add up the numbers from 1 to 10.",
+        expected: "55",
+        solution: "let total = 0; for (let i = 1; i <= 10; i++) { total = total + i; } total;",
+    },
+    Lesson {
+        title: "Arrays and higher-order functions",
+        example: include_str!("lessons/05_arrays.sl"),
+        task: "Evaluate the sum of the squares of [1, 2, 3, 4] using map and reduce.",
+        expected: "30",
+        solution: "[1, 2, 3, 4].map(fn(x) { x * x; }).reduce(0, fn(a, b) { a + b; });",
+    },
+    Lesson {
+        title: "Objects and classes",
+        example: include_str!("lessons/06_objects.sl"),
+        task: "Create an object `point` with `x: 3` and `y: 4`, then evaluate `point.x * point.y`.",
+        expected: "12",
+        solution: "let point = { x: 3, y: 4 }; point.x * point.y;",
+    },
+    Lesson {
+        title: "Option and Result",
+        example: include_str!("lessons/07_options_results.sl"),
+        task: "Use `findIndex` and `Option::unwrapOr` to get the index of 8 in [3, 5, 8].",
+        expected: "2",
+        solution: "Option::unwrapOr(findIndex([3, 5, 8], 8), -1);",
+    },
+];
+
+const HELP_TEXT: &str = "\
+Type an answer to evaluate it. Unclosed braces continue on the next line.
+  :hint      show a solution
+  :example   show this lesson's example again
+  :skip      go to the next lesson
+  :quit      stop (resume later with `slang learn <n>`)";
+
+/// Parse and evaluate `source` in `env`.
+fn eval_source(source: &str, env: EnvRef) -> Result<Object, String> {
+    let mut parser = Parser::new(Lexer::new(source));
+    let program = parser.parse_program();
+    if !parser.errors.is_empty() {
+        return Err(parser.errors.join("\n"));
+    }
+    Ok(eval(&program, env))
+}
+
+/// A fresh environment with the lesson's example evaluated into it, along
+/// with the example's result.
+pub fn lesson_env(lesson: &Lesson) -> (EnvRef, Object) {
+    let env = new_env();
+    let result = eval_source(lesson.example, Rc::clone(&env))
+        .unwrap_or_else(Object::error);
+    (env, result)
+}
+
+/// Evaluate an answer in `env` and compare it with the lesson's expected
+/// result. On a mismatch the error describes what the answer produced.
+pub fn check_answer(lesson: &Lesson, env: EnvRef, input: &str) -> Result<(), String> {
+    let result = eval_source(input, env)?;
+    if result.is_error() {
+        return Err(result.to_string());
+    }
+    let got = result.to_string();
+    if got == lesson.expected {
+        Ok(())
+    } else {
+        Err(format!("got {}, expected {}", got, lesson.expected))
+    }
+}
+
+fn print_example(lesson: &Lesson) {
+    for line in lesson.example.lines() {
+        println!("    {}", line);
+    }
+}
+
+enum Outcome {
+    Passed,
+    Skipped,
+    Quit,
+}
+
+/// Step through the lessons, starting from `first` (1-based).
+pub fn run_learn(first: usize) {
+    let mut editor = match DefaultEditor::new() {
+        Ok(editor) => editor,
+        Err(e) => {
+            eprintln!("failed to start line editor: {}", e);
+            return;
+        }
+    };
+
+    println!("Welcome to Slang! There are {} lessons; type :help at any prompt.", LESSONS.len());
+
+    for (i, lesson) in LESSONS.iter().enumerate().skip(first.saturating_sub(1)) {
+        println!("\n== Lesson {}/{}: {} ==\n", i + 1, LESSONS.len(), lesson.title);
+        print_example(lesson);
+        println!();
+        let (env, result) = lesson_env(lesson);
+        println!("=> {}\n", result);
+        println!("Exercise: {}", lesson.task);
+
+        match run_exercise(&mut editor, lesson, env) {
+            Outcome::Passed => println!("Correct!"),
+            Outcome::Skipped => println!("Skipped."),
+            Outcome::Quit => {
+                println!("Bye! Pick up again with `slang learn {}`.", i + 1);
+                return;
+            }
+        }
+    }
+
+    println!("\nThat's every lesson. The README's Standard library section covers the rest.");
+}
+
+fn run_exercise(editor: &mut DefaultEditor, lesson: &Lesson, env: EnvRef) -> Outcome {
+    let mut buffer = String::new();
+    loop {
+        let prompt = if buffer.is_empty() { "learn>> " } else { "...>> " };
+        let line = match editor.readline(prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => {
+                buffer.clear();
+                continue;
+            }
+            Err(_) => return Outcome::Quit,
+        };
+
+        if buffer.is_empty() {
+            match line.trim() {
+                "" => continue,
+                ":help" | ":h" => {
+                    println!("{}", HELP_TEXT);
+                    continue;
+                }
+                ":hint" => {
+                    println!("{}", lesson.solution);
+                    continue;
+                }
+                ":example" => {
+                    print_example(lesson);
+                    continue;
+                }
+                ":skip" => return Outcome::Skipped,
+                ":quit" | ":q" => return Outcome::Quit,
+                other if other.starts_with(':') => {
+                    println!("unknown command '{}' (try :help)", other);
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        if !buffer.is_empty() {
+            buffer.push('\n');
+        }
+        buffer.push_str(&line);
+        if !is_input_complete(&buffer) {
+            continue;
+        }
+
+        let input = std::mem::take(&mut buffer);
+        let _ = editor.add_history_entry(input.as_str());
+        match check_answer(lesson, Rc::clone(&env), &input) {
+            Ok(()) => return Outcome::Passed,
+            Err(e) => println!("Not quite: {}. Try again, or :hint for a solution.", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LESSONS, check_answer, lesson_env};
+
+    #[test]
+    fn every_example_runs_and_every_solution_passes() {
+        for lesson in LESSONS {
+            let (env, result) = lesson_env(lesson);
+            assert!(!result.is_error(), "{}: example failed: {}", lesson.title, result);
+            assert_eq!(
+                check_answer(lesson, env, lesson.solution),
+                Ok(()),
+                "{}",
+                lesson.title
+            );
+        }
+    }
+
+    #[test]
+    fn wrong_answers_explain_the_mismatch() {
+        let lesson = &LESSONS[0];
+        let (env, _) = lesson_env(lesson);
+        assert_eq!(
+            check_answer(lesson, env.clone(), "99;"),
+            Err("got 99, expected 100".to_string())
+        );
+        assert!(check_answer(lesson, env, "let = ;").is_err());
+    }
+}
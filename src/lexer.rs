@@ -1,3 +1,3 @@
 pub mod core;
 
-pub use core::Lexer;
\ No newline at end of file
+pub use core::{block_comment_end, dedent, string_end, Lexer};
\ No newline at end of file
@@ -1,3 +1,5 @@
+use std::str::Chars;
+
 use crate::token::{lookup_ident, Token, TokenType};
 
 pub struct Lexer {
@@ -5,6 +7,21 @@ pub struct Lexer {
     position: usize,
     read_position: usize,
     ch: Option<char>,
+    /// Line of `ch`, counting from 1.
+    ch_line: usize,
+    /// Line the most recently returned token started on.
+    token_line: usize,
+}
+
+/// A position in the input to rewind a [`Lexer`] to, for the parser's
+/// lookahead.
+#[derive(Clone, Copy)]
+pub struct Checkpoint {
+    position: usize,
+    read_position: usize,
+    ch: Option<char>,
+    ch_line: usize,
+    token_line: usize,
 }
 
 impl Lexer {
@@ -13,7 +30,9 @@ impl Lexer {
             input: input.chars().collect(),
             position: 0,
             read_position: 0,
-            ch: None
+            ch: None,
+            ch_line: 1,
+            token_line: 1,
         };
 
         l.read_char();
@@ -21,6 +40,9 @@ impl Lexer {
     }
 
     pub fn read_char(&mut self) {
+        if self.ch == Some('\n') {
+            self.ch_line += 1;
+        }
         if self.read_position >= self.input.len() {
             self.ch = None;
         } else {
@@ -31,8 +53,34 @@ impl Lexer {
         self.read_position += 1;
     }
 
+    /// The line (from 1) the last token returned by [`Lexer::next_token`]
+    /// started on.
+    pub fn line(&self) -> usize {
+        self.token_line
+    }
+
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            position: self.position,
+            read_position: self.read_position,
+            ch: self.ch,
+            ch_line: self.ch_line,
+            token_line: self.token_line,
+        }
+    }
+
+    /// Go back to `checkpoint`, so the tokens after it are returned again.
+    pub fn rewind(&mut self, checkpoint: Checkpoint) {
+        self.position = checkpoint.position;
+        self.read_position = checkpoint.read_position;
+        self.ch = checkpoint.ch;
+        self.ch_line = checkpoint.ch_line;
+        self.token_line = checkpoint.token_line;
+    }
+
     pub fn next_token(&mut self) -> Token {
         self.skip_whitespace();
+        self.token_line = self.ch_line;
 
         let tok = match self.ch {
             Some('/') => {
@@ -41,6 +89,18 @@ impl Lexer {
                     self.read_char(); // consume second '/'
                     self.skip_comment();
                     return self.next_token();
+                } else if self.peek_char() == Some('*') {
+                    let Some(end) = block_comment_end(&self.input, self.position) else {
+                        while self.ch.is_some() {
+                            self.read_char();
+                        }
+                        return Token::new(TokenType::Illegal, String::from("unterminated block comment"));
+                    };
+                    // Step through the comment so line numbers keep counting.
+                    while self.position < end {
+                        self.read_char();
+                    }
+                    return self.next_token();
                 } else {
                     Token::new(TokenType::Div, String::from("/"))
                 }
@@ -49,6 +109,9 @@ impl Lexer {
                 if self.peek_char() == Some('=') {
                     self.read_char();
                     Token::new(TokenType::Equal, String::from("=="))
+                } else if self.peek_char() == Some('>') {
+                    self.read_char();
+                    Token::new(TokenType::FatArrow, String::from("=>"))
                 } else {
                     Token::new(TokenType::Assign, String::from("="))
                 }
@@ -65,6 +128,9 @@ impl Lexer {
                 if self.peek_char() == Some('=') {
                     self.read_char();
                     Token::new(TokenType::LessEqual, String::from("<="))
+                } else if self.peek_char() == Some('<') {
+                    self.read_char();
+                    Token::new(TokenType::ShiftLeft, String::from("<<"))
                 } else {
                     Token::new(TokenType::LessThan, String::from("<"))
                 }
@@ -73,6 +139,9 @@ impl Lexer {
                 if self.peek_char() == Some('=') {
                     self.read_char();
                     Token::new(TokenType::GreaterEqual, String::from(">="))
+                } else if self.peek_char() == Some('>') {
+                    self.read_char();
+                    Token::new(TokenType::ShiftRight, String::from(">>"))
                 } else {
                     Token::new(TokenType::GreaterThan, String::from(">"))
                 }
@@ -82,21 +151,21 @@ impl Lexer {
                     self.read_char();
                     Token::new(TokenType::And, String::from("&&"))
                 } else {
-                    Token::new(TokenType::Illegal, String::from("&"))
+                    Token::new(TokenType::BitAnd, String::from("&"))
                 }
             },
             Some('|') => {
                 if self.peek_char() == Some('|') {
                     self.read_char();
                     Token::new(TokenType::Or, String::from("||"))
+                } else if self.peek_char() == Some('>') {
+                    self.read_char();
+                    Token::new(TokenType::Pipeline, String::from("|>"))
                 } else {
-                    Token::new(TokenType::Illegal, String::from("|"))
+                    Token::new(TokenType::BitOr, String::from("|"))
                 }
             },
-            Some('"') => {
-                let literal = self.read_string();
-                Token::new(TokenType::String, literal)
-            },
+            Some('"') => self.read_string(false),
             Some('+') => {
                 if self.peek_char() == Some('+') {
                     self.read_char();
@@ -116,9 +185,37 @@ impl Lexer {
                     Token::new(TokenType::Minus, String::from("-"))
                 }
             }
-            Some('*') => Token::new(TokenType::Mul, String::from("*")),
+            Some('?') => {
+                if self.peek_char() == Some('.') {
+                    self.read_char();
+                    Token::new(TokenType::QuestionDot, String::from("?."))
+                } else if self.peek_char() == Some('?') {
+                    self.read_char();
+                    Token::new(TokenType::QuestionQuestion, String::from("??"))
+                } else {
+                    Token::new(TokenType::Question, String::from("?"))
+                }
+            }
+            Some('*') => {
+                if self.peek_char() == Some('*') {
+                    self.read_char();
+                    Token::new(TokenType::Pow, String::from("**"))
+                } else {
+                    Token::new(TokenType::Mul, String::from("*"))
+                }
+            }
             Some('%') => Token::new(TokenType::Mod, String::from("%")),
-            Some('.') => Token::new(TokenType::Dot, String::from(".")),
+            Some('^') => Token::new(TokenType::BitXor, String::from("^")),
+            Some('~') => Token::new(TokenType::Tilde, String::from("~")),
+            Some('.') => {
+                if self.peek_char() == Some('.') && self.input.get(self.read_position + 1) == Some(&'.') {
+                    self.read_char();
+                    self.read_char();
+                    Token::new(TokenType::Ellipsis, String::from("..."))
+                } else {
+                    Token::new(TokenType::Dot, String::from("."))
+                }
+            }
             Some('(') => Token::new(TokenType::Lparen, String::from("(")),
             Some(')') => Token::new(TokenType::Rparen, String::from(")")),
             Some('{') => Token::new(TokenType::Lbrace, String::from("{")),
@@ -135,11 +232,28 @@ impl Lexer {
                     Token::new(TokenType::Colon, String::from(":"))
                 }
             }
+            Some('r') if self.peek_char() == Some('"') => {
+                self.read_char(); // the opening quote
+                self.read_string(true)
+            }
+            Some('b') if self.peek_char() == Some('"') => {
+                self.read_char(); // the opening quote
+                match self.read_byte_string() {
+                    Some(literal) => Token::new(TokenType::Bytes, literal),
+                    None => return Token::new(TokenType::Illegal, String::from("unterminated byte string")),
+                }
+            }
             None => Token::new(TokenType::Eof, String::from("")),
             Some(ch) => {
                 if is_letter(ch) {
-                    let literal = self.read_identifier();
+                    let mut literal = self.read_identifier();
                     let ttype = lookup_ident(&literal);
+                    // `fn*` / `function*` introduce a generator; the star
+                    // stays on the keyword's literal for the parser to see.
+                    if ttype == TokenType::Function && self.ch == Some('*') {
+                        self.read_char();
+                        literal.push('*');
+                    }
                     return Token::new(ttype, literal);
                 } else if ch.is_ascii_digit() {
                     let (literal, is_float) = self.read_number();
@@ -181,23 +295,51 @@ impl Lexer {
         self.input[start..self.position].iter().collect()
     }
 
+    /// Read a number literal as written, including any `0x`/`0b`/`0o`
+    /// prefix, `_` separators and exponent; the parser works out its value.
     fn read_number(&mut self) -> (String, bool) {
         let start = self.position;
 
-        while matches!(self.ch, Some(ch) if ch.is_ascii_digit()) {
+        let radix_prefix = self.ch == Some('0')
+            && matches!(self.peek_char(), Some('x' | 'X' | 'b' | 'B' | 'o' | 'O'));
+        if radix_prefix {
+            self.read_char();
             self.read_char();
+            while matches!(self.ch, Some(ch) if ch.is_ascii_alphanumeric() || ch == '_') {
+                self.read_char();
+            }
+            let literal: String = self.input[start..self.position].iter().collect();
+            return (literal, false);
         }
 
+        self.read_digits();
+
         let mut is_float = false;
-        if self.ch == Some('.') {
-            if let Some(next_ch) = self.peek_char() {
-                if next_ch.is_ascii_digit() {
-                    is_float = true;
+        if self.ch == Some('.')
+            && let Some(next_ch) = self.peek_char()
+            && next_ch.is_ascii_digit()
+        {
+            is_float = true;
+            self.read_char();
+            self.read_digits();
+        }
+
+        // Exponent: `e` or `E`, an optional sign, then at least one digit.
+        if matches!(self.ch, Some('e' | 'E')) {
+            let after_e = self.peek_char();
+            let after_sign = self.input.get(self.read_position + 1).copied();
+            let has_exponent = match after_e {
+                Some(c) if c.is_ascii_digit() => true,
+                Some('+' | '-') => after_sign.is_some_and(|c| c.is_ascii_digit()),
+                _ => false,
+            };
+            if has_exponent {
+                is_float = true;
+                self.read_char();
+                if matches!(self.ch, Some('+' | '-')) {
                     self.read_char();
-                    while matches!(self.ch, Some(ch) if ch.is_ascii_digit()) {
-                        self.read_char();
-                    }
                 }
+                self.read_digits();
             }
         }
 
@@ -205,6 +347,13 @@ impl Lexer {
         (literal, is_float)
     }
 
+    /// Consume decimal digits and `_` separators.
+    fn read_digits(&mut self) {
+        while matches!(self.ch, Some(ch) if ch.is_ascii_digit() || ch == '_') {
+            self.read_char();
+        }
+    }
+
     fn peek_char(&self) -> Option<char> {
         if self.read_position >= self.input.len() {
             None
@@ -213,20 +362,64 @@ impl Lexer {
         }
     }
 
-    fn read_string(&mut self) -> String {
+    /// Read a `"..."` or `"""..."""` string literal, decoding its escapes
+    /// unless it is `raw`. Leaves `ch` on the closing quote.
+    fn read_string(&mut self, raw: bool) -> Token {
         // currently self.ch == '"'
-        self.read_char();            // move to first char after the quote
         let start = self.position;
-
-        while self.ch != Some('"') && self.ch != Some('\0') {
+        let lexeme_start = if raw { start - 1 } else { start };
+        let Some(end) = string_end(&self.input, start) else {
+            while self.ch.is_some() {
+                self.read_char();
+            }
+            return Token::new(TokenType::Illegal, String::from("unterminated string"));
+        };
+        let quotes = if self.input[start..].starts_with(&TRIPLE_QUOTE) { 3 } else { 1 };
+        let mut content: String = self.input[start + quotes..end - quotes].iter().collect();
+        // Step through the literal so line numbers keep counting.
+        while self.position < end - 1 {
             self.read_char();
         }
 
-        // at this point self.ch == '"' or '\0'
-        let s = self.input[start..self.position].iter().collect();
+        if quotes == 3 {
+            content = dedent(&content);
+        }
+        let value = if raw {
+            content
+        } else {
+            match unescape(&content) {
+                Ok(value) => value,
+                Err(e) => return Token::new(TokenType::Illegal, format!("invalid string: {}", e)),
+            }
+        };
+        Token {
+            lexeme: Some(self.input[lexeme_start..end].iter().collect()),
+            ..Token::new(TokenType::String, value)
+        }
+    }
+
+    /// Read the raw contents of a `b"..."` literal, leaving escapes for the
+    /// parser to decode. `\"` doesn't end the literal. Returns `None` if the
+    /// input ends first.
+    fn read_byte_string(&mut self) -> Option<String> {
+        // currently self.ch == '"'
+        self.read_char();
+        let start = self.position;
+
+        loop {
+            match self.ch {
+                Some('"') => break,
+                Some('\\') => {
+                    self.read_char();
+                    self.ch?;
+                    self.read_char();
+                }
+                Some(_) => self.read_char(),
+                None => return None,
+            }
+        }
 
-        // DO NOT call read_char() here
-        s
+        Some(self.input[start..self.position].iter().collect())
     }
 }
 
@@ -234,9 +427,136 @@ fn is_letter(ch: char) -> bool {
     ch.is_ascii_alphabetic() || ch == '_'
 }
 
+const TRIPLE_QUOTE: [char; 3] = ['"'; 3];
+
+/// The index just past the `/* ... */` comment starting at `chars[start]`,
+/// or `None` if the input ends inside it. Block comments nest, so commenting
+/// out code that already has one works.
+pub fn block_comment_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = start;
+    loop {
+        match (chars.get(i)?, chars.get(i + 1)) {
+            ('/', Some('*')) => {
+                depth += 1;
+                i += 2;
+            }
+            ('*', Some('/')) => {
+                depth -= 1;
+                i += 2;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => i += 1,
+        }
+    }
+}
+
+/// The index just past the string literal whose opening quote is at
+/// `chars[quote]`, or `None` if the input ends inside it. Follows the lexer's
+/// rules for escapes, `"""` strings and `r"..."` raw strings, so other
+/// scanners over the source skip strings the same way.
+pub fn string_end(chars: &[char], quote: usize) -> Option<usize> {
+    // An `r` right before the quote makes it raw, unless the `r` ends a
+    // longer identifier.
+    let raw = quote >= 1
+        && chars[quote - 1] == 'r'
+        && !(quote >= 2 && (is_letter(chars[quote - 2]) || chars[quote - 2].is_ascii_digit()));
+    let triple = chars[quote..].starts_with(&TRIPLE_QUOTE);
+    let mut i = quote + if triple { 3 } else { 1 };
+    loop {
+        match chars.get(i)? {
+            '\\' if !raw => i += 2,
+            '"' if !triple => return Some(i + 1),
+            // In a run of more than three quotes the last three close the
+            // string, so `"""say "hi""""` ends in a quote.
+            '"' if chars[i..].starts_with(&TRIPLE_QUOTE) => {
+                let run = chars[i..].iter().take_while(|&&c| c == '"').count();
+                return Some(i + run);
+            }
+            _ => i += 1,
+        }
+    }
+}
+
+/// Decode the escapes in a string literal. A backslash before any other
+/// character is kept, so regex patterns like `"\d+"` read as written.
+fn unescape(literal: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(literal.len());
+    let mut chars = literal.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some('0') => out.push('\0'),
+            Some('"') => out.push('"'),
+            Some('\\') => out.push('\\'),
+            Some('u') => match unicode_escape(&mut chars) {
+                Some(c) => out.push(c),
+                None => return Err("\\u needs a code point in braces, like \\u{1F600}".to_string()),
+            },
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    Ok(out)
+}
+
+/// The character of a `\u{...}` escape, after the `u`.
+fn unicode_escape(chars: &mut Chars) -> Option<char> {
+    if chars.next()? != '{' {
+        return None;
+    }
+    let mut hex = String::new();
+    loop {
+        match chars.next()? {
+            '}' => break,
+            c => hex.push(c),
+        }
+    }
+    if hex.is_empty() || hex.len() > 6 {
+        return None;
+    }
+    u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32)
+}
+
+/// The contents of a `"""` string. When the opening quotes end their line,
+/// that line break is dropped; when the closing quotes sit on a line of
+/// their own, that line is dropped too and its indentation is removed from
+/// every line.
+pub fn dedent(content: &str) -> String {
+    let Some(body) = content.strip_prefix('\n').or_else(|| content.strip_prefix("\r\n")) else {
+        return content.to_string();
+    };
+    let is_blank = |s: &str| s.chars().all(|c| c == ' ' || c == '\t');
+    let (body, indent) = match body.rfind('\n') {
+        Some(i) if is_blank(&body[i + 1..]) => (body[..i].strip_suffix('\r').unwrap_or(&body[..i]), &body[i + 1..]),
+        None if is_blank(body) => return String::new(),
+        _ => return body.to_string(),
+    };
+    body.split('\n')
+        .map(|line| match line.strip_prefix(indent) {
+            Some(rest) => rest,
+            None if is_blank(line) => "",
+            None => line,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::Lexer;
+    use crate::token::TokenType;
     use crate::token::TokenType::{Assign, Eof, Ident, Int, Let, Lparen, Mul, Plus, Rparen, Semicolon};
 
     #[test]
@@ -289,4 +609,78 @@ mod tests {
             );
         }
     }
-}
\ No newline at end of file
+
+    fn string_token(input: &str) -> (crate::token::TokenType, String) {
+        let tok = Lexer::new(input).next_token();
+        (tok.token_type, tok.literal)
+    }
+
+    #[test]
+    fn test_string_escapes() {
+        let tests = vec![
+            (r#""a\tb\nc""#, "a\tb\nc"),
+            (r#""say \"hi\"""#, "say \"hi\""),
+            (r#""back\\slash""#, "back\\slash"),
+            (r#""\u{48}\u{1F600}""#, "H\u{1F600}"),
+            (r#""\d+\.\w""#, "\\d+\\.\\w"),
+            (r#"r"C:\new\table""#, "C:\\new\\table"),
+            (r#"r"\d+""#, "\\d+"),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(string_token(input), (TokenType::String, expected.to_string()), "input: {}", input);
+        }
+
+        for input in [r#""\u{110000}""#, r#""\u41""#, r#""\u{41""#, r#""open"#, r#""ends in \""#] {
+            assert_eq!(string_token(input).0, TokenType::Illegal, "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn test_multiline_strings() {
+        let input = "let s = \"\"\"\n    first \"quoted\"\n      second\\tx\n\n    \"\"\";\nlet t = \"\"\"one \"line\" \"\"\";";
+        let mut l = Lexer::new(input);
+        let tokens: Vec<_> = std::iter::from_fn(|| {
+            let tok = l.next_token();
+            (tok.token_type != TokenType::Eof).then(|| (tok.token_type, tok.literal, l.line()))
+        })
+        .filter(|(ttype, _, _)| *ttype == TokenType::String || *ttype == TokenType::Let)
+        .collect();
+        assert_eq!(
+            tokens,
+            vec![
+                (TokenType::Let, "let".to_string(), 1),
+                (TokenType::String, "first \"quoted\"\n  second\tx\n".to_string(), 1),
+                (TokenType::Let, "let".to_string(), 6),
+                (TokenType::String, "one \"line\" ".to_string(), 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_block_comments() {
+        let input = "let /* one\n /* nested */ still a comment */ x = 1; /* a \"quote */\nx";
+        let mut l = Lexer::new(input);
+        let mut tokens = Vec::new();
+        loop {
+            let tok = l.next_token();
+            if tok.token_type == TokenType::Eof {
+                break;
+            }
+            tokens.push((tok.token_type, tok.literal, l.line()));
+        }
+        let expected: Vec<(TokenType, &str, usize)> = vec![
+            (Let, "let", 1),
+            (Ident, "x", 2),
+            (Assign, "=", 2),
+            (Int, "1", 2),
+            (Semicolon, ";", 2),
+            (Ident, "x", 3),
+        ];
+        let tokens: Vec<_> = tokens.iter().map(|(t, l, n)| (t.clone(), l.as_str(), *n)).collect();
+        assert_eq!(tokens, expected);
+
+        let mut l = Lexer::new("1 /* /* */");
+        l.next_token();
+        assert_eq!(l.next_token().token_type, TokenType::Illegal);
+    }
+}
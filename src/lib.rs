@@ -1,3 +1,4 @@
+
 pub mod token;
 pub mod lexer;
 pub mod ast;
@@ -5,9 +6,33 @@ pub mod parser;
 pub mod object;
 pub mod env;
 pub mod evaluator;
+pub mod event_loop;
+pub mod task;
 pub mod runtime;
+#[cfg(feature = "repl")]
+pub mod repl;
 pub mod builtins;
+pub mod stdlib;
 pub mod debug;
+pub mod capability;
+#[cfg(feature = "cache")]
+pub mod cache;
+#[cfg(feature = "cache")]
+pub mod compile;
+pub mod profile;
+pub mod compat;
+pub mod formatter;
+pub mod lint;
+pub mod optimizer;
+pub mod diff;
+pub mod package;
+#[cfg(feature = "repl")]
+pub mod learn;
+pub mod build_info;
+#[cfg(feature = "crash-report")]
+pub mod crash;
+pub mod lsp;
+mod protocol;
 
 #[cfg(test)]
 pub mod test_support;
@@ -19,8 +44,9 @@ pub use parser::Parser;
 pub use ast::{Program, Statement, Expression};
 pub use object::Object;
 pub use env::{Environment, EnvRef};
-pub use evaluator::eval;
+pub use evaluator::{eval, eval_with_options, EvalAbort, EvalOptions};
 pub use builtins::get as get_builtin;
+pub use build_info::{BuildInfo, build_info};
 
 /// Convenient prelude for common interpreter types and functions.
 pub mod prelude {
@@ -0,0 +1,3 @@
+pub mod core;
+
+pub use core::{Diagnostic, check_program, check_source};
@@ -0,0 +1,690 @@
+//! `slang check`: finds likely mistakes in a script without running it.
+//!
+//! The checks follow the evaluator's scoping: function calls, `test` blocks
+//! and namespaces get a scope of their own, while `if`, loop and `switch`
+//! bodies share the enclosing one. A function body is checked once the
+//! scope it is declared in has been, since by the time it's called it can
+//! see every binding there, including ones declared after it.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::ast::nodes::PostfixOp;
+use crate::ast::{
+    BlockStatement, Expression, FunctionLiteral, InfixOp, PrefixOp, Program, Statement,
+};
+use crate::env::{EnvRef, is_builtin_namespace, new_env};
+use crate::lexer::Lexer;
+use crate::object::Object;
+use crate::parser::Parser;
+
+/// A problem found on a line of the script.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub message: String,
+}
+
+/// Parse and check `source`. Parse errors come back as `Err`, since the
+/// other checks need the whole program.
+pub fn check_source(source: &str) -> Result<Vec<Diagnostic>, Vec<Diagnostic>> {
+    let mut parser = Parser::new(Lexer::new(source));
+    let program = parser.parse_program();
+    if !parser.errors.is_empty() {
+        return Err(parser
+            .errors
+            .into_iter()
+            .zip(parser.error_lines)
+            .map(|(message, line)| Diagnostic { line, message })
+            .collect());
+    }
+    Ok(check_program(&program))
+}
+
+/// Check a parsed program, returning warnings ordered by line.
+pub fn check_program(program: &Program) -> Vec<Diagnostic> {
+    let mut linter = Linter {
+        scopes: Vec::new(),
+        namespaces: HashMap::new(),
+        constant_members: HashSet::new(),
+        exported: HashSet::new(),
+        env: new_env(),
+        line: 1,
+        diagnostics: Vec::new(),
+    };
+    linter.collect_namespaces(&program.statements, "");
+    for stmt in &program.statements {
+        if let Statement::Export(es) = stmt {
+            let declared = es.declaration.as_deref().and_then(declared_name);
+            linter.exported.extend(es.names.iter().map(|n| n.value.clone()).chain(declared));
+        }
+    }
+
+    linter.scopes.push(Scope::new(true));
+    linter.statements(&program.statements, &program.lines);
+    linter.pop_scope();
+
+    let mut diagnostics = linter.diagnostics;
+    diagnostics.sort_by_key(|d| d.line);
+    diagnostics
+}
+
+struct Binding {
+    name: String,
+    line: usize,
+    used: bool,
+    /// Whether to warn if it's never read: true for `let`s, false for
+    /// parameters, functions and the like.
+    reportable: bool,
+    /// Declared with `const`.
+    constant: bool,
+}
+
+/// Code whose checks wait for the end of the enclosing scope.
+enum Deferred<'a> {
+    Function(&'a FunctionLiteral, usize),
+    Block(&'a BlockStatement, usize),
+}
+
+struct Scope<'a> {
+    bindings: Vec<Binding>,
+    /// The latest binding of each name.
+    names: HashMap<String, usize>,
+    /// False in namespaces, whose bindings are used from outside as members.
+    report_unused: bool,
+    deferred: Vec<Deferred<'a>>,
+}
+
+impl Scope<'_> {
+    fn new(report_unused: bool) -> Self {
+        Scope {
+            bindings: Vec::new(),
+            names: HashMap::new(),
+            report_unused,
+            deferred: Vec::new(),
+        }
+    }
+}
+
+struct Linter<'a> {
+    scopes: Vec<Scope<'a>>,
+    /// Members of the namespaces the script declares, by path (`A::B`).
+    namespaces: HashMap<String, HashSet<String>>,
+    /// The `const` members of those namespaces, as `A::B::member`.
+    constant_members: HashSet<String>,
+    /// Top-level names other modules can import.
+    exported: HashSet<String>,
+    /// For the members of builtin namespaces.
+    env: EnvRef,
+    /// The line of the statement being checked.
+    line: usize,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Linter<'a> {
+    fn warn(&mut self, line: usize, message: String) {
+        self.diagnostics.push(Diagnostic { line, message });
+    }
+
+    fn collect_namespaces(&mut self, statements: &[Statement], prefix: &str) {
+        for stmt in statements {
+            let stmt = match stmt {
+                Statement::Export(es) => match &es.declaration {
+                    Some(decl) => decl,
+                    None => continue,
+                },
+                stmt => stmt,
+            };
+            if let Statement::Namespace(ns) = stmt {
+                let path = format!("{}{}", prefix, ns.name.value);
+                let members: Vec<String> =
+                    ns.body.statements.iter().filter_map(declared_name).collect();
+                for stmt in &ns.body.statements {
+                    if let Statement::Let(ls) = stmt && ls.constant {
+                        self.constant_members.insert(format!("{}::{}", path, ls.name.value));
+                    }
+                }
+                self.namespaces.entry(path.clone()).or_default().extend(members);
+                self.collect_namespaces(&ns.body.statements, &format!("{}::", path));
+            }
+        }
+    }
+
+    /// Check the code deferred to the end of the current scope, then leave it.
+    fn pop_scope(&mut self) {
+        loop {
+            let deferred = std::mem::take(&mut self.scopes.last_mut().expect("a scope").deferred);
+            if deferred.is_empty() {
+                break;
+            }
+            for deferred in deferred {
+                match deferred {
+                    Deferred::Function(literal, line) => self.function(literal, line),
+                    Deferred::Block(block, line) => {
+                        self.scopes.push(Scope::new(true));
+                        self.line = line;
+                        self.block(block);
+                        self.pop_scope();
+                    }
+                }
+            }
+        }
+
+        let scope = self.scopes.pop().expect("a scope");
+        if !scope.report_unused {
+            return;
+        }
+        let top_level = self.scopes.is_empty();
+        for binding in scope.bindings {
+            let exported = top_level && self.exported.contains(&binding.name);
+            if binding.reportable && !binding.used && !binding.name.starts_with('_') && !exported {
+                self.warn(binding.line, format!("`{}` is never used", binding.name));
+            }
+        }
+    }
+
+    /// Bind `name` in the current scope.
+    fn declare(&mut self, name: &str, reportable: bool) {
+        let scope = self.scopes.last().expect("a scope");
+        if let Some(previous) = scope.names.get(name).map(|&i| &scope.bindings[i])
+            && previous.constant
+        {
+            let line = previous.line;
+            self.warn(self.line, format!("`{}` redeclares the constant on line {}", name, line));
+        }
+        if reportable {
+            self.check_shadowing(name);
+        }
+        let scope = self.scopes.last_mut().expect("a scope");
+        scope.bindings.push(Binding {
+            name: name.to_string(),
+            line: self.line,
+            used: false,
+            reportable,
+            constant: false,
+        });
+        scope.names.insert(name.to_string(), scope.bindings.len() - 1);
+    }
+
+    /// Warn if binding `name` in the current scope hides one of an
+    /// enclosing scope.
+    fn check_shadowing(&mut self, name: &str) {
+        // Only bindings above this one; a later one being visible here
+        // when a function runs is rarely what the reader has in mind.
+        let outer = self.scopes[..self.scopes.len() - 1].iter().rev().find_map(|s| {
+            s.bindings.iter().rev().find(|b| b.name == name && b.line <= self.line).map(|b| b.line)
+        });
+        if let Some(line) = outer {
+            self.warn(self.line, format!("`{}` shadows the binding on line {}", name, line));
+        }
+    }
+
+    /// Record a read of `name`.
+    fn read(&mut self, name: &str) {
+        for scope in self.scopes.iter_mut().rev() {
+            if let Some(&i) = scope.names.get(name) {
+                scope.bindings[i].used = true;
+                return;
+            }
+        }
+    }
+
+    /// Warn if `target`, the left side of an assignment or `++`/`--`, is a
+    /// constant or part of one.
+    fn assign(&mut self, target: &Expression) {
+        match target {
+            Expression::Identifier(ident) => {
+                let binding = self
+                    .scopes
+                    .iter()
+                    .rev()
+                    .find_map(|s| s.names.get(&ident.value).map(|&i| &s.bindings[i]));
+                if let Some(binding) = binding.filter(|b| b.constant) {
+                    let line = binding.line;
+                    self.warn(
+                        self.line,
+                        format!("assignment to the constant `{}` declared on line {}", ident.value, line),
+                    );
+                }
+            }
+            Expression::PropertyAccess(pa) if pa.namespaced => {
+                let Some(path) = namespace_path(&pa.object) else { return };
+                let member = format!("{}::{}", path, pa.property.value);
+                if self.constant_members.contains(&member) {
+                    self.warn(self.line, format!("assignment to the constant `{}`", member));
+                }
+            }
+            Expression::PropertyAccess(pa) => self.assign(&pa.object),
+            Expression::IndexExpression(ie) => self.assign(&ie.left),
+            _ => {}
+        }
+    }
+
+    fn is_bound(&self, name: &str) -> bool {
+        self.scopes.iter().any(|s| s.names.contains_key(name))
+    }
+
+    fn function(&mut self, literal: &'a FunctionLiteral, line: usize) {
+        self.scopes.push(Scope::new(true));
+        self.line = line;
+        self.declare("this", false);
+        for param in literal.params.iter().chain(&literal.rest) {
+            self.check_shadowing(&param.value);
+            self.declare(&param.value, false);
+        }
+        for default in literal.defaults.iter().flatten() {
+            self.expression(default);
+        }
+        for clause in &literal.requires {
+            self.expression(clause);
+        }
+        self.block(&literal.body);
+        if !literal.ensures.is_empty() {
+            self.declare("result", false);
+            for clause in &literal.ensures {
+                self.expression(clause);
+            }
+        }
+        self.pop_scope();
+    }
+
+    fn block(&mut self, block: &'a BlockStatement) {
+        self.statements(&block.statements, &block.lines);
+    }
+
+    fn statements(&mut self, statements: &'a [Statement], lines: &[usize]) {
+        let mut returned_on = None;
+        for (i, stmt) in statements.iter().enumerate() {
+            let line = lines.get(i).copied().unwrap_or(self.line);
+            if let Some(return_line) = returned_on.take() {
+                self.warn(line, format!("unreachable code after `return` on line {}", return_line));
+            }
+            self.line = line;
+            if matches!(stmt, Statement::Return(_)) && i + 1 < statements.len() {
+                returned_on = Some(line);
+            }
+            self.statement(stmt);
+        }
+    }
+
+    fn statement(&mut self, stmt: &'a Statement) {
+        match stmt {
+            Statement::Let(ls) => {
+                self.expression(&ls.value);
+                self.declare(&ls.name.value, true);
+                if ls.constant {
+                    let scope = self.scopes.last_mut().expect("a scope");
+                    if let Some(binding) = scope.bindings.last_mut() {
+                        binding.constant = true;
+                    }
+                }
+            }
+            Statement::Destructure(ds) => {
+                self.expression(&ds.value);
+                for name in ds.pattern.bindings() {
+                    self.declare(&name.value, true);
+                }
+            }
+            Statement::Return(rs) => self.expression(&rs.return_value),
+            Statement::Yield(ys) => self.expression(&ys.value),
+            Statement::Expression(es) => self.expression(&es.expression),
+            Statement::While(ws) => {
+                self.condition(&ws.condition);
+                self.block(&ws.body);
+            }
+            Statement::For(fs) => {
+                if let Some(init) = &fs.init {
+                    self.statement(init);
+                }
+                if let Some(condition) = &fs.condition {
+                    self.condition(condition);
+                }
+                self.block(&fs.body);
+                if let Some(post) = &fs.post {
+                    self.statement(post);
+                }
+            }
+            Statement::ForIn(fs) => {
+                self.expression(&fs.iterable);
+                self.declare(&fs.name.value, false);
+                self.block(&fs.body);
+            }
+            Statement::Switch(ss) => {
+                self.expression(&ss.subject);
+                for case in &ss.cases {
+                    for value in &case.values {
+                        self.expression(value);
+                    }
+                    self.block(&case.body);
+                }
+                if let Some(default) = &ss.default {
+                    self.block(default);
+                }
+            }
+            Statement::Function(fs) => {
+                self.declare(&fs.name.value, false);
+                self.defer(Deferred::Function(&fs.literal, self.line));
+            }
+            Statement::Class(cs) => {
+                self.declare(&cs.name.value, false);
+                for method in &cs.methods {
+                    self.defer(Deferred::Function(&method.literal, self.line));
+                }
+            }
+            Statement::Test(ts) => self.defer(Deferred::Block(&ts.body, self.line)),
+            Statement::Describe(ds) => self.defer(Deferred::Block(&ds.body, self.line)),
+            Statement::Hook(hs) => self.defer(Deferred::Block(&hs.body, self.line)),
+            Statement::Namespace(ns) => {
+                self.declare(&ns.name.value, false);
+                self.scopes.push(Scope::new(false));
+                self.block(&ns.body);
+                self.pop_scope();
+            }
+            Statement::Import(is) => {
+                for name in &is.names {
+                    self.declare(&name.value, false);
+                }
+            }
+            Statement::Export(es) => {
+                if let Some(decl) = &es.declaration {
+                    self.statement(decl);
+                }
+                for name in &es.names {
+                    self.read(&name.value);
+                }
+            }
+            Statement::Use(us) => {
+                let names: Vec<&str> = us.path.iter().map(|n| n.value.as_str()).collect();
+                self.read(names[0]);
+                for i in 1..names.len() {
+                    self.member(&names[..i].join("::"), names[i]);
+                }
+                if let Some(name) = us.binding() {
+                    self.declare(&name.value, false);
+                }
+            }
+            Statement::Breakpoint => {}
+        }
+    }
+
+    fn defer(&mut self, deferred: Deferred<'a>) {
+        self.scopes.last_mut().expect("a scope").deferred.push(deferred);
+    }
+
+    /// The condition of an `if` or loop.
+    fn condition(&mut self, condition: &'a Expression) {
+        if let Some(assignment) = find_assignment(condition) {
+            self.warn(
+                self.line,
+                format!("assignment `{}` in a condition; did you mean `==`?", assignment),
+            );
+        }
+        self.expression(condition);
+    }
+
+    fn expression(&mut self, expr: &'a Expression) {
+        match expr {
+            Expression::Identifier(ident) => self.read(&ident.value),
+            Expression::IntegerLiteral(_)
+            | Expression::BooleanLiteral(_)
+            | Expression::FloatLiteral(_)
+            | Expression::StringLiteral(_)
+            | Expression::BytesLiteral(_)
+            | Expression::NullLiteral => {}
+            Expression::Infix(infix) => {
+                if infix.operator == InfixOp::Assign {
+                    self.assign(&infix.left);
+                }
+                // Assigning to a variable doesn't read it.
+                let is_target = infix.operator == InfixOp::Assign
+                    && matches!(*infix.left, Expression::Identifier(_));
+                if !is_target {
+                    self.expression(&infix.left);
+                }
+                self.expression(&infix.right);
+            }
+            Expression::If(ie) => {
+                self.condition(&ie.condition);
+                self.block(&ie.consequence);
+                if let Some(alternative) = &ie.alternative {
+                    self.block(alternative);
+                }
+            }
+            Expression::Prefix(pe) => {
+                if matches!(pe.operator, PrefixOp::PreIncrement | PrefixOp::PreDecrement) {
+                    self.assign(&pe.right);
+                }
+                self.expression(&pe.right);
+            }
+            Expression::Postfix(pe) => {
+                if matches!(pe.operator, PostfixOp::Increment | PostfixOp::Decrement) {
+                    self.assign(&pe.left);
+                }
+                self.expression(&pe.left);
+            }
+            Expression::FunctionLiteral(fl) => self.defer(Deferred::Function(fl, self.line)),
+            Expression::CallExpression(call) => {
+                self.expression(&call.function);
+                for arg in &call.arguments {
+                    self.expression(arg);
+                }
+            }
+            Expression::ArrayLiteral(al) => {
+                for element in &al.elements {
+                    self.expression(element);
+                }
+            }
+            Expression::IndexExpression(ie) => {
+                self.expression(&ie.left);
+                self.expression(&ie.index);
+            }
+            Expression::ObjectLiteral(ol) => {
+                for (_, value) in &ol.properties {
+                    self.expression(value);
+                }
+            }
+            Expression::PropertyAccess(pa) => {
+                self.expression(&pa.object);
+                if pa.namespaced && let Some(path) = namespace_path(&pa.object) {
+                    self.member(&path, &pa.property.value);
+                }
+            }
+            Expression::Publish(pe) => {
+                for arg in &pe.args {
+                    self.expression(arg);
+                }
+            }
+            Expression::New(ne) => {
+                self.read(&ne.class_name.value);
+                for arg in &ne.arguments {
+                    self.expression(arg);
+                }
+            }
+            Expression::Spread(se) => self.expression(&se.value),
+            Expression::Builtin(_) => {}
+        }
+    }
+
+    /// Check that `path::member` exists, for namespaces whose members are known.
+    fn member(&mut self, path: &str, member: &str) {
+        let declared = self.namespaces.get(path);
+        let root = path.split("::").next().unwrap_or(path);
+        // A variable that happens to share a builtin namespace's name.
+        let builtin = is_builtin_namespace(path) && (declared.is_some() || !self.is_bound(root));
+        if declared.is_none() && !builtin {
+            return;
+        }
+        let mut members: Vec<String> = declared.into_iter().flatten().cloned().collect();
+        if builtin && let Some(Object::Object(map)) = self.env.borrow().get(path) {
+            members.extend(map.keys().cloned());
+        }
+        if members.iter().any(|m| m == member) {
+            return;
+        }
+        let mut message = format!("`{}::{}` is not a member of `{}`", path, member, path);
+        let closest = members
+            .iter()
+            .map(|m| (edit_distance(m, member), m))
+            .filter(|(distance, _)| *distance <= 2)
+            .min();
+        if let Some((_, suggestion)) = closest {
+            message.push_str(&format!("; did you mean `{}`?", suggestion));
+        }
+        self.warn(self.line, message);
+    }
+}
+
+/// The name a declaration binds.
+fn declared_name(stmt: &Statement) -> Option<String> {
+    match stmt {
+        Statement::Let(ls) => Some(ls.name.value.clone()),
+        Statement::Function(fs) => Some(fs.name.value.clone()),
+        Statement::Class(cs) => Some(cs.name.value.clone()),
+        Statement::Namespace(ns) => Some(ns.name.value.clone()),
+        Statement::Export(es) => es.declaration.as_deref().and_then(declared_name),
+        _ => None,
+    }
+}
+
+/// `A::B` for the expression `A::B`, if it's a plain namespace path.
+fn namespace_path(expr: &Expression) -> Option<String> {
+    match expr {
+        Expression::Identifier(ident) => Some(ident.value.clone()),
+        Expression::PropertyAccess(pa) if pa.namespaced => {
+            Some(format!("{}::{}", namespace_path(&pa.object)?, pa.property.value))
+        }
+        _ => None,
+    }
+}
+
+/// An assignment in a condition, looking through `&&`, `||` and `!`.
+fn find_assignment(expr: &Expression) -> Option<&Expression> {
+    match expr {
+        Expression::Infix(infix) => match infix.operator {
+            InfixOp::Assign => Some(expr),
+            InfixOp::And | InfixOp::Or => {
+                find_assignment(&infix.left).or_else(|| find_assignment(&infix.right))
+            }
+            _ => None,
+        },
+        Expression::Prefix(pe) if pe.operator == PrefixOp::Not => find_assignment(&pe.right),
+        _ => None,
+    }
+}
+
+/// The number of single-character edits between `a` and `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = previous + usize::from(ca != *cb);
+            previous = row[j + 1];
+            row[j + 1] = substitute.min(previous + 1).min(row[j] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Diagnostic, check_source};
+
+    fn check(source: &str) -> Vec<(usize, String)> {
+        check_source(source)
+            .expect("no parse errors")
+            .into_iter()
+            .map(|d| (d.line, d.message))
+            .collect()
+    }
+
+    fn warning(line: usize, message: &str) -> (usize, String) {
+        (line, message.to_string())
+    }
+
+    #[test]
+    fn reports_unused_and_shadowed_bindings() {
+        let source = "let unused = 1;\nlet used = 2;\nfunction f(x) {\n    let used = x;\n    return used;\n}\nprint(f(used));\n";
+        assert_eq!(
+            check(source),
+            vec![
+                warning(1, "`unused` is never used"),
+                warning(4, "`used` shadows the binding on line 2"),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_parameters_that_shadow_outer_bindings() {
+        assert_eq!(
+            check("let x = 1; function f(x) { x; }\nf(x);\n"),
+            vec![warning(1, "`x` shadows the binding on line 1")]
+        );
+
+        let source = "let x = 1;\nfunction f() {\n    let x = 2;\n    return x;\n}\nlet x = 3;\nprint(f(), x);\n";
+        assert_eq!(
+            check(source),
+            vec![
+                warning(1, "`x` is never used"),
+                warning(3, "`x` shadows the binding on line 1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn functions_see_bindings_declared_after_them() {
+        let source = "function f() {\n    return config;\n}\nlet config = 1;\nlet _ignored = 2;\nexport let api = 3;\nnamespace Util {\n    let member = 4;\n}\nf();\n";
+        assert_eq!(check(source), vec![]);
+    }
+
+    #[test]
+    fn reports_unreachable_code_after_return() {
+        let source = "function g() {\n    return 1;\n    print(\"never\");\n    print(\"again\");\n}\ng();\n";
+        assert_eq!(check(source), vec![warning(3, "unreachable code after `return` on line 2")]);
+    }
+
+    #[test]
+    fn reports_unknown_namespace_members() {
+        let source = "namespace Geo {\n    function area(w, h) { w * h; }\n}\nprint(Array::mapp([1], fn(x) { x; }));\nprint(Geo::aera(1, 2), Geo::area(1, 2));\nprint(Math::PI, Mystery::anything);\n";
+        assert_eq!(
+            check(source),
+            vec![
+                warning(4, "`Array::mapp` is not a member of `Array`; did you mean `map`?"),
+                warning(5, "`Geo::aera` is not a member of `Geo`; did you mean `area`?"),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_assignment_in_conditions() {
+        let source = "let x = 1;\nif (x = 2) { print(x); }\nwhile (x == 2 && (x = 3)) { print(x); }\n";
+        let warnings = check(source);
+        assert_eq!(warnings.len(), 2, "{:?}", warnings);
+        assert_eq!(warnings[0].0, 2);
+        assert!(warnings[0].1.starts_with("assignment `"), "{:?}", warnings);
+        assert!(warnings[0].1.ends_with("in a condition; did you mean `==`?"), "{:?}", warnings);
+        assert_eq!(warnings[1].0, 3);
+    }
+
+    #[test]
+    fn reports_assignments_to_constants() {
+        let source = "const LIMIT = 1;\nnamespace Config {\n    const PORT = 80;\n}\nLIMIT = 2;\nLIMIT++;\nConfig::PORT = 8080;\nconst LIMIT = 3;\nfunction f() {\n    let LIMIT = 4;\n    LIMIT = LIMIT + 1;\n}\nprint(f(), LIMIT);\n";
+        assert_eq!(
+            check(source),
+            vec![
+                warning(5, "assignment to the constant `LIMIT` declared on line 1"),
+                warning(6, "assignment to the constant `LIMIT` declared on line 1"),
+                warning(7, "assignment to the constant `Config::PORT`"),
+                warning(8, "`LIMIT` redeclares the constant on line 1"),
+                warning(10, "`LIMIT` shadows the binding on line 8"),
+            ]
+        );
+    }
+
+    #[test]
+    fn returns_parse_errors_with_their_lines() {
+        let errors = check_source("let a = 1;\nlet = 2;\n").unwrap_err();
+        assert!(matches!(errors.first(), Some(Diagnostic { line: 2, .. })), "{:?}", errors);
+    }
+}
@@ -0,0 +1,574 @@
+//! `slang lsp`: a Language Server Protocol server for editors.
+//!
+//! Open documents are kept in memory, resent in full on every change. The
+//! server publishes parse errors, or failing those the warnings from
+//! `slang check`, as diagnostics, finds the definitions of functions,
+//! variables, classes and namespaces declared in a document,
+//! shows the builtin docs from [`crate::builtins::docs`] on hover and
+//! completes namespace members after `::`. Positions are counted in UTF-16
+//! code units, LSP's default encoding, and converted to characters here.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, BufRead, Write};
+
+use serde_json::{Value, json};
+
+use crate::ast::{Expression, Statement};
+use crate::builtins::docs::doc as builtin_doc;
+use crate::compat;
+use crate::env::{EnvRef, is_builtin_namespace, new_env};
+use crate::lexer::Lexer;
+use crate::lint::{self, Diagnostic};
+use crate::object::Object;
+use crate::parser::Parser;
+use crate::protocol::{read_message, write_message};
+
+// `CompletionItemKind` values.
+const KIND_FUNCTION: u8 = 3;
+const KIND_VARIABLE: u8 = 6;
+const KIND_CLASS: u8 = 7;
+const KIND_MODULE: u8 = 9;
+const KIND_CONSTANT: u8 = 21;
+
+/// Something declared in a document, found by go-to-definition.
+struct Definition {
+    /// Qualified by its namespaces, e.g. `Geometry::area`.
+    name: String,
+    kind: u8,
+    /// Counting from 0 in UTF-16 code units, like LSP positions.
+    line: usize,
+    column: usize,
+    width: usize,
+    /// The declaring line, shown on hover.
+    text: String,
+}
+
+struct Document {
+    text: String,
+    definitions: Vec<Definition>,
+}
+
+impl Document {
+    /// Parse `text`, returning the document and its diagnostics.
+    fn new(text: String) -> (Self, Vec<Value>) {
+        let mut parser = Parser::new(Lexer::new(&text));
+        let program = parser.parse_program();
+        let source: Vec<&str> = text.lines().collect();
+
+        // `slang check`'s warnings need the whole program, so only without
+        // parse errors.
+        let (severity, problems) = if parser.errors.is_empty() {
+            (2, lint::check_program(&program))
+        } else {
+            let errors = parser.errors.iter().zip(&parser.error_lines);
+            (1, errors.map(|(message, &line)| Diagnostic { line, message: message.clone() }).collect())
+        };
+        let diagnostics = problems
+            .into_iter()
+            .map(|problem| {
+                let line = problem.line.saturating_sub(1).min(source.len().saturating_sub(1));
+                let width = source.get(line).map_or(0, |text| text.encode_utf16().count());
+                json!({
+                    "range": {
+                        "start": { "line": line, "character": 0 },
+                        "end": { "line": line, "character": width },
+                    },
+                    "severity": severity,
+                    "source": "slang",
+                    "message": problem.message,
+                })
+            })
+            .collect();
+
+        let mut definitions = Vec::new();
+        for (stmt, line) in program.statements.iter().zip(&program.lines) {
+            define(stmt, *line, "", &source, &mut definitions);
+        }
+        (Document { text, definitions }, diagnostics)
+    }
+
+    /// The definition `symbol` refers to. A bare name also matches a
+    /// declaration inside a namespace, as used from within it.
+    fn find(&self, symbol: &str) -> Option<&Definition> {
+        self.definitions.iter().find(|d| d.name == symbol).or_else(|| {
+            if symbol.contains("::") {
+                return None;
+            }
+            self.definitions
+                .iter()
+                .find(|d| d.name.rsplit("::").next() == Some(symbol))
+        })
+    }
+}
+
+/// Record the declaration `stmt`, written on `line`, and any inside it.
+fn define(stmt: &Statement, line: usize, prefix: &str, source: &[&str], out: &mut Vec<Definition>) {
+    let (name, kind) = match stmt {
+        Statement::Function(f) => (&f.name.value, KIND_FUNCTION),
+        Statement::Let(l) if matches!(l.value, Expression::FunctionLiteral(_)) => (&l.name.value, KIND_FUNCTION),
+        Statement::Let(l) if l.constant => (&l.name.value, KIND_CONSTANT),
+        Statement::Let(l) => (&l.name.value, KIND_VARIABLE),
+        Statement::Class(c) => (&c.name.value, KIND_CLASS),
+        Statement::Namespace(ns) => {
+            let inner = format!("{}{}::", prefix, ns.name.value);
+            for (stmt, line) in ns.body.statements.iter().zip(&ns.body.lines) {
+                define(stmt, *line, &inner, source, out);
+            }
+            (&ns.name.value, KIND_MODULE)
+        }
+        Statement::Export(e) => {
+            if let Some(declaration) = &e.declaration {
+                define(declaration, line, prefix, source, out);
+            }
+            return;
+        }
+        _ => return,
+    };
+    let line = line.saturating_sub(1);
+    let text = source.get(line).copied().unwrap_or("");
+    out.push(Definition {
+        name: format!("{}{}", prefix, name),
+        kind,
+        line,
+        column: utf16_column(text, column_of(text, name)),
+        width: name.encode_utf16().count(),
+        text: text.trim().trim_end_matches('{').trim_end().to_string(),
+    });
+}
+
+fn is_ident(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Where `name` first appears as a whole word in `text`.
+fn column_of(text: &str, name: &str) -> usize {
+    let chars: Vec<char> = text.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    (0..chars.len())
+        .find(|&i| {
+            chars[i..].starts_with(&name)
+                && (i == 0 || !is_ident(chars[i - 1]))
+                && chars.get(i + name.len()).is_none_or(|&c| !is_ident(c))
+        })
+        .unwrap_or(0)
+}
+
+/// The UTF-16 offset of the character at `column` in `text`.
+fn utf16_column(text: &str, column: usize) -> usize {
+    text.chars().take(column).map(char::len_utf16).sum()
+}
+
+/// The character at UTF-16 offset `offset` in `text`.
+fn char_column(text: &str, offset: usize) -> usize {
+    let mut units = 0;
+    text.chars()
+        .take_while(|c| {
+            units += c.len_utf16();
+            units <= offset
+        })
+        .count()
+}
+
+/// The start of the `a::b::c` path that ends at `end`.
+fn path_start(chars: &[char], end: usize) -> usize {
+    let mut start = end;
+    loop {
+        while start > 0 && is_ident(chars[start - 1]) {
+            start -= 1;
+        }
+        if start >= 3 && chars[start - 1] == ':' && chars[start - 2] == ':' && is_ident(chars[start - 3]) {
+            start -= 2;
+        } else {
+            return start;
+        }
+    }
+}
+
+/// The name under the cursor with the namespaces before it, e.g.
+/// `Array::map` with the cursor on `map` but just `Array` on `Array`.
+fn symbol_at(text: &str, line: usize, character: usize) -> Option<String> {
+    let chars: Vec<char> = text.lines().nth(line)?.chars().collect();
+    let mut end = character.min(chars.len());
+    while end < chars.len() && is_ident(chars[end]) {
+        end += 1;
+    }
+    let start = path_start(&chars, end);
+    if start == end {
+        return None;
+    }
+    Some(chars[start..end].iter().collect())
+}
+
+/// The namespace path before a `::` at the cursor, e.g. `Array` in `Array::ma|`.
+fn namespace_at(text: &str, line: usize, character: usize) -> Option<String> {
+    let chars: Vec<char> = text.lines().nth(line)?.chars().take(character).collect();
+    let mut end = chars.len();
+    while end > 0 && is_ident(chars[end - 1]) {
+        end -= 1;
+    }
+    if end < 2 || chars[end - 1] != ':' || chars[end - 2] != ':' {
+        return None;
+    }
+    let start = path_start(&chars, end - 2);
+    (start < end - 2).then(|| chars[start..end - 2].iter().collect())
+}
+
+struct Server {
+    output: Box<dyn Write>,
+    documents: HashMap<String, Document>,
+    /// For the names of builtin namespaces and their members.
+    env: EnvRef,
+}
+
+/// Serve one editor session, reading requests and notifications from
+/// `input` and writing to `output`, until `exit` or the end of the input.
+pub fn serve(mut input: impl BufRead, output: impl Write + 'static) -> io::Result<()> {
+    let mut server = Server {
+        output: Box::new(output),
+        documents: HashMap::new(),
+        env: new_env(),
+    };
+    while let Some(message) = read_message(&mut input)? {
+        let params = &message["params"];
+        let result = match message["method"].as_str().unwrap_or("") {
+            "initialize" => Some(json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                    "completionProvider": { "triggerCharacters": [":"] },
+                },
+                "serverInfo": { "name": "slang", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            "shutdown" => Some(Value::Null),
+            "exit" => return Ok(()),
+            "textDocument/didOpen" => {
+                let doc = &params["textDocument"];
+                server.update(&doc["uri"], doc["text"].as_str().unwrap_or(""))?;
+                None
+            }
+            "textDocument/didChange" => {
+                // Full sync: the last change holds the whole text.
+                if let Some(change) = params["contentChanges"].as_array().and_then(|c| c.last()) {
+                    server.update(&params["textDocument"]["uri"], change["text"].as_str().unwrap_or(""))?;
+                }
+                None
+            }
+            "textDocument/didClose" => {
+                let uri = &params["textDocument"]["uri"];
+                server.documents.remove(uri.as_str().unwrap_or(""));
+                server.notify("textDocument/publishDiagnostics", json!({ "uri": uri, "diagnostics": [] }))?;
+                None
+            }
+            "textDocument/hover" => Some(server.hover(params)),
+            "textDocument/definition" => Some(server.definition(params)),
+            "textDocument/completion" => Some(server.completion(params)),
+            _ => None,
+        };
+        // Only requests have an id; notifications get no reply.
+        if let Some(id) = message.get("id") {
+            let reply = match result {
+                Some(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                None => json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": -32601, "message": format!("{} is not supported", message["method"]) },
+                }),
+            };
+            write_message(&mut *server.output, &reply)?;
+        }
+    }
+    Ok(())
+}
+
+impl Server {
+    fn notify(&mut self, method: &str, params: Value) -> io::Result<()> {
+        write_message(&mut *self.output, &json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+    }
+
+    fn update(&mut self, uri: &Value, text: &str) -> io::Result<()> {
+        let (document, diagnostics) = Document::new(text.to_string());
+        self.documents.insert(uri.as_str().unwrap_or("").to_string(), document);
+        self.notify("textDocument/publishDiagnostics", json!({ "uri": uri, "diagnostics": diagnostics }))
+    }
+
+    /// The document and cursor position a request is about, the position's
+    /// character converted from UTF-16 code units.
+    fn position(&self, params: &Value) -> Option<(&Document, usize, usize)> {
+        let document = self.documents.get(params["textDocument"]["uri"].as_str()?)?;
+        let line = params["position"]["line"].as_u64()? as usize;
+        let offset = params["position"]["character"].as_u64()? as usize;
+        let character = char_column(document.text.lines().nth(line).unwrap_or(""), offset);
+        Some((document, line, character))
+    }
+
+    fn definition(&self, params: &Value) -> Value {
+        let Some((document, line, character)) = self.position(params) else {
+            return Value::Null;
+        };
+        let Some(symbol) = symbol_at(&document.text, line, character) else {
+            return Value::Null;
+        };
+        match document.find(&symbol) {
+            Some(def) => {
+                json!({
+                    "uri": params["textDocument"]["uri"],
+                    "range": {
+                        "start": { "line": def.line, "character": def.column },
+                        "end": { "line": def.line, "character": def.column + def.width },
+                    },
+                })
+            }
+            None => Value::Null,
+        }
+    }
+
+    fn hover(&self, params: &Value) -> Value {
+        let Some((document, line, character)) = self.position(params) else {
+            return Value::Null;
+        };
+        let Some(symbol) = symbol_at(&document.text, line, character) else {
+            return Value::Null;
+        };
+        let contents = if let Some(def) = document.find(&symbol) {
+            format!("```slang\n{}\n```", def.text)
+        } else if let Some(doc) = builtin_doc(&symbol) {
+            doc
+        } else if is_builtin_namespace(&symbol) {
+            let members: Vec<String> = self.members(document, &symbol).into_keys().collect();
+            format!("`{}` namespace: {}", symbol, members.join(", "))
+        } else {
+            return Value::Null;
+        };
+        json!({ "contents": { "kind": "markdown", "value": contents } })
+    }
+
+    /// The members of the namespace `path`, builtin or declared in
+    /// `document`, with their completion kinds.
+    fn members(&self, document: &Document, path: &str) -> BTreeMap<String, u8> {
+        let mut members = BTreeMap::new();
+        if is_builtin_namespace(path)
+            && let Some(Object::Object(map)) = self.env.borrow().get(path)
+        {
+            for (name, value) in map.iter() {
+                let kind = match value {
+                    Object::Builtin(_) | Object::NativeFn(_) | Object::Function(_) => KIND_FUNCTION,
+                    _ => KIND_CONSTANT,
+                };
+                members.insert(name.clone(), kind);
+            }
+        }
+        let prefix = format!("{}::", path);
+        for def in &document.definitions {
+            if let Some(name) = def.name.strip_prefix(&prefix) && !name.contains("::") {
+                members.insert(name.to_string(), def.kind);
+            }
+        }
+        members
+    }
+
+    fn completion(&self, params: &Value) -> Value {
+        let Some((document, line, character)) = self.position(params) else {
+            return json!([]);
+        };
+        let (qualifier, names) = match namespace_at(&document.text, line, character) {
+            Some(path) => {
+                let members = self.members(document, &path);
+                (format!("{}::", path), members)
+            }
+            None => {
+                let mut names = BTreeMap::new();
+                for name in self.env.borrow().names() {
+                    let kind = if is_builtin_namespace(&name) { KIND_MODULE } else { KIND_VARIABLE };
+                    names.insert(name, kind);
+                }
+                for name in crate::builtins::native::names() {
+                    if compat::replacement_for(name).is_none() {
+                        names.insert(name.to_string(), KIND_FUNCTION);
+                    }
+                }
+                for def in &document.definitions {
+                    if !def.name.contains("::") {
+                        names.insert(def.name.clone(), def.kind);
+                    }
+                }
+                (String::new(), names)
+            }
+        };
+        let items: Vec<Value> = names
+            .into_iter()
+            .map(|(name, kind)| {
+                let mut item = json!({ "label": name, "kind": kind });
+                if let Some(doc) = builtin_doc(&format!("{}{}", qualifier, name)) {
+                    item["documentation"] = json!({ "kind": "markdown", "value": doc });
+                }
+                item
+            })
+            .collect();
+        json!(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::io::{Cursor, Write};
+    use std::rc::Rc;
+
+    use serde_json::{Value, json};
+
+    #[derive(Clone, Default)]
+    struct Output(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Output {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    const URI: &str = "file:///tmp/shapes.sl";
+
+    const SCRIPT: &str = "namespace Geometry {\n    function area(w, h) {\n        w * h;\n    }\n}\nlet size = Geometry::area(2, 3);\nArray::map([size], fn(x) { x; });\n";
+
+    /// Open `SCRIPT`, send `requests` (as `[method, params]`) with ids from 1
+    /// and return every message sent back.
+    fn session(requests: Vec<(&str, Value)>) -> Vec<Value> {
+        let mut messages = vec![
+            json!({ "jsonrpc": "2.0", "id": 0, "method": "initialize", "params": {} }),
+            json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }),
+            json!({ "jsonrpc": "2.0", "method": "textDocument/didOpen", "params": {
+                "textDocument": { "uri": URI, "languageId": "slang", "version": 1, "text": SCRIPT } } }),
+        ];
+        for (id, (method, params)) in requests.into_iter().enumerate() {
+            messages.push(json!({ "jsonrpc": "2.0", "id": id + 1, "method": method, "params": params }));
+        }
+        messages.push(json!({ "jsonrpc": "2.0", "id": 99, "method": "shutdown" }));
+        messages.push(json!({ "jsonrpc": "2.0", "method": "exit" }));
+
+        let mut input = String::new();
+        for message in messages {
+            let body = message.to_string();
+            input.push_str(&format!("Content-Length: {}\r\n\r\n{}", body.len(), body));
+        }
+        let output = Output::default();
+        super::serve(Cursor::new(input), output.clone()).unwrap();
+        let text = String::from_utf8(output.0.borrow().clone()).unwrap();
+        text.split("Content-Length: ")
+            .skip(1)
+            .map(|m| serde_json::from_str(m.split_once("\r\n\r\n").unwrap().1).unwrap())
+            .collect()
+    }
+
+    fn result(messages: &[Value], id: u64) -> &Value {
+        let reply = messages
+            .iter()
+            .find(|m| m["id"] == id)
+            .unwrap_or_else(|| panic!("no reply to {} in {:#?}", id, messages));
+        &reply["result"]
+    }
+
+    fn at(line: u64, character: u64) -> Value {
+        json!({ "textDocument": { "uri": URI }, "position": { "line": line, "character": character } })
+    }
+
+    #[test]
+    fn publishes_parse_errors_as_diagnostics() {
+        let change = |version: u64, text: &str| {
+            json!({ "textDocument": { "uri": URI, "version": version }, "contentChanges": [{ "text": text }] })
+        };
+        let messages = session(vec![
+            ("textDocument/didChange", change(2, "let a = 1;\nlet = 2;\n")),
+            ("textDocument/didChange", change(3, "let a = 1;\nlet unused = 2;\nprint(a);\n")),
+        ]);
+        assert_eq!(result(&messages, 0)["capabilities"]["hoverProvider"], true);
+        let published: Vec<&Value> = messages
+            .iter()
+            .filter(|m| m["method"] == "textDocument/publishDiagnostics")
+            .collect();
+        assert_eq!(published.len(), 3);
+        assert_eq!(published[0]["params"]["diagnostics"], json!([]));
+        let diagnostics = published[1]["params"]["diagnostics"].as_array().unwrap();
+        assert!(!diagnostics.is_empty());
+        assert_eq!(diagnostics[0]["range"]["start"]["line"], 1);
+        assert_eq!(diagnostics[0]["severity"], 1);
+        let warnings = &published[2]["params"]["diagnostics"];
+        assert_eq!(warnings[0]["message"], "`unused` is never used");
+        assert_eq!(warnings[0]["range"]["start"]["line"], 1);
+        assert_eq!(warnings[0]["severity"], 2);
+    }
+
+    #[test]
+    fn finds_definitions_inside_namespaces() {
+        let messages = session(vec![
+            ("textDocument/definition", at(5, 24)),
+            ("textDocument/definition", at(5, 14)),
+            ("textDocument/definition", at(6, 15)),
+            ("textDocument/definition", at(6, 3)),
+        ]);
+        assert_eq!(result(&messages, 1)["range"]["start"], json!({ "line": 1, "character": 13 }));
+        assert_eq!(result(&messages, 2)["range"]["start"], json!({ "line": 0, "character": 10 }));
+        assert_eq!(result(&messages, 3)["range"]["start"], json!({ "line": 5, "character": 4 }));
+        assert_eq!(result(&messages, 4), &Value::Null);
+    }
+
+    #[test]
+    fn hovers_with_declarations_and_builtin_docs() {
+        let messages = session(vec![
+            ("textDocument/hover", at(5, 24)),
+            ("textDocument/hover", at(6, 8)),
+            ("textDocument/hover", at(6, 2)),
+        ]);
+        assert_eq!(result(&messages, 1)["contents"]["value"], "```slang\nfunction area(w, h)\n```");
+        let map = result(&messages, 2)["contents"]["value"].as_str().unwrap();
+        assert!(map.starts_with("`Array::map(arr, fn)` – returns a new array"), "{}", map);
+        let array = result(&messages, 3)["contents"]["value"].as_str().unwrap();
+        assert!(array.starts_with("`Array` namespace: ") && array.contains("flatMap"), "{}", array);
+    }
+
+    #[test]
+    fn completes_namespace_members() {
+        let messages = session(vec![
+            ("textDocument/completion", at(6, 7)),
+            ("textDocument/completion", at(5, 22)),
+            ("textDocument/completion", at(5, 0)),
+        ]);
+        let labels = |id: u64| -> Vec<String> {
+            result(&messages, id)
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|item| item["label"].as_str().unwrap().to_string())
+                .collect()
+        };
+        let array = result(&messages, 1).as_array().unwrap();
+        let map = array.iter().find(|item| item["label"] == "map").unwrap();
+        assert_eq!(map["kind"], 3);
+        assert!(map["documentation"]["value"].as_str().unwrap().contains("Array::map"));
+        assert_eq!(labels(2), vec!["area"]);
+        let top = labels(3);
+        assert!(top.iter().any(|l| l == "Array") && top.iter().any(|l| l == "Geometry"), "{:?}", top);
+        assert!(top.iter().any(|l| l == "len") && !top.iter().any(|l| l == "first"), "{:?}", top);
+    }
+
+    #[test]
+    fn counts_positions_in_utf16_code_units() {
+        let change = json!({
+            "textDocument": { "uri": URI, "version": 2 },
+            "contentChanges": [{ "text": "let s = \"😀\"; let total = 1;\nlet t = \"😀😀\"; total;\n" }],
+        });
+        let messages = session(vec![
+            ("textDocument/didChange", change),
+            // The `l` of `total`: character 18, but UTF-16 offset 20.
+            ("textDocument/definition", at(1, 20)),
+        ]);
+        assert_eq!(
+            result(&messages, 2)["range"],
+            json!({ "start": { "line": 0, "character": 18 }, "end": { "line": 0, "character": 23 } })
+        );
+    }
+}
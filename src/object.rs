@@ -1,3 +1,6 @@
+pub mod bloom;
+pub mod convert;
 pub mod types;
 
-pub use types::Object;
\ No newline at end of file
+pub use convert::{ConversionError, FromSlang, ToSlang, from_slang, to_slang};
+pub use types::{FunctionData, NativeFn, Object, ObjectMap};
\ No newline at end of file
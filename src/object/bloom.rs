@@ -0,0 +1,103 @@
+//! Bloom filter backing the `Bloom` namespace.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// A fixed-size set of bits answering "possibly seen" / "definitely not seen".
+///
+/// The size is picked up front from the expected number of items and the
+/// acceptable false-positive rate, so memory use never grows with the input.
+#[derive(Debug)]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u32,
+}
+
+impl BloomFilter {
+    /// Size a filter for `expected_items` at `false_positive_rate` (0 < rate < 1).
+    pub fn with_rate(expected_items: usize, false_positive_rate: f64) -> Self {
+        let n = expected_items.max(1) as f64;
+        let ln2 = std::f64::consts::LN_2;
+        let num_bits = (-n * false_positive_rate.ln() / (ln2 * ln2)).ceil().max(64.0) as u64;
+        let num_hashes = ((num_bits as f64 / n) * ln2).round().max(1.0) as u32;
+        BloomFilter {
+            bits: vec![0; num_bits.div_ceil(64) as usize],
+            num_bits,
+            num_hashes,
+        }
+    }
+
+    pub fn num_bits(&self) -> u64 {
+        self.num_bits
+    }
+
+    pub fn num_hashes(&self) -> u32 {
+        self.num_hashes
+    }
+
+    /// Add `key`, returning true if it was definitely not present before.
+    pub fn insert(&mut self, key: &str) -> bool {
+        let mut added = false;
+        let indexes: Vec<u64> = self.bit_indexes(key).collect();
+        for bit in indexes {
+            let (word, mask) = ((bit / 64) as usize, 1u64 << (bit % 64));
+            if self.bits[word] & mask == 0 {
+                self.bits[word] |= mask;
+                added = true;
+            }
+        }
+        added
+    }
+
+    /// False means `key` was never added; true means it probably was.
+    pub fn contains(&self, key: &str) -> bool {
+        self.bit_indexes(key)
+            .all(|bit| self.bits[(bit / 64) as usize] & (1u64 << (bit % 64)) != 0)
+    }
+
+    /// The `num_hashes` bit positions for `key`, derived from two base hashes
+    /// (Kirsch-Mitzenmacher double hashing).
+    fn bit_indexes(&self, key: &str) -> impl Iterator<Item = u64> + '_ {
+        let h1 = seeded_hash(key, 0);
+        let h2 = seeded_hash(key, 1) | 1;
+        (0..self.num_hashes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % self.num_bits)
+    }
+}
+
+fn seeded_hash(key: &str, seed: u64) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BloomFilter;
+
+    #[test]
+    fn added_keys_are_always_found() {
+        let mut filter = BloomFilter::with_rate(1000, 0.01);
+        for i in 0..1000 {
+            assert!(filter.insert(&format!("key-{}", i)));
+        }
+        assert!((0..1000).all(|i| filter.contains(&format!("key-{}", i))));
+        assert!(!filter.insert("key-7"));
+    }
+
+    #[test]
+    fn false_positive_rate_stays_near_target() {
+        let mut filter = BloomFilter::with_rate(1000, 0.01);
+        for i in 0..1000 {
+            filter.insert(&format!("key-{}", i));
+        }
+        let false_positives = (0..10_000)
+            .filter(|i| filter.contains(&format!("other-{}", i)))
+            .count();
+        // 1% of 10,000 is 100; leave room for variance.
+        assert!(false_positives < 250, "{} false positives", false_positives);
+        assert_eq!(filter.num_hashes(), 7);
+        assert!(filter.num_bits() >= 9585);
+    }
+}
@@ -0,0 +1,346 @@
+//! Conversions between Rust values and Slang [`Object`]s for embedders.
+//!
+//! [`ToSlang`] turns a Rust value into an `Object` and [`FromSlang`] checks
+//! and unpacks one, so host functions don't have to match on variants by
+//! hand. Types that implement serde's traits can go through [`to_slang`] and
+//! [`from_slang`] instead.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::BuildHasher;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use super::Object;
+
+/// Why an `Object` couldn't be converted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConversionError {
+    /// The value had the wrong Slang type.
+    TypeMismatch { expected: &'static str, found: &'static str },
+    /// An integer didn't fit the target Rust type.
+    OutOfRange { value: i64, target: &'static str },
+    /// serde rejected the value.
+    Serde(String),
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConversionError::TypeMismatch { expected, found } => {
+                write!(f, "expected {}, found {}", expected, found)
+            }
+            ConversionError::OutOfRange { value, target } => {
+                write!(f, "{} does not fit in {}", value, target)
+            }
+            ConversionError::Serde(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+fn mismatch(expected: &'static str, found: &Object) -> ConversionError {
+    ConversionError::TypeMismatch { expected, found: found.type_name() }
+}
+
+/// A Rust value that can be handed to scripts.
+pub trait ToSlang {
+    fn to_slang(self) -> Object;
+}
+
+/// A Rust value that can be read back out of a script value.
+pub trait FromSlang: Sized {
+    fn from_slang(obj: Object) -> Result<Self, ConversionError>;
+}
+
+impl ToSlang for Object {
+    fn to_slang(self) -> Object {
+        self
+    }
+}
+
+impl FromSlang for Object {
+    fn from_slang(obj: Object) -> Result<Self, ConversionError> {
+        Ok(obj)
+    }
+}
+
+impl ToSlang for () {
+    fn to_slang(self) -> Object {
+        Object::Null
+    }
+}
+
+impl ToSlang for bool {
+    fn to_slang(self) -> Object {
+        Object::Boolean(self)
+    }
+}
+
+impl FromSlang for bool {
+    fn from_slang(obj: Object) -> Result<Self, ConversionError> {
+        match obj {
+            Object::Boolean(b) => Ok(b),
+            other => Err(mismatch("boolean", &other)),
+        }
+    }
+}
+
+impl ToSlang for i64 {
+    fn to_slang(self) -> Object {
+        Object::Integer(self)
+    }
+}
+
+impl FromSlang for i64 {
+    fn from_slang(obj: Object) -> Result<Self, ConversionError> {
+        match obj {
+            Object::Integer(i) => Ok(i),
+            other => Err(mismatch("integer", &other)),
+        }
+    }
+}
+
+impl ToSlang for i32 {
+    fn to_slang(self) -> Object {
+        Object::Integer(self.into())
+    }
+}
+
+impl FromSlang for i32 {
+    fn from_slang(obj: Object) -> Result<Self, ConversionError> {
+        let value = i64::from_slang(obj)?;
+        i32::try_from(value).map_err(|_| ConversionError::OutOfRange { value, target: "i32" })
+    }
+}
+
+impl FromSlang for usize {
+    fn from_slang(obj: Object) -> Result<Self, ConversionError> {
+        let value = i64::from_slang(obj)?;
+        usize::try_from(value).map_err(|_| ConversionError::OutOfRange { value, target: "usize" })
+    }
+}
+
+impl ToSlang for f64 {
+    fn to_slang(self) -> Object {
+        Object::Float(self)
+    }
+}
+
+/// Integers widen to floats, as they do in Slang arithmetic.
+impl FromSlang for f64 {
+    fn from_slang(obj: Object) -> Result<Self, ConversionError> {
+        match obj {
+            Object::Float(f) => Ok(f),
+            Object::Integer(i) => Ok(i as f64),
+            other => Err(mismatch("float", &other)),
+        }
+    }
+}
+
+impl ToSlang for String {
+    fn to_slang(self) -> Object {
+        Object::String(self)
+    }
+}
+
+impl ToSlang for &str {
+    fn to_slang(self) -> Object {
+        Object::String(self.to_string())
+    }
+}
+
+impl FromSlang for String {
+    fn from_slang(obj: Object) -> Result<Self, ConversionError> {
+        match obj {
+            Object::String(s) => Ok(s),
+            other => Err(mismatch("string", &other)),
+        }
+    }
+}
+
+impl<T: ToSlang> ToSlang for Vec<T> {
+    fn to_slang(self) -> Object {
+        Object::array(self.into_iter().map(ToSlang::to_slang).collect())
+    }
+}
+
+impl<T: FromSlang> FromSlang for Vec<T> {
+    fn from_slang(obj: Object) -> Result<Self, ConversionError> {
+        match obj {
+            Object::Array(items) => {
+                std::rc::Rc::unwrap_or_clone(items).into_iter().map(T::from_slang).collect()
+            }
+            other => Err(mismatch("array", &other)),
+        }
+    }
+}
+
+impl<T: ToSlang, S: BuildHasher> ToSlang for HashMap<String, T, S> {
+    fn to_slang(self) -> Object {
+        Object::from_map(self.into_iter().map(|(k, v)| (k, v.to_slang())).collect())
+    }
+}
+
+impl<T: FromSlang, S: BuildHasher + Default> FromSlang for HashMap<String, T, S> {
+    fn from_slang(obj: Object) -> Result<Self, ConversionError> {
+        match obj {
+            Object::Object(map) => std::rc::Rc::unwrap_or_clone(map)
+                .into_iter()
+                .map(|(k, v)| Ok((k, T::from_slang(v)?)))
+                .collect(),
+            other => Err(mismatch("object", &other)),
+        }
+    }
+}
+
+/// `Some`/`None` become `Option::Some(v)`/`Option::None()`.
+impl<T: ToSlang> ToSlang for Option<T> {
+    fn to_slang(self) -> Object {
+        match self {
+            Some(v) => Object::OptionSome(Box::new(v.to_slang())),
+            None => Object::OptionNone,
+        }
+    }
+}
+
+/// `null` is read as `None` too, so optional arguments can be omitted.
+impl<T: FromSlang> FromSlang for Option<T> {
+    fn from_slang(obj: Object) -> Result<Self, ConversionError> {
+        match obj {
+            Object::OptionSome(v) => Ok(Some(T::from_slang(*v)?)),
+            Object::OptionNone | Object::Null => Ok(None),
+            other => Err(mismatch("option", &other)),
+        }
+    }
+}
+
+impl<T: ToSlang, E: ToSlang> ToSlang for Result<T, E> {
+    fn to_slang(self) -> Object {
+        match self {
+            Ok(v) => Object::ResultOk(Box::new(v.to_slang())),
+            Err(e) => Object::ResultErr(Box::new(e.to_slang())),
+        }
+    }
+}
+
+impl<T: FromSlang, E: FromSlang> FromSlang for Result<T, E> {
+    fn from_slang(obj: Object) -> Result<Self, ConversionError> {
+        match obj {
+            Object::ResultOk(v) => Ok(Ok(T::from_slang(*v)?)),
+            Object::ResultErr(e) => Ok(Err(E::from_slang(*e)?)),
+            other => Err(mismatch("result", &other)),
+        }
+    }
+}
+
+/// Convert any serde-serializable value, going through its JSON shape:
+/// structs and maps become objects, sequences arrays and unit `null`.
+pub fn to_slang<T: Serialize>(value: &T) -> Result<Object, ConversionError> {
+    serde_json::to_value(value)
+        .map(|json| json_to_object(&json))
+        .map_err(|e| ConversionError::Serde(e.to_string()))
+}
+
+/// Deserialize a script value into any serde type. Only JSON-shaped values
+/// (null, booleans, numbers, strings, arrays and objects) can be read.
+pub fn from_slang<T: DeserializeOwned>(obj: &Object) -> Result<T, ConversionError> {
+    let json = object_to_json(obj)?;
+    serde_json::from_value(json).map_err(|e| ConversionError::Serde(e.to_string()))
+}
+
+pub(crate) fn json_to_object(v: &serde_json::Value) -> Object {
+    use serde_json::Value;
+
+    match v {
+        Value::Null => Object::Null,
+        Value::Bool(b) => Object::Boolean(*b),
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Object::Integer(i)
+            } else if let Some(f) = n.as_f64() {
+                Object::Float(f)
+            } else {
+                Object::Null
+            }
+        }
+        Value::String(s) => Object::String(s.clone()),
+        Value::Array(arr) => Object::array(arr.iter().map(json_to_object).collect()),
+        Value::Object(map) => {
+            Object::from_map(map.iter().map(|(k, v)| (k.clone(), json_to_object(v))).collect())
+        }
+    }
+}
+
+fn object_to_json(obj: &Object) -> Result<serde_json::Value, ConversionError> {
+    use serde_json::Value;
+
+    Ok(match obj {
+        Object::Null => Value::Null,
+        Object::Boolean(b) => Value::Bool(*b),
+        Object::Integer(i) => Value::from(*i),
+        Object::Float(f) => serde_json::Number::from_f64(*f)
+            .map(Value::Number)
+            .ok_or_else(|| ConversionError::Serde(format!("{} has no JSON form", f)))?,
+        Object::String(s) => Value::String(s.clone()),
+        Object::Array(items) => {
+            Value::Array(items.iter().map(object_to_json).collect::<Result<_, _>>()?)
+        }
+        Object::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| Ok((k.clone(), object_to_json(v)?)))
+                .collect::<Result<_, _>>()?,
+        ),
+        other => return Err(mismatch("a JSON-like value", other)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[test]
+    fn round_trips_nested_std_types() {
+        let mut scores = HashMap::new();
+        scores.insert("ada".to_string(), vec![Some(3_i64), None]);
+        let obj = scores.clone().to_slang();
+        assert_eq!(HashMap::<String, Vec<Option<i64>>>::from_slang(obj), Ok(scores));
+
+        let res: Result<i64, String> = Err("nope".to_string());
+        assert_eq!(res.clone().to_slang().to_string(), "Err(\"nope\")");
+        assert_eq!(Result::<i64, String>::from_slang(res.to_slang()), Ok(Err("nope".into())));
+        assert_eq!(f64::from_slang(Object::Integer(2)), Ok(2.0));
+        assert_eq!(Option::<bool>::from_slang(Object::Null), Ok(None));
+    }
+
+    #[test]
+    fn reports_mismatches_and_range_errors() {
+        let err = Vec::<i64>::from_slang(vec!["a"].to_slang()).unwrap_err();
+        assert_eq!(err.to_string(), "expected integer, found string");
+        let err = i32::from_slang(Object::Integer(1 << 40)).unwrap_err();
+        assert_eq!(err.to_string(), "1099511627776 does not fit in i32");
+        assert!(usize::from_slang(Object::Integer(-1)).is_err());
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Player {
+        name: String,
+        level: u8,
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn serde_types_go_through_json_shapes() {
+        let player = Player { name: "ada".into(), level: 7, tags: vec!["mage".into()] };
+        let obj = to_slang(&player).unwrap();
+        assert_eq!(obj.type_name(), "object");
+        assert_eq!(from_slang::<Player>(&obj), Ok(player));
+
+        let err = from_slang::<Player>(&Object::Integer(1)).unwrap_err();
+        assert!(matches!(err, ConversionError::Serde(_)), "{:?}", err);
+        assert!(from_slang::<Vec<i64>>(&Object::OptionNone).is_err());
+    }
+}
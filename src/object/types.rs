@@ -1,11 +1,29 @@
-use crate::ast::{BlockStatement, Identifier};
+use crate::ast::nodes::quote_bytes;
+use crate::ast::{BlockStatement, Expression, Identifier};
+use crate::object::bloom::BloomFilter;
 use crate::env::EnvRef;
 use std::cell::RefCell;
-use std::collections::HashMap;
 use std::fmt::{self, Display, Formatter};
 use std::fs::File;
 use std::rc::Rc;
 
+use indexmap::IndexMap;
+
+/// The fields of an object, kept in the order they were first set so that
+/// printing, `Obj::keys` and `Json::stringify` are deterministic.
+pub type ObjectMap = IndexMap<String, Object>;
+
+/// A Slang runtime value.
+///
+/// Strings, arrays and objects have value semantics: assigning one or
+/// passing it to a function hands over a copy, so the callee can't change
+/// the caller's value. Sharing mutable state goes through an explicit
+/// [`Object::Ref`] handle instead (`Ref::new`, `Array::pushMut`,
+/// `Object::setMut`), which is shared by reference like file handles.
+///
+/// Arrays and objects are `Rc`-backed and copy-on-write, so those copies are
+/// cheap: cloning shares the storage, and it is only duplicated when a
+/// shared value is modified (see [`Rc::make_mut`]).
 #[derive(Debug, Clone)]
 pub enum Object {
     // Primitive scalar types
@@ -13,23 +31,24 @@ pub enum Object {
     Float(f64),
     Boolean(bool),
     String(String),
+    /// Raw bytes, from `b"..."` literals and binary-mode file reads.
+    Bytes(Rc<Vec<u8>>),
 
-    // Compound data structures
-    Array(Vec<Object>),
-    Object(HashMap<String, Object>),
+    // Compound data structures (copy-on-write)
+    Array(Rc<Vec<Object>>),
+    Object(Rc<ObjectMap>),
 
     // Functions (user-defined and native)
-    Function {
-        params: Vec<Identifier>,
-        body: BlockStatement,
-        env: EnvRef,
-    },
+    /// A user-defined function; copies share the definition.
+    Function(Rc<FunctionData>),
     Builtin(BuiltinFunction),
+    /// A host function that can capture state, registered by an embedder.
+    NativeFn(NativeFn),
 
     // Classes
     Class {
         name: String,
-        methods: HashMap<String, Object>,
+        methods: ObjectMap,
     },
 
     // Control-flow / special runtime values
@@ -38,6 +57,13 @@ pub enum Object {
     // IO
     File(FileRef),
 
+    // Native data structures, shared by reference like file handles
+    Bloom(BloomRef),
+    /// A mutable cell holding any value; copies of a ref share the cell.
+    Ref(SharedRef),
+    /// A lazy sequence built by the `Seq` namespace.
+    Seq(SeqRef),
+
     // Error handling
     Error(String),
 
@@ -54,35 +80,274 @@ pub enum Object {
     Null,
 }
 
+/// A user-defined function: its signature, body and the environment it
+/// closes over.
+pub struct FunctionData {
+    /// Declared name (`Class.method` for methods); `None` for function literals.
+    pub name: Option<String>,
+    pub params: Vec<Identifier>,
+    pub defaults: Vec<Option<Expression>>,
+    pub rest: Option<Identifier>,
+    pub requires: Vec<Expression>,
+    pub ensures: Vec<Expression>,
+    pub body: BlockStatement,
+    pub env: EnvRef,
+    /// Declared with `fn*`: calling it returns a seq of yielded values.
+    pub generator: bool,
+}
+
+// The closure environment usually binds the function itself, so it is left
+// out; printing it would recurse until the stack overflows.
+impl fmt::Debug for FunctionData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let params: Vec<&str> = self.params.iter().map(|p| p.value.as_str()).collect();
+        f.debug_struct("FunctionData")
+            .field("name", &self.name)
+            .field("params", &params)
+            .field("generator", &self.generator)
+            .finish_non_exhaustive()
+    }
+}
+
 /// Native builtin function type. Builtins receive the evaluated argument list
 /// and the calling environment, so they can (optionally) call back into the
 /// evaluator via higher-order helpers.
 pub type BuiltinFunction = fn(Vec<Object>, EnvRef) -> Object;
 
+/// A native function that closes over host state (a database handle, a game
+/// world, ...), unlike [`BuiltinFunction`] which is a plain `fn` pointer.
+/// Copies share the same closure.
+#[derive(Clone)]
+pub struct NativeFn {
+    name: Rc<str>,
+    func: Rc<dyn Fn(Vec<Object>, EnvRef) -> Object>,
+}
+
+impl NativeFn {
+    pub fn new(name: &str, func: impl Fn(Vec<Object>, EnvRef) -> Object + 'static) -> Self {
+        NativeFn { name: Rc::from(name), func: Rc::new(func) }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn call(&self, args: Vec<Object>, env: EnvRef) -> Object {
+        (self.func)(args, env)
+    }
+}
+
+impl fmt::Debug for NativeFn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "NativeFn({})", self.name)
+    }
+}
+
+pub type BloomRef = Rc<RefCell<BloomFilter>>;
+
+pub type SeqRef = Rc<SeqNode>;
+
+/// A lazy sequence: a source plus the adapters stacked on it. Nothing runs
+/// until the sequence is consumed, and every consumption starts again from
+/// the source, so a sequence value can be shared and reused.
+#[derive(Debug)]
+pub enum SeqNode {
+    Array(Rc<Vec<Object>>),
+    /// Integers from `start` by `step`, stopping before `end` (unbounded when
+    /// `end` is `None`).
+    Range { start: i64, end: Option<i64>, step: i64 },
+    Map(SeqRef, Object),
+    Filter(SeqRef, Object),
+    Take(SeqRef, usize),
+    Drop(SeqRef, usize),
+    TakeWhile(SeqRef, Object),
+    DropWhile(SeqRef, Object),
+    Enumerate(SeqRef),
+    /// `[x, y, ...]` taking x from the first array, y from the second and so
+    /// on, the last array varying fastest.
+    Product(Vec<Rc<Vec<Object>>>),
+    /// Every `k`-element selection of the items, each in the items' order.
+    Combinations(Rc<Vec<Object>>, usize),
+    /// Every ordering of `k` of the items, in lexicographic order of position.
+    Permutations(Rc<Vec<Object>>, usize),
+    /// The body of a generator function (`fn*`), with its arguments bound in
+    /// `env`. Each consumption runs the body afresh in a scope of its own.
+    Generator { body: Rc<BlockStatement>, env: EnvRef },
+}
+
+pub type SharedRef = Rc<RefCell<Object>>;
+
 pub type FileRef = Rc<RefCell<FileHandle>>;
 #[derive(Debug)]
 pub struct FileHandle {
     pub inner: Option<File>,
+    /// Set for handles opened for writing while dry-run mode is active.
+    /// Writes are logged instead of performed, and `inner` (if present) is a
+    /// read-only view of the existing file.
+    pub dry_run: bool,
+    /// Opened with a `b` mode: reads return bytes instead of strings.
+    pub binary: bool,
+    /// How text-mode reads decode and string writes encode.
+    pub encoding: TextEncoding,
 }
 
 impl FileHandle {
     pub fn new(f: File) -> Self {
-        Self { inner: Some(f) }
+        Self { inner: Some(f), dry_run: false, binary: false, encoding: TextEncoding::Utf8 }
+    }
+
+    pub fn new_dry_run(f: Option<File>) -> Self {
+        Self { inner: f, dry_run: true, binary: false, encoding: TextEncoding::Utf8 }
     }
 
     pub fn is_closed(&self) -> bool {
-        self.inner.is_none()
+        self.inner.is_none() && !self.dry_run
     }
 }
 
+/// Text encodings accepted by `File::open(path, mode, { encoding })`.
+/// Conversions fail on data the encoding can't represent rather than
+/// substituting replacement characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    Utf8,
+    Latin1,
+    /// Little-endian unless the data starts with a big-endian byte order mark.
+    Utf16,
+    Utf16Le,
+    Utf16Be,
+}
+
+impl TextEncoding {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "utf-8" | "utf8" => Some(TextEncoding::Utf8),
+            "latin-1" | "latin1" | "iso-8859-1" => Some(TextEncoding::Latin1),
+            "utf-16" | "utf16" => Some(TextEncoding::Utf16),
+            "utf-16le" => Some(TextEncoding::Utf16Le),
+            "utf-16be" => Some(TextEncoding::Utf16Be),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "utf-8",
+            TextEncoding::Latin1 => "latin-1",
+            TextEncoding::Utf16 => "utf-16",
+            TextEncoding::Utf16Le => "utf-16le",
+            TextEncoding::Utf16Be => "utf-16be",
+        }
+    }
+
+    pub fn decode(self, bytes: Vec<u8>) -> Result<String, String> {
+        match self {
+            TextEncoding::Utf8 => String::from_utf8(bytes)
+                .map_err(|e| format!("failed to decode UTF-8: {}", e)),
+            TextEncoding::Latin1 => Ok(bytes.iter().map(|&b| b as char).collect()),
+            TextEncoding::Utf16 => match bytes.as_slice() {
+                [0xFE, 0xFF, rest @ ..] => decode_utf16(rest, u16::from_be_bytes),
+                [0xFF, 0xFE, rest @ ..] => decode_utf16(rest, u16::from_le_bytes),
+                _ => decode_utf16(&bytes, u16::from_le_bytes),
+            },
+            TextEncoding::Utf16Le => decode_utf16(&bytes, u16::from_le_bytes),
+            TextEncoding::Utf16Be => decode_utf16(&bytes, u16::from_be_bytes),
+        }
+    }
+
+    pub fn encode(self, text: &str) -> Result<Vec<u8>, String> {
+        match self {
+            TextEncoding::Utf8 => Ok(text.as_bytes().to_vec()),
+            TextEncoding::Latin1 => text
+                .chars()
+                .map(|c| {
+                    u8::try_from(c).map_err(|_| {
+                        format!("failed to encode latin-1: {:?} is not representable", c)
+                    })
+                })
+                .collect(),
+            TextEncoding::Utf16 | TextEncoding::Utf16Le => {
+                Ok(text.encode_utf16().flat_map(u16::to_le_bytes).collect())
+            }
+            TextEncoding::Utf16Be => {
+                Ok(text.encode_utf16().flat_map(u16::to_be_bytes).collect())
+            }
+        }
+    }
+}
+
+fn decode_utf16(bytes: &[u8], unit: fn([u8; 2]) -> u16) -> Result<String, String> {
+    if !bytes.len().is_multiple_of(2) {
+        return Err("failed to decode UTF-16: odd number of bytes".to_string());
+    }
+    let units = bytes.chunks_exact(2).map(|pair| unit([pair[0], pair[1]]));
+    char::decode_utf16(units)
+        .collect::<Result<String, _>>()
+        .map_err(|e| format!("failed to decode UTF-16: {}", e))
+}
+
 impl Object {
     pub fn error<S: Into<String>>(msg: S) -> Self {
         Object::Error(msg.into())
     }
 
+    pub fn bytes(bytes: Vec<u8>) -> Self {
+        Object::Bytes(Rc::new(bytes))
+    }
+
+    pub fn array(items: Vec<Object>) -> Self {
+        Object::Array(Rc::new(items))
+    }
+
+    pub fn from_map(map: ObjectMap) -> Self {
+        Object::Object(Rc::new(map))
+    }
+
+    /// Wrap a host closure as a callable value named `name`.
+    pub fn native_fn(
+        name: &str,
+        func: impl Fn(Vec<Object>, EnvRef) -> Object + 'static,
+    ) -> Self {
+        Object::NativeFn(NativeFn::new(name, func))
+    }
+
     pub fn is_error(&self) -> bool {
         matches!(self, Object::Error(_))
     }
+
+    /// True for anything a call expression can invoke: script functions,
+    /// `fn` pointer builtins and native closures.
+    pub fn is_callable(&self) -> bool {
+        matches!(self, Object::Function(_) | Object::Builtin(_) | Object::NativeFn(_))
+    }
+
+    /// The type name reported by `Type::of`.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Object::Integer(_) => "integer",
+            Object::Float(_) => "float",
+            Object::Boolean(_) => "boolean",
+            Object::String(_) => "string",
+            Object::Bytes(_) => "bytes",
+            Object::Array(_) => "array",
+            Object::Object(_) => "object",
+            Object::Function(_) => "function",
+            Object::Builtin(_) => "function",
+            Object::NativeFn(_) => "function",
+            Object::Class { .. } => "class",
+            Object::ReturnValue(_) => "return",
+            Object::File(_) => "file",
+            Object::Bloom(_) => "bloom",
+            Object::Ref(_) => "ref",
+            Object::Seq(_) => "seq",
+            Object::Error(_) => "error",
+            Object::OptionSome(_) => "option",
+            Object::OptionNone => "option",
+            Object::ResultOk(_) => "result",
+            Object::ResultErr(_) => "result",
+            Object::Null => "null",
+        }
+    }
 }
 
 impl PartialEq for Object {
@@ -94,16 +359,21 @@ impl PartialEq for Object {
             (Float(a), Float(b)) => a == b,
             (Boolean(a), Boolean(b)) => a == b,
             (String(a), String(b)) => a == b,
+            (Bytes(a), Bytes(b)) => a == b,
             (Array(a), Array(b)) => a == b,
             (Object(a), Object(b)) => a == b,
             // Functions and builtins are not compared for equality in this interpreter,
             // so we conservatively treat them as unequal (except by identity via reference,
             // which the current code never relies on).
-            (Function { .. }, Function { .. }) => false,
+            (Function(_), Function(_)) => false,
             (Builtin(_), Builtin(_)) => false,
+            (NativeFn(a), NativeFn(b)) => Rc::ptr_eq(&a.func, &b.func),
             (Class { .. }, Class { .. }) => false,
             (ReturnValue(a), ReturnValue(b)) => a == b,
             (File(_), File(_)) => false,
+            (Bloom(a), Bloom(b)) => Rc::ptr_eq(a, b),
+            (Ref(a), Ref(b)) => Rc::ptr_eq(a, b),
+            (Seq(a), Seq(b)) => Rc::ptr_eq(a, b),
             (Error(a), Error(b)) => a == b,
             (OptionSome(a), OptionSome(b)) => a == b,
             (OptionNone, OptionNone) => true,
@@ -122,6 +392,7 @@ impl Display for Object {
             Object::Float(x) => write!(f, "{}", x),
             Object::Boolean(b) => write!(f, "{}", if *b { "true" } else { "false" }),
             Object::String(s) => write!(f, "\"{}\"", s),
+            Object::Bytes(bytes) => write!(f, "{}", quote_bytes(bytes)),
             Object::Array(elements) => {
                 let inner = elements
                     .iter()
@@ -132,16 +403,23 @@ impl Display for Object {
             }
             Object::Object(map) => {
                 let mut parts = Vec::with_capacity(map.len());
-                for (k, v) in map {
+                for (k, v) in map.iter() {
                     parts.push(format!("{}: {}", k, v));
                 }
                 write!(f, "{{{}}}", parts.join(", "))
             }
-            Object::Function { .. } => write!(f, "<user fn>"),
+            Object::Function(_) => write!(f, "<user fn>"),
             Object::Builtin(_) => write!(f, "<native fn>"),
+            Object::NativeFn(closure) => write!(f, "<native fn {}>", closure.name),
             Object::Class { name, .. } => write!(f, "<class {}>", name),
-            Object::ReturnValue(obj) => write!(f, "{}", obj.to_string()),
+            Object::ReturnValue(obj) => write!(f, "{}", obj),
             Object::File(_) => write!(f, "<file>"),
+            Object::Bloom(filter) => {
+                let filter = filter.borrow();
+                write!(f, "<bloom {} bits, {} hashes>", filter.num_bits(), filter.num_hashes())
+            }
+            Object::Ref(cell) => write!(f, "Ref({})", cell.borrow()),
+            Object::Seq(_) => write!(f, "<seq>"),
             Object::Error(msg) => write!(f, "{}", msg),
             Object::OptionSome(inner) => write!(f, "Some({})", inner),
             Object::OptionNone => write!(f, "None"),
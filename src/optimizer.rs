@@ -0,0 +1,3 @@
+pub mod core;
+
+pub use core::optimize;
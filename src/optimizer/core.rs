@@ -0,0 +1,563 @@
+//! A pass over the parsed program that runs before evaluation:
+//!
+//! * arithmetic, comparisons and string concatenation on literals are
+//!   folded into a single literal, using the evaluator itself so the result
+//!   is exactly what running the code would give (anything that would fail,
+//!   like `"a" - 1`, is left for the evaluator to report);
+//! * `if (true)` and `if (false)` keep only the branch that would run;
+//! * builtin namespace functions, e.g. `Array::map`, are looked up once here
+//!   instead of on every evaluation, provided the script never binds a name
+//!   that could hide the namespace.
+//!
+//! The pass assumes the builtin namespaces are the stock ones, so hosts that
+//! replace one with [`crate::env::EnvBuilder::register_namespace`] shouldn't
+//! use it. `requires`/`ensures` clauses are left as written, since contract
+//! failures quote them.
+
+use std::collections::{HashMap, HashSet};
+use std::mem;
+
+use crate::ast::nodes::{BooleanLiteral, BuiltinRef, FloatLiteral, PostfixOp, StringLiteral};
+use crate::ast::{
+    BlockStatement, Expression, ExpressionStatement, FunctionLiteral, InfixOp, IntegerLiteral,
+    PrefixOp, Program, Statement,
+};
+use crate::env::core::builtin_namespace_members;
+use crate::env::{Environment, is_builtin_namespace};
+use crate::object::{Object, ObjectMap};
+
+/// Optimize `program` in place. Line numbers stay attached to the statements
+/// they came from.
+pub fn optimize(program: &mut Program) {
+    let mut scan = Scan::default();
+    scan.statements(&program.statements);
+    let mut optimizer = Optimizer {
+        scan,
+        namespaces: HashMap::new(),
+    };
+    optimizer.block(&mut program.statements, &mut program.lines);
+}
+
+/// The names a program binds anywhere, which rule out resolving a builtin
+/// namespace of the same name.
+#[derive(Default)]
+struct Scan {
+    bound: HashSet<String>,
+    /// Whether a whole module or namespace is imported, which may bind anything.
+    whole_import: bool,
+}
+
+impl Scan {
+    fn statements(&mut self, statements: &[Statement]) {
+        for stmt in statements {
+            self.statement(stmt);
+        }
+    }
+
+    fn statement(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::Let(ls) => {
+                self.bound.insert(ls.name.value.clone());
+                self.expression(&ls.value);
+            }
+            Statement::Destructure(ds) => {
+                self.bound.extend(ds.pattern.bindings().into_iter().map(|n| n.value));
+                self.expression(&ds.value);
+            }
+            Statement::Return(rs) => self.expression(&rs.return_value),
+            Statement::Yield(ys) => self.expression(&ys.value),
+            Statement::Expression(es) => self.expression(&es.expression),
+            Statement::While(ws) => {
+                self.expression(&ws.condition);
+                self.statements(&ws.body.statements);
+            }
+            Statement::For(fs) => {
+                for part in fs.init.iter().chain(fs.post.iter()) {
+                    self.statement(part);
+                }
+                if let Some(condition) = &fs.condition {
+                    self.expression(condition);
+                }
+                self.statements(&fs.body.statements);
+            }
+            Statement::ForIn(fs) => {
+                self.bound.insert(fs.name.value.clone());
+                self.expression(&fs.iterable);
+                self.statements(&fs.body.statements);
+            }
+            Statement::Switch(ss) => {
+                self.expression(&ss.subject);
+                for case in &ss.cases {
+                    for value in &case.values {
+                        self.expression(value);
+                    }
+                    self.statements(&case.body.statements);
+                }
+                if let Some(default) = &ss.default {
+                    self.statements(&default.statements);
+                }
+            }
+            Statement::Function(fs) => {
+                self.bound.insert(fs.name.value.clone());
+                self.function(&fs.literal);
+            }
+            Statement::Class(cs) => {
+                self.bound.insert(cs.name.value.clone());
+                for method in &cs.methods {
+                    self.function(&method.literal);
+                }
+            }
+            Statement::Namespace(ns) => {
+                self.bound.insert(ns.name.value.clone());
+                self.statements(&ns.body.statements);
+            }
+            Statement::Import(is) => {
+                self.whole_import |= is.names.is_empty();
+                self.bound.extend(is.names.iter().map(|n| n.value.clone()));
+            }
+            Statement::Use(us) => match us.binding() {
+                Some(name) => {
+                    self.bound.insert(name.value.clone());
+                }
+                None => self.whole_import = true,
+            },
+            Statement::Export(es) => {
+                if let Some(declaration) = &es.declaration {
+                    self.statement(declaration);
+                }
+            }
+            Statement::Test(ts) => self.statements(&ts.body.statements),
+            Statement::Describe(ds) => self.statements(&ds.body.statements),
+            Statement::Hook(hs) => self.statements(&hs.body.statements),
+            Statement::Breakpoint => {}
+        }
+    }
+
+    fn function(&mut self, literal: &FunctionLiteral) {
+        self.bound.extend(literal.params.iter().chain(literal.rest.iter()).map(|p| p.value.clone()));
+        for default in literal.defaults.iter().flatten() {
+            self.expression(default);
+        }
+        for clause in literal.requires.iter().chain(&literal.ensures) {
+            self.expression(clause);
+        }
+        self.statements(&literal.body.statements);
+    }
+
+    fn expression(&mut self, expr: &Expression) {
+        match expr {
+            Expression::Infix(ie) => {
+                if let (InfixOp::Assign, Expression::Identifier(name)) = (&ie.operator, &*ie.left) {
+                    self.bound.insert(name.value.clone());
+                }
+                self.expression(&ie.left);
+                self.expression(&ie.right);
+            }
+            Expression::If(ie) => {
+                self.expression(&ie.condition);
+                self.statements(&ie.consequence.statements);
+                if let Some(alternative) = &ie.alternative {
+                    self.statements(&alternative.statements);
+                }
+            }
+            Expression::Prefix(pe) => self.expression(&pe.right),
+            Expression::Postfix(pe) => self.expression(&pe.left),
+            Expression::FunctionLiteral(fl) => self.function(fl),
+            Expression::CallExpression(ce) => {
+                self.expression(&ce.function);
+                for arg in &ce.arguments {
+                    self.expression(arg);
+                }
+            }
+            Expression::ArrayLiteral(al) => {
+                for element in &al.elements {
+                    self.expression(element);
+                }
+            }
+            Expression::IndexExpression(ie) => {
+                self.expression(&ie.left);
+                self.expression(&ie.index);
+            }
+            Expression::ObjectLiteral(ol) => {
+                for (_, value) in &ol.properties {
+                    self.expression(value);
+                }
+            }
+            Expression::PropertyAccess(pa) => self.expression(&pa.object),
+            Expression::Publish(pe) => {
+                for arg in &pe.args {
+                    self.expression(arg);
+                }
+            }
+            Expression::New(ne) => {
+                for arg in &ne.arguments {
+                    self.expression(arg);
+                }
+            }
+            Expression::Spread(se) => self.expression(&se.value),
+            Expression::Identifier(_)
+            | Expression::IntegerLiteral(_)
+            | Expression::BooleanLiteral(_)
+            | Expression::FloatLiteral(_)
+            | Expression::StringLiteral(_)
+            | Expression::BytesLiteral(_)
+            | Expression::NullLiteral
+            | Expression::Builtin(_) => {}
+        }
+    }
+}
+
+struct Optimizer {
+    scan: Scan,
+    /// Members of the builtin namespaces looked up so far.
+    namespaces: HashMap<String, ObjectMap>,
+}
+
+impl Optimizer {
+    /// Optimize a statement list and its parallel `lines`, splicing in the
+    /// surviving branch of any constant `if` statement.
+    fn block(&mut self, statements: &mut Vec<Statement>, lines: &mut Vec<usize>) {
+        let count = statements.len();
+        let old_lines = mem::take(lines);
+        for (index, mut stmt) in mem::take(statements).into_iter().enumerate() {
+            let line = old_lines.get(index).copied();
+            self.statement(&mut stmt);
+            // The last statement's value is the block's, so a constant `if`
+            // there is only dropped when it gives the same value.
+            let last = index + 1 == count;
+            if let Statement::Expression(es) = &mut stmt
+                && let Expression::If(ie) = &mut es.expression
+                && let Some(truth) = constant_truth(&ie.condition)
+            {
+                let branch = if truth { Some(&mut ie.consequence) } else { ie.alternative.as_mut() };
+                match branch {
+                    Some(branch) if !last || !branch.statements.is_empty() => {
+                        statements.append(&mut branch.statements);
+                        if line.is_some() {
+                            lines.append(&mut branch.lines);
+                        }
+                        continue;
+                    }
+                    None if !last => continue,
+                    _ => {}
+                }
+            }
+            statements.push(stmt);
+            if let Some(line) = line {
+                lines.push(line);
+            }
+        }
+    }
+
+    fn block_statement(&mut self, block: &mut BlockStatement) {
+        self.block(&mut block.statements, &mut block.lines);
+    }
+
+    fn statement(&mut self, stmt: &mut Statement) {
+        match stmt {
+            Statement::Let(ls) => self.expression(&mut ls.value),
+            Statement::Destructure(ds) => self.expression(&mut ds.value),
+            Statement::Return(rs) => self.expression(&mut rs.return_value),
+            Statement::Yield(ys) => self.expression(&mut ys.value),
+            Statement::Expression(es) => self.expression(&mut es.expression),
+            Statement::While(ws) => {
+                self.expression(&mut ws.condition);
+                self.block_statement(&mut ws.body);
+            }
+            Statement::For(fs) => {
+                if let Some(init) = &mut fs.init {
+                    self.statement(init);
+                }
+                if let Some(condition) = &mut fs.condition {
+                    self.expression(condition);
+                }
+                if let Some(post) = &mut fs.post {
+                    self.statement(post);
+                }
+                self.block_statement(&mut fs.body);
+            }
+            Statement::ForIn(fs) => {
+                self.expression(&mut fs.iterable);
+                self.block_statement(&mut fs.body);
+            }
+            Statement::Switch(ss) => {
+                self.expression(&mut ss.subject);
+                for case in &mut ss.cases {
+                    for value in &mut case.values {
+                        self.expression(value);
+                    }
+                    self.block_statement(&mut case.body);
+                }
+                if let Some(default) = &mut ss.default {
+                    self.block_statement(default);
+                }
+            }
+            Statement::Function(fs) => self.function(&mut fs.literal),
+            Statement::Class(cs) => {
+                for method in &mut cs.methods {
+                    self.function(&mut method.literal);
+                }
+            }
+            Statement::Namespace(ns) => self.block_statement(&mut ns.body),
+            Statement::Export(es) => {
+                if let Some(declaration) = &mut es.declaration {
+                    self.statement(declaration);
+                }
+            }
+            Statement::Test(ts) => self.block_statement(&mut ts.body),
+            Statement::Describe(ds) => self.block_statement(&mut ds.body),
+            Statement::Hook(hs) => self.block_statement(&mut hs.body),
+            Statement::Import(_) | Statement::Use(_) | Statement::Breakpoint => {}
+        }
+    }
+
+    fn function(&mut self, literal: &mut FunctionLiteral) {
+        for default in literal.defaults.iter_mut().flatten() {
+            self.expression(default);
+        }
+        self.block_statement(&mut literal.body);
+    }
+
+    fn expression(&mut self, expr: &mut Expression) {
+        match expr {
+            Expression::Infix(ie) => {
+                // An assignment target is a place, not a value.
+                if ie.operator != InfixOp::Assign {
+                    self.expression(&mut ie.left);
+                }
+                self.expression(&mut ie.right);
+                if ie.operator != InfixOp::Assign && is_literal(&ie.left) && is_literal(&ie.right)
+                    && let Some(folded) = fold(expr)
+                {
+                    *expr = folded;
+                }
+            }
+            Expression::Prefix(pe) => {
+                if matches!(pe.operator, PrefixOp::PreIncrement | PrefixOp::PreDecrement) {
+                    return;
+                }
+                self.expression(&mut pe.right);
+                if is_literal(&pe.right) && let Some(folded) = fold(expr) {
+                    *expr = folded;
+                }
+            }
+            Expression::If(ie) => {
+                self.expression(&mut ie.condition);
+                self.block_statement(&mut ie.consequence);
+                if let Some(alternative) = &mut ie.alternative {
+                    self.block_statement(alternative);
+                }
+                let mut chosen = None;
+                if let Some(truth) = constant_truth(&ie.condition) {
+                    let branch = if truth { Some(&mut ie.consequence) } else { ie.alternative.as_mut() };
+                    if let Some(branch) = branch {
+                        // Only a single expression can stand in for the `if`.
+                        if let [Statement::Expression(es)] = branch.statements.as_mut_slice() {
+                            let placeholder = Expression::BooleanLiteral(BooleanLiteral { value: false });
+                            chosen = Some(mem::replace(&mut es.expression, placeholder));
+                        }
+                    }
+                }
+                if let Some(chosen) = chosen {
+                    *expr = chosen;
+                }
+            }
+            Expression::FunctionLiteral(fl) => self.function(fl),
+            Expression::CallExpression(ce) => {
+                self.expression(&mut ce.function);
+                for arg in &mut ce.arguments {
+                    self.expression(arg);
+                }
+            }
+            Expression::ArrayLiteral(al) => {
+                for element in &mut al.elements {
+                    self.expression(element);
+                }
+            }
+            Expression::IndexExpression(ie) => {
+                self.expression(&mut ie.left);
+                self.expression(&mut ie.index);
+            }
+            Expression::ObjectLiteral(ol) => {
+                for (_, value) in &mut ol.properties {
+                    self.expression(value);
+                }
+            }
+            Expression::PropertyAccess(pa) => {
+                self.expression(&mut pa.object);
+                if let Some(builtin) = self.resolve(pa.namespaced, &pa.object, &pa.property.value) {
+                    *expr = Expression::Builtin(builtin);
+                }
+            }
+            Expression::Publish(pe) => {
+                for arg in &mut pe.args {
+                    self.expression(arg);
+                }
+            }
+            Expression::New(ne) => {
+                for arg in &mut ne.arguments {
+                    self.expression(arg);
+                }
+            }
+            Expression::Spread(se) => self.expression(&mut se.value),
+            // `x++` and `x--` operands are assignment targets; a `?` operand
+            // is an ordinary value.
+            Expression::Postfix(pe) if pe.operator == PostfixOp::Try => self.expression(&mut pe.left),
+            Expression::Postfix(_)
+            | Expression::Identifier(_)
+            | Expression::IntegerLiteral(_)
+            | Expression::BooleanLiteral(_)
+            | Expression::FloatLiteral(_)
+            | Expression::StringLiteral(_)
+            | Expression::BytesLiteral(_)
+            | Expression::NullLiteral
+            | Expression::Builtin(_) => {}
+        }
+    }
+
+    /// The builtin function `namespace::member`, if the program can't have
+    /// rebound `namespace`.
+    fn resolve(&mut self, namespaced: bool, object: &Expression, member: &str) -> Option<BuiltinRef> {
+        let namespace = match object {
+            Expression::Identifier(ident) if namespaced => &ident.value,
+            _ => return None,
+        };
+        if self.scan.whole_import || self.scan.bound.contains(namespace) || !is_builtin_namespace(namespace) {
+            return None;
+        }
+        let members = self
+            .namespaces
+            .entry(namespace.clone())
+            .or_insert_with(|| builtin_namespace_members(namespace).unwrap_or_default());
+        match members.get(member) {
+            Some(Object::Builtin(func)) => Some(BuiltinRef {
+                namespace: namespace.clone(),
+                member: member.to_string(),
+                func: Some(*func),
+            }),
+            _ => None,
+        }
+    }
+}
+
+fn is_literal(expr: &Expression) -> bool {
+    matches!(
+        expr,
+        Expression::IntegerLiteral(_)
+            | Expression::FloatLiteral(_)
+            | Expression::BooleanLiteral(_)
+            | Expression::StringLiteral(_)
+            | Expression::NullLiteral
+    )
+}
+
+/// The condition's value, if it's a boolean literal.
+fn constant_truth(condition: &Expression) -> Option<bool> {
+    match condition {
+        Expression::BooleanLiteral(bl) => Some(bl.value),
+        _ => None,
+    }
+}
+
+/// Evaluate an expression over literals and turn the result back into a
+/// literal, unless it fails or isn't one. Integer overflow is one of those
+/// failures (the evaluator's arithmetic is checked), so `i64::MAX + 1` stays
+/// as written and only errors if it actually runs.
+fn fold(expr: &Expression) -> Option<Expression> {
+    let program = Program {
+        statements: vec![Statement::Expression(ExpressionStatement { expression: expr.clone() })],
+        lines: Vec::new(),
+    };
+    match crate::evaluator::eval(&program, Environment::new()) {
+        Object::Integer(value) => Some(Expression::IntegerLiteral(IntegerLiteral { value, lexeme: None })),
+        Object::Float(value) => Some(Expression::FloatLiteral(FloatLiteral { value, lexeme: None })),
+        Object::Boolean(value) => Some(Expression::BooleanLiteral(BooleanLiteral { value })),
+        Object::String(value) => Some(Expression::StringLiteral(StringLiteral { value: value.to_string(), lexeme: None })),
+        Object::Null => Some(Expression::NullLiteral),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::new_env;
+    use crate::evaluator::eval;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+
+    fn optimized(source: &str) -> Program {
+        let mut program = Parser::new(Lexer::new(source)).parse_program();
+        optimize(&mut program);
+        program
+    }
+
+    #[test]
+    fn folds_constant_arithmetic_and_concatenation() {
+        let program = optimized("let x = 2 * 3 + 1;\nlet s = \"a\" + \"b\" + \"c\";\nlet y = -(4 - 6) * x;\n");
+        assert_eq!(program.to_string(), "let x = 7;let s = \"abc\";let y = (2 * x);");
+
+        let program = optimized("let z = null ?? 1;\nlet n = null == null;\nlet m = null ?? null;\n");
+        assert_eq!(program.to_string(), "let z = 1;let n = true;let m = null;");
+    }
+
+    #[test]
+    fn leaves_failing_expressions_for_the_evaluator() {
+        let program = optimized("\"a\" - 1;");
+        assert_eq!(program.to_string(), "(\"a\" - 1)");
+        let result = eval(&program, new_env());
+        assert!(result.is_error(), "{:?}", result);
+    }
+
+    #[test]
+    fn leaves_overflowing_arithmetic_unfolded() {
+        let program = optimized("if (false) {\n    let q = 9223372036854775807 + 1;\n}\n\"reached\";\n");
+        assert_eq!(program.to_string(), "\"reached\"");
+        assert_eq!(eval(&program, new_env()), Object::String("reached".into()));
+
+        let program = optimized("9223372036854775807 * 2;");
+        assert_eq!(program.to_string(), "(9223372036854775807 * 2)");
+        let result = eval(&program, new_env());
+        assert!(result.is_error(), "{:?}", result);
+    }
+
+    #[test]
+    fn keeps_only_the_branch_that_runs() {
+        let source = "let x = 1;\nif (1 < 2) {\n    x = 2;\n    x = x + 1;\n} else {\n    x = 10;\n}\nif (false) {\n    x = 100;\n}\nlet y = if (true) { 5 } else { 6 };\nx + y;\n";
+        let program = optimized(source);
+        assert_eq!(program.to_string(), "let x = 1;(x = 2)(x = (x + 1))let y = 5;(x + y)");
+        assert_eq!(program.lines, vec![1, 3, 4, 11, 12]);
+        assert_eq!(eval(&program, new_env()), Object::Integer(8));
+    }
+
+    #[test]
+    fn a_final_constant_if_still_gives_the_scripts_value() {
+        let program = optimized("let x = 1;\nif (false) { x = 2; }\n");
+        assert_eq!(program.statements.len(), 2);
+        assert_eq!(eval(&program, new_env()), Object::Null);
+    }
+
+    #[test]
+    fn resolves_builtin_members_unless_the_namespace_could_be_rebound() {
+        let program = optimized("Array::map([1, 2], function(x) { x * 2; });");
+        let Statement::Expression(es) = &program.statements[0] else { panic!() };
+        let Expression::CallExpression(call) = &es.expression else { panic!() };
+        match &*call.function {
+            Expression::Builtin(builtin) => assert!(builtin.func.is_some()),
+            other => panic!("not resolved: {:?}", other),
+        }
+        assert_eq!(
+            eval(&program, new_env()),
+            Object::Array(vec![Object::Integer(2), Object::Integer(4)].into())
+        );
+
+        for source in [
+            "let Array = { map: function(a, f) { 0; } };\nArray::map([1], function(x) { x; });",
+            "function f() { Array = 1; }\nArray::map([1], function(x) { x; });",
+            "import \"lib.sl\";\nArray::map([1], function(x) { x; });",
+        ] {
+            let program = optimized(source);
+            assert!(!format!("{:?}", program).contains("Builtin("), "{}", source);
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod core;
+pub use core::{Dependency, MANIFEST, MODULES_DIR, project_root, resolve_import};
+#[cfg(feature = "formats")]
+pub use core::{add_dependency, install, read_manifest, Installed};
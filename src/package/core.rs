@@ -0,0 +1,324 @@
+//! Project manifests and the packages they depend on.
+//!
+//! A project is a directory with a `slang.toml`. `slang add` records a git
+//! dependency in its `[dependencies]` table and `slang install` clones every
+//! dependency into `slang_modules/<name>`; `import "pkg:name/path"` then loads
+//! `slang_modules/name/path.sl` from the nearest project above the importer.
+//! Reading and writing manifests needs the `formats` cargo feature.
+
+use std::path::{Component, Path, PathBuf};
+#[cfg(feature = "formats")]
+use std::process::Command;
+
+pub const MANIFEST: &str = "slang.toml";
+pub const MODULES_DIR: &str = "slang_modules";
+
+/// A `[dependencies]` entry: `name = { git = "...", rev = "..." }`, or just
+/// `name = "<git url>"` when no revision is pinned.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dependency {
+    pub name: String,
+    pub git: String,
+    pub rev: Option<String>,
+}
+
+/// The nearest directory at or above `start` that holds a `slang.toml`.
+pub fn project_root(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join(MANIFEST).is_file())
+        .map(Path::to_path_buf)
+}
+
+/// Package names become directory names, so they are kept to letters,
+/// digits, `-` and `_`.
+fn check_name(name: &str) -> Result<(), String> {
+    if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        Ok(())
+    } else {
+        Err(format!("invalid package name '{}'", name))
+    }
+}
+
+/// Git urls and revisions are passed to `git` as arguments, so one starting
+/// with `-` would be read as an option.
+#[cfg(feature = "formats")]
+fn check_git_arg(package: &str, what: &str, value: &str) -> Result<(), String> {
+    if value.starts_with('-') {
+        Err(format!("{}: invalid git {} '{}'", package, what, value))
+    } else {
+        Ok(())
+    }
+}
+
+/// Check a dependency before anything hands it to `git`.
+#[cfg(feature = "formats")]
+fn check_dependency(dep: &Dependency) -> Result<(), String> {
+    check_name(&dep.name)?;
+    check_git_arg(&dep.name, "url", &dep.git)?;
+    match &dep.rev {
+        Some(rev) => check_git_arg(&dep.name, "rev", rev),
+        None => Ok(()),
+    }
+}
+
+/// The file `import "pkg:<spec>"` loads when imported from `dir`. A bare
+/// package name loads the package's `lib.sl`; the path after it can't leave
+/// the package's directory.
+pub fn resolve_import(spec: &str, dir: &Path) -> Result<PathBuf, String> {
+    let root = project_root(dir).ok_or_else(|| {
+        format!(
+            "cannot import 'pkg:{}': no {} in {} or its parents",
+            spec,
+            MANIFEST,
+            dir.display()
+        )
+    })?;
+    let (name, rest) = spec.split_once('/').unwrap_or((spec, "lib"));
+    check_name(name)?;
+    if !Path::new(rest).components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(format!(
+            "cannot import 'pkg:{}': '{}' is not a path inside package '{}'",
+            spec, rest, name
+        ));
+    }
+    let package_dir = root.join(MODULES_DIR).join(name);
+    if !package_dir.is_dir() {
+        return Err(format!(
+            "cannot import 'pkg:{}': package '{}' is not installed (run `slang install`)",
+            spec, name
+        ));
+    }
+    let mut path = package_dir.join(rest);
+    if path.extension().is_none() {
+        path.set_extension("sl");
+    }
+    Ok(path)
+}
+
+#[cfg(feature = "formats")]
+fn load_table(root: &Path) -> Result<toml::Table, String> {
+    let path = root.join(MANIFEST);
+    match std::fs::read_to_string(&path) {
+        Ok(source) => source
+            .parse::<toml::Table>()
+            .map_err(|e| format!("{}: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(toml::Table::new()),
+        Err(e) => Err(format!("{}: failed to read: {}", path.display(), e)),
+    }
+}
+
+/// The dependencies listed in `root/slang.toml`, in manifest order.
+#[cfg(feature = "formats")]
+pub fn read_manifest(root: &Path) -> Result<Vec<Dependency>, String> {
+    let table = load_table(root)?;
+    let deps = match table.get("dependencies") {
+        None => return Ok(Vec::new()),
+        Some(toml::Value::Table(deps)) => deps,
+        Some(_) => return Err(format!("{}: [dependencies] must be a table", MANIFEST)),
+    };
+    let mut out = Vec::new();
+    for (name, entry) in deps {
+        check_name(name)?;
+        let (git, rev) = match entry {
+            toml::Value::String(url) => (url.clone(), None),
+            toml::Value::Table(t) => match t.get("git").and_then(toml::Value::as_str) {
+                Some(url) => (
+                    url.to_string(),
+                    t.get("rev").and_then(toml::Value::as_str).map(str::to_string),
+                ),
+                None => return Err(format!("{}: dependency '{}' has no git url", MANIFEST, name)),
+            },
+            _ => {
+                return Err(format!(
+                    "{}: dependency '{}' must be a git url or a table",
+                    MANIFEST, name
+                ))
+            }
+        };
+        let dep = Dependency { name: name.clone(), git, rev };
+        check_dependency(&dep)?;
+        out.push(dep);
+    }
+    Ok(out)
+}
+
+/// Add `dep` to `root/slang.toml`, creating the manifest if needed and
+/// replacing any entry of the same name. Everything else in the file is kept.
+#[cfg(feature = "formats")]
+pub fn add_dependency(root: &Path, dep: &Dependency) -> Result<(), String> {
+    check_dependency(dep)?;
+    let mut table = load_table(root)?;
+    let deps = table
+        .entry("dependencies")
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+    let toml::Value::Table(deps) = deps else {
+        return Err(format!("{}: [dependencies] must be a table", MANIFEST));
+    };
+    let entry = match &dep.rev {
+        None => toml::Value::String(dep.git.clone()),
+        Some(rev) => {
+            let mut t = toml::Table::new();
+            t.insert("git".to_string(), toml::Value::String(dep.git.clone()));
+            t.insert("rev".to_string(), toml::Value::String(rev.clone()));
+            toml::Value::Table(t)
+        }
+    };
+    deps.insert(dep.name.clone(), entry);
+    let text = toml::to_string(&table).map_err(|e| e.to_string())?;
+    let path = root.join(MANIFEST);
+    std::fs::write(&path, text).map_err(|e| format!("{}: failed to write: {}", path.display(), e))
+}
+
+/// What `install` did with one dependency.
+#[cfg(feature = "formats")]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Installed {
+    Cloned(String),
+    /// Already in `slang_modules`; checkouts without a `rev` are left alone.
+    Present(String),
+    /// An existing checkout moved to its pinned `rev`.
+    CheckedOut(String, String),
+}
+
+/// Fetch every dependency in `root/slang.toml` into `root/slang_modules`.
+#[cfg(feature = "formats")]
+pub fn install(root: &Path) -> Result<Vec<Installed>, String> {
+    let modules = root.join(MODULES_DIR);
+    std::fs::create_dir_all(&modules)
+        .map_err(|e| format!("{}: failed to create: {}", modules.display(), e))?;
+    let mut done = Vec::new();
+    for dep in read_manifest(root)? {
+        let dir = modules.join(&dep.name);
+        let existed = dir.is_dir();
+        if !existed {
+            git(&dep.name, &modules, &["clone", "--quiet", "--", &dep.git, &dep.name])?;
+        }
+        done.push(match dep.rev {
+            Some(rev) => {
+                if existed {
+                    git(&dep.name, &dir, &["fetch", "--quiet"])?;
+                }
+                git(&dep.name, &dir, &["checkout", "--quiet", &rev, "--"])?;
+                if existed {
+                    Installed::CheckedOut(dep.name, rev)
+                } else {
+                    Installed::Cloned(dep.name)
+                }
+            }
+            None if existed => Installed::Present(dep.name),
+            None => Installed::Cloned(dep.name),
+        });
+    }
+    Ok(done)
+}
+
+#[cfg(feature = "formats")]
+fn git(package: &str, dir: &Path, args: &[&str]) -> Result<(), String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("{}: failed to run git: {}", package, e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{}: git {} failed: {}",
+            package,
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+    use std::fs;
+
+    #[test]
+    fn pkg_imports_resolve_from_the_nearest_project() {
+        let root = temp_dir("slang_pkg_resolve");
+        fs::write(root.join(MANIFEST), "").unwrap();
+        fs::create_dir_all(root.join(MODULES_DIR).join("utils")).unwrap();
+        let nested = root.join("src").join("deep");
+        fs::create_dir_all(&nested).unwrap();
+
+        let utils = root.join(MODULES_DIR).join("utils");
+        assert_eq!(resolve_import("utils/strings", &nested), Ok(utils.join("strings.sl")));
+        assert_eq!(resolve_import("utils", &root), Ok(utils.join("lib.sl")));
+        assert!(resolve_import("missing/x", &root).unwrap_err().contains("not installed"));
+        assert!(resolve_import("../escape", &root).unwrap_err().contains("invalid package name"));
+        for escape in ["utils/../../x", "utils/a/../b", "utils//etc/passwd", "utils/./x"] {
+            let err = resolve_import(escape, &root).unwrap_err();
+            assert!(err.contains("is not a path inside package 'utils'"), "{}: {}", escape, err);
+        }
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[cfg(feature = "formats")]
+    #[test]
+    fn add_then_install_clones_from_git() {
+        let upstream = temp_dir("slang_pkg_upstream");
+        fs::write(upstream.join("strings.sl"), "namespace Strings { function hi() { \"hi\"; } }")
+            .unwrap();
+        let git_in = |args: &[&str]| {
+            let ok = Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+                .args(args)
+                .current_dir(&upstream)
+                .output()
+                .map(|o| o.status.success())
+                .unwrap_or(false);
+            assert!(ok, "git {:?} failed", args);
+        };
+        git_in(&["init", "--quiet"]);
+        git_in(&["add", "."]);
+        git_in(&["commit", "--quiet", "-m", "init"]);
+
+        let root = temp_dir("slang_pkg_project");
+        fs::write(root.join(MANIFEST), "[project]\nname = \"demo\"\n").unwrap();
+        let dep = Dependency {
+            name: "utils".to_string(),
+            git: upstream.display().to_string(),
+            rev: None,
+        };
+        add_dependency(&root, &dep).unwrap();
+        assert_eq!(read_manifest(&root), Ok(vec![dep]));
+        assert!(fs::read_to_string(root.join(MANIFEST)).unwrap().contains("name = \"demo\""));
+
+        assert_eq!(install(&root), Ok(vec![Installed::Cloned("utils".to_string())]));
+        assert!(root.join(MODULES_DIR).join("utils").join("strings.sl").is_file());
+        assert_eq!(install(&root), Ok(vec![Installed::Present("utils".to_string())]));
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&upstream);
+    }
+
+    #[cfg(feature = "formats")]
+    #[test]
+    fn git_urls_and_revs_cannot_pass_as_options() {
+        let root = temp_dir("slang_pkg_options");
+        let dep = |git: &str, rev: Option<&str>| Dependency {
+            name: "utils".to_string(),
+            git: git.to_string(),
+            rev: rev.map(str::to_string),
+        };
+        assert_eq!(
+            add_dependency(&root, &dep("--upload-pack=touch pwned", None)),
+            Err("utils: invalid git url '--upload-pack=touch pwned'".to_string())
+        );
+        assert_eq!(
+            add_dependency(&root, &dep("https://example.com/u.git", Some("-p"))),
+            Err("utils: invalid git rev '-p'".to_string())
+        );
+
+        fs::write(root.join(MANIFEST), "[dependencies]\nutils = \"-oProxyCommand=x\"\n").unwrap();
+        assert_eq!(install(&root), Err("utils: invalid git url '-oProxyCommand=x'".to_string()));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -9,33 +9,48 @@ use crate::token::{Token, TokenType};
 enum Precedence {
     Lowest = 0,
     Assign,      // =
+    Pipeline,    // |>
+    Coalesce,    // ??
     Or,          // ||
     And,         // &&
     Equals,      // == !=
     LessGreater, // < > <= >=
+    BitOr,       // |
+    BitXor,      // ^
+    BitAnd,      // &
+    Shift,       // << >>
     Sum,         // + -
-    Product,     // * / %
-    Prefix,      // !x, -x, ++x, --x
-    Call,        // myFunction(x), x(), x.y, x[0], x++, x--
+    Product,     // * / % div
+    Prefix,      // !x, -x, ~x, ++x, --x
+    Power,       // ** (right-associative, binds tighter than a leading -)
+    Call,        // myFunction(x), x(), x.y, x?.y, x[0], x++, x--, x?
 }
 
 fn precedence_of(ttype: &TokenType) -> Precedence {
     use crate::token::TokenType::{
-        And, Assign, ColonColon, Div, Dot, Equal, GreaterEqual, GreaterThan, Lbracket, LessEqual,
-        LessThan, Lparen, Minus, MinusMinus, Mod, Mul, NotEqual, Or, Plus, PlusPlus,
+        And, Assign, BitAnd, BitOr, BitXor, ColonColon, Div, Dot, Equal, GreaterEqual, GreaterThan,
+        IntDiv, Lbracket, LessEqual, LessThan, Lparen, Minus, MinusMinus, Mod, Mul, NotEqual, Or,
+        Pipeline, Plus, PlusPlus, Pow, Question, QuestionDot, QuestionQuestion, ShiftLeft, ShiftRight,
     };
     match ttype {
         Assign => Precedence::Assign,
+        Pipeline => Precedence::Pipeline,
+        QuestionQuestion => Precedence::Coalesce,
         Or => Precedence::Or,
         And => Precedence::And,
         Equal | NotEqual => Precedence::Equals,
         LessThan | GreaterThan | LessEqual | GreaterEqual => Precedence::LessGreater,
+        BitOr => Precedence::BitOr,
+        BitXor => Precedence::BitXor,
+        BitAnd => Precedence::BitAnd,
+        ShiftLeft | ShiftRight => Precedence::Shift,
         Plus | Minus => Precedence::Sum,
-        Mul | Div | Mod => Precedence::Product,
-        PlusPlus | MinusMinus => Precedence::Call,
+        Mul | Div | Mod | IntDiv => Precedence::Product,
+        Pow => Precedence::Power,
+        PlusPlus | MinusMinus | Question => Precedence::Call,
         Lparen => Precedence::Call,
         Lbracket => Precedence::Call,
-        Dot => Precedence::Call,
+        Dot | QuestionDot => Precedence::Call,
         ColonColon => Precedence::Call,
         _ => Precedence::Lowest,
     }
@@ -47,9 +62,14 @@ type InfixParseFn = fn(&mut Parser, Expression) -> Option<Expression>;
 pub struct Parser {
     l: Lexer,
     pub errors: Vec<String>,
+    /// The source line each of `errors` was found on.
+    pub error_lines: Vec<usize>,
 
     cur_token: Token,
     peek_token: Token,
+    /// Source lines of `cur_token` and `peek_token`.
+    cur_line: usize,
+    peek_line: usize,
 
     prefix_fns: HashMap<TokenType, PrefixParseFn>,
     infix_fns: HashMap<TokenType, InfixParseFn>,
@@ -58,16 +78,22 @@ pub struct Parser {
 impl Parser {
     pub fn new(mut l: Lexer) -> Self {
         let first = l.next_token();
+        let first_line = l.line();
         let second = l.next_token();
+        let second_line = l.line();
 
         let mut p = Parser {
             l,
             errors: Vec::new(),
+            error_lines: Vec::new(),
             cur_token: first,
             peek_token: second,
+            cur_line: first_line,
+            peek_line: second_line,
             prefix_fns: HashMap::new(),
             infix_fns: HashMap::new(),
         };
+        p.mark_int_div();
 
         // register prefix parsers
         p.register_prefix(TokenType::Ident, Parser::parse_identifier);
@@ -76,16 +102,20 @@ impl Parser {
         p.register_prefix(TokenType::Lparen, Parser::parse_grouped_expression);
         p.register_prefix(TokenType::True, Parser::parse_boolean_literal);
         p.register_prefix(TokenType::False, Parser::parse_boolean_literal);
+        p.register_prefix(TokenType::Null, Parser::parse_null_literal);
         p.register_prefix(TokenType::If, Parser::parse_if_expression);
         p.register_prefix(TokenType::Bang, Parser::parse_prefix_expression);
         p.register_prefix(TokenType::Minus, Parser::parse_prefix_expression);
+        p.register_prefix(TokenType::Tilde, Parser::parse_prefix_expression);
         p.register_prefix(TokenType::PlusPlus, Parser::parse_prefix_expression);
         p.register_prefix(TokenType::MinusMinus, Parser::parse_prefix_expression);
         p.register_prefix(TokenType::Function, Parser::parse_function_literal);
         p.register_prefix(TokenType::String, Parser::parse_string_literal);
+        p.register_prefix(TokenType::Bytes, Parser::parse_bytes_literal);
         p.register_prefix(TokenType::Lbracket, Parser::parse_array_literal);
         p.register_prefix(TokenType::Lbrace, Parser::parse_object_literal);
         p.register_prefix(TokenType::New, Parser::parse_new_expression);
+        p.register_prefix(TokenType::Ellipsis, Parser::parse_misplaced_spread);
 
         // register infix parsers
         p.register_infix(TokenType::Equal, Parser::parse_infix_expression);
@@ -97,26 +127,64 @@ impl Parser {
         p.register_infix(TokenType::And, Parser::parse_infix_expression);
         p.register_infix(TokenType::Or, Parser::parse_infix_expression);
         p.register_infix(TokenType::Assign, Parser::parse_infix_expression);
+        p.register_infix(TokenType::QuestionQuestion, Parser::parse_infix_expression);
+        p.register_infix(TokenType::Pipeline, Parser::parse_infix_expression);
 
         p.register_infix(TokenType::Plus, Parser::parse_infix_expression);
         p.register_infix(TokenType::Minus, Parser::parse_infix_expression);
         p.register_infix(TokenType::Mul, Parser::parse_infix_expression);
         p.register_infix(TokenType::Div, Parser::parse_infix_expression);
         p.register_infix(TokenType::Mod, Parser::parse_infix_expression);
+        p.register_infix(TokenType::IntDiv, Parser::parse_infix_expression);
+        p.register_infix(TokenType::Pow, Parser::parse_infix_expression);
+        p.register_infix(TokenType::BitAnd, Parser::parse_infix_expression);
+        p.register_infix(TokenType::BitOr, Parser::parse_infix_expression);
+        p.register_infix(TokenType::BitXor, Parser::parse_infix_expression);
+        p.register_infix(TokenType::ShiftLeft, Parser::parse_infix_expression);
+        p.register_infix(TokenType::ShiftRight, Parser::parse_infix_expression);
 
         p.register_infix(TokenType::Lparen, Parser::parse_call_expression);
         p.register_infix(TokenType::Lbracket, Parser::parse_index_expression);
         p.register_infix(TokenType::Dot, Parser::parse_property_access);
+        p.register_infix(TokenType::QuestionDot, Parser::parse_property_access);
         p.register_infix(TokenType::ColonColon, Parser::parse_property_access);
         p.register_infix(TokenType::PlusPlus, Parser::parse_postfix_expression);
         p.register_infix(TokenType::MinusMinus, Parser::parse_postfix_expression);
+        p.register_infix(TokenType::Question, Parser::parse_postfix_expression);
 
         p
     }
 
     fn next_token(&mut self) {
         self.cur_token = self.peek_token.clone();
+        self.cur_line = self.peek_line;
         self.peek_token = self.l.next_token();
+        self.peek_line = self.l.line();
+        self.mark_int_div();
+    }
+
+    /// `div` is integer division only when it follows something that can end
+    /// an operand (`a div b`, `f(x) div 2`); anywhere else it is an ordinary
+    /// identifier, so `function div(a, b)` keeps working.
+    fn mark_int_div(&mut self) {
+        if self.peek_token.token_type == TokenType::Ident
+            && self.peek_token.literal == "div"
+            && matches!(
+                self.cur_token.token_type,
+                TokenType::Ident
+                    | TokenType::Int
+                    | TokenType::Float
+                    | TokenType::String
+                    | TokenType::Bytes
+                    | TokenType::True
+                    | TokenType::False
+                    | TokenType::Null
+                    | TokenType::Rparen
+                    | TokenType::Rbracket
+            )
+        {
+            self.peek_token.token_type = TokenType::IntDiv;
+        }
     }
 
     fn register_prefix(&mut self, ttype: TokenType, func: PrefixParseFn) {
@@ -128,9 +196,7 @@ impl Parser {
     }
 
     pub fn parse_program(&mut self) -> Program {
-        let mut program = Program {
-            statements: Vec::new(),
-        };
+        let mut program = Program::new();
 
         debug_log!("parse_program: starting, cur_token = {:?}", self.cur_token);
 
@@ -140,10 +206,12 @@ impl Parser {
                 self.cur_token
             );
 
+            let line = self.cur_line;
             match self.parse_statement() {
                 Some(stmt) => {
                     debug_log!("  parse_statement returned: {:?}", stmt);
                     program.statements.push(stmt);
+                    program.lines.push(line);
                 }
                 None => {
                     debug_log!("  parse_statement returned None");
@@ -187,6 +255,13 @@ impl Parser {
             ttype, self.peek_token.token_type
         );
         self.errors.push(msg);
+        self.error_lines.push(self.peek_line);
+    }
+
+    /// Record an error at the current token.
+    fn error(&mut self, msg: String) {
+        self.errors.push(msg);
+        self.error_lines.push(self.cur_line);
     }
 }
 
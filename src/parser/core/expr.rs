@@ -1,17 +1,20 @@
 use crate::ast::nodes::{
     BooleanLiteral, FloatLiteral, NewExpression, ObjectLiteral, PostfixExpression, PostfixOp,
-    PrefixExpression, PrefixOp, PropertyAccess,
+    PrefixExpression, PrefixOp, PropertyAccess, SpreadExpression,
 };
 use crate::ast::{
     ArrayLiteral, BlockStatement, CallExpression, Expression, ExpressionStatement, FunctionLiteral,
-    Identifier, IfExpression, IndexExpression, InfixExpression, InfixOp, IntegerLiteral, Statement,
-    StringLiteral,
+    BytesLiteral, Identifier, IfExpression, IndexExpression, InfixExpression, InfixOp, IntegerLiteral,
+    Statement, StringLiteral,
 };
 use crate::debug_log;
 use crate::token::TokenType;
 
 use super::{Parser, Precedence};
 
+/// Parameter names, their defaults and the optional rest parameter.
+pub(super) type FunctionParameters = (Vec<Identifier>, Vec<Option<Expression>>, Option<Identifier>);
+
 impl Parser {
     // ---------- Expressions (Pratt) ----------
 
@@ -36,6 +39,12 @@ impl Parser {
                     "parse_expression: NO prefix fn for {:?}, returning None",
                     self.cur_token.token_type
                 );
+                if self.cur_token.token_type == TokenType::Illegal {
+                    self.error(format!("illegal token: {}", self.cur_token.literal));
+                }
+                if self.cur_token.token_type == TokenType::FatArrow {
+                    self.error("=> must follow a parameter name or list, like x => ... or (a, b) => ...".to_string());
+                }
                 return None;
             }
         };
@@ -112,16 +121,32 @@ impl Parser {
     }
 
     pub(super) fn parse_identifier(&mut self) -> Option<Expression> {
-        Some(Expression::Identifier(Identifier {
-            value: self.cur_token.literal.clone(),
-        }))
+        // `x => body`
+        if self.peek_token.token_type == TokenType::FatArrow {
+            let param = Identifier::new(self.cur_token.literal.clone());
+            self.next_token(); // '=>'
+            return self.parse_arrow_body((vec![param], vec![None], None));
+        }
+        Some(Expression::Identifier(Identifier::new(self.cur_token.literal.clone())))
     }
 
     pub(super) fn parse_integer_literal(&mut self) -> Option<Expression> {
-        match self.cur_token.literal.parse::<i64>() {
-            Ok(v) => Some(Expression::IntegerLiteral(IntegerLiteral { value: v })),
-            Err(_) => {
-                self.errors.push(format!(
+        let literal = self.cur_token.literal.as_str();
+        let (radix, digits) = match literal.get(..2) {
+            Some("0x" | "0X") => (16, &literal[2..]),
+            Some("0b" | "0B") => (2, &literal[2..]),
+            Some("0o" | "0O") => (8, &literal[2..]),
+            _ => (10, literal),
+        };
+        let parsed = strip_digit_separators(digits, radix)
+            .and_then(|digits| i64::from_str_radix(&digits, radix).ok());
+        match parsed {
+            Some(v) => Some(Expression::IntegerLiteral(IntegerLiteral {
+                value: v,
+                lexeme: Some(self.cur_token.literal.clone()),
+            })),
+            None => {
+                self.error(format!(
                     "could not parse {} as integer",
                     self.cur_token.literal
                 ));
@@ -131,10 +156,15 @@ impl Parser {
     }
 
     pub(super) fn parse_float_literal(&mut self) -> Option<Expression> {
-        match self.cur_token.literal.parse::<f64>() {
-            Ok(v) => Some(Expression::FloatLiteral(FloatLiteral { value: v })),
-            Err(_) => {
-                self.errors.push(format!(
+        let parsed = strip_digit_separators(&self.cur_token.literal, 10)
+            .and_then(|literal| literal.parse::<f64>().ok());
+        match parsed {
+            Some(v) => Some(Expression::FloatLiteral(FloatLiteral {
+                value: v,
+                lexeme: Some(self.cur_token.literal.clone()),
+            })),
+            None => {
+                self.error(format!(
                     "could not parse {} as float",
                     self.cur_token.literal
                 ));
@@ -145,6 +175,13 @@ impl Parser {
 
     pub(super) fn parse_grouped_expression(&mut self) -> Option<Expression> {
         // current is '('
+        if self.at_arrow_parameters() {
+            let params = self.parse_function_parameters()?;
+            if !self.expect_peek(TokenType::FatArrow) {
+                return None;
+            }
+            return self.parse_arrow_body(params);
+        }
         self.next_token(); // move to the first token inside
         let exp = self.parse_expression(Precedence::Lowest)?;
         if !self.expect_peek(TokenType::Rparen) {
@@ -160,6 +197,13 @@ impl Parser {
             TokenType::Mul => InfixOp::Multiply,
             TokenType::Div => InfixOp::Divide,
             TokenType::Mod => InfixOp::Modulo,
+            TokenType::Pow => InfixOp::Power,
+            TokenType::IntDiv => InfixOp::IntDivide,
+            TokenType::BitAnd => InfixOp::BitAnd,
+            TokenType::BitOr => InfixOp::BitOr,
+            TokenType::BitXor => InfixOp::BitXor,
+            TokenType::ShiftLeft => InfixOp::ShiftLeft,
+            TokenType::ShiftRight => InfixOp::ShiftRight,
             TokenType::LessThan => InfixOp::LessThan,
             TokenType::LessEqual => InfixOp::LessEqual,
             TokenType::GreaterThan => InfixOp::GreaterThan,
@@ -169,11 +213,19 @@ impl Parser {
             TokenType::And => InfixOp::And,
             TokenType::Or => InfixOp::Or,
             TokenType::Assign => InfixOp::Assign,
+            TokenType::QuestionQuestion => InfixOp::Coalesce,
+            TokenType::Pipeline => InfixOp::Pipeline,
             _ => return None,
         };
         let precedence = self.cur_precedence();
         self.next_token();
-        let right = self.parse_expression(precedence)?;
+        // `**` is right-associative: `2 ** 3 ** 2` is `2 ** (3 ** 2)`.
+        let right_precedence = if operator == InfixOp::Power {
+            Precedence::Prefix
+        } else {
+            precedence
+        };
+        let right = self.parse_expression(right_precedence)?;
         Some(Expression::Infix(InfixExpression {
             left: Box::new(left),
             operator,
@@ -185,6 +237,7 @@ impl Parser {
         let operator = match self.cur_token.token_type {
             TokenType::Bang => PrefixOp::Not,
             TokenType::Minus => PrefixOp::Negate,
+            TokenType::Tilde => PrefixOp::BitNot,
             TokenType::PlusPlus => PrefixOp::PreIncrement,
             TokenType::MinusMinus => PrefixOp::PreDecrement,
             _ => return None,
@@ -204,6 +257,7 @@ impl Parser {
         let operator = match self.cur_token.token_type {
             TokenType::PlusPlus => PostfixOp::Increment,
             TokenType::MinusMinus => PostfixOp::Decrement,
+            TokenType::Question => PostfixOp::Try,
             _ => return None,
         };
 
@@ -218,6 +272,10 @@ impl Parser {
         Some(Expression::BooleanLiteral(BooleanLiteral { value }))
     }
 
+    pub(super) fn parse_null_literal(&mut self) -> Option<Expression> {
+        Some(Expression::NullLiteral)
+    }
+
     pub(super) fn parse_if_expression(&mut self) -> Option<Expression> {
         // the current token is 'if'
         if !self.expect_peek(TokenType::Lparen) {
@@ -244,6 +302,7 @@ impl Parser {
             if self.peek_token.token_type == TokenType::If {
                 // Move to 'if'
                 self.next_token(); // current = 'if'
+                let line = self.cur_line;
 
                 // Parse the nested if-expression starting at this 'if'
                 let nested_if_expr = self.parse_if_expression()?;
@@ -255,6 +314,9 @@ impl Parser {
                 });
                 let block = BlockStatement {
                     statements: vec![stmt],
+                    lines: vec![line],
+                    end_line: line,
+                    scope: Default::default(),
                 };
 
                 Some(block)
@@ -277,12 +339,14 @@ impl Parser {
     }
 
     pub(super) fn parse_function_literal(&mut self) -> Option<Expression> {
-        // current token is 'fn'
+        // current token is 'fn' (or 'fn*')
+        let generator = self.cur_token_is_generator();
         if !self.expect_peek(TokenType::Lparen) {
             return None;
         }
 
-        let params = self.parse_function_parameters()?;
+        let (params, defaults, rest) = self.parse_function_parameters()?;
+        let (requires, ensures) = self.parse_function_contracts()?;
 
         if !self.expect_peek(TokenType::Lbrace) {
             return None;
@@ -292,10 +356,82 @@ impl Parser {
 
         Some(Expression::FunctionLiteral(FunctionLiteral {
             params,
+            defaults,
+            rest,
+            requires,
+            ensures,
+            body,
+            generator,
+            arrow: false,
+        }))
+    }
+
+    /// An arrow function's body, after its `=>`: a block, or a single
+    /// expression whose value the function returns.
+    fn parse_arrow_body(&mut self, (params, defaults, rest): FunctionParameters) -> Option<Expression> {
+        let body = if self.peek_token.token_type == TokenType::Lbrace {
+            self.next_token();
+            self.parse_block_statement()?
+        } else {
+            self.next_token();
+            let line = self.cur_line;
+            let expression = self.parse_expression(Precedence::Lowest)?;
+            BlockStatement {
+                statements: vec![Statement::Expression(ExpressionStatement { expression })],
+                lines: vec![line],
+                end_line: line,
+                scope: Default::default(),
+            }
+        };
+
+        Some(Expression::FunctionLiteral(FunctionLiteral {
+            params,
+            defaults,
+            rest,
+            requires: Vec::new(),
+            ensures: Vec::new(),
             body,
+            generator: false,
+            arrow: true,
         }))
     }
 
+    /// Whether the `(` at the current token opens an arrow function's
+    /// parameter list rather than a parenthesised expression, i.e. what's
+    /// inside looks like parameters and the matching `)` is followed by `=>`.
+    /// Scans ahead in the lexer and rewinds it.
+    fn at_arrow_parameters(&mut self) -> bool {
+        let checkpoint = self.l.checkpoint();
+        let mut token = self.peek_token.token_type.clone();
+        let mut previous = TokenType::Lparen;
+        // Inside a parameter's default value, and how deeply nested in it.
+        let mut in_default = false;
+        let mut depth = 0;
+        let arrow = loop {
+            match token {
+                TokenType::Eof => break false,
+                TokenType::Lparen | TokenType::Lbracket | TokenType::Lbrace if in_default => depth += 1,
+                TokenType::Rparen | TokenType::Rbracket | TokenType::Rbrace if depth > 0 => depth -= 1,
+                _ if depth > 0 => {}
+                TokenType::Rparen => break self.l.next_token().token_type == TokenType::FatArrow,
+                TokenType::Comma => in_default = false,
+                TokenType::Assign if previous == TokenType::Ident && !in_default => in_default = true,
+                _ if in_default => {}
+                TokenType::Ident | TokenType::Ellipsis => {}
+                _ => break false,
+            }
+            previous = token;
+            token = self.l.next_token().token_type;
+        };
+        self.l.rewind(checkpoint);
+        arrow
+    }
+
+    /// Whether the current `fn`/`function` token was written `fn*`.
+    pub(super) fn cur_token_is_generator(&self) -> bool {
+        self.cur_token.literal.ends_with('*')
+    }
+
     pub(super) fn parse_call_expression(&mut self, function: Expression) -> Option<Expression> {
         debug_log!(
             "parse_call_expression: ENTER, function = {:?}, cur_token = {:?}, peek_token = {:?}",
@@ -319,9 +455,7 @@ impl Parser {
             return None;
         }
 
-        let class_name = Identifier {
-            value: self.cur_token.literal.clone(),
-        };
+        let class_name = Identifier::new(self.cur_token.literal.clone());
 
         if !self.expect_peek(TokenType::Lparen) {
             return None;
@@ -335,43 +469,95 @@ impl Parser {
         })))
     }
 
-    pub(super) fn parse_function_parameters(&mut self) -> Option<Vec<Identifier>> {
+    /// Parses `(a, b = 10, ...rest)` with cur_token on the opening paren,
+    /// leaving cur_token on the closing paren.
+    pub(super) fn parse_function_parameters(&mut self) -> Option<FunctionParameters> {
         let mut params = Vec::new();
+        let mut defaults = Vec::new();
+        let mut rest = None;
 
-        // fn() ...
-        if self.peek_token.token_type == TokenType::Rparen {
-            self.next_token(); // skip ')'
-            return Some(params);
-        }
+        while self.peek_token.token_type != TokenType::Rparen {
+            if self.peek_token.token_type == TokenType::Ellipsis {
+                self.next_token();
+                if !self.expect_peek(TokenType::Ident) {
+                    return None;
+                }
+                rest = Some(Identifier::new(self.cur_token.literal.clone()));
+                // the rest parameter has to come last
+                break;
+            }
 
-        // first param
-        self.next_token(); // current = first identifier
-        params.push(Identifier {
-            value: self.cur_token.literal.clone(),
-        });
+            if !self.expect_peek(TokenType::Ident) {
+                return None;
+            }
+            params.push(Identifier::new(self.cur_token.literal.clone()));
 
-        // more params
-        while self.peek_token.token_type == TokenType::Comma {
-            self.next_token(); // skip ','
-            self.next_token(); // move to next ident
-            params.push(Identifier {
-                value: self.cur_token.literal.clone(),
-            });
+            if self.peek_token.token_type == TokenType::Assign {
+                self.next_token(); // '='
+                self.next_token(); // start of default expression
+                defaults.push(Some(self.parse_expression(Precedence::Lowest)?));
+            } else {
+                if defaults.iter().any(Option::is_some) {
+                    self.error(format!(
+                        "parameter {} without a default follows a parameter with one",
+                        self.cur_token.literal
+                    ));
+                    return None;
+                }
+                defaults.push(None);
+            }
+
+            if self.peek_token.token_type != TokenType::Comma {
+                break;
+            }
+            self.next_token();
         }
 
         if !self.expect_peek(TokenType::Rparen) {
             return None;
         }
 
-        Some(params)
+        Some((params, defaults, rest))
+    }
+
+    /// Parses any `requires <expr>` / `ensures <expr>` clauses between a
+    /// function's parameter list and its body. `requires` and `ensures` are
+    /// only keywords in this position.
+    pub(super) fn parse_function_contracts(&mut self) -> Option<(Vec<Expression>, Vec<Expression>)> {
+        let mut requires = Vec::new();
+        let mut ensures = Vec::new();
+
+        while self.peek_token.token_type == TokenType::Ident {
+            let clauses = match self.peek_token.literal.as_str() {
+                "requires" => &mut requires,
+                "ensures" => &mut ensures,
+                _ => break,
+            };
+            self.next_token(); // 'requires' / 'ensures'
+            self.next_token(); // start of the condition
+            clauses.push(self.parse_expression(Precedence::Lowest)?);
+        }
+
+        Some((requires, ensures))
     }
 
     pub(super) fn parse_string_literal(&mut self) -> Option<Expression> {
         Some(Expression::StringLiteral(StringLiteral {
             value: self.cur_token.literal.clone(),
+            lexeme: self.cur_token.lexeme.clone(),
         }))
     }
 
+    pub(super) fn parse_bytes_literal(&mut self) -> Option<Expression> {
+        match decode_byte_string(&self.cur_token.literal) {
+            Ok(value) => Some(Expression::BytesLiteral(BytesLiteral { value })),
+            Err(e) => {
+                self.error(format!("invalid byte string: {}", e));
+                None
+            }
+        }
+    }
+
     pub(super) fn parse_array_literal(&mut self) -> Option<Expression> {
         // current token is '['
         let elements = self.parse_expression_list(TokenType::Rbracket)?;
@@ -392,16 +578,14 @@ impl Parser {
             // Move to the property name identifier
             self.next_token();
             if self.cur_token.token_type != TokenType::Ident {
-                self.errors.push(format!(
+                self.error(format!(
                     "expected identifier as object property name, got {:?}",
                     self.cur_token.token_type
                 ));
                 return None;
             }
 
-            let name = Identifier {
-                value: self.cur_token.literal.clone(),
-            };
+            let name = Identifier::new(self.cur_token.literal.clone());
 
             if !self.expect_peek(TokenType::Colon) {
                 return None;
@@ -433,6 +617,24 @@ impl Parser {
         Some(Expression::ObjectLiteral(ObjectLiteral { properties }))
     }
 
+    /// An argument or array element, which may be a `...spread`.
+    fn parse_list_element(&mut self) -> Option<Expression> {
+        if self.cur_token.token_type != TokenType::Ellipsis {
+            return self.parse_expression(Precedence::Lowest);
+        }
+        self.next_token();
+        let value = self.parse_expression(Precedence::Lowest)?;
+        Some(Expression::Spread(Box::new(SpreadExpression { value })))
+    }
+
+    /// `...` anywhere other than a list element is an error.
+    pub(super) fn parse_misplaced_spread(&mut self) -> Option<Expression> {
+        self.error(
+            "spread (...) is only allowed in call arguments and array literals".to_string(),
+        );
+        None
+    }
+
     pub(super) fn parse_expression_list(&mut self, end: TokenType) -> Option<Vec<Expression>> {
         debug_log!(
             "parse_expression_list: ENTER, end = {:?}, cur_token = {:?}, peek_token = {:?}",
@@ -456,14 +658,14 @@ impl Parser {
             self.peek_token
         );
 
-        list.push(self.parse_expression(Precedence::Lowest)?);
+        list.push(self.parse_list_element()?);
         debug_log!("parse_expression_list: after first arg, list = {:?}", list);
 
         while self.peek_token.token_type == TokenType::Comma {
             debug_log!("parse_expression_list: found comma, parsing another arg");
             self.next_token(); // consume ','
             self.next_token(); // move to next argument
-            list.push(self.parse_expression(Precedence::Lowest)?);
+            list.push(self.parse_list_element()?);
             debug_log!("parse_expression_list: list now = {:?}", list);
         }
 
@@ -493,18 +695,24 @@ impl Parser {
     }
 
     pub(super) fn parse_property_access(&mut self, left: Expression) -> Option<Expression> {
-        // current token is '.'
-        if !self.expect_peek(TokenType::Ident) {
+        // current token is '.', '?.' or '::'
+        let namespaced = self.cur_token.token_type == TokenType::ColonColon;
+        let optional = self.cur_token.token_type == TokenType::QuestionDot;
+        // `new` is a keyword, but is allowed as a member name so constructors
+        // like `Bloom::new(...)` read naturally.
+        if self.peek_token.token_type == TokenType::New {
+            self.next_token();
+        } else if !self.expect_peek(TokenType::Ident) {
             return None;
         }
 
-        let property = Identifier {
-            value: self.cur_token.literal.clone(),
-        };
+        let property = Identifier::new(self.cur_token.literal.clone());
 
         Some(Expression::PropertyAccess(Box::new(PropertyAccess {
             object: Box::new(left),
             property,
+            namespaced,
+            optional,
         })))
     }
 
@@ -514,6 +722,9 @@ impl Parser {
         // current token is '{'
         let mut block = BlockStatement {
             statements: Vec::new(),
+            lines: Vec::new(),
+            end_line: 0,
+            scope: Default::default(),
         };
 
         self.next_token(); // move to first token inside block
@@ -521,12 +732,72 @@ impl Parser {
         while self.cur_token.token_type != TokenType::Rbrace
             && self.cur_token.token_type != TokenType::Eof
         {
+            let line = self.cur_line;
             if let Some(stmt) = self.parse_statement() {
                 block.statements.push(stmt);
+                block.lines.push(line);
             }
             self.next_token();
         }
 
+        block.end_line = self.cur_line;
         Some(block)
     }
 }
+
+/// Remove `_` separators from a number literal, rejecting any that aren't
+/// between two digits (`1__0`, `1_`, `0x_1`, `1_.5`).
+fn strip_digit_separators(literal: &str, radix: u32) -> Option<String> {
+    let chars: Vec<char> = literal.chars().collect();
+    let mut out = String::with_capacity(chars.len());
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '_' {
+            let before = i.checked_sub(1).map(|j| chars[j]);
+            let after = chars.get(i + 1);
+            if !before.is_some_and(|b| b.is_digit(radix))
+                || !after.is_some_and(|a| a.is_digit(radix))
+            {
+                return None;
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    Some(out)
+}
+
+/// Decode the raw contents of a `b"..."` literal. Supports `\xHH`, `\n`,
+/// `\r`, `\t`, `\0`, `\\` and `\"`; other characters must be printable ASCII.
+fn decode_byte_string(raw: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            if !ch.is_ascii() {
+                return Err(format!("non-ASCII character '{}' (use \\x escapes)", ch));
+            }
+            bytes.push(ch as u8);
+            continue;
+        }
+        let byte = match chars.next() {
+            Some('n') => b'\n',
+            Some('r') => b'\r',
+            Some('t') => b'\t',
+            Some('0') => 0,
+            Some('\\') => b'\\',
+            Some('"') => b'"',
+            Some('x') => {
+                let hex: String = chars.by_ref().take(2).collect();
+                if hex.len() != 2 {
+                    return Err(format!("\\x needs two hex digits, got \"{}\"", hex));
+                }
+                u8::from_str_radix(&hex, 16)
+                    .map_err(|_| format!("\\x needs two hex digits, got \"{}\"", hex))?
+            }
+            Some(other) => return Err(format!("unknown escape \\{}", other)),
+            None => return Err("trailing backslash".to_string()),
+        };
+        bytes.push(byte);
+    }
+    Ok(bytes)
+}
@@ -1,8 +1,9 @@
 use crate::ast::nodes::{
-    ClassStatement, ForStatement, FunctionStatement, PublishExpression, TestStatement,
+    ClassStatement, DescribeStatement, DestructurePattern, DestructureStatement, ExportStatement, ForInStatement, ForStatement, FunctionStatement,
+    HookKind, HookStatement, PublishExpression, SwitchCase, SwitchStatement, TestStatement, UseStatement, YieldStatement,
 };
 use crate::ast::{
-    Expression, ExpressionStatement, FunctionLiteral, Identifier, ImportStatement, IntegerLiteral,
+    BlockStatement, Expression, ExpressionStatement, FunctionLiteral, Identifier, ImportStatement, IntegerLiteral,
     LetStatement, NamespaceStatement, ReturnStatement, Statement, WhileStatement,
 };
 use crate::debug_log;
@@ -18,7 +19,16 @@ impl Parser {
                 self.parse_tagged_function_statement()
                     .map(Statement::Function)
             }
-            TokenType::Let => {
+            TokenType::Let
+                if matches!(
+                    self.peek_token.token_type,
+                    TokenType::Lbracket | TokenType::Lbrace
+                ) =>
+            {
+                debug_log!("  -> parsing destructuring Let statement");
+                self.parse_destructure_statement().map(Statement::Destructure)
+            }
+            TokenType::Let | TokenType::Const => {
                 debug_log!("  -> parsing Let statement");
                 self.parse_let_statement().map(Statement::Let)
             }
@@ -32,7 +42,11 @@ impl Parser {
             }
             TokenType::For => {
                 debug_log!("  -> parsing For statement");
-                self.parse_for_statement().map(Statement::For)
+                self.parse_for_statement()
+            }
+            TokenType::Switch => {
+                debug_log!("  -> parsing Switch statement");
+                self.parse_switch_statement().map(Statement::Switch)
             }
             TokenType::Function => {
                 // Disambiguate between:
@@ -65,6 +79,14 @@ impl Parser {
                 debug_log!("  -> parsing Import statement");
                 self.parse_import_statement().map(Statement::Import)
             }
+            TokenType::Use => {
+                debug_log!("  -> parsing Use statement");
+                self.parse_use_statement().map(Statement::Use)
+            }
+            TokenType::Export => {
+                debug_log!("  -> parsing Export statement");
+                self.parse_export_statement().map(Statement::Export)
+            }
             TokenType::Test => {
                 debug_log!("  -> parsing Test statement");
                 self.parse_test_statement().map(Statement::Test)
@@ -73,6 +95,31 @@ impl Parser {
                 debug_log!("  -> parsing Class statement");
                 self.parse_class_statement().map(Statement::Class)
             }
+            // `describe`, `beforeEach` and `afterEach` are only special at the
+            // start of a statement, so they stay usable as ordinary names.
+            TokenType::Ident
+                if self.cur_token.literal == "describe"
+                    && self.peek_token.token_type == TokenType::String =>
+            {
+                debug_log!("  -> parsing Describe statement");
+                self.parse_describe_statement().map(Statement::Describe)
+            }
+            TokenType::Ident if self.peek_token.token_type == TokenType::Lbrace && self.cur_hook_kind().is_some() => {
+                debug_log!("  -> parsing Hook statement");
+                self.parse_hook_statement().map(Statement::Hook)
+            }
+            TokenType::Yield => {
+                debug_log!("  -> parsing Yield statement");
+                self.parse_yield_statement().map(Statement::Yield)
+            }
+            TokenType::Ident
+                if self.cur_token.literal == "breakpoint"
+                    && self.peek_token.token_type == TokenType::Semicolon =>
+            {
+                debug_log!("  -> parsing Breakpoint statement");
+                self.next_token();
+                Some(Statement::Breakpoint)
+            }
             _ => {
                 debug_log!("  -> default: parsing Expression statement");
                 let stmt = self.parse_expression_statement();
@@ -83,13 +130,41 @@ impl Parser {
     }
 
     fn parse_let_statement(&mut self) -> Option<LetStatement> {
-        // cur_token is 'let'
+        // cur_token is 'let' or 'const'
+        let constant = self.cur_token.token_type == TokenType::Const;
         if !self.expect_peek(TokenType::Ident) {
             return None;
         }
 
-        let name = Identifier {
-            value: self.cur_token.literal.clone(),
+        let name = Identifier::new(self.cur_token.literal.clone());
+
+        if !self.expect_peek(TokenType::Assign) {
+            return None;
+        }
+
+        // move to start of expression
+        self.next_token();
+
+        let value = self.parse_expression(Precedence::Lowest)?;
+
+        // optional semicolon
+        if self.peek_token.token_type == TokenType::Semicolon {
+            self.next_token();
+        }
+
+        Some(LetStatement { name, value, constant })
+    }
+
+    fn parse_destructure_statement(&mut self) -> Option<DestructureStatement> {
+        // cur_token is 'let', peek_token is '[' or '{'
+        self.next_token();
+
+        let pattern = if self.cur_token.token_type == TokenType::Lbrace {
+            DestructurePattern::Object {
+                names: self.parse_name_list()?,
+            }
+        } else {
+            self.parse_array_pattern()?
         };
 
         if !self.expect_peek(TokenType::Assign) {
@@ -106,7 +181,42 @@ impl Parser {
             self.next_token();
         }
 
-        Some(LetStatement { name, value })
+        Some(DestructureStatement { pattern, value })
+    }
+
+    /// Parses `[a, b, ...rest]` with cur_token on the opening bracket, leaving
+    /// cur_token on the closing bracket.
+    fn parse_array_pattern(&mut self) -> Option<DestructurePattern> {
+        let mut names = Vec::new();
+        let mut rest = None;
+
+        while self.peek_token.token_type != TokenType::Rbracket {
+            if self.peek_token.token_type == TokenType::Ellipsis {
+                self.next_token();
+                if !self.expect_peek(TokenType::Ident) {
+                    return None;
+                }
+                rest = Some(Identifier::new(self.cur_token.literal.clone()));
+                // the rest binding has to come last
+                break;
+            }
+
+            if !self.expect_peek(TokenType::Ident) {
+                return None;
+            }
+            names.push(Identifier::new(self.cur_token.literal.clone()));
+
+            if self.peek_token.token_type != TokenType::Comma {
+                break;
+            }
+            self.next_token();
+        }
+
+        if !self.expect_peek(TokenType::Rbracket) {
+            return None;
+        }
+
+        Some(DestructurePattern::Array { names, rest })
     }
 
     fn parse_expression_statement(&mut self) -> Option<ExpressionStatement> {
@@ -130,17 +240,14 @@ impl Parser {
         };
 
         // If we see a comma- or arrow-led tail, treat this as a publish expression.
-        if matches!(
-            self.peek_token.token_type,
-            TokenType::Comma | TokenType::Arrow
-        ) {
-            if let Some(pub_expr) = self.parse_publish_expression(expr.clone()) {
-                let stmt = ExpressionStatement {
-                    expression: pub_expr,
-                };
-                debug_log!("parse_expression_statement: EXIT with publish {:?}", stmt);
-                return Some(stmt);
-            }
+        if matches!(self.peek_token.token_type, TokenType::Comma | TokenType::Arrow)
+            && let Some(pub_expr) = self.parse_publish_expression(expr.clone())
+        {
+            let stmt = ExpressionStatement {
+                expression: pub_expr,
+            };
+            debug_log!("parse_expression_statement: EXIT with publish {:?}", stmt);
+            return Some(stmt);
         }
 
         debug_log!(
@@ -163,9 +270,7 @@ impl Parser {
             return None;
         }
 
-        let name = Identifier {
-            value: self.cur_token.literal.clone(),
-        };
+        let name = Identifier::new(self.cur_token.literal.clone());
 
         if !self.expect_peek(TokenType::Lbrace) {
             return None;
@@ -177,6 +282,26 @@ impl Parser {
     }
 
     fn parse_import_statement(&mut self) -> Option<ImportStatement> {
+        let mut names = Vec::new();
+
+        // import { a, b } from "file.sl";
+        if self.peek_token.token_type == TokenType::Lbrace {
+            self.next_token();
+            names = self.parse_name_list()?;
+
+            // `from` is contextual so it stays usable as an ordinary identifier.
+            if !self.expect_peek(TokenType::Ident) {
+                return None;
+            }
+            if self.cur_token.literal != "from" {
+                self.error(format!(
+                    "expected `from` after import list, got {:?} instead",
+                    self.cur_token.literal
+                ));
+                return None;
+            }
+        }
+
         if !self.expect_peek(TokenType::String) {
             return None;
         }
@@ -188,7 +313,119 @@ impl Parser {
             self.next_token();
         }
 
-        Some(ImportStatement { path })
+        Some(ImportStatement { path, names })
+    }
+
+    fn parse_use_statement(&mut self) -> Option<UseStatement> {
+        // cur_token is 'use'
+        if !self.expect_peek(TokenType::Ident) {
+            return None;
+        }
+        let mut path = vec![Identifier::new(self.cur_token.literal.clone())];
+        let mut glob = false;
+        while self.peek_token.token_type == TokenType::ColonColon {
+            self.next_token();
+            if self.peek_token.token_type == TokenType::Mul {
+                self.next_token();
+                glob = true;
+                break;
+            }
+            if !self.expect_peek(TokenType::Ident) {
+                return None;
+            }
+            path.push(Identifier::new(self.cur_token.literal.clone()));
+        }
+
+        // `as` is contextual, like `from` in imports.
+        let mut alias = None;
+        if self.peek_token.token_type == TokenType::Ident && self.peek_token.literal == "as" {
+            if glob {
+                self.error("`use ...::*` can't be renamed with `as`".to_string());
+                return None;
+            }
+            self.next_token();
+            if !self.expect_peek(TokenType::Ident) {
+                return None;
+            }
+            alias = Some(Identifier::new(self.cur_token.literal.clone()));
+        }
+
+        // optional trailing semicolon
+        if self.peek_token.token_type == TokenType::Semicolon {
+            self.next_token();
+        }
+
+        Some(UseStatement { path, alias, glob })
+    }
+
+    fn parse_export_statement(&mut self) -> Option<ExportStatement> {
+        // export { a, b };
+        if self.peek_token.token_type == TokenType::Lbrace {
+            self.next_token();
+            let names = self.parse_name_list()?;
+
+            // optional trailing semicolon
+            if self.peek_token.token_type == TokenType::Semicolon {
+                self.next_token();
+            }
+
+            return Some(ExportStatement {
+                declaration: None,
+                names,
+            });
+        }
+
+        // export let / function / namespace / class ...
+        self.next_token();
+        let declaration = self.parse_statement()?;
+        let names = match &declaration {
+            Statement::Let(ls) => vec![ls.name.clone()],
+            Statement::Destructure(ds) => ds.pattern.bindings(),
+            Statement::Function(fs) => vec![fs.name.clone()],
+            Statement::Namespace(ns) => vec![ns.name.clone()],
+            Statement::Class(cs) => vec![cs.name.clone()],
+            other => {
+                self.error(format!(
+                    "only let, const, function, namespace and class declarations can be exported, got {}",
+                    other
+                ));
+                return None;
+            }
+        };
+
+        Some(ExportStatement {
+            declaration: Some(Box::new(declaration)),
+            names,
+        })
+    }
+
+    /// Parses `{ a, b, c }` with cur_token on the opening brace, leaving
+    /// cur_token on the closing brace.
+    fn parse_name_list(&mut self) -> Option<Vec<Identifier>> {
+        let mut names = Vec::new();
+
+        if self.peek_token.token_type == TokenType::Rbrace {
+            self.next_token();
+            return Some(names);
+        }
+
+        loop {
+            if !self.expect_peek(TokenType::Ident) {
+                return None;
+            }
+            names.push(Identifier::new(self.cur_token.literal.clone()));
+
+            if self.peek_token.token_type != TokenType::Comma {
+                break;
+            }
+            self.next_token();
+        }
+
+        if !self.expect_peek(TokenType::Rbrace) {
+            return None;
+        }
+
+        Some(names)
     }
 
     fn parse_return_statement(&mut self) -> Option<ReturnStatement> {
@@ -198,7 +435,7 @@ impl Parser {
         // We allow: return; (no value) which just returns null
         if self.cur_token.token_type == TokenType::Semicolon {
             return Some(ReturnStatement {
-                return_value: Expression::IntegerLiteral(IntegerLiteral { value: 0 }), // placeholder if you want, or special-case in evaluator
+                return_value: Expression::IntegerLiteral(IntegerLiteral { value: 0, lexeme: None }), // placeholder if you want, or special-case in evaluator
             });
         }
 
@@ -214,6 +451,19 @@ impl Parser {
         })
     }
 
+    fn parse_yield_statement(&mut self) -> Option<YieldStatement> {
+        // the current token is 'yield'
+        self.next_token();
+        let value = self.parse_expression(Precedence::Lowest)?;
+
+        // optional semicolon
+        if self.peek_token.token_type == TokenType::Semicolon {
+            self.next_token();
+        }
+
+        Some(YieldStatement { value })
+    }
+
     fn parse_while_statement(&mut self) -> Option<WhileStatement> {
         // the current token is 'while'
         if !self.expect_peek(TokenType::Lparen) {
@@ -235,7 +485,111 @@ impl Parser {
         Some(WhileStatement { condition, body })
     }
 
-    fn parse_for_statement(&mut self) -> Option<ForStatement> {
+    fn parse_switch_statement(&mut self) -> Option<SwitchStatement> {
+        // the current token is 'switch'
+        if !self.expect_peek(TokenType::Lparen) {
+            return None;
+        }
+
+        self.next_token(); // move to the first token inside '('
+        let subject = self.parse_expression(Precedence::Lowest)?;
+        if !self.expect_peek(TokenType::Rparen) {
+            return None;
+        }
+
+        if !self.expect_peek(TokenType::Lbrace) {
+            return None;
+        }
+        self.next_token(); // move to the first label
+
+        let mut cases = Vec::new();
+        let mut default = None;
+        while self.cur_token.token_type != TokenType::Rbrace
+            && self.cur_token.token_type != TokenType::Eof
+        {
+            if self.cur_token.token_type == TokenType::Case {
+                let mut values = Vec::new();
+                loop {
+                    self.next_token();
+                    let Some(value) = self.parse_expression(Precedence::Lowest) else {
+                        self.error(format!(
+                            "expected a value after case, got {:?} instead",
+                            self.cur_token.token_type
+                        ));
+                        return None;
+                    };
+                    values.push(value);
+                    if self.peek_token.token_type != TokenType::Comma {
+                        break;
+                    }
+                    self.next_token();
+                }
+                if !self.expect_peek(TokenType::Colon) {
+                    return None;
+                }
+                let body = self.parse_switch_case_body();
+                cases.push(SwitchCase { values, body });
+            } else if self.at_default_label() {
+                self.next_token(); // cur_token is now ':'
+                let body = self.parse_switch_case_body();
+                if default.replace(body).is_some() {
+                    self.errors
+                        .push("switch has more than one default".to_string());
+                }
+            } else {
+                self.error(format!(
+                    "expected case or default in switch, got {:?} instead",
+                    self.cur_token.token_type
+                ));
+                self.next_token();
+            }
+        }
+
+        Some(SwitchStatement {
+            subject,
+            cases,
+            default,
+        })
+    }
+
+    /// `default` is only a keyword as a switch label, so it stays usable as a
+    /// variable or property name elsewhere.
+    fn at_default_label(&self) -> bool {
+        self.cur_token.token_type == TokenType::Ident
+            && self.cur_token.literal == "default"
+            && self.peek_token.token_type == TokenType::Colon
+    }
+
+    /// Statements after a `case ...:` or `default:` label, up to the next
+    /// label or the closing brace of the switch.
+    fn parse_switch_case_body(&mut self) -> BlockStatement {
+        // the current token is ':'
+        let mut body = BlockStatement {
+            statements: Vec::new(),
+            lines: Vec::new(),
+            end_line: 0,
+            scope: Default::default(),
+        };
+        self.next_token();
+
+        while !matches!(
+            self.cur_token.token_type,
+            TokenType::Case | TokenType::Rbrace | TokenType::Eof
+        ) && !self.at_default_label()
+        {
+            let line = self.cur_line;
+            if let Some(stmt) = self.parse_statement() {
+                body.statements.push(stmt);
+                body.lines.push(line);
+            }
+            self.next_token();
+        }
+
+        body.end_line = self.cur_line;
+        body
+    }
+
+    fn parse_for_statement(&mut self) -> Option<Statement> {
         // the current token is 'for'
         if !self.expect_peek(TokenType::Lparen) {
             return None;
@@ -246,6 +600,14 @@ impl Parser {
         // Move to the first token of init or ';'
         self.next_token();
 
+        // `for (x in iterable) { ... }`; `in` is only special here.
+        if self.cur_token.token_type == TokenType::Ident
+            && self.peek_token.token_type == TokenType::Ident
+            && self.peek_token.literal == "in"
+        {
+            return self.parse_for_in_statement().map(Statement::ForIn);
+        }
+
         let init: Option<Box<Statement>> = if self.cur_token.token_type == TokenType::Semicolon {
             // for (; cond; post)
             None
@@ -312,12 +674,31 @@ impl Parser {
         }
         let body = self.parse_block_statement()?;
 
-        Some(ForStatement {
+        Some(Statement::For(ForStatement {
             init,
             condition,
             post,
             body,
-        })
+        }))
+    }
+
+    /// Parse the rest of `for (name in iterable) { ... }` with the current
+    /// token on `name`.
+    fn parse_for_in_statement(&mut self) -> Option<ForInStatement> {
+        let name = Identifier::new(self.cur_token.literal.clone());
+        self.next_token(); // 'in'
+        self.next_token();
+        let iterable = self.parse_expression(Precedence::Lowest)?;
+
+        if !self.expect_peek(TokenType::Rparen) {
+            return None;
+        }
+        if !self.expect_peek(TokenType::Lbrace) {
+            return None;
+        }
+        let body = self.parse_block_statement()?;
+
+        Some(ForInStatement { name, iterable, body })
     }
 
     fn parse_function_statement(&mut self) -> Option<FunctionStatement> {
@@ -328,19 +709,29 @@ impl Parser {
         &mut self,
         tags: Vec<String>,
     ) -> Option<FunctionStatement> {
+        let generator = self.cur_token_is_generator();
         if !self.expect_peek(TokenType::Ident) {
             return None;
         }
 
-        let name = Identifier {
-            value: self.cur_token.literal.clone(),
-        };
+        self.parse_function_statement_from_name(tags, generator)
+    }
+
+    /// Parse `name(params) [requires/ensures ...] { body }` with the current
+    /// token on `name`.
+    fn parse_function_statement_from_name(
+        &mut self,
+        tags: Vec<String>,
+        generator: bool,
+    ) -> Option<FunctionStatement> {
+        let name = Identifier::new(self.cur_token.literal.clone());
 
         if !self.expect_peek(TokenType::Lparen) {
             return None;
         }
 
-        let params = self.parse_function_parameters()?;
+        let (params, defaults, rest) = self.parse_function_parameters()?;
+        let (requires, ensures) = self.parse_function_contracts()?;
 
         if !self.expect_peek(TokenType::Lbrace) {
             return None;
@@ -350,7 +741,16 @@ impl Parser {
 
         Some(FunctionStatement {
             name,
-            literal: FunctionLiteral { params, body },
+            literal: FunctionLiteral {
+                params,
+                defaults,
+                rest,
+                requires,
+                ensures,
+                body,
+                generator,
+                arrow: false,
+            },
             tags,
         })
     }
@@ -373,15 +773,44 @@ impl Parser {
         Some(TestStatement { name, body })
     }
 
+    fn parse_describe_statement(&mut self) -> Option<DescribeStatement> {
+        // current token is 'describe'
+        self.next_token();
+        let name = self.cur_token.literal.clone();
+
+        if !self.expect_peek(TokenType::Lbrace) {
+            return None;
+        }
+
+        let body = self.parse_block_statement()?;
+
+        Some(DescribeStatement { name, body })
+    }
+
+    fn cur_hook_kind(&self) -> Option<HookKind> {
+        match self.cur_token.literal.as_str() {
+            "beforeEach" => Some(HookKind::BeforeEach),
+            "afterEach" => Some(HookKind::AfterEach),
+            _ => None,
+        }
+    }
+
+    fn parse_hook_statement(&mut self) -> Option<HookStatement> {
+        // current token is 'beforeEach' or 'afterEach'
+        let kind = self.cur_hook_kind()?;
+        self.next_token();
+        let body = self.parse_block_statement()?;
+
+        Some(HookStatement { kind, body })
+    }
+
     fn parse_class_statement(&mut self) -> Option<ClassStatement> {
         // current token is 'class'
         if !self.expect_peek(TokenType::Ident) {
             return None;
         }
 
-        let name = Identifier {
-            value: self.cur_token.literal.clone(),
-        };
+        let name = Identifier::new(self.cur_token.literal.clone());
 
         if !self.expect_peek(TokenType::Lbrace) {
             return None;
@@ -389,23 +818,42 @@ impl Parser {
 
         // Parse methods until closing brace
         let mut methods = Vec::new();
+        let mut lines = Vec::new();
         self.next_token(); // move past '{'
 
         while self.cur_token.token_type != TokenType::Rbrace
             && self.cur_token.token_type != TokenType::Eof
         {
-            if self.cur_token.token_type == TokenType::Function {
-                // Check if next token is an identifier (named function)
-                if self.peek_token.token_type == TokenType::Ident {
-                    if let Some(func) = self.parse_function_statement() {
-                        methods.push(func);
-                    }
+            let line = self.cur_line;
+            // Methods can be written `function name() {}`, `method name() {}`
+            // or just `name() {}`; `method` is contextual, like `from`.
+            let is_function = self.cur_token.token_type == TokenType::Function
+                || (self.cur_token.token_type == TokenType::Ident
+                    && self.cur_token.literal == "method"
+                    && self.peek_token.token_type == TokenType::Ident);
+
+            if is_function {
+                if let Some(func) = self.parse_function_statement() {
+                    methods.push(func);
+                    lines.push(line);
+                }
+            } else if self.cur_token.token_type == TokenType::Ident
+                && self.peek_token.token_type == TokenType::Lparen
+            {
+                if let Some(func) = self.parse_function_statement_from_name(Vec::new(), false) {
+                    methods.push(func);
+                    lines.push(line);
                 }
+            } else if self.cur_token.token_type != TokenType::Semicolon {
+                self.error(format!(
+                    "expected a method declaration in class {}, got {:?} instead",
+                    name.value, self.cur_token.literal
+                ));
             }
             self.next_token();
         }
 
-        Some(ClassStatement { name, methods })
+        Some(ClassStatement { name, methods, lines, end_line: self.cur_line })
     }
 
     fn parse_tagged_function_statement(&mut self) -> Option<FunctionStatement> {
@@ -488,7 +936,7 @@ impl Parser {
             TokenType::Colon => self.parse_single_tag(),
             TokenType::Lparen => self.parse_tag_group_from_parens(),
             _ => {
-                self.errors.push(format!(
+                self.error(format!(
                     "expected tag list starting with ':' or '(', got {:?}",
                     self.cur_token.token_type
                 ));
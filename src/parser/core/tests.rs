@@ -18,7 +18,7 @@ fn test_let_statements() {
 
     assert_eq!(program.statements.len(), 3);
 
-    let names = vec!["x", "y", "foobar"];
+    let names = ["x", "y", "foobar"];
 
     for (i, name) in names.iter().enumerate() {
         match &program.statements[i] {
@@ -28,6 +28,42 @@ fn test_let_statements() {
     }
 }
 
+#[test]
+fn test_const_statements() {
+    let input = "let x = 5; const LIMIT = 10; export const NAME = \"app\";";
+
+    let l = Lexer::new(input);
+    let mut p = Parser::new(l);
+    let program = p.parse_program();
+    check_errors(&p);
+
+    assert_eq!(program.to_string(), "let x = 5;const LIMIT = 10;export const NAME = \"app\";");
+    match &program.statements[1] {
+        Statement::Let(ls) => assert!(ls.constant && ls.name.value == "LIMIT"),
+        other => panic!("expected a const LetStatement, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_use_statements() {
+    let tests = vec![
+        ("use Geo;", "use Geo;"),
+        ("use Geo::Units as U", "use Geo::Units as U;"),
+        ("use Math::*;", "use Math::*;"),
+        ("use A::B::c as d;", "use A::B::c as d;"),
+    ];
+    for (input, expected) in tests {
+        let mut p = Parser::new(Lexer::new(input));
+        let program = p.parse_program();
+        check_errors(&p);
+        assert_eq!(program.to_string(), expected, "input: {}", input);
+    }
+
+    let mut p = Parser::new(Lexer::new("use Math::* as M;"));
+    p.parse_program();
+    assert_eq!(p.errors, vec!["`use ...::*` can't be renamed with `as`".to_string()]);
+}
+
 #[test]
 fn test_operator_precedence_parsing() {
     let tests = vec![
@@ -40,6 +76,20 @@ fn test_operator_precedence_parsing() {
         ("++x;", "(++x)"),
         ("x++ + 1;", "((x++) + 1)"),
         ("++x + 1;", "((++x) + 1)"),
+        ("f(x)? + 1;", "((f(x)?) + 1)"),
+        ("-x?;", "(-(x?))"),
+        ("a |> f(1) |> g;", "((a |> f(1)) |> g)"),
+        ("x = a + 1 |> f;", "(x = ((a + 1) |> f))"),
+        ("a div b * c;", "((a div b) * c)"),
+        ("1 << 2 + 1;", "(1 << (2 + 1))"),
+        ("a & b | c ^ d;", "((a & b) | (c ^ d))"),
+        ("x & 1 == 0;", "((x & 1) == 0)"),
+        ("~x & y;", "((~x) & y)"),
+        ("f(div) div div;", "(f(div) div div)"),
+        ("2 ** 3 ** 2;", "(2 ** (3 ** 2))"),
+        ("-2 ** 2;", "(-(2 ** 2))"),
+        ("a * b ** c;", "(a * (b ** c))"),
+        ("2 ** -x;", "(2 ** (-x))"),
     ];
 
     for (input, expected) in tests {
@@ -95,6 +145,55 @@ fn test_import_statement_parsing() {
     }
 }
 
+#[test]
+fn test_named_import_and_export_parsing() {
+    let input = r#"
+        import { Foo, bar } from "foo.sl";
+        export let answer = 42;
+        export function helper() { 1; }
+        export { answer, helper };
+    "#;
+
+    let l = Lexer::new(input);
+    let mut p = Parser::new(l);
+    let program = p.parse_program();
+    check_errors(&p);
+
+    assert_eq!(program.statements.len(), 4);
+    match &program.statements[0] {
+        Statement::Import(is) => {
+            assert_eq!(is.path, "foo.sl");
+            let names: Vec<&str> = is.names.iter().map(|n| n.value.as_str()).collect();
+            assert_eq!(names, vec!["Foo", "bar"]);
+        }
+        other => panic!("expected Import statement, got {:?}", other),
+    }
+
+    let exported: Vec<Vec<String>> = program.statements[1..]
+        .iter()
+        .map(|stmt| match stmt {
+            Statement::Export(es) => es.names.iter().map(|n| n.value.clone()).collect(),
+            other => panic!("expected Export statement, got {:?}", other),
+        })
+        .collect();
+    assert_eq!(
+        exported,
+        vec![
+            vec!["answer".to_string()],
+            vec!["helper".to_string()],
+            vec!["answer".to_string(), "helper".to_string()],
+        ]
+    );
+}
+
+#[test]
+fn test_export_rejects_non_declarations() {
+    let l = Lexer::new("export 1 + 2;");
+    let mut p = Parser::new(l);
+    p.parse_program();
+    assert!(!p.errors.is_empty(), "expected a parse error for `export 1 + 2;`");
+}
+
 #[test]
 fn test_class_statement_parsing() {
     let input = r#"
@@ -153,6 +252,268 @@ fn test_class_with_constructor_parsing() {
     }
 }
 
+#[test]
+fn test_class_method_shorthand_parsing() {
+    let input = r#"
+        class Point {
+            init(x, y) { this.x = x; }
+            method area() { this.x; }
+            function old() { 0; }
+        }
+    "#;
+
+    let l = Lexer::new(input);
+    let mut p = Parser::new(l);
+    let program = p.parse_program();
+    check_errors(&p);
+
+    match &program.statements[0] {
+        Statement::Class(cs) => {
+            let names: Vec<&str> = cs.methods.iter().map(|m| m.name.value.as_str()).collect();
+            assert_eq!(names, vec!["init", "area", "old"]);
+            assert_eq!(cs.methods[0].literal.params.len(), 2);
+        }
+        other => panic!("expected Class statement, got {:?}", other),
+    }
+
+    let mut p = Parser::new(Lexer::new("class Bad { let x = 1; }"));
+    p.parse_program();
+    assert!(!p.errors.is_empty(), "stray statements in a class body should be reported");
+}
+
+#[test]
+fn test_destructuring_let_parsing() {
+    let tests = vec![
+        ("let [a, b, ...rest] = xs;", "let [a, b, ...rest] = xs;"),
+        ("let [a, b,] = xs;", "let [a, b] = xs;"),
+        ("let { x, y } = point;", "let { x, y } = point;"),
+    ];
+
+    for (input, expected) in tests {
+        let mut p = Parser::new(Lexer::new(input));
+        let program = p.parse_program();
+        check_errors(&p);
+        assert_eq!(program.statements.len(), 1);
+        assert_eq!(program.statements[0].to_string(), expected);
+    }
+
+    let mut p = Parser::new(Lexer::new("let [...rest, a] = xs;"));
+    p.parse_program();
+    assert!(!p.errors.is_empty(), "a rest binding must come last");
+}
+
+#[test]
+fn test_default_and_rest_parameter_parsing() {
+    let tests = vec![
+        ("fn(a, b = 10, ...rest) { a; };", "fn(a, b = 10, ...rest) {a}"),
+        ("fn(...all) { all; };", "fn(...all) {all}"),
+        ("function f(x = 1 + 2) { x; }", "function f(x = (1 + 2)) {x}"),
+    ];
+
+    for (input, expected) in tests {
+        let mut p = Parser::new(Lexer::new(input));
+        let program = p.parse_program();
+        check_errors(&p);
+        assert_eq!(program.statements[0].to_string(), expected);
+    }
+
+    for input in ["fn(a = 1, b) { a; };", "fn(...rest, a) { a; };"] {
+        let mut p = Parser::new(Lexer::new(input));
+        p.parse_program();
+        assert!(!p.errors.is_empty(), "expected parse error for {}", input);
+    }
+}
+
+#[test]
+fn test_arrow_function_parsing() {
+    let tests = vec![
+        ("x => x + 1;", "fn(x) {(x + 1)}"),
+        ("(a, b = 2, ...rest) => a;", "fn(a, b = 2, ...rest) {a}"),
+        ("() => { let y = 1; y; };", "fn() {let y = 1;y}"),
+        ("a => b => a + b;", "fn(a) {fn(b) {(a + b)}}"),
+        ("f(x => x, 1);", "f(fn(x) {x}, 1)"),
+        ("(a) + (b, c) => 1;", "(a + fn(b, c) {1})"),
+        ("(a, b = f(1, (2))) => a;", "fn(a, b = f(1, 2)) {a}"),
+    ];
+
+    for (input, expected) in tests {
+        let mut p = Parser::new(Lexer::new(input));
+        let program = p.parse_program();
+        check_errors(&p);
+        assert_eq!(program.statements[0].to_string(), expected);
+    }
+
+    for input in ["(a + b) => a;", "(1) => 1;", "(a, b);"] {
+        let mut p = Parser::new(Lexer::new(input));
+        p.parse_program();
+        assert!(!p.errors.is_empty(), "expected parse error for {}", input);
+    }
+}
+
+#[test]
+fn test_optional_chaining_and_coalesce_parsing() {
+    let tests = vec![
+        ("a?.b;", "a?.b"),
+        ("a?.b.c?.d;", "a?.b.c?.d"),
+        ("a?.f(1);", "a?.f(1)"),
+        ("a ?? b ?? c;", "((a ?? b) ?? c)"),
+        ("a || b ?? c;", "((a || b) ?? c)"),
+        ("x = a?.b ?? 1;", "(x = (a?.b ?? 1))"),
+    ];
+
+    for (input, expected) in tests {
+        let mut p = Parser::new(Lexer::new(input));
+        let program = p.parse_program();
+        check_errors(&p);
+        assert_eq!(program.statements[0].to_string(), expected);
+    }
+}
+
+#[test]
+fn test_switch_statement_parsing() {
+    let input = r#"
+        switch (x) {
+            case 1, 2: a; b;
+            default: c;
+            case "s":
+        }
+    "#;
+    let mut p = Parser::new(Lexer::new(input));
+    let program = p.parse_program();
+    check_errors(&p);
+    assert_eq!(program.statements.len(), 1);
+    assert_eq!(
+        program.statements[0].to_string(),
+        r#"switch (x) {case 1, 2: abcase "s": default: c}"#
+    );
+
+    // `default` is still an ordinary name outside switch labels.
+    let mut p = Parser::new(Lexer::new("let default = 1; o.default;"));
+    p.parse_program();
+    check_errors(&p);
+
+    for input in [
+        "switch (x) { 1; }",
+        "switch (x) { default: a; default: b; }",
+        "switch (x) { case: a; }",
+    ] {
+        let mut p = Parser::new(Lexer::new(input));
+        p.parse_program();
+        assert!(!p.errors.is_empty(), "expected parse error for {}", input);
+    }
+}
+
+#[test]
+fn test_number_literal_forms() {
+    let tests = vec![
+        ("0xFF;", "255"),
+        ("0Xff_ff;", "65535"),
+        ("0b1010;", "10"),
+        ("0o755;", "493"),
+        ("1_000_000;", "1000000"),
+        ("017;", "17"),
+        ("1.5e9;", "1500000000"),
+        ("2.5E-3;", "0.0025"),
+        ("1e3;", "1000"),
+        ("1_000.000_1;", "1000.0001"),
+    ];
+
+    for (input, expected) in tests {
+        let mut p = Parser::new(Lexer::new(input));
+        let program = p.parse_program();
+        check_errors(&p);
+        assert_eq!(program.statements[0].to_string(), expected, "input: {}", input);
+    }
+
+    for input in ["1__0;", "1_;", "0x_1;", "0b102;", "0x;", "0xFFFFFFFFFFFFFFFFF;"] {
+        let mut p = Parser::new(Lexer::new(input));
+        p.parse_program();
+        assert!(!p.errors.is_empty(), "expected parse error for {}", input);
+    }
+}
+
+#[test]
+fn test_byte_string_literals() {
+    let tests = vec![
+        (r#"b"abc";"#, r#"b"abc""#),
+        (r#"b"\x00\xFF\x7f";"#, r#"b"\x00\xff\x7f""#),
+        (r#"b"a\"b\\c\n";"#, r#"b"a\"b\\c\n""#),
+        (r#"b"";"#, r#"b"""#),
+    ];
+
+    for (input, expected) in tests {
+        let mut p = Parser::new(Lexer::new(input));
+        let program = p.parse_program();
+        check_errors(&p);
+        assert_eq!(program.statements[0].to_string(), expected, "input: {}", input);
+    }
+
+    for input in [r#"b"\x4";"#, r#"b"\xZZ";"#, r#"b"\q";"#, "b\"caf\u{e9}\";", r#"b"open"#] {
+        let mut p = Parser::new(Lexer::new(input));
+        p.parse_program();
+        assert!(!p.errors.is_empty(), "expected parse error for {}", input);
+    }
+}
+
+#[test]
+fn test_new_as_member_name() {
+    let tests = vec![
+        ("Bloom::new(10, 0.01);", "Bloom::new(10, 0.01)"),
+        ("f.new;", "f.new"),
+    ];
+
+    for (input, expected) in tests {
+        let mut p = Parser::new(Lexer::new(input));
+        let program = p.parse_program();
+        check_errors(&p);
+        assert_eq!(program.statements[0].to_string(), expected);
+    }
+}
+
+#[test]
+fn test_spread_parsing() {
+    let tests = vec![
+        ("f(...args);", "f(...args)"),
+        ("[1, ...xs, 2];", "[1, ...xs, 2]"),
+        ("new P(a, ...rest);", "new P(a, ...rest)"),
+        ("f(...Array::concat(a, b));", "f(...Array::concat(a, b))"),
+    ];
+
+    for (input, expected) in tests {
+        let mut p = Parser::new(Lexer::new(input));
+        let program = p.parse_program();
+        check_errors(&p);
+        assert_eq!(program.statements[0].to_string(), expected);
+    }
+
+    let mut p = Parser::new(Lexer::new("let x = ...xs;"));
+    p.parse_program();
+    assert!(!p.errors.is_empty(), "spread is only valid in lists");
+}
+
+#[test]
+fn test_contract_parsing() {
+    let tests = vec![
+        (
+            "function div(a, b) requires b != 0 { a / b; }",
+            "function div(a, b) requires (b != 0) {(a / b)}",
+        ),
+        (
+            "fn(x) requires x > 0 requires x < 10 ensures result >= x { x; };",
+            "fn(x) requires (x > 0) requires (x < 10) ensures (result >= x) {x}",
+        ),
+        // `requires` is still an ordinary name elsewhere
+        ("let requires = 1;", "let requires = 1;"),
+    ];
+
+    for (input, expected) in tests {
+        let mut p = Parser::new(Lexer::new(input));
+        let program = p.parse_program();
+        check_errors(&p);
+        assert_eq!(program.statements[0].to_string(), expected);
+    }
+}
+
 #[test]
 fn test_new_expression_parsing() {
     let tests = vec![
@@ -180,3 +541,50 @@ fn test_new_expression_parsing() {
         }
     }
 }
+
+#[test]
+fn test_generator_and_for_in_parsing() {
+    let tests = vec![
+        ("fn*() { yield 1; };", "fn*() {yield 1;}"),
+        ("function* gen(n) { yield n; }", "function* gen(n) {yield n;}"),
+        ("for (x in xs) { print(x); }", "for (x in xs) {print(x)}"),
+        // `in` is only special straight after the loop variable
+        ("let in = 1;", "let in = 1;"),
+    ];
+
+    for (input, expected) in tests {
+        let mut p = Parser::new(Lexer::new(input));
+        let program = p.parse_program();
+        check_errors(&p);
+        assert_eq!(program.statements[0].to_string(), expected);
+    }
+}
+
+#[test]
+fn test_statements_record_their_lines() {
+    let input = "let a = 1;\n\nbreakpoint;\nfunction f() {\n    let breakpoint = 2;\n    breakpoint;\n}\n";
+    let mut p = Parser::new(Lexer::new(input));
+    let program = p.parse_program();
+    check_errors(&p);
+
+    assert_eq!(program.lines, vec![1, 3, 4]);
+    assert!(matches!(program.statements[1], Statement::Breakpoint));
+    let Statement::Function(fs) = &program.statements[2] else {
+        panic!("expected Function statement, got {:?}", program.statements[2]);
+    };
+    let body = &fs.literal.body;
+    assert_eq!(body.lines, vec![5, 6]);
+    assert_eq!(body.statements[0].to_string(), "let breakpoint = 2;");
+    assert!(matches!(body.statements[1], Statement::Breakpoint));
+}
+
+#[test]
+fn test_errors_record_their_lines() {
+    let input = "let a = 1;\nlet = 2;\nlet b = 3;\nlet c = (4;\n";
+    let mut p = Parser::new(Lexer::new(input));
+    p.parse_program();
+
+    assert_eq!(p.errors.len(), p.error_lines.len());
+    assert_eq!(p.error_lines.first(), Some(&2), "{:?}", p.errors);
+    assert_eq!(p.error_lines.last(), Some(&4), "{:?}", p.errors);
+}
@@ -0,0 +1,2 @@
+pub mod core;
+pub use core::{enable, is_enabled, record, report, time};
@@ -0,0 +1,81 @@
+//! Startup profiling for `--profile-startup`.
+//!
+//! Phases are timed with [`time`] and collected per thread; nothing is
+//! recorded unless profiling has been enabled, so the calls are cheap to
+//! leave in place.
+
+use std::cell::{Cell, RefCell};
+use std::time::{Duration, Instant};
+
+thread_local! {
+    static ENABLED: Cell<bool> = const { Cell::new(false) };
+    static SPANS: RefCell<Vec<(String, Duration)>> = const { RefCell::new(Vec::new()) };
+}
+
+pub fn enable() {
+    ENABLED.with(|flag| flag.set(true));
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.with(|flag| flag.get())
+}
+
+/// Run `f`, recording how long it took under `label` when profiling is on.
+pub fn time<T>(label: &str, f: impl FnOnce() -> T) -> T {
+    if !is_enabled() {
+        return f();
+    }
+    let start = Instant::now();
+    let result = f();
+    record(label, start.elapsed());
+    result
+}
+
+pub fn record(label: &str, elapsed: Duration) {
+    if is_enabled() {
+        SPANS.with(|spans| spans.borrow_mut().push((label.to_string(), elapsed)));
+    }
+}
+
+/// A table of the recorded phases in the order they finished.
+///
+/// Phases can nest (namespaces are built during `eval`), so the rows aren't
+/// meant to be summed.
+pub fn report() -> String {
+    SPANS.with(|spans| {
+        let spans = spans.borrow();
+        let width = spans.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+        let mut out = String::from("startup profile:\n");
+        for (label, elapsed) in spans.iter() {
+            out.push_str(&format!(
+                "  {:<width$}  {:>9.3} ms\n",
+                label,
+                elapsed.as_secs_f64() * 1000.0,
+                width = width
+            ));
+        }
+        out
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{enable, report, time};
+    use crate::env::new_env;
+
+    #[test]
+    fn namespaces_are_built_on_first_lookup() {
+        enable();
+        let env = new_env();
+        assert!(!report().contains("namespace Math"));
+
+        let math = time("lookup", || env.borrow().get("Math"));
+        assert!(math.is_some());
+        let _ = env.borrow().get("Math");
+
+        let report = report();
+        assert_eq!(report.matches("namespace Math").count(), 1, "{}", report);
+        assert!(report.contains("lookup"));
+        assert!(!report.contains("namespace HTTP"));
+    }
+}
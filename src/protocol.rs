@@ -0,0 +1,3 @@
+mod core;
+
+pub(crate) use core::{read_message, write_message};
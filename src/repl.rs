@@ -0,0 +1,3 @@
+pub mod core;
+
+pub use core::{run_repl, is_input_complete};
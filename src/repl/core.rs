@@ -0,0 +1,259 @@
+//! Interactive read-eval-print loop.
+//!
+//! Input is read with line editing and persistent history. A statement that
+//! leaves a brace, paren or bracket (or a string) open continues on the next
+//! line, and lines starting with `:` are meta-commands rather than code.
+
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use rustyline::DefaultEditor;
+use rustyline::error::ReadlineError;
+
+use crate::env::EnvRef;
+use crate::evaluator::eval;
+use crate::lexer::{block_comment_end, string_end, Lexer};
+use crate::parser::Parser;
+
+const HISTORY_FILE: &str = ".slang_history";
+const CONTINUATION_PROMPT: &str = "...>> ";
+
+const HELP_TEXT: &str = "\
+Meta-commands:
+  :help          show this help
+  :env           list bindings defined in this session
+  :load <file>   evaluate a script file in the current session
+  :quit          leave the REPL (also `exit` / `quit` or Ctrl-D)
+
+Unclosed braces, parens, brackets or strings continue on the next line.
+Ctrl-C discards the current input.";
+
+/// Run the REPL against `env` until the user exits.
+pub fn run_repl(env: EnvRef) {
+    let mut editor = match DefaultEditor::new() {
+        Ok(editor) => editor,
+        Err(e) => {
+            eprintln!("failed to start line editor: {}", e);
+            return;
+        }
+    };
+
+    let history = history_path();
+    if let Some(path) = &history {
+        // A missing history file just means this is the first session.
+        let _ = editor.load_history(path);
+    }
+
+    let prompt = format!("Slang (ver: {})>> ", env!("CARGO_PKG_VERSION"));
+    let mut buffer = String::new();
+
+    loop {
+        let current_prompt = if buffer.is_empty() {
+            prompt.as_str()
+        } else {
+            CONTINUATION_PROMPT
+        };
+
+        let line = match editor.readline(current_prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => {
+                buffer.clear();
+                continue;
+            }
+            Err(ReadlineError::Eof) => break,
+            Err(e) => {
+                println!("failed to read input: {}", e);
+                break;
+            }
+        };
+
+        if buffer.is_empty() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            if matches!(trimmed, "exit" | "exit;" | "quit" | "quit;" | ":quit" | ":q") {
+                break;
+            }
+
+            if let Some(command) = trimmed.strip_prefix(':') {
+                let _ = editor.add_history_entry(trimmed);
+                run_meta_command(command, Rc::clone(&env));
+                continue;
+            }
+        }
+
+        if !buffer.is_empty() {
+            buffer.push('\n');
+        }
+        buffer.push_str(&line);
+
+        if !is_input_complete(&buffer) {
+            continue;
+        }
+
+        let input = std::mem::take(&mut buffer);
+        let _ = editor.add_history_entry(input.as_str());
+        eval_and_print(&input, Rc::clone(&env));
+    }
+
+    if let Some(path) = &history && let Err(e) = editor.save_history(path) {
+        eprintln!("failed to save history to {}: {}", path.display(), e);
+    }
+}
+
+fn eval_and_print(input: &str, env: EnvRef) {
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+    let program = parser.parse_program();
+
+    if !parser.errors.is_empty() {
+        for err in parser.errors {
+            println!("{}", err);
+        }
+        return;
+    }
+
+    println!("{}", eval(&program, env));
+}
+
+fn run_meta_command(command: &str, env: EnvRef) {
+    let (name, arg) = match command.split_once(char::is_whitespace) {
+        Some((name, arg)) => (name, arg.trim()),
+        None => (command, ""),
+    };
+
+    match name {
+        "help" | "h" => println!("{}", HELP_TEXT),
+        "env" => print_env(env),
+        "load" => {
+            if arg.is_empty() {
+                println!("usage: :load <file.sl>");
+            } else {
+                load_file(Path::new(arg), env);
+            }
+        }
+        other => println!("unknown command ':{}' (try :help)", other),
+    }
+}
+
+/// Print the session's bindings. Builtin namespaces live outside the
+/// snapshot until they're rebound, so only user definitions show up.
+fn print_env(env: EnvRef) {
+    let mut bindings: Vec<_> = env.borrow().snapshot().into_iter().collect();
+
+    if bindings.is_empty() {
+        println!("(no bindings)");
+        return;
+    }
+
+    bindings.sort_by(|a, b| a.0.cmp(&b.0));
+    for (name, value) in bindings {
+        println!("{} = {}", name, value);
+    }
+}
+
+fn load_file(path: &Path, env: EnvRef) {
+    let source = match std::fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(e) => {
+            println!("failed to read {}: {}", path.display(), e);
+            return;
+        }
+    };
+
+    // Imports inside the loaded file resolve relative to the file itself.
+    let previous_dir = env.borrow().module_dir();
+    env.borrow_mut()
+        .set_module_dir(path.parent().map(|p| p.to_path_buf()));
+    eval_and_print(&source, Rc::clone(&env));
+    env.borrow_mut().set_module_dir(previous_dir);
+}
+
+fn history_path() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(HISTORY_FILE))
+}
+
+/// Returns false while `input` still has an unclosed `{`, `(`, `[`, string
+/// literal or block comment, meaning the REPL should keep reading lines.
+pub fn is_input_complete(input: &str) -> bool {
+    let mut depth: i64 = 0;
+    let chars: Vec<char> = input.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '"' => match string_end(&chars, i) {
+                Some(end) => {
+                    i = end;
+                    continue;
+                }
+                None => return false,
+            },
+            '/' if chars.get(i + 1) == Some(&'/') => {
+                // line comment: skip to end of line
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => match block_comment_end(&chars, i) {
+                Some(end) => {
+                    i = end;
+                    continue;
+                }
+                None => return false,
+            },
+            '{' | '(' | '[' => depth += 1,
+            '}' | ')' | ']' => depth -= 1,
+            _ => {}
+        }
+        i += 1;
+    }
+
+    // Over-closed input is complete; the parser will report the error.
+    depth <= 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_input_complete;
+
+    #[test]
+    fn complete_single_line_input() {
+        assert!(is_input_complete("let x = 5;"));
+        assert!(is_input_complete("fn(x) { x * 2; }(3);"));
+    }
+
+    #[test]
+    fn open_delimiters_need_continuation() {
+        assert!(!is_input_complete("function add(a, b) {"));
+        assert!(!is_input_complete("let xs = [1, 2,"));
+        assert!(!is_input_complete("print(\"unterminated"));
+        assert!(is_input_complete("function add(a, b) {\n  a + b;\n}"));
+    }
+
+    #[test]
+    fn delimiters_in_strings_and_comments_are_ignored() {
+        assert!(is_input_complete("let s = \"{ not a block\";"));
+        assert!(is_input_complete("let x = 1; // {"));
+        assert!(is_input_complete(r#"let s = "a \" { b";"#));
+        assert!(is_input_complete(r#"let p = r"C:\";"#));
+    }
+
+    #[test]
+    fn multiline_strings_need_continuation() {
+        assert!(!is_input_complete("let s = \"\"\"\nfirst line"));
+        assert!(is_input_complete("let s = \"\"\"\nfirst line\n\"\"\";"));
+    }
+
+    #[test]
+    fn block_comments_need_continuation() {
+        assert!(!is_input_complete("/* a { comment"));
+        assert!(!is_input_complete("/* outer /* inner */ still open"));
+        assert!(is_input_complete("/* outer /* inner */ done */ let x = 1;"));
+        assert!(is_input_complete("let s = \"/* not a comment\";"));
+    }
+}
@@ -8,83 +8,335 @@ pub use crate::object::Object;
 pub use crate::evaluator::eval;
 pub use crate::builtins::get as get_builtin;
 
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::ast::nodes::HookKind;
 use crate::ast::{Program, Statement};
+use crate::builtins::native::test_builtins::{
+    assertion_count, begin_snapshots, finish_snapshots, reset_assertion_count,
+};
 use crate::env::new_env;
 
+/// Which tests [`run_tests_with`] runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TestOptions {
+    /// Only run tests whose full name (`suite > test`) contains this.
+    pub filter: Option<String>,
+    /// The file `Test::assertSnapshot` reads and records snapshots in.
+    /// Without one, snapshot assertions fail.
+    pub snapshots: Option<PathBuf>,
+    /// Re-record snapshots that don't match instead of failing.
+    pub update_snapshots: bool,
+}
+
+/// Outcome of one `test` block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestResult {
+    /// Names of the enclosing `describe` groups, outermost first.
+    pub suites: Vec<String>,
+    pub name: String,
+    /// Why the test failed, or `None` if it passed.
+    pub error: Option<String>,
+    /// `Test::` assertions the test made, including a failing one.
+    pub assertions: usize,
+    pub duration: Duration,
+}
+
+impl TestResult {
+    /// The test's name prefixed by its groups, e.g. `math > adds`.
+    pub fn full_name(&self) -> String {
+        full_name(&self.suites, &self.name)
+    }
+
+    pub fn passed(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
 /// Summary of running all `test` blocks in a program.
 #[derive(Debug, Clone, PartialEq)]
 pub struct TestRunSummary {
     pub output: String,
     pub total: usize,
     pub failed: usize,
+    pub results: Vec<TestResult>,
+}
+
+impl TestRunSummary {
+    /// `output` with ANSI colours for terminals: PASS green, FAIL red and
+    /// assertion diff lines coloured by kind.
+    pub fn colored(&self) -> String {
+        const GREEN: &str = "\x1b[32m";
+        const RED: &str = "\x1b[31m";
+        const YELLOW: &str = "\x1b[33m";
+        const RESET: &str = "\x1b[0m";
+
+        let mut out = String::new();
+        for line in self.output.lines() {
+            let color = if line.starts_with("PASS:") || line.starts_with("    + ") {
+                Some(GREEN)
+            } else if line.starts_with("FAIL:") || line.starts_with("    - ") {
+                Some(RED)
+            } else if line.starts_with("    ~ ") {
+                Some(YELLOW)
+            } else {
+                None
+            };
+            match color {
+                Some(color) => out.push_str(&format!("{}{}{}\n", color, line, RESET)),
+                None => out.push_str(&format!("{}\n", line)),
+            }
+        }
+        out
+    }
+
+    /// The results as a JSON document, for tools that read test output.
+    pub fn to_json(&self) -> String {
+        let tests: Vec<serde_json::Value> = self
+            .results
+            .iter()
+            .map(|r| {
+                serde_json::json!({
+                    "name": r.name,
+                    "suites": r.suites,
+                    "passed": r.passed(),
+                    "error": r.error,
+                    "assertions": r.assertions,
+                    "durationMs": r.duration.as_secs_f64() * 1000.0,
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "total": self.total,
+            "passed": self.total - self.failed,
+            "failed": self.failed,
+            "tests": tests,
+        })
+        .to_string()
+    }
+
+    /// The results as a JUnit XML report, as most CI systems expect. Each
+    /// test's `describe` groups become its `classname`.
+    pub fn to_junit(&self, suite_name: &str) -> String {
+        let time: f64 = self.results.iter().map(|r| r.duration.as_secs_f64()).sum();
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">\n",
+            xml_escape(suite_name),
+            self.total,
+            self.failed,
+            time
+        ));
+        for r in &self.results {
+            let classname = if r.suites.is_empty() { suite_name.to_string() } else { r.suites.join(" > ") };
+            xml.push_str(&format!(
+                "  <testcase classname=\"{}\" name=\"{}\" assertions=\"{}\" time=\"{:.3}\"",
+                xml_escape(&classname),
+                xml_escape(&r.name),
+                r.assertions,
+                r.duration.as_secs_f64()
+            ));
+            match &r.error {
+                None => xml.push_str("/>\n"),
+                Some(msg) => {
+                    let first_line = msg.lines().next().unwrap_or_default();
+                    xml.push_str(&format!(
+                        ">\n    <failure message=\"{}\">{}</failure>\n  </testcase>\n",
+                        xml_escape(first_line),
+                        xml_escape(msg)
+                    ));
+                }
+            }
+        }
+        xml.push_str("</testsuite>\n");
+        xml
+    }
+}
+
+fn full_name(suites: &[String], name: &str) -> String {
+    let mut parts = suites.to_vec();
+    parts.push(name.to_string());
+    parts.join(" > ")
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A test ready to run: everything its enclosing scopes contribute, in order.
+struct PlannedTest {
+    suites: Vec<String>,
+    name: String,
+    setup: Vec<Statement>,
+    before: Vec<Statement>,
+    body: Vec<Statement>,
+    after: Vec<Statement>,
+}
+
+/// What a file or `describe` group passes down to the tests inside it.
+#[derive(Clone, Default)]
+struct Scope {
+    suites: Vec<String>,
+    setup: Vec<Statement>,
+    before: Vec<Statement>,
+    after: Vec<Statement>,
+}
+
+/// Gather the tests in `statements`. Setup statements and hooks apply to
+/// every test at their level and below, wherever they appear; outer
+/// `beforeEach` hooks run first and outer `afterEach` hooks last.
+fn plan_tests(statements: &[Statement], outer: &Scope, out: &mut Vec<PlannedTest>) {
+    let mut scope = outer.clone();
+    let mut after = Vec::new();
+    for stmt in statements {
+        match stmt {
+            Statement::Test(_) | Statement::Describe(_) => {}
+            Statement::Hook(hs) if hs.kind == HookKind::BeforeEach => {
+                scope.before.extend(hs.body.statements.iter().cloned())
+            }
+            Statement::Hook(hs) => after.extend(hs.body.statements.iter().cloned()),
+            other => scope.setup.push(other.clone()),
+        }
+    }
+    after.append(&mut scope.after);
+    scope.after = after;
+
+    for stmt in statements {
+        match stmt {
+            Statement::Test(ts) => out.push(PlannedTest {
+                suites: scope.suites.clone(),
+                name: ts.name.clone(),
+                setup: scope.setup.clone(),
+                before: scope.before.clone(),
+                body: ts.body.statements.clone(),
+                after: scope.after.clone(),
+            }),
+            Statement::Describe(ds) => {
+                let mut inner = scope.clone();
+                inner.suites.push(ds.name.clone());
+                plan_tests(&ds.body.statements, &inner, out);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Run one test in a fresh environment. `afterEach` hooks run even when the
+/// test fails; a failing hook fails a test that had passed.
+fn run_planned(test: PlannedTest) -> TestResult {
+    reset_assertion_count();
+    let start = Instant::now();
+
+    let env = new_env();
+    let mut statements = test.setup;
+    statements.extend(test.before);
+    statements.extend(test.body);
+    let result = eval(&Program { statements, lines: Vec::new() }, Rc::clone(&env));
+    let after = if test.after.is_empty() {
+        Object::Null
+    } else {
+        eval(&Program { statements: test.after, lines: Vec::new() }, env)
+    };
+
+    let error = match (result, after) {
+        (Object::Error(msg), _) => Some(msg),
+        (_, Object::Error(msg)) => Some(format!("afterEach: {}", msg)),
+        _ => None,
+    };
+    TestResult {
+        suites: test.suites,
+        name: test.name,
+        error,
+        assertions: assertion_count(),
+        duration: start.elapsed(),
+    }
 }
 
 /// Run all `test "name" { ... }` blocks in the given program and return a
 /// textual report plus counts. Callers can decide whether to print the
 /// output, assert on it (in Rust tests), or ignore it.
 pub fn run_tests(program: &Program) -> TestRunSummary {
-    // Split program into setup statements and tests.
-    let mut setup_statements: Vec<Statement> = Vec::new();
-    let mut tests: Vec<(String, Vec<Statement>)> = Vec::new();
+    run_tests_with(program, &TestOptions::default())
+}
 
-    for stmt in &program.statements {
-        match stmt {
-            Statement::Test(ts) => {
-                tests.push((ts.name.clone(), ts.body.statements.clone()));
-            }
-            other => setup_statements.push(other.clone()),
-        }
+/// [`run_tests`], limited to the tests `options` selects.
+pub fn run_tests_with(program: &Program, options: &TestOptions) -> TestRunSummary {
+    let mut planned = Vec::new();
+    plan_tests(&program.statements, &Scope::default(), &mut planned);
+    let found = planned.len();
+    if let Some(filter) = &options.filter {
+        planned.retain(|t| full_name(&t.suites, &t.name).contains(filter.as_str()));
     }
 
-    // No tests: return a simple message.
-    if tests.is_empty() {
+    if planned.is_empty() {
+        let output = match &options.filter {
+            Some(filter) if found > 0 => format!("No tests match filter '{}'", filter),
+            _ => "No tests found".to_string(),
+        };
         return TestRunSummary {
-            output: "No tests found".to_string(),
+            output,
             total: 0,
             failed: 0,
+            results: Vec::new(),
         };
     }
+    let skipped = found - planned.len();
 
     use std::fmt::Write as _;
 
+    if let Some(path) = &options.snapshots {
+        begin_snapshots(path.clone(), options.update_snapshots);
+    }
     let mut buf = String::new();
-    let mut total = 0usize;
-    let mut failed = 0usize;
-
-    for (name, body_stmts) in tests {
-        total += 1;
-
-        // Build a synthetic program: setup statements followed by this test body.
-        let mut all_statements = setup_statements.clone();
-        all_statements.extend(body_stmts.clone());
-        let test_program = Program { statements: all_statements };
-
-        let env = new_env();
-        let result = eval(&test_program, env);
-
-        match result {
-            Object::Error(msg) => {
-                failed += 1;
-                let _ = writeln!(buf, "FAIL: {} - {}", name, msg);
+    let mut results = Vec::with_capacity(planned.len());
+    for test in planned {
+        let result = run_planned(test);
+        let count = match result.assertions {
+            1 => "1 assertion".to_string(),
+            n => format!("{} assertions", n),
+        };
+        let _ = match &result.error {
+            Some(msg) => writeln!(buf, "FAIL: {} ({}) - {}", result.full_name(), count, msg),
+            None => writeln!(buf, "PASS: {} ({})", result.full_name(), count),
+        };
+        results.push(result);
+    }
+    if let Some(path) = &options.snapshots {
+        match finish_snapshots() {
+            Ok(0) => {}
+            Ok(n) => {
+                let _ = writeln!(buf, "Wrote {} snapshot(s) to {}", n, path.display());
             }
-            _ => {
-                let _ = writeln!(buf, "PASS: {}", name);
+            Err(e) => {
+                let _ = writeln!(buf, "{}", e);
             }
         }
     }
 
+    let total = results.len();
+    let failed = results.iter().filter(|r| !r.passed()).count();
     let passed = total - failed;
     let _ = writeln!(buf);
-    let _ = writeln!(
+    let _ = write!(
         buf,
         "Test results: {}/{} passed, {} failed",
         passed, total, failed
     );
+    if skipped > 0 {
+        let _ = write!(buf, " ({} filtered out)", skipped);
+    }
+    let _ = writeln!(buf);
 
     TestRunSummary {
         output: buf,
         total,
         failed,
+        results,
     }
 }
 
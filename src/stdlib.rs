@@ -0,0 +1,2 @@
+pub mod core;
+pub use core::{is_enabled, names, set_enabled};
@@ -0,0 +1,75 @@
+//! The prelude: parts of the standard library written in Slang.
+//!
+//! Each namespace's source is embedded in the binary with `include_str!`.
+//! Like the builtin namespaces, a prelude namespace is bound in every
+//! top-level environment but only evaluated when a script first uses it. A
+//! prelude namespace that shares its name with a builtin one (such as `Fn`)
+//! re-opens it, so scripts see both sets of members.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::capability::Capabilities;
+use crate::debug::debugger;
+use crate::env::EnvBuilder;
+use crate::evaluator::eval;
+use crate::lexer::Lexer;
+use crate::object::{Object, ObjectMap};
+use crate::parser::Parser;
+
+/// Each prelude namespace and its source.
+const SOURCES: &[(&str, &str)] = &[
+    ("Fn", include_str!("fn.sl")),
+    ("Queue", include_str!("queue.sl")),
+];
+
+thread_local! {
+    static ENABLED: Cell<bool> = const { Cell::new(true) };
+}
+
+/// Turn the prelude off (`--no-prelude`) or back on for environments built
+/// from now on.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.with(|flag| flag.set(enabled));
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.with(|flag| flag.get())
+}
+
+/// The namespaces the prelude defines.
+pub fn names() -> impl Iterator<Item = &'static str> {
+    SOURCES.iter().map(|(name, _)| *name)
+}
+
+/// Evaluate the prelude source of the namespace `name` and return its
+/// members, or `None` if the prelude doesn't define it. The code runs with
+/// `capabilities`, like the script that uses it.
+pub(crate) fn load(name: &str, capabilities: Capabilities) -> Option<ObjectMap> {
+    let (_, source) = SOURCES.iter().find(|(ns, _)| *ns == name)?;
+    let mut parser = Parser::new(Lexer::new(source));
+    let program = parser.parse_program();
+    assert!(parser.errors.is_empty(), "prelude namespace {} doesn't parse: {:?}", name, parser.errors);
+
+    let env = EnvBuilder::new().capabilities(capabilities).without_prelude().build();
+    let result = debugger::suspended(|| eval(&program, Rc::clone(&env)));
+    assert!(!result.is_error(), "prelude namespace {} failed: {}", name, result);
+    match env.borrow().get(name) {
+        Some(Object::Object(members)) => Some(Rc::unwrap_or_clone(members)),
+        other => panic!("prelude source for {} defines {:?} instead", name, other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load, names};
+    use crate::capability::Capabilities;
+
+    #[test]
+    fn every_prelude_namespace_loads() {
+        for name in names() {
+            let members = load(name, Capabilities::default()).unwrap();
+            assert!(!members.is_empty(), "{} has no members", name);
+        }
+    }
+}
@@ -0,0 +1,508 @@
+//! Tasks and channels behind the `Task` and `Channel` namespaces.
+//!
+//! Slang values are reference counted and not thread-safe, so a task doesn't
+//! share its spawner's interpreter: it runs on its own OS thread in a fresh
+//! environment with the same capabilities. Everything that crosses a thread
+//! (the task's function and arguments, its result, values sent on a channel)
+//! is copied through [`Sendable`]. A function is sent as its code together
+//! with a snapshot of the bindings it can see, so helpers, constants and
+//! namespaces defined by the script are available to the task, but changes it
+//! makes to them are not seen by the spawner. Refs, files, seqs, bloom
+//! filters and host functions are tied to the thread that made them and can't
+//! be sent.
+//!
+//! Task and channel handles are integers, like timer handles, and are valid
+//! on every thread, so a channel can be passed to the tasks that use it.
+
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::File;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Condvar, LazyLock, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::ast::{BlockStatement, Expression, Identifier};
+use crate::capability::{self, Capabilities};
+use crate::env::{EnvBuilder, EnvRef};
+use crate::evaluator::core::expr::apply_function_with_this;
+use crate::object::types::BuiltinFunction;
+use crate::object::{FunctionData, Object};
+#[cfg(feature = "cache")]
+use crate::cache;
+use crate::{event_loop, stdlib};
+
+/// How long a blocked `join` or `recv` waits between checks for a signal.
+const SIGNAL_POLL: Duration = Duration::from_millis(50);
+
+/// A copy of a Slang value that can be moved to another thread.
+#[derive(Debug, Clone)]
+pub(crate) enum Sendable {
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    String(String),
+    Bytes(Vec<u8>),
+    Array(Vec<Sendable>),
+    Object(Vec<(String, Sendable)>),
+    Function {
+        name: Option<String>,
+        params: Vec<Identifier>,
+        defaults: Vec<Option<Expression>>,
+        rest: Option<Identifier>,
+        requires: Vec<Expression>,
+        ensures: Vec<Expression>,
+        body: BlockStatement,
+        generator: bool,
+    },
+    Builtin(BuiltinFunction),
+    Class {
+        name: String,
+        methods: Vec<(String, Sendable)>,
+    },
+    Error(String),
+    OptionSome(Box<Sendable>),
+    OptionNone,
+    ResultOk(Box<Sendable>),
+    ResultErr(Box<Sendable>),
+    Null,
+}
+
+impl Sendable {
+    /// Copy `obj`, or name the kind of value that can't leave its thread.
+    pub(crate) fn from_object(obj: &Object) -> Result<Sendable, String> {
+        let entries = |map: &crate::object::ObjectMap| {
+            map.iter()
+                .map(|(k, v)| Ok((k.clone(), Sendable::from_object(v)?)))
+                .collect::<Result<Vec<_>, String>>()
+        };
+        Ok(match obj {
+            Object::Integer(i) => Sendable::Integer(*i),
+            Object::Float(f) => Sendable::Float(*f),
+            Object::Boolean(b) => Sendable::Boolean(*b),
+            Object::String(s) => Sendable::String(s.clone()),
+            Object::Bytes(b) => Sendable::Bytes(b.to_vec()),
+            Object::Array(items) => Sendable::Array(
+                items.iter().map(Sendable::from_object).collect::<Result<_, _>>()?,
+            ),
+            Object::Object(map) => Sendable::Object(entries(map)?),
+            Object::Function(function) => Sendable::Function {
+                name: function.name.clone(),
+                params: function.params.clone(),
+                defaults: function.defaults.clone(),
+                rest: function.rest.clone(),
+                requires: function.requires.clone(),
+                ensures: function.ensures.clone(),
+                body: function.body.clone(),
+                generator: function.generator,
+            },
+            Object::Builtin(f) => Sendable::Builtin(*f),
+            Object::Class { name, methods } => Sendable::Class {
+                name: name.clone(),
+                methods: entries(methods)?,
+            },
+            Object::ReturnValue(inner) => Sendable::from_object(inner)?,
+            Object::Error(msg) => Sendable::Error(msg.clone()),
+            Object::OptionSome(inner) => Sendable::OptionSome(Box::new(Sendable::from_object(inner)?)),
+            Object::OptionNone => Sendable::OptionNone,
+            Object::ResultOk(inner) => Sendable::ResultOk(Box::new(Sendable::from_object(inner)?)),
+            Object::ResultErr(inner) => Sendable::ResultErr(Box::new(Sendable::from_object(inner)?)),
+            Object::Null => Sendable::Null,
+            Object::NativeFn(_) | Object::File(_) | Object::Bloom(_) | Object::Ref(_) | Object::Seq(_) => {
+                return Err(obj.type_name().to_string())
+            }
+        })
+    }
+
+    /// Rebuild the value on this thread; functions close over `env`.
+    pub(crate) fn into_object(self, env: &EnvRef) -> Object {
+        let map = |entries: Vec<(String, Sendable)>| {
+            entries.into_iter().map(|(k, v)| (k, v.into_object(env))).collect()
+        };
+        match self {
+            Sendable::Integer(i) => Object::Integer(i),
+            Sendable::Float(f) => Object::Float(f),
+            Sendable::Boolean(b) => Object::Boolean(b),
+            Sendable::String(s) => Object::String(s),
+            Sendable::Bytes(b) => Object::Bytes(Rc::new(b)),
+            Sendable::Array(items) => {
+                Object::Array(Rc::new(items.into_iter().map(|v| v.into_object(env)).collect()))
+            }
+            Sendable::Object(entries) => Object::Object(Rc::new(map(entries))),
+            Sendable::Function { name, params, defaults, rest, requires, ensures, body, generator } => {
+                Object::Function(Rc::new(FunctionData {
+                    name,
+                    params,
+                    defaults,
+                    rest,
+                    requires,
+                    ensures,
+                    body,
+                    env: Rc::clone(env),
+                    generator,
+                }))
+            }
+            Sendable::Builtin(f) => Object::Builtin(f),
+            Sendable::Class { name, methods } => Object::Class { name, methods: map(methods) },
+            Sendable::Error(msg) => Object::Error(msg),
+            Sendable::OptionSome(inner) => Object::OptionSome(Box::new(inner.into_object(env))),
+            Sendable::OptionNone => Object::OptionNone,
+            Sendable::ResultOk(inner) => Object::ResultOk(Box::new(inner.into_object(env))),
+            Sendable::ResultErr(inner) => Object::ResultErr(Box::new(inner.into_object(env))),
+            Sendable::Null => Object::Null,
+        }
+    }
+}
+
+/// The bindings visible from `env`, innermost first. Values that can't be
+/// sent are left out (a task that uses one gets an unbound identifier), or
+/// with `complete` set, make the whole snapshot `None`.
+fn visible_bindings(env: &EnvRef, complete: bool) -> Option<Vec<(String, Sendable)>> {
+    let mut seen = HashSet::new();
+    let mut out = Vec::new();
+    let mut scope = Some(Rc::clone(env));
+    while let Some(current) = scope {
+        for (name, value) in current.borrow().snapshot() {
+            if seen.insert(name.clone()) {
+                match Sendable::from_object(&value) {
+                    Ok(value) => out.push((name, value)),
+                    Err(_) if complete => return None,
+                    Err(_) => {}
+                }
+            }
+        }
+        scope = current.borrow().outer();
+    }
+    Some(out)
+}
+
+/// Thread-local interpreter settings a task inherits from its spawner.
+struct Settings {
+    capabilities: Capabilities,
+    prelude: bool,
+    module_dir: Option<PathBuf>,
+    dry_run: bool,
+    audit_log: Option<File>,
+    #[cfg(feature = "cache")]
+    cache: bool,
+    stdlib: bool,
+}
+
+impl Settings {
+    fn current(env: &EnvRef) -> Settings {
+        let env = env.borrow();
+        Settings {
+            capabilities: env.capabilities(),
+            prelude: env.has_prelude(),
+            module_dir: env.module_dir(),
+            dry_run: capability::is_dry_run(),
+            audit_log: capability::audit_log(),
+            #[cfg(feature = "cache")]
+            cache: cache::is_enabled(),
+            stdlib: stdlib::is_enabled(),
+        }
+    }
+
+    /// Apply the settings to this thread and build the task's environment,
+    /// with `bindings` bound in it.
+    fn install(self, bindings: Vec<(String, Sendable)>) -> EnvRef {
+        if self.dry_run {
+            capability::enable_dry_run();
+        }
+        capability::set_audit_log(self.audit_log);
+        #[cfg(feature = "cache")]
+        cache::set_enabled(self.cache);
+        stdlib::set_enabled(self.stdlib);
+        IN_TASK.with(|flag| flag.set(true));
+        let mut builder = EnvBuilder::new().capabilities(self.capabilities);
+        if !self.prelude {
+            builder = builder.without_prelude();
+        }
+        let env = builder.build();
+        env.borrow_mut().set_module_dir(self.module_dir);
+        for (name, value) in bindings {
+            let value = value.into_object(&env);
+            env.borrow_mut().set(name, value);
+        }
+        env
+    }
+}
+
+type TaskResult = Result<Sendable, String>;
+
+struct TaskEntry {
+    result: mpsc::Receiver<TaskResult>,
+    thread: JoinHandle<()>,
+}
+
+static NEXT_ID: AtomicI64 = AtomicI64::new(1);
+static TASKS: LazyLock<Mutex<HashMap<i64, TaskEntry>>> = LazyLock::new(Default::default);
+static CHANNELS: LazyLock<Mutex<HashMap<i64, Arc<Channel>>>> = LazyLock::new(Default::default);
+
+thread_local! {
+    /// Set on task threads, which leave signal handling to the main thread.
+    static IN_TASK: Cell<bool> = const { Cell::new(false) };
+}
+
+fn next_id() -> i64 {
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+fn check_signal(env: &EnvRef) {
+    if !IN_TASK.with(|flag| flag.get()) {
+        event_loop::check_signal(env);
+    }
+}
+
+/// Run `callback(args...)` on a new thread. Returns the task's handle.
+pub(crate) fn spawn(callback: &Object, args: &[Object], env: &EnvRef) -> Result<i64, String> {
+    let function = Sendable::from_object(callback)
+        .map_err(|kind| format!("Task::spawn expects a function, got {}", kind))?;
+    let closure_env = match callback {
+        Object::Function(function) => Rc::clone(&function.env),
+        _ => Rc::clone(env),
+    };
+    let bindings = visible_bindings(&closure_env, false).unwrap_or_default();
+    let args = args
+        .iter()
+        .map(Sendable::from_object)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|kind| format!("Task::spawn can't send a {} to another task", kind))?;
+    let settings = Settings::current(env);
+
+    let id = next_id();
+    let (sender, receiver) = mpsc::channel();
+    let thread = thread::Builder::new()
+        .name(format!("slang-task-{}", id))
+        .spawn(move || {
+            let env = settings.install(bindings);
+            let callback = function.into_object(&env);
+            let args = args.into_iter().map(|a| a.into_object(&env)).collect();
+            let result = match apply_function_with_this(callback, args, None, Rc::clone(&env)) {
+                Object::Error(msg) => Err(msg),
+                value => Sendable::from_object(&value)
+                    .map_err(|kind| format!("a task can't return a {}", kind)),
+            };
+            let _ = sender.send(result);
+        })
+        .map_err(|e| format!("Task::spawn failed to start a thread: {}", e))?;
+    TASKS.lock().unwrap().insert(id, TaskEntry { result: receiver, thread });
+    Ok(id)
+}
+
+/// One worker thread per CPU.
+pub(crate) fn worker_count() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Call `callback` with each of `items` on up to `workers` threads and return
+/// the results in order, or the first error (in item order) a call returned.
+///
+/// `None` when the work can't be split up: the callback or an item can't be
+/// copied to another thread, the callback can see a value that can't (a ref,
+/// file, seq, bloom filter or host function, whose shared state a copy would
+/// lose), or there would be only one worker. The caller then runs the calls
+/// in order itself.
+pub(crate) fn par_apply(
+    callback: &Object,
+    items: &[Object],
+    workers: usize,
+    env: &EnvRef,
+) -> Option<Result<Vec<Object>, Object>> {
+    let workers = workers.min(items.len());
+    if workers < 2 {
+        return None;
+    }
+    let function = Sendable::from_object(callback).ok()?;
+    let bindings = match callback {
+        Object::Function(function) => visible_bindings(&function.env, true)?,
+        _ => Vec::new(),
+    };
+    let items = items.iter().map(Sendable::from_object).collect::<Result<Vec<_>, _>>().ok()?;
+
+    let chunk_size = items.len().div_ceil(workers);
+    let mut items = items.into_iter();
+    let handles: Vec<_> = (0..workers)
+        .map(|_| {
+            let chunk: Vec<Sendable> = items.by_ref().take(chunk_size).collect();
+            let settings = Settings::current(env);
+            let function = function.clone();
+            let bindings = bindings.clone();
+            thread::spawn(move || {
+                let env = settings.install(bindings);
+                let callback = function.into_object(&env);
+                let mut out = Vec::with_capacity(chunk.len());
+                for item in chunk {
+                    let args = vec![item.into_object(&env)];
+                    match apply_function_with_this(callback.clone(), args, None, Rc::clone(&env)) {
+                        Object::Error(msg) => return Err(msg),
+                        value => match Sendable::from_object(&value) {
+                            Ok(value) => out.push(value),
+                            Err(kind) => {
+                                return Err(format!("a parallel callback can't return a {}", kind))
+                            }
+                        },
+                    }
+                }
+                Ok(out)
+            })
+        })
+        .collect();
+
+    let mut results = Vec::new();
+    let mut first_error = None;
+    for handle in handles {
+        let chunk = match handle.join() {
+            Ok(chunk) => chunk,
+            Err(_) => Err("a parallel worker panicked".to_string()),
+        };
+        if first_error.is_some() {
+            continue;
+        }
+        match chunk {
+            Ok(values) => results.extend(values),
+            Err(msg) => first_error = Some(msg),
+        }
+    }
+    Some(match first_error {
+        Some(msg) => Err(Object::Error(msg)),
+        None => Ok(results.into_iter().map(|v| v.into_object(env)).collect()),
+    })
+}
+
+/// Wait for task `id` and return its result. A task that failed gives back
+/// its error. Each task can be joined once.
+pub(crate) fn join(id: i64, env: &EnvRef) -> Object {
+    let Some(entry) = TASKS.lock().unwrap().remove(&id) else {
+        return Object::error(format!("Task::join: no running task {}", id));
+    };
+    let result = loop {
+        match entry.result.recv_timeout(SIGNAL_POLL) {
+            Ok(result) => break result,
+            Err(RecvTimeoutError::Timeout) => check_signal(env),
+            Err(RecvTimeoutError::Disconnected) => break Err(format!("task {} panicked", id)),
+        }
+    };
+    let _ = entry.thread.join();
+    match result {
+        Ok(value) => value.into_object(env),
+        Err(msg) => Object::Error(msg),
+    }
+}
+
+/// Whether task `id` has finished; `None` if there is no such task.
+pub(crate) fn is_done(id: i64) -> Option<bool> {
+    TASKS.lock().unwrap().get(&id).map(|entry| entry.thread.is_finished())
+}
+
+struct Channel {
+    state: Mutex<ChannelState>,
+    ready: Condvar,
+}
+
+#[derive(Default)]
+struct ChannelState {
+    items: VecDeque<Sendable>,
+    closed: bool,
+}
+
+fn channel(id: i64, name: &str) -> Result<Arc<Channel>, String> {
+    CHANNELS
+        .lock()
+        .unwrap()
+        .get(&id)
+        .cloned()
+        .ok_or_else(|| format!("{}: no channel {}", name, id))
+}
+
+/// A new, open channel.
+pub(crate) fn channel_new() -> i64 {
+    let id = next_id();
+    let channel = Channel { state: Mutex::new(ChannelState::default()), ready: Condvar::new() };
+    CHANNELS.lock().unwrap().insert(id, Arc::new(channel));
+    id
+}
+
+pub(crate) fn channel_send(id: i64, value: &Object) -> Result<(), String> {
+    let channel = channel(id, "Channel::send")?;
+    let value = Sendable::from_object(value)
+        .map_err(|kind| format!("Channel::send can't send a {} to another task", kind))?;
+    let mut state = channel.state.lock().unwrap();
+    if state.closed {
+        return Err(format!("Channel::send: channel {} is closed", id));
+    }
+    state.items.push_back(value);
+    channel.ready.notify_one();
+    Ok(())
+}
+
+/// The next value on the channel, waiting for one if `wait` is set. `None`
+/// once the channel is closed and empty, or when it is empty and `wait` is not
+/// set.
+pub(crate) fn channel_recv(id: i64, wait: bool, env: &EnvRef) -> Result<Option<Object>, String> {
+    let channel = channel(id, if wait { "Channel::recv" } else { "Channel::tryRecv" })?;
+    loop {
+        let mut state = channel.state.lock().unwrap();
+        if let Some(value) = state.items.pop_front() {
+            return Ok(Some(value.into_object(env)));
+        }
+        if state.closed || !wait {
+            return Ok(None);
+        }
+        let (state, _) = channel.ready.wait_timeout(state, SIGNAL_POLL).unwrap();
+        drop(state);
+        check_signal(env);
+    }
+}
+
+/// Close the channel; values already sent can still be received.
+pub(crate) fn channel_close(id: i64) -> Result<(), String> {
+    let channel = channel(id, "Channel::close")?;
+    channel.state.lock().unwrap().closed = true;
+    channel.ready.notify_all();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::eval_input;
+
+    /// Evaluate `source`, then `par_apply` the `f` it defines to `items`.
+    fn par(source: &str, items: &str) -> Option<Result<Vec<Object>, Object>> {
+        let env = crate::env::new_env();
+        let program = crate::parser::Parser::new(crate::lexer::Lexer::new(source)).parse_program();
+        crate::evaluator::eval(&program, Rc::clone(&env));
+        let callback = env.borrow().get("f").expect("f is defined");
+        let items = match eval_input(items) {
+            Object::Array(items) => items.to_vec(),
+            other => panic!("expected an array, got {:?}", other),
+        };
+        par_apply(&callback, &items, 3, &env)
+    }
+
+    #[test]
+    fn par_apply_keeps_item_order_across_workers() {
+        let results = par("const K = 10; function f(x) { Time::sleep(5 - x); x * K; }", "[1, 2, 3, 4, 5]");
+        let results: Vec<String> = results.unwrap().unwrap().iter().map(|v| v.to_string()).collect();
+        assert_eq!(results, ["10", "20", "30", "40", "50"]);
+    }
+
+    #[test]
+    fn par_apply_reports_the_first_error_in_item_order() {
+        match par("function f(x) { if (x > 2) { missing; } else { x; } }", "[1, 2, 3, 4, 5]") {
+            Some(Err(Object::Error(msg))) => assert_eq!(msg, "identifier not found: missing"),
+            other => panic!("expected an error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn par_apply_declines_callbacks_that_see_shared_state() {
+        assert!(par("let counter = Ref::new(0); function f(x) { x; }", "[1, 2, 3]").is_none());
+        assert!(par("function f(x) { x; }", "[Ref::new(1), 2]").is_none());
+        assert!(par("function f(x) { x; }", "[1]").is_none());
+    }
+}
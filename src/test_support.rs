@@ -1,4 +1,4 @@
 pub mod core;
 
-pub use core::{eval_input, check_errors};
+pub use core::{eval_input, check_errors, temp_dir};
 
@@ -2,6 +2,9 @@
 //!
 //! This module is only compiled in test builds (`#[cfg(test)]` in `lib.rs`).
 
+use std::fs;
+use std::path::PathBuf;
+
 use crate::debug_log;
 use crate::env::new_env;
 use crate::evaluator::eval;
@@ -18,6 +21,12 @@ pub fn eval_input(input: &str) -> Object {
     debug_log!("AST: {} ({} statements)", program, program.statements.len());
     debug_log!("program.statements = {:#?}", program.statements);
 
+    // Keep module cache entries written by tests out of the user's cache dir.
+    #[cfg(feature = "cache")]
+    if crate::cache::cache_dir_override().is_none() {
+        crate::cache::set_cache_dir(Some(std::env::temp_dir().join("slang_test_ast_cache")));
+    }
+
     let env = new_env();
     eval(&program, env)
 }
@@ -29,4 +38,17 @@ pub fn check_errors(p: &Parser) {
     }
 }
 
-
+/// Create a fresh directory under the system temp dir, named `prefix`
+/// followed by a timestamp.
+pub fn temp_dir(prefix: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "{}_{}",
+        prefix,
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    fs::create_dir_all(&dir).expect("failed to create temp dir");
+    dir
+}
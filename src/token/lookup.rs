@@ -16,8 +16,10 @@ pub fn lookup_ident(ident: &str) -> TokenType {
 
     match ident.to_lowercase().as_str() {
         "let" => TokenType::Let,
+        "const" => TokenType::Const,
         "true" => TokenType::True,
         "false" => TokenType::False,
+        "null" => TokenType::Null,
         "if" => TokenType::If,
         "else" => TokenType::Else,
         "function" => TokenType::Function,
@@ -25,11 +27,16 @@ pub fn lookup_ident(ident: &str) -> TokenType {
         "return" => TokenType::Return,
         "while" => TokenType::While,
         "for" => TokenType::For,
+        "switch" => TokenType::Switch,
+        "case" => TokenType::Case,
         "test" => TokenType::Test,
         "namespace" => TokenType::Namespace,
         "import" => TokenType::Import,
+        "export" => TokenType::Export,
+        "use" => TokenType::Use,
         "class" => TokenType::Class,
         "new" => TokenType::New,
+        "yield" => TokenType::Yield,
         _ => TokenType::Ident,
     }
 }
@@ -43,6 +50,18 @@ mod tests {
         assert_eq!(lookup_ident("let"), TokenType::Let);
     }
 
+    #[test]
+    fn lookup_ident_recognizes_const() {
+        assert_eq!(lookup_ident("const"), TokenType::Const);
+        assert_eq!(lookup_ident("constant"), TokenType::Ident);
+    }
+
+    #[test]
+    fn lookup_ident_recognizes_null() {
+        assert_eq!(lookup_ident("null"), TokenType::Null);
+        assert_eq!(lookup_ident("nullable"), TokenType::Ident);
+    }
+
     #[test]
     fn lookup_ident_recognizes_ident() {
         assert_eq!(lookup_ident("foo"), TokenType::Ident);
@@ -7,6 +7,8 @@ pub enum TokenType {
     Int,
     Float,
     String,
+    /// `b"..."`; the literal holds the raw contents, escapes undecoded.
+    Bytes,
     Function,
 
     Assign,
@@ -15,12 +17,35 @@ pub enum TokenType {
     Minus,
     MinusMinus,
     Mul,
+    /// `**`, exponentiation
+    Pow,
     Div,
     Mod,
+    /// `div`, integer division. Only an operator between two operands (see
+    /// `Parser::next_token`), so `div` stays usable as a name.
+    IntDiv,
+
+    /// Bitwise `&`, `|`, `^`, `~`, `<<`, `>>`
+    BitAnd,
+    BitOr,
+    BitXor,
+    Tilde,
+    ShiftLeft,
+    ShiftRight,
 
     Dot,
+    /// `...`, used for rest bindings in destructuring patterns
+    Ellipsis,
     /// Double-colon, used for qualified access like `Option::Some`
     ColonColon,
+    /// `?.`, property access that yields null on a null receiver
+    QuestionDot,
+    /// `??`, null coalescing
+    QuestionQuestion,
+    /// Postfix `?`, which returns a `None` or `Err` from the enclosing function
+    Question,
+    /// `|>`, passing the left side as the first argument of the call on the right
+    Pipeline,
 
     And,
     Or,
@@ -43,26 +68,38 @@ pub enum TokenType {
     Comma,
     Colon,
     Arrow,
+    /// `=>`, between an arrow function's parameters and its body
+    FatArrow,
 
     Let,
+    Const,
     True,
     False,
+    Null,
     If,
     Else,
     Return,
     While,
     For,
+    Switch,
+    Case,
     Test,
     Namespace,
     Import,
+    Export,
+    Use,
     Class,
     New,
+    Yield,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Token {
     pub token_type: TokenType,
     pub literal: String,
+    /// The source text of a string literal (`r"\d+"`, `"\u{41}"`), whose
+    /// `literal` holds the decoded value; `None` for every other token.
+    pub lexeme: Option<String>,
 }
 
 impl Token {
@@ -70,6 +107,7 @@ impl Token {
         Token {
             token_type,
             literal,
+            lexeme: None,
         }
     }
 }